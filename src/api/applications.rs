@@ -0,0 +1,391 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::api::routes::{self, Route};
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    ApplicationCommand, ApplicationCommandCreateSchema, ApplicationCommandModifySchema,
+    ApplicationCommandPermission, ApplicationCommandPermissionsModifySchema,
+    GuildApplicationCommandPermissions, Snowflake,
+};
+
+impl ApplicationCommandCreateSchema {
+    /// Checks that the command can be registered with its type:
+    /// [context menu](crate::types::ApplicationCommandType::is_context_menu) commands have no
+    /// description or options, and [slash commands](crate::types::ApplicationCommandType::ChatInput)
+    /// need a description.
+    pub fn validate(&self) -> ChorusResult<()> {
+        let command_type = self.r#type.unwrap_or_default();
+        if command_type.is_context_menu() {
+            if !self.description.is_empty() || !self.options.is_empty() {
+                return Err(ChorusError::InvalidArguments {
+                    error: format!(
+                        "{:?} commands can not have a description or options",
+                        command_type
+                    ),
+                });
+            }
+        } else if self.description.is_empty() {
+            return Err(ChorusError::InvalidArguments {
+                error: "Slash commands need a description".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl ApplicationCommand {
+    /// Registers a global command of an application, replacing the command with the same name
+    /// and type.
+    ///
+    /// The schema is checked with [`ApplicationCommandCreateSchema::validate`] before any request
+    /// is made.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#create-global-application-command>
+    pub async fn create(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        schema: ApplicationCommandCreateSchema,
+    ) -> ChorusResult<ApplicationCommand> {
+        ApplicationCommand::register(user, routes::application_commands(application_id), schema)
+            .await
+    }
+
+    /// Registers a command of an application in a single guild, replacing the command with the
+    /// same name and type.
+    ///
+    /// The schema is checked with [`ApplicationCommandCreateSchema::validate`] before any request
+    /// is made.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#create-guild-application-command>
+    pub async fn create_in_guild(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        schema: ApplicationCommandCreateSchema,
+    ) -> ChorusResult<ApplicationCommand> {
+        ApplicationCommand::register(
+            user,
+            routes::application_guild_commands(application_id, guild_id),
+            schema,
+        )
+        .await
+    }
+
+    /// Returns the global commands of an application.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#get-global-application-commands>
+    pub async fn get_all(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+    ) -> ChorusResult<Vec<ApplicationCommand>> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::application_commands(application_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<ApplicationCommand>>(user)
+        .await
+    }
+
+    /// Returns the commands of an application in a single guild, without its global commands.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#get-guild-application-commands>
+    pub async fn get_all_in_guild(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+    ) -> ChorusResult<Vec<ApplicationCommand>> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::application_guild_commands(application_id, guild_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<ApplicationCommand>>(user)
+        .await
+    }
+
+    /// Returns a global command of an application.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#get-global-application-command>
+    pub async fn get(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        command_id: Snowflake,
+    ) -> ChorusResult<ApplicationCommand> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::application_command(application_id, command_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<ApplicationCommand>(user)
+        .await
+    }
+
+    /// Returns a command of an application in a single guild.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#get-guild-application-command>
+    pub async fn get_in_guild(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        command_id: Snowflake,
+    ) -> ChorusResult<ApplicationCommand> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::application_guild_command(application_id, guild_id, command_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<ApplicationCommand>(user)
+        .await
+    }
+
+    /// Replaces all global commands of an application with the given ones, deleting the commands
+    /// which are not given. Returns the registered commands.
+    ///
+    /// Every schema is checked with [`ApplicationCommandCreateSchema::validate`] before any
+    /// request is made.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#bulk-overwrite-global-application-commands>
+    pub async fn bulk_overwrite(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        schemas: Vec<ApplicationCommandCreateSchema>,
+    ) -> ChorusResult<Vec<ApplicationCommand>> {
+        ApplicationCommand::overwrite(user, routes::application_commands(application_id), schemas)
+            .await
+    }
+
+    /// Replaces all commands of an application in a single guild with the given ones, deleting
+    /// the commands which are not given. Returns the registered commands.
+    ///
+    /// Every schema is checked with [`ApplicationCommandCreateSchema::validate`] before any
+    /// request is made.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#bulk-overwrite-guild-application-commands>
+    pub async fn bulk_overwrite_in_guild(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        schemas: Vec<ApplicationCommandCreateSchema>,
+    ) -> ChorusResult<Vec<ApplicationCommand>> {
+        ApplicationCommand::overwrite(
+            user,
+            routes::application_guild_commands(application_id, guild_id),
+            schemas,
+        )
+        .await
+    }
+
+    /// Modifies a global command of an application, and returns the modified command.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#edit-global-application-command>
+    pub async fn edit(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        command_id: Snowflake,
+        schema: ApplicationCommandModifySchema,
+    ) -> ChorusResult<ApplicationCommand> {
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::application_command(application_id, command_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<ApplicationCommand>(user)
+        .await
+    }
+
+    /// Modifies a command of an application in a single guild, and returns the modified command.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#edit-guild-application-command>
+    pub async fn edit_in_guild(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        command_id: Snowflake,
+        schema: ApplicationCommandModifySchema,
+    ) -> ChorusResult<ApplicationCommand> {
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::application_guild_command(application_id, guild_id, command_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<ApplicationCommand>(user)
+        .await
+    }
+
+    /// Deletes a global command of an application.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#delete-global-application-command>
+    pub async fn delete(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        command_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::application_command(application_id, command_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Deletes a command of an application in a single guild.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#delete-guild-application-command>
+    pub async fn delete_in_guild(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        command_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::application_guild_command(application_id, guild_id, command_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    async fn register(
+        user: &mut ChorusUser,
+        route: Route,
+        schema: ApplicationCommandCreateSchema,
+    ) -> ChorusResult<ApplicationCommand> {
+        schema.validate()?;
+        ChorusRequest::from_route(
+            http::Method::POST,
+            route,
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<ApplicationCommand>(user)
+        .await
+    }
+
+    async fn overwrite(
+        user: &mut ChorusUser,
+        route: Route,
+        schemas: Vec<ApplicationCommandCreateSchema>,
+    ) -> ChorusResult<Vec<ApplicationCommand>> {
+        for schema in &schemas {
+            schema.validate()?;
+        }
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            route,
+            Some(to_string(&schemas).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<ApplicationCommand>>(user)
+        .await
+    }
+}
+
+impl GuildApplicationCommandPermissions {
+    /// Returns the permissions of all commands of an application in a guild which have been
+    /// changed from their defaults.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#get-guild-application-command-permissions>
+    pub async fn get_all(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+    ) -> ChorusResult<Vec<GuildApplicationCommandPermissions>> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::application_guild_commands_permissions(application_id, guild_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<GuildApplicationCommandPermissions>>(user)
+        .await
+    }
+
+    /// Returns the permissions of a command of an application in a guild.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#get-application-command-permissions>
+    pub async fn get(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        command_id: Snowflake,
+    ) -> ChorusResult<GuildApplicationCommandPermissions> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::application_guild_command_permissions(application_id, guild_id, command_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<GuildApplicationCommandPermissions>(user)
+        .await
+    }
+
+    /// Replaces the permissions of a command of an application in a guild, and returns the new
+    /// permissions.
+    ///
+    /// Bots can not use this endpoint, it requires an OAuth2 token of a user with the
+    /// `applications.commands.permissions.update` scope who can manage the guild and its roles.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/application-commands#edit-application-command-permissions>
+    pub async fn edit(
+        user: &mut ChorusUser,
+        application_id: Snowflake,
+        guild_id: Snowflake,
+        command_id: Snowflake,
+        permissions: Vec<ApplicationCommandPermission>,
+    ) -> ChorusResult<GuildApplicationCommandPermissions> {
+        let schema = ApplicationCommandPermissionsModifySchema { permissions };
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::application_guild_command_permissions(application_id, guild_id, command_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<GuildApplicationCommandPermissions>(user)
+        .await
+    }
+}
@@ -7,25 +7,30 @@ use std::sync::{Arc, RwLock};
 use reqwest::Client;
 use serde_json::to_string;
 
-use crate::errors::ChorusResult;
+use crate::api::routes;
+use crate::errors::{ApiError, ChorusError, ChorusResult, VerificationMethod};
 use crate::gateway::Gateway;
 use crate::instance::{ChorusUser, Instance};
 use crate::ratelimiter::ChorusRequest;
-use crate::types::{GatewayIdentifyPayload, LimitType, LoginResult, LoginSchema};
+use crate::types::{LoginResult, LoginSchema};
 
 impl Instance {
     /// Logs into an existing account on the spacebar server.
     ///
+    /// If the instance requires the account to be verified first, a
+    /// [`ChorusError::VerificationRequired`] is returned. If the account is scheduled for
+    /// deletion, a [`ChorusError::AccountScheduledForDeletion`] is returned.
+    ///
     /// # Reference
     /// See <https://docs.spacebar.chat/routes/#post-/auth/login/>
     pub async fn login_account(&mut self, login_schema: LoginSchema) -> ChorusResult<ChorusUser> {
-        let endpoint_url = self.urls.api.clone() + "/auth/login";
+        let route = routes::auth_login();
         let chorus_request = ChorusRequest {
             request: Client::new()
-                .post(endpoint_url)
+                .post(route.url(&self.urls.api))
                 .body(to_string(&login_schema).unwrap())
                 .header("Content-Type", "application/json"),
-            limit_type: LimitType::AuthLogin,
+            limit_type: route.limit_type(),
         };
         // We do not have a user yet, and the UserRateLimits will not be affected by a login
         // request (since login is an instance wide limit), which is why we are just cloning the
@@ -34,14 +39,15 @@ impl Instance {
             ChorusUser::shell(Arc::new(RwLock::new(self.clone())), "None".to_string()).await;
         let login_result = chorus_request
             .deserialize_response::<LoginResult>(&mut shell)
-            .await?;
+            .await
+            .map_err(login_error)?;
         let object = self.get_user(login_result.token.clone(), None).await?;
         if self.limits_information.is_some() {
-            self.limits_information.as_mut().unwrap().ratelimits = shell.limits.clone().unwrap();
+            self.limits_information.as_mut().unwrap().ratelimits =
+                shell.limits.read().unwrap().clone().unwrap();
         }
-        let mut identify = GatewayIdentifyPayload::common();
-        let gateway = Gateway::spawn(self.urls.wss.clone()).await.unwrap();
-        identify.token = login_result.token.clone();
+        let gateway = self.spawn_gateway().await.unwrap();
+        let identify = self.identify_payload(login_result.token.clone());
         gateway.send_identify(identify).await;
         let user = ChorusUser::new(
             Arc::new(RwLock::new(self.clone())),
@@ -51,6 +57,21 @@ impl Instance {
             Arc::new(RwLock::new(object)),
             gateway,
         );
+        user.track_guild_members().await;
         Ok(user)
     }
 }
+
+/// Maps the error responses for accounts which cannot log in right now to their own errors.
+fn login_error(error: ChorusError) -> ChorusError {
+    let Some(api_error) = error.api_error() else {
+        return error;
+    };
+    if api_error.code == Some(ApiError::ACCOUNT_SCHEDULED_FOR_DELETION) {
+        return ChorusError::AccountScheduledForDeletion;
+    }
+    match VerificationMethod::from_api_error(api_error) {
+        Some(method) => ChorusError::VerificationRequired { method },
+        None => error,
+    }
+}
@@ -10,15 +10,19 @@ pub use login::*;
 #[allow(unused_imports)]
 pub use register::*;
 
+#[allow(unused_imports)]
+pub use verify::*;
+
 use crate::gateway::Gateway;
 use crate::{
-    errors::ChorusResult,
-    instance::{ChorusUser, Instance},
-    types::{GatewayIdentifyPayload, User},
+    errors::{ChorusError, ChorusResult},
+    instance::{ChorusUser, Instance, SavedSession},
+    types::User,
 };
 
 pub mod login;
 pub mod register;
+pub mod verify;
 
 impl Instance {
     /// Logs into an existing account on the spacebar server, using only a token.
@@ -31,9 +35,8 @@ impl Instance {
         let user_settings = User::get_settings(&token, &self.urls.api, &mut self.clone())
             .await
             .unwrap();
-        let mut identify = GatewayIdentifyPayload::common();
-        let gateway = Gateway::spawn(self.urls.wss.clone()).await.unwrap();
-        identify.token = token.clone();
+        let gateway = self.spawn_gateway().await.unwrap();
+        let identify = self.identify_payload(token.clone());
         gateway.send_identify(identify).await;
         let user = ChorusUser::new(
             Arc::new(RwLock::new(self.clone())),
@@ -43,6 +46,41 @@ impl Instance {
             Arc::new(RwLock::new(object_result.unwrap())),
             gateway,
         );
+        user.track_guild_members().await;
+        Ok(user)
+    }
+
+    /// Restores a user saved with [`ChorusUser::save_session`], without logging in again or
+    /// fetching the user.
+    ///
+    /// The saved gateway session is resumed if there is one, which replays the events missed in
+    /// the meantime. If it has expired, a new session is identified instead.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if the session was saved on another instance.
+    pub async fn restore_session(&mut self, session: SavedSession) -> ChorusResult<ChorusUser> {
+        if session.urls != self.urls {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "The session belongs to the instance at {}, not {}",
+                    session.urls.api, self.urls.api
+                ),
+            });
+        }
+        let gateway = self.spawn_gateway().await.unwrap();
+        let identify = self.identify_payload(session.token.clone());
+        match session.gateway {
+            Some(state) => gateway.resume_session(identify, state).await,
+            None => gateway.send_identify(identify).await,
+        }
+        let user = ChorusUser::new(
+            Arc::new(RwLock::new(self.clone())),
+            session.token,
+            self.clone_limits_if_some(),
+            Arc::new(RwLock::new(session.settings)),
+            Arc::new(RwLock::new(session.object)),
+            gateway,
+        );
+        user.track_guild_members().await;
         Ok(user)
     }
 }
@@ -7,32 +7,43 @@ use std::sync::{Arc, RwLock};
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::gateway::{Gateway, GatewayHandle};
-use crate::types::GatewayIdentifyPayload;
 use crate::{
-    errors::ChorusResult,
+    errors::{ApiError, ChorusError, ChorusResult},
     instance::{ChorusUser, Instance, Token},
     ratelimiter::ChorusRequest,
-    types::LimitType,
     types::RegisterSchema,
 };
 
 impl Instance {
     /// Registers a new user on the server.
     ///
+    /// If the instance has disabled registration or only allows registering with an invite, a
+    /// [`ChorusError::RegistrationDisabled`] or [`ChorusError::InviteRequired`] is returned
+    /// respectively. If the date of birth is below the instance's minimum age,
+    /// [`ChorusError::Underage`] is returned.
+    ///
     /// # Reference
     /// See <https://docs.spacebar.chat/routes/#post-/auth/register/>
     pub async fn register_account(
         &mut self,
         register_schema: RegisterSchema,
     ) -> ChorusResult<ChorusUser> {
-        let endpoint_url = self.urls.api.clone() + "/auth/register";
+        let route = routes::auth_register();
+        let mut request = Client::new()
+            .post(route.url(&self.urls.api))
+            .body(to_string(&register_schema).unwrap())
+            .header("Content-Type", "application/json");
+        if let Some(token) = &register_schema.registration_token {
+            request = request.header(
+                http::header::REFERER,
+                format!("{}/register?token={}", self.urls.root, token),
+            );
+        }
         let chorus_request = ChorusRequest {
-            request: Client::new()
-                .post(endpoint_url)
-                .body(to_string(&register_schema).unwrap())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::AuthRegister,
+            request,
+            limit_type: route.limit_type(),
         };
         // We do not have a user yet, and the UserRateLimits will not be affected by a login
         // request (since register is an instance wide limit), which is why we are just cloning
@@ -41,16 +52,17 @@ impl Instance {
             ChorusUser::shell(Arc::new(RwLock::new(self.clone())), "None".to_string()).await;
         let token = chorus_request
             .deserialize_response::<Token>(&mut shell)
-            .await?
+            .await
+            .map_err(registration_error)?
             .token;
         if self.limits_information.is_some() {
-            self.limits_information.as_mut().unwrap().ratelimits = shell.limits.unwrap();
+            self.limits_information.as_mut().unwrap().ratelimits =
+                shell.limits.read().unwrap().clone().unwrap();
         }
         let user_object = self.get_user(token.clone(), None).await.unwrap();
         let settings = ChorusUser::get_settings(&token, &self.urls.api.clone(), self).await?;
-        let mut identify = GatewayIdentifyPayload::common();
-        let gateway: GatewayHandle = Gateway::spawn(self.urls.wss.clone()).await.unwrap();
-        identify.token = token.clone();
+        let gateway: GatewayHandle = self.spawn_gateway().await.unwrap();
+        let identify = self.identify_payload(token.clone());
         gateway.send_identify(identify).await;
         let user = ChorusUser::new(
             Arc::new(RwLock::new(self.clone())),
@@ -60,6 +72,24 @@ impl Instance {
             Arc::new(RwLock::new(user_object)),
             gateway,
         );
+        user.track_guild_members().await;
         Ok(user)
     }
 }
+
+/// Maps the field errors Spacebar reports for restricted registrations to their own errors.
+fn registration_error(error: ChorusError) -> ChorusError {
+    match error.api_error() {
+        Some(api_error) if api_error.has_field_error("REGISTRATION_DISABLED") => {
+            ChorusError::RegistrationDisabled
+        }
+        Some(api_error) if api_error.has_field_error("INVITE_ONLY") => ChorusError::InviteRequired,
+        Some(api_error)
+            if api_error.code == Some(ApiError::UNDER_MINIMUM_AGE)
+                || api_error.has_field_error("DATE_OF_BIRTH_UNDERAGE") =>
+        {
+            ChorusError::Underage
+        }
+        _ => error,
+    }
+}
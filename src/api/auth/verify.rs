@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::{Arc, RwLock};
+
+use reqwest::Client;
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::{ChorusUser, Instance, Token};
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    AddPhoneSchema, PhoneCodeRequestSchema, VerifyEmailSchema, VerifyPhoneResult, VerifyPhoneSchema,
+};
+
+impl Instance {
+    /// Verifies the email address of an account, using the token sent to it.
+    ///
+    /// Returns a new token for the verified account.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/authentication#verify-user-email>
+    pub async fn verify_email(&mut self, schema: VerifyEmailSchema) -> ChorusResult<Token> {
+        let route = routes::auth_verify();
+        let chorus_request = ChorusRequest {
+            request: Client::new()
+                .post(route.url(&self.urls.api))
+                .body(to_string(&schema).unwrap())
+                .header("Content-Type", "application/json"),
+            limit_type: route.limit_type(),
+        };
+        let mut shell =
+            ChorusUser::shell(Arc::new(RwLock::new(self.clone())), "None".to_string()).await;
+        chorus_request
+            .deserialize_response::<Token>(&mut shell)
+            .await
+    }
+}
+
+impl ChorusUser {
+    /// Sends a new verification email to the user's email address.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/authentication#resend-verification-email>
+    pub async fn resend_verification_email(&mut self) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::auth_verify_resend(),
+            None,
+            None,
+            self,
+        )
+        .handle_request_as_result(self)
+        .await
+    }
+
+    /// Requests an SMS with a verification code to be sent to a phone number, which is to be
+    /// added to the user's account.
+    ///
+    /// The code is then used with [`ChorusUser::verify_phone`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/user#add-phone-number>
+    pub async fn request_phone_code(&mut self, schema: PhoneCodeRequestSchema) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::current_user_phone(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .handle_request_as_result(self)
+        .await
+    }
+
+    /// Verifies a phone number with the code sent to it via SMS.
+    ///
+    /// The returned token is used with [`ChorusUser::add_phone`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/authentication#verify-phone-number>
+    pub async fn verify_phone(
+        &mut self,
+        schema: VerifyPhoneSchema,
+    ) -> ChorusResult<VerifyPhoneResult> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::phone_verifications_verify(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .deserialize_response::<VerifyPhoneResult>(self)
+        .await
+    }
+
+    /// Adds a verified phone number to the user's account.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/user#add-phone-number>
+    pub async fn add_phone(&mut self, schema: AddPhoneSchema) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::current_user_phone(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .handle_request_as_result(self)
+        .await
+    }
+}
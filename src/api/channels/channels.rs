@@ -5,13 +5,15 @@
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::types::{AddChannelRecipientSchema, ModifyChannelPositionsSchema};
 use crate::{
     errors::{ChorusError, ChorusResult},
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
     types::{
-        Channel, ChannelModifySchema, GetChannelMessagesSchema, LimitType, Message, Snowflake,
+        Channel, ChannelModifySchema, GetChannelMessagesSchema, LimitType, Message,
+        PermissionFlags, Snowflake,
     },
 };
 
@@ -21,18 +23,12 @@ impl Channel {
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/channel#get-channel>
     pub async fn get(user: &mut ChorusUser, channel_id: Snowflake) -> ChorusResult<Channel> {
-        let chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::from_route(
             http::Method::GET,
-            &format!(
-                "{}/channels/{}",
-                user.belongs_to.read().unwrap().urls.api.clone(),
-                channel_id
-            ),
+            routes::channel(channel_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
 
         chorus_request.deserialize_response::<Channel>(user).await
@@ -50,20 +46,12 @@ impl Channel {
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            self.id,
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::channel(self.id),
             None,
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Channel(self.id),
+            user,
         );
 
         request.handle_request_as_result(user).await
@@ -90,20 +78,17 @@ impl Channel {
         user: &mut ChorusUser,
     ) -> ChorusResult<Channel> {
         let channel_id = self.id;
-        let url = format!(
-            "{}/channels/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id
-        );
-
-        let request = ChorusRequest::new(
+        user.require_permissions(
+            PermissionFlags::VIEW_CHANNEL | PermissionFlags::MANAGE_CHANNELS,
+            channel_id,
+        )
+        .await?;
+        let request = ChorusRequest::from_route(
             http::Method::PATCH,
-            &url,
+            routes::channel(channel_id),
             Some(to_string(&modify_data).unwrap()),
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
 
         request.deserialize_response::<Channel>(user).await
@@ -123,20 +108,12 @@ impl Channel {
         channel_id: Snowflake,
         user: &mut ChorusUser,
     ) -> Result<Vec<Message>, ChorusError> {
-        let url = format!(
-            "{}/channels/{}/messages",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id
-        );
-
-        let mut chorus_request = ChorusRequest::new(
+        let mut chorus_request = ChorusRequest::from_route(
             http::Method::GET,
-            &url,
+            routes::channel_messages(channel_id),
             None,
             None,
-            None,
-            Some(user),
-            Default::default(),
+            user,
         );
         chorus_request.request = chorus_request.request.query(&range);
 
@@ -155,22 +132,14 @@ impl Channel {
         user: &mut ChorusUser,
         add_channel_recipient_schema: Option<AddChannelRecipientSchema>,
     ) -> ChorusResult<()> {
-        let mut request = Client::new()
-            .put(format!(
-                "{}/channels/{}/recipients/{}",
-                user.belongs_to.read().unwrap().urls.api,
-                self.id,
-                recipient_id
-            ))
-            .header("Authorization", user.token())
-            .header("Content-Type", "application/json");
-        if let Some(schema) = add_channel_recipient_schema {
-            request = request.body(to_string(&schema).unwrap());
-        }
-        ChorusRequest {
-            request,
-            limit_type: LimitType::Channel(self.id),
-        }
+        let body = add_channel_recipient_schema.map(|schema| to_string(&schema).unwrap());
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::channel_recipient(self.id, recipient_id),
+            body,
+            None,
+            user,
+        )
         .handle_request_as_result(user)
         .await
     }
@@ -184,21 +153,12 @@ impl Channel {
         recipient_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/recipients/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            self.id,
-            recipient_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::channel_recipient(self.id, recipient_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Channel(self.id),
+            user,
         );
 
         request.handle_request_as_result(user).await
@@ -214,20 +174,12 @@ impl Channel {
         guild_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/guilds/{}/channels",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::PATCH,
-            &url,
+            routes::guild_channels(guild_id),
             Some(to_string(&schema).unwrap()),
             None,
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
 
         request.handle_request_as_result(user).await
@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    Channel, ChannelFlags, ChannelType, ForumThreadCreateSchema, MessageSendSchema,
+    PermissionFlags, Snowflake, Tag,
+};
+
+/// The maximum amount of tags which can be applied to a post.
+pub const MAX_APPLIED_TAGS: usize = 5;
+
+/// A forum or media channel, in which every post is a thread.
+#[derive(Debug, Clone)]
+pub struct ForumChannel {
+    channel: Channel,
+}
+
+impl ForumChannel {
+    /// Returns the forum channel, or `None` if the channel is neither a
+    /// [forum](ChannelType::GuildForum) nor a [media](ChannelType::GuildMedia) channel.
+    pub fn new(channel: Channel) -> Option<Self> {
+        matches!(
+            channel.channel_type,
+            ChannelType::GuildForum | ChannelType::GuildMedia
+        )
+        .then_some(Self { channel })
+    }
+
+    /// Fetches a forum channel. Fails with [`ChorusError::InvalidArguments`] if the channel is
+    /// not a forum or media channel.
+    pub async fn get(user: &mut ChorusUser, channel_id: Snowflake) -> ChorusResult<Self> {
+        let channel = Channel::get(user, channel_id).await?;
+        Self::new(channel).ok_or_else(|| ChorusError::InvalidArguments {
+            error: format!("Channel {} is not a forum or media channel", channel_id),
+        })
+    }
+
+    pub fn channel(&self) -> &Channel {
+        &self.channel
+    }
+
+    /// The tags which can be applied to posts in this channel.
+    pub fn available_tags(&self) -> &[Tag] {
+        #[cfg(feature = "sqlx")]
+        let tags = self.channel.available_tags.as_ref().map(|tags| &tags.0);
+        #[cfg(not(feature = "sqlx"))]
+        let tags = self.channel.available_tags.as_ref();
+        tags.map_or(&[], |tags| tags.as_slice())
+    }
+
+    /// Returns the tag with the given name, ignoring case.
+    pub fn tag_by_name(&self, name: &str) -> Option<&Tag> {
+        self.available_tags()
+            .iter()
+            .find(|tag| tag.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether posts have to be created with at least one tag.
+    pub fn requires_tag(&self) -> bool {
+        self.channel
+            .flags
+            .map(|flags| ChannelFlags::from_bits_truncate(flags as u64))
+            .unwrap_or_default()
+            .contains(ChannelFlags::REQUIRE_TAG)
+    }
+
+    /// Checks that the tags can be applied to a new post: they have to be available in this
+    /// channel, there can be at most [`MAX_APPLIED_TAGS`] of them, and at least one if the
+    /// channel [requires a tag](ForumChannel::requires_tag).
+    ///
+    /// Returns the applied tags.
+    pub fn validate_tags(&self, applied_tags: &[Snowflake]) -> ChorusResult<Vec<&Tag>> {
+        if applied_tags.is_empty() && self.requires_tag() {
+            return Err(ChorusError::InvalidArguments {
+                error: "Posts in this channel require at least one tag".to_string(),
+            });
+        }
+        if applied_tags.len() > MAX_APPLIED_TAGS {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "At most {} tags can be applied to a post, got {}",
+                    MAX_APPLIED_TAGS,
+                    applied_tags.len()
+                ),
+            });
+        }
+        applied_tags
+            .iter()
+            .map(|id| {
+                self.available_tags()
+                    .iter()
+                    .find(|tag| tag.id == *id)
+                    .ok_or_else(|| ChorusError::InvalidArguments {
+                        error: format!("Tag {} is not available in this channel", id),
+                    })
+            })
+            .collect()
+    }
+
+    /// Creates a post with the given title and first message, and returns its thread.
+    ///
+    /// The tags are validated with [`ForumChannel::validate_tags`] before any request is made.
+    /// Applying [moderated](Tag::moderated) tags requires the
+    /// [`MANAGE_THREADS`](PermissionFlags::MANAGE_THREADS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#create-thread>
+    pub async fn create_post(
+        &self,
+        user: &mut ChorusUser,
+        title: &str,
+        mut message: MessageSendSchema,
+        applied_tags: Vec<Snowflake>,
+    ) -> ChorusResult<Channel> {
+        let mut permissions = PermissionFlags::VIEW_CHANNEL | PermissionFlags::SEND_MESSAGES;
+        if self
+            .validate_tags(&applied_tags)?
+            .iter()
+            .any(|tag| tag.moderated)
+        {
+            permissions |= PermissionFlags::MANAGE_THREADS;
+        }
+        user.require_permissions(permissions, self.channel.id)
+            .await?;
+
+        message.ensure_nonce();
+        let schema = ForumThreadCreateSchema {
+            name: title.to_string(),
+            applied_tags: (!applied_tags.is_empty()).then_some(applied_tags),
+            message,
+            ..Default::default()
+        };
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::channel_threads(self.channel.id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<Channel>(user)
+        .await
+    }
+}
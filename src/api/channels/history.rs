@@ -0,0 +1,105 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::io::Write;
+
+use futures_util::{stream, Stream, StreamExt};
+
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::types::{Channel, GetChannelMessagesSchema, Message, MessageHistoryRange, Snowflake};
+
+/// The amount of messages requested per page while walking the message history.
+const HISTORY_PAGE_SIZE: i32 = 100;
+
+struct HistoryCursor {
+    after: Snowflake,
+    buffer: VecDeque<Message>,
+    exhausted: bool,
+}
+
+impl Channel {
+    /// Walks the message history of a channel within the given range, yielding messages
+    /// oldest-first.
+    ///
+    /// Messages are fetched lazily in pages of 100, so only one page is held in memory at a time.
+    /// The stream ends after the first error.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#get-messages>
+    pub fn export_history(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        range: MessageHistoryRange,
+    ) -> impl Stream<Item = ChorusResult<Message>> + '_ {
+        let cursor = HistoryCursor {
+            after: range.after.unwrap_or(Snowflake(0)),
+            buffer: VecDeque::new(),
+            exhausted: false,
+        };
+        stream::try_unfold((user, cursor), move |(user, mut cursor)| async move {
+            loop {
+                if let Some(message) = cursor.buffer.pop_front() {
+                    return Ok(Some((message, (user, cursor))));
+                }
+                if cursor.exhausted {
+                    return Ok(None);
+                }
+                let mut page = Channel::messages(
+                    GetChannelMessagesSchema::after(cursor.after).limit(HISTORY_PAGE_SIZE),
+                    channel_id,
+                    user,
+                )
+                .await?;
+                // The order of a page is up to the server, so we sort it ourselves
+                page.sort_by_key(|message| message.id);
+                cursor.exhausted = page.len() < HISTORY_PAGE_SIZE as usize;
+                if let Some(last) = page.last() {
+                    cursor.after = last.id;
+                }
+                for message in page {
+                    if range.contains(message.id) {
+                        cursor.buffer.push_back(message);
+                    } else {
+                        // Everything after this lies past the end of the range
+                        cursor.exhausted = true;
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Exports the message history of a channel within the given range as JSON Lines, writing one
+    /// message per line, oldest-first.
+    ///
+    /// Returns the amount of exported messages.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#get-messages>
+    pub async fn export_history_jsonl<W: Write>(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        range: MessageHistoryRange,
+        writer: &mut W,
+    ) -> ChorusResult<u64> {
+        let mut count = 0;
+        let mut history = Box::pin(Channel::export_history(user, channel_id, range));
+        while let Some(message) = history.next().await {
+            let message = message?;
+            serde_json::to_writer(&mut *writer, &message).map_err(|e| ChorusError::Io {
+                error: e.to_string(),
+            })?;
+            writer.write_all(b"\n").map_err(|e| ChorusError::Io {
+                error: e.to_string(),
+            })?;
+            count += 1;
+        }
+        writer.flush().map_err(|e| ChorusError::Io {
+            error: e.to_string(),
+        })?;
+        Ok(count)
+    }
+}
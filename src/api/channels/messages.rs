@@ -2,23 +2,44 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
 use http::header::CONTENT_DISPOSITION;
 use http::HeaderMap;
 use reqwest::{multipart, Client};
 use serde_json::{from_value, to_string, Value};
+use tokio::sync::oneshot;
 
-use crate::errors::{ChorusError, ChorusResult};
+use crate::api::routes;
+use crate::errors::{ApiError, ChorusError, ChorusResult};
+use crate::gateway::Observer;
 use crate::instance::ChorusUser;
-use crate::ratelimiter::ChorusRequest;
+use crate::ratelimiter::{ChorusRequest, RateLimitEvent};
 use crate::types::{
-    Channel, CreateGreetMessage, LimitType, Message, MessageAck, MessageModifySchema,
-    MessageSearchEndpoint, MessageSearchQuery, MessageSendSchema, Snowflake,
+    Channel, CreateGreetMessage, LimitType, Message, MessageAck, MessageCreate,
+    MessageModifySchema, MessageSearchEndpoint, MessageSearchQuery, MessageSendSchema,
+    PartialDiscordFileAttachment, PermissionFlags, Snowflake,
 };
 
+/// The maximum length of a message's content, if the instance does not say otherwise.
+const DEFAULT_MAX_MESSAGE_LENGTH: usize = 2000;
+
 impl Message {
     /// Sends a message in the channel with the provided channel_id.
     /// Returns the sent message.
     ///
+    /// If the upload limit of the channel is known, attachments are checked against it before
+    /// uploading them, see [`ChorusUser::upload_limit`]. A [`ChorusError::AttachmentTooLarge`] is
+    /// returned for the first attachment exceeding the limit.
+    ///
+    /// Embed images and thumbnails may show attachments of the message, by referring to them
+    /// with `attachment://filename` urls. A [`ChorusError::UnknownAttachment`] is returned if the
+    /// referenced file is not attached.
+    ///
+    /// If the message has no nonce, one is generated, see [`MessageSendSchema::ensure_nonce`].
+    ///
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/message#create-message>
     pub async fn send(
@@ -26,52 +47,54 @@ impl Message {
         channel_id: Snowflake,
         mut message: MessageSendSchema,
     ) -> ChorusResult<Message> {
-        let url_api = user.belongs_to.read().unwrap().urls.api.clone();
+        let route = routes::channel_messages(channel_id);
+        message.ensure_nonce();
+        if let Some(filename) = message.unknown_attachment_reference() {
+            return Err(ChorusError::UnknownAttachment {
+                filename: filename.to_string(),
+            });
+        }
+
+        if let Some(error) = message.flags_error() {
+            return Err(ChorusError::InvalidArguments { error });
+        }
+
+        let mut permissions = PermissionFlags::VIEW_CHANNEL | PermissionFlags::SEND_MESSAGES;
+        if message.attachments.is_some() {
+            permissions |= PermissionFlags::ATTACH_FILES;
+        }
+        if message.tts == Some(true) {
+            permissions |= PermissionFlags::SEND_TTS_MESSAGES;
+        }
+        user.require_permissions(permissions, channel_id).await?;
 
         if message.attachments.is_none() {
-            let chorus_request = ChorusRequest {
-                request: Client::new()
-                    .post(format!("{}/channels/{}/messages", url_api, channel_id))
-                    .header("Authorization", user.token())
-                    .body(to_string(&message).unwrap())
-                    .header("Content-Type", "application/json"),
-                limit_type: LimitType::Channel(channel_id),
-            };
-            chorus_request.deserialize_response::<Message>(user).await
+            ChorusRequest::from_route(
+                http::Method::POST,
+                route,
+                Some(to_string(&message).unwrap()),
+                None,
+                user,
+            )
+            .deserialize_response::<Message>(user)
+            .await
         } else {
+            if let Some(limit) = user.upload_limit(channel_id).await {
+                check_attachment_sizes(message.attachments.as_deref().unwrap_or_default(), limit)?;
+            }
             for (index, attachment) in message.attachments.iter_mut().enumerate() {
                 attachment.get_mut(index).unwrap().id = Some(index as i16);
             }
-            let mut form = reqwest::multipart::Form::new();
             let payload_json = to_string(&message).unwrap();
-            let payload_field = reqwest::multipart::Part::text(payload_json);
-
-            form = form.part("payload_json", payload_field);
-
-            for (index, attachment) in message.attachments.unwrap().into_iter().enumerate() {
-                let attachment_content = attachment.content;
-                let attachment_filename = attachment.filename;
-                let part_name = format!("files[{}]", index);
-                let content_disposition = format!(
-                    "form-data; name=\"{}\"'; filename=\"{}\"",
-                    part_name, &attachment_filename
-                );
-                let mut header_map = HeaderMap::new();
-                header_map.insert(CONTENT_DISPOSITION, content_disposition.parse().unwrap());
-
-                let part = multipart::Part::bytes(attachment_content)
-                    .file_name(attachment_filename)
-                    .headers(header_map);
-
-                form = form.part(part_name, part);
-            }
+            let form = multipart_form(payload_json, message.attachments.unwrap());
 
+            let url_api = user.belongs_to.read().unwrap().urls.api.clone();
             let chorus_request = ChorusRequest {
                 request: Client::new()
-                    .post(format!("{}/channels/{}/messages", url_api, channel_id))
+                    .post(route.url(&url_api))
                     .header("Authorization", user.token())
                     .multipart(form),
-                limit_type: LimitType::Channel(channel_id),
+                limit_type: route.limit_type(),
             };
             chorus_request.deserialize_response::<Message>(user).await
         }
@@ -92,22 +115,13 @@ impl Message {
         query: MessageSearchQuery,
         user: &mut ChorusUser,
     ) -> ChorusResult<Vec<Message>> {
-        let limit_type = match &endpoint {
-            MessageSearchEndpoint::Channel(id) => LimitType::Channel(*id),
-            MessageSearchEndpoint::GuildChannel(id) => LimitType::Guild(*id),
-        };
-        let request = ChorusRequest {
-            limit_type,
-            request: Client::new()
-                .get(format!(
-                    "{}/{}/messages/search",
-                    &user.belongs_to.read().unwrap().urls.api,
-                    endpoint
-                ))
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(to_string(&query).unwrap()),
-        };
+        let request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::messages_search(&endpoint),
+            Some(to_string(&query).unwrap()),
+            None,
+            user,
+        );
         let result = request.send_request(user).await?;
         let result_json = result.json::<Value>().await.unwrap();
         if !result_json.is_object() {
@@ -126,14 +140,46 @@ impl Message {
         }
         let code = value_map.get("code").unwrap().as_u64().unwrap();
         let retry_after = value_map.get("retry_after").unwrap().as_u64().unwrap();
-        Err(ChorusError::NotFound {
-            error: format!(
-                "Index not yet available. Try again later. Code: {}. Retry after {}s",
-                code, retry_after
-            ),
+        // The search index is still being built. The server answers with 202 Accepted
+        Err(ChorusError::ReceivedErrorCode {
+            route: format!("/{}/messages/search", endpoint),
+            source: ApiError {
+                status: 202,
+                code: Some(code),
+                message: format!(
+                    "Index not yet available. Try again later. Retry after {}s",
+                    retry_after
+                ),
+                ..Default::default()
+            },
         })
     }
 
+    /// Sends a message whose content may be longer than the instance allows, by splitting it into
+    /// multiple messages which are sent one after another. Returns the sent messages.
+    ///
+    /// The maximum length is taken from the instance's limits, or is 2000 characters if they are
+    /// not known. See [`MessageSendSchema::split_content`] for how the content is split.
+    ///
+    /// Stops at the first part which cannot be sent, and returns its error.
+    pub async fn send_long(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        message: MessageSendSchema,
+    ) -> ChorusResult<Vec<Message>> {
+        let max_length = user
+            .belongs_to
+            .read()
+            .unwrap()
+            .max_message_length()
+            .map_or(DEFAULT_MAX_MESSAGE_LENGTH, |length| length as usize);
+        let mut messages = Vec::new();
+        for part in message.split_content(max_length) {
+            messages.push(Message::send(user, channel_id, part).await?);
+        }
+        Ok(messages)
+    }
+
     /// Returns all pinned messages in the channel as a Vector of message objects without the reactions key.
     /// # Reference:
     /// See: <https://discord-userdoccers.vercel.app/resources/message#get-pinned-messages>
@@ -141,19 +187,12 @@ impl Message {
         channel_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<Vec<Message>> {
-        let chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::from_route(
             http::Method::GET,
-            format!(
-                "{}/channels/{}/pins",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id
-            )
-            .as_str(),
+            routes::channel_pins(channel_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         chorus_request
             .deserialize_response::<Vec<Message>>(user)
@@ -171,20 +210,12 @@ impl Message {
         audit_log_reason: Option<&str>,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::PUT,
-            format!(
-                "{}/channels/{}/pins/{}",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-                message_id
-            )
-            .as_str(),
+            routes::channel_pin(channel_id, message_id),
             None,
             audit_log_reason,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
@@ -198,20 +229,12 @@ impl Message {
         audit_log_reason: Option<&str>,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            format!(
-                "{}/channels/{}/pins/{}",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-                message_id
-            )
-            .as_str(),
+            routes::channel_pin(channel_id, message_id),
             None,
             audit_log_reason,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
@@ -221,26 +244,22 @@ impl Message {
     /// # Reference:
     /// See: <https://discord-userdoccers.vercel.app/resources/message#get-message>
     pub async fn get(
+        user: &mut ChorusUser,
         channel_id: Snowflake,
         message_id: Snowflake,
-        user: &mut ChorusUser,
     ) -> ChorusResult<Message> {
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .get(format!(
-                    "{}/channels/{}/messages/{}",
-                    user.belongs_to.read().unwrap().urls.api,
-                    channel_id,
-                    message_id
-                ))
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Channel(channel_id),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::channel_message(channel_id, message_id),
+            None,
+            None,
+            user,
+        );
         chorus_request.deserialize_response::<Message>(user).await
     }
 
     /// Posts a greet message to a channel. This endpoint requires the channel is a DM channel or you reply to a system message.
+    /// Greet messages only consist of stickers; see [`CreateGreetMessage::new`].
     /// # Reference:
     /// See: <https://discord-userdoccers.vercel.app/resources/message#create-greet-message>
     pub async fn create_greet(
@@ -248,19 +267,12 @@ impl Message {
         schema: CreateGreetMessage,
         user: &mut ChorusUser,
     ) -> ChorusResult<Message> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::POST,
-            format!(
-                "{}/channels/{}/messages/greet",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-            )
-            .as_str(),
+            routes::channel_greet(channel_id),
             Some(to_string(&schema).unwrap()),
             None,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         request.deserialize_response::<Message>(user).await
     }
@@ -281,20 +293,12 @@ impl Message {
         schema: MessageAck,
         user: &mut ChorusUser,
     ) -> ChorusResult<Option<String>> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::POST,
-            format!(
-                "{}/channels/{}/messages/{}/ack",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-                message_id
-            )
-            .as_str(),
+            routes::channel_message_ack(channel_id, message_id),
             Some(to_string(&schema).unwrap()),
             None,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         request.deserialize_response::<Option<String>>(user).await
     }
@@ -310,20 +314,12 @@ impl Message {
         message_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<Message> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::POST,
-            format!(
-                "{}/channels/{}/messages/{}/crosspost",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-                message_id
-            )
-            .as_str(),
-            None,
+            routes::channel_message_crosspost(channel_id, message_id),
             None,
             None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         request.deserialize_response::<Message>(user).await
     }
@@ -337,20 +333,12 @@ impl Message {
         message_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/hide-guild-feed",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id,
-            message_id
-        );
-        let chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
-            None,
+            routes::channel_message_hide_guild_feed(channel_id, message_id),
             None,
             None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         chorus_request.handle_request_as_result(user).await
     }
@@ -371,20 +359,12 @@ impl Message {
         schema: MessageModifySchema,
         user: &mut ChorusUser,
     ) -> ChorusResult<Message> {
-        let url = format!(
-            "{}/channels/{}/messages/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id,
-            message_id
-        );
-        let chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::from_route(
             http::Method::PATCH,
-            &url,
+            routes::channel_message(channel_id, message_id),
             Some(to_string(&schema).unwrap()),
             None,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         chorus_request.deserialize_response::<Message>(user).await
     }
@@ -397,21 +377,12 @@ impl Message {
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id,
-            message_id
-        );
-
-        let chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::channel_message(channel_id, message_id),
             None,
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
 
         chorus_request.handle_request_as_result(user).await
@@ -437,19 +408,12 @@ impl Message {
                 error: "`messages` must contain at least 2 entries.".to_string(),
             });
         }
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::POST,
-            format!(
-                "{}/channels/{}/messages/bulk-delete",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-            )
-            .as_str(),
+            routes::channel_messages_bulk_delete(channel_id),
             Some(to_string(&messages).unwrap()),
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
@@ -462,25 +426,66 @@ impl Message {
         channel_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let chorus_request = ChorusRequest::new(
+        let chorus_request = ChorusRequest::from_route(
             http::Method::POST,
-            format!(
-                "{}/channels/{}/pins/ack",
-                user.belongs_to.read().unwrap().urls.api,
-                channel_id,
-            )
-            .as_str(),
+            routes::channel_pins_ack(channel_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
 
         chorus_request.handle_request_as_result(user).await
     }
 }
 
+/// Builds the multipart form of a message with attachments, with the message's json in the
+/// `payload_json` field and the attachments in `files[n]` fields.
+pub(crate) fn multipart_form(
+    payload_json: String,
+    attachments: Vec<PartialDiscordFileAttachment>,
+) -> multipart::Form {
+    let mut form = multipart::Form::new();
+    form = form.part("payload_json", multipart::Part::text(payload_json));
+
+    for (index, attachment) in attachments.into_iter().enumerate() {
+        let attachment_content = attachment.content;
+        let attachment_filename = attachment.filename;
+        let part_name = format!("files[{}]", index);
+        let content_disposition = format!(
+            "form-data; name=\"{}\"'; filename=\"{}\"",
+            part_name, &attachment_filename
+        );
+        let mut header_map = HeaderMap::new();
+        header_map.insert(CONTENT_DISPOSITION, content_disposition.parse().unwrap());
+
+        let part = multipart::Part::bytes(attachment_content)
+            .file_name(attachment_filename)
+            .headers(header_map);
+
+        form = form.part(part_name, part);
+    }
+    form
+}
+
+/// Returns a [`ChorusError::AttachmentTooLarge`] for the first of the `attachments` which is
+/// larger than `limit` bytes.
+pub(crate) fn check_attachment_sizes(
+    attachments: &[PartialDiscordFileAttachment],
+    limit: u64,
+) -> ChorusResult<()> {
+    for attachment in attachments {
+        let size = attachment.content.len() as u64;
+        if size > limit {
+            return Err(ChorusError::AttachmentTooLarge {
+                filename: attachment.filename.clone(),
+                size,
+                limit,
+            });
+        }
+    }
+    Ok(())
+}
+
 fn search_error(result_text: String) -> ChorusError {
     ChorusError::InvalidResponse {
         error: format!(
@@ -526,4 +531,125 @@ impl Channel {
     ) -> ChorusResult<Vec<Message>> {
         Message::search(MessageSearchEndpoint::Channel(channel_id), query, user).await
     }
+
+    /// Sends a message in an announcement channel and publishes it to the channels following it,
+    /// returning the published message.
+    ///
+    /// Announcement channels can publish 10 messages per hour. If that limit is exhausted, the
+    /// message is still sent, and [`ChorusError::PublishRateLimited`] is returned; other errors
+    /// while publishing are returned as [`ChorusError::PublishFailed`]. In both cases, the message
+    /// can be published later with [`Message::crosspost`]. Errors while sending the message are
+    /// returned as they are.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#crosspost-message>
+    pub async fn send_and_publish(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        message: MessageSendSchema,
+    ) -> ChorusResult<Message> {
+        let message = Message::send(user, channel_id, message).await?;
+        let mut rate_limit_events = user.belongs_to.read().unwrap().rate_limit_events();
+        match Message::crosspost(channel_id, message.id, user).await {
+            Ok(published) => Ok(published),
+            Err(ChorusError::RateLimited { .. }) => {
+                let route = routes::channel_message_crosspost(channel_id, message.id);
+                let wait =
+                    RateLimitEvent::last_wait(&mut rate_limit_events, &route).unwrap_or_default();
+                Err(ChorusError::PublishRateLimited {
+                    message_id: message.id,
+                    retry_after_secs: wait.as_secs(),
+                })
+            }
+            Err(error) => Err(ChorusError::PublishFailed {
+                message_id: message.id,
+                error: Box::new(error),
+            }),
+        }
+    }
+
+    /// Sends a message and only returns once it has been confirmed, which is the primitive needed
+    /// for optimistic sending with rollback in client applications.
+    ///
+    /// The message is confirmed by its `MESSAGE_CREATE` dispatch on the user's gateway, which is
+    /// matched by the message's nonce (one is generated if it is not set). If the dispatch does
+    /// not arrive within `timeout`, the message is fetched through the API instead.
+    ///
+    /// If this returns an error, the message should be considered as not sent.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#create-message>
+    pub async fn send_with_confirmation(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        mut message: MessageSendSchema,
+        timeout: Duration,
+    ) -> ChorusResult<Message> {
+        let nonce = message.ensure_nonce().to_string();
+        let (sender, receiver) = oneshot::channel();
+        let observer = Arc::new(MessageConfirmationObserver {
+            channel_id,
+            nonce,
+            sender: Mutex::new(Some(sender)),
+        });
+        user.gateway
+            .events
+            .lock()
+            .await
+            .message
+            .create
+            .subscribe(observer.clone());
+
+        let sent = Message::send(user, channel_id, message).await;
+        let confirmed = match sent {
+            Ok(_) => crate::with_timeout(timeout, receiver)
+                .await
+                .and_then(|received| received.ok()),
+            Err(_) => None,
+        };
+        user.gateway
+            .events
+            .lock()
+            .await
+            .message
+            .create
+            .unsubscribe(observer.as_ref());
+
+        let sent = sent?;
+        match confirmed {
+            Some(message) => Ok(message),
+            None => {
+                log::debug!(
+                    "No MESSAGE_CREATE received for message {} within {:?}, fetching it instead",
+                    sent.id,
+                    timeout
+                );
+                Message::get(user, channel_id, sent.id).await
+            }
+        }
+    }
+}
+
+/// Waits for the `MESSAGE_CREATE` dispatch of a message sent with
+/// [`Channel::send_with_confirmation`].
+///
+/// The nonce is part of the [`Debug`] output, which tells concurrent confirmations apart when
+/// unsubscribing them.
+#[derive(Debug)]
+struct MessageConfirmationObserver {
+    channel_id: Snowflake,
+    nonce: String,
+    sender: Mutex<Option<oneshot::Sender<Message>>>,
+}
+
+#[async_trait]
+impl Observer<MessageCreate> for MessageConfirmationObserver {
+    async fn update(&self, data: &MessageCreate) {
+        if data.message.channel_id != self.channel_id || !data.matches_nonce(&self.nonce) {
+            return;
+        }
+        if let Some(sender) = self.sender.lock().unwrap().take() {
+            let _ = sender.send(data.message.clone());
+        }
+    }
 }
@@ -4,11 +4,23 @@
 
 #![allow(unused_imports)]
 pub use channels::*;
+pub use forum::*;
+pub use history::*;
 pub use messages::*;
+pub use overwrite_templates::*;
 pub use permissions::*;
 pub use reactions::*;
+pub use stage::*;
+pub use threads::*;
+pub use webhooks::*;
 
 pub mod channels;
+pub mod forum;
+pub mod history;
 pub mod messages;
+pub mod overwrite_templates;
 pub mod permissions;
 pub mod reactions;
+pub mod stage;
+pub mod threads;
+pub mod webhooks;
@@ -0,0 +1,199 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::types::{Channel, PermissionOverwrite, RoleObject, Snowflake};
+
+/// The `type` of permission overwrites for roles.
+const ROLE_OVERWRITE: &str = "0";
+/// The `type` of permission overwrites for members.
+const MEMBER_OVERWRITE: &str = "1";
+
+/// Who a [`TemplateOverwrite`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OverwriteTarget {
+    /// The @everyone role of whichever guild the template is applied in.
+    Everyone,
+    /// A role. In the guild the template was captured in, the role is found by its id; in other
+    /// guilds, by its name.
+    Role { id: Snowflake, name: Option<String> },
+    /// A member. User ids are the same in every guild, so member overwrites are kept as they are.
+    Member { id: Snowflake },
+}
+
+/// A permission overwrite of an [`OverwriteTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TemplateOverwrite {
+    pub target: OverwriteTarget,
+    pub allow: String,
+    pub deny: String,
+}
+
+/// The permission overwrites of a channel, captured to be applied to other channels and
+/// categories, possibly in other guilds.
+///
+/// # Example
+/// ```no_run
+/// # async fn example(user: &mut chorus::instance::ChorusUser) -> chorus::errors::ChorusResult<()> {
+/// use chorus::api::channels::OverwriteTemplate;
+/// use chorus::types::Snowflake;
+///
+/// let template = OverwriteTemplate::from_channel(user, Snowflake(1)).await?;
+/// for channel_id in [Snowflake(2), Snowflake(3)] {
+///     let applied = template.apply(user, channel_id, None).await?;
+///     if !applied.missing_roles.is_empty() {
+///         println!("No roles named {:?}", applied.missing_roles);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverwriteTemplate {
+    /// The guild the template was captured in.
+    pub guild_id: Option<Snowflake>,
+    pub overwrites: Vec<TemplateOverwrite>,
+}
+
+/// The permission overwrites of an [`OverwriteTemplate`], resolved for a guild.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedOverwrites {
+    pub overwrites: Vec<PermissionOverwrite>,
+    /// The names of the template's roles which the guild has no role for. Their overwrites are
+    /// left out.
+    pub missing_roles: Vec<String>,
+}
+
+impl OverwriteTemplate {
+    /// Captures the overwrites of a channel, with `roles` being the roles of the channel's guild.
+    pub fn capture(channel: &Channel, roles: &[RoleObject]) -> Self {
+        let guild_id = channel.guild_id;
+        let overwrites = channel
+            .overwrites()
+            .into_iter()
+            .map(|overwrite| {
+                let target = if overwrite.overwrite_type == MEMBER_OVERWRITE {
+                    OverwriteTarget::Member { id: overwrite.id }
+                } else if Some(overwrite.id) == guild_id {
+                    OverwriteTarget::Everyone
+                } else {
+                    OverwriteTarget::Role {
+                        id: overwrite.id,
+                        name: roles
+                            .iter()
+                            .find(|role| role.id == overwrite.id)
+                            .map(|role| role.name.clone()),
+                    }
+                };
+                TemplateOverwrite {
+                    target,
+                    allow: overwrite.allow,
+                    deny: overwrite.deny,
+                }
+            })
+            .collect();
+        Self {
+            guild_id,
+            overwrites,
+        }
+    }
+
+    /// Fetches a guild channel and the roles of its guild, and captures the channel's overwrites.
+    pub async fn from_channel(user: &mut ChorusUser, channel_id: Snowflake) -> ChorusResult<Self> {
+        let channel = Channel::get(user, channel_id).await?;
+        let guild_id = guild_id_of(&channel)?;
+        let roles = RoleObject::get_all(user, guild_id).await?;
+        Ok(Self::capture(&channel, &roles))
+    }
+
+    /// Resolves the template's overwrites for a guild with the given roles.
+    ///
+    /// If several roles have the name of a template role, the highest one is used.
+    pub fn resolve(&self, guild_id: Snowflake, roles: &[RoleObject]) -> ResolvedOverwrites {
+        let mut resolved = ResolvedOverwrites::default();
+        for overwrite in &self.overwrites {
+            let (id, overwrite_type) = match &overwrite.target {
+                OverwriteTarget::Everyone => (guild_id, ROLE_OVERWRITE),
+                OverwriteTarget::Member { id } => (*id, MEMBER_OVERWRITE),
+                OverwriteTarget::Role { id, name } => {
+                    let same_role = (Some(guild_id) == self.guild_id)
+                        .then(|| roles.iter().find(|role| role.id == *id))
+                        .flatten();
+                    let named_role = || {
+                        roles
+                            .iter()
+                            .filter(|role| Some(&role.name) == name.as_ref())
+                            .max_by_key(|role| role.position)
+                    };
+                    match same_role.or_else(named_role) {
+                        Some(role) => (role.id, ROLE_OVERWRITE),
+                        None => {
+                            resolved
+                                .missing_roles
+                                .push(name.clone().unwrap_or_else(|| id.to_string()));
+                            continue;
+                        }
+                    }
+                }
+            };
+            resolved.overwrites.push(PermissionOverwrite {
+                id,
+                overwrite_type: overwrite_type.to_string(),
+                allow: overwrite.allow.clone(),
+                deny: overwrite.deny.clone(),
+            });
+        }
+        resolved
+    }
+
+    /// Replaces the overwrites of a guild channel or category with the overwrites of this
+    /// template, resolved for the channel's guild. Overwrites the channel has, but the template
+    /// does not, are deleted.
+    ///
+    /// Returns the overwrites which were set.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
+    pub async fn apply(
+        &self,
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<ResolvedOverwrites> {
+        let channel = Channel::get(user, channel_id).await?;
+        let guild_id = guild_id_of(&channel)?;
+        let roles = RoleObject::get_all(user, guild_id).await?;
+        let resolved = self.resolve(guild_id, &roles);
+
+        for overwrite in &resolved.overwrites {
+            Channel::modify_permissions(
+                user,
+                channel_id,
+                audit_log_reason.clone(),
+                overwrite.clone(),
+            )
+            .await?;
+        }
+        for overwrite in channel.overwrites() {
+            if !resolved.overwrites.iter().any(|new| new.id == overwrite.id) {
+                Channel::delete_permission(user, channel_id, overwrite.id).await?;
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+fn guild_id_of(channel: &Channel) -> ChorusResult<Snowflake> {
+    channel
+        .guild_id
+        .ok_or_else(|| ChorusError::InvalidArguments {
+            error: format!(
+                "Channel {} is not a guild channel and has no permission overwrites",
+                channel.id
+            ),
+        })
+}
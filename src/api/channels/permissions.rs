@@ -5,6 +5,7 @@
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::{
     errors::{ChorusError, ChorusResult},
     instance::ChorusUser,
@@ -29,12 +30,7 @@ impl types::Channel {
         audit_log_reason: Option<String>,
         overwrite: PermissionOverwrite,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/permissions/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id,
-            overwrite.id
-        );
+        let route = routes::channel_permission(channel_id, overwrite.id);
         let body = match to_string(&overwrite) {
             Ok(string) => string,
             Err(e) => {
@@ -43,19 +39,15 @@ impl types::Channel {
                 });
             }
         };
-        let mut request = Client::new()
-            .put(url)
-            .header("Authorization", user.token())
-            .header("Content-Type", "application/json")
-            .body(body);
-        if let Some(reason) = audit_log_reason {
-            request = request.header("X-Audit-Log-Reason", reason);
-        }
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::Channel(channel_id),
-        };
-        chorus_request.handle_request_as_result(user).await
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            route,
+            Some(body),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
     }
 
     /// Deletes a permission overwrite for a user or role in a channel.
@@ -71,21 +63,12 @@ impl types::Channel {
         channel_id: Snowflake,
         overwrite_id: Snowflake,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/permissions/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            channel_id,
-            overwrite_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
-            None,
+            routes::channel_permission(channel_id, overwrite_id),
             None,
             None,
-            Some(user),
-            LimitType::Channel(channel_id),
+            user,
         );
 
         request.handle_request_as_result(user).await
@@ -2,11 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use crate::api::routes;
 use crate::{
     errors::ChorusResult,
     instance::ChorusUser,
     ratelimiter::ChorusRequest,
-    types::{self, LimitType, PublicUser, Snowflake},
+    types::{self, LimitType, PublicUser, ReactionType, Snowflake},
 };
 
 /// Useful metadata for working with [`types::Reaction`], bundled together nicely.
@@ -24,21 +25,12 @@ impl ReactionMeta {
     /// # Reference
     /// See <https://discord.com/developers/docs/resources/channel#delete-all-reactions>
     pub async fn delete_all(&self, user: &mut ChorusUser) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/reactions",
-            user.belongs_to.read().unwrap().urls.api,
-            self.channel_id,
-            self.message_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::channel_message_reactions(self.channel_id, self.message_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Channel(self.channel_id),
+            user,
         );
 
         request.handle_request_as_result(user).await
@@ -52,23 +44,28 @@ impl ReactionMeta {
     /// # Reference
     /// See <https://discord.com/developers/docs/resources/channel#get-reactions>
     pub async fn get(&self, emoji: &str, user: &mut ChorusUser) -> ChorusResult<Vec<PublicUser>> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/reactions/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            self.channel_id,
-            self.message_id,
-            emoji
-        );
+        self.get_with_type(emoji, ReactionType::Normal, user).await
+    }
 
-        let request = ChorusRequest::new(
-            http::Method::GET,
-            &url,
-            None,
-            None,
-            None,
-            Some(user),
-            LimitType::Channel(self.channel_id),
-        );
+    /// Gets a list of users that reacted with a specific emoji and [`ReactionType`] to a message.
+    ///
+    /// The emoji must be URL Encoded or the request will fail with 10014: Unknown Emoji.
+    /// To use custom emoji, the format of the emoji string must be name:id.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#get-reactions>
+    pub async fn get_with_type(
+        &self,
+        emoji: &str,
+        reaction_type: ReactionType,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<PublicUser>> {
+        let mut route = routes::channel_message_reaction(self.channel_id, self.message_id, emoji);
+        // Only burst reactions need the parameter, so that instances without them keep working
+        if reaction_type != ReactionType::Normal {
+            route = route.query("type", reaction_type as u8);
+        }
+        let request = ChorusRequest::from_route(http::Method::GET, route, None, None, user);
 
         request.deserialize_response::<Vec<PublicUser>>(user).await
     }
@@ -83,22 +80,12 @@ impl ReactionMeta {
     /// # Reference
     /// See <https://discord.com/developers/docs/resources/channel#delete-all-reactions-for-emoji>
     pub async fn delete_emoji(&self, emoji: &str, user: &mut ChorusUser) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/reactions/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            self.channel_id,
-            self.message_id,
-            emoji
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::channel_message_reaction(self.channel_id, self.message_id, emoji),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Channel(self.channel_id),
+            user,
         );
 
         request.handle_request_as_result(user).await
@@ -117,23 +104,29 @@ impl ReactionMeta {
     /// # Reference
     /// See <https://discord.com/developers/docs/resources/channel#create-reaction>
     pub async fn create(&self, emoji: &str, user: &mut ChorusUser) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/reactions/{}/@me",
-            user.belongs_to.read().unwrap().urls.api,
-            self.channel_id,
-            self.message_id,
-            emoji
-        );
+        self.create_with_type(emoji, ReactionType::Normal, user)
+            .await
+    }
 
-        let request = ChorusRequest::new(
-            http::Method::PUT,
-            &url,
-            None,
-            None,
-            None,
-            Some(user),
-            LimitType::Channel(self.channel_id),
-        );
+    /// Create a reaction of the given [`ReactionType`] on a message. See [`ReactionMeta::create`]
+    /// for the required permissions.
+    ///
+    /// Burst reactions require the user to have Nitro, or remaining free burst reactions.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#create-reaction>
+    pub async fn create_with_type(
+        &self,
+        emoji: &str,
+        reaction_type: ReactionType,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let mut route =
+            routes::channel_message_own_reaction(self.channel_id, self.message_id, emoji);
+        if reaction_type != ReactionType::Normal {
+            route = route.query("type", reaction_type as u8);
+        }
+        let request = ChorusRequest::from_route(http::Method::PUT, route, None, None, user);
 
         request.handle_request_as_result(user).await
     }
@@ -146,23 +139,32 @@ impl ReactionMeta {
     /// # Reference
     /// See <https://discord.com/developers/docs/resources/channel#delete-own-reaction>
     pub async fn remove(&self, emoji: &str, user: &mut ChorusUser) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/reactions/{}/@me",
-            user.belongs_to.read().unwrap().urls.api,
-            self.channel_id,
-            self.message_id,
-            emoji
-        );
+        self.remove_with_type(emoji, ReactionType::Normal, user)
+            .await
+    }
 
-        let request = ChorusRequest::new(
-            http::Method::DELETE,
-            &url,
-            None,
-            None,
-            None,
-            Some(user),
-            LimitType::Channel(self.channel_id),
-        );
+    /// Deletes a reaction of the given [`ReactionType`] the current user has made to the message.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#delete-own-reaction>
+    pub async fn remove_with_type(
+        &self,
+        emoji: &str,
+        reaction_type: ReactionType,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<()> {
+        let route = match reaction_type {
+            ReactionType::Normal => {
+                routes::channel_message_own_reaction(self.channel_id, self.message_id, emoji)
+            }
+            _ => routes::channel_message_own_typed_reaction(
+                self.channel_id,
+                self.message_id,
+                emoji,
+                reaction_type as u8,
+            ),
+        };
+        let request = ChorusRequest::from_route(http::Method::DELETE, route, None, None, user);
 
         request.handle_request_as_result(user).await
     }
@@ -182,23 +184,12 @@ impl ReactionMeta {
         emoji: &str,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/channels/{}/messages/{}/reactions/{}/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            self.channel_id,
-            self.message_id,
-            emoji,
-            user_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
-            None,
+            routes::channel_message_user_reaction(self.channel_id, self.message_id, emoji, user_id),
             None,
             None,
-            Some(user),
-            LimitType::Channel(self.channel_id),
+            user,
         );
 
         request.handle_request_as_result(user).await
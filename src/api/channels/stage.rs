@@ -0,0 +1,291 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::Utc;
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    Channel, ChannelType, Snowflake, StageInstance, StageInstanceCreateSchema,
+    StageInstanceModifySchema, VoiceStateModifyCurrentUserSchema, VoiceStateModifySchema,
+};
+
+impl StageInstance {
+    /// Starts a stage in a stage channel.
+    ///
+    /// Requires the [`MANAGE_CHANNELS`](crate::types::PermissionFlags::MANAGE_CHANNELS),
+    /// [`MUTE_MEMBERS`](crate::types::PermissionFlags::MUTE_MEMBERS) and
+    /// [`MOVE_MEMBERS`](crate::types::PermissionFlags::MOVE_MEMBERS) permissions.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/stage-instance#create-stage-instance>
+    pub async fn create(
+        user: &mut ChorusUser,
+        schema: StageInstanceCreateSchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<StageInstance> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::stage_instances(),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<StageInstance>(user)
+        .await
+    }
+
+    /// Returns the stage which is live in a stage channel.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/stage-instance#get-stage-instance>
+    pub async fn get(user: &mut ChorusUser, channel_id: Snowflake) -> ChorusResult<StageInstance> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::stage_instance(channel_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<StageInstance>(user)
+        .await
+    }
+
+    /// Modifies the stage which is live in a stage channel.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/stage-instance#modify-stage-instance>
+    pub async fn modify(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        schema: StageInstanceModifySchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<StageInstance> {
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::stage_instance(channel_id),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<StageInstance>(user)
+        .await
+    }
+
+    /// Ends the stage which is live in a stage channel.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/stage-instance#delete-stage-instance>
+    pub async fn delete(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::stage_instance(channel_id),
+            None,
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+}
+
+/// Manages the stage and the speakers of a stage channel.
+///
+/// Combines the [`StageInstance`] endpoints with the endpoints which modify the voice states of
+/// the channel's members.
+///
+/// # Example
+/// ```no_run
+/// # async fn example(user: &mut chorus::instance::ChorusUser) -> chorus::errors::ChorusResult<()> {
+/// use chorus::api::channels::StageChannel;
+/// use chorus::types::Snowflake;
+///
+/// let stage = StageChannel::new(Snowflake(1), Snowflake(2));
+/// stage.open_stage(user, "Weekly Q&A").await?;
+/// stage.invite_to_speak(user, Snowflake(3)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StageChannel {
+    pub guild_id: Snowflake,
+    pub channel_id: Snowflake,
+}
+
+impl StageChannel {
+    pub fn new(guild_id: Snowflake, channel_id: Snowflake) -> Self {
+        Self {
+            guild_id,
+            channel_id,
+        }
+    }
+
+    /// Returns the stage channel, or `None` if the channel is not a stage channel.
+    pub fn from_channel(channel: &Channel) -> Option<Self> {
+        if channel.channel_type != ChannelType::GuildStageVoice {
+            return None;
+        }
+        Some(Self::new(channel.guild_id?, channel.id))
+    }
+
+    /// Starts a stage with the given topic. See [`StageInstance::create`].
+    pub async fn open_stage(
+        &self,
+        user: &mut ChorusUser,
+        topic: &str,
+    ) -> ChorusResult<StageInstance> {
+        StageInstance::create(
+            user,
+            StageInstanceCreateSchema {
+                channel_id: self.channel_id,
+                topic: topic.to_string(),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+    }
+
+    /// Returns the live stage. See [`StageInstance::get`].
+    pub async fn stage(&self, user: &mut ChorusUser) -> ChorusResult<StageInstance> {
+        StageInstance::get(user, self.channel_id).await
+    }
+
+    /// Changes the topic of the live stage. See [`StageInstance::modify`].
+    pub async fn set_topic(
+        &self,
+        user: &mut ChorusUser,
+        topic: &str,
+    ) -> ChorusResult<StageInstance> {
+        StageInstance::modify(
+            user,
+            self.channel_id,
+            StageInstanceModifySchema {
+                topic: Some(topic.to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+    }
+
+    /// Ends the live stage. See [`StageInstance::delete`].
+    pub async fn close_stage(&self, user: &mut ChorusUser) -> ChorusResult<()> {
+        StageInstance::delete(user, self.channel_id, None).await
+    }
+
+    /// Invites an audience member to speak. Requires the
+    /// [`MUTE_MEMBERS`](crate::types::PermissionFlags::MUTE_MEMBERS) permission.
+    pub async fn invite_to_speak(
+        &self,
+        user: &mut ChorusUser,
+        user_id: Snowflake,
+    ) -> ChorusResult<()> {
+        self.set_suppressed(user, user_id, false).await
+    }
+
+    /// Moves a speaker back into the audience. Requires the
+    /// [`MUTE_MEMBERS`](crate::types::PermissionFlags::MUTE_MEMBERS) permission.
+    pub async fn move_to_audience(
+        &self,
+        user: &mut ChorusUser,
+        user_id: Snowflake,
+    ) -> ChorusResult<()> {
+        self.set_suppressed(user, user_id, true).await
+    }
+
+    /// Requests to speak, as the current user. Requires the
+    /// [`REQUEST_TO_SPEAK`](crate::types::PermissionFlags::REQUEST_TO_SPEAK) permission.
+    pub async fn raise_hand(&self, user: &mut ChorusUser) -> ChorusResult<()> {
+        self.modify_own_voice_state(
+            user,
+            VoiceStateModifyCurrentUserSchema {
+                channel_id: Some(self.channel_id),
+                request_to_speak_timestamp: Some(Some(Utc::now())),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Withdraws the current user's request to speak.
+    pub async fn lower_hand(&self, user: &mut ChorusUser) -> ChorusResult<()> {
+        self.modify_own_voice_state(
+            user,
+            VoiceStateModifyCurrentUserSchema {
+                channel_id: Some(self.channel_id),
+                request_to_speak_timestamp: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Becomes a speaker, or moves back into the audience, as the current user. Becoming a
+    /// speaker requires the [`MUTE_MEMBERS`](crate::types::PermissionFlags::MUTE_MEMBERS)
+    /// permission, or an invitation to speak.
+    pub async fn set_own_suppressed(
+        &self,
+        user: &mut ChorusUser,
+        suppress: bool,
+    ) -> ChorusResult<()> {
+        self.modify_own_voice_state(
+            user,
+            VoiceStateModifyCurrentUserSchema {
+                channel_id: Some(self.channel_id),
+                suppress: Some(suppress),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/voice#modify-current-user-voice-state>
+    async fn modify_own_voice_state(
+        &self,
+        user: &mut ChorusUser,
+        schema: VoiceStateModifyCurrentUserSchema,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_own_voice_state(self.guild_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/voice#modify-user-voice-state>
+    async fn set_suppressed(
+        &self,
+        user: &mut ChorusUser,
+        user_id: Snowflake,
+        suppress: bool,
+    ) -> ChorusResult<()> {
+        let schema = VoiceStateModifySchema {
+            channel_id: self.channel_id,
+            suppress: Some(suppress),
+        };
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_voice_state(self.guild_id, user_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+}
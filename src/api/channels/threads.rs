@@ -0,0 +1,217 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    ArchivedThreads, Channel, GetArchivedThreadsSchema, GetThreadMembersSchema, Snowflake,
+    StartThreadFromMessageSchema, StartThreadWithoutMessageSchema, ThreadMember,
+};
+
+impl Channel {
+    /// Creates a thread from an existing message, and returns the thread.
+    ///
+    /// Requires the [`CREATE_PUBLIC_THREADS`](crate::types::PermissionFlags::CREATE_PUBLIC_THREADS)
+    /// permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#create-thread-from-message>
+    pub async fn start_thread_from_message(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        schema: StartThreadFromMessageSchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<Channel> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::channel_message_threads(channel_id, message_id),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<Channel>(user)
+        .await
+    }
+
+    /// Creates a thread which does not start from an existing message, and returns the thread.
+    ///
+    /// Requires the [`CREATE_PUBLIC_THREADS`](crate::types::PermissionFlags::CREATE_PUBLIC_THREADS)
+    /// or [`CREATE_PRIVATE_THREADS`](crate::types::PermissionFlags::CREATE_PRIVATE_THREADS)
+    /// permission, depending on the type of the thread.
+    ///
+    /// For threads in forum and media channels, see
+    /// [`ForumChannel::create_post`](crate::api::ForumChannel::create_post).
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#create-thread>
+    pub async fn start_thread_without_message(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        schema: StartThreadWithoutMessageSchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<Channel> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::channel_threads(channel_id),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<Channel>(user)
+        .await
+    }
+
+    /// Adds the current user to a thread.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#join-thread>
+    pub async fn join_thread(user: &mut ChorusUser, thread_id: Snowflake) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::channel_own_thread_member(thread_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Removes the current user from a thread.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#leave-thread>
+    pub async fn leave_thread(user: &mut ChorusUser, thread_id: Snowflake) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::channel_own_thread_member(thread_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Adds another user to a thread.
+    ///
+    /// Requires being able to send messages in the thread.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#add-thread-member>
+    pub async fn add_thread_member(
+        user: &mut ChorusUser,
+        thread_id: Snowflake,
+        user_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::channel_thread_member(thread_id, user_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Removes another user from a thread.
+    ///
+    /// Requires the [`MANAGE_THREADS`](crate::types::PermissionFlags::MANAGE_THREADS)
+    /// permission, or being the creator of a private thread.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#remove-thread-member>
+    pub async fn remove_thread_member(
+        user: &mut ChorusUser,
+        thread_id: Snowflake,
+        user_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::channel_thread_member(thread_id, user_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Returns the members of a thread, sorted by user id.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#get-thread-members>
+    pub async fn list_thread_members(
+        user: &mut ChorusUser,
+        thread_id: Snowflake,
+        query: GetThreadMembersSchema,
+    ) -> ChorusResult<Vec<ThreadMember>> {
+        let mut chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::channel_thread_members(thread_id),
+            None,
+            None,
+            user,
+        );
+        chorus_request.request = chorus_request.request.query(&query);
+        chorus_request
+            .deserialize_response::<Vec<ThreadMember>>(user)
+            .await
+    }
+
+    /// Returns the archived public threads of a channel, most recently archived first.
+    ///
+    /// Further pages can be requested with [`ArchivedThreads::next_page`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#get-public-archived-threads>
+    pub async fn list_public_archived_threads(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        query: GetArchivedThreadsSchema,
+    ) -> ChorusResult<ArchivedThreads> {
+        let mut chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::channel_public_archived_threads(channel_id),
+            None,
+            None,
+            user,
+        );
+        chorus_request.request = chorus_request.request.query(&query);
+        chorus_request
+            .deserialize_response::<ArchivedThreads>(user)
+            .await
+    }
+
+    /// Returns the archived private threads of a channel, most recently archived first.
+    ///
+    /// Requires the [`MANAGE_THREADS`](crate::types::PermissionFlags::MANAGE_THREADS)
+    /// permission. Further pages can be requested with [`ArchivedThreads::next_page`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#get-private-archived-threads>
+    pub async fn list_private_archived_threads(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        query: GetArchivedThreadsSchema,
+    ) -> ChorusResult<ArchivedThreads> {
+        let mut chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::channel_private_archived_threads(channel_id),
+            None,
+            None,
+            user,
+        );
+        chorus_request.request = chorus_request.request.query(&query);
+        chorus_request
+            .deserialize_response::<ArchivedThreads>(user)
+            .await
+    }
+}
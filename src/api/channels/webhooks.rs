@@ -0,0 +1,432 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use http::HeaderMap;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde_json::{from_str, to_string};
+
+use crate::api::routes::{self, Route};
+use crate::errors::{ApiError, ChorusError, ChorusResult};
+use crate::instance::{ChorusUser, Instance};
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    Message, PermissionFlags, Snowflake, Webhook, WebhookCreateSchema, WebhookExecuteQuery,
+    WebhookExecuteSchema, WebhookModifySchema,
+};
+
+use super::messages::{check_attachment_sizes, multipart_form};
+
+impl Webhook {
+    /// Creates a webhook in a channel.
+    ///
+    /// Requires the [`MANAGE_WEBHOOKS`](PermissionFlags::MANAGE_WEBHOOKS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#create-webhook>
+    pub async fn create(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+        schema: WebhookCreateSchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<Webhook> {
+        user.require_permissions(PermissionFlags::MANAGE_WEBHOOKS, channel_id)
+            .await?;
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::channel_webhooks(channel_id),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<Webhook>(user)
+        .await
+    }
+
+    /// Returns the webhooks of a channel.
+    ///
+    /// Requires the [`MANAGE_WEBHOOKS`](PermissionFlags::MANAGE_WEBHOOKS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#get-channel-webhooks>
+    pub async fn get_channel_webhooks(
+        user: &mut ChorusUser,
+        channel_id: Snowflake,
+    ) -> ChorusResult<Vec<Webhook>> {
+        user.require_permissions(PermissionFlags::MANAGE_WEBHOOKS, channel_id)
+            .await?;
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::channel_webhooks(channel_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<Webhook>>(user)
+        .await
+    }
+
+    /// Returns the webhooks of a guild.
+    ///
+    /// Requires the [`MANAGE_WEBHOOKS`](PermissionFlags::MANAGE_WEBHOOKS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#get-guild-webhooks>
+    pub async fn get_guild_webhooks(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+    ) -> ChorusResult<Vec<Webhook>> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_webhooks(guild_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<Webhook>>(user)
+        .await
+    }
+
+    /// Returns a webhook by its id.
+    ///
+    /// Requires the [`MANAGE_WEBHOOKS`](PermissionFlags::MANAGE_WEBHOOKS) permission. Use
+    /// [`WebhookClient::get`] to get a webhook with its token instead.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#get-webhook>
+    pub async fn get(user: &mut ChorusUser, webhook_id: Snowflake) -> ChorusResult<Webhook> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::webhook(webhook_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Webhook>(user)
+        .await
+    }
+
+    /// Modifies a webhook, which can also be moved to another channel of its guild.
+    ///
+    /// Requires the [`MANAGE_WEBHOOKS`](PermissionFlags::MANAGE_WEBHOOKS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#modify-webhook>
+    pub async fn modify(
+        user: &mut ChorusUser,
+        webhook_id: Snowflake,
+        schema: WebhookModifySchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<Webhook> {
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::webhook(webhook_id),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<Webhook>(user)
+        .await
+    }
+
+    /// Deletes a webhook.
+    ///
+    /// Requires the [`MANAGE_WEBHOOKS`](PermissionFlags::MANAGE_WEBHOOKS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#delete-webhook>
+    pub async fn delete(
+        user: &mut ChorusUser,
+        webhook_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::webhook(webhook_id),
+            None,
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Sends a message through a webhook, with the webhook's token.
+    ///
+    /// Returns the sent message if [`WebhookExecuteQuery::wait`] is set, `None` otherwise. The
+    /// checks of [`Message::send`] for attachments are applied as well.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#execute-webhook>
+    pub async fn execute(
+        user: &mut ChorusUser,
+        webhook_id: Snowflake,
+        webhook_token: &str,
+        schema: WebhookExecuteSchema,
+        query: WebhookExecuteQuery,
+    ) -> ChorusResult<Option<Message>> {
+        let route = routes::webhook_with_token(webhook_id, webhook_token);
+        let api_url = user.belongs_to.read().unwrap().urls.api.clone();
+        let upload_limit = user.belongs_to.read().unwrap().max_attachment_size();
+        let client = user.belongs_to.read().unwrap().client.clone();
+        let request = ChorusRequest {
+            request: execute_request(&client, &route, &api_url, upload_limit, schema, query)?,
+            limit_type: route.limit_type(),
+        };
+        if query.wait == Some(true) {
+            request
+                .deserialize_response::<Message>(user)
+                .await
+                .map(Some)
+        } else {
+            request.handle_request_as_result(user).await.map(|_| None)
+        }
+    }
+}
+
+/// Builds the request to execute a webhook, which is authenticated by the token in its route.
+fn execute_request(
+    client: &Client,
+    route: &Route,
+    api_url: &str,
+    upload_limit: Option<u64>,
+    mut schema: WebhookExecuteSchema,
+    query: WebhookExecuteQuery,
+) -> ChorusResult<RequestBuilder> {
+    if let Some(filename) = schema.unknown_attachment_reference() {
+        return Err(ChorusError::UnknownAttachment {
+            filename: filename.to_string(),
+        });
+    }
+    if let Some(error) = schema.flags_error() {
+        return Err(ChorusError::InvalidArguments { error });
+    }
+    let request = client.post(route.url(api_url)).query(&query);
+    match schema.attachments.take() {
+        None => Ok(request
+            .body(to_string(&schema).unwrap())
+            .header("Content-Type", "application/json")),
+        Some(mut attachments) => {
+            if let Some(limit) = upload_limit {
+                check_attachment_sizes(&attachments, limit)?;
+            }
+            for (index, attachment) in attachments.iter_mut().enumerate() {
+                attachment.id = Some(index as i16);
+            }
+            schema.attachments = Some(attachments);
+            let payload_json = to_string(&schema).unwrap();
+            Ok(request.multipart(multipart_form(payload_json, schema.attachments.unwrap())))
+        }
+    }
+}
+
+/// Manages and executes a single webhook with only its id and token, without a
+/// [`ChorusUser`], for example to post to a channel from a script.
+///
+/// Requests are not rate limited by chorus; rate limited requests fail with a
+/// [`ChorusError::ReceivedErrorCode`] instead. To send them with the client and headers of an
+/// [`Instance`], create the webhook client with [`WebhookClient::for_instance`].
+///
+/// # Example
+/// ```no_run
+/// # async fn example() -> chorus::errors::ChorusResult<()> {
+/// use chorus::api::WebhookClient;
+/// use chorus::types::{WebhookExecuteQuery, WebhookExecuteSchema};
+///
+/// let webhook = WebhookClient::from_url("https://example.com/api/webhooks/1234/token")?;
+/// let schema = WebhookExecuteSchema {
+///     content: Some("Hello!".to_string()),
+///     ..Default::default()
+/// };
+/// let query = WebhookExecuteQuery {
+///     wait: Some(true),
+///     ..Default::default()
+/// };
+/// let message = webhook.execute(schema, query).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebhookClient {
+    /// The url of the instance's api, such as `https://example.com/api`
+    pub api_url: String,
+    pub id: Snowflake,
+    pub token: String,
+    /// The maximum size of attachments in bytes, which are checked before uploading them if set
+    pub upload_limit: Option<u64>,
+    /// Headers sent with every request, see [`Instance::default_headers`]
+    pub headers: HeaderMap,
+    client: Client,
+}
+
+impl PartialEq for WebhookClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.api_url == other.api_url
+            && self.id == other.id
+            && self.token == other.token
+            && self.upload_limit == other.upload_limit
+            && self.headers == other.headers
+    }
+}
+
+impl Eq for WebhookClient {}
+
+impl WebhookClient {
+    pub fn new(api_url: impl Into<String>, id: Snowflake, token: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            id,
+            token: token.into(),
+            upload_limit: None,
+            headers: HeaderMap::new(),
+            client: Client::new(),
+        }
+    }
+
+    /// Creates a client for a webhook of the given instance, which sends its requests with the
+    /// instance's client and headers, and checks attachments against the instance's upload
+    /// limit.
+    pub fn for_instance(instance: &Instance, id: Snowflake, token: impl Into<String>) -> Self {
+        Self {
+            api_url: instance.urls.api.clone(),
+            id,
+            token: token.into(),
+            upload_limit: instance.max_attachment_size(),
+            headers: instance.request_headers(),
+            client: instance.client.clone(),
+        }
+    }
+
+    /// Creates a client from the url of a webhook, such as
+    /// `https://example.com/api/webhooks/{id}/{token}`.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if the url is not the url of a webhook.
+    pub fn from_url(url: &str) -> ChorusResult<Self> {
+        let invalid = || ChorusError::InvalidArguments {
+            error: format!("{} is not the url of a webhook", url),
+        };
+        let url = url.split(['?', '#']).next().unwrap_or_default();
+        let (api_url, path) = url.rsplit_once("/webhooks/").ok_or_else(invalid)?;
+        let (id, token) = path
+            .trim_end_matches('/')
+            .split_once('/')
+            .ok_or_else(invalid)?;
+        let id = id.parse::<u64>().map_err(|_| invalid())?;
+        if token.is_empty() || token.contains('/') {
+            return Err(invalid());
+        }
+        Ok(Self::new(api_url, Snowflake(id), token))
+    }
+
+    fn route(&self) -> Route {
+        routes::webhook_with_token(self.id, &self.token)
+    }
+
+    fn request(&self, method: http::Method, route: &Route) -> RequestBuilder {
+        self.client
+            .request(method, route.url(&self.api_url))
+            .headers(self.headers.clone())
+    }
+
+    /// Returns the webhook, without its user.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#get-webhook-with-token>
+    pub async fn get(&self) -> ChorusResult<Webhook> {
+        let route = self.route();
+        let request = self.request(http::Method::GET, &route);
+        deserialize(&route, send(&route, request).await?).await
+    }
+
+    /// Modifies the webhook. Its [channel](WebhookModifySchema::channel_id) can not be changed
+    /// with the token, which fails with [`ChorusError::InvalidArguments`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#modify-webhook-with-token>
+    pub async fn modify(&self, schema: WebhookModifySchema) -> ChorusResult<Webhook> {
+        if schema.channel_id.is_some() {
+            return Err(ChorusError::InvalidArguments {
+                error: "The channel of a webhook can not be changed with its token".to_string(),
+            });
+        }
+        let route = self.route();
+        let request = self
+            .request(http::Method::PATCH, &route)
+            .body(to_string(&schema).unwrap())
+            .header("Content-Type", "application/json");
+        deserialize(&route, send(&route, request).await?).await
+    }
+
+    /// Deletes the webhook.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#delete-webhook-with-token>
+    pub async fn delete(&self) -> ChorusResult<()> {
+        let route = self.route();
+        let request = self.request(http::Method::DELETE, &route);
+        send(&route, request).await.map(|_| ())
+    }
+
+    /// Sends a message through the webhook, see [`Webhook::execute`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/webhook#execute-webhook>
+    pub async fn execute(
+        &self,
+        schema: WebhookExecuteSchema,
+        query: WebhookExecuteQuery,
+    ) -> ChorusResult<Option<Message>> {
+        let route = self.route();
+        let request = execute_request(
+            &self.client,
+            &route,
+            &self.api_url,
+            self.upload_limit,
+            schema,
+            query,
+        )?
+        .headers(self.headers.clone());
+        let response = send(&route, request).await?;
+        if query.wait == Some(true) {
+            deserialize(&route, response).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Sends a request of a [`WebhookClient`], returning the response if it was successful.
+async fn send(route: &Route, request: RequestBuilder) -> ChorusResult<Response> {
+    let response = request
+        .send()
+        .await
+        .map_err(|error| ChorusError::RequestFailed {
+            url: route.path().to_string(),
+            error: error.to_string(),
+        })?;
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ChorusError::ReceivedErrorCode {
+            route: route.path().to_string(),
+            source: ApiError::from_response_body(status, &body),
+        });
+    }
+    Ok(response)
+}
+
+async fn deserialize<T: DeserializeOwned>(route: &Route, response: Response) -> ChorusResult<T> {
+    let body = response
+        .text()
+        .await
+        .map_err(|error| ChorusError::InvalidResponse {
+            error: error.to_string(),
+        })?;
+    from_str(&body).map_err(|error| ChorusError::InvalidResponse {
+        error: format!(
+            "Error while deserializing the response of {}: {}. JSON Response: {}",
+            route, error, body
+        ),
+    })
+}
@@ -0,0 +1,22 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::GatewayBotResponse;
+
+impl ChorusUser {
+    /// Fetches how many shards the bot should connect with, and how many sessions it can still
+    /// start. Only available to bots, see [`ShardManager`](crate::gateway::ShardManager).
+    ///
+    /// # Reference
+    /// See <https://docs.discord.sex/topics/gateway#get-gateway-bot>
+    pub async fn get_gateway_bot(&mut self) -> ChorusResult<GatewayBotResponse> {
+        ChorusRequest::from_route(http::Method::GET, routes::gateway_bot(), None, None, self)
+            .deserialize_response::<GatewayBotResponse>(self)
+            .await
+    }
+}
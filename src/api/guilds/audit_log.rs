@@ -0,0 +1,223 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::gateway::{GatewayHandle, Observer};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    AuditLog, AuditLogEntry, Guild, GuildAuditLogEntryCreate, GuildAuditLogsQuery, Snowflake,
+};
+
+/// The maximum amount of entries returned by [`Guild::audit_logs`] at once.
+const AUDIT_LOG_PAGE_SIZE: u8 = 100;
+
+impl Guild {
+    /// Returns the audit log of a guild, newest entries first.
+    ///
+    /// Requires the [`VIEW_AUDIT_LOG`](crate::types::PermissionFlags::VIEW_AUDIT_LOG) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/audit-log#get-guild-audit-log>
+    pub async fn audit_logs(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        query: GuildAuditLogsQuery,
+    ) -> ChorusResult<AuditLog> {
+        let mut request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_audit_logs(guild_id),
+            None,
+            None,
+            user,
+        );
+        request.request = request.request.query(&query);
+        request.deserialize_response::<AuditLog>(user).await
+    }
+}
+
+/// Watches the audit log of a guild, yielding every new entry once, in the order of their ids.
+///
+/// Entries are received through `GUILD_AUDIT_LOG_ENTRY_CREATE` dispatches on the user's gateway.
+/// Entries which were created while not watching, such as before the watcher was created or
+/// while the gateway was disconnected, are fetched through [`Guild::audit_logs`] by
+/// [`AuditLogWatcher::backfill`]. Entries which are both fetched and dispatched are only yielded
+/// once.
+///
+/// Requires the [`VIEW_AUDIT_LOG`](crate::types::PermissionFlags::VIEW_AUDIT_LOG) permission.
+///
+/// # Example
+/// ```no_run
+/// # async fn example(user: &mut chorus::instance::ChorusUser) -> chorus::errors::ChorusResult<()> {
+/// use chorus::api::AuditLogWatcher;
+/// use chorus::types::Snowflake;
+///
+/// // Continues after the last entry which was logged before a restart
+/// let mut watcher = AuditLogWatcher::new(user, Snowflake(1), Some(Snowflake(2))).await;
+/// while let Some(entry) = watcher.next(user).await? {
+///     println!("{:?} by {:?}", entry.action_type, entry.user_id);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AuditLogWatcher {
+    guild_id: Snowflake,
+    last_id: Option<Snowflake>,
+    needs_backfill: bool,
+    pending: VecDeque<AuditLogEntry>,
+    receiver: mpsc::UnboundedReceiver<AuditLogEntry>,
+    observer: Arc<AuditLogObserver>,
+    gateway: GatewayHandle,
+}
+
+impl AuditLogWatcher {
+    /// Starts watching the audit log of a guild on the user's gateway.
+    ///
+    /// If `after` is set, the entries after it are [backfilled](AuditLogWatcher::backfill) on the
+    /// first call to [`AuditLogWatcher::next`]. Otherwise, only entries dispatched from now on
+    /// are yielded.
+    pub async fn new(user: &ChorusUser, guild_id: Snowflake, after: Option<Snowflake>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let observer = Arc::new(AuditLogObserver {
+            id: Snowflake::generate(),
+            guild_id,
+            sender,
+        });
+        user.gateway
+            .events
+            .lock()
+            .await
+            .guild
+            .audit_log_entry_create
+            .subscribe(observer.clone());
+        Self {
+            guild_id,
+            last_id: after,
+            needs_backfill: after.is_some(),
+            pending: VecDeque::new(),
+            receiver,
+            observer,
+            gateway: user.gateway.clone(),
+        }
+    }
+
+    pub fn guild_id(&self) -> Snowflake {
+        self.guild_id
+    }
+
+    /// The id of the last yielded entry, which can be passed to [`AuditLogWatcher::new`] to
+    /// continue watching later on.
+    pub fn last_id(&self) -> Option<Snowflake> {
+        self.last_id
+    }
+
+    /// Returns the next entry, waiting for one to be dispatched if there are none yet.
+    ///
+    /// Returns `None` if entries can no longer be received, which happens once the gateway's
+    /// [events](GatewayHandle::events) are dropped.
+    pub async fn next(&mut self, user: &mut ChorusUser) -> ChorusResult<Option<AuditLogEntry>> {
+        if self.needs_backfill {
+            self.backfill(user).await?;
+        }
+        while let Some(entry) = self.pending.pop_front() {
+            if self.mark_yielded(&entry) {
+                return Ok(Some(entry));
+            }
+        }
+        while let Some(entry) = self.receiver.recv().await {
+            if self.mark_yielded(&entry) {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Fetches the entries after the last yielded one, which are then yielded by
+    /// [`AuditLogWatcher::next`] before any newly dispatched entries.
+    ///
+    /// Should be called after the gateway reconnects, as entries created while it was
+    /// disconnected are never dispatched. Does nothing if no entry has been yielded yet, and no
+    /// entry to continue after was given to [`AuditLogWatcher::new`].
+    pub async fn backfill(&mut self, user: &mut ChorusUser) -> ChorusResult<()> {
+        self.needs_backfill = false;
+        let mut after = match self.pending.back().map(|entry| entry.id).or(self.last_id) {
+            Some(after) => after,
+            None => return Ok(()),
+        };
+        loop {
+            let mut entries = Guild::audit_logs(
+                user,
+                self.guild_id,
+                GuildAuditLogsQuery {
+                    after: Some(after),
+                    limit: Some(AUDIT_LOG_PAGE_SIZE),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .audit_log_entries;
+            entries.retain(|entry| entry.id > after);
+            entries.sort_unstable_by_key(|entry| entry.id);
+            let complete = entries.len() < AUDIT_LOG_PAGE_SIZE as usize;
+            match entries.last() {
+                Some(last) => after = last.id,
+                None => return Ok(()),
+            }
+            self.pending.extend(entries);
+            if complete {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Stops watching, unsubscribing from the gateway.
+    pub async fn stop(self) {
+        self.gateway
+            .events
+            .lock()
+            .await
+            .guild
+            .audit_log_entry_create
+            .unsubscribe(self.observer.as_ref());
+    }
+
+    /// Returns whether the entry has not been yielded yet, remembering it as the last yielded
+    /// entry if so.
+    fn mark_yielded(&mut self, entry: &AuditLogEntry) -> bool {
+        if self.last_id.map_or(false, |last_id| entry.id <= last_id) {
+            return false;
+        }
+        self.last_id = Some(entry.id);
+        true
+    }
+}
+
+/// Forwards the dispatched entries of a guild to an [`AuditLogWatcher`].
+///
+/// The id is part of the [`Debug`] output, which tells watchers of the same guild apart when
+/// unsubscribing them.
+#[derive(Debug)]
+struct AuditLogObserver {
+    #[allow(dead_code)]
+    id: Snowflake,
+    guild_id: Snowflake,
+    sender: mpsc::UnboundedSender<AuditLogEntry>,
+}
+
+#[async_trait]
+impl Observer<GuildAuditLogEntryCreate> for AuditLogObserver {
+    async fn update(&self, data: &GuildAuditLogEntryCreate) {
+        if data.guild_id == Some(self.guild_id) {
+            let _ = self.sender.send(data.entry.clone());
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    AutoModerationRule, AutoModerationRuleCreateSchema, AutoModerationRuleModifySchema, Snowflake,
+};
+
+impl AutoModerationRule {
+    /// Retrieves the auto moderation rules of a guild.
+    ///
+    /// Requires the [`MANAGE_GUILD`](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/resources/auto-moderation#list-auto-moderation-rules-for-guild>
+    pub async fn get_all(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+    ) -> ChorusResult<Vec<AutoModerationRule>> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_auto_moderation_rules(guild_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Vec<AutoModerationRule>>(user)
+        .await
+    }
+
+    /// Retrieves a single auto moderation rule of a guild.
+    ///
+    /// Requires the [`MANAGE_GUILD`](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/resources/auto-moderation#get-auto-moderation-rule>
+    pub async fn get(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        rule_id: Snowflake,
+    ) -> ChorusResult<AutoModerationRule> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_auto_moderation_rule(guild_id, rule_id),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<AutoModerationRule>(user)
+        .await
+    }
+
+    /// Creates an auto moderation rule in a guild.
+    ///
+    /// Requires the [`MANAGE_GUILD`](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/resources/auto-moderation#create-auto-moderation-rule>
+    pub async fn create(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        schema: AutoModerationRuleCreateSchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<AutoModerationRule> {
+        let body = to_string(&schema).map_err(|e| ChorusError::FormCreation {
+            error: e.to_string(),
+        })?;
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::guild_auto_moderation_rules(guild_id),
+            Some(body),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<AutoModerationRule>(user)
+        .await
+    }
+
+    /// Modifies an auto moderation rule of a guild.
+    ///
+    /// Requires the [`MANAGE_GUILD`](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/resources/auto-moderation#modify-auto-moderation-rule>
+    pub async fn modify(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        rule_id: Snowflake,
+        schema: AutoModerationRuleModifySchema,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<AutoModerationRule> {
+        let body = to_string(&schema).map_err(|e| ChorusError::FormCreation {
+            error: e.to_string(),
+        })?;
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_auto_moderation_rule(guild_id, rule_id),
+            Some(body),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<AutoModerationRule>(user)
+        .await
+    }
+
+    /// Deletes an auto moderation rule of a guild.
+    ///
+    /// Requires the [`MANAGE_GUILD`](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/resources/auto-moderation#delete-auto-moderation-rule>
+    pub async fn delete(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        rule_id: Snowflake,
+        audit_log_reason: Option<String>,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::guild_auto_moderation_rule(guild_id, rule_id),
+            None,
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+}
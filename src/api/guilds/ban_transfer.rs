@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::routes;
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{Guild, GuildBan, GuildBanCreateSchema, GuildBansQuery, Snowflake};
+
+/// How many bans are requested per page when exporting; the most Discord allows.
+const BAN_PAGE_SIZE: u16 = 1000;
+
+/// How often a single ban is retried after being rate limited.
+const MAX_BAN_RETRIES: u32 = 5;
+
+/// A ban, as exported by [`Guild::export_bans`] and imported by [`Guild::import_bans`].
+///
+/// Can be serialized to move a ban list between guilds or keep it in a file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub user_id: Snowflake,
+    pub reason: Option<String>,
+}
+
+impl From<&GuildBan> for BanEntry {
+    fn from(ban: &GuildBan) -> Self {
+        Self {
+            user_id: ban.user.id,
+            reason: ban.reason.clone(),
+        }
+    }
+}
+
+/// The progress of [`Guild::import_bans`], reported after each ban.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BanImportProgress {
+    /// The amount of bans which have been applied or have failed so far.
+    pub completed: usize,
+    /// The amount of bans being imported.
+    pub total: usize,
+    /// The user the last ban was for.
+    pub user_id: Snowflake,
+    /// Whether the last ban succeeded.
+    pub succeeded: bool,
+}
+
+/// The outcome of [`Guild::import_bans`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BanImportReport {
+    /// The users which have been banned.
+    pub banned: Vec<Snowflake>,
+    /// The users which could not be banned, with the reason why.
+    pub failed: Vec<(Snowflake, ChorusError)>,
+}
+
+impl BanImportReport {
+    /// Returns true if every ban has been applied.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+impl Guild {
+    /// Returns all bans of a guild, requesting them page by page.
+    ///
+    /// Requires the [BAN_MEMBERS](crate::types::PermissionFlags::BAN_MEMBERS) permission.
+    pub async fn export_bans(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+    ) -> ChorusResult<Vec<BanEntry>> {
+        let mut bans: Vec<BanEntry> = Vec::new();
+        let mut after = None;
+        loop {
+            let query = GuildBansQuery {
+                before: None,
+                after,
+                limit: Some(BAN_PAGE_SIZE),
+            };
+            let page = Guild::get_bans(user, guild_id, Some(query)).await?;
+            let last = page.iter().map(|ban| ban.user.id).max();
+            let full_page = page.len() >= BAN_PAGE_SIZE as usize;
+            bans.extend(
+                page.iter()
+                    .filter(|ban| after.map_or(true, |after| ban.user.id > after))
+                    .map(BanEntry::from),
+            );
+            // Instances which ignore the pagination return the same page again; stop once a page
+            // brings no new bans
+            match last {
+                Some(last) if full_page && after.map_or(true, |after| last > after) => {
+                    after = Some(last);
+                }
+                _ => break,
+            }
+        }
+        Ok(bans)
+    }
+
+    /// Bans every user of the list from a guild, one request at a time, and calls `on_progress`
+    /// after each ban.
+    ///
+    /// Waits for `pacing` between bans, to stay clear of the rate limits when importing long
+    /// lists. Bans which are held back by a rate limit anyway are retried once the rate limit is
+    /// expected to be replenished. Other errors do not stop the import; they are collected in
+    /// the returned report.
+    ///
+    /// The reason of each ban is sent as its audit log reason.
+    ///
+    /// Requires the [BAN_MEMBERS](crate::types::PermissionFlags::BAN_MEMBERS) permission.
+    pub async fn import_bans(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        bans: Vec<BanEntry>,
+        pacing: Duration,
+        mut on_progress: impl FnMut(&BanImportProgress),
+    ) -> BanImportReport {
+        let total = bans.len();
+        let mut report = BanImportReport::default();
+        for (index, ban) in bans.into_iter().enumerate() {
+            if index > 0 && !pacing.is_zero() {
+                crate::sleep(pacing).await;
+            }
+            let result = Guild::import_ban(user, guild_id, &ban).await;
+            on_progress(&BanImportProgress {
+                completed: index + 1,
+                total,
+                user_id: ban.user_id,
+                succeeded: result.is_ok(),
+            });
+            match result {
+                Ok(()) => report.banned.push(ban.user_id),
+                Err(e) => {
+                    log::warn!("Banning user {} failed: {}", ban.user_id, e);
+                    report.failed.push((ban.user_id, e));
+                }
+            }
+        }
+        report
+    }
+
+    async fn import_ban(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        ban: &BanEntry,
+    ) -> ChorusResult<()> {
+        let route = routes::guild_ban(guild_id, ban.user_id);
+        ChorusRequest::retry_rate_limited(user, &route, MAX_BAN_RETRIES, |user| {
+            let ban = ban.clone();
+            Box::pin(async move {
+                Guild::create_ban(
+                    guild_id,
+                    ban.user_id,
+                    ban.reason,
+                    GuildBanCreateSchema::default(),
+                    user,
+                )
+                .await
+            })
+        })
+        .await
+    }
+}
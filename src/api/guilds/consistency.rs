@@ -0,0 +1,216 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{Channel, Guild, RoleObject, Snowflake};
+
+/// A difference between the cached state of a guild and its state on the instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GuildInconsistency {
+    /// The role exists on the instance, but not in the cache
+    MissingRole { role_id: Snowflake },
+    /// The role is cached, but no longer exists on the instance
+    StaleRole { role_id: Snowflake },
+    /// The cached role differs from the role on the instance in the given fields
+    RoleMismatch {
+        role_id: Snowflake,
+        fields: Vec<&'static str>,
+    },
+    /// The channel exists on the instance, but not in the cache
+    MissingChannel { channel_id: Snowflake },
+    /// The channel is cached, but no longer exists on the instance
+    StaleChannel { channel_id: Snowflake },
+    /// The cached channel differs from the channel on the instance in the given fields
+    ChannelMismatch {
+        channel_id: Snowflake,
+        fields: Vec<&'static str>,
+    },
+    /// The cached approximate member count differs from the instance's
+    MemberCountMismatch { cached: i32, actual: i32 },
+}
+
+/// The result of [`Guild::audit_consistency`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuildConsistencyReport {
+    pub guild_id: Snowflake,
+    pub inconsistencies: Vec<GuildInconsistency>,
+}
+
+impl GuildConsistencyReport {
+    /// Compares a cached guild with the roles, channels and approximate member count fetched
+    /// from the instance.
+    ///
+    /// Roles and channels are only compared if the cached guild has them. Channels are compared
+    /// by name, type, position, parent and topic, roles by name, color, position, permissions,
+    /// hoist and mentionable.
+    pub fn new(
+        cached: &Guild,
+        roles: &[RoleObject],
+        channels: &[Channel],
+        approximate_member_count: Option<i32>,
+    ) -> Self {
+        let mut inconsistencies = Vec::new();
+        if let Some(cached_roles) = &cached.roles {
+            let cached_roles: Vec<RoleObject> = cached_roles
+                .iter()
+                .map(|role| role.read().unwrap().clone())
+                .collect();
+            compare(
+                &cached_roles,
+                roles,
+                |role| role.id,
+                role_differences,
+                &mut inconsistencies,
+                |role_id| GuildInconsistency::MissingRole { role_id },
+                |role_id| GuildInconsistency::StaleRole { role_id },
+                |role_id, fields| GuildInconsistency::RoleMismatch { role_id, fields },
+            );
+        }
+        if let Some(cached_channels) = &cached.channels {
+            let cached_channels: Vec<Channel> = cached_channels
+                .iter()
+                .map(|channel| channel.read().unwrap().clone())
+                .collect();
+            compare(
+                &cached_channels,
+                channels,
+                |channel| channel.id,
+                channel_differences,
+                &mut inconsistencies,
+                |channel_id| GuildInconsistency::MissingChannel { channel_id },
+                |channel_id| GuildInconsistency::StaleChannel { channel_id },
+                |channel_id, fields| GuildInconsistency::ChannelMismatch { channel_id, fields },
+            );
+        }
+        if let (Some(cached), Some(actual)) =
+            (cached.approximate_member_count, approximate_member_count)
+        {
+            if cached != actual {
+                inconsistencies.push(GuildInconsistency::MemberCountMismatch { cached, actual });
+            }
+        }
+        Self {
+            guild_id: cached.id,
+            inconsistencies,
+        }
+    }
+
+    /// Whether no inconsistencies were found.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+}
+
+/// Compares cached entities with the entities on the instance by their ids.
+#[allow(clippy::too_many_arguments)]
+fn compare<T>(
+    cached: &[T],
+    actual: &[T],
+    id: impl Fn(&T) -> Snowflake,
+    differences: impl Fn(&T, &T) -> Vec<&'static str>,
+    inconsistencies: &mut Vec<GuildInconsistency>,
+    missing: impl Fn(Snowflake) -> GuildInconsistency,
+    stale: impl Fn(Snowflake) -> GuildInconsistency,
+    mismatch: impl Fn(Snowflake, Vec<&'static str>) -> GuildInconsistency,
+) {
+    let cached: HashMap<Snowflake, &T> = cached.iter().map(|entity| (id(entity), entity)).collect();
+    let mut actual_ids: Vec<Snowflake> = Vec::new();
+    for entity in actual {
+        let entity_id = id(entity);
+        actual_ids.push(entity_id);
+        match cached.get(&entity_id) {
+            None => inconsistencies.push(missing(entity_id)),
+            Some(cached) => {
+                let fields = differences(cached, entity);
+                if !fields.is_empty() {
+                    inconsistencies.push(mismatch(entity_id, fields));
+                }
+            }
+        }
+    }
+    let mut stale_ids: Vec<Snowflake> = cached
+        .keys()
+        .filter(|id| !actual_ids.contains(id))
+        .copied()
+        .collect();
+    stale_ids.sort_unstable();
+    inconsistencies.extend(stale_ids.into_iter().map(stale));
+}
+
+fn role_differences(cached: &RoleObject, actual: &RoleObject) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if cached.name != actual.name {
+        fields.push("name");
+    }
+    if cached.color != actual.color {
+        fields.push("color");
+    }
+    if cached.position != actual.position {
+        fields.push("position");
+    }
+    if cached.permissions != actual.permissions {
+        fields.push("permissions");
+    }
+    if cached.hoist != actual.hoist {
+        fields.push("hoist");
+    }
+    if cached.mentionable != actual.mentionable {
+        fields.push("mentionable");
+    }
+    fields
+}
+
+fn channel_differences(cached: &Channel, actual: &Channel) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if cached.name != actual.name {
+        fields.push("name");
+    }
+    if cached.channel_type != actual.channel_type {
+        fields.push("type");
+    }
+    if cached.position != actual.position {
+        fields.push("position");
+    }
+    if cached.parent_id != actual.parent_id {
+        fields.push("parent_id");
+    }
+    if cached.topic != actual.topic {
+        fields.push("topic");
+    }
+    fields
+}
+
+impl Guild {
+    /// Cross-checks this cached guild against the instance, fetching its roles, channels and
+    /// approximate member count, and reports where they differ.
+    ///
+    /// Meant for debugging the cache, and inconsistencies of self-hosted instances.
+    pub async fn audit_consistency(
+        &self,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildConsistencyReport> {
+        let roles = RoleObject::get_all(user, self.id).await?;
+        let channels = self.channels(user).await?;
+        let with_counts = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild(self.id).query("with_counts", true),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Guild>(user)
+        .await?;
+        Ok(GuildConsistencyReport::new(
+            self,
+            &roles,
+            &channels,
+            with_counts.approximate_member_count,
+        ))
+    }
+}
@@ -3,16 +3,17 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use reqwest::Client;
-use serde_json::from_str;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::errors::ChorusError;
 use crate::errors::ChorusResult;
 use crate::instance::ChorusUser;
 use crate::ratelimiter::ChorusRequest;
 use crate::types::{
-    Channel, ChannelCreateSchema, Guild, GuildBanCreateSchema, GuildBansQuery, GuildCreateSchema,
-    GuildMember, GuildMemberSearchSchema, GuildModifySchema, GuildPreview, LimitType,
+    Channel, ChannelCreateSchema, ChannelType, Guild, GuildBanCreateSchema, GuildBansQuery,
+    GuildCreateSchema, GuildIncidentActionsSchema, GuildIncidentsData, GuildMember,
+    GuildMemberSearchSchema, GuildModifySchema, GuildPreview, LimitType,
     ModifyGuildMemberProfileSchema, ModifyGuildMemberSchema, UserProfileMetadata,
 };
 use crate::types::{GuildBan, Snowflake};
@@ -23,16 +24,8 @@ impl Guild {
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild>
     pub async fn get(guild_id: Snowflake, user: &mut ChorusUser) -> ChorusResult<Guild> {
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .get(format!(
-                    "{}/guilds/{}",
-                    user.belongs_to.read().unwrap().urls.api,
-                    guild_id
-                ))
-                .header("Authorization", user.token()),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request =
+            ChorusRequest::from_route(http::Method::GET, routes::guild(guild_id), None, None, user);
         let response = chorus_request.deserialize_response::<Guild>(user).await?;
         Ok(response)
     }
@@ -45,15 +38,13 @@ impl Guild {
         user: &mut ChorusUser,
         guild_create_schema: GuildCreateSchema,
     ) -> ChorusResult<Guild> {
-        let url = format!("{}/guilds", user.belongs_to.read().unwrap().urls.api);
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .post(url.clone())
-                .header("Authorization", user.token.clone())
-                .header("Content-Type", "application/json")
-                .body(to_string(&guild_create_schema).unwrap()),
-            limit_type: LimitType::Global,
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::guilds(),
+            Some(to_string(&guild_create_schema).unwrap()),
+            None,
+            user,
+        );
         chorus_request.deserialize_response::<Guild>(user).await
     }
 
@@ -70,22 +61,43 @@ impl Guild {
         schema: GuildModifySchema,
         user: &mut ChorusUser,
     ) -> ChorusResult<Guild> {
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .patch(format!(
-                    "{}/guilds/{}",
-                    user.belongs_to.read().unwrap().urls.api,
-                    guild_id,
-                ))
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(to_string(&schema).unwrap()),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild(guild_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        );
         let response = chorus_request.deserialize_response::<Guild>(user).await?;
         Ok(response)
     }
 
+    /// Toggles the security actions of a guild, such as pausing invites or direct messages
+    /// during a raid.
+    ///
+    /// Requires the [MANAGE_GUILD](crate::types::PermissionFlags::MANAGE_GUILD) permission.
+    ///
+    /// Returns the guild's updated incidents data.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#modify-guild-incident-actions>
+    pub async fn modify_incident_actions(
+        guild_id: Snowflake,
+        schema: GuildIncidentActionsSchema,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<GuildIncidentsData> {
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::guild_incident_actions(guild_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        );
+        chorus_request
+            .deserialize_response::<GuildIncidentsData>(user)
+            .await
+    }
+
     /// Deletes a guild by its id.
     ///
     /// User must be the owner.
@@ -106,18 +118,13 @@ impl Guild {
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/guild#delete-guild>
     pub async fn delete(user: &mut ChorusUser, guild_id: Snowflake) -> ChorusResult<()> {
-        let url = format!(
-            "{}/guilds/{}/delete",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::guild_delete(guild_id),
+            None,
+            None,
+            user,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .post(url.clone())
-                .header("Authorization", user.token.clone())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Global,
-        };
         chorus_request.handle_request_as_result(user).await
     }
 
@@ -125,6 +132,10 @@ impl Guild {
     ///
     /// Requires the [MANAGE_CHANNELS](crate::types::PermissionFlags::MANAGE_CHANNELS) permission.
     ///
+    /// If the schema specifies a `parent_id`, the guild's channels are fetched first to make sure
+    /// that the parent is a [category](crate::types::ChannelType::GuildCategory) of this guild.
+    /// Categories themselves cannot have a parent.
+    ///
     /// # Notes
     /// This method is a wrapper for [Channel::create].
     ///
@@ -136,6 +147,30 @@ impl Guild {
         audit_log_reason: Option<String>,
         schema: ChannelCreateSchema,
     ) -> ChorusResult<Channel> {
+        if let Some(parent_id) = schema.parent_id {
+            if schema.channel_type == Some(ChannelType::GuildCategory) {
+                return Err(ChorusError::InvalidArguments {
+                    error: "Categories cannot have a parent channel.".to_string(),
+                });
+            }
+            let channels = self.channels(user).await?;
+            match channels.iter().find(|channel| channel.id == parent_id) {
+                None => {
+                    return Err(ChorusError::InvalidArguments {
+                        error: format!(
+                            "Parent channel {} is not a channel of guild {}.",
+                            parent_id, self.id
+                        ),
+                    })
+                }
+                Some(parent) if parent.channel_type != ChannelType::GuildCategory => {
+                    return Err(ChorusError::InvalidArguments {
+                        error: format!("Parent channel {} is not a category.", parent_id),
+                    })
+                }
+                Some(_) => (),
+            }
+        }
         Channel::create(user, self.id, audit_log_reason, schema).await
     }
 
@@ -146,33 +181,16 @@ impl Guild {
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/channel#get-guild-channels>
     pub async fn channels(&self, user: &mut ChorusUser) -> ChorusResult<Vec<Channel>> {
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .get(format!(
-                    "{}/guilds/{}/channels",
-                    user.belongs_to.read().unwrap().urls.api,
-                    self.id
-                ))
-                .header("Authorization", user.token()),
-            limit_type: LimitType::Channel(self.id),
-        };
-        let result = chorus_request.send_request(user).await?;
-        let stringed_response = match result.text().await {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(ChorusError::InvalidResponse {
-                    error: e.to_string(),
-                });
-            }
-        };
-        let _: Vec<Channel> = match from_str(&stringed_response) {
-            Ok(result) => return Ok(result),
-            Err(e) => {
-                return Err(ChorusError::InvalidResponse {
-                    error: e.to_string(),
-                });
-            }
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_channels(self.id),
+            None,
+            None,
+            user,
+        );
+        chorus_request
+            .deserialize_response::<Vec<Channel>>(user)
+            .await
     }
 
     /// Returns a guild preview object for the given guild ID.
@@ -185,17 +203,13 @@ impl Guild {
         guild_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<GuildPreview> {
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .patch(format!(
-                    "{}/guilds/{}/preview",
-                    user.belongs_to.read().unwrap().urls.api,
-                    guild_id,
-                ))
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_preview(guild_id),
+            None,
+            None,
+            user,
+        );
         let response = chorus_request
             .deserialize_response::<GuildPreview>(user)
             .await?;
@@ -210,19 +224,12 @@ impl Guild {
         guild_id: Snowflake,
         user: &mut ChorusUser,
     ) -> ChorusResult<Vec<GuildMember>> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::GET,
-            format!(
-                "{}/guilds/{}/members",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-            )
-            .as_str(),
+            routes::guild_members(guild_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.deserialize_response::<Vec<GuildMember>>(user).await
     }
@@ -236,19 +243,12 @@ impl Guild {
         query: GuildMemberSearchSchema,
         user: &mut ChorusUser,
     ) -> ChorusResult<Vec<GuildMember>> {
-        let mut request = ChorusRequest::new(
+        let mut request = ChorusRequest::from_route(
             http::Method::GET,
-            format!(
-                "{}/guilds/{}/members/search",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-            )
-            .as_str(),
-            None,
+            routes::guild_members_search(guild_id),
             None,
             None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.request = request
             .request
@@ -268,20 +268,12 @@ impl Guild {
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            format!(
-                "{}/guilds/{}/members/{}",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-                member_id,
-            )
-            .as_str(),
+            routes::guild_member(guild_id, member_id),
             None,
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
@@ -298,20 +290,12 @@ impl Guild {
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<GuildMember> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::PATCH,
-            format!(
-                "{}/guilds/{}/members/{}",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-                member_id,
-            )
-            .as_str(),
+            routes::guild_member(guild_id, member_id),
             Some(to_string(&schema).unwrap()),
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.deserialize_response::<GuildMember>(user).await
     }
@@ -326,19 +310,12 @@ impl Guild {
         audit_log_reason: Option<String>,
         user: &mut ChorusUser,
     ) -> ChorusResult<GuildMember> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::PATCH,
-            format!(
-                "{}/guilds/{}/members/@me",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-            )
-            .as_str(),
+            routes::guild_own_member(guild_id),
             Some(to_string(&schema).unwrap()),
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.deserialize_response::<GuildMember>(user).await
     }
@@ -352,19 +329,12 @@ impl Guild {
         schema: ModifyGuildMemberProfileSchema,
         user: &mut ChorusUser,
     ) -> ChorusResult<UserProfileMetadata> {
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::PATCH,
-            format!(
-                "{}/guilds/{}/profile/@me",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-            )
-            .as_str(),
+            routes::guild_own_profile(guild_id),
             Some(to_string(&schema).unwrap()),
             None,
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request
             .deserialize_response::<UserProfileMetadata>(user)
@@ -382,23 +352,15 @@ impl Guild {
         guild_id: Snowflake,
         query: Option<GuildBansQuery>,
     ) -> ChorusResult<Vec<GuildBan>> {
-        let url = format!(
-            "{}/guilds/{}/bans",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-        );
-
-        let mut request = ChorusRequest::new(
+        let mut request = ChorusRequest::from_route(
             http::Method::GET,
-            &url,
-            None,
+            routes::guild_bans(guild_id),
             None,
             None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         if let Some(query) = query {
-            request.request = request.request.query(&to_string(&query).unwrap());
+            request.request = request.request.query(&query);
         }
         request.deserialize_response::<Vec<GuildBan>>(user).await
     }
@@ -414,21 +376,12 @@ impl Guild {
         guild_id: Snowflake,
         user_id: Snowflake,
     ) -> ChorusResult<GuildBan> {
-        let url = format!(
-            "{}/guilds/{}/bans/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            user_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::GET,
-            &url,
+            routes::guild_ban(guild_id, user_id),
             None,
             None,
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.deserialize_response::<GuildBan>(user).await
     }
@@ -445,20 +398,12 @@ impl Guild {
         user: &mut ChorusUser,
     ) -> ChorusResult<()> {
         // FIXME: Return GuildBan instead of (). Requires <https://github.com/spacebarchat/server/issues/1096> to be resolved.
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::PUT,
-            format!(
-                "{}/guilds/{}/bans/{}",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id,
-                user_id
-            )
-            .as_str(),
+            routes::guild_ban(guild_id, user_id),
             Some(to_string(&schema).unwrap()),
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
@@ -475,21 +420,12 @@ impl Guild {
         user_id: Snowflake,
         audit_log_reason: Option<String>,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/guilds/{}/bans/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            user_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::guild_ban(guild_id, user_id),
             None,
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
@@ -500,6 +436,9 @@ impl Channel {
     ///
     /// Requires the [MANAGE_CHANNELS](crate::types::PermissionFlags::MANAGE_CHANNELS) permission.
     ///
+    /// The created channel is observed on the user's gateway and added to its cache, if it is
+    /// enabled, so it is known before the gateway receives the channel create event.
+    ///
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/channel#create-guild-channel>
     pub async fn create(
@@ -508,22 +447,16 @@ impl Channel {
         audit_log_reason: Option<String>,
         schema: ChannelCreateSchema,
     ) -> ChorusResult<Channel> {
-        let mut request = Client::new()
-            .post(format!(
-                "{}/guilds/{}/channels",
-                user.belongs_to.read().unwrap().urls.api,
-                guild_id
-            ))
-            .header("Authorization", user.token())
-            .header("Content-Type", "application/json")
-            .body(to_string(&schema).unwrap());
-        if let Some(reason) = audit_log_reason {
-            request = request.header("X-Audit-Log-Reason", reason);
-        }
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::Guild(guild_id),
-        };
-        chorus_request.deserialize_response::<Channel>(user).await
+        let channel = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::guild_channels(guild_id),
+            Some(to_string(&schema).unwrap()),
+            audit_log_reason.as_deref(),
+            user,
+        )
+        .deserialize_response::<Channel>(user)
+        .await?;
+        user.remember_channel(&channel).await;
+        Ok(channel)
     }
 }
@@ -0,0 +1,95 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    GuildJoinRequest, GuildJoinRequestActionSchema, GuildJoinRequestStatus, GuildJoinRequests,
+    GuildJoinRequestsQuery, Snowflake,
+};
+
+impl GuildJoinRequest {
+    /// Returns a page of the guild's join requests, filtered by the given query.
+    ///
+    /// Requires the [KICK_MEMBERS](crate::types::PermissionFlags::KICK_MEMBERS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-join-requests>
+    pub async fn list(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        query: GuildJoinRequestsQuery,
+    ) -> ChorusResult<GuildJoinRequests> {
+        let mut chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_join_requests(guild_id),
+            None,
+            None,
+            user,
+        );
+        chorus_request.request = chorus_request.request.query(&query);
+        chorus_request
+            .deserialize_response::<GuildJoinRequests>(user)
+            .await
+    }
+
+    /// Approves or rejects a join request, and returns the updated request.
+    ///
+    /// Requires the [KICK_MEMBERS](crate::types::PermissionFlags::KICK_MEMBERS) permission.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#action-guild-join-request>
+    pub async fn action(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        request_id: Snowflake,
+        schema: GuildJoinRequestActionSchema,
+    ) -> ChorusResult<GuildJoinRequest> {
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_join_request(guild_id, request_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        );
+        chorus_request
+            .deserialize_response::<GuildJoinRequest>(user)
+            .await
+    }
+
+    /// Approves a join request, admitting the user into the guild.
+    ///
+    /// See [`GuildJoinRequest::action`].
+    pub async fn approve(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        request_id: Snowflake,
+    ) -> ChorusResult<GuildJoinRequest> {
+        let schema = GuildJoinRequestActionSchema {
+            action: GuildJoinRequestStatus::Approved,
+            rejection_reason: None,
+        };
+        GuildJoinRequest::action(user, guild_id, request_id, schema).await
+    }
+
+    /// Rejects a join request, optionally telling the user why.
+    ///
+    /// See [`GuildJoinRequest::action`].
+    pub async fn reject(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        request_id: Snowflake,
+        reason: Option<String>,
+    ) -> ChorusResult<GuildJoinRequest> {
+        let schema = GuildJoinRequestActionSchema {
+            action: GuildJoinRequestStatus::Rejected,
+            rejection_reason: reason,
+        };
+        GuildJoinRequest::action(user, guild_id, request_id, schema).await
+    }
+}
@@ -3,7 +3,9 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use reqwest::Client;
+use serde_json::json;
 
+use crate::api::routes;
 use crate::{
     errors::ChorusResult,
     instance::ChorusUser,
@@ -14,6 +16,9 @@ use crate::{
 impl types::GuildMember {
     /// Retrieves a guild member.
     ///
+    /// If the member is the user themselves, it is stored in [`ChorusUser::guild_members`] for
+    /// checking permissions locally.
+    ///
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-member>
     pub async fn get(
@@ -21,16 +26,46 @@ impl types::GuildMember {
         guild_id: Snowflake,
         member_id: Snowflake,
     ) -> ChorusResult<GuildMember> {
-        let url = format!(
-            "{}/guilds/{}/members/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            member_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_member(guild_id, member_id),
+            None,
+            None,
+            user,
+        );
+        let member = chorus_request
+            .deserialize_response::<GuildMember>(user)
+            .await?;
+        if member_id == user.object.read().unwrap().id {
+            user.guild_members
+                .write()
+                .unwrap()
+                .insert(guild_id, member.clone());
+        }
+        Ok(member)
+    }
+
+    /// Replaces all roles of a guild member with the given roles.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
+    /// To change the roles of many members, see [`RoleBatch`](crate::api::RoleBatch).
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#modify-guild-member>
+    pub async fn set_roles(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        member_id: Snowflake,
+        roles: Vec<Snowflake>,
+    ) -> ChorusResult<GuildMember> {
+        // Only the roles are sent, as the other fields of the member are reset when sent as null
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_member(guild_id, member_id),
+            Some(json!({ "roles": roles }).to_string()),
+            None,
+            user,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new().get(url).header("Authorization", user.token()),
-            limit_type: LimitType::Guild(guild_id),
-        };
         chorus_request
             .deserialize_response::<GuildMember>(user)
             .await
@@ -48,20 +83,13 @@ impl types::GuildMember {
         member_id: Snowflake,
         role_id: Snowflake,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/guilds/{}/members/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            member_id,
-            role_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::guild_member_role(guild_id, member_id, role_id),
+            None,
+            None,
+            user,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .put(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Guild(guild_id),
-        };
         chorus_request.handle_request_as_result(user).await
     }
 
@@ -77,19 +105,13 @@ impl types::GuildMember {
         member_id: Snowflake,
         role_id: Snowflake,
     ) -> Result<(), crate::errors::ChorusError> {
-        let url = format!(
-            "{}/guilds/{}/members/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            member_id,
-            role_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::guild_member_role(guild_id, member_id, role_id),
+            None,
+            None,
+            user,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .delete(url)
-                .header("Authorization", user.token()),
-            limit_type: LimitType::Guild(guild_id),
-        };
         chorus_request.handle_request_as_result(user).await
     }
 }
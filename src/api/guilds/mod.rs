@@ -3,12 +3,26 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![allow(unused_imports)]
+pub use audit_log::*;
+pub use auto_moderation::*;
+pub use ban_transfer::*;
+pub use consistency::*;
 pub use guilds::*;
 pub use messages::*;
+pub use role_batch::*;
 pub use roles::*;
 pub use roles::*;
+pub use structure::*;
 
+pub mod audit_log;
+pub mod auto_moderation;
+pub mod ban_transfer;
+pub mod consistency;
 pub mod guilds;
+pub mod join_requests;
 pub mod member;
 pub mod messages;
+pub mod role_batch;
 pub mod roles;
+pub mod scheduled_events;
+pub mod structure;
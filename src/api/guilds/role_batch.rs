@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{GuildMember, Snowflake};
+
+/// A change to the roles of a single guild member. See [`RoleBatch`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RoleChange {
+    /// Replaces all roles of the member, see [`GuildMember::set_roles`].
+    Set(Vec<Snowflake>),
+    /// Adds a role to the member, see [`GuildMember::add_role`].
+    Add(Snowflake),
+    /// Removes a role from the member, see [`GuildMember::remove_role`].
+    Remove(Snowflake),
+}
+
+/// The progress of [`RoleBatch::apply`], reported after each change.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RoleBatchProgress {
+    /// The amount of changes which have been applied or have failed so far.
+    pub completed: usize,
+    /// The amount of changes in the batch.
+    pub total: usize,
+    /// The member the last change was made to.
+    pub member_id: Snowflake,
+    /// Whether the last change succeeded.
+    pub succeeded: bool,
+}
+
+/// Applies role changes to many members of a guild, one request at a time.
+///
+/// Changes which are held back by a rate limit are retried once the rate limit is expected to
+/// be replenished, up to [`max_retries`](RoleBatch::max_retries) times. Other errors do not stop
+/// the batch; they are returned along with the member they occurred for.
+///
+/// # Example
+/// ```no_run
+/// # async fn example(user: &mut chorus::instance::ChorusUser) {
+/// use chorus::api::RoleBatch;
+/// use chorus::types::Snowflake;
+///
+/// let verified_role = Snowflake(2);
+/// let results = RoleBatch::new(Snowflake(1))
+///     .add_role(Snowflake(10), verified_role)
+///     .add_role(Snowflake(11), verified_role)
+///     .apply(user, |progress| {
+///         println!("{}/{}", progress.completed, progress.total)
+///     })
+///     .await;
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleBatch {
+    guild_id: Snowflake,
+    changes: Vec<(Snowflake, RoleChange)>,
+    max_retries: u32,
+}
+
+impl RoleBatch {
+    /// Creates an empty batch of role changes in the given guild.
+    pub fn new(guild_id: Snowflake) -> Self {
+        Self {
+            guild_id,
+            changes: Vec::new(),
+            max_retries: 5,
+        }
+    }
+
+    /// Adds a change to a member's roles to the batch.
+    pub fn change(mut self, member_id: Snowflake, change: RoleChange) -> Self {
+        self.changes.push((member_id, change));
+        self
+    }
+
+    /// Replaces all roles of a member.
+    pub fn set_roles(self, member_id: Snowflake, roles: Vec<Snowflake>) -> Self {
+        self.change(member_id, RoleChange::Set(roles))
+    }
+
+    /// Adds a role to a member.
+    pub fn add_role(self, member_id: Snowflake, role_id: Snowflake) -> Self {
+        self.change(member_id, RoleChange::Add(role_id))
+    }
+
+    /// Removes a role from a member.
+    pub fn remove_role(self, member_id: Snowflake, role_id: Snowflake) -> Self {
+        self.change(member_id, RoleChange::Remove(role_id))
+    }
+
+    /// Sets how often a single change is retried after being rate limited. Defaults to 5.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The changes of this batch, in the order they are applied.
+    pub fn changes(&self) -> &[(Snowflake, RoleChange)] {
+        &self.changes
+    }
+
+    /// The amount of changes in this batch.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns true if this batch has no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies all changes in order, and calls `on_progress` after each one.
+    ///
+    /// Returns the result of every change, along with the member it was made to.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission.
+    pub async fn apply(
+        self,
+        user: &mut ChorusUser,
+        mut on_progress: impl FnMut(&RoleBatchProgress),
+    ) -> Vec<(Snowflake, ChorusResult<()>)> {
+        let total = self.changes.len();
+        let mut results = Vec::with_capacity(total);
+        for (member_id, change) in &self.changes {
+            let member_id = *member_id;
+            let result = self.apply_change(user, member_id, change).await;
+            if let Err(e) = &result {
+                log::warn!("Changing the roles of member {} failed: {}", member_id, e);
+            }
+            on_progress(&RoleBatchProgress {
+                completed: results.len() + 1,
+                total,
+                member_id,
+                succeeded: result.is_ok(),
+            });
+            results.push((member_id, result));
+        }
+        results
+    }
+
+    async fn apply_change(
+        &self,
+        user: &mut ChorusUser,
+        member_id: Snowflake,
+        change: &RoleChange,
+    ) -> ChorusResult<()> {
+        let guild_id = self.guild_id;
+        let route = match change {
+            RoleChange::Set(_) => routes::guild_member(guild_id, member_id),
+            RoleChange::Add(role_id) | RoleChange::Remove(role_id) => {
+                routes::guild_member_role(guild_id, member_id, *role_id)
+            }
+        };
+        ChorusRequest::retry_rate_limited(user, &route, self.max_retries, |user| {
+            let change = change.clone();
+            Box::pin(async move {
+                match change {
+                    RoleChange::Set(roles) => {
+                        GuildMember::set_roles(user, guild_id, member_id, roles)
+                            .await
+                            .map(|_| ())
+                    }
+                    RoleChange::Add(role_id) => {
+                        GuildMember::add_role(user, guild_id, member_id, role_id).await
+                    }
+                    RoleChange::Remove(role_id) => {
+                        GuildMember::remove_role(user, guild_id, member_id, role_id).await
+                    }
+                }
+            })
+        })
+        .await
+    }
+}
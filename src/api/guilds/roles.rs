@@ -2,9 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::{
     errors::{ChorusError, ChorusResult},
     instance::ChorusUser,
@@ -23,20 +26,16 @@ impl types::RoleObject {
         user: &mut ChorusUser,
         guild_id: Snowflake,
     ) -> ChorusResult<Vec<RoleObject>> {
-        let url = format!(
-            "{}/guilds/{}/roles",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_roles(guild_id),
+            None,
+            None,
+            user,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new().get(url).header("Authorization", user.token()),
-            limit_type: LimitType::Guild(guild_id),
-        };
-        let roles = chorus_request
+        chorus_request
             .deserialize_response::<Vec<RoleObject>>(user)
             .await
-            .unwrap();
-        Ok(roles)
     }
 
     /// Retrieves a single role for a given guild.
@@ -48,16 +47,13 @@ impl types::RoleObject {
         guild_id: Snowflake,
         role_id: Snowflake,
     ) -> ChorusResult<RoleObject> {
-        let url = format!(
-            "{}/guilds/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            role_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_role(guild_id, role_id),
+            None,
+            None,
+            user,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new().get(url).header("Authorization", user.token()),
-            limit_type: LimitType::Guild(guild_id),
-        };
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
@@ -74,24 +70,18 @@ impl types::RoleObject {
         guild_id: Snowflake,
         role_create_schema: RoleCreateModifySchema,
     ) -> ChorusResult<RoleObject> {
-        let url = format!(
-            "{}/guilds/{}/roles",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id
-        );
         let body = to_string::<RoleCreateModifySchema>(&role_create_schema).map_err(|e| {
             ChorusError::FormCreation {
                 error: e.to_string(),
             }
         })?;
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .post(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(body),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::guild_roles(guild_id),
+            Some(body),
+            None,
+            user,
+        );
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
@@ -108,23 +98,17 @@ impl types::RoleObject {
         guild_id: Snowflake,
         role_position_update_schema: RolePositionUpdateSchema,
     ) -> ChorusResult<RoleObject> {
-        let url = format!(
-            "{}/guilds/{}/roles",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id
-        );
         let body =
             to_string(&role_position_update_schema).map_err(|e| ChorusError::FormCreation {
                 error: e.to_string(),
             })?;
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .patch(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(body),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_roles(guild_id),
+            Some(body),
+            None,
+            user,
+        );
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
@@ -142,25 +126,18 @@ impl types::RoleObject {
         role_id: Snowflake,
         role_create_schema: RoleCreateModifySchema,
     ) -> ChorusResult<RoleObject> {
-        let url = format!(
-            "{}/guilds/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            role_id
-        );
         let body = to_string::<RoleCreateModifySchema>(&role_create_schema).map_err(|e| {
             ChorusError::FormCreation {
                 error: e.to_string(),
             }
         })?;
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .patch(url)
-                .header("Authorization", user.token())
-                .header("Content-Type", "application/json")
-                .body(body),
-            limit_type: LimitType::Guild(guild_id),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::guild_role(guild_id, role_id),
+            Some(body),
+            None,
+            user,
+        );
         chorus_request
             .deserialize_response::<RoleObject>(user)
             .await
@@ -176,22 +153,54 @@ impl types::RoleObject {
         role_id: Snowflake,
         audit_log_reason: Option<String>,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/guilds/{}/roles/{}",
-            user.belongs_to.read().unwrap().urls.api,
-            guild_id,
-            role_id
-        );
-
-        let request = ChorusRequest::new(
+        let request = ChorusRequest::from_route(
             http::Method::DELETE,
-            &url,
+            routes::guild_role(guild_id, role_id),
             None,
             audit_log_reason.as_deref(),
-            None,
-            Some(user),
-            LimitType::Guild(guild_id),
+            user,
         );
         request.handle_request_as_result(user).await
     }
+
+    /// Returns the amount of members each role of a guild is assigned to, keyed by role id.
+    ///
+    /// The `@everyone` role is not included.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-role-member-counts>
+    pub async fn get_member_counts(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+    ) -> ChorusResult<HashMap<Snowflake, u64>> {
+        let request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_role_member_counts(guild_id),
+            None,
+            None,
+            user,
+        );
+        request
+            .deserialize_response::<HashMap<Snowflake, u64>>(user)
+            .await
+    }
+
+    /// Returns the ids of (up to 100) members the given role is assigned to.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-role-members>
+    pub async fn get_member_ids(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        role_id: Snowflake,
+    ) -> ChorusResult<Vec<Snowflake>> {
+        let request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_role_member_ids(guild_id, role_id),
+            None,
+            None,
+            user,
+        );
+        request.deserialize_response::<Vec<Snowflake>>(user).await
+    }
 }
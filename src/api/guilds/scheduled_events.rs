@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{GuildScheduledEvent, Snowflake};
+
+impl GuildScheduledEvent {
+    /// Marks the current user as interested in a scheduled event.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild-scheduled-event#create-guild-scheduled-event-user>
+    pub async fn add_interested_user(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        event_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::guild_scheduled_event_own_user(guild_id, event_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Removes the current user from the users interested in a scheduled event.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild-scheduled-event#delete-guild-scheduled-event-user>
+    pub async fn remove_interested_user(
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        event_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::guild_scheduled_event_own_user(guild_id, event_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+}
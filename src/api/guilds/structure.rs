@@ -0,0 +1,144 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::types::{
+    Channel, ChannelCreateSchema, ChannelFlags, ChannelType, Guild, GuildCreateSchema,
+    PermissionOverwrite, RoleCreateModifySchema, RoleObject, Snowflake,
+};
+
+/// A guild created by [`Guild::clone_structure`], along with the ids its roles and channels were
+/// given.
+#[derive(Debug, Clone)]
+pub struct GuildStructureClone {
+    /// The newly created guild.
+    pub guild: Guild,
+    /// The ids of the new roles, by the ids of the roles they were copied from. The @everyone
+    /// role is mapped onto the new guild's @everyone role.
+    pub roles: HashMap<Snowflake, Snowflake>,
+    /// The ids of the new channels, by the ids of the channels they were copied from.
+    pub channels: HashMap<Snowflake, Snowflake>,
+}
+
+impl Guild {
+    /// Recreates the structure of this guild in a new guild, owned by `target_user`. The target
+    /// user may belong to a different instance than `user`, which is used to read this guild.
+    ///
+    /// The roles, the categories and channels, and their permission overwrites are copied, with
+    /// the ids of roles and parent categories referring to the new guild. Managed roles, such as
+    /// the roles of bots, and overwrites for single members are left out, as are threads.
+    /// Channels the instance creates for new guilds by default are kept.
+    ///
+    /// Requires the [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) permission in
+    /// this guild, to see all of its roles and overwrites.
+    ///
+    /// If a request fails, the error is returned and the partially created guild is left in
+    /// place.
+    pub async fn clone_structure(
+        &self,
+        user: &mut ChorusUser,
+        target_user: &mut ChorusUser,
+    ) -> ChorusResult<GuildStructureClone> {
+        let mut roles = RoleObject::get_all(user, self.id).await?;
+        let mut channels = self.channels(user).await?;
+
+        let guild = Guild::create(
+            target_user,
+            GuildCreateSchema {
+                name: self.name.clone(),
+                region: None,
+                icon: None,
+                channels: None,
+                guild_template_code: None,
+                system_channel_id: None,
+                rules_channel_id: None,
+            },
+        )
+        .await?;
+
+        let mut role_ids = HashMap::new();
+        roles.sort_by_key(|role| role.position);
+        for role in roles.into_iter().filter(|role| !role.managed) {
+            let schema = RoleCreateModifySchema {
+                name: Some(role.name.clone()),
+                permissions: Some(role.permissions.clone()),
+                color: Some(role.color as u32),
+                hoist: Some(role.hoist),
+                icon: None,
+                unicode_emoji: role.unicode_emoji.clone(),
+                mentionable: Some(role.mentionable),
+                position: Some(role.position as i32),
+            };
+            // The @everyone role shares its id with the guild, and already exists in the new guild
+            let new_role = if role.id == self.id {
+                RoleObject::modify(target_user, guild.id, guild.id, schema).await?
+            } else {
+                RoleObject::create(target_user, guild.id, schema).await?
+            };
+            role_ids.insert(role.id, new_role.id);
+        }
+
+        let mut channel_ids = HashMap::new();
+        // Categories have to exist before the channels inside of them
+        channels.sort_by_key(|channel| {
+            (
+                channel.channel_type != ChannelType::GuildCategory,
+                channel.position,
+            )
+        });
+        for channel in channels {
+            let schema = ChannelCreateSchema {
+                name: channel.name.clone().unwrap_or_default(),
+                channel_type: Some(channel.channel_type),
+                topic: channel.topic.clone(),
+                bitrate: channel.bitrate,
+                user_limit: channel.user_limit,
+                rate_limit_per_user: channel.rate_limit_per_user,
+                position: channel.position,
+                permission_overwrites: Some(remap_overwrites(&channel, &role_ids)),
+                parent_id: channel
+                    .parent_id
+                    .and_then(|parent_id| channel_ids.get(&parent_id).copied()),
+                nsfw: channel.nsfw,
+                default_auto_archive_duration: channel.default_auto_archive_duration,
+                default_thread_rate_limit_per_user: channel.default_thread_rate_limit_per_user,
+                video_quality_mode: channel.video_quality_mode,
+                flags: channel
+                    .flags
+                    .map(|flags| ChannelFlags::from_bits_truncate(flags as u64)),
+                ..Default::default()
+            };
+            let new_channel = Channel::create(target_user, guild.id, None, schema).await?;
+            channel_ids.insert(channel.id, new_channel.id);
+        }
+
+        Ok(GuildStructureClone {
+            guild,
+            roles: role_ids,
+            channels: channel_ids,
+        })
+    }
+}
+
+/// Returns the role overwrites of a channel, pointing to the copied roles. Overwrites for members
+/// and for roles which were not copied are dropped.
+fn remap_overwrites(
+    channel: &Channel,
+    role_ids: &HashMap<Snowflake, Snowflake>,
+) -> Vec<PermissionOverwrite> {
+    channel
+        .overwrites()
+        .into_iter()
+        .filter(|overwrite| overwrite.overwrite_type != "1")
+        .filter_map(|overwrite| {
+            Some(PermissionOverwrite {
+                id: *role_ids.get(&overwrite.id)?,
+                ..overwrite
+            })
+        })
+        .collect()
+}
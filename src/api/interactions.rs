@@ -0,0 +1,341 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::{to_string, Value};
+
+use crate::api::routes;
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    ApplicationCommandOptionChoice, Interaction, InteractionApplicationCommandCallbackData,
+    InteractionAutocompleteCallbackData, InteractionCallbackData, InteractionModalCallbackData,
+    InteractionResponse, InteractionResponseModifySchema, InteractionResponseType, InteractionType,
+    Message, MessageFlags, ModalActionRow, Snowflake,
+};
+
+/// The maximum amount of choices an autocomplete interaction can be responded to with.
+pub const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+/// The maximum length of the name of a choice, and of the value of a string choice.
+pub const MAX_CHOICE_LENGTH: usize = 100;
+/// The maximum amount of rows of a modal.
+pub const MAX_MODAL_ROWS: usize = 5;
+/// The maximum length of the title of a modal, and of the labels of its inputs.
+pub const MAX_MODAL_TITLE_LENGTH: usize = 45;
+
+impl InteractionAutocompleteCallbackData {
+    /// Creates the response to an autocomplete interaction.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if there are more than
+    /// [`MAX_AUTOCOMPLETE_CHOICES`] choices, or if the name or string value of a choice is empty
+    /// or longer than [`MAX_CHOICE_LENGTH`] characters.
+    pub fn new(choices: Vec<ApplicationCommandOptionChoice>) -> ChorusResult<Self> {
+        if choices.len() > MAX_AUTOCOMPLETE_CHOICES {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "At most {} choices can be suggested, got {}",
+                    MAX_AUTOCOMPLETE_CHOICES,
+                    choices.len()
+                ),
+            });
+        }
+        for choice in &choices {
+            check_choice_length("name", &choice.name)?;
+            if let Value::String(value) = &choice.value {
+                check_choice_length("value", value)?;
+            }
+        }
+        Ok(Self { choices })
+    }
+}
+
+fn check_choice_length(field: &str, text: &str) -> ChorusResult<()> {
+    let length = text.chars().count();
+    if length == 0 || length > MAX_CHOICE_LENGTH {
+        return Err(ChorusError::InvalidArguments {
+            error: format!(
+                "The {} of a choice must be between 1 and {} characters long, got {}",
+                field, MAX_CHOICE_LENGTH, length
+            ),
+        });
+    }
+    Ok(())
+}
+
+impl InteractionModalCallbackData {
+    /// Creates a modal.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if there are no rows or more than
+    /// [`MAX_MODAL_ROWS`], if a row does not hold exactly one text input, or if the title or a
+    /// label is empty or longer than [`MAX_MODAL_TITLE_LENGTH`] characters.
+    pub fn new(
+        custom_id: impl Into<String>,
+        title: impl Into<String>,
+        components: Vec<ModalActionRow>,
+    ) -> ChorusResult<Self> {
+        let title = title.into();
+        check_modal_text_length("title", &title)?;
+        if components.is_empty() || components.len() > MAX_MODAL_ROWS {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "A modal must have between 1 and {} rows, got {}",
+                    MAX_MODAL_ROWS,
+                    components.len()
+                ),
+            });
+        }
+        for row in &components {
+            let [input] = row.components.as_slice() else {
+                return Err(ChorusError::InvalidArguments {
+                    error: "Each row of a modal must hold exactly one text input".to_string(),
+                });
+            };
+            check_modal_text_length("label", input.label.as_deref().unwrap_or_default())?;
+        }
+        Ok(Self {
+            custom_id: custom_id.into(),
+            title,
+            components,
+        })
+    }
+}
+
+fn check_modal_text_length(field: &str, text: &str) -> ChorusResult<()> {
+    let length = text.chars().count();
+    if length == 0 || length > MAX_MODAL_TITLE_LENGTH {
+        return Err(ChorusError::InvalidArguments {
+            error: format!(
+                "The {} of a modal must be between 1 and {} characters long, got {}",
+                field, MAX_MODAL_TITLE_LENGTH, length
+            ),
+        });
+    }
+    Ok(())
+}
+
+impl Interaction {
+    /// Responds to the interaction. Interactions have to be responded to within 3 seconds.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if the response's flags can not be set, see
+    /// [`InteractionResponse::flags_error`].
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#create-interaction-response>
+    pub async fn respond(
+        &self,
+        user: &mut ChorusUser,
+        response: InteractionResponse,
+    ) -> ChorusResult<()> {
+        if let Some(error) = response.flags_error() {
+            return Err(ChorusError::InvalidArguments { error });
+        }
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::interaction_callback(self.id, &self.token),
+            Some(to_string(&response).unwrap()),
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Suggests choices for the option the user is typing, in response to an
+    /// [autocomplete interaction](InteractionType::ApplicationCommandAutocomplete).
+    ///
+    /// The choices are validated with [`InteractionAutocompleteCallbackData::new`] before any
+    /// request is made.
+    pub async fn respond_autocomplete(
+        &self,
+        user: &mut ChorusUser,
+        choices: Vec<ApplicationCommandOptionChoice>,
+    ) -> ChorusResult<()> {
+        if self.r#type != InteractionType::ApplicationCommandAutocomplete {
+            return Err(ChorusError::InvalidArguments {
+                error: "Only autocomplete interactions can be responded to with choices"
+                    .to_string(),
+            });
+        }
+        let data = InteractionAutocompleteCallbackData::new(choices)?;
+        self.respond(
+            user,
+            InteractionResponse {
+                response_type: InteractionResponseType::ApplicationCommandAutocompleteResult,
+                data: Some(InteractionCallbackData::Autocomplete(data)),
+            },
+        )
+        .await
+    }
+
+    /// Shows a modal to the user, whose answers are sent in a
+    /// [modal submit interaction](InteractionType::ModalSubmit).
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] in response to autocomplete and modal submit
+    /// interactions, which can not be responded to with modals.
+    pub async fn respond_with_modal(
+        &self,
+        user: &mut ChorusUser,
+        modal: InteractionModalCallbackData,
+    ) -> ChorusResult<()> {
+        if matches!(
+            self.r#type,
+            InteractionType::ApplicationCommandAutocomplete | InteractionType::ModalSubmit
+        ) {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "{:?} interactions can not be responded to with modals",
+                    self.r#type
+                ),
+            });
+        }
+        self.respond(user, InteractionResponse::modal(modal)).await
+    }
+
+    /// Acknowledges the interaction, showing that the application is "thinking", see
+    /// [`InteractionResponse::deferred`]. The final response is sent with
+    /// [`Interaction::edit_original_response`], within 15 minutes.
+    pub async fn defer(&self, user: &mut ChorusUser, ephemeral: bool) -> ChorusResult<()> {
+        self.respond(user, InteractionResponse::deferred(ephemeral))
+            .await
+    }
+
+    /// Returns the original response to the interaction.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#get-original-interaction-response>
+    pub async fn get_original_response(&self, user: &mut ChorusUser) -> ChorusResult<Message> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::interaction_original_response(self.application_id, &self.token),
+            None,
+            None,
+            user,
+        )
+        .deserialize_response::<Message>(user)
+        .await
+    }
+
+    /// Edits the original response to the interaction, such as to turn a
+    /// [deferred](Interaction::defer) response into the final one. A deferred ephemeral
+    /// response stays ephemeral.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if the flags can not be set, see
+    /// [`InteractionResponseModifySchema::flags_error`].
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#edit-original-interaction-response>
+    pub async fn edit_original_response(
+        &self,
+        user: &mut ChorusUser,
+        schema: InteractionResponseModifySchema,
+    ) -> ChorusResult<Message> {
+        if let Some(error) = schema.flags_error() {
+            return Err(ChorusError::InvalidArguments { error });
+        }
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::interaction_original_response(self.application_id, &self.token),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<Message>(user)
+        .await
+    }
+
+    /// Sends a followup message, after the interaction has been responded to.
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if the message has flags other than
+    /// [`MessageFlags::INTERACTION_RESPONSE`].
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#create-followup-message>
+    pub async fn followup(
+        &self,
+        user: &mut ChorusUser,
+        message: InteractionApplicationCommandCallbackData,
+    ) -> ChorusResult<Message> {
+        if message.flags.unwrap_or_default() & !MessageFlags::INTERACTION_RESPONSE.bits() != 0 {
+            return Err(ChorusError::InvalidArguments {
+                error: format!(
+                    "Only the flags {:?} can be set on followup messages",
+                    MessageFlags::INTERACTION_RESPONSE
+                ),
+            });
+        }
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::interaction_followups(self.application_id, &self.token),
+            Some(to_string(&message).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<Message>(user)
+        .await
+    }
+
+    /// Deletes the original response to the interaction. Ephemeral responses can not be
+    /// deleted.
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#delete-original-interaction-response>
+    pub async fn delete_original_response(&self, user: &mut ChorusUser) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::interaction_original_response(self.application_id, &self.token),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+
+    /// Edits a [followup message](Interaction::followup).
+    ///
+    /// Fails with [`ChorusError::InvalidArguments`] if the flags can not be set, see
+    /// [`InteractionResponseModifySchema::flags_error`].
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#edit-followup-message>
+    pub async fn edit_followup(
+        &self,
+        user: &mut ChorusUser,
+        message_id: Snowflake,
+        schema: InteractionResponseModifySchema,
+    ) -> ChorusResult<Message> {
+        if let Some(error) = schema.flags_error() {
+            return Err(ChorusError::InvalidArguments { error });
+        }
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::interaction_followup(self.application_id, &self.token, message_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            user,
+        )
+        .deserialize_response::<Message>(user)
+        .await
+    }
+
+    /// Deletes a [followup message](Interaction::followup).
+    ///
+    /// # Reference
+    /// See <https://discord.com/developers/docs/interactions/receiving-and-responding#delete-followup-message>
+    pub async fn delete_followup(
+        &self,
+        user: &mut ChorusUser,
+        message_id: Snowflake,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::interaction_followup(self.application_id, &self.token, message_id),
+            None,
+            None,
+            user,
+        )
+        .handle_request_as_result(user)
+        .await
+    }
+}
@@ -5,6 +5,7 @@
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::errors::ChorusResult;
 use crate::instance::ChorusUser;
 use crate::ratelimiter::ChorusRequest;
@@ -22,16 +23,13 @@ impl ChorusUser {
         invite_code: &str,
         session_id: Option<&str>,
     ) -> ChorusResult<Invite> {
-        let mut request = ChorusRequest {
-            request: Client::new()
-                .post(format!(
-                    "{}/invites/{}",
-                    self.belongs_to.read().unwrap().urls.api,
-                    invite_code
-                ))
-                .header("Authorization", self.token()),
-            limit_type: LimitType::Global,
-        };
+        let mut request = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::invite(invite_code),
+            None,
+            None,
+            self,
+        );
         if let Some(session_id) = session_id {
             request.request = request
                 .request
@@ -48,17 +46,13 @@ impl ChorusUser {
     /// # Reference:
     /// See <https://discord-userdoccers.vercel.app/resources/invite#create-user-invite>
     pub async fn create_user_invite(&mut self, code: Option<&str>) -> ChorusResult<Invite> {
-        ChorusRequest {
-            request: Client::new()
-                .post(format!(
-                    "{}/users/@me/invites",
-                    self.belongs_to.read().unwrap().urls.api
-                ))
-                .body(to_string(&code).unwrap())
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Global,
-        }
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::current_user_invites(),
+            Some(to_string(&code).unwrap()),
+            None,
+            self,
+        )
         .deserialize_response::<Invite>(self)
         .await
     }
@@ -75,18 +69,13 @@ impl ChorusUser {
         create_channel_invite_schema: CreateChannelInviteSchema,
         channel_id: Snowflake,
     ) -> ChorusResult<GuildInvite> {
-        ChorusRequest {
-            request: Client::new()
-                .post(format!(
-                    "{}/channels/{}/invites",
-                    self.belongs_to.read().unwrap().urls.api,
-                    channel_id
-                ))
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json")
-                .body(to_string(&create_channel_invite_schema).unwrap()),
-            limit_type: LimitType::Channel(channel_id),
-        }
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::channel_invites(channel_id),
+            Some(to_string(&create_channel_invite_schema).unwrap()),
+            None,
+            self,
+        )
         .deserialize_response::<GuildInvite>(self)
         .await
     }
@@ -0,0 +1,220 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+
+use crate::errors::ChorusResult;
+#[cfg(feature = "cache")]
+use crate::gateway::Cache;
+use crate::instance::ChorusUser;
+use crate::types::{Channel, Guild, Message, Snowflake, User};
+
+/// An entity which can be fetched through the API by its id alone.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Resolvable: Clone + Send + Sync + Sized {
+    async fn fetch(user: &mut ChorusUser, id: Snowflake) -> ChorusResult<Self>;
+
+    /// Looks the entity up in the gateway's [`Cache`], to avoid fetching it.
+    #[cfg(feature = "cache")]
+    fn from_cache(_cache: &Cache, _id: Snowflake) -> Option<Self> {
+        None
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Resolvable for Channel {
+    async fn fetch(user: &mut ChorusUser, id: Snowflake) -> ChorusResult<Self> {
+        Channel::get(user, id).await
+    }
+
+    #[cfg(feature = "cache")]
+    fn from_cache(cache: &Cache, id: Snowflake) -> Option<Self> {
+        cache.channel(id)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Resolvable for Guild {
+    async fn fetch(user: &mut ChorusUser, id: Snowflake) -> ChorusResult<Self> {
+        Guild::get(id, user).await
+    }
+
+    #[cfg(feature = "cache")]
+    fn from_cache(cache: &Cache, id: Snowflake) -> Option<Self> {
+        cache.full_guild(id)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Resolvable for User {
+    async fn fetch(user: &mut ChorusUser, id: Snowflake) -> ChorusResult<Self> {
+        User::get(user, Some(&id.to_string())).await
+    }
+
+    #[cfg(feature = "cache")]
+    fn from_cache(cache: &Cache, id: Snowflake) -> Option<Self> {
+        match cache.current_user() {
+            Some(current_user) if current_user.id == id => Some(current_user),
+            _ => cache.user(id).map(User::from),
+        }
+    }
+}
+
+/// A reference to an entity of which only the id is known, such as the
+/// [parent](Channel::parent_id) of a channel.
+///
+/// The entity is fetched on the first call to [`LazyRef::resolve`], unless it is in the user's
+/// gateway `Cache`, and cached afterwards. Clones share the same cache. References are equal if
+/// they refer to the same id.
+///
+/// Accessors such as [`Channel::parent`] return a new reference on every call, which has not
+/// been resolved yet. Keep the returned reference, or a clone of it, to resolve the entity only
+/// once.
+///
+/// # Example
+/// ```no_run
+/// # async fn example(user: &mut chorus::instance::ChorusUser, channel: chorus::types::Channel) -> chorus::errors::ChorusResult<()> {
+/// if let Some(category) = channel.parent() {
+///     // Only fetched once, however often this reference is resolved
+///     let name = category.resolve(user).await?.name;
+///     let position = category.resolve(user).await?.position;
+///     println!("{:?} is in {:?} at {:?}", channel.name, name, position);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct LazyRef<T> {
+    id: Snowflake,
+    cached: Arc<RwLock<Option<T>>>,
+}
+
+impl<T: Resolvable> LazyRef<T> {
+    /// Creates a reference to the entity with the given id, which is fetched once it is
+    /// resolved.
+    pub fn new(id: Snowflake) -> Self {
+        Self {
+            id,
+            cached: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Creates a reference to an entity which is already known, so resolving it does not fetch
+    /// it.
+    pub fn with_value(id: Snowflake, value: T) -> Self {
+        Self {
+            id,
+            cached: Arc::new(RwLock::new(Some(value))),
+        }
+    }
+
+    pub fn id(&self) -> Snowflake {
+        self.id
+    }
+
+    /// Returns the entity if it has been resolved, without fetching it.
+    pub fn get(&self) -> Option<T> {
+        self.cached.read().unwrap().clone()
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.cached.read().unwrap().is_some()
+    }
+
+    /// Returns the entity, fetching it if it has not been resolved yet and is not in the user's
+    /// gateway `Cache`.
+    pub async fn resolve(&self, user: &mut ChorusUser) -> ChorusResult<T> {
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+        #[cfg(feature = "cache")]
+        let cached = user
+            .cache()
+            .and_then(|cache| T::from_cache(&cache, self.id));
+        #[cfg(not(feature = "cache"))]
+        let cached = None;
+        let value = match cached {
+            Some(value) => value,
+            None => T::fetch(user, self.id).await?,
+        };
+        *self.cached.write().unwrap() = Some(value.clone());
+        Ok(value)
+    }
+
+    /// Forgets the cached entity, so it is fetched again on the next call to
+    /// [`LazyRef::resolve`].
+    pub fn invalidate(&self) {
+        *self.cached.write().unwrap() = None;
+    }
+}
+
+impl<T> Clone for LazyRef<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            cached: self.cached.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LazyRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyRef")
+            .field("id", &self.id)
+            .field("cached", &self.cached.read().unwrap())
+            .finish()
+    }
+}
+
+impl<T> PartialEq for LazyRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for LazyRef<T> {}
+
+impl<T> Hash for LazyRef<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Channel {
+    /// The category or, for threads, the channel this channel is in.
+    pub fn parent(&self) -> Option<LazyRef<Channel>> {
+        self.parent_id.map(LazyRef::new)
+    }
+
+    /// The guild this channel is in.
+    pub fn guild(&self) -> Option<LazyRef<Guild>> {
+        self.guild_id.map(LazyRef::new)
+    }
+
+    /// The creator of a thread, or the owner of a group DM.
+    pub fn owner(&self) -> Option<LazyRef<User>> {
+        self.owner_id.map(LazyRef::new)
+    }
+}
+
+impl Message {
+    /// The channel this message was sent in.
+    pub fn channel(&self) -> LazyRef<Channel> {
+        LazyRef::new(self.channel_id)
+    }
+
+    /// The user who sent this message, already resolved to the author included in the message.
+    pub fn author(&self) -> Option<LazyRef<User>> {
+        self.author
+            .as_ref()
+            .map(|author| LazyRef::with_value(author.id, User::from(author.clone())))
+    }
+}
@@ -0,0 +1,81 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::api::routes;
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::Instance;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{Guild, LimitType, Snowflake, WidgetStyle};
+
+impl Guild {
+    /// Returns the url of a guild's widget image in the given style.
+    ///
+    /// The guild must have its widget enabled.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-widget-image>
+    pub fn widget_image_url(api_url: &str, guild_id: Snowflake, style: WidgetStyle) -> String {
+        routes::guild_widget_image(guild_id)
+            .query("style", style)
+            .url(api_url)
+    }
+}
+
+impl Instance {
+    /// Fetches a guild's widget image in the given style, as PNG data. This endpoint does not
+    /// require authentication.
+    ///
+    /// The guild must have its widget enabled.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-widget-image>
+    pub async fn get_guild_widget_image(
+        &self,
+        guild_id: Snowflake,
+        style: WidgetStyle,
+    ) -> ChorusResult<Vec<u8>> {
+        let route = routes::guild_widget_image(guild_id).query("style", style);
+        self.get_bytes(ChorusRequest::unauthenticated(
+            http::Method::GET,
+            route,
+            self,
+        ))
+        .await
+    }
+
+    /// Fetches an external image through the instance's media proxy, see
+    /// [`UrlBundle::media_proxy_url`](crate::UrlBundle::media_proxy_url).
+    ///
+    /// Returns a [`ChorusError::InvalidArguments`] if `external_url` is not a valid `http` or
+    /// `https` url.
+    pub async fn get_proxied_image(&self, external_url: &str) -> ChorusResult<Vec<u8>> {
+        let url = self.urls.media_proxy_url(external_url).ok_or_else(|| {
+            ChorusError::InvalidArguments {
+                error: format!("{} is not a valid http(s) url", external_url),
+            }
+        })?;
+        let request = ChorusRequest::new(
+            http::Method::GET,
+            &url,
+            None,
+            None,
+            None,
+            None,
+            LimitType::Global,
+        );
+        self.get_bytes(request).await
+    }
+
+    /// Sends a [`ChorusRequest`] for binary data, such as an image, to this instance.
+    async fn get_bytes(&self, request: ChorusRequest) -> ChorusResult<Vec<u8>> {
+        let response = request.send_unauthenticated(self).await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| ChorusError::InvalidResponse {
+                error: format!("Error while trying to read the response body: {}", e),
+            })?;
+        Ok(bytes.to_vec())
+    }
+}
@@ -5,15 +5,36 @@
 //! All of the API's endpoints.
 
 #![allow(unused_imports)]
+pub use applications::*;
 pub use channels::messages::*;
+pub use channels::webhooks::*;
+pub use gateway::*;
 pub use guilds::*;
+pub use interactions::*;
 pub use invites::*;
+pub use lazy_ref::*;
+pub use media::*;
+pub use oauth2::*;
 pub use policies::instance::instance::*;
+pub use raw::*;
+pub use regions::*;
+pub use reporting::*;
 pub use users::*;
 
+pub mod applications;
 pub mod auth;
 pub mod channels;
+pub mod gateway;
 pub mod guilds;
+pub mod interactions;
 pub mod invites;
+pub mod lazy_ref;
+pub mod media;
+pub mod oauth2;
+pub mod permissions;
 pub mod policies;
+pub mod raw;
+pub mod regions;
+pub mod reporting;
+pub mod routes;
 pub mod users;
@@ -0,0 +1,145 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+use url::form_urlencoded;
+
+use crate::api::routes::{self, Route};
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{
+    ApplicationAuthorizeResponse, ApplicationAuthorizeSchema, PermissionFlags, Snowflake,
+};
+
+/// The authorization of a bot application, which adds the bot to a guild.
+///
+/// Can be turned into the invite url users open to add the bot
+/// ([`BotAuthorization::url`]), or be authorized directly by a user who is allowed to manage the
+/// guild ([`ChorusUser::authorize_bot`]).
+///
+/// # Example
+/// ```
+/// use chorus::api::BotAuthorization;
+/// use chorus::types::{PermissionFlags, Snowflake};
+///
+/// let url = BotAuthorization::new(Snowflake(1234))
+///     .permissions(PermissionFlags::SEND_MESSAGES | PermissionFlags::VIEW_CHANNEL)
+///     .url("https://discord.com");
+/// assert_eq!(
+///     url,
+///     "https://discord.com/oauth2/authorize?client_id=1234&scope=bot+applications.commands&permissions=3072"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotAuthorization {
+    pub client_id: Snowflake,
+    /// The OAuth2 scopes to authorize. Defaults to `bot` and `applications.commands`.
+    pub scopes: Vec<String>,
+    /// The permissions the bot asks for in the guild.
+    pub permissions: PermissionFlags,
+    /// The guild which is preselected on the authorization page.
+    pub guild_id: Option<Snowflake>,
+    /// Whether the user can not select a different guild than [`BotAuthorization::guild_id`].
+    pub disable_guild_select: bool,
+}
+
+impl BotAuthorization {
+    pub fn new(client_id: Snowflake) -> Self {
+        Self {
+            client_id,
+            scopes: vec!["bot".to_string(), "applications.commands".to_string()],
+            permissions: PermissionFlags::empty(),
+            guild_id: None,
+            disable_guild_select: false,
+        }
+    }
+
+    pub fn permissions(mut self, permissions: PermissionFlags) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// Preselects a guild, optionally without letting the user select a different one.
+    pub fn guild(mut self, guild_id: Snowflake, disable_guild_select: bool) -> Self {
+        self.guild_id = Some(guild_id);
+        self.disable_guild_select = disable_guild_select;
+        self
+    }
+
+    /// The query parameters of the authorization.
+    fn query(&self) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        query
+            .append_pair("client_id", &self.client_id.to_string())
+            .append_pair("scope", &self.scopes.join(" "));
+        if !self.permissions.is_empty() {
+            query.append_pair("permissions", &self.permissions.bits().to_string());
+        }
+        if let Some(guild_id) = self.guild_id {
+            query.append_pair("guild_id", &guild_id.to_string());
+            if self.disable_guild_select {
+                query.append_pair("disable_guild_select", "true");
+            }
+        }
+        query.finish()
+    }
+
+    /// Returns the url which users open to add the bot, on the instance with the given root url
+    /// (see [`UrlBundle::root`](crate::UrlBundle::root)), for example `https://discord.com`.
+    pub fn url(&self, root_url: &str) -> String {
+        format!(
+            "{}/oauth2/authorize?{}",
+            root_url.trim_end_matches('/'),
+            self.query()
+        )
+    }
+
+    /// The API route which authorizes the bot, see [`ChorusUser::authorize_bot`].
+    pub fn route(&self) -> Route {
+        Route::new(format!(
+            "{}?{}",
+            routes::oauth2_authorize().path(),
+            self.query()
+        ))
+    }
+}
+
+impl ChorusUser {
+    /// Adds a bot to a guild, granting it the permissions it asks for.
+    ///
+    /// Only works for user accounts, and requires the
+    /// [`MANAGE_GUILD`](PermissionFlags::MANAGE_GUILD) permission in the guild. Instances may
+    /// require a captcha to be solved, in which case the request fails and has to be completed
+    /// by the user through [`BotAuthorization::url`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/topics/oauth2#authorize>
+    pub async fn authorize_bot(
+        &mut self,
+        authorization: &BotAuthorization,
+        guild_id: Snowflake,
+    ) -> ChorusResult<ApplicationAuthorizeResponse> {
+        let schema = ApplicationAuthorizeSchema {
+            authorize: true,
+            guild_id: Some(guild_id),
+            permissions: Some(authorization.permissions.bits().to_string()),
+            ..Default::default()
+        };
+        ChorusRequest::from_route(
+            http::Method::POST,
+            authorization.route(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .deserialize_response::<ApplicationAuthorizeResponse>(self)
+        .await
+    }
+}
@@ -0,0 +1,184 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::{ChorusError, ChorusResult};
+use crate::gateway::{Observer, Shared};
+use crate::instance::ChorusUser;
+use crate::types::{
+    Channel, ChannelType, Guild, GuildDelete, GuildMember, GuildMemberRemove, GuildMemberUpdate,
+    IntoShared, PermissionFlags, Snowflake, User,
+};
+
+impl ChorusUser {
+    /// Returns whether the user has the given permissions in a guild channel, without making any
+    /// requests.
+    ///
+    /// The permissions are computed from the channel and its guild, which have to be observed on
+    /// the user's gateway connection (see
+    /// [`GatewayHandle::observe`](crate::gateway::GatewayHandle::observe)) or be in its
+    /// `Cache`, and the user's own member object in [`ChorusUser::guild_members`], which follows
+    /// the member updates of the user's gateway, or in its
+    /// [`MemberCache`](crate::gateway::MemberCache). Returns `None` if any of them is not known,
+    /// or if the channel is not a guild channel.
+    pub async fn can(&self, permission: PermissionFlags, channel_id: Snowflake) -> Option<bool> {
+        self.channel_permissions(channel_id)
+            .await
+            .map(|permissions| permissions.contains(permission))
+    }
+
+    /// Computes the user's permissions in a guild channel, without making any requests. See
+    /// [`ChorusUser::can`].
+    ///
+    /// In threads, the permission overwrites of the parent channel apply, and
+    /// [`SEND_MESSAGES`](PermissionFlags::SEND_MESSAGES) is only granted along with
+    /// [`SEND_MESSAGES_IN_THREADS`](PermissionFlags::SEND_MESSAGES_IN_THREADS).
+    pub async fn channel_permissions(&self, channel_id: Snowflake) -> Option<PermissionFlags> {
+        let channel = self.known_channel(channel_id).await?;
+        let guild_id = channel.guild_id?;
+        let guild = self.known_guild(guild_id).await?;
+        let user_id = self.object.read().unwrap().id;
+        let member_roles = self.known_member(guild_id, user_id)?.roles;
+
+        let is_thread = matches!(
+            channel.channel_type,
+            ChannelType::GuildNewsThread
+                | ChannelType::GuildPublicThread
+                | ChannelType::GuildPrivateThread
+        );
+        let overwrites = match channel.parent_id {
+            Some(parent_id) if is_thread => self.known_channel(parent_id).await?.overwrites(),
+            _ => channel.overwrites(),
+        };
+
+        let mut permissions = guild.member_permissions(user_id, &member_roles, &overwrites);
+        if is_thread {
+            permissions.set(
+                PermissionFlags::SEND_MESSAGES,
+                permissions.contains(PermissionFlags::SEND_MESSAGES_IN_THREADS),
+            );
+        }
+        Some(permissions)
+    }
+
+    /// Returns a channel observed on the user's gateway, or in its cache.
+    async fn known_channel(&self, channel_id: Snowflake) -> Option<Channel> {
+        if let Some(channel) = self.gateway.get_observed::<Channel>(channel_id).await {
+            return Some(channel);
+        }
+        #[cfg(feature = "cache")]
+        {
+            self.cache()?.channel(channel_id)
+        }
+        #[cfg(not(feature = "cache"))]
+        None
+    }
+
+    /// Returns a guild observed on the user's gateway, or in its cache, along with its roles.
+    async fn known_guild(&self, guild_id: Snowflake) -> Option<Guild> {
+        if let Some(guild) = self.gateway.get_observed::<Guild>(guild_id).await {
+            return Some(guild);
+        }
+        #[cfg(feature = "cache")]
+        {
+            self.cache()?.full_guild(guild_id)
+        }
+        #[cfg(not(feature = "cache"))]
+        None
+    }
+
+    /// Returns the user's own member object in a guild.
+    fn known_member(&self, guild_id: Snowflake, user_id: Snowflake) -> Option<GuildMember> {
+        if let Some(member) = self.guild_members.read().unwrap().get(&guild_id) {
+            return Some(member.clone());
+        }
+        self.gateway.member_cache()?.get(guild_id, user_id)
+    }
+
+    /// Keeps [`ChorusUser::guild_members`] up to date with the member updates the user's gateway
+    /// receives about the user themselves.
+    pub(crate) async fn track_guild_members(&self) {
+        let tracker = Arc::new(GuildMemberTracker {
+            user: self.object.clone(),
+            members: self.guild_members.clone(),
+        });
+        let mut events = self.gateway.events.lock().await;
+        events.guild.member_update.subscribe(tracker.clone());
+        events.guild.member_remove.subscribe(tracker.clone());
+        events.guild.delete.subscribe(tracker);
+    }
+
+    /// Returns a [`ChorusError::MissingPermissions`] if permission checks are enabled and the user
+    /// is known to lack any of the given permissions in the channel.
+    pub(crate) async fn require_permissions(
+        &self,
+        permissions: PermissionFlags,
+        channel_id: Snowflake,
+    ) -> ChorusResult<()> {
+        if !self.check_permissions {
+            return Ok(());
+        }
+        match self.channel_permissions(channel_id).await {
+            Some(granted) if !granted.contains(permissions.clone()) => {
+                Err(ChorusError::MissingPermissions {
+                    permissions: permissions.difference(granted),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Applies the gateway events concerning the user's own guild members to
+/// [`ChorusUser::guild_members`], so that role changes are reflected in permission checks.
+#[derive(Debug)]
+struct GuildMemberTracker {
+    user: Shared<User>,
+    members: Shared<HashMap<Snowflake, GuildMember>>,
+}
+
+impl GuildMemberTracker {
+    fn is_user(&self, user_id: Snowflake) -> bool {
+        self.user.read().unwrap().id == user_id
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberUpdate> for GuildMemberTracker {
+    async fn update(&self, data: &GuildMemberUpdate) {
+        if !self.is_user(data.user.id) {
+            return;
+        }
+        let mut members = self.members.write().unwrap();
+        // Updates include the member's roles, so an unknown member can be added from one
+        let member = members.entry(data.guild_id).or_default();
+        if member.user.is_none() {
+            member.user = Some(data.user.clone().into_shared());
+        }
+        data.apply(member);
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberRemove> for GuildMemberTracker {
+    async fn update(&self, data: &GuildMemberRemove) {
+        if self.is_user(data.user.id) {
+            self.members.write().unwrap().remove(&data.guild_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildDelete> for GuildMemberTracker {
+    async fn update(&self, data: &GuildDelete) {
+        // Unavailable guilds are only temporarily unreachable
+        if !data.guild.unavailable {
+            self.members.write().unwrap().remove(&data.guild.id);
+        }
+    }
+}
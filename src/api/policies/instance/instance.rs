@@ -4,9 +4,10 @@
 
 use serde_json::from_str;
 
-use crate::errors::{ChorusError, ChorusResult};
+use crate::api::routes;
+use crate::errors::{ApiError, ChorusError, ChorusResult};
 use crate::instance::Instance;
-use crate::types::GeneralConfiguration;
+use crate::types::{GeneralConfiguration, InstancePolicies};
 
 impl Instance {
     /// Gets the instance policies schema.
@@ -17,8 +18,13 @@ impl Instance {
     /// # Reference
     /// See <https://docs.spacebar.chat/routes/#get-/policies/instance/>
     pub async fn general_configuration_schema(&self) -> ChorusResult<GeneralConfiguration> {
-        let endpoint_url = self.urls.api.clone() + "/policies/instance";
-        let request = match self.client.get(&endpoint_url).send().await {
+        let route = routes::policies_instance();
+        let endpoint_url = route.url(&self.urls.api);
+        let request = match self
+            .unauthenticated_request(http::Method::GET, &endpoint_url)
+            .send()
+            .await
+        {
             Ok(result) => result,
             Err(e) => {
                 return Err(ChorusError::RequestFailed {
@@ -29,13 +35,36 @@ impl Instance {
         };
 
         if !request.status().as_str().starts_with('2') {
+            let status = request.status().as_u16();
+            let body = request.text().await.unwrap_or_default();
             return Err(ChorusError::ReceivedErrorCode {
-                error_code: request.status().as_u16(),
-                error: request.text().await.unwrap(),
+                route: route.path().to_string(),
+                source: ApiError::from_response_body(status, &body),
             });
         }
 
-        let body = request.text().await.unwrap();
-        Ok(from_str::<GeneralConfiguration>(&body).unwrap())
+        let body = request.text().await.unwrap_or_default();
+        from_str::<GeneralConfiguration>(&body).map_err(|e| ChorusError::InvalidResponse {
+            error: e.to_string(),
+        })
+    }
+
+    /// Gets the instance's terms of service url, from its
+    /// [general configuration](Instance::general_configuration_schema).
+    ///
+    /// Spacebar does not serve its registration configuration, so what an instance requires from
+    /// new accounts is not part of its policies, see
+    /// [`RegistrationRequirements`](crate::types::RegistrationRequirements).
+    ///
+    /// # Notes
+    /// This is a Spacebar only endpoint.
+    ///
+    /// # Reference
+    /// See <https://docs.spacebar.chat/routes/#get-/policies/instance/>
+    pub async fn policies(&self) -> ChorusResult<InstancePolicies> {
+        let general = self.general_configuration_schema().await?;
+        Ok(InstancePolicies {
+            tos_url: general.tos_page,
+        })
     }
 }
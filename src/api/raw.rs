@@ -0,0 +1,68 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::{from_str, Value};
+
+use crate::api::routes::Route;
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+
+/// The response to a request made with [`ChorusUser::request_raw`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawResponse {
+    /// The HTTP status code of the response.
+    pub status: http::StatusCode,
+    /// The JSON body of the response. [`Value::Null`] if the response had no body.
+    pub body: Value,
+}
+
+impl ChorusUser {
+    /// Sends an authenticated request to an endpoint which chorus does not wrap (yet).
+    ///
+    /// `path` is relative to the instance's API url, for example `/channels/1234/messages`. The
+    /// request counts towards the rate limit bucket of the path's major parameter, just like the
+    /// endpoints wrapped by chorus.
+    ///
+    /// Error responses are returned as a [`ChorusError`], like for any other endpoint.
+    /// If the response body is not valid JSON, a [`ChorusError::InvalidResponse`] is returned.
+    pub async fn request_raw(
+        &mut self,
+        method: http::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> ChorusResult<RawResponse> {
+        let chorus_request = ChorusRequest::from_route(
+            method,
+            Route::new(path),
+            body.map(|body| body.to_string()),
+            None,
+            self,
+        );
+        let response = chorus_request.send_request(self).await?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ChorusError::InvalidResponse {
+                error: format!(
+                    "Error while trying to process the HTTP response into a String: {}",
+                    e
+                ),
+            })?;
+        if text.trim().is_empty() {
+            return Ok(RawResponse {
+                status,
+                body: Value::Null,
+            });
+        }
+        let body = from_str::<Value>(&text).map_err(|e| ChorusError::InvalidResponse {
+            error: format!(
+                "Error while trying to parse the response as JSON: {}. Response: {}",
+                e, text
+            ),
+        })?;
+        Ok(RawResponse { status, body })
+    }
+}
@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::{ChorusUser, Instance};
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{Guild, Snowflake, VoiceRegion};
+
+impl Instance {
+    /// Fetches the voice regions of the instance. This endpoint does not require authentication.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/voice#get-voice-regions>
+    pub async fn get_voice_regions(&self) -> ChorusResult<Vec<VoiceRegion>> {
+        ChorusRequest::unauthenticated(http::Method::GET, routes::voice_regions(), self)
+            .deserialize_unauthenticated_response::<Vec<VoiceRegion>>(self)
+            .await
+    }
+}
+
+impl Guild {
+    /// Fetches the voice regions available to a guild, including custom regions.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-voice-regions>
+    pub async fn get_voice_regions(
+        guild_id: Snowflake,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Vec<VoiceRegion>> {
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::guild_voice_regions(guild_id),
+            None,
+            None,
+            user,
+        );
+        chorus_request
+            .deserialize_response::<Vec<VoiceRegion>>(user)
+            .await
+    }
+}
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde_json::to_string;
+
+use crate::api::routes;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::ratelimiter::ChorusRequest;
+use crate::types::{ReportMenu, ReportSchema, ReportSubmission, ReportType};
+
+impl ChorusUser {
+    /// Fetches the menu which has to be walked through to report content of the given type.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/report#get-report-menu>
+    pub async fn get_report_menu(&mut self, report_type: ReportType) -> ChorusResult<ReportMenu> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::reporting_menu(report_type),
+            None,
+            None,
+            self,
+        )
+        .deserialize_response::<ReportMenu>(self)
+        .await
+    }
+
+    /// Submits a report of the given type.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/report#create-report>
+    pub async fn submit_report(
+        &mut self,
+        report_type: ReportType,
+        schema: ReportSchema,
+    ) -> ChorusResult<ReportSubmission> {
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::reporting(report_type),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .deserialize_response::<ReportSubmission>(self)
+        .await
+    }
+}
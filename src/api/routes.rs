@@ -0,0 +1,307 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Paths of the API's endpoints.
+//!
+//! Every endpoint wrapped by chorus builds its path through one of the functions in this module.
+//! They can also be used to make raw requests through the
+//! [`ChorusRequest`](crate::ratelimiter::ChorusRequest) ratelimiter, see
+//! [`ChorusRequest::from_route`](crate::ratelimiter::ChorusRequest::from_route).
+
+use std::fmt;
+
+use crate::types::{LimitType, MessageSearchEndpoint, ReportType, Snowflake};
+
+/// The path of an API endpoint, relative to the API url, along with the rate limit bucket of its
+/// major parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Route {
+    path: String,
+    limit_type: LimitType,
+}
+
+impl Route {
+    /// Creates a route from a path, such as `/channels/1234/messages`.
+    ///
+    /// The rate limit bucket is derived from the major parameter of the path: the channel id of
+    /// `/channels/{id}/..`, the guild id of `/guilds/{id}/..` or the webhook id of
+    /// `/webhooks/{id}/..`. Other paths use the global bucket.
+    pub fn new(path: impl Into<String>) -> Route {
+        let path = path.into();
+        let limit_type = major_parameter(&path);
+        Route { path, limit_type }
+    }
+
+    /// The path, relative to the API url.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The rate limit bucket requests to this route count towards.
+    pub fn limit_type(&self) -> LimitType {
+        self.limit_type
+    }
+
+    /// Appends a query parameter to the path.
+    pub fn query(mut self, name: &str, value: impl fmt::Display) -> Route {
+        let separator = if self.path.contains('?') { '&' } else { '?' };
+        self.path = format!("{}{}{}={}", self.path, separator, name, value);
+        self
+    }
+
+    /// Returns the full url of this route on the API with the given url.
+    pub fn url(&self, api_url: &str) -> String {
+        format!("{}{}", api_url, self.path)
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// Extracts the rate limit bucket from the major parameter of a path.
+fn major_parameter(path: &str) -> LimitType {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let resource = segments.next().unwrap_or_default();
+    let id = segments.next().and_then(|id| id.parse::<u64>().ok());
+    match (resource, id) {
+        ("channels", Some(id)) => LimitType::Channel(Snowflake(id)),
+        ("guilds", Some(id)) => LimitType::Guild(Snowflake(id)),
+        ("webhooks", Some(id)) => LimitType::Webhook(Snowflake(id)),
+        ("auth", _) if path.ends_with("/login") => LimitType::AuthLogin,
+        ("auth", _) if path.ends_with("/register") => LimitType::AuthRegister,
+        _ => LimitType::Global,
+    }
+}
+
+macro_rules! routes {
+    ($($(#[$meta:meta])* $name:ident($($arg:ident: $ty:ty),*) => $path:literal;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name($($arg: $ty),*) -> Route {
+                Route::new(format!($path $(, $arg)*))
+            }
+        )*
+    };
+}
+
+routes! {
+    /// `/auth/login`
+    auth_login() => "/auth/login";
+    /// `/auth/register`
+    auth_register() => "/auth/register";
+    /// `/auth/verify`
+    auth_verify() => "/auth/verify";
+    /// `/auth/verify/resend`
+    auth_verify_resend() => "/auth/verify/resend";
+    /// `/phone-verifications/verify`
+    phone_verifications_verify() => "/phone-verifications/verify";
+    /// `/policies/instance`
+    policies_instance() => "/policies/instance";
+    /// `/policies/instance/limits`
+    policies_instance_limits() => "/policies/instance/limits";
+
+    /// `/applications/{application_id}/commands`
+    application_commands(application_id: Snowflake) => "/applications/{}/commands";
+    /// `/applications/{application_id}/commands/{command_id}`
+    application_command(application_id: Snowflake, command_id: Snowflake) => "/applications/{}/commands/{}";
+    /// `/applications/{application_id}/guilds/{guild_id}/commands`
+    application_guild_commands(application_id: Snowflake, guild_id: Snowflake) => "/applications/{}/guilds/{}/commands";
+    /// `/applications/{application_id}/guilds/{guild_id}/commands/permissions`
+    application_guild_commands_permissions(application_id: Snowflake, guild_id: Snowflake) => "/applications/{}/guilds/{}/commands/permissions";
+    /// `/applications/{application_id}/guilds/{guild_id}/commands/{command_id}`
+    application_guild_command(application_id: Snowflake, guild_id: Snowflake, command_id: Snowflake) => "/applications/{}/guilds/{}/commands/{}";
+    /// `/applications/{application_id}/guilds/{guild_id}/commands/{command_id}/permissions`
+    application_guild_command_permissions(application_id: Snowflake, guild_id: Snowflake, command_id: Snowflake) => "/applications/{}/guilds/{}/commands/{}/permissions";
+
+    /// `/channels/{channel_id}`
+    channel(channel_id: Snowflake) => "/channels/{}";
+    /// `/channels/{channel_id}/greet`
+    channel_greet(channel_id: Snowflake) => "/channels/{}/greet";
+    /// `/channels/{channel_id}/invites`
+    channel_invites(channel_id: Snowflake) => "/channels/{}/invites";
+    /// `/channels/{channel_id}/messages`
+    channel_messages(channel_id: Snowflake) => "/channels/{}/messages";
+    /// `/channels/{channel_id}/messages/bulk-delete`
+    channel_messages_bulk_delete(channel_id: Snowflake) => "/channels/{}/messages/bulk-delete";
+    /// `/channels/{channel_id}/messages/{message_id}`
+    channel_message(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/messages/{}";
+    /// `/channels/{channel_id}/messages/{message_id}/ack`
+    channel_message_ack(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/messages/{}/ack";
+    /// `/channels/{channel_id}/messages/{message_id}/crosspost`
+    channel_message_crosspost(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/messages/{}/crosspost";
+    /// `/channels/{channel_id}/messages/{message_id}/hide-guild-feed`
+    channel_message_hide_guild_feed(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/messages/{}/hide-guild-feed";
+    /// `/channels/{channel_id}/messages/{message_id}/threads`
+    channel_message_threads(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/messages/{}/threads";
+    /// `/channels/{channel_id}/messages/{message_id}/reactions`
+    channel_message_reactions(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/messages/{}/reactions";
+    /// `/channels/{channel_id}/messages/{message_id}/reactions/{emoji}`
+    channel_message_reaction(channel_id: Snowflake, message_id: Snowflake, emoji: &str) => "/channels/{}/messages/{}/reactions/{}";
+    /// `/channels/{channel_id}/messages/{message_id}/reactions/{emoji}/@me`
+    channel_message_own_reaction(channel_id: Snowflake, message_id: Snowflake, emoji: &str) => "/channels/{}/messages/{}/reactions/{}/@me";
+    /// `/channels/{channel_id}/messages/{message_id}/reactions/{emoji}/{reaction_type}/@me`
+    channel_message_own_typed_reaction(channel_id: Snowflake, message_id: Snowflake, emoji: &str, reaction_type: u8) => "/channels/{}/messages/{}/reactions/{}/{}/@me";
+    /// `/channels/{channel_id}/messages/{message_id}/reactions/{emoji}/{user_id}`
+    channel_message_user_reaction(channel_id: Snowflake, message_id: Snowflake, emoji: &str, user_id: Snowflake) => "/channels/{}/messages/{}/reactions/{}/{}";
+    /// `/channels/{channel_id}/permissions/{overwrite_id}`
+    channel_permission(channel_id: Snowflake, overwrite_id: Snowflake) => "/channels/{}/permissions/{}";
+    /// `/channels/{channel_id}/pins`
+    channel_pins(channel_id: Snowflake) => "/channels/{}/pins";
+    /// `/channels/{channel_id}/pins/ack`
+    channel_pins_ack(channel_id: Snowflake) => "/channels/{}/pins/ack";
+    /// `/channels/{channel_id}/pins/{message_id}`
+    channel_pin(channel_id: Snowflake, message_id: Snowflake) => "/channels/{}/pins/{}";
+    /// `/channels/{channel_id}/recipients/{user_id}`
+    channel_recipient(channel_id: Snowflake, user_id: Snowflake) => "/channels/{}/recipients/{}";
+    /// `/channels/{thread_id}/thread-members`
+    channel_thread_members(thread_id: Snowflake) => "/channels/{}/thread-members";
+    /// `/channels/{thread_id}/thread-members/@me`
+    channel_own_thread_member(thread_id: Snowflake) => "/channels/{}/thread-members/@me";
+    /// `/channels/{thread_id}/thread-members/{user_id}`
+    channel_thread_member(thread_id: Snowflake, user_id: Snowflake) => "/channels/{}/thread-members/{}";
+    /// `/channels/{channel_id}/threads`
+    channel_threads(channel_id: Snowflake) => "/channels/{}/threads";
+    /// `/channels/{channel_id}/threads/archived/private`
+    channel_private_archived_threads(channel_id: Snowflake) => "/channels/{}/threads/archived/private";
+    /// `/channels/{channel_id}/threads/archived/public`
+    channel_public_archived_threads(channel_id: Snowflake) => "/channels/{}/threads/archived/public";
+    /// `/channels/{channel_id}/webhooks`
+    channel_webhooks(channel_id: Snowflake) => "/channels/{}/webhooks";
+    /// `/channels/{channel_id}/messages/search` or `/guilds/{guild_id}/messages/search`
+    messages_search(endpoint: &MessageSearchEndpoint) => "/{}/messages/search";
+
+    /// `/gateway/bot`
+    gateway_bot() => "/gateway/bot";
+
+    /// `/guilds`
+    guilds() => "/guilds";
+    /// `/guilds/{guild_id}`
+    guild(guild_id: Snowflake) => "/guilds/{}";
+    /// `/guilds/{guild_id}/audit-logs`
+    guild_audit_logs(guild_id: Snowflake) => "/guilds/{}/audit-logs";
+    /// `/guilds/{guild_id}/auto-moderation/rules`
+    guild_auto_moderation_rules(guild_id: Snowflake) => "/guilds/{}/auto-moderation/rules";
+    /// `/guilds/{guild_id}/auto-moderation/rules/{rule_id}`
+    guild_auto_moderation_rule(guild_id: Snowflake, rule_id: Snowflake) => "/guilds/{}/auto-moderation/rules/{}";
+    /// `/guilds/{guild_id}/bans`
+    guild_bans(guild_id: Snowflake) => "/guilds/{}/bans";
+    /// `/guilds/{guild_id}/bans/{user_id}`
+    guild_ban(guild_id: Snowflake, user_id: Snowflake) => "/guilds/{}/bans/{}";
+    /// `/guilds/{guild_id}/channels`
+    guild_channels(guild_id: Snowflake) => "/guilds/{}/channels";
+    /// `/guilds/{guild_id}/delete`
+    guild_delete(guild_id: Snowflake) => "/guilds/{}/delete";
+    /// `/guilds/{guild_id}/incident-actions`
+    guild_incident_actions(guild_id: Snowflake) => "/guilds/{}/incident-actions";
+    /// `/guilds/{guild_id}/regions`
+    guild_voice_regions(guild_id: Snowflake) => "/guilds/{}/regions";
+    /// `/guilds/{guild_id}/requests`
+    guild_join_requests(guild_id: Snowflake) => "/guilds/{}/requests";
+    /// `/guilds/{guild_id}/requests/id/{request_id}`
+    guild_join_request(guild_id: Snowflake, request_id: Snowflake) => "/guilds/{}/requests/id/{}";
+    /// `/guilds/{guild_id}/members`
+    guild_members(guild_id: Snowflake) => "/guilds/{}/members";
+    /// `/guilds/{guild_id}/members/search`
+    guild_members_search(guild_id: Snowflake) => "/guilds/{}/members/search";
+    /// `/guilds/{guild_id}/members/@me`
+    guild_own_member(guild_id: Snowflake) => "/guilds/{}/members/@me";
+    /// `/guilds/{guild_id}/members/{user_id}`
+    guild_member(guild_id: Snowflake, user_id: Snowflake) => "/guilds/{}/members/{}";
+    /// `/guilds/{guild_id}/members/{user_id}/roles/{role_id}`
+    guild_member_role(guild_id: Snowflake, user_id: Snowflake, role_id: Snowflake) => "/guilds/{}/members/{}/roles/{}";
+    /// `/guilds/{guild_id}/preview`
+    guild_preview(guild_id: Snowflake) => "/guilds/{}/preview";
+    /// `/guilds/{guild_id}/profile/@me`
+    guild_own_profile(guild_id: Snowflake) => "/guilds/{}/profile/@me";
+    /// `/guilds/{guild_id}/roles`
+    guild_roles(guild_id: Snowflake) => "/guilds/{}/roles";
+    /// `/guilds/{guild_id}/roles/member-counts`
+    guild_role_member_counts(guild_id: Snowflake) => "/guilds/{}/roles/member-counts";
+    /// `/guilds/{guild_id}/roles/{role_id}`
+    guild_role(guild_id: Snowflake, role_id: Snowflake) => "/guilds/{}/roles/{}";
+    /// `/guilds/{guild_id}/roles/{role_id}/member-ids`
+    guild_role_member_ids(guild_id: Snowflake, role_id: Snowflake) => "/guilds/{}/roles/{}/member-ids";
+    /// `/guilds/{guild_id}/widget.png`
+    guild_widget_image(guild_id: Snowflake) => "/guilds/{}/widget.png";
+    /// `/guilds/{guild_id}/scheduled-events/{event_id}/users/@me`
+    guild_scheduled_event_own_user(guild_id: Snowflake, event_id: Snowflake) => "/guilds/{}/scheduled-events/{}/users/@me";
+    /// `/guilds/{guild_id}/voice-states/@me`
+    guild_own_voice_state(guild_id: Snowflake) => "/guilds/{}/voice-states/@me";
+    /// `/guilds/{guild_id}/voice-states/{user_id}`
+    guild_voice_state(guild_id: Snowflake, user_id: Snowflake) => "/guilds/{}/voice-states/{}";
+    /// `/guilds/{guild_id}/webhooks`
+    guild_webhooks(guild_id: Snowflake) => "/guilds/{}/webhooks";
+
+    /// `/interactions/{interaction_id}/{interaction_token}/callback`
+    interaction_callback(interaction_id: Snowflake, interaction_token: &str) => "/interactions/{}/{}/callback";
+
+    /// `/invites/{invite_code}`
+    invite(invite_code: &str) => "/invites/{}";
+
+    /// `/oauth2/authorize`
+    oauth2_authorize() => "/oauth2/authorize";
+
+    /// `/reporting/{report_type}`
+    reporting(report_type: ReportType) => "/reporting/{}";
+    /// `/reporting/menu/{report_type}`
+    reporting_menu(report_type: ReportType) => "/reporting/menu/{}";
+
+    /// `/stage-instances`
+    stage_instances() => "/stage-instances";
+    /// `/stage-instances/{channel_id}`
+    stage_instance(channel_id: Snowflake) => "/stage-instances/{}";
+
+    /// `/users/@me`
+    current_user() => "/users/@me";
+    /// `/users/@me/channels`
+    current_user_channels() => "/users/@me/channels";
+    /// `/users/@me/clan`
+    current_user_clan() => "/users/@me/clan";
+    /// `/users/@me/delete`
+    current_user_delete() => "/users/@me/delete";
+    /// `/users/@me/guilds`
+    current_user_guilds() => "/users/@me/guilds";
+    /// `/users/@me/guilds/settings`
+    current_user_guilds_settings() => "/users/@me/guilds/settings";
+    /// `/users/@me/guilds/{guild_id}`
+    current_user_guild(guild_id: Snowflake) => "/users/@me/guilds/{}";
+    /// `/users/@me/guilds/{guild_id}/member`
+    current_user_guild_member(guild_id: Snowflake) => "/users/@me/guilds/{}/member";
+    /// `/users/@me/guilds/{guild_id}/settings`
+    current_user_guild_settings(guild_id: Snowflake) => "/users/@me/guilds/{}/settings";
+    /// `/users/@me/invites`
+    current_user_invites() => "/users/@me/invites";
+    /// `/users/@me/phone`
+    current_user_phone() => "/users/@me/phone";
+    /// `/users/@me/relationships`
+    current_user_relationships() => "/users/@me/relationships";
+    /// `/users/@me/relationships/{user_id}`
+    current_user_relationship(user_id: Snowflake) => "/users/@me/relationships/{}";
+    /// `/users/@me/settings`
+    current_user_settings() => "/users/@me/settings";
+    /// `/users/search`
+    users_search() => "/users/search";
+    /// `/users/{user_id}`
+    user(user_id: Snowflake) => "/users/{}";
+    /// `/users/{user_id}/relationships`
+    user_relationships(user_id: Snowflake) => "/users/{}/relationships";
+
+    /// `/voice/regions`
+    voice_regions() => "/voice/regions";
+
+    /// `/webhooks/{webhook_id}`
+    webhook(webhook_id: Snowflake) => "/webhooks/{}";
+    /// `/webhooks/{webhook_id}/{webhook_token}`
+    webhook_with_token(webhook_id: Snowflake, webhook_token: &str) => "/webhooks/{}/{}";
+    /// `/webhooks/{application_id}/{interaction_token}`
+    interaction_followups(application_id: Snowflake, interaction_token: &str) => "/webhooks/{}/{}";
+    /// `/webhooks/{application_id}/{interaction_token}/messages/@original`
+    interaction_original_response(application_id: Snowflake, interaction_token: &str) => "/webhooks/{}/{}/messages/@original";
+    /// `/webhooks/{application_id}/{interaction_token}/messages/{message_id}`
+    interaction_followup(application_id: Snowflake, interaction_token: &str, message_id: Snowflake) => "/webhooks/{}/{}/messages/{}";
+}
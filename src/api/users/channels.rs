@@ -5,6 +5,7 @@
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::{
     errors::ChorusResult,
     instance::ChorusUser,
@@ -18,17 +19,13 @@ impl ChorusUser {
     /// # Reference:
     /// See <https://docs.discord.sex/resources/channel#get-private-channels>
     pub async fn get_private_channels(&mut self) -> ChorusResult<Vec<Channel>> {
-        let url = format!(
-            "{}/users/@me/channels",
-            self.belongs_to.read().unwrap().urls.api
-        );
-        ChorusRequest {
-            request: Client::new()
-                .get(url)
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json"),
-            limit_type: LimitType::Global,
-        }
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::current_user_channels(),
+            None,
+            None,
+            self,
+        )
         .deserialize_response::<Vec<Channel>>(self)
         .await
     }
@@ -44,18 +41,13 @@ impl ChorusUser {
         &mut self,
         create_private_channel_schema: PrivateChannelCreateSchema,
     ) -> ChorusResult<Channel> {
-        let url = format!(
-            "{}/users/@me/channels",
-            self.belongs_to.read().unwrap().urls.api
-        );
-        ChorusRequest {
-            request: Client::new()
-                .post(url)
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json")
-                .body(to_string(&create_private_channel_schema).unwrap()),
-            limit_type: LimitType::Global,
-        }
+        ChorusRequest::from_route(
+            http::Method::POST,
+            routes::current_user_channels(),
+            Some(to_string(&create_private_channel_schema).unwrap()),
+            None,
+            self,
+        )
         .deserialize_response::<Channel>(self)
         .await
     }
@@ -2,13 +2,29 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::VecDeque;
+
+use futures_util::{stream, Stream};
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::errors::ChorusResult;
 use crate::instance::ChorusUser;
 use crate::ratelimiter::ChorusRequest;
-use crate::types::{GetUserGuildSchema, Guild, LimitType, Snowflake};
+use crate::types::{
+    GetUserGuildSchema, Guild, GuildMember, LimitType, Snowflake, UserGuild, UserGuildSettings,
+    UserGuildSettingsEntries, UserGuildSettingsModifySchema,
+};
+
+/// The amount of guilds requested per page by [`ChorusUser::guilds`].
+const GUILDS_PAGE_SIZE: u8 = 200;
+
+struct GuildsCursor {
+    after: Option<Snowflake>,
+    buffer: VecDeque<UserGuild>,
+    exhausted: bool,
+}
 
 impl ChorusUser {
     /// Leaves a given guild.
@@ -19,46 +35,141 @@ impl ChorusUser {
     // It is documented as "Whether the user is lurking in the guild",
     // but that says nothing about what this field actually does / means
     pub async fn leave_guild(&mut self, guild_id: &Snowflake, lurking: bool) -> ChorusResult<()> {
-        ChorusRequest {
-            request: Client::new()
-                .delete(format!(
-                    "{}/users/@me/guilds/{}",
-                    self.belongs_to.read().unwrap().urls.api,
-                    guild_id
-                ))
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json")
-                .body(to_string(&lurking).unwrap()),
-            limit_type: LimitType::Guild(*guild_id),
-        }
+        ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::current_user_guild(*guild_id),
+            Some(to_string(&lurking).unwrap()),
+            None,
+            self,
+        )
         .handle_request_as_result(self)
         .await
     }
 
-    /// Returns a list of user guild objects representing the guilds the current user is a member of.
-    /// This endpoint returns 200 guilds by default
+    /// Returns the guilds the current user is a member of, sorted by id.
+    ///
+    /// Returns at most 200 guilds per request, see [`ChorusUser::guilds`] for walking all of them.
     ///
     /// # Reference:
     /// See: <https://discord-userdoccers.vercel.app/resources/guild#get-user-guilds>
     pub async fn get_guilds(
         &mut self,
         query: Option<GetUserGuildSchema>,
-    ) -> ChorusResult<Vec<Guild>> {
-        let url = format!(
-            "{}/users/@me/guilds",
-            self.belongs_to.read().unwrap().urls.api,
+    ) -> ChorusResult<Vec<UserGuild>> {
+        let mut chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::current_user_guilds(),
+            None,
+            None,
+            self,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .get(url)
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json")
-                .body(to_string(&query).unwrap()),
-
-            limit_type: LimitType::Global,
-        };
+        chorus_request.request = chorus_request.request.query(&query.unwrap_or_default());
         chorus_request
-            .deserialize_response::<Vec<Guild>>(self)
+            .deserialize_response::<Vec<UserGuild>>(self)
             .await
     }
+
+    /// Walks all guilds the current user is a member of, sorted by id.
+    ///
+    /// Guilds are fetched lazily in pages of 200. The stream ends after the first error.
+    ///
+    /// # Reference:
+    /// See: <https://discord-userdoccers.vercel.app/resources/guild#get-user-guilds>
+    pub fn guilds(
+        &mut self,
+        with_counts: bool,
+    ) -> impl Stream<Item = ChorusResult<UserGuild>> + '_ {
+        let cursor = GuildsCursor {
+            after: None,
+            buffer: VecDeque::new(),
+            exhausted: false,
+        };
+        stream::try_unfold((self, cursor), move |(user, mut cursor)| async move {
+            loop {
+                if let Some(guild) = cursor.buffer.pop_front() {
+                    return Ok(Some((guild, (user, cursor))));
+                }
+                if cursor.exhausted {
+                    return Ok(None);
+                }
+                let mut page = user
+                    .get_guilds(Some(GetUserGuildSchema {
+                        before: None,
+                        after: cursor.after,
+                        limit: Some(GUILDS_PAGE_SIZE),
+                        with_counts: Some(with_counts),
+                    }))
+                    .await?;
+                page.sort_by_key(|guild| guild.id);
+                cursor.exhausted = page.len() < GUILDS_PAGE_SIZE as usize;
+                if let Some(last) = page.last() {
+                    cursor.after = Some(last.id);
+                }
+                cursor.buffer.extend(page);
+            }
+        })
+    }
+
+    /// Returns the current user's member object in a guild.
+    ///
+    /// The member is stored in [`ChorusUser::guild_members`] for checking permissions locally.
+    ///
+    /// # Reference:
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#get-current-guild-member>
+    pub async fn get_guild_member(&mut self, guild_id: Snowflake) -> ChorusResult<GuildMember> {
+        let member = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::current_user_guild_member(guild_id),
+            None,
+            None,
+            self,
+        )
+        .deserialize_response::<GuildMember>(self)
+        .await?;
+        self.guild_members
+            .write()
+            .unwrap()
+            .insert(guild_id, member.clone());
+        Ok(member)
+    }
+
+    /// Returns the current user's notification settings of all guilds they have changed them
+    /// for.
+    ///
+    /// # Reference:
+    /// See <https://discord-userdoccers.vercel.app/resources/user-settings#get-user-guild-settings>
+    pub async fn get_guild_settings(&mut self) -> ChorusResult<UserGuildSettingsEntries> {
+        ChorusRequest::from_route(
+            http::Method::GET,
+            routes::current_user_guilds_settings(),
+            None,
+            None,
+            self,
+        )
+        .deserialize_response::<UserGuildSettingsEntries>(self)
+        .await
+    }
+
+    /// Modifies the current user's notification settings of a guild, such as muting it or some
+    /// of its channels. Returns the changed settings.
+    ///
+    /// Other sessions of the user are notified with a `USER_GUILD_SETTINGS_UPDATE` event.
+    ///
+    /// # Reference:
+    /// See <https://discord-userdoccers.vercel.app/resources/user-settings#modify-user-guild-settings>
+    pub async fn modify_guild_settings(
+        &mut self,
+        guild_id: Snowflake,
+        schema: UserGuildSettingsModifySchema,
+    ) -> ChorusResult<UserGuildSettings> {
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::current_user_guild_settings(guild_id),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .deserialize_response::<UserGuildSettings>(self)
+        .await
+    }
 }
@@ -5,6 +5,7 @@
 use reqwest::Client;
 use serde_json::to_string;
 
+use crate::api::routes;
 use crate::{
     errors::ChorusResult,
     instance::ChorusUser,
@@ -24,15 +25,13 @@ impl ChorusUser {
         &mut self,
         user_id: Snowflake,
     ) -> ChorusResult<Vec<types::PublicUser>> {
-        let url = format!(
-            "{}/users/{}/relationships",
-            self.belongs_to.read().unwrap().urls.api,
-            user_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::user_relationships(user_id),
+            None,
+            None,
+            self,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new().get(url).header("Authorization", self.token()),
-            limit_type: LimitType::Global,
-        };
         chorus_request
             .deserialize_response::<Vec<types::PublicUser>>(self)
             .await
@@ -43,14 +42,13 @@ impl ChorusUser {
     /// # Reference
     /// See <https://luna.gitlab.io/discord-unofficial-docs/docs/relationships.html#get-usersmerelationships>
     pub async fn get_relationships(&mut self) -> ChorusResult<Vec<types::Relationship>> {
-        let url = format!(
-            "{}/users/@me/relationships",
-            self.belongs_to.read().unwrap().urls.api
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::GET,
+            routes::current_user_relationships(),
+            None,
+            None,
+            self,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new().get(url).header("Authorization", self.token()),
-            limit_type: LimitType::Global,
-        };
         chorus_request
             .deserialize_response::<Vec<types::Relationship>>(self)
             .await
@@ -64,19 +62,14 @@ impl ChorusUser {
         &mut self,
         schema: FriendRequestSendSchema,
     ) -> ChorusResult<()> {
-        let url = format!(
-            "{}/users/@me/relationships",
-            self.belongs_to.read().unwrap().urls.api
-        );
         let body = to_string(&schema).unwrap();
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .post(url)
-                .header("Authorization", self.token())
-                .header("Content-Type", "application/json")
-                .body(body),
-            limit_type: LimitType::Global,
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::current_user_relationships(),
+            Some(body),
+            None,
+            self,
+        );
         chorus_request.handle_request_as_result(self).await
     }
 
@@ -88,16 +81,12 @@ impl ChorusUser {
         user_id: Snowflake,
         relationship_type: RelationshipType,
     ) -> ChorusResult<()> {
-        let api_url = self.belongs_to.read().unwrap().urls.api.clone();
+        let route = routes::current_user_relationship(user_id);
         match relationship_type {
             RelationshipType::None => {
-                let chorus_request = ChorusRequest {
-                    request: Client::new()
-                        .delete(format!("{}/users/@me/relationships/{}", api_url, user_id))
-                        .header("Authorization", self.token()),
-                    limit_type: LimitType::Global,
-                };
-                chorus_request.handle_request_as_result(self).await
+                ChorusRequest::from_route(http::Method::DELETE, route, None, None, self)
+                    .handle_request_as_result(self)
+                    .await
             }
             RelationshipType::Friends | RelationshipType::Incoming | RelationshipType::Outgoing => {
                 let body = CreateUserRelationshipSchema {
@@ -105,14 +94,15 @@ impl ChorusUser {
                     from_friend_suggestion: None,
                     friend_token: None,
                 };
-                let chorus_request = ChorusRequest {
-                    request: Client::new()
-                        .put(format!("{}/users/@me/relationships/{}", api_url, user_id))
-                        .header("Authorization", self.token())
-                        .body(to_string(&body).unwrap()),
-                    limit_type: LimitType::Global,
-                };
-                chorus_request.handle_request_as_result(self).await
+                ChorusRequest::from_route(
+                    http::Method::PUT,
+                    route,
+                    Some(to_string(&body).unwrap()),
+                    None,
+                    self,
+                )
+                .handle_request_as_result(self)
+                .await
             }
             RelationshipType::Blocked => {
                 let body = CreateUserRelationshipSchema {
@@ -120,14 +110,15 @@ impl ChorusUser {
                     from_friend_suggestion: None,
                     friend_token: None,
                 };
-                let chorus_request = ChorusRequest {
-                    request: Client::new()
-                        .put(format!("{}/users/@me/relationships/{}", api_url, user_id))
-                        .header("Authorization", self.token())
-                        .body(to_string(&body).unwrap()),
-                    limit_type: LimitType::Global,
-                };
-                chorus_request.handle_request_as_result(self).await
+                ChorusRequest::from_route(
+                    http::Method::PUT,
+                    route,
+                    Some(to_string(&body).unwrap()),
+                    None,
+                    self,
+                )
+                .handle_request_as_result(self)
+                .await
             }
             RelationshipType::Suggestion | RelationshipType::Implicit => Ok(()),
         }
@@ -138,17 +129,13 @@ impl ChorusUser {
     /// # Reference
     /// See <https://luna.gitlab.io/discord-unofficial-docs/docs/relationships.html#delete-usersmerelationshipspeer_id>
     pub async fn remove_relationship(&mut self, user_id: Snowflake) -> ChorusResult<()> {
-        let url = format!(
-            "{}/users/@me/relationships/{}",
-            self.belongs_to.read().unwrap().urls.api,
-            user_id
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::DELETE,
+            routes::current_user_relationship(user_id),
+            None,
+            None,
+            self,
         );
-        let chorus_request = ChorusRequest {
-            request: Client::new()
-                .delete(url)
-                .header("Authorization", self.token()),
-            limit_type: LimitType::Global,
-        };
         chorus_request.handle_request_as_result(self).await
     }
 }
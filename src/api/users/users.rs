@@ -5,13 +5,17 @@
 use std::sync::{Arc, RwLock};
 
 use reqwest::Client;
-use serde_json::to_string;
+use serde_json::{json, to_string};
 
+use crate::api::routes::{self, Route};
 use crate::{
     errors::{ChorusError, ChorusResult},
     instance::{ChorusUser, Instance},
     ratelimiter::ChorusRequest,
-    types::{LimitType, User, UserModifySchema, UserSettings},
+    types::{
+        GuildFolders, LimitType, PrimaryGuildModifySchema, PublicUser, User, UserModifySchema,
+        UserSearchQuery, UserSettings,
+    },
 };
 
 impl ChorusUser {
@@ -33,7 +37,7 @@ impl ChorusUser {
     /// This functions is a wrapper around [`User::get_settings`].
     pub async fn get_settings(
         token: &String,
-        url_api: &String,
+        url_api: &str,
         instance: &mut Instance,
     ) -> ChorusResult<UserSettings> {
         User::get_settings(token, url_api, instance).await
@@ -50,19 +54,93 @@ impl ChorusUser {
         {
             return Err(ChorusError::PasswordRequired);
         }
-        let request = Client::new()
-            .patch(format!(
-                "{}/users/@me",
-                self.belongs_to.read().unwrap().urls.api
-            ))
-            .body(to_string(&modify_schema).unwrap())
-            .header("Authorization", self.token())
-            .header("Content-Type", "application/json");
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::default(),
-        };
-        chorus_request.deserialize_response::<User>(self).await
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::current_user(),
+            Some(to_string(&modify_schema).unwrap()),
+            None,
+            self,
+        )
+        .deserialize_response::<User>(self)
+        .await
+    }
+
+    /// Returns the user's guild list, as of the last time the user's settings were fetched or
+    /// modified.
+    pub fn guild_folders(&self) -> GuildFolders {
+        GuildFolders::new(self.settings.read().unwrap().guild_folders.to_vec())
+    }
+
+    /// Replaces the user's guild folders, along with the guild positions derived from them.
+    ///
+    /// The user's cached [`settings`](ChorusUser::settings) are updated on success.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/user-settings#modify-user-settings>
+    pub async fn modify_guild_folders(&mut self, folders: GuildFolders) -> ChorusResult<()> {
+        let guild_positions = folders.guild_positions();
+        let body = json!({
+            "guild_folders": folders.entries(),
+            "guild_positions": guild_positions,
+        });
+        ChorusRequest::from_route(
+            http::Method::PATCH,
+            routes::current_user_settings(),
+            Some(body.to_string()),
+            None,
+            self,
+        )
+        .handle_request_as_result(self)
+        .await?;
+        let mut settings = self.settings.write().unwrap();
+        settings.guild_folders.clear();
+        settings.guild_folders.extend(folders.into_entries());
+        settings.guild_positions.clear();
+        settings.guild_positions.extend(guild_positions);
+        Ok(())
+    }
+
+    /// Sets the guild whose tag the current user displays next to their name.
+    ///
+    /// The updated user is dispatched through a `USER_UPDATE` event. Instances which do not
+    /// support guild tags respond with a [`ChorusError::NotFound`].
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/user#modify-user-primary-guild>
+    pub async fn modify_primary_guild(
+        &mut self,
+        schema: PrimaryGuildModifySchema,
+    ) -> ChorusResult<()> {
+        ChorusRequest::from_route(
+            http::Method::PUT,
+            routes::current_user_clan(),
+            Some(to_string(&schema).unwrap()),
+            None,
+            self,
+        )
+        .handle_request_as_result(self)
+        .await
+    }
+
+    /// Searches all users of the instance by their username, for example for administration or
+    /// moderation tools. Returns partial users.
+    ///
+    /// # Notes
+    /// This is a Spacebar only endpoint. On other instances, a
+    /// [`ChorusError::UnsupportedByInstance`] is returned without sending a request, see
+    /// [`Instance::is_spacebar`].
+    pub async fn search_users(&mut self, query: UserSearchQuery) -> ChorusResult<Vec<PublicUser>> {
+        if !self.belongs_to.read().unwrap().is_spacebar() {
+            return Err(ChorusError::UnsupportedByInstance {
+                feature: "searching users".to_string(),
+            });
+        }
+        let mut chorus_request =
+            ChorusRequest::from_route(http::Method::GET, routes::users_search(), None, None, self);
+        chorus_request.request = chorus_request.request.query(&query);
+        chorus_request
+            .deserialize_response::<Vec<PublicUser>>(self)
+            .await
     }
 
     /// Deletes the user from the Instance.
@@ -70,17 +148,13 @@ impl ChorusUser {
     /// # Reference
     /// See <https://discord-userdoccers.vercel.app/resources/user#disable-user>
     pub async fn delete(mut self) -> ChorusResult<()> {
-        let request = Client::new()
-            .post(format!(
-                "{}/users/@me/delete",
-                self.belongs_to.read().unwrap().urls.api
-            ))
-            .header("Authorization", self.token())
-            .header("Content-Type", "application/json");
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::default(),
-        };
+        let chorus_request = ChorusRequest::from_route(
+            http::Method::POST,
+            routes::current_user_delete(),
+            None,
+            None,
+            &mut self,
+        );
         chorus_request.handle_request_as_result(&mut self).await
     }
 }
@@ -92,19 +166,11 @@ impl User {
     /// See <https://discord-userdoccers.vercel.app/resources/user#get-user> and
     /// <https://discord-userdoccers.vercel.app/resources/user#get-current-user>
     pub async fn get(user: &mut ChorusUser, id: Option<&String>) -> ChorusResult<User> {
-        let url_api = user.belongs_to.read().unwrap().urls.api.clone();
-        let url = if id.is_none() {
-            format!("{}/users/@me", url_api)
-        } else {
-            format!("{}/users/{}", url_api, id.unwrap())
-        };
-        let request = reqwest::Client::new()
-            .get(url)
-            .header("Authorization", user.token());
-        let chorus_request = ChorusRequest {
-            request,
-            limit_type: LimitType::Global,
+        let route = match id {
+            None => routes::current_user(),
+            Some(id) => Route::new(format!("/users/{}", id)),
         };
+        let chorus_request = ChorusRequest::from_route(http::Method::GET, route, None, None, user);
         match chorus_request.send_request(user).await {
             Ok(result) => {
                 let result_text = result.text().await.unwrap();
@@ -120,17 +186,18 @@ impl User {
     /// See <https://luna.gitlab.io/discord-unofficial-docs/docs/user_settings.html#get-usersmesettings>
     pub async fn get_settings(
         token: &String,
-        url_api: &String,
+        url_api: &str,
         instance: &mut Instance,
     ) -> ChorusResult<UserSettings> {
+        let route = routes::current_user_settings();
         let request: reqwest::RequestBuilder = Client::new()
-            .get(format!("{}/users/@me/settings", url_api))
+            .get(route.url(url_api))
             .header("Authorization", token);
         let mut user =
             ChorusUser::shell(Arc::new(RwLock::new(instance.clone())), token.clone()).await;
         let chorus_request = ChorusRequest {
             request,
-            limit_type: LimitType::Global,
+            limit_type: route.limit_type(),
         };
         let result = match chorus_request.send_request(&mut user).await {
             Ok(result) => Ok(serde_json::from_str(&result.text().await.unwrap()).unwrap()),
@@ -3,9 +3,12 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 //! Contains all the errors that can be returned by the library.
+use std::fmt;
+
 use custom_error::custom_error;
+use serde::Deserialize;
 
-use crate::types::WebSocketEvent;
+use crate::types::{PermissionFlags, Snowflake, WebSocketEvent};
 
 custom_error! {
     #[derive(PartialEq, Eq, Clone, Hash)]
@@ -23,11 +26,11 @@ custom_error! {
     /// Reqwest returned an Error instead of a Response object.
     RequestFailed{url:String, error: String} = "An error occurred while trying to GET from {url}: {error}",
     /// Response received, however, it was not of the successful responses type. Used when no other, special case applies.
-    ReceivedErrorCode{error_code: u16, error: String} = "Received the following error code while requesting from the route: {error_code}",
+    ReceivedErrorCode{route: String, source: ApiError} = "Received an error response while requesting {route}: {source}",
     /// Used when there is likely something wrong with the instance, the request was directed to.
     CantGetInformation{error:String} = "Something seems to be wrong with the instance. Cannot get information about the instance: {error}",
-    /// The requests form body was malformed/invalid.
-    InvalidFormBody{error_type: String, error:String} = "The server responded with: {error_type}: {error}",
+    /// The server rejected the request as malformed or invalid, for example because of an invalid form body.
+    InvalidRequest{route: String, source: ApiError} = "The server rejected the request to {route}: {source}",
     /// The request has not been processed by the server due to a relevant rate limit bucket being exhausted.
     RateLimited{bucket:String} = "Ratelimited on Bucket {bucket}",
     /// The multipart form could not be created.
@@ -36,16 +39,240 @@ custom_error! {
     FormCreation{error: String} = "Got an error whilst creating the form: {error}",
     /// The token is invalid.
     TokenExpired = "Token expired, invalid or not found.",
-    /// No permission
-    NoPermission = "You do not have the permissions needed to perform this action.",
-    /// Resource not found
-    NotFound{error: String} = "The provided resource hasn't been found: {error}",
+    /// The server responded, that you do not have the permissions needed to perform this action.
+    NoPermission{route: String, source: ApiError} = "You do not have the permissions needed to request {route}: {source}",
+    /// The server responded, that the requested resource does not exist.
+    NotFound{route: String, source: ApiError} = "The resource at {route} hasn't been found: {source}",
     /// Used when you, for example, try to change your spacebar account password without providing your old password for verification.
     PasswordRequired = "You need to provide your current password to authenticate for this action.",
     /// Malformed or unexpected response.
     InvalidResponse{error: String} = "The response is malformed and cannot be processed. Error: {error}",
     /// Invalid, insufficient or too many arguments provided.
-    InvalidArguments{error: String} = "Invalid arguments were provided. Error: {error}"
+    InvalidArguments{error: String} = "Invalid arguments were provided. Error: {error}",
+    /// An attachment is larger than the upload limit, and was therefore not sent.
+    AttachmentTooLarge{filename: String, size: u64, limit: u64} = "The attachment {filename} ({size} bytes) exceeds the upload limit of {limit} bytes.",
+    /// An embed refers to an attachment (`attachment://filename`) which is not part of the message.
+    UnknownAttachment{filename: String} = "An embed refers to the attachment {filename}, which is not part of the message.",
+    /// Reading from or writing to a local resource, such as a file, failed.
+    Io{error: String} = "An I/O error occurred: {error}",
+    /// The server did not respond within the [request timeout](crate::instance::Instance::request_timeout).
+    RequestTimedOut{url: String} = "The request to {url} timed out.",
+    /// The account has to be verified before logging in.
+    VerificationRequired{method: VerificationMethod} = "The account needs to be verified by {method} before logging in.",
+    /// The instance does not allow new registrations.
+    RegistrationDisabled = "The instance has disabled registration.",
+    /// The instance only allows registering with an invite.
+    InviteRequired = "The instance requires an invite to register.",
+    /// The date of birth given when registering is below the instance's minimum age.
+    Underage = "The date of birth is below the instance's minimum age.",
+    /// The account is scheduled for deletion and can no longer be logged into.
+    AccountScheduledForDeletion = "The account is scheduled for deletion.",
+    /// The instance does not support a feature, such as a Spacebar-only endpoint. The request was
+    /// not sent.
+    UnsupportedByInstance{feature: String} = "The instance does not support {feature}.",
+    /// The user is known to lack permissions needed for the request, which was therefore not sent.
+    /// See [`ChorusUser::set_check_permissions`](crate::instance::ChorusUser::set_check_permissions).
+    MissingPermissions{permissions: PermissionFlags} = "Missing the permissions {permissions:?} needed for this action.",
+    /// A message was sent in an announcement channel, but could not be published because the
+    /// channel's publish rate limit (10 messages per hour) is exhausted.
+    PublishRateLimited{message_id: Snowflake, retry_after_secs: u64} = "The message {message_id} was sent, but publishing it is rate limited for {retry_after_secs} seconds.",
+    /// A message was sent in an announcement channel, but publishing it failed for another reason.
+    PublishFailed{message_id: Snowflake, error: Box<ChorusError>} = "The message {message_id} was sent, but publishing it failed: {error}"
+}
+
+impl ChorusError {
+    /// Returns true if the same request might succeed when retried later, for example after a
+    /// rate limit has been replenished or a server error has been resolved.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ChorusError::NoResponse
+            | ChorusError::RequestFailed { .. }
+            | ChorusError::RequestTimedOut { .. }
+            | ChorusError::RateLimited { .. } => true,
+            // 202 Accepted is sent while a resource, such as the search index, is still being built
+            ChorusError::ReceivedErrorCode { source, .. } => {
+                source.status >= 500 || matches!(source.status, 202 | 408)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if this error was caused by missing permissions or missing / invalid
+    /// authentication.
+    pub fn is_permission_error(&self) -> bool {
+        match self {
+            ChorusError::NoPermission { .. }
+            | ChorusError::MissingPermissions { .. }
+            | ChorusError::TokenExpired
+            | ChorusError::PasswordRequired => true,
+            _ => self
+                .api_error()
+                .map_or(false, ApiError::is_permission_error),
+        }
+    }
+
+    /// Returns the error response of the server, if this error was caused by one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            ChorusError::ReceivedErrorCode { source, .. }
+            | ChorusError::InvalidRequest { source, .. }
+            | ChorusError::NoPermission { source, .. }
+            | ChorusError::NotFound { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+
+    /// Returns the route which was requested, if this error was caused by an error response of
+    /// the server.
+    pub fn route(&self) -> Option<&str> {
+        match self {
+            ChorusError::ReceivedErrorCode { route, .. }
+            | ChorusError::InvalidRequest { route, .. }
+            | ChorusError::NoPermission { route, .. }
+            | ChorusError::NotFound { route, .. } => Some(route),
+            _ => None,
+        }
+    }
+}
+
+/// How an account has to be verified, before it can be logged into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VerificationMethod {
+    /// By following the link sent to the account's email address. See
+    /// [`Instance::verify_email`](crate::instance::Instance::verify_email).
+    Email,
+    /// By entering a code sent to the account's phone number.
+    Phone,
+}
+
+impl VerificationMethod {
+    /// The field error code the server uses to report, that this verification is required.
+    pub fn field_error_code(&self) -> &'static str {
+        match self {
+            VerificationMethod::Email => "ACCOUNT_LOGIN_VERIFICATION_EMAIL",
+            VerificationMethod::Phone => "ACCOUNT_LOGIN_VERIFICATION_PHONE",
+        }
+    }
+
+    /// Returns the verification required by an error response, if it reports one.
+    pub fn from_api_error(error: &ApiError) -> Option<VerificationMethod> {
+        [VerificationMethod::Email, VerificationMethod::Phone]
+            .into_iter()
+            .find(|method| error.has_field_error(method.field_error_code()))
+    }
+}
+
+impl fmt::Display for VerificationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationMethod::Email => f.write_str("email"),
+            VerificationMethod::Phone => f.write_str("phone"),
+        }
+    }
+}
+
+/// An error response of the server.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/topics/opcodes-and-status-codes#json-error-codes>
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ApiError {
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The JSON error code, if the server sent one.
+    pub code: Option<u64>,
+    /// The error message of the server, or the raw response body, if it did not contain one.
+    pub message: String,
+    /// The codes of the errors of individual fields, such as `ACCOUNT_LOGIN_VERIFICATION_EMAIL`
+    /// for the `login` field of a login request.
+    pub field_error_codes: Vec<String>,
+}
+
+impl ApiError {
+    /// Unknown access; the user has no access to the resource.
+    pub const MISSING_ACCESS: u64 = 50001;
+    /// The user lacks the permissions needed to perform the action.
+    pub const MISSING_PERMISSIONS: u64 = 50013;
+    /// The account has been scheduled for deletion.
+    pub const ACCOUNT_SCHEDULED_FOR_DELETION: u64 = 20011;
+    /// The user is under the minimum age.
+    pub const UNDER_MINIMUM_AGE: u64 = 20024;
+
+    /// Creates an [`ApiError`] from the status code and body of an error response.
+    pub fn from_response_body(status: u16, body: &str) -> ApiError {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            code: Option<u64>,
+            message: Option<String>,
+            errors: Option<serde_json::Value>,
+        }
+
+        match serde_json::from_str::<ErrorBody>(body) {
+            Ok(error) => {
+                let mut field_error_codes = Vec::new();
+                if let Some(errors) = &error.errors {
+                    collect_field_error_codes(errors, &mut field_error_codes);
+                }
+                ApiError {
+                    status,
+                    code: error.code,
+                    message: error.message.unwrap_or_else(|| body.to_string()),
+                    field_error_codes,
+                }
+            }
+            Err(_) => ApiError {
+                status,
+                code: None,
+                message: body.to_string(),
+                field_error_codes: Vec::new(),
+            },
+        }
+    }
+
+    /// Returns true if one of the field errors has the given code.
+    pub fn has_field_error(&self, code: &str) -> bool {
+        self.field_error_codes.iter().any(|c| c == code)
+    }
+
+    /// Returns true if the server reported missing permissions or access.
+    pub fn is_permission_error(&self) -> bool {
+        matches!(self.status, 401 | 403)
+            || matches!(
+                self.code,
+                Some(ApiError::MISSING_ACCESS | ApiError::MISSING_PERMISSIONS)
+            )
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "HTTP {} (code {}): {}", self.status, code, self.message),
+            None => write!(f, "HTTP {}: {}", self.status, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Collects the codes of the `_errors` arrays in the nested `errors` object of an error response.
+fn collect_field_error_codes(errors: &serde_json::Value, codes: &mut Vec<String>) {
+    let Some(object) = errors.as_object() else {
+        return;
+    };
+    for (key, value) in object {
+        if key == "_errors" {
+            let field_errors = value.as_array().map(Vec::as_slice).unwrap_or_default();
+            codes.extend(
+                field_errors
+                    .iter()
+                    .filter_map(|error| error.get("code")?.as_str())
+                    .map(str::to_string),
+            );
+        } else {
+            collect_field_error_codes(value, codes);
+        }
+    }
 }
 
 impl From<reqwest::Error> for ChorusError {
@@ -60,6 +287,16 @@ impl From<reqwest::Error> for ChorusError {
     }
 }
 
+custom_error! {
+    /// Errors of a [`TokenStore`](crate::token::TokenStore).
+    #[derive(PartialEq, Eq, Clone)]
+    pub TokenStoreError
+    Io{error: String} = "Couldn't access the token store: {error}",
+    InvalidFormat{error: String} = "The token store is corrupted: {error}",
+    /// The passphrase is wrong, or the stored token has been tampered with.
+    Decryption = "Couldn't decrypt the stored token",
+}
+
 custom_error! {
     #[derive(PartialEq, Eq)]
     pub ObserverError
@@ -94,13 +331,86 @@ custom_error! {
     // Errors when initiating a gateway connection
     CannotConnect{error: String} = "Cannot connect due to a tungstenite error: {error}",
     NonHelloOnInitiate{opcode: u8} = "Received non hello on initial gateway connection ({opcode}), something is definitely wrong",
+    ConnectionTimedOut = "Timed out while connecting to the gateway",
 
     // Other misc errors
     UnexpectedOpcodeReceived{opcode: u8} = "Received an opcode we weren't expecting to receive: {opcode}",
+    /// Sending or receiving a message over the websocket failed.
+    WebSocketError{error: String} = "The websocket connection failed: {error}",
+    /// More messages were received than could be buffered, see
+    /// [`OverflowPolicy::Error`](crate::gateway::OverflowPolicy::Error).
+    EventBufferOverflow{capacity: usize} = "More than {capacity} received gateway messages were waiting to be handled",
+    /// A payload could not be converted from or to the External Term Format, see
+    /// [`GatewayEncoding::Etf`](crate::gateway::GatewayEncoding).
+    Etf{error: String} = "Couldn't convert a payload from or to ETF: {error}",
+    /// A received payload or its event data could not be deserialized.
+    InvalidPayload{error: String} = "Couldn't parse a received payload: {error}",
+    /// A heartbeat was not acknowledged before the next one was due, so the connection is
+    /// considered a zombie.
+    HeartbeatAckMissed = "The last heartbeat was not acknowledged, the connection seems to be broken",
 }
 
 impl WebSocketEvent for GatewayError {}
 
+custom_error! {
+    /// Errors when parsing a dispatched gateway event, see
+    /// [`parse_dispatch`](crate::gateway::parse_dispatch).
+    #[derive(PartialEq, Eq, Clone)]
+    pub DispatchError
+    UnknownEvent{event_name: String} = "Unknown gateway event {event_name}",
+    InvalidPayload{event_name: String, error: String} = "Couldn't parse gateway event {event_name}: {error}",
+}
+
+impl GatewayError {
+    /// Returns the close code the gateway sends along with this error, if it is one of the errors
+    /// received from the gateway.
+    ///
+    /// See <https://discord.com/developers/docs/topics/opcodes-and-status-codes#gateway-gateway-close-event-codes>
+    pub fn close_code(&self) -> Option<u16> {
+        match self {
+            GatewayError::Unknown => Some(4000),
+            GatewayError::UnknownOpcode => Some(4001),
+            GatewayError::Decode => Some(4002),
+            GatewayError::NotAuthenticated => Some(4003),
+            GatewayError::AuthenticationFailed => Some(4004),
+            GatewayError::AlreadyAuthenticated => Some(4005),
+            GatewayError::InvalidSequenceNumber => Some(4007),
+            GatewayError::RateLimited => Some(4008),
+            GatewayError::SessionTimedOut => Some(4009),
+            GatewayError::InvalidShard => Some(4010),
+            GatewayError::ShardingRequired => Some(4011),
+            GatewayError::InvalidAPIVersion => Some(4012),
+            GatewayError::InvalidIntents => Some(4013),
+            GatewayError::DisallowedIntents => Some(4014),
+            _ => None,
+        }
+    }
+
+    /// Returns false if connecting again would fail with the same error, for example because the
+    /// token is invalid.
+    pub fn is_reconnectable(&self) -> bool {
+        !matches!(
+            self,
+            GatewayError::AuthenticationFailed
+                | GatewayError::InvalidShard
+                | GatewayError::ShardingRequired
+                | GatewayError::InvalidAPIVersion
+                | GatewayError::InvalidIntents
+                | GatewayError::DisallowedIntents
+        )
+    }
+
+    /// Returns true if the session can be resumed after the connection was closed with this
+    /// error, instead of identifying again.
+    pub fn is_resumable(&self) -> bool {
+        self.is_reconnectable()
+            && !matches!(
+                self,
+                GatewayError::InvalidSequenceNumber | GatewayError::SessionTimedOut
+            )
+    }
+}
+
 custom_error! {
     /// Voice Gateway errors
     ///
@@ -127,9 +437,12 @@ custom_error! {
     // Errors when initiating a gateway connection
     CannotConnect{error: String} = "Cannot connect due to a tungstenite error: {error}",
     NonHelloOnInitiate{opcode: u8} = "Received non hello on initial gateway connection ({opcode}), something is definitely wrong",
+    ConnectionTimedOut = "Timed out while connecting to the voice gateway",
 
     // Other misc errors
     UnexpectedOpcodeReceived{opcode: u8} = "Received an opcode we weren't expecting to receive: {opcode}",
+    /// Sending or receiving a message over the websocket failed.
+    WebSocketError{error: String} = "The websocket connection failed: {error}",
 }
 
 impl WebSocketEvent for VoiceGatewayError {}
@@ -153,6 +466,7 @@ custom_error! {
     // Errors when initiating a socket connection
     CannotBind{error: String} = "Cannot bind socket due to a UDP error: {error}",
     CannotConnect{error: String} = "Cannot connect due to a UDP error: {error}",
+    IpDiscoveryTimedOut = "Timed out while waiting for the ip discovery response",
 }
 
 impl WebSocketEvent for VoiceUdpError {}
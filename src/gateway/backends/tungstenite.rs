@@ -2,19 +2,26 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use async_trait::async_trait;
 use futures_util::{
+    future,
     stream::{SplitSink, SplitStream},
-    StreamExt,
+    SinkExt, StreamExt,
 };
+use http::HeaderMap;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{
-    connect_async_tls_with_config, tungstenite, Connector, MaybeTlsStream, WebSocketStream,
+    connect_async_tls_with_config,
+    tungstenite::{self, client::IntoClientRequest},
+    Connector, MaybeTlsStream, WebSocketStream,
 };
 
 use crate::errors::GatewayError;
-use crate::gateway::GatewayMessage;
+use crate::gateway::{GatewayEncoding, GatewayMessage, Sink, Stream, WebSocketTransport};
 
-#[derive(Debug, Clone)]
+/// The default [`WebSocketTransport`], using tokio-tungstenite with the platform's native
+/// certificates.
+#[derive(Debug, Clone, Default)]
 pub struct TungsteniteBackend;
 
 // These could be made into inherent associated types when that's stabilized
@@ -23,9 +30,21 @@ pub type TungsteniteSink =
 pub type TungsteniteStream = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
 
 impl TungsteniteBackend {
-    pub async fn connect(
+    /// Connects to the given url, and returns both halves of the tungstenite websocket.
+    pub async fn connect_raw(
         websocket_url: &str,
-    ) -> Result<(TungsteniteSink, TungsteniteStream), crate::errors::GatewayError> {
+        headers: &HeaderMap,
+    ) -> Result<(TungsteniteSink, TungsteniteStream), GatewayError> {
+        let mut request =
+            websocket_url
+                .into_client_request()
+                .map_err(|e| GatewayError::CannotConnect {
+                    error: e.to_string(),
+                })?;
+        for (name, value) in headers {
+            request.headers_mut().append(name, value.clone());
+        }
+
         let mut roots = rustls::RootCertStore::empty();
         let certs = rustls_native_certs::load_native_certs();
 
@@ -40,7 +59,7 @@ impl TungsteniteBackend {
             roots.add(&rustls::Certificate(cert.0)).unwrap();
         }
         let (websocket_stream, _) = match connect_async_tls_with_config(
-            websocket_url,
+            request,
             None,
             false,
             Some(Connector::Rustls(
@@ -65,14 +84,68 @@ impl TungsteniteBackend {
     }
 }
 
+#[async_trait]
+impl WebSocketTransport for TungsteniteBackend {
+    async fn connect(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+    ) -> Result<(Sink, Stream), GatewayError> {
+        let encoding = GatewayEncoding::from_url(url);
+        let (sink, stream) = TungsteniteBackend::connect_raw(url, headers).await?;
+        let sink = sink
+            .sink_map_err(websocket_error)
+            .with(move |message: GatewayMessage| future::ready(encode(message, encoding)));
+        let stream =
+            stream.map(|message| message.map(GatewayMessage::from).map_err(websocket_error));
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
+
+/// Converts a message to a frame of the negotiated encoding.
+fn encode(
+    message: GatewayMessage,
+    encoding: GatewayEncoding,
+) -> Result<tungstenite::Message, GatewayError> {
+    match encoding {
+        GatewayEncoding::Json => Ok(message.into()),
+        #[cfg(feature = "etf")]
+        GatewayEncoding::Etf => match message {
+            GatewayMessage::Text(json) => Ok(tungstenite::Message::Binary(
+                crate::gateway::ErlpackCodec::encode(&json)?,
+            )),
+            GatewayMessage::Etf(term) => Ok(tungstenite::Message::Binary(term)),
+        },
+    }
+}
+
+fn websocket_error(error: tungstenite::Error) -> GatewayError {
+    GatewayError::WebSocketError {
+        error: error.to_string(),
+    }
+}
+
 impl From<GatewayMessage> for tungstenite::Message {
     fn from(message: GatewayMessage) -> Self {
-        Self::Text(message.0)
+        match message {
+            GatewayMessage::Text(text) => Self::Text(text),
+            #[cfg(feature = "etf")]
+            GatewayMessage::Etf(term) => Self::Binary(term),
+        }
     }
 }
 
 impl From<tungstenite::Message> for GatewayMessage {
+    /// Takes over the payload of text and binary frames without copying it. Close frames are
+    /// converted with [`GatewayMessage::from_close`], and other frames are empty.
     fn from(value: tungstenite::Message) -> Self {
-        Self(value.to_string())
+        match value {
+            tungstenite::Message::Text(text) => Self::Text(text),
+            tungstenite::Message::Binary(bytes) => Self::from_bytes(bytes),
+            tungstenite::Message::Close(Some(frame)) => {
+                Self::from_close(frame.code.into(), frame.reason.into_owned())
+            }
+            _ => Self::Text(String::new()),
+        }
     }
 }
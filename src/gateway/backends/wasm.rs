@@ -2,17 +2,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use async_trait::async_trait;
 use futures_util::{
+    future,
     stream::{SplitSink, SplitStream},
-    StreamExt,
+    SinkExt, StreamExt,
 };
 
+use http::HeaderMap;
 use ws_stream_wasm::*;
 
 use crate::errors::GatewayError;
-use crate::gateway::GatewayMessage;
+use crate::gateway::{GatewayEncoding, GatewayMessage, Sink, Stream, WebSocketTransport};
 
-#[derive(Debug, Clone)]
+/// The default [`WebSocketTransport`] on wasm, using the browser's websockets.
+#[derive(Debug, Clone, Default)]
 pub struct WasmBackend;
 
 // These could be made into inherent associated types when that's stabilized
@@ -20,7 +24,8 @@ pub type WasmSink = SplitSink<WsStream, WsMessage>;
 pub type WasmStream = SplitStream<WsStream>;
 
 impl WasmBackend {
-    pub async fn connect(
+    /// Connects to the given url, and returns both halves of the websocket.
+    pub async fn connect_raw(
         websocket_url: &str,
     ) -> Result<(WasmSink, WasmStream), crate::errors::GatewayError> {
         let (_, websocket_stream) = match WsMeta::connect(websocket_url, None).await {
@@ -34,21 +39,61 @@ impl WasmBackend {
     }
 }
 
+#[async_trait(?Send)]
+impl WebSocketTransport for WasmBackend {
+    /// Browsers do not allow setting headers of the handshake request, so the headers are not
+    /// sent.
+    async fn connect(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+    ) -> Result<(Sink, Stream), GatewayError> {
+        if !headers.is_empty() {
+            log::warn!(
+                "Headers cannot be sent with websocket handshakes in the browser, ignoring them"
+            );
+        }
+        let encoding = GatewayEncoding::from_url(url);
+        let (sink, stream) = WasmBackend::connect_raw(url).await?;
+        let sink = sink
+            .sink_map_err(|error| GatewayError::WebSocketError {
+                error: error.to_string(),
+            })
+            .with(move |message: GatewayMessage| future::ready(encode(message, encoding)));
+        let stream = stream.map(|message| Ok(GatewayMessage::from(message)));
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
+
+/// Converts a message to a frame of the negotiated encoding.
+fn encode(message: GatewayMessage, encoding: GatewayEncoding) -> Result<WsMessage, GatewayError> {
+    match encoding {
+        GatewayEncoding::Json => Ok(message.into()),
+        #[cfg(feature = "etf")]
+        GatewayEncoding::Etf => match message {
+            GatewayMessage::Text(json) => Ok(WsMessage::Binary(
+                crate::gateway::ErlpackCodec::encode(&json)?,
+            )),
+            GatewayMessage::Etf(term) => Ok(WsMessage::Binary(term)),
+        },
+    }
+}
+
 impl From<GatewayMessage> for WsMessage {
     fn from(message: GatewayMessage) -> Self {
-        Self::Text(message.0)
+        match message {
+            GatewayMessage::Text(text) => Self::Text(text),
+            #[cfg(feature = "etf")]
+            GatewayMessage::Etf(term) => Self::Binary(term),
+        }
     }
 }
 
 impl From<WsMessage> for GatewayMessage {
     fn from(value: WsMessage) -> Self {
         match value {
-            WsMessage::Text(text) => Self(text),
-            WsMessage::Binary(bin) => {
-                let mut text = String::new();
-                let _ = bin.iter().map(|v| text.push_str(&v.to_string()));
-                Self(text)
-            }
+            WsMessage::Text(text) => Self::Text(text),
+            WsMessage::Binary(bytes) => Self::from_bytes(bytes),
         }
     }
 }
@@ -0,0 +1,187 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use super::*;
+
+/// The default amount of received messages which can wait to be handled, see
+/// [`EventBufferConfig`].
+pub const DEFAULT_EVENT_BUFFER_CAPACITY: usize = 1024;
+
+/// What to do with a received message when the [event buffer](EventBufferConfig) is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OverflowPolicy {
+    /// Stops reading from the websocket until a message has been handled.
+    ///
+    /// No events are lost, but the gateway may close connections which fall too far behind.
+    #[default]
+    Block,
+    /// Drops the oldest buffered dispatch to make room, or the oldest message if no dispatches
+    /// are buffered, counting it in [`GatewayHandle::dropped_events`].
+    DropOldest,
+    /// Closes the connection with [`GatewayError::EventBufferOverflow`], which is also sent to
+    /// the [error event](super::events::Events::error).
+    Error,
+}
+
+/// Configures the buffer between receiving messages from the gateway and handling them, in which
+/// messages wait while observers are still busy with earlier events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventBufferConfig {
+    /// How many received messages can wait to be handled, at least 1
+    pub capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for EventBufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_EVENT_BUFFER_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BufferState {
+    messages: VecDeque<Result<GatewayMessage, GatewayError>>,
+    /// Set once nothing is pushed anymore
+    closed: bool,
+}
+
+/// The queue between the task receiving messages from the websocket and the task handling them.
+#[derive(Debug)]
+pub(super) struct EventBuffer {
+    config: EventBufferConfig,
+    state: std::sync::Mutex<BufferState>,
+    pushed: Notify,
+    popped: Notify,
+    dropped: AtomicU64,
+}
+
+impl EventBuffer {
+    pub(super) fn new(config: EventBufferConfig) -> Self {
+        Self {
+            config: EventBufferConfig {
+                capacity: config.capacity.max(1),
+                ..config
+            },
+            state: std::sync::Mutex::new(BufferState::default()),
+            pushed: Notify::new(),
+            popped: Notify::new(),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Buffers a received message, applying the [`OverflowPolicy`] if the buffer is full.
+    ///
+    /// Returns false if the buffer has been closed because it overflowed.
+    pub(super) async fn push(&self, message: GatewayMessage) -> bool {
+        loop {
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.closed {
+                    return false;
+                }
+                if state.messages.len() < self.config.capacity {
+                    state.messages.push_back(Ok(message));
+                    drop(state);
+                    self.pushed.notify_one();
+                    return true;
+                }
+                match self.config.overflow_policy {
+                    OverflowPolicy::Block => {}
+                    OverflowPolicy::DropOldest => {
+                        let oldest_dispatch = state.messages.iter().position(|message| {
+                            matches!(message, Ok(message) if message.op_code() == Some(GATEWAY_DISPATCH))
+                        });
+                        state.messages.remove(oldest_dispatch.unwrap_or(0));
+                        state.messages.push_back(Ok(message));
+                        drop(state);
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.pushed.notify_one();
+                        return true;
+                    }
+                    OverflowPolicy::Error => {
+                        state
+                            .messages
+                            .push_back(Err(GatewayError::EventBufferOverflow {
+                                capacity: self.config.capacity,
+                            }));
+                        state.closed = true;
+                        drop(state);
+                        self.pushed.notify_one();
+                        return false;
+                    }
+                }
+            }
+            self.popped.notified().await;
+        }
+    }
+
+    /// Marks the end of the received messages, after the websocket broke.
+    pub(super) fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.pushed.notify_one();
+    }
+
+    /// Marks the end of the received messages with an error, after which the connection is
+    /// considered broken, unless the buffer has been closed already.
+    pub(super) fn fail(&self, error: GatewayError) {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return;
+        }
+        state.messages.push_back(Err(error));
+        state.closed = true;
+        drop(state);
+        self.pushed.notify_one();
+    }
+
+    /// Discards the messages which have not been handled yet and accepts new ones again, after
+    /// reconnecting.
+    ///
+    /// Discarded dispatches are sent again when the session is resumed, as the sequence number is
+    /// only advanced by handled events.
+    pub(super) fn reopen(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.messages.clear();
+        state.closed = false;
+        drop(state);
+        self.popped.notify_one();
+    }
+
+    /// Returns the next buffered message, waiting for one if there are none.
+    ///
+    /// Returns `None` once the buffer has been closed and all messages have been returned.
+    pub(super) async fn pop(&self) -> Option<Result<GatewayMessage, GatewayError>> {
+        loop {
+            {
+                let mut state = self.state.lock().unwrap();
+                if let Some(message) = state.messages.pop_front() {
+                    drop(state);
+                    self.popped.notify_one();
+                    return Some(message);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.pushed.notified().await;
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.state.lock().unwrap().messages.len()
+    }
+
+    pub(super) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
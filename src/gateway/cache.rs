@@ -0,0 +1,417 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::types::{
+    Channel, ChannelCreate, ChannelDelete, ChannelUpdate, GatewayReady, Guild, GuildCreate,
+    GuildCreateDataOption, GuildDelete, GuildEmojisUpdate, GuildMember, GuildMemberAdd,
+    GuildMemberRemove, GuildMemberUpdate, GuildMembersChunk, GuildRoleCreate, GuildRoleDelete,
+    GuildRoleUpdate, GuildStickersUpdate, GuildUpdate, IntoShared, PresenceUpdate, PublicUser,
+    RoleObject, ThreadCreate, ThreadDelete, ThreadUpdate, User, UserUpdate,
+};
+
+/// Everything but the members, which are kept by a [`MemberCache`].
+///
+/// The maps sit behind a single lock instead of being concurrent maps, as events such as a guild
+/// being deleted update several of them at once, which readers must not see half applied.
+#[derive(Debug, Default)]
+struct CacheState {
+    current_user: Option<User>,
+    /// Without their channels and roles, which are kept separately
+    guilds: HashMap<Snowflake, Guild>,
+    channels: HashMap<Snowflake, Channel>,
+    users: HashMap<Snowflake, PublicUser>,
+    /// By role id, with the id of their guild
+    roles: HashMap<Snowflake, (Snowflake, RoleObject)>,
+}
+
+impl CacheState {
+    fn insert_guild(&mut self, mut guild: Guild) {
+        for channel in guild.channels.take().into_iter().flatten() {
+            let mut channel = channel.read().unwrap().clone();
+            channel.guild_id = Some(guild.id);
+            self.channels.insert(channel.id, channel);
+        }
+        for role in guild.roles.take().into_iter().flatten() {
+            let role = role.read().unwrap().clone();
+            self.roles.insert(role.id, (guild.id, role));
+        }
+        self.guilds.insert(guild.id, guild);
+    }
+
+    fn remove_guild(&mut self, guild_id: Snowflake) {
+        self.guilds.remove(&guild_id);
+        self.channels
+            .retain(|_, channel| channel.guild_id != Some(guild_id));
+        self.roles
+            .retain(|_, (role_guild_id, _)| *role_guild_id != guild_id);
+    }
+
+    fn insert_member_user(&mut self, member: &GuildMember) {
+        if let Some(user) = &member.user {
+            let user = user.read().unwrap().clone();
+            self.users.insert(user.id, user);
+        }
+    }
+}
+
+/// The guilds, channels, users, members and roles the connection has seen, kept up to date by
+/// gateway events so they can be looked up without a request.
+///
+/// Enabled by setting [`GatewayOptions::cache`], after which it is returned by
+/// [`GatewayHandle::cache`] and [`ChorusUser::cache`](crate::instance::ChorusUser::cache).
+/// Requires the `cache` feature.
+///
+/// Guilds are cached without their channels and roles, which are returned by
+/// [`Cache::guild_channels`] and [`Cache::guild_roles`], or along with them by
+/// [`Cache::full_guild`]. Members are kept by the connection's [`MemberCache`], see
+/// [`Cache::member_cache`], and are only known once they have been sent, for example by
+/// [`GatewayHandle::send_request_guild_members`].
+#[derive(Debug)]
+pub struct Cache {
+    state: RwLock<CacheState>,
+    members: Arc<MemberCache>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cache {
+    /// Creates a cache which keeps members in a [`MemberCache`] with the default configuration.
+    pub fn new() -> Self {
+        Self::with_member_cache(Arc::new(MemberCache::new(MemberCacheConfig::default())))
+    }
+
+    /// Creates a cache which keeps members in the given [`MemberCache`], and passes the member
+    /// and presence events it observes on to it.
+    pub fn with_member_cache(members: Arc<MemberCache>) -> Self {
+        Self {
+            state: RwLock::new(CacheState::default()),
+            members,
+        }
+    }
+
+    /// Returns the [`MemberCache`] the members are kept in.
+    pub fn member_cache(&self) -> Arc<MemberCache> {
+        self.members.clone()
+    }
+
+    /// Returns the user the connection is logged in as, once the session is ready.
+    pub fn current_user(&self) -> Option<User> {
+        self.state.read().unwrap().current_user.clone()
+    }
+
+    pub fn guild(&self, guild_id: Snowflake) -> Option<Guild> {
+        self.state.read().unwrap().guilds.get(&guild_id).cloned()
+    }
+
+    /// Returns a cached guild along with its cached channels and roles, as the API returns it.
+    pub fn full_guild(&self, guild_id: Snowflake) -> Option<Guild> {
+        let state = self.state.read().unwrap();
+        let mut guild = state.guilds.get(&guild_id)?.clone();
+        guild.channels = Some(
+            state
+                .channels
+                .values()
+                .filter(|channel| channel.guild_id == Some(guild_id))
+                .map(|channel| channel.clone().into_shared())
+                .collect(),
+        );
+        guild.roles = Some(
+            state
+                .roles
+                .values()
+                .filter(|(role_guild_id, _)| *role_guild_id == guild_id)
+                .map(|(_, role)| role.clone().into_shared())
+                .collect(),
+        );
+        Some(guild)
+    }
+
+    /// Returns the cached guilds, in no particular order.
+    pub fn guilds(&self) -> Vec<Guild> {
+        self.state
+            .read()
+            .unwrap()
+            .guilds
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a cached channel or thread.
+    pub fn channel(&self, channel_id: Snowflake) -> Option<Channel> {
+        self.state
+            .read()
+            .unwrap()
+            .channels
+            .get(&channel_id)
+            .cloned()
+    }
+
+    /// Returns the cached channels and threads of a guild, in no particular order.
+    pub fn guild_channels(&self, guild_id: Snowflake) -> Vec<Channel> {
+        self.state
+            .read()
+            .unwrap()
+            .channels
+            .values()
+            .filter(|channel| channel.guild_id == Some(guild_id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn user(&self, user_id: Snowflake) -> Option<PublicUser> {
+        self.state.read().unwrap().users.get(&user_id).cloned()
+    }
+
+    /// Returns a cached member, see [`MemberCache::get`].
+    pub fn member(&self, guild_id: Snowflake, user_id: Snowflake) -> Option<GuildMember> {
+        self.members.get(guild_id, user_id)
+    }
+
+    pub fn role(&self, role_id: Snowflake) -> Option<RoleObject> {
+        self.state
+            .read()
+            .unwrap()
+            .roles
+            .get(&role_id)
+            .map(|(_, role)| role.clone())
+    }
+
+    /// Returns the cached roles of a guild, in no particular order.
+    pub fn guild_roles(&self, guild_id: Snowflake) -> Vec<RoleObject> {
+        self.state
+            .read()
+            .unwrap()
+            .roles
+            .values()
+            .filter(|(role_guild_id, _)| *role_guild_id == guild_id)
+            .map(|(_, role)| role.clone())
+            .collect()
+    }
+
+    /// Returns an index of the custom emojis and stickers of the cached guilds, as they are
+    /// now. Emoji and sticker updates are applied to the cached guilds, so call this again
+    /// rather than keeping the index up to date.
+    pub fn expressions(&self) -> ExpressionIndex {
+        let mut index = ExpressionIndex::new();
+        for guild in self.state.read().unwrap().guilds.values() {
+            index.insert_guild(guild);
+        }
+        index
+    }
+
+    /// Forgets everything cached.
+    pub fn clear(&self) {
+        *self.state.write().unwrap() = CacheState::default();
+        self.members.clear();
+    }
+}
+
+#[async_trait]
+impl Observer<GatewayReady> for Cache {
+    async fn update(&self, data: &GatewayReady) {
+        self.members.clear();
+        let mut state = self.state.write().unwrap();
+        *state = CacheState::default();
+        state.current_user = Some(data.user.clone());
+        for guild in &data.guilds {
+            state.insert_guild(guild.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildCreate> for Cache {
+    async fn update(&self, data: &GuildCreate) {
+        if let GuildCreateDataOption::Guild(guild) = &data.d {
+            self.state.write().unwrap().insert_guild(guild.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildUpdate> for Cache {
+    async fn update(&self, data: &GuildUpdate) {
+        let mut guild = data.guild.clone();
+        // Updates do not include channels, and the cached roles are kept up to date by role events
+        guild.channels = None;
+        guild.roles = None;
+        let mut state = self.state.write().unwrap();
+        // Nor do they have to include expressions, which have their own update events
+        if let Some(cached) = state.guilds.get(&guild.id) {
+            if guild.emojis.is_empty() {
+                guild.emojis = cached.emojis.clone();
+            }
+            if guild.stickers.is_none() {
+                guild.stickers = cached.stickers.clone();
+            }
+        }
+        state.guilds.insert(guild.id, guild);
+    }
+}
+
+#[async_trait]
+impl Observer<GuildDelete> for Cache {
+    async fn update(&self, data: &GuildDelete) {
+        // Unavailable guilds are only temporarily unreachable
+        if !data.guild.unavailable {
+            self.state.write().unwrap().remove_guild(data.guild.id);
+        }
+        Observer::<GuildDelete>::update(&*self.members, data).await;
+    }
+}
+
+#[async_trait]
+impl Observer<GuildEmojisUpdate> for Cache {
+    async fn update(&self, data: &GuildEmojisUpdate) {
+        if let Some(guild) = self.state.write().unwrap().guilds.get_mut(&data.guild_id) {
+            guild.emojis = data
+                .emojis
+                .iter()
+                .map(|emoji| emoji.clone().into_shared())
+                .collect();
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildStickersUpdate> for Cache {
+    async fn update(&self, data: &GuildStickersUpdate) {
+        if let Some(guild) = self.state.write().unwrap().guilds.get_mut(&data.guild_id) {
+            guild.stickers = Some(data.stickers.clone());
+        }
+    }
+}
+
+/// Implements [`Observer`] for events which create or update a channel or thread.
+macro_rules! observe_channel {
+    ($($event:ty => $field:ident),*) => {
+        $(
+            #[async_trait]
+            impl Observer<$event> for Cache {
+                async fn update(&self, data: &$event) {
+                    let channel = data.$field.clone();
+                    self.state.write().unwrap().channels.insert(channel.id, channel);
+                }
+            }
+        )*
+    };
+}
+
+observe_channel!(
+    ChannelCreate => channel,
+    ChannelUpdate => channel,
+    ThreadCreate => thread,
+    ThreadUpdate => thread
+);
+
+#[async_trait]
+impl Observer<ChannelDelete> for Cache {
+    async fn update(&self, data: &ChannelDelete) {
+        self.state
+            .write()
+            .unwrap()
+            .channels
+            .remove(&data.channel.id);
+    }
+}
+
+#[async_trait]
+impl Observer<ThreadDelete> for Cache {
+    async fn update(&self, data: &ThreadDelete) {
+        self.state.write().unwrap().channels.remove(&data.thread.id);
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberAdd> for Cache {
+    async fn update(&self, data: &GuildMemberAdd) {
+        self.state.write().unwrap().insert_member_user(&data.member);
+        Observer::<GuildMemberAdd>::update(&*self.members, data).await;
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMembersChunk> for Cache {
+    async fn update(&self, data: &GuildMembersChunk) {
+        {
+            let mut state = self.state.write().unwrap();
+            for member in &data.members {
+                state.insert_member_user(member);
+            }
+        }
+        Observer::<GuildMembersChunk>::update(&*self.members, data).await;
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberUpdate> for Cache {
+    async fn update(&self, data: &GuildMemberUpdate) {
+        self.state
+            .write()
+            .unwrap()
+            .users
+            .insert(data.user.id, data.user.clone());
+        Observer::<GuildMemberUpdate>::update(&*self.members, data).await;
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberRemove> for Cache {
+    async fn update(&self, data: &GuildMemberRemove) {
+        Observer::<GuildMemberRemove>::update(&*self.members, data).await;
+    }
+}
+
+#[async_trait]
+impl Observer<PresenceUpdate> for Cache {
+    async fn update(&self, data: &PresenceUpdate) {
+        Observer::<PresenceUpdate>::update(&*self.members, data).await;
+    }
+}
+
+#[async_trait]
+impl Observer<GuildRoleCreate> for Cache {
+    async fn update(&self, data: &GuildRoleCreate) {
+        self.state
+            .write()
+            .unwrap()
+            .roles
+            .insert(data.role.id, (data.guild_id, data.role.clone()));
+    }
+}
+
+#[async_trait]
+impl Observer<GuildRoleUpdate> for Cache {
+    async fn update(&self, data: &GuildRoleUpdate) {
+        self.state
+            .write()
+            .unwrap()
+            .roles
+            .insert(data.role.id, (data.guild_id, data.role.clone()));
+    }
+}
+
+#[async_trait]
+impl Observer<GuildRoleDelete> for Cache {
+    async fn update(&self, data: &GuildRoleDelete) {
+        self.state.write().unwrap().roles.remove(&data.role_id);
+    }
+}
+
+#[async_trait]
+impl Observer<UserUpdate> for Cache {
+    async fn update(&self, data: &UserUpdate) {
+        self.state
+            .write()
+            .unwrap()
+            .users
+            .insert(data.user.id, data.user.clone());
+    }
+}
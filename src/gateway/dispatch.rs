@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+
+use super::events::Events;
+use super::*;
+use crate::errors::DispatchError;
+use crate::types::SessionsReplace;
+
+/// Calls `$callback` with every dispatched event the gateway handles, as
+/// `"EVENT_NAME" => path.to.event`, optionally followed by `MessageType: UpdatedType` for events
+/// which update observed objects.
+///
+/// Events which need special handling, such as `SESSIONS_REPLACE`, are not listed.
+macro_rules! dispatch_events {
+    ($callback:ident) => {
+        $callback!(
+            // See https://discord.com/developers/docs/topics/gateway-events#receive-events
+            // "Some" of these are undocumented
+            "READY" => session.ready,
+            "READY_SUPPLEMENTAL" => session.ready_supplemental,
+            "APPLICATION_COMMAND_PERMISSIONS_UPDATE" => application.command_permissions_update,
+            "AUTO_MODERATION_RULE_CREATE" =>auto_moderation.rule_create,
+            "AUTO_MODERATION_RULE_UPDATE" =>auto_moderation.rule_update AutoModerationRuleUpdate: AutoModerationRule,
+            "AUTO_MODERATION_RULE_DELETE" => auto_moderation.rule_delete,
+            "AUTO_MODERATION_ACTION_EXECUTION" => auto_moderation.action_execution,
+            "CHANNEL_CREATE" => channel.create ChannelCreate: Guild,
+            "CHANNEL_UPDATE" => channel.update ChannelUpdate: Channel,
+            "CHANNEL_UNREAD_UPDATE" => channel.unread_update,
+            "CHANNEL_DELETE" => channel.delete ChannelDelete: Guild,
+            "CHANNEL_PINS_UPDATE" => channel.pins_update,
+            "CALL_CREATE" => call.create,
+            "CALL_UPDATE" => call.update,
+            "CALL_DELETE" => call.delete,
+            "THREAD_CREATE" => thread.create, // TODO
+            "THREAD_UPDATE" => thread.update ThreadUpdate: Channel,
+            "THREAD_DELETE" => thread.delete, // TODO
+            "THREAD_LIST_SYNC" => thread.list_sync, // TODO
+            "THREAD_MEMBER_UPDATE" => thread.member_update, // TODO
+            "THREAD_MEMBERS_UPDATE" => thread.members_update, // TODO
+            "GUILD_CREATE" => guild.create, // TODO
+            "GUILD_UPDATE" => guild.update, // TODO
+            "GUILD_DELETE" => guild.delete, // TODO
+            "GUILD_AUDIT_LOG_ENTRY_CREATE" => guild.audit_log_entry_create,
+            "GUILD_BAN_ADD" => guild.ban_add, // TODO
+            "GUILD_BAN_REMOVE" => guild.ban_remove, // TODO
+            "GUILD_EMOJIS_UPDATE" => guild.emojis_update, // TODO
+            "GUILD_STICKERS_UPDATE" => guild.stickers_update, // TODO
+            "GUILD_INTEGRATIONS_UPDATE" => guild.integrations_update,
+            "GUILD_JOIN_REQUEST_CREATE" => guild.join_request_create,
+            "GUILD_JOIN_REQUEST_UPDATE" => guild.join_request_update,
+            "GUILD_JOIN_REQUEST_DELETE" => guild.join_request_delete,
+            "GUILD_MEMBER_ADD" => guild.member_add,
+            "GUILD_MEMBER_REMOVE" => guild.member_remove,
+            "GUILD_MEMBER_UPDATE" => guild.member_update, // TODO
+            "GUILD_MEMBERS_CHUNK" => guild.members_chunk, // TODO
+            "GUILD_ROLE_CREATE" => guild.role_create GuildRoleCreate: Guild,
+            "GUILD_ROLE_UPDATE" => guild.role_update GuildRoleUpdate: RoleObject,
+            "GUILD_ROLE_DELETE" => guild.role_delete, // TODO
+            "GUILD_SCHEDULED_EVENT_CREATE" => guild.role_scheduled_event_create, // TODO
+            "GUILD_SCHEDULED_EVENT_UPDATE" => guild.role_scheduled_event_update, // TODO
+            "GUILD_SCHEDULED_EVENT_DELETE" => guild.role_scheduled_event_delete, // TODO
+            "GUILD_SCHEDULED_EVENT_USER_ADD" => guild.role_scheduled_event_user_add,
+            "GUILD_SCHEDULED_EVENT_USER_REMOVE" => guild.role_scheduled_event_user_remove,
+            "PASSIVE_UPDATE_V1" => guild.passive_update_v1, // TODO
+            "INTEGRATION_CREATE" => integration.create, // TODO
+            "INTEGRATION_UPDATE" => integration.update, // TODO
+            "INTEGRATION_DELETE" => integration.delete, // TODO
+            "INTERACTION_CREATE" => interaction.create, // TODO
+            "INVITE_CREATE" => invite.create, // TODO
+            "INVITE_DELETE" => invite.delete, // TODO
+            "MESSAGE_CREATE" => message.create,
+            "MESSAGE_UPDATE" => message.update, // TODO
+            "MESSAGE_DELETE" => message.delete,
+            "MESSAGE_DELETE_BULK" => message.delete_bulk,
+            "MESSAGE_REACTION_ADD" => message.reaction_add, // TODO
+            "MESSAGE_REACTION_REMOVE" => message.reaction_remove, // TODO
+            "MESSAGE_REACTION_REMOVE_ALL" => message.reaction_remove_all, // TODO
+            "MESSAGE_REACTION_REMOVE_EMOJI" => message.reaction_remove_emoji, // TODO
+            "MESSAGE_ACK" => message.ack,
+            "PRESENCE_UPDATE" => user.presence_update, // TODO
+            "RELATIONSHIP_ADD" => relationship.add,
+            "RELATIONSHIP_REMOVE" => relationship.remove,
+            "STAGE_INSTANCE_CREATE" => stage_instance.create,
+            "STAGE_INSTANCE_UPDATE" => stage_instance.update, // TODO
+            "STAGE_INSTANCE_DELETE" => stage_instance.delete,
+            "TYPING_START" => user.typing_start,
+            "USER_UPDATE" => user.update, // TODO
+            "USER_GUILD_SETTINGS_UPDATE" => user.guild_settings_update,
+            "VOICE_STATE_UPDATE" => voice.state_update, // TODO
+            "VOICE_SERVER_UPDATE" => voice.server_update,
+            "WEBHOOKS_UPDATE" => webhooks.update
+        )
+    };
+}
+pub(super) use dispatch_events;
+
+/// A dispatched gateway event parsed with [`parse_dispatch`].
+pub struct ParsedDispatch {
+    event_name: String,
+    data: Box<dyn ParsedEvent>,
+}
+
+impl ParsedDispatch {
+    pub fn event_name(&self) -> &str {
+        &self.event_name
+    }
+
+    /// Returns the event if it is of type `T`, such as [`MessageCreate`](crate::types::MessageCreate)
+    /// for `MESSAGE_CREATE`.
+    pub fn downcast_ref<T: WebSocketEvent + 'static>(&self) -> Option<&T> {
+        self.data.as_any().downcast_ref()
+    }
+}
+
+impl fmt::Debug for ParsedDispatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParsedDispatch")
+            .field("event_name", &self.event_name)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+trait ParsedEvent: fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: WebSocketEvent + 'static> ParsedEvent for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Parses the data of an event as the type the gateway deserializes it to.
+fn parse_as<T: WebSocketEvent + DeserializeOwned + 'static>(
+    _event: &GatewayEvent<T>,
+    json: &str,
+) -> Result<Box<dyn ParsedEvent>, serde_json::Error> {
+    Ok(Box::new(serde_json::from_str::<T>(json)?))
+}
+
+/// Parses the data (`d`) of a dispatched gateway event the same way the gateway does, without
+/// a connection.
+///
+/// Useful to check payloads of a server against the types of chorus, for example in tests
+/// built on a corpus of recorded events. See [`dispatch_event_names`] for the events which can
+/// be parsed; events without data, like `RESUMED`, are not included.
+pub fn parse_dispatch(event_name: &str, json: &str) -> Result<ParsedDispatch, DispatchError> {
+    let invalid_payload = |error: serde_json::Error| DispatchError::InvalidPayload {
+        event_name: event_name.to_string(),
+        error: error.to_string(),
+    };
+
+    macro_rules! parse {
+        ($($name:literal => $($path:ident).+ $( $message_type:ty: $update_type:ty)?),*) => {{
+            let events = Events::default();
+            match event_name {
+                $($name => parse_as(&events.$($path).+, json).map_err(invalid_payload)?,)*
+                "SESSIONS_REPLACE" => Box::new(SessionsReplace {
+                    sessions: serde_json::from_str(json).map_err(invalid_payload)?,
+                }),
+                _ => {
+                    return Err(DispatchError::UnknownEvent {
+                        event_name: event_name.to_string(),
+                    })
+                }
+            }
+        }};
+    }
+
+    Ok(ParsedDispatch {
+        event_name: event_name.to_string(),
+        data: dispatch_events!(parse),
+    })
+}
+
+/// Returns the names of the events [`parse_dispatch`] can parse.
+pub fn dispatch_event_names() -> Vec<&'static str> {
+    macro_rules! names {
+        ($($name:literal => $($path:ident).+ $( $message_type:ty: $update_type:ty)?),*) => {
+            vec![$($name,)* "SESSIONS_REPLACE"]
+        };
+    }
+    dispatch_events!(names)
+}
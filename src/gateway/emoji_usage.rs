@@ -0,0 +1,208 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::types::{
+    Emoji, GatewayReady, MessageCreate, MessageReactionAdd, Snowflake, CUSTOM_EMOJI,
+};
+
+/// How many of the latest uses of an emoji or sticker are kept, see [`FrecencyItem`].
+pub const MAX_RECENT_USES: usize = 10;
+
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// How often and how recently an emoji or sticker was used, like the items of official clients'
+/// frecency settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrecencyItem {
+    pub total_uses: u32,
+    /// The unix timestamps of the latest uses in milliseconds, oldest first, up to
+    /// [`MAX_RECENT_USES`]
+    pub recent_uses: Vec<u64>,
+}
+
+impl FrecencyItem {
+    /// Records a use at the given unix timestamp in milliseconds.
+    pub fn record(&mut self, timestamp: u64) {
+        self.total_uses = self.total_uses.saturating_add(1);
+        self.recent_uses.push(timestamp);
+        if self.recent_uses.len() > MAX_RECENT_USES {
+            self.recent_uses.remove(0);
+        }
+    }
+
+    /// Scores the item by its total uses, weighted by how recent its latest uses are, the way
+    /// official clients sort their frequently used emojis.
+    ///
+    /// Uses within the last 3 days weigh the most, uses older than 80 days do not count.
+    pub fn frecency(&self, now: u64) -> u64 {
+        if self.recent_uses.is_empty() {
+            return 0;
+        }
+        let weights: u64 = self
+            .recent_uses
+            .iter()
+            .map(
+                |timestamp| match now.saturating_sub(*timestamp) / DAY_MILLIS {
+                    0..=3 => 100,
+                    4..=15 => 70,
+                    16..=30 => 50,
+                    31..=45 => 30,
+                    46..=80 => 10,
+                    _ => 0,
+                },
+            )
+            .sum();
+        self.total_uses as u64 * weights / self.recent_uses.len() as u64
+    }
+}
+
+/// The most frequently used emojis and stickers, see [`EmojiUsage::frequently_used`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FrequentlyUsed {
+    /// See [`EmojiUsage::emojis`] for the keys
+    pub emojis: Vec<String>,
+    pub stickers: Vec<Snowflake>,
+}
+
+/// Counts which emojis and stickers the current user uses in messages and reactions, so they can
+/// be offered as frequently used, like official clients do.
+///
+/// Can be kept up to date by applying the user's [`MessageCreate`] and [`MessageReactionAdd`]
+/// events, or automatically through [`GatewayOptions::track_emoji_usage`]. It can be serialized,
+/// to keep it between sessions.
+///
+/// Only custom emojis are counted from the content of messages, as telling unicode emojis apart
+/// in text is out of scope; reactions count both.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmojiUsage {
+    /// By the id of custom emojis, or the emoji itself for unicode emojis
+    pub emojis: HashMap<String, FrecencyItem>,
+    pub stickers: HashMap<Snowflake, FrecencyItem>,
+}
+
+impl EmojiUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the key an emoji is counted under in [`EmojiUsage::emojis`].
+    pub fn emoji_key(emoji: &Emoji) -> Option<String> {
+        if emoji.id != Snowflake::default() {
+            return Some(emoji.id.to_string());
+        }
+        emoji.name.clone()
+    }
+
+    pub fn record_emoji(&mut self, key: String, timestamp: u64) {
+        self.emojis.entry(key).or_default().record(timestamp);
+    }
+
+    pub fn record_sticker(&mut self, sticker_id: Snowflake, timestamp: u64) {
+        self.stickers
+            .entry(sticker_id)
+            .or_default()
+            .record(timestamp);
+    }
+
+    /// Counts the custom emojis and stickers of a message, if it was sent by the given user.
+    pub fn apply_message_create(&mut self, event: &MessageCreate, user_id: Snowflake) {
+        let message = &event.message;
+        if message.author.as_ref().map(|author| author.id) != Some(user_id) {
+            return;
+        }
+        let timestamp = message.id.timestamp().timestamp_millis() as u64;
+        let content = message.content.as_deref().unwrap_or_default();
+        for emoji in CUSTOM_EMOJI.captures_iter(content) {
+            self.record_emoji(emoji[1].to_string(), timestamp);
+        }
+        for sticker in message.sticker_items.iter().flatten() {
+            self.record_sticker(sticker.id, timestamp);
+        }
+    }
+
+    /// Counts the emoji of a reaction, if it was added by the given user.
+    pub fn apply_reaction_add(&mut self, event: &MessageReactionAdd, user_id: Snowflake) {
+        if event.user_id != user_id {
+            return;
+        }
+        if let Some(key) = EmojiUsage::emoji_key(&event.emoji) {
+            self.record_emoji(key, chrono::Utc::now().timestamp_millis() as u64);
+        }
+    }
+
+    /// Returns up to `limit` emojis and stickers each, the highest [frecency](FrecencyItem::frecency)
+    /// first.
+    pub fn frequently_used(&self, limit: usize) -> FrequentlyUsed {
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        FrequentlyUsed {
+            emojis: most_frequent(&self.emojis, limit, now),
+            stickers: most_frequent(&self.stickers, limit, now),
+        }
+    }
+}
+
+fn most_frequent<K: Clone + Ord>(
+    items: &HashMap<K, FrecencyItem>,
+    limit: usize,
+    now: u64,
+) -> Vec<K> {
+    let mut scored: Vec<(u64, &K)> = items
+        .iter()
+        .map(|(key, item)| (item.frecency(now), key))
+        .filter(|(frecency, _)| *frecency > 0)
+        .collect();
+    // Ties are broken by the key, so the order does not depend on the map's
+    scored.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, key)| key.clone())
+        .collect()
+}
+
+/// Keeps the [`EmojiUsage`] returned by [`GatewayHandle::emoji_usage`] up to date, with the
+/// messages and reactions of the user who identified on the connection.
+#[derive(Debug)]
+pub(super) struct EmojiUsageTracker {
+    pub(super) user_id: RwLock<Option<Snowflake>>,
+    pub(super) usage: RwLock<EmojiUsage>,
+}
+
+#[async_trait]
+impl Observer<GatewayReady> for EmojiUsageTracker {
+    async fn update(&self, data: &GatewayReady) {
+        *self.user_id.write().unwrap() = Some(data.user.id);
+    }
+}
+
+#[async_trait]
+impl Observer<MessageCreate> for EmojiUsageTracker {
+    async fn update(&self, data: &MessageCreate) {
+        let Some(user_id) = *self.user_id.read().unwrap() else {
+            return;
+        };
+        self.usage
+            .write()
+            .unwrap()
+            .apply_message_create(data, user_id);
+    }
+}
+
+#[async_trait]
+impl Observer<MessageReactionAdd> for EmojiUsageTracker {
+    async fn update(&self, data: &MessageReactionAdd) {
+        let Some(user_id) = *self.user_id.read().unwrap() else {
+            return;
+        };
+        self.usage
+            .write()
+            .unwrap()
+            .apply_reaction_add(data, user_id);
+    }
+}
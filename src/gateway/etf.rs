@@ -0,0 +1,533 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Encoding of gateway payloads in Erlang's External Term Format, see [`ErlpackCodec`].
+
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, IgnoredAny, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::errors::GatewayError;
+use crate::types::RAW_EVENT_DATA;
+
+/// The first byte of every term encoded in the External Term Format.
+pub const ETF_VERSION: u8 = 131;
+
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const SMALL_ATOM_EXT: u8 = 115;
+const MAP_EXT: u8 = 116;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// Converts gateway payloads between JSON and Erlang's External Term Format, like the erlpack
+/// library of official clients.
+///
+/// Payloads are converted the way the gateway does it: `nil`, `true` and `false` atoms are
+/// null and booleans, other atoms and binaries are strings, lists and tuples are arrays and maps
+/// are objects. Objects are encoded as maps with binary keys.
+///
+/// Received terms are deserialized straight into the payload types, without converting them to
+/// JSON first. Terms may be nested at most [`MAX_DEPTH`] levels deep.
+///
+/// See <https://www.erlang.org/doc/apps/erts/erl_ext_dist.html>
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErlpackCodec;
+
+impl ErlpackCodec {
+    /// Encodes a JSON payload as a term.
+    pub fn encode(json: &str) -> Result<Vec<u8>, GatewayError> {
+        let value: Value = serde_json::from_str(json).map_err(|e| GatewayError::Etf {
+            error: e.to_string(),
+        })?;
+        let mut bytes = vec![ETF_VERSION];
+        encode_value(&value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes a term, starting with the version byte, into `T`.
+    pub fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, GatewayError> {
+        match bytes.split_first() {
+            Some((&ETF_VERSION, term)) => Self::decode_term(term),
+            _ => Err(etf_error("unsupported version")),
+        }
+    }
+
+    /// Deserializes a term without a version byte, such as the event data of a payload.
+    pub(crate) fn decode_term<'a, T: Deserialize<'a>>(term: &'a [u8]) -> Result<T, GatewayError> {
+        let mut deserializer = Deserializer {
+            bytes: term,
+            position: 0,
+            depth: 0,
+        };
+        let value = T::deserialize(&mut deserializer)?;
+        if deserializer.position != term.len() {
+            return Err(etf_error("trailing bytes after term"));
+        }
+        Ok(value)
+    }
+}
+
+fn etf_error(error: &str) -> GatewayError {
+    GatewayError::Etf {
+        error: error.to_string(),
+    }
+}
+
+fn encode_len(len: usize, bytes: &mut Vec<u8>) -> Result<(), GatewayError> {
+    let len = u32::try_from(len).map_err(|_| etf_error("term too long"))?;
+    bytes.extend_from_slice(&len.to_be_bytes());
+    Ok(())
+}
+
+fn encode_atom(atom: &str, bytes: &mut Vec<u8>) {
+    bytes.push(ATOM_EXT);
+    bytes.extend_from_slice(&(atom.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(atom.as_bytes());
+}
+
+fn encode_binary(string: &str, bytes: &mut Vec<u8>) -> Result<(), GatewayError> {
+    bytes.push(BINARY_EXT);
+    encode_len(string.len(), bytes)?;
+    bytes.extend_from_slice(string.as_bytes());
+    Ok(())
+}
+
+fn encode_big(negative: bool, mut magnitude: u64, bytes: &mut Vec<u8>) {
+    let mut digits = Vec::new();
+    while magnitude > 0 {
+        digits.push(magnitude as u8);
+        magnitude >>= 8;
+    }
+    bytes.push(SMALL_BIG_EXT);
+    bytes.push(digits.len() as u8);
+    bytes.push(negative as u8);
+    bytes.extend(digits);
+}
+
+fn encode_value(value: &Value, bytes: &mut Vec<u8>) -> Result<(), GatewayError> {
+    match value {
+        Value::Null => encode_atom("nil", bytes),
+        Value::Bool(true) => encode_atom("true", bytes),
+        Value::Bool(false) => encode_atom("false", bytes),
+        Value::Number(number) => {
+            if let Some(small) = number.as_u64().and_then(|n| u8::try_from(n).ok()) {
+                bytes.push(SMALL_INTEGER_EXT);
+                bytes.push(small);
+            } else if let Some(integer) = number.as_i64().and_then(|n| i32::try_from(n).ok()) {
+                bytes.push(INTEGER_EXT);
+                bytes.extend_from_slice(&integer.to_be_bytes());
+            } else if let Some(unsigned) = number.as_u64() {
+                encode_big(false, unsigned, bytes);
+            } else if let Some(signed) = number.as_i64() {
+                encode_big(signed < 0, signed.unsigned_abs(), bytes);
+            } else {
+                bytes.push(NEW_FLOAT_EXT);
+                bytes.extend_from_slice(&number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        }
+        Value::String(string) => encode_binary(string, bytes)?,
+        Value::Array(array) if array.is_empty() => bytes.push(NIL_EXT),
+        Value::Array(array) => {
+            bytes.push(LIST_EXT);
+            encode_len(array.len(), bytes)?;
+            for element in array {
+                encode_value(element, bytes)?;
+            }
+            bytes.push(NIL_EXT);
+        }
+        Value::Object(object) => {
+            bytes.push(MAP_EXT);
+            encode_len(object.len(), bytes)?;
+            for (key, value) in object {
+                encode_binary(key, bytes)?;
+                encode_value(value, bytes)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How deeply lists, tuples and maps may be nested in a received term, so that terms from the
+/// wire cannot overflow the stack.
+pub const MAX_DEPTH: usize = 128;
+
+/// An error while deserializing a term, converted to [`GatewayError::Etf`].
+#[derive(Debug)]
+struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl From<Error> for GatewayError {
+    fn from(error: Error) -> Self {
+        GatewayError::Etf { error: error.0 }
+    }
+}
+
+/// Deserializes terms, borrowing strings and raw event data from the received bytes.
+struct Deserializer<'de> {
+    bytes: &'de [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| Error("unexpected end of term".to_string()))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+    }
+
+    fn u32(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn str(&mut self, len: usize) -> Result<&'de str, Error> {
+        std::str::from_utf8(self.take(len)?).map_err(de::Error::custom)
+    }
+
+    /// Returns whether the next term is the `nil` atom, which stands for null.
+    fn next_is_nil(&self) -> bool {
+        matches!(
+            &self.bytes[self.position..],
+            [ATOM_EXT | ATOM_UTF8_EXT, 0, 3, b'n', b'i', b'l', ..]
+                | [
+                    SMALL_ATOM_EXT | SMALL_ATOM_UTF8_EXT,
+                    3,
+                    b'n',
+                    b'i',
+                    b'l',
+                    ..
+                ]
+                | [ATOM_EXT | ATOM_UTF8_EXT, 0, 4, b'n', b'u', b'l', b'l', ..]
+                | [
+                    SMALL_ATOM_EXT | SMALL_ATOM_UTF8_EXT,
+                    4,
+                    b'n',
+                    b'u',
+                    b'l',
+                    b'l',
+                    ..
+                ]
+        )
+    }
+
+    /// Enters a list, tuple or map, failing if they are nested too deeply.
+    fn enter(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error(format!("term nested deeper than {MAX_DEPTH} levels")));
+        }
+        Ok(())
+    }
+
+    fn atom<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        match self.str(len)? {
+            "nil" | "null" => visitor.visit_unit(),
+            "true" => visitor.visit_bool(true),
+            "false" => visitor.visit_bool(false),
+            atom => visitor.visit_borrowed_str(atom),
+        }
+    }
+
+    fn big<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let negative = self.u8()? != 0;
+        let digits = self.take(len)?;
+        if digits.iter().skip(8).any(|digit| *digit != 0) {
+            return Err(Error("integer too large".to_string()));
+        }
+        let magnitude = digits
+            .iter()
+            .take(8)
+            .rev()
+            .fold(0u64, |magnitude, digit| magnitude << 8 | *digit as u64);
+        if !negative {
+            return visitor.visit_u64(magnitude);
+        }
+        match i64::try_from(magnitude) {
+            Ok(magnitude) => visitor.visit_i64(-magnitude),
+            Err(_) => Err(Error("integer too large".to_string())),
+        }
+    }
+
+    fn seq<V: Visitor<'de>>(
+        &mut self,
+        len: usize,
+        list: bool,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.enter()?;
+        let mut access = Access {
+            de: self,
+            remaining: len,
+        };
+        let value = visitor.visit_seq(&mut access)?;
+        if access.remaining != 0 {
+            return Err(Error("trailing elements in list".to_string()));
+        }
+        self.depth -= 1;
+        // Proper lists end with an empty list
+        if list && self.u8()? != NIL_EXT {
+            return Err(Error("improper lists are not supported".to_string()));
+        }
+        Ok(value)
+    }
+
+    fn map<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.enter()?;
+        let mut access = Access {
+            de: self,
+            remaining: len,
+        };
+        let value = visitor.visit_map(&mut access)?;
+        if access.remaining != 0 {
+            return Err(Error("trailing entries in map".to_string()));
+        }
+        self.depth -= 1;
+        Ok(value)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.u8()? {
+            SMALL_INTEGER_EXT => visitor.visit_u8(self.u8()?),
+            INTEGER_EXT => {
+                let bytes = self.take(4)?;
+                visitor.visit_i32(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+            NEW_FLOAT_EXT => {
+                let mut float = [0; 8];
+                float.copy_from_slice(self.take(8)?);
+                visitor.visit_f64(f64::from_be_bytes(float))
+            }
+            SMALL_BIG_EXT => {
+                let len = self.u8()? as usize;
+                self.big(len, visitor)
+            }
+            LARGE_BIG_EXT => {
+                let len = self.u32()?;
+                self.big(len, visitor)
+            }
+            ATOM_EXT | ATOM_UTF8_EXT => {
+                let len = self.u16()?;
+                self.atom(len, visitor)
+            }
+            SMALL_ATOM_EXT | SMALL_ATOM_UTF8_EXT => {
+                let len = self.u8()? as usize;
+                self.atom(len, visitor)
+            }
+            BINARY_EXT => {
+                let len = self.u32()?;
+                let bytes = self.take(len)?;
+                match std::str::from_utf8(bytes) {
+                    Ok(string) => visitor.visit_borrowed_str(string),
+                    Err(_) => visitor.visit_borrowed_bytes(bytes),
+                }
+            }
+            STRING_EXT => {
+                // A list of small integers
+                let len = self.u16()?;
+                let bytes = self.take(len)?;
+                visitor.visit_seq(de::value::SeqDeserializer::<_, Error>::new(
+                    bytes.iter().copied(),
+                ))
+            }
+            NIL_EXT => self.seq(0, false, visitor),
+            LIST_EXT => {
+                let len = self.u32()?;
+                self.seq(len, true, visitor)
+            }
+            SMALL_TUPLE_EXT => {
+                let len = self.u8()? as usize;
+                self.seq(len, false, visitor)
+            }
+            LARGE_TUPLE_EXT => {
+                let len = self.u32()?;
+                self.seq(len, false, visitor)
+            }
+            MAP_EXT => {
+                let len = self.u32()?;
+                self.map(len, visitor)
+            }
+            tag => Err(Error(format!("unsupported tag {tag}"))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.next_is_nil() {
+            self.deserialize_any(IgnoredAny)?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name != RAW_EVENT_DATA {
+            return visitor.visit_newtype_struct(self);
+        }
+        // Event data is kept as the raw term, and deserialized once its type is known
+        let start = self.position;
+        self.deserialize_any(IgnoredAny)?;
+        visitor.visit_borrowed_bytes(&self.bytes[start..self.position])
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        // Variants with data are maps with a single entry, unit variants are strings
+        if self.bytes.get(self.position) != Some(&MAP_EXT) {
+            return visitor.visit_enum(Enum { de: self });
+        }
+        self.u8()?;
+        if self.u32()? != 1 {
+            return Err(Error("enum maps must have exactly one entry".to_string()));
+        }
+        self.enter()?;
+        let value = visitor.visit_enum(Enum { de: &mut *self })?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// The elements of a list or tuple, or the entries of a map.
+struct Access<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// An enum variant, either a string for unit variants or the entry of a single entry map.
+struct Enum<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant = seed.deserialize(&mut *self.de)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self.de, visitor)
+    }
+}
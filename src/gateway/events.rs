@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::fmt;
+
 use super::*;
 use crate::types;
 
@@ -23,6 +25,8 @@ pub struct Events {
     pub call: Call,
     pub voice: Voice,
     pub webhooks: Webhooks,
+    /// Notified for every dispatched event, see [`types::GatewayDispatch`]
+    pub dispatch: GatewayEvent<types::GatewayDispatch>,
     pub gateway_identify_payload: GatewayEvent<types::GatewayIdentifyPayload>,
     pub gateway_resume: GatewayEvent<types::GatewayResume>,
     pub error: GatewayEvent<GatewayError>,
@@ -112,8 +116,16 @@ pub struct Guild {
     pub ban_add: GatewayEvent<types::GuildBanAdd>,
     pub ban_remove: GatewayEvent<types::GuildBanRemove>,
     pub emojis_update: GatewayEvent<types::GuildEmojisUpdate>,
+    /// Notified after [`emojis_update`](Guild::emojis_update), see [`types::GuildEmojisDiff`]
+    pub emojis_diff: GatewayEvent<types::GuildEmojisDiff>,
     pub stickers_update: GatewayEvent<types::GuildStickersUpdate>,
+    /// Notified after [`stickers_update`](Guild::stickers_update), see
+    /// [`types::GuildStickersDiff`]
+    pub stickers_diff: GatewayEvent<types::GuildStickersDiff>,
     pub integrations_update: GatewayEvent<types::GuildIntegrationsUpdate>,
+    pub join_request_create: GatewayEvent<types::GuildJoinRequestCreate>,
+    pub join_request_update: GatewayEvent<types::GuildJoinRequestUpdate>,
+    pub join_request_delete: GatewayEvent<types::GuildJoinRequestDelete>,
     pub member_add: GatewayEvent<types::GuildMemberAdd>,
     pub member_remove: GatewayEvent<types::GuildMemberRemove>,
     pub member_update: GatewayEvent<types::GuildMemberUpdate>,
@@ -121,6 +133,8 @@ pub struct Guild {
     pub role_create: GatewayEvent<types::GuildRoleCreate>,
     pub role_update: GatewayEvent<types::GuildRoleUpdate>,
     pub role_delete: GatewayEvent<types::GuildRoleDelete>,
+    /// Notified after role events, see [`types::GuildRolesDiff`]
+    pub roles_diff: GatewayEvent<types::GuildRolesDiff>,
     pub role_scheduled_event_create: GatewayEvent<types::GuildScheduledEventCreate>,
     pub role_scheduled_event_update: GatewayEvent<types::GuildScheduledEventUpdate>,
     pub role_scheduled_event_delete: GatewayEvent<types::GuildScheduledEventDelete>,
@@ -164,3 +178,150 @@ pub struct Voice {
 pub struct Webhooks {
     pub update: GatewayEvent<types::WebhooksUpdate>,
 }
+
+impl Events {
+    /// Returns a view of the events which only notifies observers about events belonging to the
+    /// given guild, such as messages sent in its channels, member updates and voice states.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use chorus::gateway::{GatewayHandle, Observer};
+    /// # use chorus::types::{MessageCreate, Snowflake};
+    /// # async fn example(gateway: GatewayHandle, observer: Arc<dyn Observer<MessageCreate>>) {
+    /// gateway
+    ///     .events
+    ///     .lock()
+    ///     .await
+    ///     .for_guild(Snowflake(1))
+    ///     .subscribe(observer);
+    /// # }
+    /// ```
+    pub fn for_guild(&mut self, guild_id: Snowflake) -> GuildEvents<'_> {
+        GuildEvents {
+            events: self,
+            guild_id,
+        }
+    }
+}
+
+/// A gateway event which can belong to a guild, and which can therefore be observed with
+/// [`GuildEvents`].
+pub trait GuildScopedEvent: WebSocketEvent + Sized + 'static {
+    /// Returns the id of the guild the event belongs to, or `None` if it does not belong to one.
+    fn guild_id(&self) -> Option<Snowflake>;
+
+    /// Returns the [`GatewayEvent`] this event is dispatched by.
+    fn gateway_event(events: &mut Events) -> &mut GatewayEvent<Self>;
+}
+
+macro_rules! guild_scoped_events {
+    ($($event:ident => $category:ident.$field:ident, |$data:ident| $guild_id:expr;)*) => {
+        $(
+            impl GuildScopedEvent for types::$event {
+                fn guild_id(&self) -> Option<Snowflake> {
+                    let $data = self;
+                    $guild_id
+                }
+
+                fn gateway_event(events: &mut Events) -> &mut GatewayEvent<Self> {
+                    &mut events.$category.$field
+                }
+            }
+        )*
+    };
+}
+
+guild_scoped_events! {
+    MessageCreate => message.create, |event| event.guild_id;
+    MessageUpdate => message.update, |event| event.guild_id;
+    MessageDelete => message.delete, |event| event.guild_id;
+    MessageDeleteBulk => message.delete_bulk, |event| event.guild_id;
+    MessageReactionAdd => message.reaction_add, |event| event.guild_id;
+    MessageReactionRemove => message.reaction_remove, |event| event.guild_id;
+    MessageReactionRemoveAll => message.reaction_remove_all, |event| event.guild_id;
+    MessageReactionRemoveEmoji => message.reaction_remove_emoji, |event| event.guild_id;
+    TypingStartEvent => user.typing_start, |event| event.guild_id;
+    PresenceUpdate => user.presence_update, |event| event.guild_id;
+    ChannelCreate => channel.create, |event| event.channel.guild_id;
+    ChannelUpdate => channel.update, |event| event.channel.guild_id;
+    ChannelDelete => channel.delete, |event| event.channel.guild_id;
+    ThreadCreate => thread.create, |event| event.thread.guild_id;
+    ThreadUpdate => thread.update, |event| event.thread.guild_id;
+    GuildBanAdd => guild.ban_add, |event| Some(event.guild_id);
+    GuildBanRemove => guild.ban_remove, |event| Some(event.guild_id);
+    GuildMemberAdd => guild.member_add, |event| Some(event.guild_id);
+    GuildMemberRemove => guild.member_remove, |event| Some(event.guild_id);
+    GuildMemberUpdate => guild.member_update, |event| Some(event.guild_id);
+    GuildMembersChunk => guild.members_chunk, |event| Some(event.guild_id);
+    GuildRoleCreate => guild.role_create, |event| Some(event.guild_id);
+    GuildRoleUpdate => guild.role_update, |event| Some(event.guild_id);
+    GuildRoleDelete => guild.role_delete, |event| Some(event.guild_id);
+    GuildRolesDiff => guild.roles_diff, |event| Some(event.guild_id);
+    GuildEmojisDiff => guild.emojis_diff, |event| Some(event.guild_id);
+    GuildStickersDiff => guild.stickers_diff, |event| Some(event.guild_id);
+    GuildJoinRequestCreate => guild.join_request_create, |event| Some(event.guild_id);
+    GuildJoinRequestUpdate => guild.join_request_update, |event| Some(event.guild_id);
+    GuildJoinRequestDelete => guild.join_request_delete, |event| Some(event.guild_id);
+    VoiceStateUpdate => voice.state_update, |event| event.state.guild_id;
+}
+
+/// The events of a single guild, see [`Events::for_guild`].
+///
+/// Observers subscribed through this view are wrapped in a filter, which only passes on events
+/// whose [`GuildScopedEvent::guild_id`] matches the guild.
+#[derive(Debug)]
+pub struct GuildEvents<'a> {
+    events: &'a mut Events,
+    guild_id: Snowflake,
+}
+
+impl GuildEvents<'_> {
+    /// The guild whose events this view yields.
+    pub fn guild_id(&self) -> Snowflake {
+        self.guild_id
+    }
+
+    /// Subscribes an Observer to the events of type `T` belonging to this guild.
+    pub fn subscribe<T: GuildScopedEvent>(&mut self, observer: Arc<dyn Observer<T>>) -> &mut Self {
+        T::gateway_event(self.events).subscribe(Arc::new(GuildFilter {
+            guild_id: self.guild_id,
+            observer,
+        }));
+        self
+    }
+
+    /// Unsubscribes an Observer which has been subscribed through a view of the same guild.
+    pub fn unsubscribe<T: GuildScopedEvent>(&mut self, observer: &dyn Observer<T>) -> &mut Self {
+        let to_remove = GuildFilter::<T>::debug_string(self.guild_id, observer);
+        T::gateway_event(self.events).unsubscribe_by_debug(&to_remove);
+        self
+    }
+}
+
+/// Passes events on to an Observer, if they belong to a certain guild.
+struct GuildFilter<T> {
+    guild_id: Snowflake,
+    observer: Arc<dyn Observer<T>>,
+}
+
+impl<T> GuildFilter<T> {
+    fn debug_string(guild_id: Snowflake, observer: &dyn Observer<T>) -> String {
+        format!("GuildFilter({}, {:?})", guild_id, observer)
+    }
+}
+
+impl<T> fmt::Debug for GuildFilter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Self::debug_string(self.guild_id, self.observer.as_ref()))
+    }
+}
+
+#[async_trait]
+impl<T: GuildScopedEvent> Observer<T> for GuildFilter<T> {
+    async fn update(&self, data: &T) {
+        if data.guild_id() == Some(self.guild_id) {
+            self.observer.update(data).await;
+        }
+    }
+}
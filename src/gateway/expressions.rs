@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use crate::types::{
+    Emoji, Guild, GuildCreate, GuildCreateDataOption, GuildDelete, GuildEmojisUpdate,
+    GuildStickersUpdate, Snowflake, Sticker,
+};
+
+/// Aggregates the custom emojis and stickers of all known guilds, as needed for expression
+/// pickers or for looking up emojis by name.
+///
+/// With the `cache` feature and [`GatewayOptions::cache`](super::GatewayOptions) enabled, use
+/// `Cache::expressions`, which builds the index from the emojis and stickers the cache already
+/// keeps up to date, instead of maintaining a second copy of them.
+///
+/// The index is a plain value rather than a view of the cache, since the cache is optional and
+/// searching it should not hold the cache's lock. Without the cache, it is kept up to date by
+/// applying the corresponding gateway events, for example from observers of
+/// [`Guild::create`](super::events::Guild::create),
+/// [`Guild::emojis_update`](super::events::Guild::emojis_update) and
+/// [`Guild::stickers_update`](super::events::Guild::stickers_update).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExpressionIndex {
+    emojis: HashMap<Snowflake, Vec<Emoji>>,
+    stickers: HashMap<Snowflake, Vec<Sticker>>,
+}
+
+impl ExpressionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the emojis and stickers of a guild.
+    pub fn insert_guild(&mut self, guild: &Guild) {
+        let emojis = guild
+            .emojis
+            .iter()
+            .map(|emoji| emoji.read().unwrap().clone())
+            .collect();
+        self.set_emojis(guild.id, emojis);
+        self.set_stickers(guild.id, guild.stickers.clone().unwrap_or_default());
+    }
+
+    /// Forgets the emojis and stickers of a guild, for example after leaving it.
+    pub fn remove_guild(&mut self, guild_id: Snowflake) {
+        self.emojis.remove(&guild_id);
+        self.stickers.remove(&guild_id);
+    }
+
+    /// Replaces the emojis of a guild.
+    pub fn set_emojis(&mut self, guild_id: Snowflake, mut emojis: Vec<Emoji>) {
+        for emoji in emojis.iter_mut() {
+            emoji.guild_id = Some(guild_id);
+        }
+        self.emojis.insert(guild_id, emojis);
+    }
+
+    /// Replaces the stickers of a guild.
+    pub fn set_stickers(&mut self, guild_id: Snowflake, mut stickers: Vec<Sticker>) {
+        for sticker in stickers.iter_mut() {
+            sticker.guild_id = Some(guild_id);
+        }
+        self.stickers.insert(guild_id, stickers);
+    }
+
+    /// Applies a [`GuildCreate`] event. Unavailable guilds are ignored.
+    pub fn apply_guild_create(&mut self, event: &GuildCreate) {
+        if let GuildCreateDataOption::Guild(guild) = &event.d {
+            self.insert_guild(guild);
+        }
+    }
+
+    /// Applies a [`GuildDelete`] event. Guilds which only became unavailable because of an
+    /// outage are kept.
+    pub fn apply_guild_delete(&mut self, event: &GuildDelete) {
+        if !event.guild.unavailable {
+            self.remove_guild(event.guild.id);
+        }
+    }
+
+    /// Applies a [`GuildEmojisUpdate`] event.
+    pub fn apply_emojis_update(&mut self, event: &GuildEmojisUpdate) {
+        self.set_emojis(event.guild_id, event.emojis.clone());
+    }
+
+    /// Applies a [`GuildStickersUpdate`] event.
+    pub fn apply_stickers_update(&mut self, event: &GuildStickersUpdate) {
+        self.set_stickers(event.guild_id, event.stickers.clone());
+    }
+
+    /// Returns the emoji with the given id.
+    pub fn emoji(&self, emoji_id: Snowflake) -> Option<&Emoji> {
+        self.emojis().find(|emoji| emoji.id == emoji_id)
+    }
+
+    /// Returns the sticker with the given id.
+    pub fn sticker(&self, sticker_id: Snowflake) -> Option<&Sticker> {
+        self.stickers().find(|sticker| sticker.id == sticker_id)
+    }
+
+    /// Returns the emojis of all guilds.
+    pub fn emojis(&self) -> impl Iterator<Item = &Emoji> {
+        self.emojis.values().flatten()
+    }
+
+    /// Returns the stickers of all guilds.
+    pub fn stickers(&self) -> impl Iterator<Item = &Sticker> {
+        self.stickers.values().flatten()
+    }
+
+    /// Returns the emojis of a guild.
+    pub fn guild_emojis(&self, guild_id: Snowflake) -> &[Emoji] {
+        self.emojis.get(&guild_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the stickers of a guild.
+    pub fn guild_stickers(&self, guild_id: Snowflake) -> &[Sticker] {
+        self.stickers.get(&guild_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the emojis which can be used in `guild_id` (`None` for direct messages).
+    ///
+    /// Without premium, only the static emojis of the current guild are usable. Unavailable
+    /// emojis, for example those of a guild which lost its boosts, are never usable. Role
+    /// restrictions are not taken into account.
+    pub fn usable_emojis(
+        &self,
+        guild_id: Option<Snowflake>,
+        premium: bool,
+    ) -> impl Iterator<Item = &Emoji> {
+        self.emojis().filter(move |emoji| {
+            emoji.available != Some(false)
+                && (premium || (emoji.guild_id == guild_id && emoji.animated != Some(true)))
+        })
+    }
+
+    /// Returns the stickers which can be used in `guild_id` (`None` for direct messages).
+    ///
+    /// Without premium, only the stickers of the current guild are usable.
+    pub fn usable_stickers(
+        &self,
+        guild_id: Option<Snowflake>,
+        premium: bool,
+    ) -> impl Iterator<Item = &Sticker> {
+        self.stickers().filter(move |sticker| {
+            sticker.available != Some(false) && (premium || sticker.guild_id == guild_id)
+        })
+    }
+
+    /// Searches emojis by name, ignoring case and surrounding colons (`:name:`).
+    ///
+    /// Exact matches come first, followed by names starting with the query and names containing
+    /// it.
+    pub fn search_emojis(&self, query: &str) -> Vec<&Emoji> {
+        search(self.emojis(), query, |emoji| {
+            vec![emoji.name.clone().unwrap_or_default()]
+        })
+    }
+
+    /// Searches stickers by name and tags, ignoring case.
+    ///
+    /// Exact matches come first, followed by names or tags starting with the query and names or
+    /// tags containing it.
+    pub fn search_stickers(&self, query: &str) -> Vec<&Sticker> {
+        search(self.stickers(), query, |sticker| {
+            let mut keys: Vec<String> = sticker
+                .tags
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .collect();
+            keys.push(sticker.name.clone());
+            keys
+        })
+    }
+}
+
+/// Ranks `items` by how well one of their keys matches `query`, dropping items which do not match.
+fn search<'a, T>(
+    items: impl Iterator<Item = &'a T>,
+    query: &str,
+    keys: impl Fn(&T) -> Vec<String>,
+) -> Vec<&'a T> {
+    let query = query.trim().trim_matches(':').to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<(u8, String, &T)> = items
+        .filter_map(|item| {
+            let keys: Vec<String> = keys(item).iter().map(|key| key.to_lowercase()).collect();
+            let rank = keys
+                .iter()
+                .filter_map(|key| {
+                    if *key == query {
+                        Some(0)
+                    } else if key.starts_with(&query) {
+                        Some(1)
+                    } else if key.contains(&query) {
+                        Some(2)
+                    } else {
+                        None
+                    }
+                })
+                .min()?;
+            Some((rank, keys.into_iter().last().unwrap_or_default(), item))
+        })
+        .collect();
+    matches.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    matches.into_iter().map(|(_, _, item)| item).collect()
+}
@@ -6,36 +6,220 @@ use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
 use log::*;
+use rand::Rng;
+use tokio::sync::{oneshot, watch};
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::task;
 
+use super::dispatch::dispatch_events;
 use super::events::Events;
 use super::*;
-use super::{Sink, Stream};
 use crate::types::{
-    self, AutoModerationRule, AutoModerationRuleUpdate, Channel, ChannelCreate, ChannelDelete,
-    ChannelUpdate, GatewayInvalidSession, GatewayReconnect, Guild, GuildRoleCreate,
-    GuildRoleUpdate, JsonField, RoleObject, SourceUrlField, ThreadUpdate, UpdateMessage,
-    WebSocketEvent,
+    self, AutoModerationRule, AutoModerationRuleUpdate, ChangeSet, Channel, ChannelCreate,
+    ChannelDelete, ChannelUpdate, Emoji, GatewayInvalidSession, GatewayReconnect, Guild,
+    GuildRoleCreate, GuildRoleUpdate, IntoShared, JsonField, RoleObject, SourceUrlField,
+    ThreadUpdate, UpdateMessage, WebSocketEvent,
 };
 
+/// A change to the emojis, stickers or roles of a guild, see [`Gateway::diff_guild_lists`].
+enum GuildListDiff {
+    Emojis(types::GuildEmojisDiff),
+    Stickers(types::GuildStickersDiff),
+    Roles(types::GuildRolesDiff),
+}
+
+/// Options for a gateway connection, see [`Gateway::spawn_with_options`].
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GatewayOptions {
+    #[serde(default)]
+    pub event_buffer: EventBufferConfig,
+    /// How many members a guild can have before only its online members are sent, between 50
+    /// and 250. Sent as [`GatewayIdentifyPayload::large_threshold`](types::GatewayIdentifyPayload::large_threshold)
+    #[serde(default)]
+    pub large_threshold: Option<i16>,
+    /// Whether to receive presence and typing events, sent as
+    /// [`GatewayIdentifyPayload::guild_subscriptions`](types::GatewayIdentifyPayload::guild_subscriptions)
+    #[serde(default)]
+    pub guild_subscriptions: Option<bool>,
+    /// The status to start the session with, sent as
+    /// [`GatewayIdentifyPayload::presence`](types::GatewayIdentifyPayload::presence)
+    #[serde(default)]
+    pub presence: Option<types::UpdatePresence>,
+    /// Enables the [`MessageCache`] of the connection if set
+    #[serde(default)]
+    pub message_cache: Option<MessageCacheConfig>,
+    /// Enables the [`MemberCache`] of the connection if set. The `Cache` keeps its members in it,
+    /// with the default configuration if this is not set
+    #[serde(default)]
+    pub member_cache: Option<MemberCacheConfig>,
+    /// Enables the `Cache` of the connection if set. Requires the `cache` feature
+    #[cfg(feature = "cache")]
+    #[serde(default)]
+    pub cache: bool,
+    /// Counts the emojis and stickers the user uses, see [`GatewayHandle::emoji_usage`]
+    #[serde(default)]
+    pub track_emoji_usage: bool,
+    /// Reconnects after losing the connection if set, instead of closing it
+    #[serde(default)]
+    pub reconnect: Option<ReconnectConfig>,
+    /// The encoding of payloads, negotiated through the `encoding` parameter of the gateway url
+    #[serde(default)]
+    pub encoding: GatewayEncoding,
+}
+
+/// The encoding of the payloads sent over a gateway connection.
+///
+/// The encoding is negotiated through the `encoding` query parameter of the gateway url, which
+/// [`Gateway::spawn_with_options`] sets from [`GatewayOptions::encoding`]. The default
+/// [`WebSocketTransport`] reads it from there, converting sent messages to the encoding.
+/// Received messages are kept in the encoding they were received in, and
+/// [`GatewayMessage::payload`] deserializes them accordingly.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum GatewayEncoding {
+    #[default]
+    Json,
+    /// Erlang's External Term Format, see `ErlpackCodec`
+    #[cfg(feature = "etf")]
+    Etf,
+}
+
+impl GatewayEncoding {
+    /// The value of the `encoding` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GatewayEncoding::Json => "json",
+            #[cfg(feature = "etf")]
+            GatewayEncoding::Etf => "etf",
+        }
+    }
+
+    /// Returns the encoding requested by the `encoding` query parameter of the url, JSON if it
+    /// is not set or not supported.
+    pub fn from_url(url: &str) -> Self {
+        let Some((_, query)) = url.split_once('?') else {
+            return GatewayEncoding::Json;
+        };
+        match query
+            .split('&')
+            .find_map(|parameter| parameter.strip_prefix("encoding="))
+        {
+            #[cfg(feature = "etf")]
+            Some("etf") => GatewayEncoding::Etf,
+            _ => GatewayEncoding::Json,
+        }
+    }
+
+    /// Sets the `encoding` query parameter of the url to this encoding.
+    pub fn apply_to_url(&self, url: &str) -> String {
+        let parameter = format!("encoding={}", self.as_str());
+        let Some((base, query)) = url.split_once('?') else {
+            return format!("{url}?{parameter}");
+        };
+        let mut parameters: Vec<&str> = query
+            .split('&')
+            .filter(|parameter| !parameter.is_empty() && !parameter.starts_with("encoding="))
+            .collect();
+        parameters.push(&parameter);
+        format!("{base}?{}", parameters.join("&"))
+    }
+}
+
+impl GatewayOptions {
+    /// Sets the identify options on the payload, where the payload does not set them itself.
+    pub fn apply_to_identify(&self, identify: &mut types::GatewayIdentifyPayload) {
+        if identify.large_threshold.is_none() {
+            identify.large_threshold = self.large_threshold;
+        }
+        if identify.guild_subscriptions.is_none() {
+            identify.guild_subscriptions = self.guild_subscriptions;
+        }
+        if identify.presence.is_none() {
+            identify.presence = self.presence.clone();
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Gateway {
     events: Arc<Mutex<Events>>,
     heartbeat_handler: HeartbeatHandler,
     websocket_send: Arc<Mutex<Sink>>,
-    websocket_receive: Stream,
+    buffer: Arc<EventBuffer>,
     kill_send: tokio::sync::broadcast::Sender<()>,
     kill_receive: tokio::sync::broadcast::Receiver<()>,
     store: Arc<Mutex<HashMap<Snowflake, Arc<RwLock<ObservableObject>>>>>,
+    subscriptions: Arc<Mutex<SubscriptionManager>>,
+    shard: Arc<RwLock<Option<ShardInfo>>>,
+    state: Arc<watch::Sender<ConnectionState>>,
     url: String,
+    headers: http::HeaderMap,
+    transport: Arc<dyn WebSocketTransport>,
+    reconnect_config: Option<ReconnectConfig>,
+    /// Failed attempts to reconnect since the session was last established
+    reconnect_attempts: u32,
+    resume: Arc<RwLock<ResumeState>>,
+    /// Stops the task receiving from the current websocket, and tells when it has stopped
+    receive_task: Option<(oneshot::Sender<()>, oneshot::Receiver<()>)>,
+    /// Compared with bulk updates of guilds which are not observed, see
+    /// [`Gateway::diff_guild_lists`]
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<Cache>>,
 }
 
 impl Gateway {
+    /// Connects to the gateway at the given url and spawns the tasks handling the connection.
+    ///
+    /// Dropping the returned future before it completes aborts the connection attempt, without
+    /// leaving any tasks behind.
     #[allow(clippy::new_ret_no_self)]
     pub async fn spawn(websocket_url: String) -> Result<GatewayHandle, GatewayError> {
+        Gateway::spawn_with_headers(websocket_url, &http::HeaderMap::new()).await
+    }
+
+    /// Like [`Gateway::spawn`], but sends additional headers with the websocket handshake, for
+    /// example [`Instance::default_headers`](crate::instance::Instance::default_headers).
+    ///
+    /// Browsers do not allow setting handshake headers, so they are ignored on wasm.
+    pub async fn spawn_with_headers(
+        websocket_url: String,
+        headers: &http::HeaderMap,
+    ) -> Result<GatewayHandle, GatewayError> {
+        Gateway::spawn_with_transport(
+            websocket_url,
+            headers,
+            Arc::new(WebSocketBackend::default()),
+        )
+        .await
+    }
+
+    /// Like [`Gateway::spawn_with_headers`], but opens the websocket connection with the given
+    /// [`WebSocketTransport`] instead of the default backend.
+    pub async fn spawn_with_transport(
+        websocket_url: String,
+        headers: &http::HeaderMap,
+        transport: Arc<dyn WebSocketTransport>,
+    ) -> Result<GatewayHandle, GatewayError> {
+        Gateway::spawn_with_options(websocket_url, headers, transport, GatewayOptions::default())
+            .await
+    }
+
+    /// Like [`Gateway::spawn_with_transport`], but configures the connection with the given
+    /// [`GatewayOptions`].
+    pub async fn spawn_with_options(
+        websocket_url: String,
+        headers: &http::HeaderMap,
+        transport: Arc<dyn WebSocketTransport>,
+        options: GatewayOptions,
+    ) -> Result<GatewayHandle, GatewayError> {
+        let websocket_url = match options.encoding {
+            // Leave urls without an encoding as they are, JSON is the default
+            GatewayEncoding::Json if !websocket_url.contains("encoding=") => websocket_url,
+            encoding => encoding.apply_to_url(&websocket_url),
+        };
         let (websocket_send, mut websocket_receive) =
-            WebSocketBackend::connect(&websocket_url).await?;
+            transport.connect(&websocket_url, headers).await?;
 
         let shared_websocket_send = Arc::new(Mutex::new(websocket_send));
 
@@ -44,44 +228,123 @@ impl Gateway {
 
         // Wait for the first hello and then spawn both tasks so we avoid nested tasks
         // This automatically spawns the heartbeat task, but from the main thread
-        #[cfg(not(target_arch = "wasm32"))]
-        let msg: GatewayMessage = websocket_receive.next().await.unwrap().unwrap().into();
-        #[cfg(target_arch = "wasm32")]
-        let msg: GatewayMessage = websocket_receive.next().await.unwrap().into();
-        let gateway_payload: types::GatewayReceivePayload = serde_json::from_str(&msg.0).unwrap();
+        let gateway_hello = Gateway::receive_hello(&mut websocket_receive).await?;
 
-        if gateway_payload.op_code != GATEWAY_HELLO {
-            return Err(GatewayError::NonHelloOnInitiate {
-                opcode: gateway_payload.op_code,
-            });
+        let mut events = Events::default();
+        let sessions = Arc::new(RwLock::new(Vec::new()));
+        let session_tracker = Arc::new(SessionTracker {
+            sessions: sessions.clone(),
+        });
+        events.session.ready.subscribe(session_tracker.clone());
+        events.session.replace.subscribe(session_tracker);
+        let resume = Arc::new(RwLock::new(ResumeState::default()));
+        events.session.ready.subscribe(Arc::new(ResumeTracker {
+            state: resume.clone(),
+        }));
+        let message_cache = options.message_cache.map(|config| {
+            let message_cache = Arc::new(MessageCache::new(config));
+            events.message.create.subscribe(message_cache.clone());
+            events.message.update.subscribe(message_cache.clone());
+            events.message.delete.subscribe(message_cache.clone());
+            events.message.delete_bulk.subscribe(message_cache.clone());
+            message_cache
+        });
+        #[allow(unused_mut)]
+        let mut member_cache = options
+            .member_cache
+            .map(|config| Arc::new(MemberCache::new(config)));
+        #[cfg(feature = "cache")]
+        let cache = options.cache.then(|| {
+            // The cache keeps its members in the member cache, enabling it if needed
+            let members = member_cache
+                .get_or_insert_with(|| Arc::new(MemberCache::new(MemberCacheConfig::default())));
+            let cache = Arc::new(Cache::with_member_cache(members.clone()));
+            events.session.ready.subscribe(cache.clone());
+            events.guild.create.subscribe(cache.clone());
+            events.guild.update.subscribe(cache.clone());
+            events.guild.delete.subscribe(cache.clone());
+            events.guild.emojis_update.subscribe(cache.clone());
+            events.guild.stickers_update.subscribe(cache.clone());
+            events.channel.create.subscribe(cache.clone());
+            events.channel.update.subscribe(cache.clone());
+            events.channel.delete.subscribe(cache.clone());
+            events.thread.create.subscribe(cache.clone());
+            events.thread.update.subscribe(cache.clone());
+            events.thread.delete.subscribe(cache.clone());
+            events.guild.members_chunk.subscribe(cache.clone());
+            events.guild.member_add.subscribe(cache.clone());
+            events.guild.member_update.subscribe(cache.clone());
+            events.guild.member_remove.subscribe(cache.clone());
+            events.guild.role_create.subscribe(cache.clone());
+            events.guild.role_update.subscribe(cache.clone());
+            events.guild.role_delete.subscribe(cache.clone());
+            events.user.update.subscribe(cache.clone());
+            events.user.presence_update.subscribe(cache.clone());
+            cache
+        });
+        // Otherwise the member cache observes its events itself
+        #[cfg(feature = "cache")]
+        let members_observed = cache.is_some();
+        #[cfg(not(feature = "cache"))]
+        let members_observed = false;
+        if let Some(member_cache) = member_cache.as_ref().filter(|_| !members_observed) {
+            events.guild.members_chunk.subscribe(member_cache.clone());
+            events.guild.member_add.subscribe(member_cache.clone());
+            events.guild.member_update.subscribe(member_cache.clone());
+            events.guild.member_remove.subscribe(member_cache.clone());
+            events.guild.delete.subscribe(member_cache.clone());
+            events.user.presence_update.subscribe(member_cache.clone());
         }
-
-        info!("GW: Received Hello");
-
-        let gateway_hello: types::HelloData =
-            serde_json::from_str(gateway_payload.event_data.unwrap().get()).unwrap();
-
-        let events = Events::default();
+        let emoji_usage = options.track_emoji_usage.then(|| {
+            let tracker = Arc::new(EmojiUsageTracker {
+                user_id: RwLock::new(None),
+                usage: RwLock::new(EmojiUsage::default()),
+            });
+            events.session.ready.subscribe(tracker.clone());
+            events.message.create.subscribe(tracker.clone());
+            events.message.reaction_add.subscribe(tracker.clone());
+            tracker
+        });
         let shared_events = Arc::new(Mutex::new(events));
 
         let store = Arc::new(Mutex::new(HashMap::new()));
+        let subscriptions = Arc::new(Mutex::new(SubscriptionManager::default()));
+        let shard = Arc::new(RwLock::new(None));
+        let (state, _) = watch::channel(ConnectionState::Connecting);
+        let state = Arc::new(state);
+        let buffer = Arc::new(EventBuffer::new(options.event_buffer));
 
         let mut gateway = Gateway {
             events: shared_events.clone(),
             heartbeat_handler: HeartbeatHandler::new(
                 Duration::from_millis(gateway_hello.heartbeat_interval),
                 shared_websocket_send.clone(),
+                buffer.clone(),
                 kill_send.subscribe(),
             ),
             websocket_send: shared_websocket_send.clone(),
-            websocket_receive,
+            buffer: buffer.clone(),
             kill_send: kill_send.clone(),
             kill_receive: kill_send.subscribe(),
             store: store.clone(),
+            subscriptions: subscriptions.clone(),
+            shard: shard.clone(),
+            state: state.clone(),
             url: websocket_url.clone(),
+            headers: headers.clone(),
+            transport,
+            reconnect_config: options.reconnect,
+            reconnect_attempts: 0,
+            resume: resume.clone(),
+            receive_task: None,
+            #[cfg(feature = "cache")]
+            cache: cache.clone(),
         };
 
         // Now we can continuously check for messages in a different task, since we aren't going to receive another hello
+        // Receiving and handling happen in separate tasks, so slow observers do not stop us
+        // from reading the websocket, unless the buffer between them is full
+        gateway.spawn_receive_task(websocket_receive);
         #[cfg(not(target_arch = "wasm32"))]
         task::spawn(async move {
             gateway.gateway_listen_task().await;
@@ -97,10 +360,125 @@ impl Gateway {
             websocket_send: shared_websocket_send.clone(),
             kill_send: kill_send.clone(),
             store,
+            subscriptions,
+            shard,
+            state,
+            buffer,
+            options,
+            sessions,
+            message_cache,
+            member_cache,
+            #[cfg(feature = "cache")]
+            cache,
+            emoji_usage,
+            resume,
         })
     }
 
-    /// The main gateway listener task;
+    /// Like [`Gateway::spawn`], but fails with [`GatewayError::ConnectionTimedOut`] if the
+    /// connection is not established within the given duration.
+    pub async fn spawn_with_timeout(
+        websocket_url: String,
+        timeout: Duration,
+    ) -> Result<GatewayHandle, GatewayError> {
+        crate::with_timeout(timeout, Gateway::spawn(websocket_url))
+            .await
+            .unwrap_or(Err(GatewayError::ConnectionTimedOut))
+    }
+
+    /// Waits for the hello the gateway sends after connecting, which tells how often to heartbeat.
+    async fn receive_hello(
+        websocket_receive: &mut Stream,
+    ) -> Result<types::HelloData, GatewayError> {
+        let msg: GatewayMessage = websocket_receive
+            .next()
+            .await
+            .ok_or_else(|| GatewayError::CannotConnect {
+                error: "The connection was closed before receiving hello".to_string(),
+            })?
+            .map_err(|e| GatewayError::CannotConnect {
+                error: e.to_string(),
+            })?;
+        let gateway_payload = msg.payload().map_err(|_| GatewayError::Decode)?;
+
+        if gateway_payload.op_code != GATEWAY_HELLO {
+            return Err(GatewayError::NonHelloOnInitiate {
+                opcode: gateway_payload.op_code,
+            });
+        }
+
+        info!("GW: Received Hello");
+
+        // The heartbeat interval has to be the one the server sent us
+        gateway_payload
+            .event_data
+            .and_then(|data| data.parse().ok())
+            .ok_or(GatewayError::Decode)
+    }
+
+    /// Spawns the task reading messages from the given websocket into the event buffer.
+    fn spawn_receive_task(&mut self, websocket_receive: Stream) {
+        let (stop_send, stop_receive) = oneshot::channel();
+        let (stopped_send, stopped_receive) = oneshot::channel();
+        self.receive_task = Some((stop_send, stopped_receive));
+        let receive = Gateway::receive_task(
+            websocket_receive,
+            self.buffer.clone(),
+            self.kill_send.subscribe(),
+            stop_receive,
+            stopped_send,
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        task::spawn(receive);
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(receive);
+    }
+
+    /// Stops the task receiving from the current websocket, and waits until it has stopped, so
+    /// it does not buffer any more messages.
+    async fn stop_receiving(&mut self) {
+        if let Some((stop, stopped)) = self.receive_task.take() {
+            drop(stop);
+            stopped.await.ok();
+        }
+    }
+
+    /// Reads messages from the websocket into the event buffer, until the websocket breaks, the
+    /// buffer overflows with [`OverflowPolicy::Error`], or the task is stopped by dropping the
+    /// sender of `stop`.
+    async fn receive_task(
+        mut websocket_receive: Stream,
+        buffer: Arc<EventBuffer>,
+        mut kill_receive: tokio::sync::broadcast::Receiver<()>,
+        mut stop: oneshot::Receiver<()>,
+        _stopped: oneshot::Sender<()>,
+    ) {
+        loop {
+            let message = tokio::select! {
+                Ok(_) = kill_receive.recv() => break,
+                _ = &mut stop => break,
+                message = websocket_receive.next() => message,
+            };
+
+            let Some(Ok(message)) = message else {
+                buffer.close();
+                break;
+            };
+
+            tokio::select! {
+                Ok(_) = kill_receive.recv() => break,
+                _ = &mut stop => break,
+                buffered = buffer.push(message) => {
+                    if !buffered {
+                        break;
+                    }
+                }
+            }
+        }
+        log::trace!("GW: Closing receive task");
+    }
+
+    /// The main gateway listener task, handling the messages of the event buffer;
     pub async fn gateway_listen_task(&mut self) {
         loop {
             let msg;
@@ -110,25 +488,46 @@ impl Gateway {
                     log::trace!("GW: Closing listener task");
                     break;
                 }
-                message = self.websocket_receive.next() => {
+                message = self.buffer.pop() => {
                     msg = message;
                 }
             }
 
-            // PRETTYFYME: Remove inline conditional compiling
-            #[cfg(not(target_arch = "wasm32"))]
-            if let Some(Ok(message)) = msg {
-                self.handle_message(message.into()).await;
-                continue;
-            }
-            #[cfg(target_arch = "wasm32")]
-            if let Some(message) = msg {
-                self.handle_message(message.into()).await;
-                continue;
+            match msg {
+                Some(Ok(message)) => {
+                    self.handle_message(message).await;
+                    continue;
+                }
+                Some(Err(error)) => {
+                    let reconnected = self.reconnect(true).await;
+                    if !reconnected {
+                        warn!("GW: {}, connection will close..", error);
+                        self.state
+                            .send_replace(ConnectionState::Closed { code: None });
+                        self.close().await;
+                    }
+                    self.events.lock().await.error.notify(error).await;
+                    if reconnected {
+                        continue;
+                    }
+                    break;
+                }
+                None => {
+                    if self.reconnect(true).await {
+                        continue;
+                    }
+                }
             }
 
             // We couldn't receive the next message or it was an error, something is wrong with the websocket, close
             warn!("GW: Websocket is broken, stopping gateway");
+            self.state.send_if_modified(|state| {
+                if state.is_closed() {
+                    return false;
+                }
+                *state = ConnectionState::Closed { code: None };
+                true
+            });
             break;
         }
     }
@@ -136,7 +535,325 @@ impl Gateway {
     /// Closes the websocket connection and stops all tasks
     async fn close(&mut self) {
         self.kill_send.send(()).unwrap();
-        self.websocket_send.lock().await.close().await.unwrap();
+        // The websocket may already be broken
+        self.websocket_send.lock().await.close().await.ok();
+    }
+
+    /// Waits for the given duration, returning false if the connection is closed in the meantime.
+    async fn wait_unless_closed(&self, duration: Duration) -> bool {
+        let mut state = self.state.subscribe();
+        tokio::select! {
+            _ = state.wait_for(|state| state.is_closed()) => false,
+            () = crate::sleep(duration) => true,
+        }
+    }
+
+    /// Connects to the gateway again after the connection was lost, as configured by
+    /// [`GatewayOptions::reconnect`], and resumes the session if `resume` is true, or identifies
+    /// again otherwise. See [`Gateway::start_session`].
+    ///
+    /// Returns false if the connection should be closed instead, because reconnecting is not
+    /// configured, the connection has been closed, or all attempts have failed.
+    async fn reconnect(&mut self, resume: bool) -> bool {
+        let Some(config) = self.reconnect_config else {
+            return false;
+        };
+        if self.state.borrow().is_closed() {
+            return false;
+        }
+
+        self.stop_receiving().await;
+        self.websocket_send.lock().await.close().await.ok();
+
+        let resume_gateway_url = self.resume.read().unwrap().resume_gateway_url.clone();
+        let url = match resume_gateway_url {
+            // The resume url does not include the version and encoding we connected with
+            Some(url) if resume => match self.url.split_once('?') {
+                Some((_, query)) if !url.contains('?') => format!("{url}?{query}"),
+                _ => url,
+            },
+            _ => self.url.clone(),
+        };
+
+        while self.reconnect_attempts < config.max_retries {
+            self.reconnect_attempts += 1;
+            let attempt = self.reconnect_attempts;
+            self.state
+                .send_replace(ConnectionState::Reconnecting { attempt });
+            if !self.wait_unless_closed(config.backoff.delay(attempt)).await {
+                return false;
+            }
+
+            info!("GW: Reconnecting to {url} (attempt {attempt})..");
+            match self.connect(&url).await {
+                Ok(()) => {
+                    self.start_session(resume).await;
+                    return true;
+                }
+                Err(error) => warn!("GW: Couldn't reconnect ({error})"),
+            }
+        }
+
+        warn!(
+            "GW: Couldn't reconnect after {} attempts, connection will close..",
+            config.max_retries
+        );
+        false
+    }
+
+    /// Opens a new websocket connection in place of the current one, restarting the heartbeat and
+    /// receive tasks.
+    async fn connect(&mut self, url: &str) -> Result<(), GatewayError> {
+        let (websocket_send, mut websocket_receive) =
+            self.transport.connect(url, &self.headers).await?;
+        let gateway_hello = Gateway::receive_hello(&mut websocket_receive).await?;
+
+        *self.websocket_send.lock().await = websocket_send;
+        // Replacing the handler stops the previous heartbeat task
+        self.heartbeat_handler = HeartbeatHandler::new(
+            Duration::from_millis(gateway_hello.heartbeat_interval),
+            self.websocket_send.clone(),
+            self.buffer.clone(),
+            self.kill_send.subscribe(),
+        );
+        self.buffer.reopen();
+        self.spawn_receive_task(websocket_receive);
+        Ok(())
+    }
+
+    /// Resumes the session on the current connection if `resume` is true and a session has been
+    /// established, or sends the last identify again otherwise.
+    ///
+    /// If nothing has been identified with yet, the connection is left for the user to identify.
+    async fn start_session(&mut self, resume: bool) {
+        let (resume_payload, sequence_number, identify) = {
+            let state = self.resume.read().unwrap();
+            let resume_payload = if resume { state.to_resume() } else { None };
+            (
+                resume_payload,
+                state.sequence_number,
+                state.identify.clone(),
+            )
+        };
+
+        if let Some(resume_payload) = resume_payload {
+            // Heartbeats continue from the resumed sequence number
+            self.heartbeat_handler
+                .send
+                .send(HeartbeatThreadCommunication {
+                    sequence_number,
+                    op_code: None,
+                })
+                .await
+                .ok();
+
+            trace!("GW: Sending Resume..");
+            self.state.send_replace(ConnectionState::Resuming);
+            self.send_payload(GATEWAY_RESUME, &resume_payload).await;
+        } else if let Some(identify) = identify {
+            {
+                let mut state = self.resume.write().unwrap();
+                state.session_id = None;
+                state.sequence_number = None;
+            }
+
+            trace!("GW: Sending Identify..");
+            self.state.send_replace(ConnectionState::Identifying);
+            self.send_payload(GATEWAY_IDENTIFY, &identify).await;
+        } else {
+            self.state.send_replace(ConnectionState::Connecting);
+        }
+    }
+
+    /// Sends a payload with the given opcode on the current websocket.
+    async fn send_payload(&self, op_code: u8, data: &impl serde::Serialize) {
+        let gateway_payload = types::GatewaySendPayload {
+            op_code,
+            event_data: Some(serde_json::to_value(data).unwrap()),
+            sequence_number: None,
+        };
+
+        let payload_json = serde_json::to_string(&gateway_payload).unwrap();
+        let message = GatewayMessage::Text(payload_json);
+
+        if self
+            .websocket_send
+            .lock()
+            .await
+            .send(message)
+            .await
+            .is_err()
+        {
+            warn!("GW: Couldn't send opcode {op_code}, websocket seems broken");
+        }
+    }
+
+    /// Re-sends all guild subscriptions tracked by the [`SubscriptionManager`], if there are any.
+    ///
+    /// Called after a session has been resumed.
+    async fn resubscribe(&mut self) {
+        let subscriptions = self.subscriptions.lock().await;
+        if subscriptions.is_empty() {
+            return;
+        }
+
+        trace!("GW: Resending Guild Subscriptions Bulk..");
+
+        let gateway_payload = types::GatewaySendPayload {
+            op_code: GATEWAY_GUILD_SUBSCRIPTIONS_BULK,
+            event_data: Some(serde_json::to_value(subscriptions.to_bulk()).unwrap()),
+            sequence_number: None,
+        };
+        drop(subscriptions);
+
+        let payload_json = serde_json::to_string(&gateway_payload).unwrap();
+        let message = GatewayMessage::Text(payload_json);
+
+        if self
+            .websocket_send
+            .lock()
+            .await
+            .send(message)
+            .await
+            .is_err()
+        {
+            warn!("GW: Couldn't resend guild subscriptions, websocket seems broken");
+        }
+    }
+
+    /// Returns the observed object with the given id, if it is of type `T`.
+    async fn observed<T: 'static>(&self, id: Snowflake) -> Option<Shared<T>> {
+        let object = self.store.lock().await.get(&id)?.clone();
+        if !object.read().unwrap().is::<T>() {
+            return None;
+        }
+        let ptr = Arc::into_raw(object);
+        // SAFETY:
+        // - We have just checked that the typeid of the `dyn Any ...` matches that of `T`.
+        // - The reference count is taken over from the `Arc` which was turned into the pointer
+        Some(unsafe { Arc::from_raw(ptr as *const RwLock<T>) })
+    }
+
+    /// Compares the emojis, stickers or roles of a guild with a bulk update of them, so
+    /// observers can tell what changed without keeping copies of the lists themselves.
+    ///
+    /// Observed guilds are compared with the store, others with the `Cache` if it is enabled.
+    /// Emoji, sticker updates and role deletions are also applied to observed guilds here, as the
+    /// store does not handle them otherwise; the cache applies them itself.
+    async fn diff_guild_lists(
+        &self,
+        event_name: &str,
+        data: types::GatewayEventData<'_>,
+    ) -> Option<GuildListDiff> {
+        match event_name {
+            "GUILD_EMOJIS_UPDATE" => {
+                let update: types::GuildEmojisUpdate = data.parse().ok()?;
+                let cached: Vec<Emoji> = match self.observed::<Guild>(update.guild_id).await {
+                    Some(guild) => {
+                        let mut guild = guild.write().unwrap();
+                        let cached = guild
+                            .emojis
+                            .iter()
+                            .map(|emoji| emoji.read().unwrap().clone())
+                            .collect();
+                        guild.emojis = update
+                            .emojis
+                            .iter()
+                            .map(|emoji| emoji.clone().into_shared())
+                            .collect();
+                        cached
+                    }
+                    None => self
+                        .cached_guild(update.guild_id)?
+                        .emojis
+                        .iter()
+                        .map(|emoji| emoji.read().unwrap().clone())
+                        .collect(),
+                };
+                let emojis = ChangeSet::between(&cached, &update.emojis, |emoji| emoji.id);
+                (!emojis.is_empty()).then_some(GuildListDiff::Emojis(types::GuildEmojisDiff {
+                    guild_id: update.guild_id,
+                    emojis,
+                }))
+            }
+            "GUILD_STICKERS_UPDATE" => {
+                let update: types::GuildStickersUpdate = data.parse().ok()?;
+                let cached = match self.observed::<Guild>(update.guild_id).await {
+                    Some(guild) => guild
+                        .write()
+                        .unwrap()
+                        .stickers
+                        .replace(update.stickers.clone()),
+                    None => self.cached_guild(update.guild_id)?.stickers,
+                };
+                let stickers =
+                    ChangeSet::between(&cached.unwrap_or_default(), &update.stickers, |sticker| {
+                        sticker.id
+                    });
+                (!stickers.is_empty()).then_some(GuildListDiff::Stickers(
+                    types::GuildStickersDiff {
+                        guild_id: update.guild_id,
+                        stickers,
+                    },
+                ))
+            }
+            "GUILD_ROLE_CREATE" | "GUILD_ROLE_UPDATE" | "GUILD_ROLE_DELETE" => {
+                let (guild_id, role_id, role) = if event_name == "GUILD_ROLE_DELETE" {
+                    let delete: types::GuildRoleDelete = data.parse().ok()?;
+                    (delete.guild_id, delete.role_id, None)
+                } else {
+                    let update: GuildRoleUpdate = data.parse().ok()?;
+                    (update.guild_id, update.role.id, Some(update.role))
+                };
+                let cached: Vec<RoleObject> = match self.observed::<Guild>(guild_id).await {
+                    Some(guild) => {
+                        let mut guild = guild.write().unwrap();
+                        let cached = guild
+                            .roles
+                            .iter()
+                            .flatten()
+                            .map(|role| role.read().unwrap().clone())
+                            .collect();
+                        if role.is_none() {
+                            if let Some(roles) = guild.roles.as_mut() {
+                                roles.retain(|role| role.read().unwrap().id != role_id);
+                            }
+                        }
+                        cached
+                    }
+                    None => self
+                        .cached_guild(guild_id)?
+                        .roles
+                        .iter()
+                        .flatten()
+                        .map(|role| role.read().unwrap().clone())
+                        .collect(),
+                };
+                let mut updated: Vec<RoleObject> = cached
+                    .iter()
+                    .filter(|cached| cached.id != role_id)
+                    .cloned()
+                    .collect();
+                updated.extend(role);
+                let roles = ChangeSet::between(&cached, &updated, |role| role.id);
+                (!roles.is_empty()).then_some(GuildListDiff::Roles(types::GuildRolesDiff {
+                    guild_id,
+                    roles,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a guild along with its roles from the `Cache`, if it is enabled and has the guild.
+    #[cfg(feature = "cache")]
+    fn cached_guild(&self, guild_id: Snowflake) -> Option<Guild> {
+        self.cache.as_ref()?.full_guild(guild_id)
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn cached_guild(&self, _guild_id: Snowflake) -> Option<Guild> {
+        None
     }
 
     /// Deserializes and updates a dispatched event, when we already know its type;
@@ -158,19 +875,26 @@ impl Gateway {
 
     /// This handles a message as a websocket event and updates its events along with the events' observers
     pub async fn handle_message(&mut self, msg: GatewayMessage) {
-        if msg.0.is_empty() {
+        if msg.is_empty() {
             return;
         }
 
         let Ok(gateway_payload) = msg.payload() else {
             if let Some(error) = msg.error() {
-                warn!("GW: Received error {:?}, connection will close..", error);
-                self.close().await;
+                if error.is_reconnectable() && self.reconnect(error.is_resumable()).await {
+                    warn!("GW: Received error {:?}, reconnected", error);
+                } else {
+                    warn!("GW: Received error {:?}, connection will close..", error);
+                    self.state.send_replace(ConnectionState::Closed {
+                        code: error.close_code(),
+                    });
+                    self.close().await;
+                }
                 self.events.lock().await.error.notify(error).await;
             } else {
                 warn!(
                     "Message unrecognised: {:?}, please open an issue on the chorus github",
-                    msg.0
+                    msg
                 );
             }
             return;
@@ -187,13 +911,36 @@ impl Gateway {
 
                 trace!("Gateway: Received {event_name}");
 
+                if matches!(event_name.as_str(), "READY" | "RESUMED") {
+                    self.state.send_replace(ConnectionState::Ready);
+                    self.reconnect_attempts = 0;
+                }
+
+                {
+                    let events = self.events.lock().await;
+                    if events.dispatch.is_observed() {
+                        let dispatch = types::GatewayDispatch {
+                            shard: *self.shard.read().unwrap(),
+                            event_name: event_name.clone(),
+                            sequence_number: gateway_payload.sequence_number,
+                        };
+                        events.dispatch.notify(dispatch).await;
+                    }
+                }
+
+                // Compared with the store before the event updates it
+                let list_diff = match gateway_payload.event_data {
+                    Some(data) => self.diff_guild_lists(&event_name, data).await,
+                    None => None,
+                };
+
                 macro_rules! handle {
                     ($($name:literal => $($path:ident).+ $( $message_type:ty: $update_type:ty)?),*) => {
                         match event_name.as_str() {
                             $($name => {
                                 let event = &mut self.events.lock().await.$($path).+;
-                                let json = gateway_payload.event_data.unwrap().get();
-                                match serde_json::from_str(json) {
+                                let data = gateway_payload.event_data.unwrap();
+                                match data.parse() {
                                     Err(err) => warn!("Failed to parse gateway event {event_name} ({err})"),
                                     Ok(message) => {
                                         $(
@@ -216,7 +963,10 @@ impl Gateway {
                                                     // - The reference count is not being modified
                                                     let downcasted = unsafe { Arc::from_raw(ptr as *const RwLock<$update_type>).clone() };
                                                     drop(inner_object);
-                                                    message.set_json(json.to_string());
+                                                    match data.json() {
+                                                        Ok(json) => message.set_json(json.into_owned()),
+                                                        Err(err) => warn!("Failed to convert gateway event {event_name} to JSON ({err})"),
+                                                    }
                                                     message.set_source_url(self.url.clone());
                                                     message.update(downcasted.clone());
                                                 } else {
@@ -228,10 +978,12 @@ impl Gateway {
                                     }
                                 }
                             },)*
-                            "RESUMED" => (),
+                            "RESUMED" => {
+                                self.resubscribe().await;
+                            },
                             "SESSIONS_REPLACE" => {
-                                let result: Result<Vec<types::Session>, serde_json::Error> =
-                                    serde_json::from_str(gateway_payload.event_data.unwrap().get());
+                                let result: Result<Vec<types::Session>, GatewayError> =
+                                    gateway_payload.event_data.unwrap().parse();
                                 match result {
                                     Err(err) => {
                                         warn!(
@@ -255,80 +1007,16 @@ impl Gateway {
                     };
                 }
 
-                // See https://discord.com/developers/docs/topics/gateway-events#receive-events
-                // "Some" of these are undocumented
-                handle!(
-                    "READY" => session.ready,
-                    "READY_SUPPLEMENTAL" => session.ready_supplemental,
-                    "APPLICATION_COMMAND_PERMISSIONS_UPDATE" => application.command_permissions_update,
-                    "AUTO_MODERATION_RULE_CREATE" =>auto_moderation.rule_create,
-                    "AUTO_MODERATION_RULE_UPDATE" =>auto_moderation.rule_update AutoModerationRuleUpdate: AutoModerationRule,
-                    "AUTO_MODERATION_RULE_DELETE" => auto_moderation.rule_delete,
-                    "AUTO_MODERATION_ACTION_EXECUTION" => auto_moderation.action_execution,
-                    "CHANNEL_CREATE" => channel.create ChannelCreate: Guild,
-                    "CHANNEL_UPDATE" => channel.update ChannelUpdate: Channel,
-                    "CHANNEL_UNREAD_UPDATE" => channel.unread_update,
-                    "CHANNEL_DELETE" => channel.delete ChannelDelete: Guild,
-                    "CHANNEL_PINS_UPDATE" => channel.pins_update,
-                    "CALL_CREATE" => call.create,
-                    "CALL_UPDATE" => call.update,
-                    "CALL_DELETE" => call.delete,
-                    "THREAD_CREATE" => thread.create, // TODO
-                    "THREAD_UPDATE" => thread.update ThreadUpdate: Channel,
-                    "THREAD_DELETE" => thread.delete, // TODO
-                    "THREAD_LIST_SYNC" => thread.list_sync, // TODO
-                    "THREAD_MEMBER_UPDATE" => thread.member_update, // TODO
-                    "THREAD_MEMBERS_UPDATE" => thread.members_update, // TODO
-                    "GUILD_CREATE" => guild.create, // TODO
-                    "GUILD_UPDATE" => guild.update, // TODO
-                    "GUILD_DELETE" => guild.delete, // TODO
-                    "GUILD_AUDIT_LOG_ENTRY_CREATE" => guild.audit_log_entry_create,
-                    "GUILD_BAN_ADD" => guild.ban_add, // TODO
-                    "GUILD_BAN_REMOVE" => guild.ban_remove, // TODO
-                    "GUILD_EMOJIS_UPDATE" => guild.emojis_update, // TODO
-                    "GUILD_STICKERS_UPDATE" => guild.stickers_update, // TODO
-                    "GUILD_INTEGRATIONS_UPDATE" => guild.integrations_update,
-                    "GUILD_MEMBER_ADD" => guild.member_add,
-                    "GUILD_MEMBER_REMOVE" => guild.member_remove,
-                    "GUILD_MEMBER_UPDATE" => guild.member_update, // TODO
-                    "GUILD_MEMBERS_CHUNK" => guild.members_chunk, // TODO
-                    "GUILD_ROLE_CREATE" => guild.role_create GuildRoleCreate: Guild,
-                    "GUILD_ROLE_UPDATE" => guild.role_update GuildRoleUpdate: RoleObject,
-                    "GUILD_ROLE_DELETE" => guild.role_delete, // TODO
-                    "GUILD_SCHEDULED_EVENT_CREATE" => guild.role_scheduled_event_create, // TODO
-                    "GUILD_SCHEDULED_EVENT_UPDATE" => guild.role_scheduled_event_update, // TODO
-                    "GUILD_SCHEDULED_EVENT_DELETE" => guild.role_scheduled_event_delete, // TODO
-                    "GUILD_SCHEDULED_EVENT_USER_ADD" => guild.role_scheduled_event_user_add,
-                    "GUILD_SCHEDULED_EVENT_USER_REMOVE" => guild.role_scheduled_event_user_remove,
-                    "PASSIVE_UPDATE_V1" => guild.passive_update_v1, // TODO
-                    "INTEGRATION_CREATE" => integration.create, // TODO
-                    "INTEGRATION_UPDATE" => integration.update, // TODO
-                    "INTEGRATION_DELETE" => integration.delete, // TODO
-                    "INTERACTION_CREATE" => interaction.create, // TODO
-                    "INVITE_CREATE" => invite.create, // TODO
-                    "INVITE_DELETE" => invite.delete, // TODO
-                    "MESSAGE_CREATE" => message.create,
-                    "MESSAGE_UPDATE" => message.update, // TODO
-                    "MESSAGE_DELETE" => message.delete,
-                    "MESSAGE_DELETE_BULK" => message.delete_bulk,
-                    "MESSAGE_REACTION_ADD" => message.reaction_add, // TODO
-                    "MESSAGE_REACTION_REMOVE" => message.reaction_remove, // TODO
-                    "MESSAGE_REACTION_REMOVE_ALL" => message.reaction_remove_all, // TODO
-                    "MESSAGE_REACTION_REMOVE_EMOJI" => message.reaction_remove_emoji, // TODO
-                    "MESSAGE_ACK" => message.ack,
-                    "PRESENCE_UPDATE" => user.presence_update, // TODO
-                    "RELATIONSHIP_ADD" => relationship.add,
-                    "RELATIONSHIP_REMOVE" => relationship.remove,
-                    "STAGE_INSTANCE_CREATE" => stage_instance.create,
-                    "STAGE_INSTANCE_UPDATE" => stage_instance.update, // TODO
-                    "STAGE_INSTANCE_DELETE" => stage_instance.delete,
-                    "TYPING_START" => user.typing_start,
-                    "USER_UPDATE" => user.update, // TODO
-                    "USER_GUILD_SETTINGS_UPDATE" => user.guild_settings_update,
-                    "VOICE_STATE_UPDATE" => voice.state_update, // TODO
-                    "VOICE_SERVER_UPDATE" => voice.server_update,
-                    "WEBHOOKS_UPDATE" => webhooks.update
-                );
+                dispatch_events!(handle);
+
+                if let Some(list_diff) = list_diff {
+                    let events = &mut self.events.lock().await.guild;
+                    match list_diff {
+                        GuildListDiff::Emojis(diff) => events.emojis_diff.notify(diff).await,
+                        GuildListDiff::Stickers(diff) => events.stickers_diff.notify(diff).await,
+                        GuildListDiff::Roles(diff) => events.roles_diff.notify(diff).await,
+                    }
+                }
             }
             // We received a heartbeat from the server
             // "Discord may send the app a Heartbeat (opcode 1) event, in which case the app should send a Heartbeat event immediately."
@@ -346,7 +1034,7 @@ impl Gateway {
                     .send
                     .send(heartbeat_communication)
                     .await
-                    .unwrap();
+                    .ok();
             }
             GATEWAY_RECONNECT => {
                 trace!("GW: Received Reconnect");
@@ -360,23 +1048,29 @@ impl Gateway {
                     .reconnect
                     .notify(reconnect)
                     .await;
+
+                if self.reconnect_config.is_some() && !self.reconnect(true).await {
+                    self.state
+                        .send_replace(ConnectionState::Closed { code: None });
+                    self.close().await;
+                }
             }
             GATEWAY_INVALID_SESSION => {
                 trace!("GW: Received Invalid Session");
 
                 let mut resumable: bool = false;
 
-                if let Some(raw_value) = gateway_payload.event_data {
-                    if let Ok(deserialized) = serde_json::from_str(raw_value.get()) {
-                        resumable = deserialized;
-                    } else {
-                        warn!("Failed to parse part of INVALID_SESSION ('{}' as bool), assuming non-resumable", raw_value.get());
+                if let Some(data) = gateway_payload.event_data {
+                    match data.parse() {
+                        Ok(deserialized) => resumable = deserialized,
+                        Err(err) => warn!("Failed to parse part of INVALID_SESSION as bool ({err}), assuming non-resumable"),
                     }
                 } else {
                     warn!("Failed to parse part of INVALID_SESSION ('d' missing), assuming non-resumable");
                 }
 
                 let invalid_session = GatewayInvalidSession { resumable };
+                self.state.send_replace(ConnectionState::Connecting);
 
                 self.events
                     .lock()
@@ -385,6 +1079,24 @@ impl Gateway {
                     .invalid
                     .notify(invalid_session)
                     .await;
+
+                if self.reconnect_config.is_none() {
+                    return;
+                }
+                if resumable {
+                    if !self.reconnect(true).await {
+                        self.state
+                            .send_replace(ConnectionState::Closed { code: None });
+                        self.close().await;
+                    }
+                } else {
+                    // Identifying right away may be rate limited, so wait a random 1 to 5 seconds
+                    // as recommended
+                    let delay = Duration::from_millis(rand::thread_rng().gen_range(1000..=5000));
+                    if self.wait_unless_closed(delay).await {
+                        self.start_session(false).await;
+                    }
+                }
             }
             // Starts our heartbeat
             // We should have already handled this in gateway init
@@ -405,7 +1117,7 @@ impl Gateway {
                     .send
                     .send(heartbeat_communication)
                     .await
-                    .unwrap();
+                    .ok();
             }
             GATEWAY_IDENTIFY
             | GATEWAY_UPDATE_PRESENCE
@@ -413,7 +1125,8 @@ impl Gateway {
             | GATEWAY_RESUME
             | GATEWAY_REQUEST_GUILD_MEMBERS
             | GATEWAY_CALL_SYNC
-            | GATEWAY_LAZY_REQUEST => {
+            | GATEWAY_LAZY_REQUEST
+            | GATEWAY_GUILD_SUBSCRIPTIONS_BULK => {
                 info!(
                     "Received unexpected opcode ({}) for current state. This might be due to a faulty server implementation and is likely not the fault of chorus.",
                     gateway_payload.op_code
@@ -426,6 +1139,7 @@ impl Gateway {
 
         // If we we received a seq number we should let it know
         if let Some(seq_num) = gateway_payload.sequence_number {
+            self.resume.write().unwrap().sequence_number = Some(seq_num);
             let heartbeat_communication = HeartbeatThreadCommunication {
                 sequence_number: Some(seq_num),
                 // Op code is irrelevant here
@@ -436,7 +1150,7 @@ impl Gateway {
                 .send
                 .send(heartbeat_communication)
                 .await
-                .unwrap();
+                .ok();
         }
     }
 }
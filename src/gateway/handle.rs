@@ -4,6 +4,7 @@
 
 use futures_util::SinkExt;
 use log::*;
+use tokio::sync::watch;
 
 use std::fmt::Debug;
 
@@ -22,6 +23,21 @@ pub struct GatewayHandle {
     /// Tells gateway tasks to close
     pub(super) kill_send: tokio::sync::broadcast::Sender<()>,
     pub(crate) store: Arc<Mutex<HashMap<Snowflake, Arc<RwLock<ObservableObject>>>>>,
+    /// The guild subscriptions sent on this connection, re-sent after resuming
+    pub subscriptions: Arc<Mutex<SubscriptionManager>>,
+    /// The shard this connection identified as
+    pub(super) shard: Arc<RwLock<Option<ShardInfo>>>,
+    pub(super) state: Arc<watch::Sender<ConnectionState>>,
+    pub(super) buffer: Arc<EventBuffer>,
+    /// The options the connection was spawned with
+    pub(super) options: GatewayOptions,
+    pub(super) sessions: Arc<RwLock<Vec<types::Session>>>,
+    pub(super) message_cache: Option<Arc<MessageCache>>,
+    pub(super) member_cache: Option<Arc<MemberCache>>,
+    #[cfg(feature = "cache")]
+    pub(super) cache: Option<Arc<Cache>>,
+    pub(super) emoji_usage: Option<Arc<EmojiUsageTracker>>,
+    pub(super) resume: Arc<RwLock<ResumeState>>,
 }
 
 impl GatewayHandle {
@@ -34,12 +50,12 @@ impl GatewayHandle {
         };
 
         let payload_json = serde_json::to_string(&gateway_payload).unwrap();
-        let message = GatewayMessage(payload_json);
+        let message = GatewayMessage::Text(payload_json);
 
         self.websocket_send
             .lock()
             .await
-            .send(message.into())
+            .send(message)
             .await
             .unwrap();
     }
@@ -84,11 +100,14 @@ impl GatewayHandle {
             *downcasted.write().unwrap() = watched_object;
             downcasted
         } else {
-            let id = object.read().unwrap().id();
+            // Inserted while the store is still locked, so concurrent observers of the same object
+            // share it. Observing the children of the object locks the store again
             let object = object.read().unwrap().clone();
-            let object = object.clone().watch_whole(self).await;
-            let wrapped = Arc::new(RwLock::new(object));
+            let wrapped = Arc::new(RwLock::new(object.clone()));
             store.insert(id, wrapped.clone());
+            drop(store);
+            let object = object.watch_whole(self).await;
+            *wrapped.write().unwrap() = object;
             wrapped
         }
     }
@@ -104,8 +123,127 @@ impl GatewayHandle {
         object
     }
 
+    /// Returns a copy of the observed object with the given id, if an object of type `T` with that
+    /// id is being observed. See [`GatewayHandle::observe`].
+    pub async fn get_observed<T: Clone + 'static>(&self, id: Snowflake) -> Option<T> {
+        let object = self.store.lock().await.get(&id)?.clone();
+        let object = object.read().unwrap();
+        object.downcast_ref::<T>().cloned()
+    }
+
+    /// Returns the shard this connection has identified as, if it has identified as one.
+    ///
+    /// Dispatched events are tagged with it, see [`types::GatewayDispatch`].
+    pub fn shard(&self) -> Option<ShardInfo> {
+        *self.shard.read().unwrap()
+    }
+
+    /// Returns the current state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// Returns a receiver which is notified whenever the state of the connection changes.
+    pub fn watch_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Returns the id of the session established on this connection, which is needed to resume
+    /// it, see [`GatewayHandle::send_resume`].
+    pub fn session_id(&self) -> Option<String> {
+        self.resume.read().unwrap().session_id.clone()
+    }
+
+    /// Returns what is needed to resume the session established on this connection on another
+    /// connection, see [`GatewayHandle::resume_session`].
+    ///
+    /// None until the session has been established and an event has been received.
+    pub fn session_state(&self) -> Option<GatewaySessionState> {
+        let resume = self.resume.read().unwrap();
+        Some(GatewaySessionState {
+            session_id: resume.session_id.clone()?,
+            sequence_number: resume.sequence_number?,
+            resume_gateway_url: resume.resume_gateway_url.clone(),
+        })
+    }
+
+    /// Returns the sessions of the user on all of their devices, including the summarizing
+    /// session, as last sent by the gateway.
+    ///
+    /// Only sent to user accounts. Empty until the session has been established.
+    pub fn sessions(&self) -> Vec<types::Session> {
+        self.sessions.read().unwrap().clone()
+    }
+
+    /// Returns the cache of recently sent messages, if it has been enabled through
+    /// [`GatewayOptions::message_cache`].
+    pub fn message_cache(&self) -> Option<Arc<MessageCache>> {
+        self.message_cache.clone()
+    }
+
+    /// Returns the cache of guild members and their presences, if it has been enabled through
+    /// [`GatewayOptions::member_cache`] or `GatewayOptions::cache`.
+    pub fn member_cache(&self) -> Option<Arc<MemberCache>> {
+        self.member_cache.clone()
+    }
+
+    /// Returns the connection's [`Cache`], if it has been enabled with [`GatewayOptions::cache`].
+    #[cfg(feature = "cache")]
+    pub fn cache(&self) -> Option<Arc<Cache>> {
+        self.cache.clone()
+    }
+
+    /// Returns the emojis and stickers the user has used since connecting, if tracking them has
+    /// been enabled through [`GatewayOptions::track_emoji_usage`].
+    pub fn emoji_usage(&self) -> Option<EmojiUsage> {
+        self.emoji_usage
+            .as_ref()
+            .map(|tracker| tracker.usage.read().unwrap().clone())
+    }
+
+    /// Replaces the tracked emoji usage, for example with the usage saved in a previous session.
+    ///
+    /// Does nothing if tracking has not been enabled through
+    /// [`GatewayOptions::track_emoji_usage`].
+    pub fn set_emoji_usage(&self, usage: EmojiUsage) {
+        if let Some(tracker) = &self.emoji_usage {
+            *tracker.usage.write().unwrap() = usage;
+        }
+    }
+
+    /// Returns how many received messages are waiting to be handled, see [`EventBufferConfig`].
+    pub fn buffered_events(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns how many received messages have been dropped because the event buffer was full,
+    /// see [`OverflowPolicy::DropOldest`].
+    pub fn dropped_events(&self) -> u64 {
+        self.buffer.dropped()
+    }
+
+    /// Waits until the session is established, see [`ConnectionState::Ready`].
+    ///
+    /// Returns false if the connection is closed before that.
+    pub async fn wait_until_ready(&self) -> bool {
+        let mut receiver = self.watch_state();
+        let state = receiver
+            .wait_for(|state| state.is_ready() || state.is_closed())
+            .await
+            .map(|state| *state);
+        state.map_or(false, |state| state.is_ready())
+    }
+
     /// Sends an identify event to the gateway
-    pub async fn send_identify(&self, to_send: types::GatewayIdentifyPayload) {
+    ///
+    /// The identify options of the connection's [`GatewayOptions`] are sent, unless the payload
+    /// sets them itself. The payload is sent again if the connection has to start a new session
+    /// after reconnecting, see [`GatewayOptions::reconnect`].
+    pub async fn send_identify(&self, mut to_send: types::GatewayIdentifyPayload) {
+        self.options.apply_to_identify(&mut to_send);
+        *self.shard.write().unwrap() = to_send.shard;
+        self.resume.write().unwrap().identify = Some(to_send.clone());
+        self.state.send_replace(ConnectionState::Identifying);
         let to_send_value = serde_json::to_value(&to_send).unwrap();
 
         trace!("GW: Sending Identify..");
@@ -113,8 +251,22 @@ impl GatewayHandle {
         self.send_json_event(GATEWAY_IDENTIFY, to_send_value).await;
     }
 
+    /// Waits for the payload's shard to be allowed to identify by the [`IdentifyQueue`], then
+    /// sends the identify event.
+    ///
+    /// Payloads without a shard are treated as shard 0.
+    pub async fn send_identify_queued(
+        &self,
+        to_send: types::GatewayIdentifyPayload,
+        queue: &IdentifyQueue,
+    ) {
+        queue.wait(to_send.shard.map_or(0, |shard| shard.id)).await;
+        self.send_identify(to_send).await;
+    }
+
     /// Sends a resume event to the gateway
     pub async fn send_resume(&self, to_send: types::GatewayResume) {
+        self.state.send_replace(ConnectionState::Resuming);
         let to_send_value = serde_json::to_value(&to_send).unwrap();
 
         trace!("GW: Sending Resume..");
@@ -122,6 +274,33 @@ impl GatewayHandle {
         self.send_json_event(GATEWAY_RESUME, to_send_value).await;
     }
 
+    /// Resumes a session established on another connection, such as one saved with
+    /// [`GatewayHandle::session_state`] before the program was restarted.
+    ///
+    /// The identify payload is not sent, but kept like with [`GatewayHandle::send_identify`]: if
+    /// the server can not resume the session, a new one is identified with it.
+    pub async fn resume_session(
+        &self,
+        mut identify: types::GatewayIdentifyPayload,
+        session: GatewaySessionState,
+    ) {
+        self.options.apply_to_identify(&mut identify);
+        *self.shard.write().unwrap() = identify.shard;
+        let resume = types::GatewayResume {
+            token: identify.token.clone(),
+            session_id: session.session_id.clone(),
+            seq: session.sequence_number.to_string(),
+        };
+        {
+            let mut state = self.resume.write().unwrap();
+            state.identify = Some(identify);
+            state.session_id = Some(session.session_id);
+            state.sequence_number = Some(session.sequence_number);
+            state.resume_gateway_url = session.resume_gateway_url;
+        }
+        self.send_resume(resume).await;
+    }
+
     /// Sends an update presence event to the gateway
     pub async fn send_update_presence(&self, to_send: types::UpdatePresence) {
         let to_send_value = serde_json::to_value(&to_send).unwrap();
@@ -171,11 +350,26 @@ impl GatewayHandle {
             .await;
     }
 
+    /// Sends a Guild Subscriptions Bulk request and records it in [`Self::subscriptions`]
+    pub async fn send_guild_subscriptions_bulk(&self, to_send: types::GuildSubscriptionsBulk) {
+        self.subscriptions.lock().await.subscribe(&to_send);
+
+        let to_send_value = serde_json::to_value(&to_send).unwrap();
+
+        trace!("GW: Sending Guild Subscriptions Bulk..");
+
+        self.send_json_event(GATEWAY_GUILD_SUBSCRIPTIONS_BULK, to_send_value)
+            .await;
+    }
+
     /// Closes the websocket connection and stops all gateway tasks;
     ///
     /// Essentially pulls the plug on the gateway, leaving it possible to resume;
     pub async fn close(&self) {
-        self.kill_send.send(()).unwrap();
-        self.websocket_send.lock().await.close().await.unwrap();
+        self.state
+            .send_replace(ConnectionState::Closed { code: None });
+        // The gateway tasks may already have stopped, and the websocket may already be broken
+        self.kill_send.send(()).ok();
+        self.websocket_send.lock().await.close().await.ok();
     }
 }
@@ -25,9 +25,19 @@ use tokio::task;
 use super::*;
 use crate::types;
 
-/// The amount of time we wait for a heartbeat ack before resending our heartbeat in ms
+/// The amount of time the voice gateway waits for a heartbeat ack before resending its heartbeat
+/// in ms
 pub const HEARTBEAT_ACK_TIMEOUT: u64 = 2000;
 
+/// Returns how long to wait before sending the first heartbeat: the heartbeat interval, multiplied
+/// by a random jitter between 0 and 1, so that clients which connect at the same time do not
+/// heartbeat at the same time.
+///
+/// See <https://discord.com/developers/docs/topics/gateway#sending-heartbeats>
+pub fn first_heartbeat_delay(heartbeat_interval: Duration) -> Duration {
+    heartbeat_interval.mul_f64(rand::random::<f64>())
+}
+
 /// Handles sending heartbeats to the gateway in another thread
 #[allow(dead_code)] // FIXME: Remove this, once HeartbeatHandler is used
 #[derive(Debug)]
@@ -42,6 +52,7 @@ impl HeartbeatHandler {
     pub fn new(
         heartbeat_interval: Duration,
         websocket_tx: Arc<Mutex<Sink>>,
+        buffer: Arc<EventBuffer>,
         kill_rc: tokio::sync::broadcast::Receiver<()>,
     ) -> Self {
         let (send, receive) = tokio::sync::mpsc::channel(32);
@@ -49,11 +60,25 @@ impl HeartbeatHandler {
 
         #[cfg(not(target_arch = "wasm32"))]
         task::spawn(async move {
-            Self::heartbeat_task(websocket_tx, heartbeat_interval, receive, kill_receive).await;
+            Self::heartbeat_task(
+                websocket_tx,
+                buffer,
+                heartbeat_interval,
+                receive,
+                kill_receive,
+            )
+            .await;
         });
         #[cfg(target_arch = "wasm32")]
         wasm_bindgen_futures::spawn_local(async move {
-            Self::heartbeat_task(websocket_tx, heartbeat_interval, receive, kill_receive).await;
+            Self::heartbeat_task(
+                websocket_tx,
+                buffer,
+                heartbeat_interval,
+                receive,
+                kill_receive,
+            )
+            .await;
         });
 
         Self {
@@ -64,33 +89,46 @@ impl HeartbeatHandler {
 
     /// The main heartbeat task;
     ///
-    /// Can be killed by the kill broadcast;
+    /// Sends the first heartbeat after a [random delay](first_heartbeat_delay), and every
+    /// heartbeat interval after that, always with the last received sequence number;
+    ///
+    /// If a heartbeat has not been acknowledged by the time the next one is due, the connection
+    /// is considered a zombie: the event buffer is failed with
+    /// [`GatewayError::HeartbeatAckMissed`], so that the gateway reconnects and resumes;
+    ///
+    /// Can be killed by the kill broadcast, or by dropping the handler;
     /// If the websocket is closed, will die out next time it tries to send a heartbeat;
     pub async fn heartbeat_task(
         websocket_tx: Arc<Mutex<Sink>>,
+        buffer: Arc<EventBuffer>,
         heartbeat_interval: Duration,
         mut receive: Receiver<HeartbeatThreadCommunication>,
         mut kill_receive: tokio::sync::broadcast::Receiver<()>,
     ) {
-        let mut last_heartbeat_timestamp: Instant = Instant::now();
+        let mut next_heartbeat: Instant =
+            Instant::now() + first_heartbeat_delay(heartbeat_interval);
         let mut last_heartbeat_acknowledged = true;
         let mut last_seq_number: Option<u64> = None;
 
         loop {
-            let timeout = if last_heartbeat_acknowledged {
-                heartbeat_interval
-            } else {
-                // If the server hasn't acknowledged our heartbeat we should resend it
-                Duration::from_millis(HEARTBEAT_ACK_TIMEOUT)
-            };
-
             let mut should_send = false;
 
             tokio::select! {
-                () = sleep_until(last_heartbeat_timestamp + timeout) => {
+                () = sleep_until(next_heartbeat) => {
+                    if !last_heartbeat_acknowledged {
+                        warn!("GW: The last heartbeat was not acknowledged, the connection seems to be broken");
+                        buffer.fail(GatewayError::HeartbeatAckMissed);
+                        break;
+                    }
                     should_send = true;
                 }
-                Some(communication) = receive.recv() => {
+                communication = receive.recv() => {
+                    // The handler was dropped, for example because the gateway reconnected
+                    let Some(communication) = communication else {
+                        log::trace!("GW: Closing heartbeat task");
+                        break;
+                    };
+
                     // If we received a seq number update, use that as the last seq number
                     if communication.sequence_number.is_some() {
                         last_seq_number = communication.sequence_number;
@@ -126,16 +164,20 @@ impl HeartbeatHandler {
 
                 let heartbeat_json = serde_json::to_string(&heartbeat).unwrap();
 
-                let msg = GatewayMessage(heartbeat_json);
+                let msg = GatewayMessage::Text(heartbeat_json);
 
-                let send_result = websocket_tx.lock().await.send(msg.into()).await;
+                let send_result = websocket_tx.lock().await.send(msg).await;
                 if send_result.is_err() {
                     // We couldn't send, the websocket is broken
                     warn!("GW: Couldn't send heartbeat, websocket seems broken");
                     break;
                 }
 
-                last_heartbeat_timestamp = Instant::now();
+                // Heartbeats requested by the server do not shift the interval
+                let now = Instant::now();
+                if now >= next_heartbeat {
+                    next_heartbeat = now + heartbeat_interval;
+                }
                 last_heartbeat_acknowledged = false;
             }
         }
@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+
+/// How long a bucket of an [`IdentifyQueue`] waits between two identifies.
+const IDENTIFY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spaces out the identifies of the shards of a bot, so that they are not rejected for
+/// identifying too quickly.
+///
+/// Shards are put into `max_concurrency` buckets by `shard_id % max_concurrency`. Shards of
+/// different buckets may identify at the same time, while the shards of a single bucket identify
+/// one after another, 5 seconds apart.
+///
+/// Clones share the same buckets.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway#max-concurrency>
+#[derive(Debug, Clone)]
+pub struct IdentifyQueue {
+    /// When each bucket last identified
+    buckets: Arc<Vec<Mutex<Option<DateTime<Utc>>>>>,
+    interval: Duration,
+}
+
+impl IdentifyQueue {
+    /// Creates a queue for the given `max_concurrency`, as returned by the instance along with
+    /// the recommended amount of shards.
+    pub fn new(max_concurrency: u64) -> Self {
+        let buckets = (0..max_concurrency.max(1))
+            .map(|_| Mutex::new(None))
+            .collect();
+        Self {
+            buckets: Arc::new(buckets),
+            interval: IDENTIFY_INTERVAL,
+        }
+    }
+
+    /// Sets the time between two identifies in the same bucket. Defaults to 5 seconds.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// The amount of shards which may identify at the same time.
+    pub fn max_concurrency(&self) -> u64 {
+        self.buckets.len() as u64
+    }
+
+    /// Returns the bucket of the given shard.
+    pub fn bucket(&self, shard_id: u64) -> u64 {
+        shard_id % self.max_concurrency()
+    }
+
+    /// Waits until the given shard may identify, and reserves the slot for it.
+    ///
+    /// The identify should be sent right after this returns, see
+    /// [`GatewayHandle::send_identify_queued`](super::GatewayHandle::send_identify_queued).
+    pub async fn wait(&self, shard_id: u64) {
+        let mut last_identify = self.buckets[self.bucket(shard_id) as usize].lock().await;
+        if let Some(last) = *last_identify {
+            let elapsed = (Utc::now() - last).to_std().unwrap_or_default();
+            if elapsed < self.interval {
+                crate::sleep(self.interval - elapsed).await;
+            }
+        }
+        *last_identify = Some(Utc::now());
+    }
+}
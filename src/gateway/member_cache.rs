@@ -0,0 +1,301 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::errors::ChorusResult;
+use crate::instance::ChorusUser;
+use crate::types::{
+    GuildDelete, GuildMember, GuildMemberAdd, GuildMemberRemove, GuildMemberUpdate,
+    GuildMembersChunk, PresenceUpdate, UserStatus,
+};
+
+/// The default amount of offline members cached per guild, see [`MemberCacheConfig`].
+pub const DEFAULT_MAX_OFFLINE_MEMBERS: usize = 1000;
+
+/// Configures the [`MemberCache`] of a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MemberCacheConfig {
+    /// How many offline members are kept per guild, evicting the least recently used ones
+    /// first. Online members are always kept
+    pub max_offline_members_per_guild: usize,
+    /// Whether to keep the presences of offline members, which are only needed to tell when
+    /// they were last seen
+    pub keep_offline_presences: bool,
+}
+
+impl Default for MemberCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_offline_members_per_guild: DEFAULT_MAX_OFFLINE_MEMBERS,
+            keep_offline_presences: false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct GuildMemberCache {
+    /// By user id, with when they were last used
+    members: HashMap<Snowflake, (GuildMember, u64)>,
+    /// By user id
+    presences: HashMap<Snowflake, PresenceUpdate>,
+    /// The ids of the cached offline members, by when they were last used
+    offline: BTreeMap<u64, Snowflake>,
+}
+
+impl GuildMemberCache {
+    fn is_online(&self, user_id: Snowflake) -> bool {
+        self.presences
+            .get(&user_id)
+            .map(|presence| is_online(&presence.status))
+            .unwrap_or(false)
+    }
+}
+
+fn is_online(status: &UserStatus) -> bool {
+    !matches!(status, UserStatus::Offline | UserStatus::Invisible)
+}
+
+fn member_id(member: &GuildMember) -> Option<Snowflake> {
+    Some(member.user.as_ref()?.read().unwrap().id)
+}
+
+#[derive(Debug, Default)]
+struct MemberCacheState {
+    guilds: HashMap<Snowflake, GuildMemberCache>,
+    /// Increases every time a member is used, to order them by when they were last used
+    clock: u64,
+}
+
+/// The members and presences of every guild, keeping memory bounded in large guilds by only
+/// retaining the most recently used offline members.
+///
+/// Enabled by setting [`GatewayOptions::member_cache`], after which it is returned by
+/// [`GatewayHandle::member_cache`]. It is filled by member chunks, member and presence events,
+/// and by [`MemberCache::get_or_fetch`], which fetches evicted members again when they are
+/// needed.
+///
+/// Members are considered offline unless their cached presence says otherwise.
+#[derive(Debug)]
+pub struct MemberCache {
+    config: MemberCacheConfig,
+    state: RwLock<MemberCacheState>,
+}
+
+impl MemberCache {
+    pub fn new(config: MemberCacheConfig) -> Self {
+        Self {
+            config,
+            state: RwLock::new(MemberCacheState::default()),
+        }
+    }
+
+    pub fn config(&self) -> MemberCacheConfig {
+        self.config
+    }
+
+    /// Returns the cached member of a guild, marking it as recently used.
+    pub fn get(&self, guild_id: Snowflake, user_id: Snowflake) -> Option<GuildMember> {
+        let mut state = self.state.write().unwrap();
+        let clock = state.clock + 1;
+        let guild = state.guilds.get_mut(&guild_id)?;
+        let online = guild.is_online(user_id);
+        let (member, last_used) = guild.members.get_mut(&user_id)?;
+        let member = member.clone();
+        if !online {
+            guild.offline.remove(last_used);
+            guild.offline.insert(clock, user_id);
+        }
+        *last_used = clock;
+        state.clock = clock;
+        Some(member)
+    }
+
+    /// Returns the cached member of a guild, or fetches it if it is not cached, for example
+    /// because it has been evicted.
+    pub async fn get_or_fetch(
+        &self,
+        user: &mut ChorusUser,
+        guild_id: Snowflake,
+        user_id: Snowflake,
+    ) -> ChorusResult<GuildMember> {
+        if let Some(member) = self.get(guild_id, user_id) {
+            return Ok(member);
+        }
+        let member = GuildMember::get(user, guild_id, user_id).await?;
+        self.insert(guild_id, member.clone());
+        Ok(member)
+    }
+
+    /// Returns the cached presence of a guild member.
+    pub fn presence(&self, guild_id: Snowflake, user_id: Snowflake) -> Option<PresenceUpdate> {
+        self.state
+            .read()
+            .unwrap()
+            .guilds
+            .get(&guild_id)?
+            .presences
+            .get(&user_id)
+            .cloned()
+    }
+
+    /// Returns the cached members of a guild, in no particular order.
+    pub fn members(&self, guild_id: Snowflake) -> Vec<GuildMember> {
+        self.state
+            .read()
+            .unwrap()
+            .guilds
+            .get(&guild_id)
+            .map(|guild| {
+                guild
+                    .members
+                    .values()
+                    .map(|(member, _)| member.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns how many members of a guild are cached.
+    pub fn len(&self, guild_id: Snowflake) -> usize {
+        self.state
+            .read()
+            .unwrap()
+            .guilds
+            .get(&guild_id)
+            .map(|guild| guild.members.len())
+            .unwrap_or_default()
+    }
+
+    /// Returns true if no members of the guild are cached.
+    pub fn is_empty(&self, guild_id: Snowflake) -> bool {
+        self.len(guild_id) == 0
+    }
+
+    /// Forgets all cached members and presences.
+    pub fn clear(&self) {
+        self.state.write().unwrap().guilds.clear();
+    }
+
+    fn insert(&self, guild_id: Snowflake, member: GuildMember) {
+        let Some(user_id) = member_id(&member) else {
+            return;
+        };
+        let mut state = self.state.write().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        let guild = state.guilds.entry(guild_id).or_default();
+        if let Some((_, last_used)) = guild.members.insert(user_id, (member, clock)) {
+            guild.offline.remove(&last_used);
+        }
+        if !guild.is_online(user_id) {
+            guild.offline.insert(clock, user_id);
+            self.evict(guild);
+        }
+    }
+
+    fn update_presence(&self, guild_id: Snowflake, presence: PresenceUpdate) {
+        let user_id = presence.user.id;
+        let online = is_online(&presence.status);
+        let mut state = self.state.write().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        let guild = state.guilds.entry(guild_id).or_default();
+        if online || self.config.keep_offline_presences {
+            guild.presences.insert(user_id, presence);
+        } else {
+            guild.presences.remove(&user_id);
+        }
+        let Some((_, last_used)) = guild.members.get_mut(&user_id) else {
+            return;
+        };
+        guild.offline.remove(last_used);
+        if !online {
+            *last_used = clock;
+            guild.offline.insert(clock, user_id);
+            self.evict(guild);
+        }
+    }
+
+    fn remove(&self, guild_id: Snowflake, user_id: Snowflake) {
+        let mut state = self.state.write().unwrap();
+        let Some(guild) = state.guilds.get_mut(&guild_id) else {
+            return;
+        };
+        if let Some((_, last_used)) = guild.members.remove(&user_id) {
+            guild.offline.remove(&last_used);
+        }
+        guild.presences.remove(&user_id);
+    }
+
+    /// Evicts the least recently used offline members above the limit.
+    fn evict(&self, guild: &mut GuildMemberCache) {
+        while guild.offline.len() > self.config.max_offline_members_per_guild {
+            let Some((_, user_id)) = guild.offline.pop_first() else {
+                break;
+            };
+            guild.members.remove(&user_id);
+            guild.presences.remove(&user_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMembersChunk> for MemberCache {
+    async fn update(&self, data: &GuildMembersChunk) {
+        // Presences first, so that online members are not counted as offline
+        for presence in data.presences.iter().flatten() {
+            self.update_presence(data.guild_id, presence.clone());
+        }
+        for member in &data.members {
+            self.insert(data.guild_id, member.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberAdd> for MemberCache {
+    async fn update(&self, data: &GuildMemberAdd) {
+        self.insert(data.guild_id, data.member.clone());
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberUpdate> for MemberCache {
+    async fn update(&self, data: &GuildMemberUpdate) {
+        // Partial members are only applied to cached ones
+        let Some(mut member) = self.get(data.guild_id, data.user.id) else {
+            return;
+        };
+        data.apply(&mut member);
+        self.insert(data.guild_id, member);
+    }
+}
+
+#[async_trait]
+impl Observer<GuildMemberRemove> for MemberCache {
+    async fn update(&self, data: &GuildMemberRemove) {
+        self.remove(data.guild_id, data.user.id);
+    }
+}
+
+#[async_trait]
+impl Observer<PresenceUpdate> for MemberCache {
+    async fn update(&self, data: &PresenceUpdate) {
+        // Presences of friends are not sent for a guild
+        if let Some(guild_id) = data.guild_id {
+            self.update_presence(guild_id, data.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<GuildDelete> for MemberCache {
+    async fn update(&self, data: &GuildDelete) {
+        self.state.write().unwrap().guilds.remove(&data.guild.id);
+    }
+}
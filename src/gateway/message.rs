@@ -7,16 +7,74 @@ use crate::types;
 use super::*;
 
 /// Represents a message received from the gateway. This will be either a [types::GatewayReceivePayload], containing events, or a [GatewayError].
-/// This struct is used internally when handling messages.
-#[derive(Clone, Debug)]
-pub struct GatewayMessage(pub String);
+/// This enum is used internally when handling messages.
+///
+/// Messages are converted from websocket frames without copying their payload, and
+/// [payloads](GatewayMessage::payload) borrow their event data from the message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GatewayMessage {
+    /// A JSON payload, or the reason a connection was closed with
+    Text(String),
+    /// A payload in Erlang's External Term Format, see [`GatewayEncoding::Etf`]
+    #[cfg(feature = "etf")]
+    Etf(Vec<u8>),
+}
 
 impl GatewayMessage {
+    /// Creates a message from the payload of a binary frame, taking over the bytes.
+    ///
+    /// With the `etf` feature, terms in the External Term Format are kept as they are, and only
+    /// deserialized by [`GatewayMessage::payload`].
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        // The version byte of terms is never the first byte of valid UTF-8
+        #[cfg(feature = "etf")]
+        if bytes.first() == Some(&super::ETF_VERSION) {
+            return Self::Etf(bytes);
+        }
+        match String::from_utf8(bytes) {
+            Ok(text) => Self::Text(text),
+            Err(error) => Self::Text(String::from_utf8_lossy(error.as_bytes()).into_owned()),
+        }
+    }
+
+    /// Returns the text of the message, unless it is a term in the External Term Format.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            GatewayMessage::Text(text) => Some(text),
+            #[cfg(feature = "etf")]
+            GatewayMessage::Etf(_) => None,
+        }
+    }
+
+    /// Returns whether the message is empty, which is the case for frames without a payload,
+    /// such as pings.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            GatewayMessage::Text(text) => text.is_empty(),
+            #[cfg(feature = "etf")]
+            GatewayMessage::Etf(bytes) => bytes.is_empty(),
+        }
+    }
+
+    /// Creates a message from a close frame, so that [`GatewayMessage::error`] recognizes the
+    /// error the connection was closed with.
+    ///
+    /// Uses the close code if it is a gateway error code, otherwise the reason.
+    pub fn from_close(code: u16, reason: String) -> Self {
+        if (4000..5000).contains(&code) {
+            Self::Text(code.to_string())
+        } else {
+            Self::Text(reason)
+        }
+    }
+
     /// Parses the message as an error;
     /// Returns the error if successfully parsed, None if the message isn't an error
     pub fn error(&self) -> Option<GatewayError> {
+        // Errors are never sent as terms
+        let text = self.text()?;
         // Some error strings have dots on the end, which we don't care about
-        let processed_content = self.0.to_lowercase().replace('.', "");
+        let processed_content = text.to_lowercase().replace('.', "");
 
         match processed_content.as_str() {
             "unknown error" | "4000" => Some(GatewayError::Unknown),
@@ -39,9 +97,33 @@ impl GatewayMessage {
         }
     }
 
-    /// Parses the message as a payload;
+    /// Returns the opcode of the message, without deserializing the rest of the payload.
+    pub fn op_code(&self) -> Option<u8> {
+        #[derive(serde::Deserialize)]
+        struct OpCode {
+            op: u8,
+        }
+
+        let payload: Option<OpCode> = match self {
+            GatewayMessage::Text(text) => serde_json::from_str(text).ok(),
+            #[cfg(feature = "etf")]
+            GatewayMessage::Etf(bytes) => super::ErlpackCodec::decode(bytes).ok(),
+        };
+        payload.map(|payload| payload.op)
+    }
+
+    /// Parses the message as a payload in the encoding it was received in, borrowing the event
+    /// data from the message;
     /// Returns a result of deserializing
-    pub fn payload(&self) -> Result<types::GatewayReceivePayload, serde_json::Error> {
-        serde_json::from_str(&self.0)
+    pub fn payload(&self) -> Result<types::GatewayReceivePayload<'_>, GatewayError> {
+        match self {
+            GatewayMessage::Text(text) => {
+                serde_json::from_str(text).map_err(|e| GatewayError::InvalidPayload {
+                    error: e.to_string(),
+                })
+            }
+            #[cfg(feature = "etf")]
+            GatewayMessage::Etf(bytes) => super::ErlpackCodec::decode(bytes),
+        }
     }
 }
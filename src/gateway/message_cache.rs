@@ -0,0 +1,200 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::types::{Message, MessageCreate, MessageDelete, MessageDeleteBulk, MessageUpdate};
+
+/// The default amount of messages cached per channel, see [`MessageCacheConfig`].
+pub const DEFAULT_MESSAGE_CACHE_CAPACITY: usize = 100;
+
+/// The default amount of earlier versions kept per edited message, see [`MessageCacheConfig`].
+pub const DEFAULT_MESSAGE_CACHE_REVISIONS: usize = 10;
+
+/// Configures the [`MessageCache`] of a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MessageCacheConfig {
+    /// How many of the most recently sent messages are kept per channel, at least 1
+    pub capacity_per_channel: usize,
+    /// How many earlier versions are kept per edited message, dropping the oldest ones first
+    pub max_revisions: usize,
+}
+
+impl Default for MessageCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity_per_channel: DEFAULT_MESSAGE_CACHE_CAPACITY,
+            max_revisions: DEFAULT_MESSAGE_CACHE_REVISIONS,
+        }
+    }
+}
+
+/// A message of the [`MessageCache`], along with what it looked like before it was edited.
+#[derive(Debug, Clone)]
+pub struct CachedMessage {
+    /// The latest version of the message. For deleted messages, the version which was deleted
+    pub message: Message,
+    /// Earlier versions of the message, oldest first
+    pub revisions: Vec<Message>,
+    pub deleted: bool,
+}
+
+impl CachedMessage {
+    /// The oldest version of the message which is still cached.
+    pub fn original(&self) -> &Message {
+        self.revisions.first().unwrap_or(&self.message)
+    }
+
+    pub fn is_edited(&self) -> bool {
+        !self.revisions.is_empty()
+    }
+}
+
+/// The most recently sent messages of every channel, which keeps snapshots of them from before
+/// they were edited or deleted.
+///
+/// Enabled by setting [`GatewayOptions::message_cache`], after which it is returned by
+/// [`GatewayHandle::message_cache`]. It is updated before the observers of
+/// [`Events::message`](super::events::Events::message) are notified, so observers of
+/// [`MessageDelete`] can still look up the content of the deleted message.
+///
+/// Only messages sent while connected are cached, edits and deletions of other messages are
+/// ignored.
+#[derive(Debug)]
+pub struct MessageCache {
+    config: MessageCacheConfig,
+    channels: RwLock<HashMap<Snowflake, VecDeque<CachedMessage>>>,
+}
+
+impl MessageCache {
+    pub fn new(config: MessageCacheConfig) -> Self {
+        Self {
+            config: MessageCacheConfig {
+                capacity_per_channel: config.capacity_per_channel.max(1),
+                ..config
+            },
+            channels: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> MessageCacheConfig {
+        self.config
+    }
+
+    /// Returns the cached message with the given id, including deleted ones.
+    pub fn get(&self, channel_id: Snowflake, message_id: Snowflake) -> Option<CachedMessage> {
+        self.channels
+            .read()
+            .unwrap()
+            .get(&channel_id)?
+            .iter()
+            .find(|cached| cached.message.id == message_id)
+            .cloned()
+    }
+
+    /// Returns the cached messages of a channel, including deleted ones, oldest first.
+    pub fn channel(&self, channel_id: Snowflake) -> Vec<CachedMessage> {
+        self.channels
+            .read()
+            .unwrap()
+            .get(&channel_id)
+            .map(|messages| messages.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forgets all cached messages.
+    pub fn clear(&self) {
+        self.channels.write().unwrap().clear();
+    }
+
+    fn insert(&self, message: Message) {
+        let mut channels = self.channels.write().unwrap();
+        let messages = channels.entry(message.channel_id).or_default();
+        if messages.len() >= self.config.capacity_per_channel {
+            messages.pop_front();
+        }
+        messages.push_back(CachedMessage {
+            message,
+            revisions: Vec::new(),
+            deleted: false,
+        });
+    }
+
+    /// Applies an update to a cached message, keeping its previous version if the update
+    /// edited it, rather than for example only changing its flags.
+    fn edit(&self, update: &MessageUpdate) {
+        let mut channels = self.channels.write().unwrap();
+        let Some(cached) = channels
+            .get_mut(&update.message.channel_id)
+            .and_then(|messages| {
+                messages
+                    .iter_mut()
+                    .find(|cached| cached.message.id == update.message.id)
+            })
+        else {
+            return;
+        };
+        let mut message = cached.message.clone();
+        update.apply(&mut message);
+        let previous = std::mem::replace(&mut cached.message, message);
+        if !is_revision(&previous, &cached.message) {
+            return;
+        }
+        cached.revisions.push(previous);
+        if cached.revisions.len() > self.config.max_revisions {
+            cached.revisions.remove(0);
+        }
+    }
+
+    fn delete(&self, channel_id: Snowflake, message_ids: &[Snowflake]) {
+        let mut channels = self.channels.write().unwrap();
+        let Some(messages) = channels.get_mut(&channel_id) else {
+            return;
+        };
+        messages
+            .iter_mut()
+            .filter(|cached| message_ids.contains(&cached.message.id))
+            .for_each(|cached| cached.deleted = true);
+    }
+}
+
+/// Whether `edited` is an edit of `previous`, rather than the same message with for example
+/// different flags or reactions.
+fn is_revision(previous: &Message, edited: &Message) -> bool {
+    previous.edited_timestamp != edited.edited_timestamp
+        || previous.content != edited.content
+        || previous.attachments != edited.attachments
+        || previous.embeds != edited.embeds
+}
+
+#[async_trait]
+impl Observer<MessageCreate> for MessageCache {
+    async fn update(&self, data: &MessageCreate) {
+        self.insert(data.message.clone());
+    }
+}
+
+#[async_trait]
+impl Observer<MessageUpdate> for MessageCache {
+    async fn update(&self, data: &MessageUpdate) {
+        self.edit(data);
+    }
+}
+
+#[async_trait]
+impl Observer<MessageDelete> for MessageCache {
+    async fn update(&self, data: &MessageDelete) {
+        self.delete(data.channel_id, &[data.id]);
+    }
+}
+
+#[async_trait]
+impl Observer<MessageDeleteBulk> for MessageCache {
+    async fn update(&self, data: &MessageDeleteBulk) {
+        self.delete(data.channel_id, &data.ids);
+    }
+}
@@ -5,23 +5,59 @@
 use async_trait::async_trait;
 
 pub mod backends;
+pub mod buffer;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod dispatch;
+pub mod emoji_usage;
+#[cfg(feature = "etf")]
+pub mod etf;
 pub mod events;
+pub mod expressions;
 pub mod gateway;
 pub mod handle;
 pub mod heartbeat;
+pub mod identify_queue;
+pub mod member_cache;
 pub mod message;
+pub mod message_cache;
+pub mod reconnect;
+mod sessions;
+pub mod shard_manager;
+pub mod state;
+pub mod subscriptions;
+pub mod transport;
 
 pub use backends::*;
+pub use buffer::*;
+#[cfg(feature = "cache")]
+pub use cache::*;
+pub use dispatch::*;
+pub use emoji_usage::*;
+#[cfg(feature = "etf")]
+pub use etf::*;
+pub use expressions::*;
 pub use gateway::*;
 pub use handle::*;
 use heartbeat::*;
+pub use identify_queue::*;
+pub use member_cache::*;
 pub use message::*;
+pub use message_cache::*;
+pub use reconnect::*;
+use sessions::*;
+pub use shard_manager::*;
+pub use state::*;
+pub use subscriptions::*;
+pub use transport::*;
 
 use crate::errors::GatewayError;
-use crate::types::{Snowflake, WebSocketEvent};
+use crate::types::{ShardInfo, Snowflake, WebSocketEvent};
 
 use std::any::Any;
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 use tokio::sync::Mutex;
@@ -73,6 +109,12 @@ const GATEWAY_CALL_SYNC: u8 = 13;
 ///
 /// See [types::LazyRequest]
 const GATEWAY_LAZY_REQUEST: u8 = 14;
+/// Opcode sent to subscribe to events of multiple guilds at once
+///
+/// Supersedes [GATEWAY_LAZY_REQUEST] on newer clients
+///
+/// See [types::GuildSubscriptionsBulk]
+const GATEWAY_GUILD_SUBSCRIPTIONS_BULK: u8 = 37;
 
 pub type ObservableObject = dyn Send + Sync + Any;
 
@@ -114,6 +156,33 @@ impl<T: WebSocketEvent> GatewayEvent<T> {
         self.observers.push(observable);
     }
 
+    /// Subscribes an async closure to the GatewayEvent, which is called with a clone of every
+    /// event, so no [`Observer`] has to be implemented:
+    ///
+    /// ```no_run
+    /// # async fn example(gateway: chorus::gateway::GatewayHandle) {
+    /// gateway.events.lock().await.message.create.subscribe_fn(|event| async move {
+    ///     println!("{:?}", event.message.content);
+    /// });
+    /// # }
+    /// ```
+    ///
+    /// Returns the Observer calling the closure, which can be passed to
+    /// [`GatewayEvent::unsubscribe`].
+    pub fn subscribe_fn<F, Fut>(&mut self, callback: F) -> Arc<dyn Observer<T>>
+    where
+        T: Clone + 'static,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let observer: Arc<dyn Observer<T>> = Arc::new(FnObserver {
+            id: NEXT_FN_OBSERVER_ID.fetch_add(1, Ordering::Relaxed),
+            callback,
+        });
+        self.subscribe(observer.clone());
+        observer
+    }
+
     /// Unsubscribes an Observer from the GatewayEvent.
     pub fn unsubscribe(&mut self, observable: &dyn Observer<T>) {
         // .retain()'s closure retains only those elements of the vector, which have a different
@@ -121,6 +190,11 @@ impl<T: WebSocketEvent> GatewayEvent<T> {
         // The usage of the debug format to compare the generic T of observers is quite stupid, but the only thing to compare between them is T and if T == T they are the same
         // anddd there is no way to do that without using format
         let to_remove = format!("{:?}", observable);
+        self.unsubscribe_by_debug(&to_remove);
+    }
+
+    /// Unsubscribes the Observers whose debug representation equals `to_remove`.
+    pub(crate) fn unsubscribe_by_debug(&mut self, to_remove: &str) {
         self.observers
             .retain(|obs| format!("{:?}", obs) != to_remove);
     }
@@ -133,6 +207,33 @@ impl<T: WebSocketEvent> GatewayEvent<T> {
     }
 }
 
+static NEXT_FN_OBSERVER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Calls a closure for every event, see [`GatewayEvent::subscribe_fn`].
+struct FnObserver<F> {
+    /// Tells closure observers apart when unsubscribing
+    id: u64,
+    callback: F,
+}
+
+impl<F> std::fmt::Debug for FnObserver<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FnObserver({})", self.id)
+    }
+}
+
+#[async_trait]
+impl<T, F, Fut> Observer<T> for FnObserver<F>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(T) -> Fut + Send + Sync,
+    Fut: Future<Output = ()> + Send,
+{
+    async fn update(&self, data: &T) {
+        (self.callback)(data.clone()).await;
+    }
+}
+
 /// A type alias for [`Arc<RwLock<T>>`], used to make the public facing API concerned with
 /// Composite structs more ergonomic.
 /// ## Note
@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::types::{self, GatewayReady};
+
+/// How long to wait before each attempt to reconnect, see [`ReconnectConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Backoff {
+    /// Waits the same time before every attempt
+    Constant(Duration),
+    /// Waits `initial` before the first attempt, and twice as long before every following one,
+    /// up to `max`
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::Exponential {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns how long to wait before the given attempt, starting at 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match *self {
+            Backoff::Constant(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+                initial.checked_mul(factor).unwrap_or(max).min(max)
+            }
+        }
+    }
+}
+
+/// Configures how a connection is reestablished after it was lost, see
+/// [`GatewayOptions::reconnect`].
+///
+/// The gateway first tries to resume the session, so no events are missed. If the server
+/// invalidates the session instead, the last identify sent with
+/// [`GatewayHandle::send_identify`] is sent again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReconnectConfig {
+    /// How many times to try connecting again before closing the connection for good. Reset once
+    /// the session is established again
+    pub max_retries: u32,
+    #[serde(default)]
+    pub backoff: Backoff,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            backoff: Backoff::default(),
+        }
+    }
+}
+
+/// What is needed to resume a session on a new connection, for example after restarting the
+/// program. See [`GatewayHandle::session_state`] and [`GatewayHandle::resume_session`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GatewaySessionState {
+    pub session_id: String,
+    /// The sequence number of the last handled event
+    pub sequence_number: u64,
+    #[serde(default)]
+    pub resume_gateway_url: Option<String>,
+}
+
+/// What is needed to resume the session, or to start a new one, after reconnecting.
+#[derive(Debug, Default)]
+pub(super) struct ResumeState {
+    pub(super) session_id: Option<String>,
+    pub(super) resume_gateway_url: Option<String>,
+    /// The sequence number of the last handled event
+    pub(super) sequence_number: Option<u64>,
+    /// The last identify sent on the connection
+    pub(super) identify: Option<types::GatewayIdentifyPayload>,
+}
+
+impl ResumeState {
+    /// Returns the resume payload for the session, if a session has been established.
+    pub(super) fn to_resume(&self) -> Option<types::GatewayResume> {
+        Some(types::GatewayResume {
+            token: self.identify.as_ref()?.token.clone(),
+            session_id: self.session_id.clone()?,
+            seq: self.sequence_number?.to_string(),
+        })
+    }
+}
+
+/// Keeps the [`ResumeState`] of a connection up to date with the sessions it establishes.
+#[derive(Debug)]
+pub(super) struct ResumeTracker {
+    pub(super) state: Arc<RwLock<ResumeState>>,
+}
+
+#[async_trait]
+impl Observer<GatewayReady> for ResumeTracker {
+    async fn update(&self, data: &GatewayReady) {
+        let mut state = self.state.write().unwrap();
+        state.session_id = Some(data.session_id.clone());
+        state.resume_gateway_url = data.resume_gateway_url.clone();
+    }
+}
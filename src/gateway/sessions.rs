@@ -0,0 +1,29 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::*;
+use crate::types::{self, GatewayReady, SessionsReplace};
+
+/// Keeps the sessions returned by [`GatewayHandle::sessions`] up to date, with the sessions of
+/// `READY` and every `SESSIONS_REPLACE`.
+#[derive(Debug)]
+pub(super) struct SessionTracker {
+    pub(super) sessions: Arc<RwLock<Vec<types::Session>>>,
+}
+
+#[async_trait]
+impl Observer<GatewayReady> for SessionTracker {
+    async fn update(&self, data: &GatewayReady) {
+        if let Some(sessions) = &data.sessions {
+            *self.sessions.write().unwrap() = sessions.clone();
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<SessionsReplace> for SessionTracker {
+    async fn update(&self, data: &SessionsReplace) {
+        *self.sessions.write().unwrap() = data.sessions.clone();
+    }
+}
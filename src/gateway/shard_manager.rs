@@ -0,0 +1,194 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use futures_util::future::join_all;
+use tokio::sync::broadcast;
+
+use super::events::Events;
+use super::*;
+use crate::instance::Instance;
+use crate::types::{self, GatewayBotResponse, GatewayIdentifyPayload};
+
+/// How many dispatches [`ShardManager::dispatches`] keeps for receivers which fall behind.
+const DISPATCH_CHANNEL_CAPACITY: usize = 1024;
+
+/// Runs the shards of a bot, each on its own gateway connection.
+///
+/// Every shard receives the events of the guilds with
+/// [`ShardInfo::for_guild`](types::ShardInfo::for_guild) equal to it. The events of a single shard
+/// can be observed through its [`GatewayHandle`], those of all shards through
+/// [`ShardManager::subscribe_all`] and [`ShardManager::dispatches`].
+///
+/// # Example
+/// ```no_run
+/// # async fn run(user: &mut chorus::instance::ChorusUser) {
+/// use chorus::gateway::{IdentifyQueue, ShardManager};
+///
+/// let gateway_bot = user.get_gateway_bot().await.unwrap();
+/// let instance = user.belongs_to.read().unwrap().clone();
+/// let shards = ShardManager::spawn(&instance, &gateway_bot).await.unwrap();
+/// let queue = IdentifyQueue::new(gateway_bot.session_start_limit.max_concurrency);
+/// shards
+///     .identify(instance.identify_payload(user.token()), &queue)
+///     .await;
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShardManager {
+    /// By shard id
+    shards: Vec<GatewayHandle>,
+    dispatches: broadcast::Sender<types::GatewayDispatch>,
+}
+
+/// Forwards the dispatches of every shard to [`ShardManager::dispatches`].
+#[derive(Debug)]
+struct DispatchForwarder {
+    send: broadcast::Sender<types::GatewayDispatch>,
+}
+
+#[async_trait]
+impl Observer<types::GatewayDispatch> for DispatchForwarder {
+    async fn update(&self, data: &types::GatewayDispatch) {
+        // Nobody is listening
+        self.send.send(data.clone()).ok();
+    }
+}
+
+impl ShardManager {
+    /// Connects the recommended amount of shards to the gateway url returned by
+    /// [`ChorusUser::get_gateway_bot`](crate::instance::ChorusUser::get_gateway_bot), with the
+    /// handshake headers and gateway options of the instance, like [`Instance::spawn_gateway`].
+    ///
+    /// The shards still have to identify, see [`ShardManager::identify`].
+    pub async fn spawn(
+        instance: &Instance,
+        gateway_bot: &GatewayBotResponse,
+    ) -> Result<Self, GatewayError> {
+        ShardManager::spawn_with_options(
+            gateway_bot.url.clone(),
+            &instance.handshake_headers(),
+            Arc::new(WebSocketBackend::default()),
+            instance.gateway_options.clone(),
+            gateway_bot.shards,
+        )
+        .await
+    }
+
+    /// Like [`ShardManager::spawn`], but connects each shard like
+    /// [`Gateway::spawn_with_options`].
+    ///
+    /// Fails if any of the shards can not connect, closing the ones which did.
+    pub async fn spawn_with_options(
+        websocket_url: String,
+        headers: &http::HeaderMap,
+        transport: Arc<dyn WebSocketTransport>,
+        options: GatewayOptions,
+        shard_count: u64,
+    ) -> Result<Self, GatewayError> {
+        let mut shards = Vec::new();
+        for _ in 0..shard_count.max(1) {
+            let shard = Gateway::spawn_with_options(
+                websocket_url.clone(),
+                headers,
+                transport.clone(),
+                options.clone(),
+            )
+            .await;
+            match shard {
+                Ok(shard) => shards.push(shard),
+                Err(error) => {
+                    join_all(shards.iter().map(|shard| shard.close())).await;
+                    return Err(error);
+                }
+            }
+        }
+
+        let (dispatches, _) = broadcast::channel(DISPATCH_CHANNEL_CAPACITY);
+        let forwarder = Arc::new(DispatchForwarder {
+            send: dispatches.clone(),
+        });
+        for shard in &shards {
+            shard
+                .events
+                .lock()
+                .await
+                .dispatch
+                .subscribe(forwarder.clone());
+        }
+
+        Ok(Self { shards, dispatches })
+    }
+
+    /// Identifies every shard with the given payload, setting its
+    /// [`shard`](GatewayIdentifyPayload::shard), as fast as the queue allows.
+    ///
+    /// Returns once every shard has sent its identify.
+    pub async fn identify(&self, identify: GatewayIdentifyPayload, queue: &IdentifyQueue) {
+        let count = self.shard_count();
+        join_all(self.shards.iter().enumerate().map(|(id, shard)| {
+            let identify = GatewayIdentifyPayload {
+                shard: Some(types::ShardInfo::new(id as u64, count)),
+                ..identify.clone()
+            };
+            shard.send_identify_queued(identify, queue)
+        }))
+        .await;
+    }
+
+    pub fn shard_count(&self) -> u64 {
+        self.shards.len() as u64
+    }
+
+    /// Returns the shards, by shard id.
+    pub fn shards(&self) -> &[GatewayHandle] {
+        &self.shards
+    }
+
+    pub fn shard(&self, shard_id: u64) -> Option<&GatewayHandle> {
+        self.shards.get(shard_id as usize)
+    }
+
+    /// Returns the shard which receives the events of the given guild.
+    pub fn shard_for_guild(&self, guild_id: Snowflake) -> &GatewayHandle {
+        let shard = types::ShardInfo::for_guild(guild_id, self.shard_count());
+        &self.shards[shard.id as usize]
+    }
+
+    /// Calls the given function with the [`Events`] of every shard, to subscribe observers to
+    /// the events of all shards.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(shards: chorus::gateway::ShardManager, observer: std::sync::Arc<dyn chorus::gateway::Observer<chorus::types::MessageCreate>>) {
+    /// shards
+    ///     .subscribe_all(|events| events.message.create.subscribe(observer.clone()))
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn subscribe_all(&self, mut subscribe: impl FnMut(&mut Events)) {
+        for shard in &self.shards {
+            subscribe(&mut *shard.events.lock().await);
+        }
+    }
+
+    /// Returns a receiver of the dispatches of all shards, each tagged with its shard.
+    pub fn dispatches(&self) -> broadcast::Receiver<types::GatewayDispatch> {
+        self.dispatches.subscribe()
+    }
+
+    /// Waits until the session of every shard is established.
+    ///
+    /// Returns false if a shard is closed before that.
+    pub async fn wait_until_ready(&self) -> bool {
+        join_all(self.shards.iter().map(|shard| shard.wait_until_ready()))
+            .await
+            .into_iter()
+            .all(|ready| ready)
+    }
+
+    /// Closes the connections of all shards.
+    pub async fn close(&self) {
+        join_all(self.shards.iter().map(|shard| shard.close())).await;
+    }
+}
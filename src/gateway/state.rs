@@ -0,0 +1,42 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// The state of a gateway connection, see
+/// [`GatewayHandle::watch_state`](super::GatewayHandle::watch_state).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    /// The websocket is connecting or connected, but no session has been started yet.
+    ///
+    /// Also entered after the server invalidated the session.
+    #[default]
+    Connecting,
+    /// An identify has been sent, and the `READY` event has not been received yet.
+    Identifying,
+    /// The session is established, and events are being received.
+    Ready,
+    /// A resume has been sent, and the `RESUMED` event has not been received yet.
+    Resuming,
+    /// The connection is being reestablished after it was lost.
+    Reconnecting {
+        /// The number of the current attempt, starting at 1
+        attempt: u32,
+    },
+    /// The connection has been closed, and no more events are received.
+    Closed {
+        /// The close code, if the gateway closed the connection because of an error
+        code: Option<u16>,
+    },
+}
+
+impl ConnectionState {
+    /// Returns true if the session is established.
+    pub fn is_ready(&self) -> bool {
+        matches!(self, ConnectionState::Ready)
+    }
+
+    /// Returns true if the connection has been closed.
+    pub fn is_closed(&self) -> bool {
+        matches!(self, ConnectionState::Closed { .. })
+    }
+}
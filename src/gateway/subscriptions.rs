@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use crate::types::{GuildSubscription, GuildSubscriptionsBulk, Snowflake};
+
+/// Keeps track of which guilds a gateway connection has subscribed to via
+/// [`GuildSubscriptionsBulk`] (opcode 37).
+///
+/// The [`Gateway`](super::Gateway) uses this to re-send all active subscriptions after a session
+/// has been resumed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SubscriptionManager {
+    guilds: HashMap<Snowflake, GuildSubscription>,
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the subscriptions of a [`GuildSubscriptionsBulk`], merging them with the ones which
+    /// have already been sent for the same guilds.
+    pub fn subscribe(&mut self, bulk: &GuildSubscriptionsBulk) {
+        for (guild_id, subscription) in bulk.subscriptions.iter() {
+            self.guilds
+                .entry(*guild_id)
+                .or_default()
+                .merge(subscription.clone());
+        }
+    }
+
+    /// Forgets the subscription of a guild, for example after leaving it. Returns the removed
+    /// subscription, if there was one.
+    pub fn unsubscribe(&mut self, guild_id: Snowflake) -> Option<GuildSubscription> {
+        self.guilds.remove(&guild_id)
+    }
+
+    /// Returns the current subscription state of a guild.
+    pub fn get(&self, guild_id: Snowflake) -> Option<&GuildSubscription> {
+        self.guilds.get(&guild_id)
+    }
+
+    /// Returns true if any subscription has been sent for the given guild.
+    pub fn is_subscribed(&self, guild_id: Snowflake) -> bool {
+        self.guilds.contains_key(&guild_id)
+    }
+
+    /// Returns true if no guild has been subscribed to.
+    pub fn is_empty(&self) -> bool {
+        self.guilds.is_empty()
+    }
+
+    /// Forgets all subscriptions.
+    pub fn clear(&mut self) {
+        self.guilds.clear();
+    }
+
+    /// Builds a single [`GuildSubscriptionsBulk`] containing every tracked subscription.
+    pub fn to_bulk(&self) -> GuildSubscriptionsBulk {
+        GuildSubscriptionsBulk {
+            subscriptions: self.guilds.clone(),
+        }
+    }
+}
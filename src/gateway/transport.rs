@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::Debug;
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use http::HeaderMap;
+
+use super::GatewayMessage;
+use crate::errors::GatewayError;
+
+/// The sending half of a gateway connection, see [`WebSocketTransport`].
+pub type Sink = Pin<Box<dyn GatewaySink>>;
+
+/// The receiving half of a gateway connection, see [`WebSocketTransport`].
+///
+/// The connection is considered broken once the stream yields an error or ends.
+pub type Stream = Pin<Box<dyn GatewayStream>>;
+
+/// A [`futures_util::Sink`] of [`GatewayMessage`]s, implemented for every such sink.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait GatewaySink: futures_util::Sink<GatewayMessage, Error = GatewayError> + Send {}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: futures_util::Sink<GatewayMessage, Error = GatewayError> + Send> GatewaySink for T {}
+/// A [`futures_util::Sink`] of [`GatewayMessage`]s, implemented for every such sink.
+#[cfg(target_arch = "wasm32")]
+pub trait GatewaySink: futures_util::Sink<GatewayMessage, Error = GatewayError> {}
+#[cfg(target_arch = "wasm32")]
+impl<T: futures_util::Sink<GatewayMessage, Error = GatewayError>> GatewaySink for T {}
+
+/// A [`futures_util::Stream`] of [`GatewayMessage`]s, implemented for every such stream.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait GatewayStream:
+    futures_util::Stream<Item = Result<GatewayMessage, GatewayError>> + Send
+{
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: futures_util::Stream<Item = Result<GatewayMessage, GatewayError>> + Send> GatewayStream
+    for T
+{
+}
+/// A [`futures_util::Stream`] of [`GatewayMessage`]s, implemented for every such stream.
+#[cfg(target_arch = "wasm32")]
+pub trait GatewayStream: futures_util::Stream<Item = Result<GatewayMessage, GatewayError>> {}
+#[cfg(target_arch = "wasm32")]
+impl<T: futures_util::Stream<Item = Result<GatewayMessage, GatewayError>>> GatewayStream for T {}
+
+impl Debug for dyn GatewaySink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("GatewaySink")
+    }
+}
+
+impl Debug for dyn GatewayStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("GatewayStream")
+    }
+}
+
+/// Establishes the websocket connections of the [`Gateway`](super::Gateway).
+///
+/// The default transport is [`WebSocketBackend`](super::WebSocketBackend), which uses
+/// tokio-tungstenite, or the browser's websockets on wasm. Other transports, such as sockets
+/// with pinned certificates or in-memory connections for tests, can be used with
+/// [`Gateway::spawn_with_transport`](super::Gateway::spawn_with_transport).
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait WebSocketTransport: Send + Sync + Debug {
+    /// Connects to the given url, sending the headers along with the handshake, and returns both
+    /// halves of the connection.
+    async fn connect(&self, url: &str, headers: &HeaderMap)
+        -> Result<(Sink, Stream), GatewayError>;
+}
@@ -4,20 +4,29 @@
 
 //! Instance and ChorusUser objects.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+use http::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use crate::errors::ChorusResult;
-use crate::gateway::{Gateway, GatewayHandle, Shared};
-use crate::ratelimiter::ChorusRequest;
+use crate::api::routes;
+use crate::errors::{ChorusResult, GatewayError};
+use crate::gateway::{
+    FrequentlyUsed, Gateway, GatewayHandle, GatewayOptions, GatewaySessionState, Shared,
+    WebSocketBackend,
+};
+use crate::ratelimiter::{ChorusRequest, RateLimitEvent, RateLimitEvents, RequestLimiter};
+use crate::token::TokenProvider;
 use crate::types::types::subconfigs::limits::rates::RateLimits;
 use crate::types::{
-    GeneralConfiguration, Limit, LimitType, LimitsConfiguration, User, UserSettings,
+    Channel, ClientProfile, GatewayIdentifyPayload, GeneralConfiguration, Guild, GuildMember,
+    IntoShared, Limit, LimitType, LimitsConfiguration, Locale, Session, SessionPlatform, Snowflake,
+    User, UserSettings,
 };
 use crate::UrlBundle;
 
@@ -29,8 +38,35 @@ pub struct Instance {
     pub urls: UrlBundle,
     pub instance_info: GeneralConfiguration,
     pub limits_information: Option<LimitsInformation>,
+    /// The limits of the instance, as reported by its `/policies/instance/limits` endpoint.
+    /// `None`, if the instance did not provide them.
+    pub limits_configuration: Option<LimitsConfiguration>,
+    /// How long to wait for a response to a REST request, before failing with
+    /// [`ChorusError::RequestTimedOut`](crate::errors::ChorusError::RequestTimedOut).
+    ///
+    /// `None` waits indefinitely, which is the default.
+    #[serde(default)]
+    pub request_timeout: Option<Duration>,
+    /// The client which users of this instance present themselves as, when making requests and
+    /// when logging in. `None` sends no user agent, and identifies with
+    /// [`GatewayIdentifyPayload::common`].
+    #[serde(default)]
+    pub client_profile: Option<ClientProfile>,
+    /// Headers sent with every REST request to this instance and with the websocket handshake of
+    /// gateway connections, for example access tokens of a reverse proxy in front of the
+    /// instance. Headers set by a request itself take precedence.
+    ///
+    /// To also send them with the requests made while creating the instance, use
+    /// [`Instance::new_with_headers`] or [`Instance::from_url_bundle_with_headers`].
+    #[serde(skip)]
+    pub default_headers: HeaderMap,
+    /// Options for the gateway connections of this instance's users
+    #[serde(default)]
+    pub gateway_options: GatewayOptions,
     #[serde(skip)]
     pub client: Client,
+    #[serde(skip)]
+    pub rate_limit_events: RateLimitEvents,
 }
 
 impl PartialEq for Instance {
@@ -38,6 +74,11 @@ impl PartialEq for Instance {
         self.urls == other.urls
             && self.instance_info == other.instance_info
             && self.limits_information == other.limits_information
+            && self.limits_configuration == other.limits_configuration
+            && self.request_timeout == other.request_timeout
+            && self.client_profile == other.client_profile
+            && self.default_headers == other.default_headers
+            && self.gateway_options == other.gateway_options
     }
 }
 
@@ -48,6 +89,7 @@ impl std::hash::Hash for Instance {
         if let Some(inf) = &self.limits_information {
             inf.hash(state);
         }
+        self.request_timeout.hash(state);
     }
 }
 
@@ -75,6 +117,120 @@ impl PartialEq for LimitsInformation {
 }
 
 impl Instance {
+    /// Returns the maximum size of a single file upload in bytes, as configured by the instance.
+    ///
+    /// To get the limit for a specific guild, see [`Guild::upload_limit`], or
+    /// [`ChorusUser::upload_limit`] for a channel.
+    pub fn max_attachment_size(&self) -> Option<u64> {
+        self.limits_configuration
+            .as_ref()
+            .map(|limits| limits.message.max_attachment_size)
+    }
+
+    /// Returns the maximum amount of characters in a message's content, as configured by the
+    /// instance.
+    pub fn max_message_length(&self) -> Option<u32> {
+        self.limits_configuration
+            .as_ref()
+            .map(|limits| limits.message.max_characters)
+    }
+
+    /// Sets the [`ClientProfile`] users of this instance present themselves as.
+    pub fn set_client_profile(&mut self, profile: ClientProfile) {
+        self.client_profile = Some(profile);
+    }
+
+    /// Adds a header to [`Instance::default_headers`], replacing any previous value.
+    pub fn set_default_header(&mut self, name: HeaderName, value: HeaderValue) {
+        self.default_headers.insert(name, value);
+    }
+
+    /// Sets the `X-Discord-Locale` header of all requests to the given locale, which makes the
+    /// instance respond in its language where it can, such as in error messages.
+    pub fn set_locale(&mut self, locale: &Locale) {
+        if let Ok(value) = HeaderValue::from_str(locale.as_str()) {
+            self.set_default_header(HeaderName::from_static("x-discord-locale"), value);
+        }
+    }
+
+    /// Returns the headers sent with every REST request: the default headers, and the
+    /// `User-Agent` and `X-Super-Properties` headers of the [`ClientProfile`].
+    pub(crate) fn request_headers(&self) -> HeaderMap {
+        let mut headers = self.handshake_headers();
+        if let Some(profile) = &self.client_profile {
+            if let Ok(properties) = HeaderValue::from_str(&profile.super_properties()) {
+                headers.entry("X-Super-Properties").or_insert(properties);
+            }
+        }
+        headers
+    }
+
+    /// Builds a request which is not sent as a user, such as one for the instance's policies,
+    /// with the instance's client and [`request_headers`](Instance::request_headers).
+    pub(crate) fn unauthenticated_request(
+        &self,
+        method: http::Method,
+        url: &str,
+    ) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .headers(self.request_headers())
+    }
+
+    /// Returns the headers sent with the websocket handshake of gateway connections: the
+    /// default headers, and the user agent of the [`ClientProfile`].
+    pub(crate) fn handshake_headers(&self) -> HeaderMap {
+        let mut headers = self.default_headers.clone();
+        if let Some(profile) = &self.client_profile {
+            if let Ok(user_agent) = HeaderValue::from_str(&profile.user_agent) {
+                headers
+                    .entry(http::header::USER_AGENT)
+                    .or_insert(user_agent);
+            }
+        }
+        headers
+    }
+
+    /// Connects to the gateway of this instance, sending the
+    /// [`default headers`](Instance::default_headers) with the handshake, and using the
+    /// instance's [`gateway_options`](Instance::gateway_options).
+    pub async fn spawn_gateway(&self) -> Result<GatewayHandle, GatewayError> {
+        Gateway::spawn_with_options(
+            self.urls.wss.clone(),
+            &self.handshake_headers(),
+            Arc::new(WebSocketBackend::default()),
+            self.gateway_options.clone(),
+        )
+        .await
+    }
+
+    /// Creates the identify payload for logging in with the given token, using the instance's
+    /// [`ClientProfile`] if it has one.
+    pub fn identify_payload(&self, token: String) -> GatewayIdentifyPayload {
+        match &self.client_profile {
+            Some(profile) => profile.identify(token),
+            None => GatewayIdentifyPayload {
+                token,
+                ..GatewayIdentifyPayload::common()
+            },
+        }
+    }
+
+    /// Returns true if the instance is a Spacebar server, which is determined by whether it
+    /// serves its [`LimitsConfiguration`] like Spacebar does.
+    ///
+    /// Spacebar-only endpoints return a [`ChorusError::UnsupportedByInstance`](crate::errors::ChorusError::UnsupportedByInstance)
+    /// on other instances, without sending the request.
+    pub fn is_spacebar(&self) -> bool {
+        self.limits_configuration.is_some()
+    }
+
+    /// Returns a receiver for [`RateLimitEvent`]s, which are emitted every time a request to this
+    /// instance is held back by a rate limit bucket, or rejected by the server with a 429.
+    pub fn rate_limit_events(&self) -> tokio::sync::broadcast::Receiver<RateLimitEvent> {
+        self.rate_limit_events.subscribe()
+    }
+
     pub(crate) fn clone_limits_if_some(&self) -> Option<HashMap<LimitType, Limit>> {
         if self.limits_information.is_some() {
             return Some(self.limits_information.as_ref().unwrap().ratelimits.clone());
@@ -86,25 +242,38 @@ impl Instance {
     ///
     /// To create an Instance from one singular url, use [`Instance::new()`].
     pub async fn from_url_bundle(urls: UrlBundle) -> ChorusResult<Instance> {
-        let is_limited: Option<LimitsConfiguration> = Instance::is_limited(&urls.api).await?;
-        let limit_information;
+        Instance::from_url_bundle_with_headers(urls, HeaderMap::new()).await
+    }
 
-        if let Some(limits_configuration) = is_limited {
+    /// Creates a new [`Instance`] from the [relevant instance urls](UrlBundle), sending the given
+    /// [`default_headers`](Instance::default_headers) with every request, including the ones
+    /// made while creating it.
+    pub async fn from_url_bundle_with_headers(
+        urls: UrlBundle,
+        default_headers: HeaderMap,
+    ) -> ChorusResult<Instance> {
+        let mut instance = Instance {
+            urls,
+            // Will be overwritten in the next steps
+            instance_info: GeneralConfiguration::default(),
+            limits_information: None,
+            limits_configuration: None,
+            request_timeout: None,
+            client_profile: None,
+            default_headers,
+            gateway_options: GatewayOptions::default(),
+            client: Client::new(),
+            rate_limit_events: RateLimitEvents::default(),
+        };
+        let is_limited = instance.limits_configuration().await;
+        if let Some(limits_configuration) = is_limited.clone() {
             let limits = ChorusRequest::limits_config_to_hashmap(&limits_configuration.rate);
-            limit_information = Some(LimitsInformation {
+            instance.limits_information = Some(LimitsInformation {
                 ratelimits: limits,
                 configuration: limits_configuration.rate,
             });
-        } else {
-            limit_information = None
         }
-        let mut instance = Instance {
-            urls: urls.clone(),
-            // Will be overwritten in the next step
-            instance_info: GeneralConfiguration::default(),
-            limits_information: limit_information,
-            client: Client::new(),
-        };
+        instance.limits_configuration = is_limited;
         instance.instance_info = match instance.general_configuration_schema().await {
             Ok(schema) => schema,
             Err(e) => {
@@ -119,25 +288,42 @@ impl Instance {
     ///
     /// Shorthand for `Instance::from_url_bundle(UrlBundle::from_root_domain(root_domain).await?)`.
     pub async fn new(root_url: &str) -> ChorusResult<Instance> {
-        let urls = UrlBundle::from_root_url(root_url).await?;
-        Instance::from_url_bundle(urls).await
+        Instance::new_with_headers(root_url, HeaderMap::new()).await
+    }
+
+    /// Creates a new [`Instance`] from a root url like [`Instance::new`], sending the given
+    /// [`default_headers`](Instance::default_headers) with every request, including the ones
+    /// made to discover the instance's urls. Needed for instances behind a reverse proxy which
+    /// requires a header, such as an access token.
+    pub async fn new_with_headers(
+        root_url: &str,
+        default_headers: HeaderMap,
+    ) -> ChorusResult<Instance> {
+        let urls = UrlBundle::from_root_url_with_headers(root_url, &default_headers).await?;
+        Instance::from_url_bundle_with_headers(urls, default_headers).await
     }
 
     pub async fn is_limited(api_url: &str) -> ChorusResult<Option<LimitsConfiguration>> {
-        let api_url = UrlBundle::parse_url(api_url.to_string());
-        let client = Client::new();
-        let request = client
-            .get(format!("{}/policies/instance/limits", &api_url))
-            .header(http::header::ACCEPT, "application/json")
-            .build()?;
-        let resp = match client.execute(request).await {
-            Ok(response) => response,
-            Err(_) => return Ok(None),
+        let instance = Instance {
+            urls: UrlBundle {
+                api: UrlBundle::parse_url(api_url.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
         };
-        match resp.json::<LimitsConfiguration>().await {
-            Ok(limits) => Ok(Some(limits)),
-            Err(_) => Ok(None),
-        }
+        Ok(instance.limits_configuration().await)
+    }
+
+    /// Fetches the instance's [`LimitsConfiguration`], or [`None`] if it does not serve one.
+    async fn limits_configuration(&self) -> Option<LimitsConfiguration> {
+        let url = routes::policies_instance_limits().url(&self.urls.api);
+        let response = self
+            .unauthenticated_request(http::Method::GET, &url)
+            .header(http::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .ok()?;
+        response.json::<LimitsConfiguration>().await.ok()
     }
 }
 
@@ -152,34 +338,199 @@ impl fmt::Display for Token {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The login of a [`ChorusUser`], saved with [`ChorusUser::save_session`] so it can be restored
+/// with [`Instance::restore_session`] without logging in again, for example by command line tools
+/// which run once per invocation.
+///
+/// Contains the user's token, so it has to be stored as securely as the token itself.
+pub struct SavedSession {
+    /// The urls of the instance the user belongs to
+    pub urls: UrlBundle,
+    pub token: String,
+    pub object: User,
+    pub settings: UserSettings,
+    /// The gateway session to resume, if one has been established
+    #[serde(default)]
+    pub gateway: Option<GatewaySessionState>,
+}
+
 #[derive(Debug, Clone)]
 /// A ChorusUser is a representation of an authenticated user on an [Instance].
 /// It is used for most authenticated actions on a Spacebar server.
 /// It also has its own [Gateway] connection.
+///
+/// Clones of a user are handles to the same session: they share its token and rate limits, so a
+/// token refreshed by one clone is used by all of them.
 pub struct ChorusUser {
     pub belongs_to: Shared<Instance>,
-    pub token: String,
-    pub limits: Option<HashMap<LimitType, Limit>>,
+    /// The user's token. Shared between clones.
+    pub token: Shared<String>,
+    /// The rate limits dictated by the user, rather than by the instance. Shared between clones.
+    pub limits: Shared<Option<HashMap<LimitType, Limit>>>,
     pub settings: Shared<UserSettings>,
     pub object: Shared<User>,
     pub gateway: GatewayHandle,
+    /// Limits the amount of concurrent REST requests of this user. Shared between clones.
+    pub request_limiter: RequestLimiter,
+    /// Supplies a new token once the current one is rejected. See [`TokenProvider`].
+    pub token_provider: Option<Arc<dyn TokenProvider>>,
+    /// The user's own member objects, by guild id, used to check permissions locally. See
+    /// [`ChorusUser::can`].
+    ///
+    /// Users created by logging in, registering or restoring a session keep them up to date with
+    /// the member updates of their gateway.
+    pub guild_members: Shared<HashMap<Snowflake, GuildMember>>,
+    /// Whether sending messages and modifying channels first checks the user's permissions
+    /// locally. Defaults to false. See [`ChorusUser::set_check_permissions`].
+    pub check_permissions: bool,
 }
 
 impl PartialEq for ChorusUser {
     fn eq(&self, other: &Self) -> bool {
-        self.token == other.token
-            && self.limits == other.limits
+        *self.token.read().unwrap() == *other.token.read().unwrap()
+            && *self.limits.read().unwrap() == *other.limits.read().unwrap()
             && self.gateway.url == other.gateway.url
     }
 }
 
 impl ChorusUser {
     pub fn token(&self) -> String {
-        self.token.clone()
+        self.token.read().unwrap().clone()
     }
 
+    /// Replaces the token of this user and all of its clones.
     pub fn set_token(&mut self, token: String) {
-        self.token = token;
+        *self.token.write().unwrap() = token;
+    }
+
+    /// Sets the [`TokenProvider`] which is asked for a new token when a request of this user is
+    /// rejected with `401 Unauthorized`. The request is then retried once with the new token.
+    pub fn set_token_provider(&mut self, provider: impl TokenProvider + 'static) {
+        self.token_provider = Some(Arc::new(provider));
+    }
+
+    /// Asks the user's [`TokenProvider`] for a new token to replace the current one.
+    ///
+    /// Returns `true` if the token has been replaced, or `false` if the user has no token
+    /// provider or the provider could not supply a new token.
+    pub async fn refresh_token(&mut self) -> bool {
+        let provider = match &self.token_provider {
+            Some(provider) => provider.clone(),
+            None => return false,
+        };
+        match provider.refresh(&self.token()).await {
+            Some(token) => {
+                log::info!("Replaced the token of a user after it was rejected");
+                self.set_token(token);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the maximum amount of REST requests this user may have in flight at once. Defaults to
+    /// [`DEFAULT_MAX_CONCURRENT_REQUESTS`](crate::ratelimiter::DEFAULT_MAX_CONCURRENT_REQUESTS).
+    ///
+    /// Requests which are already in flight are not affected. Clones of this user made before
+    /// calling this keep the previous limit.
+    pub fn set_max_concurrent_requests(&mut self, max_concurrent: usize) {
+        self.request_limiter = RequestLimiter::new(max_concurrent);
+    }
+
+    /// Sets whether sending messages and modifying channels first checks the user's permissions
+    /// locally, with [`ChorusUser::can`]. If the user is known to lack a required permission, a
+    /// [`ChorusError::MissingPermissions`](crate::errors::ChorusError::MissingPermissions) is
+    /// returned without sending the request.
+    ///
+    /// If the permissions cannot be determined locally, the request is sent regardless.
+    pub fn set_check_permissions(&mut self, check_permissions: bool) {
+        self.check_permissions = check_permissions;
+    }
+
+    /// Returns the user's sessions on each of their devices, without the session summarizing
+    /// them. See [`GatewayHandle::sessions`].
+    pub fn sessions(&self) -> Vec<Session> {
+        let mut sessions = self.gateway.sessions();
+        sessions.retain(|session| !session.is_summary());
+        sessions
+    }
+
+    /// Returns the kinds of devices the user has sessions on, for example to show that they are
+    /// active on desktop.
+    pub fn active_platforms(&self) -> HashSet<SessionPlatform> {
+        self.sessions()
+            .iter()
+            .map(|session| session.platform())
+            .collect()
+    }
+
+    /// Returns the cache of the user's gateway, if it has been enabled with
+    /// [`GatewayOptions::cache`]. Requires the `cache` feature.
+    #[cfg(feature = "cache")]
+    pub fn cache(&self) -> Option<Arc<crate::gateway::Cache>> {
+        self.gateway.cache()
+    }
+
+    /// Observes a channel the user has just created on the user's gateway, and adds it to the
+    /// gateway's cache if it is enabled.
+    pub(crate) async fn remember_channel(&self, channel: &Channel) {
+        self.gateway.observe(channel.clone().into_shared()).await;
+        #[cfg(feature = "cache")]
+        if let Some(cache) = self.cache() {
+            let create = crate::types::ChannelCreate {
+                channel: channel.clone(),
+                ..Default::default()
+            };
+            crate::gateway::Observer::<crate::types::ChannelCreate>::update(&*cache, &create).await;
+        }
+    }
+
+    /// Returns up to `limit` of the emojis and stickers the user uses most, if the user's gateway
+    /// tracks them, see [`GatewayOptions::track_emoji_usage`].
+    pub fn frequently_used(&self, limit: usize) -> Option<FrequentlyUsed> {
+        self.gateway
+            .emoji_usage()
+            .map(|usage| usage.frequently_used(limit))
+    }
+
+    /// Returns the maximum size of a single file upload to the given channel in bytes.
+    ///
+    /// For guild channels, this is the guild's [`Guild::upload_limit`], which grows with its
+    /// boosts, if the channel and its guild are observed on the user's gateway (see
+    /// [`GatewayHandle::observe`]). Otherwise it is the instance's
+    /// [`Instance::max_attachment_size`], if that is known.
+    pub async fn upload_limit(&self, channel_id: Snowflake) -> Option<u64> {
+        let instance_limits = self
+            .belongs_to
+            .read()
+            .unwrap()
+            .limits_configuration
+            .as_ref()
+            .map(|limits| limits.message.clone());
+        let guild_id = self
+            .gateway
+            .get_observed::<Channel>(channel_id)
+            .await
+            .and_then(|channel| channel.guild_id);
+        if let Some(guild_id) = guild_id {
+            if let Some(guild) = self.gateway.get_observed::<Guild>(guild_id).await {
+                return Some(guild.upload_limit(instance_limits.as_ref()));
+            }
+        }
+        instance_limits.map(|limits| limits.max_attachment_size)
+    }
+
+    /// Saves the user's login and gateway session, to restore them later with
+    /// [`Instance::restore_session`].
+    pub fn save_session(&self) -> SavedSession {
+        SavedSession {
+            urls: self.belongs_to.read().unwrap().urls.clone(),
+            token: self.token(),
+            object: self.object.read().unwrap().clone(),
+            settings: self.settings.read().unwrap().clone(),
+            gateway: self.gateway.session_state(),
+        }
     }
 
     /// Creates a new [ChorusUser] from existing data.
@@ -197,11 +548,15 @@ impl ChorusUser {
     ) -> ChorusUser {
         ChorusUser {
             belongs_to,
-            token,
-            limits,
+            token: token.into_shared(),
+            limits: limits.into_shared(),
             settings,
             object,
             gateway,
+            request_limiter: RequestLimiter::default(),
+            token_provider: None,
+            guild_members: Arc::new(RwLock::new(HashMap::new())),
+            check_permissions: false,
         }
     }
 
@@ -214,20 +569,28 @@ impl ChorusUser {
         let settings = Arc::new(RwLock::new(UserSettings::default()));
         let object = Arc::new(RwLock::new(User::default()));
         let wss_url = instance.read().unwrap().urls.wss.clone();
+        let headers = instance.read().unwrap().handshake_headers();
         // Dummy gateway object
-        let gateway = Gateway::spawn(wss_url).await.unwrap();
+        let gateway = Gateway::spawn_with_headers(wss_url, &headers)
+            .await
+            .unwrap();
         ChorusUser {
-            token,
+            token: token.into_shared(),
             belongs_to: instance.clone(),
             limits: instance
                 .read()
                 .unwrap()
                 .limits_information
                 .as_ref()
-                .map(|info| info.ratelimits.clone()),
+                .map(|info| info.ratelimits.clone())
+                .into_shared(),
             settings,
             object,
             gateway,
+            request_limiter: RequestLimiter::default(),
+            token_provider: None,
+            guild_members: Arc::new(RwLock::new(HashMap::new())),
+            check_permissions: false,
         }
     }
 }
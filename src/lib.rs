@@ -118,7 +118,7 @@ compile_error!("feature \"rt\" and feature \"rt_multi_thread\" cannot be enabled
 use errors::ChorusResult;
 use serde::{Deserialize, Serialize};
 use types::types::domains_configuration::WellKnownResponse;
-use url::{ParseError, Url};
+use url::{ParseError, Position, Url};
 
 use crate::errors::ChorusError;
 
@@ -131,6 +131,12 @@ pub mod gateway;
 pub mod instance;
 #[cfg(feature = "client")]
 pub mod ratelimiter;
+#[cfg(feature = "client")]
+pub mod scheduler;
+#[cfg(feature = "client")]
+pub mod session;
+#[cfg(feature = "client")]
+pub mod token;
 pub mod types;
 #[cfg(all(
     feature = "client",
@@ -138,6 +144,33 @@ pub mod types;
 ))]
 pub mod voice;
 
+/// Runs a future to completion, or returns `None` if it does not complete within `duration`.
+///
+/// The future is dropped once the timeout elapses.
+#[cfg(feature = "client")]
+pub(crate) async fn with_timeout<F: std::future::Future>(
+    duration: std::time::Duration,
+    future: F,
+) -> Option<F::Output> {
+    #[cfg(not(target_arch = "wasm32"))]
+    use tokio::time::timeout;
+    #[cfg(target_arch = "wasm32")]
+    use wasmtimer::tokio::timeout;
+
+    timeout(duration, future).await.ok()
+}
+
+/// Waits for the given duration.
+#[cfg(feature = "client")]
+pub(crate) async fn sleep(duration: std::time::Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    use tokio::time::sleep;
+    #[cfg(target_arch = "wasm32")]
+    use wasmtimer::tokio::sleep;
+
+    sleep(duration).await
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// A URLBundle bundles together the API-, Gateway- and CDN-URLs of a Spacebar instance.
 ///
@@ -214,24 +247,43 @@ impl UrlBundle {
     /// of the above approaches fail, it is very likely that the instance is misconfigured, unreachable, or that
     /// a wrong URL was provided.
     pub async fn from_root_url(url: &str) -> ChorusResult<UrlBundle> {
+        UrlBundle::from_root_url_with_headers(url, &http::HeaderMap::new()).await
+    }
+
+    /// Retrieves a `UrlBundle` from an instance's root url like [`UrlBundle::from_root_url`],
+    /// sending the given headers with every request, for example the
+    /// [default headers](crate::instance::Instance::default_headers) of the instance.
+    pub async fn from_root_url_with_headers(
+        url: &str,
+        headers: &http::HeaderMap,
+    ) -> ChorusResult<UrlBundle> {
         let parsed = UrlBundle::parse_url(url.to_string());
         let client = reqwest::Client::new();
         let request_wellknown = client
             .get(format!("{}/.well-known/spacebar", &parsed))
+            .headers(headers.clone())
             .header(http::header::ACCEPT, "application/json")
             .build()?;
         let response_wellknown = client.execute(request_wellknown).await?;
         if response_wellknown.status().is_success() {
             let body = response_wellknown.json::<WellKnownResponse>().await?.api;
-            UrlBundle::from_api_url(&body).await
+            UrlBundle::from_api_url(&client, &body, headers).await
         } else {
-            if let Ok(response_slash_api) =
-                UrlBundle::from_api_url(&format!("{}/api/policies/instance/domains", parsed)).await
+            if let Ok(response_slash_api) = UrlBundle::from_api_url(
+                &client,
+                &format!("{}/api/policies/instance/domains", parsed),
+                headers,
+            )
+            .await
             {
                 return Ok(response_slash_api);
             }
-            if let Ok(response_api) =
-                UrlBundle::from_api_url(&format!("{}/policies/instance/domains", parsed)).await
+            if let Ok(response_api) = UrlBundle::from_api_url(
+                &client,
+                &format!("{}/policies/instance/domains", parsed),
+                headers,
+            )
+            .await
             {
                 Ok(response_api)
             } else {
@@ -240,10 +292,36 @@ impl UrlBundle {
         }
     }
 
-    async fn from_api_url(url: &str) -> ChorusResult<UrlBundle> {
-        let client = reqwest::Client::new();
+    /// Returns the url under which the instance's media proxy serves an external image, such as
+    /// `https://example.com/cat.png`. Loading images through the proxy hides the client's IP
+    /// address from the image's host.
+    ///
+    /// The image is proxied under `{cdn}/external/{scheme}/{host}/{path}`. Note that instances
+    /// which sign proxied urls reject this url; for embeds, prefer their `proxy_url`.
+    ///
+    /// Returns `None` if `external_url` is not a valid `http` or `https` url.
+    pub fn media_proxy_url(&self, external_url: &str) -> Option<String> {
+        let url = Url::parse(external_url).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+            return None;
+        }
+        // The host along with its port, the path and the query
+        Some(format!(
+            "{}/external/{}/{}",
+            self.cdn,
+            url.scheme(),
+            &url[Position::BeforeHost..Position::AfterQuery]
+        ))
+    }
+
+    async fn from_api_url(
+        client: &reqwest::Client,
+        url: &str,
+        headers: &http::HeaderMap,
+    ) -> ChorusResult<UrlBundle> {
         let request = client
             .get(url)
+            .headers(headers.clone())
             .header(http::header::ACCEPT, "application/json")
             .build()?;
         let response = client.execute(request).await?;
@@ -281,4 +359,35 @@ mod lib {
         result = UrlBundle::parse_url(String::from("https://some.url.com"));
         assert_eq!(result, String::from("https://some.url.com"));
     }
+
+    #[test]
+    fn test_media_proxy_url() {
+        let urls = UrlBundle::new(
+            "https://some.url.com".to_string(),
+            "https://some.url.com/api".to_string(),
+            "wss://gateway.some.url.com".to_string(),
+            "https://cdn.some.url.com".to_string(),
+        );
+        assert_eq!(
+            urls.media_proxy_url("https://example.com/images/cat.png?size=64"),
+            Some(
+                "https://cdn.some.url.com/external/https/example.com/images/cat.png?size=64"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            urls.media_proxy_url("http://user@localhost:8080/cat.png#fragment"),
+            Some("https://cdn.some.url.com/external/http/localhost:8080/cat.png".to_string())
+        );
+        assert_eq!(urls.media_proxy_url("ftp://example.com/cat.png"), None);
+        assert_eq!(urls.media_proxy_url("not a url"), None);
+        assert_eq!(
+            types::Guild::widget_image_url(
+                &urls.api,
+                types::Snowflake(1),
+                types::WidgetStyle::Banner2
+            ),
+            "https://some.url.com/api/guilds/1/widget.png?style=banner2"
+        );
+    }
 }
@@ -5,18 +5,33 @@
 //! Ratelimiter and request handling functionality.
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use log::{self, debug};
 use reqwest::{Client, RequestBuilder, Response};
 use serde::Deserialize;
 use serde_json::from_str;
+use tokio::sync::{broadcast, OwnedSemaphorePermit, Semaphore};
 
 use crate::{
-    errors::{ChorusError, ChorusResult},
-    instance::ChorusUser,
+    api::routes::Route,
+    errors::{ApiError, ChorusError, ChorusResult},
+    instance::{ChorusUser, Instance},
     types::{types::subconfigs::limits::rates::RateLimits, Limit, LimitType, LimitsConfiguration},
 };
 
+/// How long to wait before retrying a request which was held back by a rate limit, if the
+/// instance did not say how long to wait.
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(1);
+
+/// A request run by [`ChorusRequest::retry_rate_limited`], borrowing the user it is sent as.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) type RetryFuture<'a, T> = futures_util::future::BoxFuture<'a, ChorusResult<T>>;
+/// A request run by [`ChorusRequest::retry_rate_limited`], borrowing the user it is sent as.
+#[cfg(target_arch = "wasm32")]
+pub(crate) type RetryFuture<'a, T> = futures_util::future::LocalBoxFuture<'a, ChorusResult<T>>;
+
 /// Chorus' request struct. This struct is used to send rate-limited requests to the Spacebar server.
 /// See <https://discord.com/developers/docs/topics/rate-limits#rate-limits> for more information.
 #[derive(Debug)]
@@ -74,62 +89,253 @@ impl ChorusRequest {
         }
     }
 
+    /// Makes a new [`ChorusRequest`] to a [`Route`] of the user's instance, authenticated as the
+    /// user.
+    ///
+    /// The request counts towards the rate limit bucket of the route's major parameter.
+    pub fn from_route(
+        method: http::Method,
+        route: Route,
+        body: Option<String>,
+        audit_log_reason: Option<&str>,
+        user: &mut ChorusUser,
+    ) -> ChorusRequest {
+        let url = route.url(&user.belongs_to.read().unwrap().urls.api);
+        ChorusRequest::new(
+            method,
+            &url,
+            body,
+            audit_log_reason,
+            None,
+            Some(user),
+            route.limit_type(),
+        )
+    }
+
+    /// Makes a new [`ChorusRequest`] to a [`Route`] of an instance, which is not sent as a user,
+    /// such as one for the instance's voice regions. Send it with
+    /// [`ChorusRequest::send_unauthenticated`].
+    pub fn unauthenticated(
+        method: http::Method,
+        route: Route,
+        instance: &Instance,
+    ) -> ChorusRequest {
+        let url = route.url(&instance.urls.api);
+        ChorusRequest::new(method, &url, None, None, None, None, route.limit_type())
+    }
+
+    /// Sends a [`ChorusRequest`] which is not sent as a user, with the instance's client,
+    /// headers and request timeout.
+    ///
+    /// Like requests sent as a user, the request is held back while the instance's global or ip
+    /// rate limit is exhausted.
+    pub async fn send_unauthenticated(self, instance: &Instance) -> ChorusResult<Response> {
+        let limit_type = self.limit_type;
+        let mut request = self.request.build().unwrap();
+        ChorusRequest::apply_instance_headers(&mut request, instance);
+        let route = request.url().path().to_string();
+        let exhausted = instance
+            .limits_information
+            .as_ref()
+            .and_then(|information| {
+                [LimitType::Global, LimitType::Ip]
+                    .iter()
+                    .filter_map(|bucket| information.ratelimits.get(bucket))
+                    .find(|limit| limit.remaining == 0)
+                    .cloned()
+            });
+        if let Some(exhausted) = exhausted {
+            log::info!("Rate limit hit. Bucket: {:?}", exhausted.bucket);
+            let kind = match exhausted.bucket {
+                LimitType::Global => RateLimitEventKind::GlobalLimit,
+                _ => RateLimitEventKind::BucketExhausted,
+            };
+            instance.rate_limit_events.emit(RateLimitEvent {
+                kind,
+                route,
+                bucket: exhausted.bucket,
+                wait: seconds_until(exhausted.reset),
+            });
+            return Err(ChorusError::RateLimited {
+                bucket: format!("{:?}", limit_type),
+            });
+        }
+        let response =
+            ChorusRequest::fetch(&instance.client, instance.request_timeout, request).await?;
+        if response.status().as_u16() == 429 {
+            log::warn!("Rate limit hit unexpectedly. Bucket: {:?}.", limit_type);
+            instance.rate_limit_events.emit(RateLimitEvent {
+                kind: RateLimitEventKind::TooManyRequests,
+                route,
+                bucket: limit_type,
+                wait: retry_after(&response).unwrap_or_default(),
+            });
+            return Err(ChorusError::RateLimited {
+                bucket: format!("{:?}", limit_type),
+            });
+        }
+        if !response.status().is_success() {
+            log::warn!("Request failed: {:?}", response);
+            return Err(ChorusRequest::interpret_error(response, route).await);
+        }
+        Ok(response)
+    }
+
     /// Sends a [`ChorusRequest`]. Checks if the user is rate limited, and if not, sends the request.
     /// If the user is not rate limited and the instance has rate limits enabled, it will update the
     /// rate limits.
+    ///
+    /// If the request is rejected with `401 Unauthorized` and the user has a
+    /// [`TokenProvider`](crate::token::TokenProvider), the user's token is refreshed and the
+    /// request is retried once.
+    pub async fn send_request(self, user: &mut ChorusUser) -> ChorusResult<Response> {
+        let limit_type = self.limit_type;
+        let request = self.request.build().unwrap();
+        // Multipart bodies cannot be cloned, so these requests are never retried
+        let retry = match user.token_provider {
+            Some(_) => request.try_clone(),
+            None => None,
+        };
+        let result = ChorusRequest::execute(request, limit_type, user).await;
+        let unauthorized = match &result {
+            Err(error) => error.api_error().map_or(false, |error| error.status == 401),
+            Ok(_) => false,
+        };
+        let mut retry = match retry {
+            Some(retry) if unauthorized => retry,
+            _ => return result,
+        };
+        if !user.refresh_token().await {
+            return result;
+        }
+        match http::HeaderValue::from_str(&user.token()) {
+            Ok(token) => {
+                retry
+                    .headers_mut()
+                    .insert(http::header::AUTHORIZATION, token);
+            }
+            Err(_) => return result,
+        }
+        ChorusRequest::execute(retry, limit_type, user).await
+    }
+
+    /// Adds the instance's [default headers](crate::instance::Instance::default_headers), and the
+    /// `User-Agent` and `X-Super-Properties` headers of its
+    /// [`ClientProfile`](crate::types::ClientProfile), to the request, unless it already has them.
+    fn apply_instance_headers(request: &mut reqwest::Request, instance: &Instance) {
+        let instance_headers = instance.request_headers();
+        let headers = request.headers_mut();
+        for (name, value) in &instance_headers {
+            headers.entry(name).or_insert_with(|| value.clone());
+        }
+    }
+
     #[allow(clippy::await_holding_refcell_ref)]
-    pub(crate) async fn send_request(self, user: &mut ChorusUser) -> ChorusResult<Response> {
-        if !ChorusRequest::can_send_request(user, &self.limit_type) {
-            log::info!("Rate limit hit. Bucket: {:?}", self.limit_type);
+    async fn execute(
+        mut request: reqwest::Request,
+        limit_type: LimitType,
+        user: &mut ChorusUser,
+    ) -> ChorusResult<Response> {
+        ChorusRequest::apply_instance_headers(&mut request, &user.belongs_to.read().unwrap());
+        let route = request.url().path().to_string();
+        if let Some(exhausted) = ChorusRequest::exhausted_limit(user, &limit_type) {
+            log::info!("Rate limit hit. Bucket: {:?}", exhausted.bucket);
+            let kind = match exhausted.bucket {
+                LimitType::Global => RateLimitEventKind::GlobalLimit,
+                _ => RateLimitEventKind::BucketExhausted,
+            };
+            user.belongs_to
+                .read()
+                .unwrap()
+                .rate_limit_events
+                .emit(RateLimitEvent {
+                    kind,
+                    route,
+                    bucket: exhausted.bucket,
+                    wait: seconds_until(exhausted.reset),
+                });
             return Err(ChorusError::RateLimited {
-                bucket: format!("{:?}", self.limit_type),
+                bucket: format!("{:?}", limit_type),
             });
         }
         let client = user.belongs_to.read().unwrap().client.clone();
-        let result = match client.execute(self.request.build().unwrap()).await {
-            Ok(result) => {
-                debug!("Request successful: {:?}", result);
-                result
-            }
-            Err(error) => {
-                log::warn!("Request failed: {:?}", error);
-                return Err(ChorusError::RequestFailed {
-                    url: error.url().unwrap().to_string(),
-                    error: error.to_string(),
-                });
-            }
-        };
+        let request_timeout = user.belongs_to.read().unwrap().request_timeout;
+        // Held until the response has arrived
+        let _permit = user.request_limiter.clone().acquire().await;
+        let result = ChorusRequest::fetch(&client, request_timeout, request).await?;
         drop(client);
         if !result.status().is_success() {
             if result.status().as_u16() == 429 {
-                log::warn!("Rate limit hit unexpectedly. Bucket: {:?}. Setting the instances' remaining global limit to 0 to have cooldown.", self.limit_type);
-                user.belongs_to
-                    .write()
-                    .unwrap()
+                log::warn!("Rate limit hit unexpectedly. Bucket: {:?}. Setting the instances' remaining global limit to 0 to have cooldown.", limit_type);
+                let mut belongs_to = user.belongs_to.write().unwrap();
+                let mut wait = retry_after(&result);
+                if let Some(global) = belongs_to
                     .limits_information
                     .as_mut()
-                    .unwrap()
-                    .ratelimits
-                    .get_mut(&LimitType::Global)
-                    .unwrap()
-                    .remaining = 0;
+                    .and_then(|information| information.ratelimits.get_mut(&LimitType::Global))
+                {
+                    global.remaining = 0;
+                    wait = wait.or_else(|| Some(seconds_until(global.reset)));
+                }
+                belongs_to.rate_limit_events.emit(RateLimitEvent {
+                    kind: RateLimitEventKind::TooManyRequests,
+                    route,
+                    bucket: limit_type,
+                    wait: wait.unwrap_or_default(),
+                });
+                drop(belongs_to);
                 return Err(ChorusError::RateLimited {
-                    bucket: format!("{:?}", self.limit_type),
+                    bucket: format!("{:?}", limit_type),
                 });
             }
             log::warn!("Request failed: {:?}", result);
-            return Err(ChorusRequest::interpret_error(result).await);
+            return Err(ChorusRequest::interpret_error(result, route).await);
         }
-        ChorusRequest::update_rate_limits(user, &self.limit_type, !result.status().is_success());
+        ChorusRequest::update_rate_limits(user, &limit_type, !result.status().is_success());
         Ok(result)
     }
 
-    fn can_send_request(user: &mut ChorusUser, limit_type: &LimitType) -> bool {
+    /// Executes a request with the given client, failing with
+    /// [`ChorusError::RequestTimedOut`] if it takes longer than `request_timeout`.
+    async fn fetch(
+        client: &Client,
+        request_timeout: Option<Duration>,
+        request: reqwest::Request,
+    ) -> ChorusResult<Response> {
+        let url = request.url().to_string();
+        let response = match request_timeout {
+            Some(duration) => match crate::with_timeout(duration, client.execute(request)).await {
+                Some(response) => response,
+                None => {
+                    log::warn!("Request to {} timed out after {:?}", url, duration);
+                    return Err(ChorusError::RequestTimedOut { url });
+                }
+            },
+            None => client.execute(request).await,
+        };
+        match response {
+            Ok(result) => {
+                debug!("Request successful: {:?}", result);
+                Ok(result)
+            }
+            Err(error) => {
+                log::warn!("Request failed: {:?}", error);
+                Err(ChorusError::RequestFailed {
+                    url,
+                    error: error.to_string(),
+                })
+            }
+        }
+    }
+
+    /// Returns the first exhausted limit which prevents a request in the given bucket from being
+    /// sent, or `None` if the request can be sent.
+    fn exhausted_limit(user: &mut ChorusUser, limit_type: &LimitType) -> Option<Limit> {
         log::trace!("Checking if user or instance is rate-limited...");
         let mut belongs_to = user.belongs_to.write().unwrap();
         if belongs_to.limits_information.is_none() {
             log::trace!("Instance indicates no rate limits are configured. Continuing.");
-            return true;
+            return None;
         }
         let instance_dictated_limits = [
             &LimitType::AuthLogin,
@@ -161,28 +367,22 @@ impl ChorusRequest {
                         .as_ref()
                         .unwrap()
                         .configuration,
-                    user.limits.as_mut().unwrap(),
+                    user.limits.write().unwrap().as_mut().unwrap(),
                     limit_type,
                 );
-                user.limits.as_mut().unwrap().clone()
+                user.limits.read().unwrap().clone().unwrap()
             }
         };
-        let global = belongs_to
-            .limits_information
-            .as_ref()
-            .unwrap()
-            .ratelimits
-            .get(&LimitType::Global)
-            .unwrap();
-        let ip = belongs_to
-            .limits_information
-            .as_ref()
-            .unwrap()
-            .ratelimits
-            .get(&LimitType::Ip)
-            .unwrap();
+        let ratelimits = &belongs_to.limits_information.as_ref().unwrap().ratelimits;
+        let global = ratelimits.get(&LimitType::Global).unwrap();
+        let ip = ratelimits.get(&LimitType::Ip).unwrap();
         let limit_type_limit = limits.get(limit_type).unwrap();
-        global.remaining > 0 && ip.remaining > 0 && limit_type_limit.remaining > 0
+        for limit in [global, ip, limit_type_limit] {
+            if limit.remaining == 0 {
+                return Some(limit.clone());
+            }
+        }
+        None
     }
 
     fn ensure_limit_in_map(
@@ -265,20 +465,17 @@ impl ChorusRequest {
         }
     }
 
-    async fn interpret_error(response: reqwest::Response) -> ChorusError {
-        match response.status().as_u16() {
-            401..=403 | 407 => ChorusError::NoPermission,
-            404 => ChorusError::NotFound {
-                error: response.text().await.unwrap(),
-            },
-            405 | 408 | 409 => ChorusError::ReceivedErrorCode { error_code: response.status().as_u16(), error: response.text().await.unwrap() },
-            411..=421 | 426 | 428 | 431 => ChorusError::InvalidArguments {
-                error: response.text().await.unwrap(),
-            },
+    async fn interpret_error(response: reqwest::Response, route: String) -> ChorusError {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        let source = ApiError::from_response_body(status, &body);
+        match status {
+            401..=403 | 407 => ChorusError::NoPermission { route, source },
+            404 => ChorusError::NotFound { route, source },
+            400 | 411..=421 | 426 | 428 | 431 => ChorusError::InvalidRequest { route, source },
             429 => panic!("Illegal state: Rate limit exception should have been caught before this function call."),
             451 => ChorusError::NoResponse,
-            500..=599 => ChorusError::ReceivedErrorCode { error_code: response.status().as_u16(), error: response.text().await.unwrap() },
-            _ => ChorusError::ReceivedErrorCode { error_code: response.status().as_u16(), error: response.text().await.unwrap()},
+            _ => ChorusError::ReceivedErrorCode { route, source },
         }
     }
 
@@ -312,6 +509,7 @@ impl ChorusRequest {
         let time: u64 = chrono::Utc::now().timestamp() as u64;
         for relevant_limit in relevant_limits.iter() {
             let mut belongs_to = user.belongs_to.write().unwrap();
+            let mut user_limits = user.limits.write().unwrap();
             let limit = match relevant_limit.0 {
                 LimitOrigin::Instance => {
                     log::trace!(
@@ -328,7 +526,7 @@ impl ChorusRequest {
                 }
                 LimitOrigin::User => {
                     log::trace!("Updating user rate limit. Bucket: {:?}", relevant_limit.1);
-                    user.limits
+                    user_limits
                         .as_mut()
                         .unwrap()
                         .get_mut(&relevant_limit.1)
@@ -374,9 +572,11 @@ impl ChorusRequest {
                     bucket: format!("{:?}", LimitType::Ip),
                 })
             }
-            404 => return Err(ChorusError::NotFound { error: "Route \"/policies/instance/limits/\" not found. Are you perhaps trying to request the Limits configuration from an unsupported server?".to_string() }),
+            404 => return Err(ChorusError::NotFound { route: "/policies/instance/limits/".to_string(), source: ApiError { status: 404, code: None, message: "Route not found. Are you perhaps trying to request the Limits configuration from an unsupported server?".to_string(), ..Default::default() } }),
             400..=u16::MAX => {
-                return Err(ChorusError::ReceivedErrorCode { error_code: request.status().as_u16(), error: request.text().await.unwrap() })
+                let status = request.status().as_u16();
+                let body = request.text().await.unwrap_or_default();
+                return Err(ChorusError::ReceivedErrorCode { route: "/policies/instance/limits/".to_string(), source: ApiError::from_response_body(status, &body) })
             }
             _ => {
                 return Err(ChorusError::InvalidResponse {
@@ -478,9 +678,37 @@ impl ChorusRequest {
         map
     }
 
+    /// Runs `request`, which sends a request to `route`, and retries it up to `max_retries` times
+    /// while it is held back by a rate limit.
+    ///
+    /// Before each retry, waits for as long as the [`RateLimitEvent`] emitted for the request
+    /// expects the rate limit to take to be replenished.
+    pub(crate) async fn retry_rate_limited<T>(
+        user: &mut ChorusUser,
+        route: &Route,
+        max_retries: u32,
+        mut request: impl for<'u> FnMut(&'u mut ChorusUser) -> RetryFuture<'u, T>,
+    ) -> ChorusResult<T> {
+        let mut retries = 0;
+        loop {
+            let mut rate_limit_events = user.belongs_to.read().unwrap().rate_limit_events();
+            match request(user).await {
+                Err(ChorusError::RateLimited { .. }) if retries < max_retries => {
+                    retries += 1;
+                    let wait = RateLimitEvent::last_wait(&mut rate_limit_events, route)
+                        .unwrap_or_default()
+                        .max(DEFAULT_RATE_LIMIT_WAIT);
+                    log::debug!("Rate limited on {}, retrying in {:?}", route.path(), wait);
+                    crate::sleep(wait).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Sends a [`ChorusRequest`] and returns a [`ChorusResult`] that contains nothing if the request
     /// was successful, or a [`ChorusError`] if the request failed.
-    pub(crate) async fn handle_request_as_result(self, user: &mut ChorusUser) -> ChorusResult<()> {
+    pub async fn handle_request_as_result(self, user: &mut ChorusUser) -> ChorusResult<()> {
         match self.send_request(user).await {
             Ok(_) => Ok(()),
             Err(e) => Err(e),
@@ -489,7 +717,7 @@ impl ChorusRequest {
 
     /// Sends a [`ChorusRequest`] and returns a [`ChorusResult`] that contains a [`T`] if the request
     /// was successful, or a [`ChorusError`] if the request failed.
-    pub(crate) async fn deserialize_response<T: for<'a> Deserialize<'a>>(
+    pub async fn deserialize_response<T: for<'a> Deserialize<'a>>(
         self,
         user: &mut ChorusUser,
     ) -> ChorusResult<T> {
@@ -519,9 +747,185 @@ impl ChorusRequest {
         };
         Ok(object)
     }
+
+    /// Sends a [`ChorusRequest`] which is not sent as a user, see
+    /// [`ChorusRequest::send_unauthenticated`], and deserializes the response into a [`T`].
+    pub async fn deserialize_unauthenticated_response<T: for<'a> Deserialize<'a>>(
+        self,
+        instance: &Instance,
+    ) -> ChorusResult<T> {
+        let response = self.send_unauthenticated(instance).await?;
+        debug!("Got response: {:?}", response);
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| ChorusError::InvalidResponse {
+                error: format!(
+                    "Error while trying to process the HTTP response into a String: {}",
+                    e
+                ),
+            })?;
+        from_str::<T>(&response_text).map_err(|e| ChorusError::InvalidResponse {
+            error: format!(
+                "Error while trying to deserialize the JSON response into requested type T: {}. JSON Response: {}",
+                e, response_text
+            ),
+        })
+    }
+}
+
+/// Returns how long the `Retry-After` header of a `429 Too Many Requests` response says to wait.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(http::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<f64>().ok())
+        .map(Duration::from_secs_f64)
 }
 
 enum LimitOrigin {
     Instance,
     User,
 }
+
+/// Returns the time left until the given unix timestamp (in seconds) is reached.
+fn seconds_until(timestamp: u64) -> Duration {
+    let now = chrono::Utc::now().timestamp() as u64;
+    Duration::from_secs(timestamp.saturating_sub(now))
+}
+
+/// The default amount of requests a single [`ChorusUser`] may have in flight at once.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Limits the amount of concurrent in-flight requests of a [`ChorusUser`], so that spawning many
+/// tasks at once does not open hundreds of connections to an instance.
+///
+/// Clones share the same limit.
+#[derive(Debug, Clone)]
+pub struct RequestLimiter {
+    semaphore: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+impl Default for RequestLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+}
+
+impl RequestLimiter {
+    /// Creates a new [`RequestLimiter`] allowing `max_concurrent` requests at once. A limit of 0
+    /// is treated as 1.
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+        }
+    }
+
+    /// The maximum amount of concurrent requests.
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// The amount of requests which are currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrent - self.semaphore.available_permits()
+    }
+
+    /// Waits until a request may be sent. The returned permit has to be held while the request
+    /// is in flight.
+    pub async fn acquire(self) -> OwnedSemaphorePermit {
+        self.semaphore
+            .acquire_owned()
+            .await
+            .expect("Illegal state: The request semaphore is never closed.")
+    }
+}
+
+/// The capacity of the [`RateLimitEvents`] channel. Receivers lagging behind by more events than
+/// this will miss the oldest ones.
+const RATE_LIMIT_EVENTS_CAPACITY: usize = 256;
+
+/// What caused a [`RateLimitEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitEventKind {
+    /// A request was held back, because its bucket has no remaining requests.
+    BucketExhausted,
+    /// A request was held back, because the instance-wide global limit is exhausted.
+    GlobalLimit,
+    /// The server rejected a request with `429 Too Many Requests`.
+    TooManyRequests,
+}
+
+/// Emitted by an [`Instance`](crate::instance::Instance) every time a request is affected by a
+/// rate limit.
+///
+/// Subscribe to these via [`Instance::rate_limit_events`](crate::instance::Instance::rate_limit_events).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RateLimitEvent {
+    pub kind: RateLimitEventKind,
+    /// The path of the requested route, for example `/api/channels/1234/messages`.
+    pub route: String,
+    /// The bucket which caused the event.
+    pub bucket: LimitType,
+    /// How long to wait until the bucket is expected to be replenished.
+    pub wait: Duration,
+}
+
+impl RateLimitEvent {
+    /// Returns true if the event was emitted for a request to the given route, held back either
+    /// by the route's bucket or by the global limit.
+    pub fn concerns(&self, route: &Route) -> bool {
+        let path = route.path().split('?').next().unwrap_or_default();
+        self.route.ends_with(path)
+            && (self.bucket == route.limit_type() || self.bucket == LimitType::Global)
+    }
+
+    /// Drains the events received so far and returns the wait of the last one which
+    /// [concerns](RateLimitEvent::concerns) the given route, if any did.
+    pub fn last_wait(
+        events: &mut broadcast::Receiver<RateLimitEvent>,
+        route: &Route,
+    ) -> Option<Duration> {
+        let mut wait = None;
+        loop {
+            match events.try_recv() {
+                Ok(event) if event.concerns(route) => wait = Some(event.wait),
+                Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => {}
+                Err(_) => return wait,
+            }
+        }
+    }
+}
+
+/// A broadcast channel of [`RateLimitEvent`]s.
+///
+/// Clones share the same channel.
+#[derive(Debug, Clone)]
+pub struct RateLimitEvents {
+    sender: broadcast::Sender<RateLimitEvent>,
+}
+
+impl Default for RateLimitEvents {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(RATE_LIMIT_EVENTS_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl RateLimitEvents {
+    /// Returns a receiver which gets all events emitted after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<RateLimitEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Sends an event to all current receivers. Events are dropped if there are none.
+    pub fn emit(&self, event: RateLimitEvent) {
+        log::trace!("Emitting rate limit event: {:?}", event);
+        // Only errors if there are no receivers, which is fine
+        let _ = self.sender.send(event);
+    }
+}
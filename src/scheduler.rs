@@ -0,0 +1,265 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Sending messages and making other requests at a later time.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::errors::{ChorusError, ChorusResult};
+use crate::instance::ChorusUser;
+use crate::types::{Message, MessageSendSchema, Snowflake};
+
+/// A request which can be scheduled with a [`Scheduler`].
+///
+/// Actions are serializable, so that a [`TaskStore`] can persist them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    /// Sends a message, see [`Message::send`].
+    SendMessage {
+        channel_id: Snowflake,
+        message: Box<MessageSendSchema>,
+    },
+    /// Sends a request to an arbitrary endpoint, see
+    /// [`ChorusUser::request_raw`](crate::instance::ChorusUser::request_raw).
+    Request {
+        /// The HTTP method, for example `POST`.
+        method: String,
+        /// The path relative to the instance's API url, for example `/channels/1234/typing`.
+        path: String,
+        body: Option<Value>,
+    },
+}
+
+/// A scheduled [`ScheduledAction`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub id: Snowflake,
+    /// When the action is executed next.
+    pub run_at: DateTime<Utc>,
+    pub action: ScheduledAction,
+    /// How often executing the action has failed so far.
+    pub failed_attempts: u32,
+}
+
+/// Persists the tasks of a [`Scheduler`], so that they survive restarts.
+///
+/// A store may be shared between schedulers of different users, as long as every scheduler only
+/// sees its own tasks.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait TaskStore: Send + Sync + fmt::Debug {
+    /// Inserts a task, or replaces the task with the same id.
+    async fn save(&self, task: ScheduledTask);
+    /// Removes the task with the given id, if it exists.
+    async fn remove(&self, id: Snowflake);
+    /// Returns all tasks of the store.
+    async fn load(&self) -> Vec<ScheduledTask>;
+}
+
+/// A [`TaskStore`] which keeps the tasks in memory. Tasks are lost once it is dropped.
+#[derive(Debug, Default)]
+pub struct MemoryTaskStore {
+    tasks: Mutex<HashMap<Snowflake, ScheduledTask>>,
+}
+
+impl MemoryTaskStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TaskStore for MemoryTaskStore {
+    async fn save(&self, task: ScheduledTask) {
+        self.tasks.lock().await.insert(task.id, task);
+    }
+
+    async fn remove(&self, id: Snowflake) {
+        self.tasks.lock().await.remove(&id);
+    }
+
+    async fn load(&self) -> Vec<ScheduledTask> {
+        self.tasks.lock().await.values().cloned().collect()
+    }
+}
+
+/// Executes [`ScheduledAction`]s of a user once they are due.
+///
+/// Tasks are kept in a [`TaskStore`]. They are executed by [`Scheduler::run_due`], or
+/// continuously by [`Scheduler::run`]. If a task fails with an error which might go away (see
+/// [`ChorusError::is_retryable`]), it is retried after [`Scheduler::retry_delay`], multiplied by
+/// the amount of failed attempts so far, until [`Scheduler::max_attempts`] is reached.
+///
+/// Clones share the same user and store.
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    user: Arc<Mutex<ChorusUser>>,
+    store: Arc<dyn TaskStore>,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl ChorusUser {
+    /// Creates a [`Scheduler`] which makes its requests as this user, and keeps its tasks in the
+    /// given store.
+    pub fn scheduler(&self, store: impl TaskStore + 'static) -> Scheduler {
+        Scheduler {
+            user: Arc::new(Mutex::new(self.clone())),
+            store: Arc::new(store),
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Scheduler {
+    /// Sets how often a task is attempted before it is given up. Defaults to 3.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay after the first failed attempt of a task. Defaults to 30 seconds.
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Schedules an action to be executed at the given time, and returns the scheduled task.
+    ///
+    /// Messages are given a nonce, if they have none, and the server is asked to enforce it, so
+    /// that a retried message is not sent twice if an earlier attempt reached the server.
+    pub async fn schedule(
+        &self,
+        run_at: DateTime<Utc>,
+        mut action: ScheduledAction,
+    ) -> ScheduledTask {
+        if let ScheduledAction::SendMessage { message, .. } = &mut action {
+            message.ensure_nonce();
+            message.enforce_nonce.get_or_insert(true);
+        }
+        let task = ScheduledTask {
+            id: Snowflake::generate(),
+            run_at,
+            action,
+            failed_attempts: 0,
+        };
+        self.store.save(task.clone()).await;
+        task
+    }
+
+    /// Schedules a message to be sent at the given time.
+    pub async fn schedule_message(
+        &self,
+        run_at: DateTime<Utc>,
+        channel_id: Snowflake,
+        message: MessageSendSchema,
+    ) -> ScheduledTask {
+        self.schedule(
+            run_at,
+            ScheduledAction::SendMessage {
+                channel_id,
+                message: Box::new(message),
+            },
+        )
+        .await
+    }
+
+    /// Cancels a scheduled task.
+    pub async fn cancel(&self, id: Snowflake) {
+        self.store.remove(id).await;
+    }
+
+    /// Returns all scheduled tasks, ordered by when they are executed next.
+    pub async fn tasks(&self) -> Vec<ScheduledTask> {
+        let mut tasks = self.store.load().await;
+        tasks.sort_by_key(|task| (task.run_at, task.id));
+        tasks
+    }
+
+    /// Executes all tasks which are due, in order.
+    ///
+    /// Returns the id and result of every task which has been completed or given up. Tasks which
+    /// will be retried are not included.
+    pub async fn run_due(&self) -> Vec<(Snowflake, ChorusResult<()>)> {
+        let now = Utc::now();
+        let mut finished = Vec::new();
+        for mut task in self.tasks().await {
+            if task.run_at > now {
+                break;
+            }
+            let result = self.execute(&task.action).await;
+            match result {
+                Err(e) if e.is_retryable() && task.failed_attempts + 1 < self.max_attempts => {
+                    task.failed_attempts += 1;
+                    let delay = self.retry_delay.saturating_mul(task.failed_attempts);
+                    task.run_at = chrono::Duration::from_std(delay)
+                        .ok()
+                        .and_then(|delay| Utc::now().checked_add_signed(delay))
+                        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                    log::info!(
+                        "Scheduled task {} failed ({}), retrying at {}",
+                        task.id,
+                        e,
+                        task.run_at
+                    );
+                    self.store.save(task).await;
+                }
+                result => {
+                    if let Err(e) = &result {
+                        log::warn!("Scheduled task {} failed: {}", task.id, e);
+                    }
+                    self.store.remove(task.id).await;
+                    finished.push((task.id, result));
+                }
+            }
+        }
+        finished
+    }
+
+    /// Executes due tasks every `poll_interval`, forever.
+    ///
+    /// Usually spawned as a separate task, which is aborted once the scheduler is not needed
+    /// anymore.
+    pub async fn run(&self, poll_interval: Duration) {
+        loop {
+            self.run_due().await;
+            crate::sleep(poll_interval).await;
+        }
+    }
+
+    async fn execute(&self, action: &ScheduledAction) -> ChorusResult<()> {
+        let mut user = self.user.lock().await;
+        match action {
+            ScheduledAction::SendMessage {
+                channel_id,
+                message,
+            } => Message::send(&mut user, *channel_id, message.as_ref().clone())
+                .await
+                .map(|_| ()),
+            ScheduledAction::Request { method, path, body } => {
+                let method =
+                    method
+                        .parse::<http::Method>()
+                        .map_err(|e| ChorusError::InvalidArguments {
+                            error: format!("{} is not a valid HTTP method: {}", method, e),
+                        })?;
+                user.request_raw(method, path, body.clone())
+                    .await
+                    .map(|_| ())
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Managing multiple authenticated users at once.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use futures_util::future::join_all;
+use reqwest::Client;
+
+use crate::gateway::Shared;
+use crate::instance::{ChorusUser, Instance};
+use crate::types::Snowflake;
+use crate::UrlBundle;
+
+/// Owns multiple [`ChorusUser`]s, which may belong to one or more [`Instance`]s.
+///
+/// All instances added to the manager use the same HTTP [`Client`], and all users of the same
+/// instance share the instance-wide rate limits (global, IP and auth limits), the same way users
+/// logged in through the same [`Instance`] object would. Per-user rate limits and request
+/// limiters stay separate.
+///
+/// Users are looked up by the [`UrlBundle`] of their instance and their id, since users of
+/// different instances may share an id. All of their gateway connections can be closed at once
+/// with [`SessionManager::shutdown`].
+#[derive(Debug, Default)]
+pub struct SessionManager {
+    client: Client,
+    instances: HashMap<UrlBundle, Shared<Instance>>,
+    users: HashMap<(UrlBundle, Snowflake), ChorusUser>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`SessionManager`] which uses the given HTTP client for all of its instances.
+    pub fn with_client(client: Client) -> Self {
+        Self {
+            client,
+            ..Default::default()
+        }
+    }
+
+    /// The HTTP client shared between all instances of this manager.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Adds an instance to the manager, and returns the shared handle to it.
+    ///
+    /// If an instance with the same [`UrlBundle`] has already been added, it is returned instead
+    /// and `instance` is dropped.
+    pub fn add_instance(&mut self, mut instance: Instance) -> Shared<Instance> {
+        if let Some(existing) = self.instances.get(&instance.urls) {
+            return existing.clone();
+        }
+        instance.client = self.client.clone();
+        let urls = instance.urls.clone();
+        let shared = Arc::new(RwLock::new(instance));
+        self.instances.insert(urls, shared.clone());
+        shared
+    }
+
+    /// Returns the instance with the given [`UrlBundle`], if it has been added.
+    pub fn instance(&self, urls: &UrlBundle) -> Option<Shared<Instance>> {
+        self.instances.get(urls).cloned()
+    }
+
+    /// Returns all instances of this manager.
+    pub fn instances(&self) -> impl Iterator<Item = &Shared<Instance>> {
+        self.instances.values()
+    }
+
+    /// Adds a user to the manager. The user is moved onto the manager's instance with the same
+    /// [`UrlBundle`], which is added if it is not yet known.
+    ///
+    /// Returns the user which was previously stored under the same instance and user id, if there
+    /// was one. Its gateway connection is left open.
+    pub fn add_user(&mut self, mut user: ChorusUser) -> Option<ChorusUser> {
+        let instance = user.belongs_to.read().unwrap().clone();
+        let urls = instance.urls.clone();
+        user.belongs_to = self.add_instance(instance);
+        let id = user.object.read().unwrap().id;
+        self.users.insert((urls, id), user)
+    }
+
+    /// Returns the user with the given id on the instance with the given [`UrlBundle`].
+    pub fn get(&self, urls: &UrlBundle, user_id: Snowflake) -> Option<&ChorusUser> {
+        self.users.get(&(urls.clone(), user_id))
+    }
+
+    /// Returns the user with the given id on the instance with the given [`UrlBundle`] mutably,
+    /// for example to make requests with it.
+    pub fn get_mut(&mut self, urls: &UrlBundle, user_id: Snowflake) -> Option<&mut ChorusUser> {
+        self.users.get_mut(&(urls.clone(), user_id))
+    }
+
+    /// Removes a user from the manager and returns it. Its gateway connection is left open.
+    pub fn remove(&mut self, urls: &UrlBundle, user_id: Snowflake) -> Option<ChorusUser> {
+        self.users.remove(&(urls.clone(), user_id))
+    }
+
+    /// Returns the instance's [`UrlBundle`] and the id of all users of this manager.
+    pub fn user_ids(&self) -> impl Iterator<Item = &(UrlBundle, Snowflake)> {
+        self.users.keys()
+    }
+
+    /// Returns all users of this manager.
+    pub fn users(&self) -> impl Iterator<Item = &ChorusUser> {
+        self.users.values()
+    }
+
+    /// Returns all users belonging to the instance with the given [`UrlBundle`].
+    pub fn users_of<'a>(&'a self, urls: &'a UrlBundle) -> impl Iterator<Item = &'a ChorusUser> {
+        self.users
+            .iter()
+            .filter(move |((user_urls, _), _)| user_urls == urls)
+            .map(|(_, user)| user)
+    }
+
+    /// The amount of users in this manager.
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Returns true if the manager has no users.
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// Closes the gateway connections of all users at once and removes them, along with all
+    /// instances, from the manager.
+    pub async fn shutdown(&mut self) {
+        let users: Vec<ChorusUser> = self.users.drain().map(|(_, user)| user).collect();
+        log::info!("Shutting down {} sessions", users.len());
+        join_all(users.iter().map(|user| user.gateway.close())).await;
+        self.instances.clear();
+    }
+}
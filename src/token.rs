@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Providers for replacing the token of a [`ChorusUser`](crate::instance::ChorusUser) once it has
+//! been rotated or revoked.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+
+pub mod store;
+pub use store::*;
+
+/// Supplies a [`ChorusUser`](crate::instance::ChorusUser) with a new token, once the server has
+/// rejected its current one.
+///
+/// When a request fails with `401 Unauthorized` and the user has a token provider, the provider
+/// is asked for a new token and the request is retried once with it. See
+/// [`ChorusUser::set_token_provider`](crate::instance::ChorusUser::set_token_provider).
+///
+/// Note that the user's gateway connection is not re-identified with the new token.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait TokenProvider: Send + Sync + fmt::Debug {
+    /// Returns a token to replace `rejected`, or `None` if no new token can be obtained.
+    async fn refresh(&self, rejected: &str) -> Option<String>;
+}
+
+/// A fixed token.
+///
+/// A rejected token is only replaced if it differs from this one, so that a user whose token was
+/// overwritten can return to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticToken(pub String);
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TokenProvider for StaticToken {
+    async fn refresh(&self, rejected: &str) -> Option<String> {
+        (self.0 != rejected).then(|| self.0.clone())
+    }
+}
+
+/// An OAuth2 access token, which is renewed through the `refresh_token` grant of the token
+/// endpoint.
+///
+/// The new token is used as `{token_type} {access_token}`, for example `Bearer abc`. If the
+/// endpoint hands out a new refresh token, it replaces the previous one.
+#[derive(Debug)]
+pub struct OAuth2Token {
+    token_url: String,
+    client_id: String,
+    client_secret: Option<String>,
+    refresh_token: Mutex<String>,
+    client: Client,
+}
+
+impl OAuth2Token {
+    /// Creates a provider which refreshes the access token at `token_url`, for example
+    /// `https://example.com/api/oauth2/token`.
+    pub fn new(
+        token_url: String,
+        client_id: String,
+        client_secret: Option<String>,
+        refresh_token: String,
+    ) -> Self {
+        Self {
+            token_url,
+            client_id,
+            client_secret,
+            refresh_token: Mutex::new(refresh_token),
+            client: Client::new(),
+        }
+    }
+
+    /// The refresh token which will be used for the next refresh.
+    pub fn refresh_token(&self) -> String {
+        self.refresh_token.lock().unwrap().clone()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OAuth2TokenResponse {
+    access_token: String,
+    token_type: String,
+    refresh_token: Option<String>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TokenProvider for OAuth2Token {
+    async fn refresh(&self, _rejected: &str) -> Option<String> {
+        let refresh_token = self.refresh_token();
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", self.client_id.as_str()),
+        ];
+        if let Some(client_secret) = &self.client_secret {
+            form.push(("client_secret", client_secret.as_str()));
+        }
+        let response = match self.client.post(&self.token_url).form(&form).send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                log::warn!(
+                    "Refreshing the OAuth2 token failed with status {}",
+                    response.status()
+                );
+                return None;
+            }
+            Err(e) => {
+                log::warn!("Refreshing the OAuth2 token failed: {}", e);
+                return None;
+            }
+        };
+        let token = match response.json::<OAuth2TokenResponse>().await {
+            Ok(token) => token,
+            Err(e) => {
+                log::warn!("Received an invalid OAuth2 token response: {}", e);
+                return None;
+            }
+        };
+        if let Some(refresh_token) = token.refresh_token {
+            *self.refresh_token.lock().unwrap() = refresh_token;
+        }
+        Some(format!("{} {}", token.token_type, token.access_token))
+    }
+}
+
+/// A token provided by a callback, which receives the rejected token.
+#[derive(Clone)]
+pub struct TokenCallback {
+    callback: Arc<dyn Fn(String) -> BoxFuture<'static, Option<String>> + Send + Sync>,
+}
+
+impl TokenCallback {
+    pub fn new<F, Fut>(callback: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<String>> + Send + 'static,
+    {
+        Self {
+            callback: Arc::new(move |rejected| Box::pin(callback(rejected))),
+        }
+    }
+}
+
+impl fmt::Debug for TokenCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCallback").finish_non_exhaustive()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TokenProvider for TokenCallback {
+    async fn refresh(&self, rejected: &str) -> Option<String> {
+        (self.callback)(rejected.to_string()).await
+    }
+}
@@ -0,0 +1,301 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Storage for tokens between runs, see [`TokenStore`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::errors::TokenStoreError;
+
+/// Keeps tokens between runs, for example so command line tools do not have to log in on every
+/// invocation.
+///
+/// Tokens are stored by a key chosen by the application, such as the account's email address or
+/// user id. Implement this trait to keep tokens somewhere else, such as in the keychain of the
+/// operating system.
+pub trait TokenStore: Send + Sync + fmt::Debug {
+    /// Returns the token stored under `key`, if there is one.
+    fn get(&self, key: &str) -> Result<Option<String>, TokenStoreError>;
+
+    /// Stores a token under `key`, replacing the previous one.
+    fn set(&self, key: &str, token: &str) -> Result<(), TokenStoreError>;
+
+    /// Removes the token stored under `key`, if there is one.
+    fn remove(&self, key: &str) -> Result<(), TokenStoreError>;
+}
+
+/// Keeps tokens in memory, so they are lost once the program exits. Mostly useful for tests.
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: Mutex<HashMap<String, String>>,
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn get(&self, key: &str) -> Result<Option<String>, TokenStoreError> {
+        Ok(self.tokens.lock().unwrap().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, token: &str) -> Result<(), TokenStoreError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), token.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), TokenStoreError> {
+        self.tokens.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "token_store", not(target_arch = "wasm32")))]
+pub use encrypted::*;
+
+#[cfg(all(feature = "token_store", not(target_arch = "wasm32")))]
+mod encrypted {
+    use std::collections::BTreeMap;
+    use std::io::{ErrorKind, Write};
+    use std::path::PathBuf;
+
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use crypto_secretbox::aead::{Aead, KeyInit};
+    use crypto_secretbox::cipher::generic_array::GenericArray;
+    use crypto_secretbox::XSalsa20Poly1305;
+    use hmac::Hmac;
+    use rand::RngCore;
+    use serde::{Deserialize, Serialize};
+    use sha2::Sha256;
+
+    use super::*;
+
+    /// The default amount of PBKDF2 iterations, as recommended by OWASP for HMAC-SHA256.
+    pub const DEFAULT_PBKDF2_ITERATIONS: u32 = 600_000;
+
+    const SALT_LENGTH: usize = 16;
+    const NONCE_LENGTH: usize = 24;
+
+    /// Keys which have already been derived, by salt and iterations
+    type DerivedKeys = HashMap<(Vec<u8>, u32), [u8; 32]>;
+
+    /// Keeps tokens in a file, encrypted with a passphrase.
+    ///
+    /// Each token is encrypted with XSalsa20-Poly1305, using a key derived from the passphrase
+    /// and a random salt with PBKDF2-HMAC-SHA256. On unix, the file is only readable by its
+    /// owner.
+    ///
+    /// Deriving a key is deliberately slow, so derived keys are kept for as long as the store
+    /// lives; only the first access with each salt takes a noticeable amount of time.
+    ///
+    /// Requires the `token_store` feature.
+    pub struct EncryptedTokenFile {
+        path: PathBuf,
+        passphrase: String,
+        iterations: u32,
+        /// The salt newly stored tokens are encrypted with
+        salt: [u8; SALT_LENGTH],
+        keys: Mutex<DerivedKeys>,
+        /// Serializes reading and rewriting the file
+        lock: Mutex<()>,
+    }
+
+    impl fmt::Debug for EncryptedTokenFile {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("EncryptedTokenFile")
+                .field("path", &self.path)
+                .field("iterations", &self.iterations)
+                .finish_non_exhaustive()
+        }
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct TokenFile {
+        tokens: BTreeMap<String, EncryptedToken>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct EncryptedToken {
+        /// Kept so tokens stay readable if the amount of iterations is changed
+        iterations: u32,
+        salt: String,
+        nonce: String,
+        ciphertext: String,
+    }
+
+    fn io_error(error: std::io::Error) -> TokenStoreError {
+        TokenStoreError::Io {
+            error: error.to_string(),
+        }
+    }
+
+    fn format_error(error: impl fmt::Display) -> TokenStoreError {
+        TokenStoreError::InvalidFormat {
+            error: error.to_string(),
+        }
+    }
+
+    /// Derives a key with PBKDF2-HMAC-SHA256.
+    fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+        let mut key = [0; 32];
+        // HMAC accepts keys of any length, so this cannot fail
+        pbkdf2::pbkdf2::<Hmac<Sha256>>(passphrase.as_bytes(), salt, iterations, &mut key).unwrap();
+        key
+    }
+
+    impl EncryptedTokenFile {
+        /// Keeps tokens in the file at `path`, which is created once the first token is stored.
+        pub fn new(path: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+            let mut salt = [0; SALT_LENGTH];
+            rand::thread_rng().fill_bytes(&mut salt);
+            Self {
+                path: path.into(),
+                passphrase: passphrase.into(),
+                iterations: DEFAULT_PBKDF2_ITERATIONS,
+                salt,
+                keys: Mutex::new(HashMap::new()),
+                lock: Mutex::new(()),
+            }
+        }
+
+        /// Sets how many PBKDF2 iterations newly stored tokens are encrypted with. Defaults to
+        /// [`DEFAULT_PBKDF2_ITERATIONS`]; fewer iterations make guessing the passphrase easier.
+        pub fn iterations(mut self, iterations: u32) -> Self {
+            self.iterations = iterations.max(1);
+            self
+        }
+
+        fn read(&self) -> Result<TokenFile, TokenStoreError> {
+            match std::fs::read(&self.path) {
+                Ok(contents) => serde_json::from_slice(&contents).map_err(format_error),
+                Err(error) if error.kind() == ErrorKind::NotFound => Ok(TokenFile::default()),
+                Err(error) => Err(io_error(error)),
+            }
+        }
+
+        /// Writes the file to a temporary file next to it first, which then replaces it, so the
+        /// stored tokens survive a crash while writing.
+        fn write(&self, file: &TokenFile) -> Result<(), TokenStoreError> {
+            let mut temporary_name = self.path.file_name().unwrap_or_default().to_os_string();
+            temporary_name.push(".tmp");
+            let temporary_path = self.path.with_file_name(temporary_name);
+
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let mut handle = options.open(&temporary_path).map_err(io_error)?;
+            // The mode is only applied when the file is created, so a leftover temporary file
+            // could still be readable by others
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                handle
+                    .set_permissions(std::fs::Permissions::from_mode(0o600))
+                    .map_err(io_error)?;
+            }
+            handle
+                .write_all(&serde_json::to_vec(file).unwrap())
+                .and_then(|_| handle.sync_all())
+                .map_err(io_error)?;
+            std::fs::rename(&temporary_path, &self.path).map_err(io_error)
+        }
+
+        /// Returns the key for the given salt and iterations, deriving it if it is not known yet.
+        fn key(&self, salt: &[u8], iterations: u32) -> [u8; 32] {
+            *self
+                .keys
+                .lock()
+                .unwrap()
+                .entry((salt.to_vec(), iterations))
+                .or_insert_with(|| derive_key(&self.passphrase, salt, iterations))
+        }
+
+        fn encrypt(&self, token: &str) -> EncryptedToken {
+            let mut nonce = [0; NONCE_LENGTH];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let key = self.key(&self.salt, self.iterations);
+            let ciphertext = XSalsa20Poly1305::new(GenericArray::from_slice(&key))
+                .encrypt(GenericArray::from_slice(&nonce), token.as_bytes())
+                .unwrap();
+            EncryptedToken {
+                iterations: self.iterations,
+                salt: STANDARD.encode(self.salt),
+                nonce: STANDARD.encode(nonce),
+                ciphertext: STANDARD.encode(ciphertext),
+            }
+        }
+
+        fn decrypt(&self, token: &EncryptedToken) -> Result<String, TokenStoreError> {
+            let salt = STANDARD.decode(&token.salt).map_err(format_error)?;
+            let nonce = STANDARD.decode(&token.nonce).map_err(format_error)?;
+            let ciphertext = STANDARD.decode(&token.ciphertext).map_err(format_error)?;
+            if nonce.len() != NONCE_LENGTH {
+                return Err(format_error("invalid nonce length"));
+            }
+            let key = self.key(&salt, token.iterations);
+            let plaintext = XSalsa20Poly1305::new(GenericArray::from_slice(&key))
+                .decrypt(GenericArray::from_slice(&nonce), ciphertext.as_slice())
+                .map_err(|_| TokenStoreError::Decryption)?;
+            String::from_utf8(plaintext).map_err(format_error)
+        }
+    }
+
+    impl TokenStore for EncryptedTokenFile {
+        fn get(&self, key: &str) -> Result<Option<String>, TokenStoreError> {
+            let _lock = self.lock.lock().unwrap();
+            self.read()?
+                .tokens
+                .get(key)
+                .map(|token| self.decrypt(token))
+                .transpose()
+        }
+
+        fn set(&self, key: &str, token: &str) -> Result<(), TokenStoreError> {
+            let _lock = self.lock.lock().unwrap();
+            let mut file = self.read()?;
+            file.tokens.insert(key.to_string(), self.encrypt(token));
+            self.write(&file)
+        }
+
+        fn remove(&self, key: &str) -> Result<(), TokenStoreError> {
+            let _lock = self.lock.lock().unwrap();
+            let mut file = self.read()?;
+            if file.tokens.remove(key).is_some() {
+                self.write(&file)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::derive_key;
+
+        #[test]
+        fn pbkdf2_test_vectors() {
+            let hex = |key: [u8; 32]| {
+                key.iter().fold(String::new(), |mut hex, byte| {
+                    hex.push_str(&format!("{byte:02x}"));
+                    hex
+                })
+            };
+            assert_eq!(
+                hex(derive_key("password", b"salt", 1)),
+                "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+            );
+            assert_eq!(
+                hex(derive_key("password", b"salt", 2)),
+                "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+            );
+            // The first half of the 64 byte key of https://www.rfc-editor.org/rfc/rfc7914#section-11
+            assert_eq!(
+                hex(derive_key("passwd", b"salt", 1)),
+                "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+            );
+        }
+    }
+}
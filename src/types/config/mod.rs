@@ -7,18 +7,25 @@ use serde_json::{Map, Value};
 
 pub use crate::{
     types::config::types::{
-        api_configuration::ApiConfiguration, cdn_configuration::CdnConfiguration,
-        defaults_configuration::DefaultsConfiguration, email_configuration::EmailConfiguration,
+        api_configuration::ApiConfiguration,
+        cdn_configuration::CdnConfiguration,
+        defaults_configuration::DefaultsConfiguration,
+        email_configuration::EmailConfiguration,
         endpoint_configuration::EndpointConfiguration,
         external_tokens_configuration::ExternalTokensConfiguration,
-        general_configuration::GeneralConfiguration, gif_configuration::GifConfiguration,
-        guild_configuration::GuildConfiguration, kafka_configuration::KafkaConfiguration,
-        limit_configuration::LimitsConfiguration, login_configuration::LoginConfiguration,
+        general_configuration::{GeneralConfiguration, InstancePolicies},
+        gif_configuration::GifConfiguration,
+        guild_configuration::GuildConfiguration,
+        kafka_configuration::KafkaConfiguration,
+        limit_configuration::LimitsConfiguration,
+        login_configuration::LoginConfiguration,
         metrics_configuration::MetricsConfiguration,
         password_reset_configuration::PasswordResetConfiguration,
-        rabbit_mq_configuration::RabbitMQConfiguration, region_configuration::RegionConfiguration,
-        register_configuration::RegisterConfiguration,
-        security_configuration::SecurityConfiguration, sentry_configuration::SentryConfiguration,
+        rabbit_mq_configuration::RabbitMQConfiguration,
+        region_configuration::RegionConfiguration,
+        register_configuration::{RegisterConfiguration, RegistrationRequirements},
+        security_configuration::SecurityConfiguration,
+        sentry_configuration::SentryConfiguration,
         template_configuration::TemplateConfiguration,
     },
     types::entities::ConfigEntity,
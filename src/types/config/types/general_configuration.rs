@@ -33,3 +33,11 @@ impl Default for GeneralConfiguration {
         }
     }
 }
+
+/// The policies of an instance which decide what a client has to show when signing up, as
+/// returned by [`Instance::policies`](crate::instance::Instance::policies).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstancePolicies {
+    /// The url of the instance's terms of service, if it has any
+    pub tos_url: Option<String>,
+}
@@ -43,3 +43,43 @@ impl Default for RegisterConfiguration {
         }
     }
 }
+
+/// The requirements an instance places on new accounts, as far as a registration form needs to
+/// know about them.
+///
+/// Built from an instance's [`RegisterConfiguration`], without the parts only the server itself
+/// uses. Spacebar does not serve its registration configuration over the api, so this is only
+/// available to those who have the configuration, such as the instance's operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RegistrationRequirements {
+    /// Whether the instance accepts new registrations at all
+    pub registration_open: bool,
+    pub email_required: bool,
+    pub captcha_required: bool,
+    pub invite_required: bool,
+    pub date_of_birth_required: bool,
+    /// The minimum age in years, if a date of birth is required
+    pub minimum_age: Option<u8>,
+}
+
+impl From<&RegisterConfiguration> for RegistrationRequirements {
+    fn from(config: &RegisterConfiguration) -> Self {
+        Self {
+            registration_open: !config.disabled && config.allow_new_registration,
+            email_required: config.email.required,
+            captcha_required: config.require_captcha,
+            invite_required: config.require_invite,
+            date_of_birth_required: config.date_of_birth.required,
+            minimum_age: config
+                .date_of_birth
+                .required
+                .then_some(config.date_of_birth.minimum),
+        }
+    }
+}
+
+impl From<RegisterConfiguration> for RegistrationRequirements {
+    fn from(config: RegisterConfiguration) -> Self {
+        Self::from(&config)
+    }
+}
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -9,7 +11,9 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::gateway::Shared;
 use crate::types::utils::Snowflake;
-use crate::types::{Team, User};
+use crate::types::{
+    Attachment, Channel, GuildMember, IntoShared, Locale, Message, RoleObject, Team, User,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
@@ -142,27 +146,107 @@ bitflags! {
 /// See <https://discord.com/developers/docs/interactions/application-commands#application-command-object>
 pub struct ApplicationCommand {
     pub id: Snowflake,
+    #[serde(default)]
+    pub r#type: ApplicationCommandType,
     pub application_id: Snowflake,
     pub name: String,
+    #[serde(default)]
+    pub name_localizations: Option<HashMap<Locale, String>>,
+    /// Empty for [context menu](ApplicationCommandType::is_context_menu) commands
     pub description: String,
+    #[serde(default)]
+    pub description_localizations: Option<HashMap<Locale, String>>,
+    /// Empty for [context menu](ApplicationCommandType::is_context_menu) commands
+    #[serde(default)]
     pub options: Vec<Shared<ApplicationCommandOption>>,
 }
 
+impl ApplicationCommand {
+    /// Returns the name of the command in the given locale, see [`Locale::localize`].
+    pub fn localized_name(&self, locale: &Locale) -> &str {
+        localize(&self.name_localizations, locale, &self.name)
+    }
+
+    /// Returns the description of the command in the given locale, see [`Locale::localize`].
+    pub fn localized_description(&self, locale: &Locale) -> &str {
+        localize(&self.description_localizations, locale, &self.description)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-types>
+pub enum ApplicationCommandType {
+    /// A slash command
+    #[default]
+    ChatInput = 1,
+    /// A command in the context menu of users
+    User = 2,
+    /// A command in the context menu of messages
+    Message = 3,
+}
+
+impl ApplicationCommandType {
+    /// Whether the command is invoked from a context menu, on a target user or message. Such
+    /// commands have no description or options.
+    pub fn is_context_menu(&self) -> bool {
+        matches!(
+            self,
+            ApplicationCommandType::User | ApplicationCommandType::Message
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Reference
 /// See <https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-option-structure>
 pub struct ApplicationCommandOption {
     pub r#type: ApplicationCommandOptionType,
     pub name: String,
+    #[serde(default)]
+    pub name_localizations: Option<HashMap<Locale, String>>,
     pub description: String,
+    #[serde(default)]
+    pub description_localizations: Option<HashMap<Locale, String>>,
     pub required: bool,
     pub choices: Vec<ApplicationCommandOptionChoice>,
+    /// Whether the choices are suggested while the user types, through
+    /// [autocomplete interactions](crate::types::InteractionType::ApplicationCommandAutocomplete),
+    /// instead of being given in [`choices`](Self::choices)
+    #[serde(default)]
+    pub autocomplete: Option<bool>,
     pub options: Shared<Vec<ApplicationCommandOption>>,
 }
 
+impl ApplicationCommandOption {
+    /// Returns the name of the option in the given locale, see [`Locale::localize`].
+    pub fn localized_name(&self, locale: &Locale) -> &str {
+        localize(&self.name_localizations, locale, &self.name)
+    }
+
+    /// Returns the description of the option in the given locale, see [`Locale::localize`].
+    pub fn localized_description(&self, locale: &Locale) -> &str {
+        localize(&self.description_localizations, locale, &self.description)
+    }
+}
+
+fn localize<'a>(
+    localizations: &'a Option<HashMap<Locale, String>>,
+    locale: &Locale,
+    default: &'a str,
+) -> &'a str {
+    match localizations {
+        Some(localizations) => locale.localize(localizations, default),
+        None => default,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ApplicationCommandOptionChoice {
     pub name: String,
+    #[serde(default)]
+    pub name_localizations: Option<HashMap<Locale, String>>,
     pub value: Value,
 }
 
@@ -190,17 +274,242 @@ pub enum ApplicationCommandOptionType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-object-application-command-data-structure>
 pub struct ApplicationCommandInteractionData {
     pub id: Snowflake,
     pub name: String,
+    #[serde(default)]
+    pub r#type: ApplicationCommandType,
+    /// The user or message a [context menu](ApplicationCommandType::is_context_menu) command was
+    /// invoked on. The target is included in the [resolved data](Self::resolved).
+    #[serde(default)]
+    pub target_id: Option<Snowflake>,
+    #[serde(default)]
     pub options: Vec<Shared<ApplicationCommandInteractionDataOption>>,
+    /// The users, members, roles and channels the options refer to
+    #[serde(default)]
+    pub resolved: Option<InteractionResolvedData>,
+}
+
+impl ApplicationCommandInteractionData {
+    /// Returns the user a [`User`](ApplicationCommandType::User) command was invoked on, out of
+    /// the [resolved data](Self::resolved).
+    pub fn target_user(&self) -> Option<User> {
+        if self.r#type != ApplicationCommandType::User {
+            return None;
+        }
+        self.resolved.as_ref()?.users.get(&self.target_id?).cloned()
+    }
+
+    /// Returns the member a [`User`](ApplicationCommandType::User) command was invoked on, out of
+    /// the [resolved data](Self::resolved). Only available for commands invoked in guilds.
+    pub fn target_member(&self) -> Option<GuildMember> {
+        if self.r#type != ApplicationCommandType::User {
+            return None;
+        }
+        self.resolved.as_ref()?.member(self.target_id?)
+    }
+
+    /// Returns the message a [`Message`](ApplicationCommandType::Message) command was invoked
+    /// on, out of the [resolved data](Self::resolved).
+    pub fn target_message(&self) -> Option<Message> {
+        if self.r#type != ApplicationCommandType::Message {
+            return None;
+        }
+        self.resolved
+            .as_ref()?
+            .messages
+            .get(&self.target_id?)
+            .cloned()
+    }
+
+    /// Returns the names of the invoked subcommand group and subcommand, if any.
+    ///
+    /// For `/settings notifications enable`, this is `["notifications", "enable"]`.
+    pub fn subcommand_path(&self) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut options = self.options.clone();
+        while let Some(subcommand) = first_subcommand(&options) {
+            let subcommand = subcommand.read().unwrap();
+            path.push(subcommand.name.clone());
+            options = subcommand.options.clone();
+        }
+        path
+    }
+
+    /// Returns the options of the invoked subcommand, or the options of the command itself if no
+    /// subcommand was invoked.
+    pub fn subcommand_options(&self) -> Vec<Shared<ApplicationCommandInteractionDataOption>> {
+        let mut options = self.options.clone();
+        while let Some(subcommand) = first_subcommand(&options) {
+            options = subcommand.read().unwrap().options.clone();
+        }
+        options
+    }
+
+    /// Returns the value of the option with the given name, out of the
+    /// [options of the invoked subcommand](Self::subcommand_options).
+    pub fn option_value(&self, name: &str) -> Option<Value> {
+        self.subcommand_options()
+            .iter()
+            .map(|option| option.read().unwrap())
+            .find(|option| option.name == name)
+            .map(|option| option.value.clone())
+            .filter(|value| !value.is_null())
+    }
+
+    /// Returns the option the user is typing, out of the
+    /// [options of the invoked subcommand](Self::subcommand_options). Only present in
+    /// [autocomplete interactions](crate::types::InteractionType::ApplicationCommandAutocomplete).
+    pub fn focused_option(&self) -> Option<ApplicationCommandInteractionDataOption> {
+        self.subcommand_options()
+            .iter()
+            .map(|option| option.read().unwrap())
+            .find(|option| option.focused == Some(true))
+            .map(|option| option.clone())
+    }
+
+    /// Returns the value of a [`String`](ApplicationCommandOptionType::String) option.
+    pub fn get_string(&self, name: &str) -> Option<String> {
+        match self.option_value(name)? {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an [`Integer`](ApplicationCommandOptionType::Integer) option.
+    pub fn get_integer(&self, name: &str) -> Option<i64> {
+        self.option_value(name)?.as_i64()
+    }
+
+    /// Returns the value of a [`Number`](ApplicationCommandOptionType::Number) option.
+    pub fn get_number(&self, name: &str) -> Option<f64> {
+        self.option_value(name)?.as_f64()
+    }
+
+    /// Returns the value of a [`Boolean`](ApplicationCommandOptionType::Boolean) option.
+    pub fn get_boolean(&self, name: &str) -> Option<bool> {
+        self.option_value(name)?.as_bool()
+    }
+
+    /// Returns the id of a [`User`](ApplicationCommandOptionType::User),
+    /// [`Channel`](ApplicationCommandOptionType::Channel),
+    /// [`Role`](ApplicationCommandOptionType::Role),
+    /// [`Mentionable`](ApplicationCommandOptionType::Mentionable) or
+    /// [`Attachment`](ApplicationCommandOptionType::Attachment) option.
+    pub fn get_snowflake(&self, name: &str) -> Option<Snowflake> {
+        serde_json::from_value(self.option_value(name)?).ok()
+    }
+
+    /// Returns the user a [`User`](ApplicationCommandOptionType::User) option refers to, out of
+    /// the [resolved data](Self::resolved).
+    pub fn get_user(&self, name: &str) -> Option<User> {
+        let id = self.get_snowflake(name)?;
+        self.resolved.as_ref()?.users.get(&id).cloned()
+    }
+
+    /// Returns the member a [`User`](ApplicationCommandOptionType::User) option refers to, out of
+    /// the [resolved data](Self::resolved). Only available for commands invoked in guilds.
+    pub fn get_member(&self, name: &str) -> Option<GuildMember> {
+        let id = self.get_snowflake(name)?;
+        self.resolved.as_ref()?.member(id)
+    }
+
+    /// Returns the role a [`Role`](ApplicationCommandOptionType::Role) option refers to, out of
+    /// the [resolved data](Self::resolved).
+    pub fn get_role(&self, name: &str) -> Option<RoleObject> {
+        let id = self.get_snowflake(name)?;
+        self.resolved.as_ref()?.roles.get(&id).cloned()
+    }
+
+    /// Returns the partial channel a [`Channel`](ApplicationCommandOptionType::Channel) option
+    /// refers to, out of the [resolved data](Self::resolved).
+    pub fn get_channel(&self, name: &str) -> Option<Channel> {
+        let id = self.get_snowflake(name)?;
+        self.resolved.as_ref()?.channels.get(&id).cloned()
+    }
+
+    /// Returns the attachment an [`Attachment`](ApplicationCommandOptionType::Attachment) option
+    /// refers to, out of the [resolved data](Self::resolved).
+    pub fn get_attachment(&self, name: &str) -> Option<Attachment> {
+        let id = self.get_snowflake(name)?;
+        self.resolved.as_ref()?.attachments.get(&id).cloned()
+    }
+}
+
+/// Returns the subcommand or subcommand group among the options, if any.
+fn first_subcommand(
+    options: &[Shared<ApplicationCommandInteractionDataOption>],
+) -> Option<Shared<ApplicationCommandInteractionDataOption>> {
+    options
+        .iter()
+        .find(|option| {
+            matches!(
+                option.read().unwrap().r#type,
+                ApplicationCommandOptionType::SubCommand
+                    | ApplicationCommandOptionType::SubCommandGroup
+            )
+        })
+        .cloned()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-object-application-command-interaction-data-option-structure>
 pub struct ApplicationCommandInteractionDataOption {
     pub name: String,
+    pub r#type: ApplicationCommandOptionType,
+    /// The value of the option, [`Value::Null`] for subcommands and subcommand groups
+    #[serde(default)]
     pub value: Value,
+    /// The options of a subcommand or subcommand group
+    #[serde(default)]
     pub options: Vec<Shared<ApplicationCommandInteractionDataOption>>,
+    /// Whether the user is typing this option, in
+    /// [autocomplete interactions](crate::types::InteractionType::ApplicationCommandAutocomplete).
+    /// Its value is what the user typed so far, and may be incomplete.
+    #[serde(default)]
+    pub focused: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// The objects the options of an application command refer to, keyed by their ids.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-object-resolved-data-structure>
+pub struct InteractionResolvedData {
+    #[serde(default)]
+    pub users: HashMap<Snowflake, User>,
+    /// Partial members, without their [`user`](GuildMember::user), `deaf` and `mute` fields.
+    /// The users are included in [`users`](Self::users).
+    #[serde(default)]
+    pub members: HashMap<Snowflake, GuildMember>,
+    #[serde(default)]
+    pub roles: HashMap<Snowflake, RoleObject>,
+    /// Partial channels, with only their id, name, type, permissions, and for threads the
+    /// thread metadata and parent id
+    #[serde(default)]
+    pub channels: HashMap<Snowflake, Channel>,
+    #[serde(default)]
+    pub messages: HashMap<Snowflake, Message>,
+    #[serde(default)]
+    pub attachments: HashMap<Snowflake, Attachment>,
+}
+
+impl InteractionResolvedData {
+    /// Returns the member with the given id, with its [`user`](GuildMember::user) filled in from
+    /// the resolved users.
+    pub fn member(&self, id: Snowflake) -> Option<GuildMember> {
+        let mut member = self.members.get(&id)?.clone();
+        if member.user.is_none() {
+            member.user = self
+                .users
+                .get(&id)
+                .map(|user| user.clone().into_public_user().into_shared());
+        }
+        Some(member)
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
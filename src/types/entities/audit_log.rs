@@ -2,10 +2,30 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 use crate::gateway::Shared;
 use crate::types::utils::Snowflake;
+use crate::types::{
+    ExplicitContentFilterLevel, MFALevel, MessageNotificationLevel, NSFWLevel, PermissionOverwrite,
+    User, VerificationLevel,
+};
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+/// The audit log of a guild, as returned by [`Guild::audit_logs`](crate::types::Guild::audit_logs).
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/audit-log#audit-log-object>
+pub struct AuditLog {
+    pub audit_log_entries: Vec<AuditLogEntry>,
+    /// The users referenced in the entries
+    #[serde(default)]
+    pub users: Vec<User>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 /// See <https://discord.com/developers/docs/resources/audit-log#audit-log-entry-object>
@@ -21,10 +41,209 @@ pub struct AuditLogEntry {
     pub reason: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
-/// See <https://discord.com/developers/docs/resources/audit-log#audit-log-change-object>
-pub struct AuditLogChange {
-    pub new_value: Option<serde_json::Value>,
-    pub old_value: Option<serde_json::Value>,
-    pub key: String,
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+/// The old and new value of a changed property.
+///
+/// Either value is missing if the property was not set before or after the change, such as
+/// when the entity was created or deleted.
+pub struct AuditLogValueChange<T> {
+    pub old_value: Option<T>,
+    pub new_value: Option<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
+/// A role which was added to or removed from a member, as in the `$add` and `$remove` changes.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/audit-log#audit-log-change-object-audit-log-change-exceptions>
+pub struct AuditLogRole {
+    pub id: Snowflake,
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawAuditLogChange {
+    key: String,
+    #[serde(default)]
+    old_value: Option<Value>,
+    #[serde(default)]
+    new_value: Option<Value>,
+}
+
+impl RawAuditLogChange {
+    fn values<T: DeserializeOwned>(&self) -> Result<AuditLogValueChange<T>, serde_json::Error> {
+        let value = |value: &Option<Value>| value.clone().map(serde_json::from_value).transpose();
+        Ok(AuditLogValueChange {
+            old_value: value(&self.old_value)?,
+            new_value: value(&self.new_value)?,
+        })
+    }
+}
+
+fn serialize_change<S: Serializer, T: Serialize>(
+    serializer: S,
+    key: &str,
+    old_value: &Option<T>,
+    new_value: &Option<T>,
+) -> Result<S::Ok, S::Error> {
+    let mut change = serializer.serialize_struct("AuditLogChange", 3)?;
+    change.serialize_field("key", key)?;
+    match old_value {
+        Some(old_value) => change.serialize_field("old_value", old_value)?,
+        None => change.skip_field("old_value")?,
+    }
+    match new_value {
+        Some(new_value) => change.serialize_field("new_value", new_value)?,
+        None => change.skip_field("new_value")?,
+    }
+    change.end()
+}
+
+macro_rules! audit_log_changes {
+    ($($(#[$meta:meta])* $variant:ident($value:ty) = $key:literal,)*) => {
+        #[derive(Debug, Clone, PartialEq)]
+        /// A changed property of the entity an [`AuditLogEntry`] is about, with its old and new
+        /// value typed according to its key.
+        ///
+        /// Changes of unknown keys, and changes whose values do not have the type the key
+        /// usually has, are kept as [`AuditLogChange::Other`].
+        ///
+        /// # Reference
+        /// See <https://discord.com/developers/docs/resources/audit-log#audit-log-change-object>
+        pub enum AuditLogChange {
+            $(
+                $(#[$meta])*
+                #[doc = concat!("`", $key, "`")]
+                $variant(AuditLogValueChange<$value>),
+            )*
+            Other {
+                key: String,
+                old_value: Option<Value>,
+                new_value: Option<Value>,
+            },
+        }
+
+        impl AuditLogChange {
+            /// The key of the changed property, as sent by the instance.
+            pub fn key(&self) -> &str {
+                match self {
+                    $(Self::$variant(_) => $key,)*
+                    Self::Other { key, .. } => key,
+                }
+            }
+
+            fn from_raw(raw: RawAuditLogChange) -> Self {
+                let typed = match raw.key.as_str() {
+                    $($key => raw.values().map(Self::$variant),)*
+                    _ => return Self::other(raw),
+                };
+                typed.unwrap_or_else(|e| {
+                    log::debug!("Unexpected value for audit log change {}: {}", raw.key, e);
+                    Self::other(raw)
+                })
+            }
+        }
+
+        impl Serialize for AuditLogChange {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $(
+                        Self::$variant(change) => {
+                            serialize_change(serializer, $key, &change.old_value, &change.new_value)
+                        }
+                    )*
+                    Self::Other {
+                        key,
+                        old_value,
+                        new_value,
+                    } => serialize_change(serializer, key, old_value, new_value),
+                }
+            }
+        }
+    };
+}
+
+audit_log_changes! {
+    Name(String) = "name",
+    Description(String) = "description",
+    IconHash(String) = "icon_hash",
+    SplashHash(String) = "splash_hash",
+    DiscoverySplashHash(String) = "discovery_splash_hash",
+    BannerHash(String) = "banner_hash",
+    OwnerId(Snowflake) = "owner_id",
+    Region(String) = "region",
+    PreferredLocale(String) = "preferred_locale",
+    AfkChannelId(Snowflake) = "afk_channel_id",
+    AfkTimeout(u32) = "afk_timeout",
+    RulesChannelId(Snowflake) = "rules_channel_id",
+    PublicUpdatesChannelId(Snowflake) = "public_updates_channel_id",
+    MfaLevel(MFALevel) = "mfa_level",
+    VerificationLevel(VerificationLevel) = "verification_level",
+    ExplicitContentFilter(ExplicitContentFilterLevel) = "explicit_content_filter",
+    DefaultMessageNotifications(MessageNotificationLevel) = "default_message_notifications",
+    NsfwLevel(NSFWLevel) = "nsfw_level",
+    VanityUrlCode(String) = "vanity_url_code",
+    /// Roles added to a member
+    AddRoles(Vec<AuditLogRole>) = "$add",
+    /// Roles removed from a member
+    RemoveRoles(Vec<AuditLogRole>) = "$remove",
+    PruneDeleteDays(u32) = "prune_delete_days",
+    WidgetEnabled(bool) = "widget_enabled",
+    WidgetChannelId(Snowflake) = "widget_channel_id",
+    SystemChannelId(Snowflake) = "system_channel_id",
+    Position(i32) = "position",
+    Topic(String) = "topic",
+    Bitrate(u32) = "bitrate",
+    UserLimit(u32) = "user_limit",
+    RtcRegion(String) = "rtc_region",
+    PermissionOverwrites(Vec<PermissionOverwrite>) = "permission_overwrites",
+    Nsfw(bool) = "nsfw",
+    ApplicationId(Snowflake) = "application_id",
+    RateLimitPerUser(u32) = "rate_limit_per_user",
+    DefaultAutoArchiveDuration(u32) = "default_auto_archive_duration",
+    AutoArchiveDuration(u32) = "auto_archive_duration",
+    Archived(bool) = "archived",
+    Locked(bool) = "locked",
+    Invitable(bool) = "invitable",
+    Permissions(String) = "permissions",
+    Color(u32) = "color",
+    Hoist(bool) = "hoist",
+    Mentionable(bool) = "mentionable",
+    Allow(String) = "allow",
+    Deny(String) = "deny",
+    Code(String) = "code",
+    ChannelId(Snowflake) = "channel_id",
+    InviterId(Snowflake) = "inviter_id",
+    MaxUses(u32) = "max_uses",
+    Uses(u32) = "uses",
+    MaxAge(u32) = "max_age",
+    Temporary(bool) = "temporary",
+    Deaf(bool) = "deaf",
+    Mute(bool) = "mute",
+    Nick(String) = "nick",
+    AvatarHash(String) = "avatar_hash",
+    CommunicationDisabledUntil(DateTime<Utc>) = "communication_disabled_until",
+    Id(Snowflake) = "id",
+    GuildId(Snowflake) = "guild_id",
+    Tags(String) = "tags",
+    Available(bool) = "available",
+    EnableEmoticons(bool) = "enable_emoticons",
+    ExpireBehavior(u8) = "expire_behavior",
+    ExpireGracePeriod(u32) = "expire_grace_period",
+}
+
+impl AuditLogChange {
+    fn other(raw: RawAuditLogChange) -> Self {
+        Self::Other {
+            key: raw.key,
+            old_value: raw.old_value,
+            new_value: raw.new_value,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AuditLogChange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawAuditLogChange::deserialize(deserializer).map(Self::from_raw)
+    }
 }
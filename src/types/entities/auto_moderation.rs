@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::types::utils::Snowflake;
+use crate::types::IntoShared;
 
 #[cfg_attr(feature = "client", derive(Updateable))]
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -31,16 +32,18 @@ pub struct AutoModerationRule {
     pub exempt_channels: Vec<Snowflake>,
 }
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Default)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u8)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// See <https://discord.com/developers/docs/resources/auto-moderation#auto-moderation-rule-object-event-types>
 pub enum AutoModerationRuleEventType {
     #[default]
     MessageSend = 1,
+    /// When a member edits their profile
+    MemberUpdate = 2,
 }
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Default)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u8)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// See <https://discord.com/developers/docs/resources/auto-moderation#auto-moderation-rule-object-trigger-types>
@@ -50,6 +53,8 @@ pub enum AutoModerationRuleTriggerType {
     Spam = 3,
     KeywordPreset = 4,
     MentionSpam = 5,
+    /// Checks the profiles of members against keywords
+    MemberProfile = 6,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -86,7 +91,7 @@ pub struct AutoModerationRuleTriggerMetadataForMentionSpam {
     pub mention_raid_protection_enabled: bool,
 }
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Default)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u8)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// See <https://discord.com/developers/docs/resources/auto-moderation#auto-moderation-rule-object-keyword-preset-types>
@@ -105,7 +110,48 @@ pub struct AutoModerationAction {
     pub metadata: Option<Shared<AutoModerationActionMetadata>>,
 }
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Default)]
+impl AutoModerationAction {
+    /// Blocks the message, optionally showing the author a custom explanation.
+    pub fn block_message(custom_message: Option<String>) -> Self {
+        Self {
+            action_type: AutoModerationActionType::BlockMessage,
+            metadata: Some(
+                AutoModerationActionMetadata::ForBlockMessage(
+                    AutoModerationActionMetadataForBlockMessage { custom_message },
+                )
+                .into_shared(),
+            ),
+        }
+    }
+
+    /// Sends an alert about the message to a channel.
+    pub fn send_alert_message(channel_id: Snowflake) -> Self {
+        Self {
+            action_type: AutoModerationActionType::SendAlertMessage,
+            metadata: Some(
+                AutoModerationActionMetadata::ForSendAlertMessage(
+                    AutoModerationActionMetadataForSendAlertMessage { channel_id },
+                )
+                .into_shared(),
+            ),
+        }
+    }
+
+    /// Times the author out for the given amount of seconds, at most 2419200 (four weeks).
+    pub fn timeout(duration_seconds: u32) -> Self {
+        Self {
+            action_type: AutoModerationActionType::Timeout,
+            metadata: Some(
+                AutoModerationActionMetadata::ForTimeout(AutoModerationActionMetadataForTimeout {
+                    duration_seconds,
+                })
+                .into_shared(),
+            ),
+        }
+    }
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u8)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 /// See <https://discord.com/developers/docs/resources/auto-moderation#auto-moderation-action-object-action-types>
@@ -114,15 +160,18 @@ pub enum AutoModerationActionType {
     BlockMessage = 1,
     SendAlertMessage = 2,
     Timeout = 3,
+    /// Prevents a member from interacting in the guild, for [`MemberProfile`](AutoModerationRuleTriggerType::MemberProfile) rules
+    BlockMemberInteraction = 4,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(untagged)]
 /// See <https://discord.com/developers/docs/resources/auto-moderation#auto-moderation-action-object-action-metadata>
 pub enum AutoModerationActionMetadata {
-    ForBlockMessage(AutoModerationActionMetadataForBlockMessage),
+    // The metadata of blocked messages has no required fields, so it has to be tried last
     ForSendAlertMessage(AutoModerationActionMetadataForSendAlertMessage),
     ForTimeout(AutoModerationActionMetadataForTimeout),
+    ForBlockMessage(AutoModerationActionMetadataForBlockMessage),
     #[default]
     None,
 }
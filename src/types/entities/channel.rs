@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use bitflags::bitflags;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_aux::prelude::deserialize_string_from_number;
@@ -90,6 +91,25 @@ pub struct Channel {
     pub video_quality_mode: Option<i32>,
 }
 
+impl Channel {
+    /// Returns copies of the channel's permission overwrites.
+    pub fn overwrites(&self) -> Vec<PermissionOverwrite> {
+        #[cfg(feature = "sqlx")]
+        let overwrites = self
+            .permission_overwrites
+            .as_ref()
+            .map(|overwrites| overwrites.0.clone());
+        #[cfg(not(feature = "sqlx"))]
+        let overwrites = self.permission_overwrites.as_ref().map(|overwrites| {
+            overwrites
+                .iter()
+                .map(|overwrite| overwrite.read().unwrap().clone())
+                .collect()
+        });
+        overwrites.unwrap_or_default()
+    }
+}
+
 impl PartialEq for Channel {
     fn eq(&self, other: &Self) -> bool {
         self.application_id == other.application_id
@@ -124,7 +144,7 @@ impl PartialEq for Channel {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd)]
 /// A tag that can be applied to a thread in a [ChannelType::GuildForum] or [ChannelType::GuildMedia] channel.
 ///
 /// # Reference
@@ -177,7 +197,7 @@ pub struct ThreadMember {
     pub member: Option<Shared<GuildMember>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd)]
 /// Specifies the emoji to use as the default way to react to a [ChannelType::GuildForum] or [ChannelType::GuildMedia] channel post.
 ///
 /// # Reference
@@ -256,3 +276,91 @@ pub enum ChannelType {
     // TODO: Couldn't find reference
     Unhandled = 255,
 }
+
+bitflags! {
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/channel#channel-flags>
+    pub struct ChannelFlags: u64 {
+        /// This channel has been removed from the guild's home feed
+        const GUILD_FEED_REMOVED = 1 << 0;
+        /// This thread is pinned to the top of its parent [ChannelType::GuildForum] or [ChannelType::GuildMedia] channel
+        const PINNED = 1 << 1;
+        /// This channel has been removed from the guild's active channels
+        const ACTIVE_CHANNELS_REMOVED = 1 << 2;
+        /// A tag is required to be specified when creating a thread in this [ChannelType::GuildForum] or [ChannelType::GuildMedia] channel
+        const REQUIRE_TAG = 1 << 4;
+        /// This channel is marked as spam
+        const IS_SPAM = 1 << 5;
+        /// This channel is a guild resource channel
+        const IS_GUILD_RESOURCE_CHANNEL = 1 << 7;
+        /// This channel was created by Clyde AI
+        const CLYDE_AI = 1 << 8;
+        /// This channel is scheduled for deletion
+        const IS_SCHEDULED_FOR_DELETION = 1 << 9;
+        /// Summaries are disabled for this channel
+        const SUMMARIES_DISABLED = 1 << 11;
+        /// This channel is a role subscription template preview channel
+        const IS_ROLE_SUBSCRIPTION_TEMPLATE_PREVIEW_CHANNEL = 1 << 13;
+        /// This channel is currently broadcasting
+        const IS_BROADCASTING = 1 << 14;
+        /// The download options of media in this [ChannelType::GuildMedia] channel are hidden
+        const HIDE_MEDIA_DOWNLOAD_OPTIONS = 1 << 15;
+        /// This channel is used to interview applicants for guild membership
+        const IS_JOIN_REQUEST_INTERVIEW_CHANNEL = 1 << 16;
+    }
+}
+
+#[derive(
+    Default,
+    Clone,
+    Copy,
+    Debug,
+    Serialize_repr,
+    Deserialize_repr,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[repr(u8)]
+/// The default sort order of posts in a [ChannelType::GuildForum] or [ChannelType::GuildMedia] channel.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#sort-order-type>
+pub enum SortOrderType {
+    #[default]
+    /// Sort forum posts by activity
+    LatestActivity = 0,
+    /// Sort forum posts by creation time (from most recent to oldest)
+    CreationDate = 1,
+}
+
+#[derive(
+    Default,
+    Clone,
+    Copy,
+    Debug,
+    Serialize_repr,
+    Deserialize_repr,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[repr(u8)]
+/// The default layout used to display posts in a [ChannelType::GuildForum] channel.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#forum-layout-type>
+pub enum ForumLayoutType {
+    #[default]
+    /// No default has been set for the forum channel
+    NotSet = 0,
+    /// Display posts as a list
+    ListView = 1,
+    /// Display posts as a collection of tiles
+    GalleryView = 2,
+}
@@ -7,8 +7,8 @@ use std::fmt::Debug;
 use serde::{Deserialize, Serialize};
 
 use crate::gateway::Shared;
-use crate::types::entities::User;
-use crate::types::Snowflake;
+use crate::types::entities::{Guild, GuildMember, User};
+use crate::types::{is_valid_image_size, ImageFormat, Snowflake};
 
 #[cfg(feature = "client")]
 use crate::gateway::GatewayHandle;
@@ -30,6 +30,9 @@ use chorus_macros::{Composite, Updateable};
 pub struct Emoji {
     pub id: Snowflake,
     pub name: Option<String>,
+    /// The id of the guild this emoji belongs to. Only sent by Spacebar servers.
+    #[serde(default)]
+    pub guild_id: Option<Snowflake>,
     #[cfg(feature = "sqlx")]
     pub roles: Option<sqlx::types::Json<Vec<Snowflake>>>,
     #[cfg(not(feature = "sqlx"))]
@@ -46,7 +49,7 @@ impl std::hash::Hash for Emoji {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.id.hash(state);
         self.name.hash(state);
-        self.roles.hash(state);
+        self.guild_id.hash(state);
         self.roles.hash(state);
         self.require_colons.hash(state);
         self.managed.hash(state);
@@ -59,6 +62,7 @@ impl PartialEq for Emoji {
     fn eq(&self, other: &Self) -> bool {
         !(self.id != other.id
             || self.name != other.name
+            || self.guild_id != other.guild_id
             || self.roles != other.roles
             || self.require_colons != other.require_colons
             || self.managed != other.managed
@@ -66,3 +70,48 @@ impl PartialEq for Emoji {
             || self.available != other.available)
     }
 }
+
+impl Emoji {
+    /// Returns the CDN url of this emoji.
+    ///
+    /// `size` must be a power of 2 between 16 and 4096; other values are not added to the url.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/reference#cdn-formatting>
+    pub fn url(&self, cdn_url: &str, format: ImageFormat, size: Option<u16>) -> String {
+        let mut url = format!("{}/emojis/{}.{}", cdn_url, self.id, format);
+        if let Some(size) = size.filter(|size| is_valid_image_size(*size)) {
+            url.push_str(&format!("?size={}", size));
+        }
+        url
+    }
+
+    /// Returns whether `member` can use this emoji in `guild`.
+    ///
+    /// This checks that the emoji is available, that the member has one of the roles the emoji is
+    /// restricted to, and that the member has Nitro if the emoji is animated or is used outside of
+    /// the guild it belongs to. Role restricted emojis can only be used in their own guild.
+    pub fn is_usable_by(&self, member: &GuildMember, guild: &Guild) -> bool {
+        if self.available == Some(false) {
+            return false;
+        }
+
+        let is_local = self.guild_id == Some(guild.id)
+            || guild
+                .emojis
+                .iter()
+                .any(|emoji| emoji.read().unwrap().id == self.id);
+
+        if let Some(roles) = self.roles.as_ref().filter(|roles| !roles.is_empty()) {
+            if !is_local || !roles.iter().any(|role| member.roles.contains(role)) {
+                return false;
+            }
+        }
+
+        let has_premium = member.user.as_ref().map_or(false, |user| {
+            user.read().unwrap().premium_type.unwrap_or(0) > 0
+        });
+
+        has_premium || (is_local && self.animated != Some(true))
+    }
+}
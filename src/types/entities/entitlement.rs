@@ -0,0 +1,48 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::types::Snowflake;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A user's or guild's access to a premium offering of an application.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/entitlement#entitlement-object>
+pub struct Entitlement {
+    pub id: Snowflake,
+    pub sku_id: Snowflake,
+    pub application_id: Snowflake,
+    pub user_id: Option<Snowflake>,
+    pub guild_id: Option<Snowflake>,
+    #[serde(rename = "type")]
+    pub entitlement_type: EntitlementType,
+    #[serde(default)]
+    pub deleted: bool,
+    /// Start date at which the entitlement is valid. Not present for test entitlements.
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Date at which the entitlement is no longer valid. Not present for test entitlements.
+    pub ends_at: Option<DateTime<Utc>>,
+    /// For consumable items, whether or not the entitlement has been consumed
+    pub consumed: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/entitlement#entitlement-object-entitlement-types>
+pub enum EntitlementType {
+    #[default]
+    Purchase = 1,
+    PremiumSubscription = 2,
+    DeveloperGift = 3,
+    TestModePurchase = 4,
+    FreePurchase = 5,
+    UserGift = 6,
+    PremiumPurchase = 7,
+    ApplicationSubscription = 8,
+}
@@ -11,10 +11,15 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::gateway::Shared;
 use crate::types::types::guild_configuration::GuildFeaturesList;
+use crate::types::types::subconfigs::limits::message::MessageLimits;
 use crate::types::{
-    entities::{Channel, Emoji, RoleObject, Sticker, User, VoiceState, Webhook},
+    entities::{
+        Channel, Emoji, PermissionFlags, PermissionOverwrite, RoleObject, Sticker, User,
+        VoiceState, Webhook,
+    },
     interfaces::WelcomeScreenObject,
-    utils::Snowflake,
+    utils::{Locale, Snowflake},
+    GuildScheduledEventUserAdd, GuildScheduledEventUserRemove,
 };
 
 use super::PublicUser;
@@ -65,6 +70,8 @@ pub struct Guild {
     pub icon_hash: Option<String>,
     pub id: Snowflake,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub incidents_data: Option<GuildIncidentsData>,
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub invites: Option<Vec<GuildInvite>>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub joined_at: Option<String>,
@@ -96,6 +103,7 @@ pub struct Guild {
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub rules_channel: Option<String>,
     pub rules_channel_id: Option<Snowflake>,
+    pub safety_alerts_channel_id: Option<Snowflake>,
     pub splash: Option<String>,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub stickers: Option<Vec<Sticker>>,
@@ -118,6 +126,92 @@ pub struct Guild {
     pub widget_enabled: Option<bool>,
 }
 
+impl Guild {
+    /// Returns the [`Locale`] of the guild's [`preferred_locale`](Guild::preferred_locale) field.
+    pub fn preferred_locale(&self) -> Option<Locale> {
+        self.preferred_locale.as_deref().map(Locale::from_code)
+    }
+
+    /// Returns the maximum size of a single file upload to this guild in bytes.
+    ///
+    /// This is the limit of the guild's [`PremiumTier`], capped by the instance's
+    /// `max_attachment_size`, if the instance's [`MessageLimits`] are known.
+    pub fn upload_limit(&self, instance_limits: Option<&MessageLimits>) -> u64 {
+        let premium_limit = self.premium_tier.unwrap_or_default().upload_limit();
+        match instance_limits {
+            Some(limits) => premium_limit.min(limits.max_attachment_size),
+            None => premium_limit,
+        }
+    }
+
+    /// Computes the permissions of a member of this guild, given the ids of the member's roles and
+    /// the permission overwrites of a channel. Pass no overwrites to get the member's guild-wide
+    /// permissions.
+    ///
+    /// The guild's [`roles`](Guild::roles) and [`owner_id`](Guild::owner_id) have to be known.
+    /// Roles missing from the guild object are ignored.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/topics/permissions#permission-hierarchy>
+    pub fn member_permissions(
+        &self,
+        user_id: Snowflake,
+        member_roles: &[Snowflake],
+        overwrites: &[PermissionOverwrite],
+    ) -> PermissionFlags {
+        if self.owner_id == Some(user_id) {
+            return PermissionFlags::all();
+        }
+
+        let mut permissions = PermissionFlags::empty();
+        for role in self.roles.iter().flatten() {
+            let role = role.read().unwrap();
+            // The @everyone role shares its id with the guild
+            if role.id == self.id || member_roles.contains(&role.id) {
+                permissions |= parse_permissions(&role.permissions);
+            }
+        }
+        if permissions.contains(PermissionFlags::ADMINISTRATOR) {
+            return PermissionFlags::all();
+        }
+        if overwrites.is_empty() {
+            return permissions;
+        }
+
+        if let Some(everyone) = overwrites.iter().find(|overwrite| overwrite.id == self.id) {
+            permissions &= !parse_permissions(&everyone.deny);
+            permissions |= parse_permissions(&everyone.allow);
+        }
+        let mut role_allow = PermissionFlags::empty();
+        let mut role_deny = PermissionFlags::empty();
+        for overwrite in overwrites.iter().filter(|overwrite| {
+            overwrite.overwrite_type != "1" && member_roles.contains(&overwrite.id)
+        }) {
+            role_allow |= parse_permissions(&overwrite.allow);
+            role_deny |= parse_permissions(&overwrite.deny);
+        }
+        permissions &= !role_deny;
+        permissions |= role_allow;
+        if let Some(member) = overwrites
+            .iter()
+            .find(|overwrite| overwrite.overwrite_type == "1" && overwrite.id == user_id)
+        {
+            permissions &= !parse_permissions(&member.deny);
+            permissions |= parse_permissions(&member.allow);
+        }
+
+        // Without access to the channel, no other permission in it applies
+        if !permissions.contains(PermissionFlags::VIEW_CHANNEL) {
+            return PermissionFlags::empty();
+        }
+        permissions
+    }
+}
+
+fn parse_permissions(permissions: &str) -> PermissionFlags {
+    PermissionFlags::from_bits_truncate(permissions.parse().unwrap_or_default())
+}
+
 impl std::hash::Hash for Guild {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.afk_channel_id.hash(state);
@@ -135,6 +229,7 @@ impl std::hash::Hash for Guild {
         self.icon.hash(state);
         self.icon_hash.hash(state);
         self.id.hash(state);
+        self.incidents_data.hash(state);
         self.invites.hash(state);
         self.joined_at.hash(state);
         self.large.hash(state);
@@ -157,6 +252,7 @@ impl std::hash::Hash for Guild {
         self.region.hash(state);
         self.rules_channel.hash(state);
         self.rules_channel_id.hash(state);
+        self.safety_alerts_channel_id.hash(state);
         self.splash.hash(state);
         self.stickers.hash(state);
         self.system_channel_flags.hash(state);
@@ -187,6 +283,7 @@ impl std::cmp::PartialEq for Guild {
             && self.icon == other.icon
             && self.icon_hash == other.icon_hash
             && self.id == other.id
+            && self.incidents_data == other.incidents_data
             && self.joined_at == other.joined_at
             && self.large == other.large
             && self.max_members == other.max_members
@@ -208,6 +305,7 @@ impl std::cmp::PartialEq for Guild {
             && self.region == other.region
             && self.rules_channel == other.rules_channel
             && self.rules_channel_id == other.rules_channel_id
+            && self.safety_alerts_channel_id == other.safety_alerts_channel_id
             && self.splash == other.splash
             && self.stickers == other.stickers
             && self.system_channel_flags == other.system_channel_flags
@@ -221,6 +319,88 @@ impl std::cmp::PartialEq for Guild {
     }
 }
 
+/// The state of a guild's security actions and the incidents which were detected in it.
+///
+/// Sent as part of the guild, for example in [`GuildUpdate`](crate::types::GuildUpdate) events
+/// when a raid is detected or security actions are toggled.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#incidents-data-structure>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct GuildIncidentsData {
+    /// When invites will be enabled again
+    pub invites_disabled_until: Option<DateTime<Utc>>,
+    /// When direct messages between guild members will be enabled again
+    pub dms_disabled_until: Option<DateTime<Utc>>,
+    /// When DM spam was last detected in the guild
+    pub dm_spam_detected_at: Option<DateTime<Utc>>,
+    /// When a raid was last detected in the guild
+    pub raid_detected_at: Option<DateTime<Utc>>,
+}
+
+impl GuildIncidentsData {
+    /// Returns true if joining the guild through invites is currently disabled.
+    pub fn invites_disabled(&self) -> bool {
+        self.invites_disabled_until
+            .map_or(false, |until| until > Utc::now())
+    }
+
+    /// Returns true if direct messages between guild members are currently disabled.
+    pub fn dms_disabled(&self) -> bool {
+        self.dms_disabled_until
+            .map_or(false, |until| until > Utc::now())
+    }
+
+    /// Returns true if any security action is currently active, such as during a lockdown.
+    pub fn is_alert_mode(&self) -> bool {
+        self.invites_disabled() || self.dms_disabled()
+    }
+
+    /// Returns true if a raid has been detected.
+    pub fn raid_detected(&self) -> bool {
+        self.raid_detected_at.is_some()
+    }
+
+    /// Returns true if DM spam has been detected.
+    pub fn dm_spam_detected(&self) -> bool {
+        self.dm_spam_detected_at.is_some()
+    }
+}
+
+/// The style of a guild's widget image.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#widget-style-options>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum WidgetStyle {
+    /// A small shield with the guild's online member count
+    #[default]
+    Shield,
+    /// A large image with the guild's icon, name and online member count, with a "Powered by"
+    /// footer
+    Banner1,
+    /// A smaller banner with the guild's icon, name and online member count
+    Banner2,
+    /// A large image with the guild's icon, name and online member count, with a "Chat Now" footer
+    Banner3,
+    /// A large image with a "Join my server" header, along with the guild's icon, name and online
+    /// member count
+    Banner4,
+}
+
+impl std::fmt::Display for WidgetStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WidgetStyle::Shield => "shield",
+            WidgetStyle::Banner1 => "banner1",
+            WidgetStyle::Banner2 => "banner2",
+            WidgetStyle::Banner3 => "banner3",
+            WidgetStyle::Banner4 => "banner4",
+        })
+    }
+}
+
 /// See <https://docs.spacebar.chat/routes/#get-/guilds/-guild_id-/bans/-user->
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
@@ -282,6 +462,38 @@ pub struct GuildCreateResponse {
     pub id: Snowflake,
 }
 
+/// A guild the current user is a member of, as listed by `ChorusUser::get_guilds`.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#get-user-guilds>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct UserGuild {
+    pub id: Snowflake,
+    pub name: String,
+    pub icon: Option<String>,
+    pub banner: Option<String>,
+    /// Whether the current user owns the guild
+    #[serde(default)]
+    pub owner: bool,
+    /// The current user's permissions in the guild, see [`UserGuild::permission_flags`]
+    pub permissions: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Only sent when requested with
+    /// [`GetUserGuildSchema::with_counts`](crate::types::GetUserGuildSchema::with_counts)
+    pub approximate_member_count: Option<i32>,
+    /// Only sent when requested with
+    /// [`GetUserGuildSchema::with_counts`](crate::types::GetUserGuildSchema::with_counts)
+    pub approximate_presence_count: Option<i32>,
+}
+
+impl UserGuild {
+    /// Parses [`UserGuild::permissions`], ignoring unknown permissions.
+    pub fn permission_flags(&self) -> PermissionFlags {
+        PermissionFlags::from_bits_truncate(self.permissions.parse().unwrap_or_default())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 /// See <https://discord.com/developers/docs/resources/guild-scheduled-event#guild-scheduled-event-object>
 pub struct GuildScheduledEvent {
@@ -303,6 +515,32 @@ pub struct GuildScheduledEvent {
     pub image: Option<String>,
 }
 
+impl GuildScheduledEvent {
+    /// Increments the [`user_count`](Self::user_count) of this event, if a
+    /// [`GuildScheduledEventUserAdd`] event refers to it.
+    ///
+    /// Returns true if the count was updated.
+    pub fn apply_user_add(&mut self, event: &GuildScheduledEventUserAdd) -> bool {
+        if event.guild_scheduled_event_id != self.id {
+            return false;
+        }
+        self.user_count = Some(self.user_count.unwrap_or_default() + 1);
+        true
+    }
+
+    /// Decrements the [`user_count`](Self::user_count) of this event, if a
+    /// [`GuildScheduledEventUserRemove`] event refers to it.
+    ///
+    /// Returns true if the count was updated.
+    pub fn apply_user_remove(&mut self, event: &GuildScheduledEventUserRemove) -> bool {
+        if event.guild_scheduled_event_id != self.id {
+            return false;
+        }
+        self.user_count = Some(self.user_count.unwrap_or_default().saturating_sub(1));
+        true
+    }
+}
+
 #[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone)]
 #[repr(u8)]
 /// See <https://discord.com/developers/docs/resources/guild-scheduled-event#guild-scheduled-event-object-guild-scheduled-event-privacy-level>
@@ -339,12 +577,68 @@ pub struct GuildScheduledEventEntityMetadata {
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, Hash)]
+/// A region voice connections can be hosted in.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/voice#voice-region-object>
 pub struct VoiceRegion {
-    id: String,
-    name: String,
-    optimal: bool,
-    deprecated: bool,
-    custom: bool,
+    pub id: String,
+    pub name: String,
+    /// Whether this is the region closest to the user
+    pub optimal: bool,
+    /// Whether this region should no longer be used
+    pub deprecated: bool,
+    /// Whether this is a custom region, such as one used for events
+    pub custom: bool,
+}
+
+/// The region a voice channel's calls are hosted in.
+///
+/// Serialized as the region's id, or as `null` for [`RtcRegion::Automatic`].
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#channel-object>
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RtcRegion {
+    /// The region is chosen automatically for each call.
+    #[default]
+    Automatic,
+    /// The id of a [`VoiceRegion`].
+    Region(String),
+}
+
+impl From<&VoiceRegion> for RtcRegion {
+    fn from(region: &VoiceRegion) -> Self {
+        RtcRegion::Region(region.id.clone())
+    }
+}
+
+impl From<String> for RtcRegion {
+    fn from(id: String) -> Self {
+        RtcRegion::Region(id)
+    }
+}
+
+impl From<&str> for RtcRegion {
+    fn from(id: &str) -> Self {
+        RtcRegion::Region(id.to_string())
+    }
+}
+
+impl Serialize for RtcRegion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            RtcRegion::Automatic => serializer.serialize_none(),
+            RtcRegion::Region(id) => serializer.serialize_str(id),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RtcRegion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Option::<String>::deserialize(deserializer)?
+            .map_or(RtcRegion::Automatic, RtcRegion::Region))
+    }
 }
 
 #[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Eq, PartialEq, Hash, Copy)]
@@ -421,6 +715,21 @@ pub enum PremiumTier {
     Tier3 = 3,
 }
 
+impl PremiumTier {
+    /// Returns the maximum size of a single file upload in bytes, which this premium tier allows
+    /// for members of the guild.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/guild#premium-tier>
+    pub fn upload_limit(&self) -> u64 {
+        match self {
+            PremiumTier::None | PremiumTier::Tier1 => 25 * 1024 * 1024,
+            PremiumTier::Tier2 => 50 * 1024 * 1024,
+            PremiumTier::Tier3 => 100 * 1024 * 1024,
+        }
+    }
+}
+
 bitflags! {
     #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
     /// # Reference
@@ -0,0 +1,65 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{PublicUser, Snowflake};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// A user's request to join a guild with member verification (membership screening) enabled.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#guild-join-request-object>
+pub struct GuildJoinRequest {
+    pub id: Snowflake,
+    /// The id of the join request, identical to `id`.
+    pub join_request_id: Option<Snowflake>,
+    pub created_at: DateTime<Utc>,
+    pub application_status: GuildJoinRequestStatus,
+    pub guild_id: Snowflake,
+    pub user_id: Snowflake,
+    pub user: Option<PublicUser>,
+    /// The user's answers to the guild's verification form.
+    #[serde(default)]
+    pub form_responses: Vec<Value>,
+    /// The reason given by the moderator who rejected the request.
+    pub rejection_reason: Option<String>,
+    /// A snowflake of when the request was approved or rejected.
+    pub actioned_at: Option<Snowflake>,
+    /// The moderator who approved or rejected the request.
+    pub actioned_by_user: Option<PublicUser>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl GuildJoinRequest {
+    /// Whether the request is waiting for a moderator to approve or reject it.
+    pub fn is_pending(&self) -> bool {
+        self.application_status == GuildJoinRequestStatus::Submitted
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#guild-join-request-application-status>
+pub enum GuildJoinRequestStatus {
+    /// The user has started, but not yet submitted, the verification form.
+    #[default]
+    Started,
+    /// The request is waiting for review.
+    Submitted,
+    Rejected,
+    Approved,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// A page of a guild's join requests.
+pub struct GuildJoinRequests {
+    pub guild_join_requests: Vec<GuildJoinRequest>,
+    /// The total amount of join requests matching the query.
+    pub total: u32,
+    pub limit: u32,
+}
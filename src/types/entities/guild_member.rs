@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::gateway::Shared;
-use crate::types::{entities::PublicUser, Snowflake};
+use crate::types::{entities::PublicUser, GuildMemberFlags, Snowflake};
 
 #[derive(Debug, Deserialize, Default, Serialize, Clone)]
 /// Represents a participating user in a guild.
@@ -19,10 +19,22 @@ pub struct GuildMember {
     pub roles: Vec<Snowflake>,
     pub joined_at: String,
     pub premium_since: Option<String>,
+    /// Not included in the members of [interaction resolved data](crate::types::InteractionResolvedData)
+    #[serde(default)]
     pub deaf: bool,
+    /// Not included in the members of [interaction resolved data](crate::types::InteractionResolvedData)
+    #[serde(default)]
     pub mute: bool,
-    pub flags: Option<i32>,
+    /// See [`GuildMember::member_flags`]
+    pub flags: Option<u64>,
     pub pending: Option<bool>,
     pub permissions: Option<String>,
     pub communication_disabled_until: Option<String>,
 }
+
+impl GuildMember {
+    /// Returns the member's flags, ignoring unknown bits.
+    pub fn member_flags(&self) -> GuildMemberFlags {
+        GuildMemberFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+}
@@ -2,7 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use bitflags::bitflags;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::gateway::Shared;
 use crate::types::{
@@ -25,9 +28,11 @@ pub struct Message {
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub author: Option<PublicUser>,
     pub content: Option<String>,
+    #[serde(default)]
     pub timestamp: String,
     pub edited_timestamp: Option<String>,
     pub tts: Option<bool>,
+    #[serde(default)]
     pub mention_everyone: bool,
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub mentions: Option<Vec<User>>,
@@ -46,10 +51,12 @@ pub struct Message {
     #[cfg(not(feature = "sqlx"))]
     pub reactions: Option<Vec<Reaction>>,
     pub nonce: Option<serde_json::Value>,
+    #[serde(default)]
     pub pinned: bool,
     pub webhook_id: Option<Snowflake>,
-    #[serde(rename = "type")]
-    pub message_type: i32,
+    #[serde(rename = "type", default)]
+    #[cfg_attr(feature = "sqlx", sqlx(try_from = "i32"))]
+    pub message_type: MessageType,
     #[cfg(feature = "sqlx")]
     pub activity: Option<sqlx::types::Json<MessageActivity>>,
     #[cfg(not(feature = "sqlx"))]
@@ -61,6 +68,7 @@ pub struct Message {
     pub message_reference: Option<sqlx::types::Json<MessageReference>>,
     #[cfg(not(feature = "sqlx"))]
     pub message_reference: Option<MessageReference>,
+    /// See [`MessageFlags`]
     pub flags: Option<u64>,
     pub referenced_message: Option<Box<Message>>,
     pub interaction: Option<MessageInteraction>,
@@ -69,7 +77,359 @@ pub struct Message {
     pub sticker_items: Option<Vec<StickerItem>>,
     pub stickers: Option<Vec<Sticker>>,
     pub position: Option<i32>,
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub role_subscription_data: Option<RoleSubscriptionData>,
+    /// Information about the purchase this message was sent for, if it is a purchase
+    /// notification
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub purchase_notification: Option<MessagePurchaseNotification>,
+}
+
+bitflags! {
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/resources/message#message-flags>
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+    pub struct MessageFlags: u64 {
+        /// The message has been published to following channels
+        const CROSSPOSTED = 1 << 0;
+        /// The message originated from a message in another channel
+        const IS_CROSSPOST = 1 << 1;
+        /// Embeds are not included when serializing the message
+        const SUPPRESS_EMBEDS = 1 << 2;
+        /// The source message of this crosspost has been deleted
+        const SOURCE_MESSAGE_DELETED = 1 << 3;
+        /// The message came from the urgent message system
+        const URGENT = 1 << 4;
+        /// The message has an associated thread
+        const HAS_THREAD = 1 << 5;
+        /// The message is only visible to the user who invoked the interaction
+        const EPHEMERAL = 1 << 6;
+        /// The message is an interaction response and the bot is "thinking"
+        const LOADING = 1 << 7;
+        /// Some roles could not be mentioned and added to the message's thread
+        const FAILED_TO_MENTION_SOME_ROLES_IN_THREAD = 1 << 8;
+        /// The message contains a link which official clients warn about
+        const SHOULD_SHOW_LINK_NOT_DISCORD_WARNING = 1 << 10;
+        /// The message does not trigger push and desktop notifications
+        const SUPPRESS_NOTIFICATIONS = 1 << 12;
+        /// The message is a voice message
+        const IS_VOICE_MESSAGE = 1 << 13;
+    }
+}
+
+impl MessageFlags {
+    /// The flags which can be set when sending a message.
+    pub const SENDABLE: MessageFlags = MessageFlags::SUPPRESS_EMBEDS
+        .union(MessageFlags::SUPPRESS_NOTIFICATIONS)
+        .union(MessageFlags::IS_VOICE_MESSAGE);
+
+    /// The flags which can be set on interaction responses and followup messages.
+    pub const INTERACTION_RESPONSE: MessageFlags = MessageFlags::SUPPRESS_EMBEDS
+        .union(MessageFlags::EPHEMERAL)
+        .union(MessageFlags::SUPPRESS_NOTIFICATIONS);
+}
+
+impl Message {
+    /// Returns the message's flags, ignoring unknown bits.
+    pub fn message_flags(&self) -> MessageFlags {
+        MessageFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+
+    /// Returns true if the message was sent with the given nonce.
+    ///
+    /// Nonces are echoed back the way they were sent, either as a string or as an integer, so
+    /// both representations are compared.
+    pub fn has_nonce(&self, nonce: &str) -> bool {
+        match &self.nonce {
+            Some(serde_json::Value::String(value)) => value == nonce,
+            Some(serde_json::Value::Number(value)) => value.to_string() == nonce,
+            _ => false,
+        }
+    }
+
+    /// For [`MessageType::GuildIncidentAlertModeEnabled`] messages, returns when the security
+    /// actions which were enabled will expire. The expiry is sent as the message content.
+    pub fn incident_alert_mode_until(&self) -> Option<DateTime<Utc>> {
+        if self.message_type != MessageType::GuildIncidentAlertModeEnabled {
+            return None;
+        }
+        self.content
+            .as_ref()
+            .and_then(|content| DateTime::parse_from_rfc3339(content).ok())
+            .map(|until| until.with_timezone(&Utc))
+    }
+
+    /// Renders a system message into the text official clients display for it, such as
+    /// "Alice pinned a message to this channel."
+    ///
+    /// Returns `None` for messages which are displayed with their own content, such as
+    /// [`MessageType::Default`] and [`MessageType::Reply`].
+    pub fn system_content(&self) -> Option<String> {
+        let author = self
+            .author
+            .as_ref()
+            .and_then(|author| author.username.clone())
+            .unwrap_or_else(|| "Someone".to_string());
+        let content = self.content.clone().unwrap_or_default();
+        let mentioned = self
+            .mentions
+            .as_ref()
+            .and_then(|mentions| mentions.first())
+            .map(|user| (user.id, user.username.clone()));
+        let text = match self.message_type {
+            MessageType::RecipientAdd => match mentioned {
+                Some((_, name)) => format!("{} added {} to the group.", author, name),
+                None => format!("{} added someone to the group.", author),
+            },
+            MessageType::RecipientRemove => match mentioned {
+                Some((id, _)) if self.author.as_ref().map(|author| author.id) == Some(id) => {
+                    format!("{} left the group.", author)
+                }
+                Some((_, name)) => format!("{} removed {} from the group.", author, name),
+                None => format!("{} left the group.", author),
+            },
+            MessageType::Call => format!("{} started a call.", author),
+            MessageType::ChannelNameChange => {
+                format!("{} changed the channel name: **{}**", author, content)
+            }
+            MessageType::ChannelIconChange => format!("{} changed the channel icon.", author),
+            MessageType::ChannelPinnedMessage => {
+                format!("{} pinned a message to this channel.", author)
+            }
+            MessageType::UserJoin => format!("{} joined the server.", author),
+            MessageType::GuildBoost => match content.parse::<u32>() {
+                Ok(count) if count > 1 => {
+                    format!("{} just boosted the server **{}** times!", author, count)
+                }
+                _ => format!("{} just boosted the server!", author),
+            },
+            MessageType::GuildBoostTier1 | MessageType::GuildBoostTier2 | MessageType::GuildBoostTier3 => {
+                let level = match self.message_type {
+                    MessageType::GuildBoostTier1 => 1,
+                    MessageType::GuildBoostTier2 => 2,
+                    _ => 3,
+                };
+                format!(
+                    "{} just boosted the server! The server has achieved **Level {}!**",
+                    author, level
+                )
+            }
+            MessageType::ChannelFollowAdd => format!(
+                "{} has added {} to this channel. Its most important updates will show up here.",
+                author, content
+            ),
+            MessageType::GuildDiscoveryDisqualified => "This server has been removed from Server Discovery because it no longer passes all the requirements.".to_string(),
+            MessageType::GuildDiscoveryRequalified => "This server is eligible for Server Discovery again and has been automatically relisted!".to_string(),
+            MessageType::GuildDiscoveryGracePeriodInitialWarning => "This server has failed Discovery activity requirements for 1 week. If this server fails for 4 weeks in a row, it will be automatically removed from Discovery.".to_string(),
+            MessageType::GuildDiscoveryGracePeriodFinalWarning => "This server has failed Discovery activity requirements for 3 weeks in a row. If this server fails for 1 more week, it will be removed from Discovery.".to_string(),
+            MessageType::ThreadCreated => format!("{} started a thread: **{}**", author, content),
+            MessageType::GuildInviteReminder => "Wondering who to invite? Start by inviting anyone who can help you build the server!".to_string(),
+            MessageType::AutoModerationAction => "AutoMod has blocked a message.".to_string(),
+            MessageType::RoleSubscriptionPurchase => match &self.role_subscription_data {
+                Some(data) => format!(
+                    "{} {} {} and has been a subscriber of this server for {} month(s)!",
+                    author,
+                    if data.is_renewal { "renewed" } else { "joined" },
+                    data.tier_name,
+                    data.total_months_subscribed
+                ),
+                None => format!("{} subscribed to this server!", author),
+            },
+            MessageType::StageStart => format!("{} started **{}**", author, content),
+            MessageType::StageEnd => format!("{} ended **{}**", author, content),
+            MessageType::StageSpeaker => format!("{} is now a speaker.", author),
+            MessageType::StageRaiseHand => format!("{} requested to speak.", author),
+            MessageType::StageTopic => {
+                format!("{} changed the Stage topic: **{}**", author, content)
+            }
+            MessageType::GuildIncidentAlertModeEnabled => {
+                format!("{} enabled security actions.", author)
+            }
+            MessageType::GuildIncidentAlertModeDisabled => {
+                format!("{} disabled security actions.", author)
+            }
+            MessageType::GuildIncidentReportRaid => {
+                format!("{} reported a raid in this server.", author)
+            }
+            MessageType::GuildIncidentReportFalseAlarm => {
+                format!("{} reported a false alarm in this server.", author)
+            }
+            MessageType::PurchaseNotification => {
+                let product = self
+                    .purchase_notification
+                    .as_ref()
+                    .and_then(|notification| notification.guild_product_purchase.as_ref())
+                    .map(|purchase| purchase.product_name.clone())
+                    .unwrap_or_else(|| "a product".to_string());
+                format!("{} has purchased {}!", author, product)
+            }
+            _ => return None,
+        };
+        Some(text)
+    }
+}
+
+/// The type of a [`Message`].
+///
+/// Types which are not known to chorus are deserialized as [`MessageType::Unknown`].
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#message-type>
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "i32", into = "i32")]
+#[non_exhaustive]
+pub enum MessageType {
+    #[default]
+    Default,
+    RecipientAdd,
+    RecipientRemove,
+    Call,
+    ChannelNameChange,
+    ChannelIconChange,
+    ChannelPinnedMessage,
+    UserJoin,
+    GuildBoost,
+    GuildBoostTier1,
+    GuildBoostTier2,
+    GuildBoostTier3,
+    ChannelFollowAdd,
+    GuildStream,
+    GuildDiscoveryDisqualified,
+    GuildDiscoveryRequalified,
+    GuildDiscoveryGracePeriodInitialWarning,
+    GuildDiscoveryGracePeriodFinalWarning,
+    ThreadCreated,
+    Reply,
+    ChatInputCommand,
+    ThreadStarterMessage,
+    GuildInviteReminder,
+    ContextMenuCommand,
+    AutoModerationAction,
+    RoleSubscriptionPurchase,
+    InteractionPremiumUpsell,
+    StageStart,
+    StageEnd,
+    StageSpeaker,
+    StageRaiseHand,
+    StageTopic,
+    GuildApplicationPremiumSubscription,
+    PrivateChannelIntegrationAdded,
+    PrivateChannelIntegrationRemoved,
+    PremiumReferral,
+    GuildIncidentAlertModeEnabled,
+    GuildIncidentAlertModeDisabled,
+    GuildIncidentReportRaid,
+    GuildIncidentReportFalseAlarm,
+    GuildDeadchatRevivePrompt,
+    CustomGift,
+    GuildGamingStatsPrompt,
+    PurchaseNotification,
+    PollResult,
+    /// A type which is not known to chorus
+    Unknown(i32),
+}
+
+macro_rules! message_type_values {
+    ($($variant:ident = $value:literal),* $(,)?) => {
+        impl From<i32> for MessageType {
+            fn from(value: i32) -> Self {
+                match value {
+                    $($value => MessageType::$variant,)*
+                    other => MessageType::Unknown(other),
+                }
+            }
+        }
+
+        impl From<MessageType> for i32 {
+            fn from(value: MessageType) -> Self {
+                match value {
+                    $(MessageType::$variant => $value,)*
+                    MessageType::Unknown(other) => other,
+                }
+            }
+        }
+    };
+}
+
+message_type_values! {
+    Default = 0,
+    RecipientAdd = 1,
+    RecipientRemove = 2,
+    Call = 3,
+    ChannelNameChange = 4,
+    ChannelIconChange = 5,
+    ChannelPinnedMessage = 6,
+    UserJoin = 7,
+    GuildBoost = 8,
+    GuildBoostTier1 = 9,
+    GuildBoostTier2 = 10,
+    GuildBoostTier3 = 11,
+    ChannelFollowAdd = 12,
+    GuildStream = 13,
+    GuildDiscoveryDisqualified = 14,
+    GuildDiscoveryRequalified = 15,
+    GuildDiscoveryGracePeriodInitialWarning = 16,
+    GuildDiscoveryGracePeriodFinalWarning = 17,
+    ThreadCreated = 18,
+    Reply = 19,
+    ChatInputCommand = 20,
+    ThreadStarterMessage = 21,
+    GuildInviteReminder = 22,
+    ContextMenuCommand = 23,
+    AutoModerationAction = 24,
+    RoleSubscriptionPurchase = 25,
+    InteractionPremiumUpsell = 26,
+    StageStart = 27,
+    StageEnd = 28,
+    StageSpeaker = 29,
+    StageRaiseHand = 30,
+    StageTopic = 31,
+    GuildApplicationPremiumSubscription = 32,
+    PrivateChannelIntegrationAdded = 33,
+    PrivateChannelIntegrationRemoved = 34,
+    PremiumReferral = 35,
+    GuildIncidentAlertModeEnabled = 36,
+    GuildIncidentAlertModeDisabled = 37,
+    GuildIncidentReportRaid = 38,
+    GuildIncidentReportFalseAlarm = 39,
+    GuildDeadchatRevivePrompt = 40,
+    CustomGift = 41,
+    GuildGamingStatsPrompt = 42,
+    PurchaseNotification = 44,
+    PollResult = 46,
+}
+
+impl MessageType {
+    /// Returns true if messages of this type are sent by the system, rather than written by
+    /// their author.
+    pub fn is_system(&self) -> bool {
+        !matches!(
+            self,
+            MessageType::Default
+                | MessageType::Reply
+                | MessageType::ChatInputCommand
+                | MessageType::ContextMenuCommand
+                | MessageType::ThreadStarterMessage
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#message-purchase-notification-structure>
+pub struct MessagePurchaseNotification {
+    /// 0 for guild product purchases
+    #[serde(rename = "type")]
+    pub notification_type: u8,
+    pub guild_product_purchase: Option<GuildProductPurchase>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#guild-product-purchase-structure>
+pub struct GuildProductPurchase {
+    pub listing_id: Snowflake,
+    pub product_name: String,
 }
 
 impl PartialEq for Message {
@@ -152,86 +512,200 @@ pub struct ChannelMention {
     name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+/// The url scheme which refers to a file uploaded along with a message, like
+/// `attachment://image.png`.
+pub const ATTACHMENT_URL_SCHEME: &str = "attachment://";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 pub struct Embed {
-    title: Option<String>,
+    pub title: Option<String>,
     #[serde(rename = "type")]
-    embed_type: Option<String>,
-    description: Option<String>,
-    url: Option<String>,
-    timestamp: Option<String>,
-    color: Option<i32>,
-    footer: Option<EmbedFooter>,
-    image: Option<EmbedImage>,
-    thumbnail: Option<EmbedThumbnail>,
-    video: Option<EmbedVideo>,
-    provider: Option<EmbedProvider>,
-    author: Option<EmbedAuthor>,
-    fields: Option<Vec<EmbedField>>,
+    pub embed_type: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub timestamp: Option<String>,
+    pub color: Option<i32>,
+    pub footer: Option<EmbedFooter>,
+    pub image: Option<EmbedImage>,
+    pub thumbnail: Option<EmbedThumbnail>,
+    pub video: Option<EmbedVideo>,
+    pub provider: Option<EmbedProvider>,
+    pub author: Option<EmbedAuthor>,
+    pub fields: Option<Vec<EmbedField>>,
+}
+
+impl Embed {
+    /// Returns the filenames of the attachments the image and thumbnail of this embed refer to.
+    pub fn attachment_references(&self) -> Vec<&str> {
+        let image = self
+            .image
+            .as_ref()
+            .and_then(|image| image.attachment_filename());
+        let thumbnail = self
+            .thumbnail
+            .as_ref()
+            .and_then(|thumbnail| thumbnail.attachment_filename());
+        image.into_iter().chain(thumbnail).collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EmbedFooter {
-    text: String,
-    icon_url: Option<String>,
-    proxy_icon_url: Option<String>,
+    pub text: String,
+    pub icon_url: Option<String>,
+    pub proxy_icon_url: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct EmbedImage {
-    url: String,
-    proxy_url: String,
-    height: Option<i32>,
-    width: Option<i32>,
+    /// The url of the image. Can refer to an attachment of the same message, see
+    /// [`EmbedImage::attachment`].
+    pub url: String,
+    pub proxy_url: Option<String>,
+    pub height: Option<i32>,
+    pub width: Option<i32>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
+impl EmbedImage {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an image which shows an attachment uploaded along with the message, such as
+    /// `attachment://image.png`.
+    pub fn attachment(filename: &str) -> Self {
+        Self::new(format!("{}{}", ATTACHMENT_URL_SCHEME, filename))
+    }
+
+    /// Returns the filename of the attachment this image refers to, if it refers to one.
+    pub fn attachment_filename(&self) -> Option<&str> {
+        self.url.strip_prefix(ATTACHMENT_URL_SCHEME)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct EmbedThumbnail {
-    url: String,
-    proxy_url: Option<String>,
-    height: Option<i32>,
-    width: Option<i32>,
+    /// The url of the thumbnail. Can refer to an attachment of the same message, see
+    /// [`EmbedThumbnail::attachment`].
+    pub url: String,
+    pub proxy_url: Option<String>,
+    pub height: Option<i32>,
+    pub width: Option<i32>,
+}
+
+impl EmbedThumbnail {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a thumbnail which shows an attachment uploaded along with the message, such as
+    /// `attachment://image.png`.
+    pub fn attachment(filename: &str) -> Self {
+        Self::new(format!("{}{}", ATTACHMENT_URL_SCHEME, filename))
+    }
+
+    /// Returns the filename of the attachment this thumbnail refers to, if it refers to one.
+    pub fn attachment_filename(&self) -> Option<&str> {
+        self.url.strip_prefix(ATTACHMENT_URL_SCHEME)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
-struct EmbedVideo {
-    url: Option<String>,
-    proxy_url: Option<String>,
-    height: Option<i32>,
-    width: Option<i32>,
+pub struct EmbedVideo {
+    pub url: Option<String>,
+    pub proxy_url: Option<String>,
+    pub height: Option<i32>,
+    pub width: Option<i32>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct EmbedProvider {
-    name: Option<String>,
-    url: Option<String>,
+    pub name: Option<String>,
+    pub url: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct EmbedAuthor {
-    name: String,
-    url: Option<String>,
-    icon_url: Option<String>,
-    proxy_icon_url: Option<String>,
+    pub name: String,
+    pub url: Option<String>,
+    pub icon_url: Option<String>,
+    pub proxy_icon_url: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct EmbedField {
-    name: String,
-    value: String,
-    inline: Option<bool>,
+    pub name: String,
+    pub value: String,
+    pub inline: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#reaction-object>
 pub struct Reaction {
+    /// The total amount of reactions with this emoji, including burst reactions
     pub count: u32,
+    /// The amount of reactions with this emoji, by type. Not sent by instances which do not
+    /// implement burst reactions.
+    pub count_details: Option<ReactionCountDetails>,
+    #[serde(default)]
     pub burst_count: u32,
     pub me: bool,
+    #[serde(default)]
     pub burst_me: bool,
+    /// The colors of the burst reaction animation, as hex codes
+    #[serde(default)]
     pub burst_colors: Vec<String>,
     pub emoji: Emoji,
 }
 
+impl Reaction {
+    /// The amount of normal, non-burst reactions with this emoji.
+    pub fn normal_count(&self) -> u32 {
+        match &self.count_details {
+            Some(details) => details.normal,
+            None => self.count.saturating_sub(self.burst_count),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#reaction-count-details-structure>
+pub struct ReactionCountDetails {
+    pub normal: u32,
+    pub burst: u32,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize_repr,
+    Deserialize_repr,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[repr(u8)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#reaction-type>
+pub enum ReactionType {
+    #[default]
+    Normal = 0,
+    /// A super reaction
+    Burst = 1,
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, Eq, PartialOrd, Ord)]
 pub enum Component {
     ActionRow = 1,
@@ -9,13 +9,16 @@ pub use auto_moderation::*;
 pub use channel::*;
 pub use config::*;
 pub use emoji::*;
+pub use entitlement::*;
 pub use guild::*;
+pub use guild_join_request::*;
 pub use guild_member::*;
 pub use integration::*;
 pub use invite::*;
 pub use message::*;
 pub use ratelimits::*;
 pub use relationship::*;
+pub use report::*;
 pub use role::*;
 pub use security_key::*;
 pub use stage_instance::*;
@@ -23,6 +26,7 @@ pub use sticker::*;
 pub use team::*;
 pub use template::*;
 pub use user::*;
+pub use user_guild_settings::*;
 pub use user_settings::*;
 pub use voice_state::*;
 pub use webhook::*;
@@ -49,13 +53,16 @@ mod auto_moderation;
 mod channel;
 mod config;
 mod emoji;
+mod entitlement;
 mod guild;
+mod guild_join_request;
 mod guild_member;
 mod integration;
 mod invite;
 mod message;
 mod ratelimits;
 mod relationship;
+mod report;
 mod role;
 mod security_key;
 mod stage_instance;
@@ -63,6 +70,7 @@ mod sticker;
 mod team;
 mod template;
 mod user;
+mod user_guild_settings;
 mod user_settings;
 mod voice_state;
 mod webhook;
@@ -0,0 +1,163 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::Snowflake;
+
+/// The kinds of content which can be reported.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/report#report-type>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportType {
+    #[default]
+    Message,
+    User,
+    Guild,
+    GuildDirectoryEntry,
+    GuildScheduledEvent,
+    StageChannel,
+    FirstDm,
+}
+
+impl std::fmt::Display for ReportType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReportType::Message => "message",
+            ReportType::User => "user",
+            ReportType::Guild => "guild",
+            ReportType::GuildDirectoryEntry => "guild_directory_entry",
+            ReportType::GuildScheduledEvent => "guild_scheduled_event",
+            ReportType::StageChannel => "stage_channel",
+            ReportType::FirstDm => "first_dm",
+        })
+    }
+}
+
+/// The menu a user walks through to submit a report, as a tree of [`ReportNode`]s.
+///
+/// The ids of the nodes visited from the root node to the submitting node make up the
+/// breadcrumbs of the report, see [`ReportMenu::path_to`].
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/report#menu-object>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportMenu {
+    pub name: String,
+    pub variant: String,
+    pub version: String,
+    pub language: String,
+    #[serde(default)]
+    pub postback_url: Option<String>,
+    pub root_node_id: u64,
+    pub success_node_id: u64,
+    pub fail_node_id: u64,
+    pub nodes: HashMap<u64, ReportNode>,
+}
+
+impl ReportMenu {
+    /// Returns the node with the given id.
+    pub fn node(&self, node_id: u64) -> Option<&ReportNode> {
+        self.nodes.get(&node_id)
+    }
+
+    /// Returns the node the menu starts at.
+    pub fn root(&self) -> Option<&ReportNode> {
+        self.node(self.root_node_id)
+    }
+
+    /// Returns the ids of the nodes leading from the root node to the node with the given id,
+    /// including both, or `None` if the node cannot be reached.
+    pub fn path_to(&self, node_id: u64) -> Option<Vec<u64>> {
+        let mut parents: HashMap<u64, u64> = HashMap::new();
+        let mut queue = VecDeque::from([self.root_node_id]);
+        while let Some(current) = queue.pop_front() {
+            if current == node_id {
+                let mut path = vec![current];
+                while let Some(parent) = parents.get(path.last().unwrap()) {
+                    path.push(*parent);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let Some(node) = self.node(current) else {
+                continue;
+            };
+            for (_, child) in node.children.iter() {
+                if *child != self.root_node_id && !parents.contains_key(child) {
+                    parents.insert(*child, current);
+                    queue.push_back(*child);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A single step of a [`ReportMenu`].
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/report#node-object>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportNode {
+    pub id: u64,
+    pub key: String,
+    pub header: String,
+    #[serde(default)]
+    pub subheader: Option<String>,
+    #[serde(default)]
+    pub info: Option<String>,
+    #[serde(default)]
+    pub button: Option<ReportButton>,
+    #[serde(default)]
+    pub elements: Vec<ReportElement>,
+    /// The reason which is reported, if this node selects one
+    #[serde(default)]
+    pub report_type: Option<String>,
+    /// The label and node id of the options which lead away from this node
+    #[serde(default)]
+    pub children: Vec<(String, u64)>,
+    #[serde(default)]
+    pub is_multi_select_required: bool,
+    #[serde(default)]
+    pub is_auto_submit: bool,
+}
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/report#button-object>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportButton {
+    /// For example `next`, `submit`, `done` or `cancel`
+    #[serde(rename = "type")]
+    pub button_type: String,
+    /// The node the button leads to
+    #[serde(default)]
+    pub target: Option<u64>,
+}
+
+/// An element of a [`ReportNode`], such as a checkbox list or a text field.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/report#element-object>
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportElement {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub element_type: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+    #[serde(default)]
+    pub should_submit_data: bool,
+}
+
+/// The response to a submitted report.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSubmission {
+    pub report_id: Snowflake,
+}
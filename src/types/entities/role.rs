@@ -44,7 +44,12 @@ pub struct RoleObject {
     pub tags: Option<RoleTags>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Information about the role subscription purchase or renewal a
+/// [`Message`](crate::types::Message) was sent for.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/message#role-subscription-data-structure>
 pub struct RoleSubscriptionData {
     pub role_subscription_listing_id: Snowflake,
     pub tier_name: String,
@@ -52,6 +57,98 @@ pub struct RoleSubscriptionData {
     pub is_renewal: bool,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The role subscriptions a guild offers, grouping its tiers.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild-role-subscription#role-subscription-group-listing-object>
+pub struct RoleSubscriptionGroupListing {
+    pub id: Snowflake,
+    pub application_id: Snowflake,
+    pub image_asset: Option<StoreAsset>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub subscription_listings: Vec<RoleSubscriptionListing>,
+    #[serde(default)]
+    pub subscription_listings_ids: Vec<Snowflake>,
+    #[serde(default)]
+    pub published: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A single tier of a guild's role subscriptions.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild-role-subscription#role-subscription-listing-object>
+pub struct RoleSubscriptionListing {
+    pub id: Snowflake,
+    pub application_id: Snowflake,
+    pub name: String,
+    pub description: Option<String>,
+    pub image_asset: Option<StoreAsset>,
+    /// The id of the role subscribers receive
+    pub role_id: Option<Snowflake>,
+    pub role_benefits: Option<RoleSubscriptionBenefits>,
+    #[serde(default)]
+    pub subscription_plans: Vec<SubscriptionPlan>,
+    #[serde(default)]
+    pub published: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild-role-subscription#role-subscription-benefits-structure>
+pub struct RoleSubscriptionBenefits {
+    #[serde(default)]
+    pub benefits: Vec<RoleSubscriptionBenefit>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A perk of a role subscription tier, such as access to a channel or an intangible benefit.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild-role-subscription#role-subscription-benefit-structure>
+pub struct RoleSubscriptionBenefit {
+    /// The id of the channel the benefit grants access to, if any
+    pub ref_id: Option<Snowflake>,
+    /// 1 for channels, 2 for intangible benefits
+    pub ref_type: u8,
+    pub emoji_id: Option<Snowflake>,
+    pub emoji_name: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// How much and how often subscribers of a role subscription tier pay.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/subscription#subscription-plan-object>
+pub struct SubscriptionPlan {
+    pub id: Snowflake,
+    pub name: String,
+    pub sku_id: Snowflake,
+    /// 1 for monthly, 2 for yearly
+    pub interval: u8,
+    pub interval_count: u32,
+    /// The price in the smallest unit of the currency
+    pub price: Option<u64>,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An image uploaded for a store listing.
+pub struct StoreAsset {
+    pub id: Snowflake,
+    pub size: Option<u64>,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub filename: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 /// See <https://discord.com/developers/docs/topics/permissions#role-object-role-tags-structure>
 pub struct RoleTags {
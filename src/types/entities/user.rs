@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::utils::Snowflake;
+use crate::types::utils::{Locale, Snowflake};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_aux::prelude::deserialize_option_number_from_string;
@@ -33,6 +33,11 @@ impl User {
     pub fn into_public_user(self) -> PublicUser {
         PublicUser::from(self)
     }
+
+    /// Returns the [`Locale`] of the user's [`locale`](User::locale) field.
+    pub fn locale(&self) -> Option<Locale> {
+        self.locale.as_deref().map(Locale::from_code)
+    }
 }
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "client", derive(Updateable, Composite))]
@@ -45,7 +50,7 @@ pub struct User {
     pub bot: Option<bool>,
     pub system: Option<bool>,
     pub mfa_enabled: Option<bool>,
-    pub accent_color: Option<u8>,
+    pub accent_color: Option<u32>,
     #[cfg_attr(feature = "sqlx", sqlx(default))]
     pub locale: Option<String>,
     pub verified: Option<bool>,
@@ -61,13 +66,17 @@ pub struct User {
     pub public_flags: Option<u32>,
     pub banner: Option<String>,
     pub bio: Option<String>,
-    pub theme_colors: Option<Vec<u8>>,
+    pub theme_colors: Option<ThemeColors>,
     pub phone: Option<String>,
     pub nsfw_allowed: Option<bool>,
     pub premium: Option<bool>,
     pub purchased_flags: Option<i32>,
     pub premium_usage_flags: Option<i32>,
     pub disabled: Option<bool>,
+    /// Not sent by instances which do not support guild tags
+    #[serde(default, alias = "clan")]
+    #[cfg_attr(feature = "sqlx", sqlx(skip))]
+    pub primary_guild: Option<PrimaryGuild>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -76,15 +85,18 @@ pub struct PublicUser {
     pub username: Option<String>,
     pub discriminator: Option<String>,
     pub avatar: Option<String>,
-    pub accent_color: Option<u8>,
+    pub accent_color: Option<u32>,
     pub banner: Option<String>,
-    pub theme_colors: Option<Vec<u8>>,
+    pub theme_colors: Option<ThemeColors>,
     pub pronouns: Option<String>,
     pub bot: Option<bool>,
     pub bio: Option<String>,
     pub premium_type: Option<u8>,
     pub premium_since: Option<DateTime<Utc>>,
     pub public_flags: Option<u32>,
+    /// Not sent by instances which do not support guild tags
+    #[serde(default, alias = "clan")]
+    pub primary_guild: Option<PrimaryGuild>,
 }
 
 impl From<User> for PublicUser {
@@ -103,10 +115,78 @@ impl From<User> for PublicUser {
             premium_type: value.premium_type,
             premium_since: value.premium_since,
             public_flags: value.public_flags,
+            primary_guild: value.primary_guild,
+        }
+    }
+}
+
+impl From<PublicUser> for User {
+    /// Fills the fields which are only known for the current user with their defaults.
+    fn from(value: PublicUser) -> Self {
+        Self {
+            id: value.id,
+            username: value.username.unwrap_or_default(),
+            discriminator: value.discriminator.unwrap_or_default(),
+            avatar: value.avatar,
+            accent_color: value.accent_color,
+            banner: value.banner,
+            theme_colors: value.theme_colors,
+            pronouns: value.pronouns,
+            bot: value.bot,
+            bio: value.bio,
+            premium_type: value.premium_type,
+            premium_since: value.premium_since,
+            public_flags: value.public_flags,
+            primary_guild: value.primary_guild,
+            ..Default::default()
         }
     }
 }
 
+/// The guild whose tag a user displays next to their name, also known as their clan.
+///
+/// All fields are optional, as instances send partial objects or leave out fields which they do
+/// not support.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user#primary-guild-object>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PrimaryGuild {
+    /// The id of the guild
+    #[serde(default)]
+    pub identity_guild_id: Option<Snowflake>,
+    /// Whether the user displays the guild's tag. `None` if the tag was cleared by the system,
+    /// for example because the guild removed its tag.
+    #[serde(default)]
+    pub identity_enabled: Option<bool>,
+    /// The text of the tag, up to 4 characters long
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// The hash of the tag's badge
+    #[serde(default)]
+    pub badge: Option<String>,
+}
+
+impl PrimaryGuild {
+    /// Returns true if the tag is displayed, meaning that it is enabled and has a text.
+    pub fn is_displayed(&self) -> bool {
+        self.identity_enabled == Some(true) && self.tag.is_some()
+    }
+
+    /// Returns the CDN url of the tag's badge, if it has one.
+    ///
+    /// # Reference
+    /// See <https://discord-userdoccers.vercel.app/reference#cdn-formatting>
+    pub fn badge_url(&self, cdn_url: &str) -> Option<String> {
+        let guild_id = self.identity_guild_id?;
+        let badge = self.badge.as_ref()?;
+        Some(format!(
+            "{}/clan-badges/{}/{}.png",
+            cdn_url, guild_id, badge
+        ))
+    }
+}
+
 #[allow(dead_code)] // FIXME: Remove this when we actually use this
 const CUSTOM_USER_FLAG_OFFSET: u64 = 1 << 32;
 
@@ -143,8 +223,39 @@ pub struct UserProfileMetadata {
     pub pronouns: String,
     pub bio: Option<String>,
     pub banner: Option<String>,
-    pub accent_color: Option<i32>,
-    pub theme_colors: Option<Vec<i32>>,
+    pub accent_color: Option<u32>,
+    pub theme_colors: Option<ThemeColors>,
     pub popout_animation_particle_type: Option<Snowflake>,
     pub emoji: Option<Emoji>,
 }
+
+/// The primary and accent colors of a profile theme, as RGB integers such as `0xff0000`.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user#user-profile-metadata-object>
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "[u32; 2]", into = "[u32; 2]")]
+pub struct ThemeColors {
+    pub primary: u32,
+    pub accent: u32,
+}
+
+impl ThemeColors {
+    pub fn new(primary: u32, accent: u32) -> Self {
+        Self { primary, accent }
+    }
+}
+
+impl From<[u32; 2]> for ThemeColors {
+    fn from([primary, accent]: [u32; 2]) -> Self {
+        Self { primary, accent }
+    }
+}
+
+impl From<ThemeColors> for [u32; 2] {
+    fn from(colors: ThemeColors) -> Self {
+        [colors.primary, colors.accent]
+    }
+}
@@ -0,0 +1,156 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::types::Snowflake;
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The notification settings of the current user for a guild, or for direct messages.
+///
+/// Ex: {"version":2,"suppress_roles":false,"suppress_everyone":false,"notify_highlights":0,"muted":false,"mute_scheduled_events":false,"mute_config":null,"mobile_push":true,"message_notifications":1,"hide_muted_channels":false,"guild_id":"848582562217590824","flags":0,"channel_overrides":[{"muted":false,"mute_config":null,"message_notifications":3,"flags":4096,"collapsed":false,"channel_id":"1042689182893604885"}]}
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user-settings#user-guild-settings-object>
+pub struct UserGuildSettings {
+    pub version: u64,
+    pub suppress_roles: bool,
+    pub suppress_everyone: bool,
+    pub notify_highlights: u8,
+    pub muted: bool,
+    pub mute_scheduled_events: bool,
+    /// When the guild is unmuted again, if it is muted temporarily
+    pub mute_config: Option<MuteConfig>,
+    pub mobile_push: bool,
+    pub message_notifications: UserNotificationLevel,
+    pub hide_muted_channels: bool,
+    /// `None` for the settings of direct messages
+    pub guild_id: Option<Snowflake>,
+    pub flags: i32,
+    pub channel_overrides: Vec<UserGuildSettingsChannelOverride>,
+}
+
+impl UserGuildSettings {
+    /// Returns true if the guild is muted at the given time.
+    pub fn is_muted_at(&self, time: DateTime<Utc>) -> bool {
+        is_muted(self.muted, &self.mute_config, time)
+    }
+
+    /// Returns the settings of a channel, if they differ from the guild's.
+    pub fn channel_override(
+        &self,
+        channel_id: Snowflake,
+    ) -> Option<&UserGuildSettingsChannelOverride> {
+        self.channel_overrides
+            .iter()
+            .find(|channel_override| channel_override.channel_id == channel_id)
+    }
+
+    /// Returns true if the channel, or the category it is in, is muted at the given time.
+    ///
+    /// Muting the guild does not mute its channels, see [`UserGuildSettings::is_muted_at`].
+    pub fn is_channel_muted_at(
+        &self,
+        channel_id: Snowflake,
+        parent_id: Option<Snowflake>,
+        time: DateTime<Utc>,
+    ) -> bool {
+        [Some(channel_id), parent_id]
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.channel_override(id))
+            .any(|channel_override| {
+                is_muted(channel_override.muted, &channel_override.mute_config, time)
+            })
+    }
+
+    /// Returns the notification level of a channel, falling back to its category's and then to
+    /// the guild's.
+    pub fn channel_notifications(
+        &self,
+        channel_id: Snowflake,
+        parent_id: Option<Snowflake>,
+    ) -> UserNotificationLevel {
+        [Some(channel_id), parent_id]
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.channel_override(id))
+            .map(|channel_override| channel_override.message_notifications)
+            .find(|level| *level != UserNotificationLevel::ParentDefault)
+            .unwrap_or(self.message_notifications)
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The notification settings of the current user for all guilds.
+pub struct UserGuildSettingsEntries {
+    pub entries: Vec<UserGuildSettings>,
+    /// Whether only the settings of some guilds are included
+    #[serde(default)]
+    pub partial: bool,
+    #[serde(default)]
+    pub version: u64,
+}
+
+impl UserGuildSettingsEntries {
+    /// Returns the settings of a guild, or of direct messages if `guild_id` is `None`.
+    pub fn get(&self, guild_id: Option<Snowflake>) -> Option<&UserGuildSettings> {
+        self.entries
+            .iter()
+            .find(|settings| settings.guild_id == guild_id)
+    }
+}
+
+fn is_muted(muted: bool, mute_config: &Option<MuteConfig>, time: DateTime<Utc>) -> bool {
+    muted
+        && mute_config
+            .as_ref()
+            .and_then(|config| config.end_time)
+            .map_or(true, |end_time| end_time > time)
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// The settings of the current user for a channel, overriding the settings of its guild.
+///
+/// Ex: {"muted":false,"mute_config":null,"message_notifications":3,"flags":4096,"collapsed":false,"channel_id":"1042689182893604885"}
+pub struct UserGuildSettingsChannelOverride {
+    pub muted: bool,
+    /// When the channel is unmuted again, if it is muted temporarily
+    pub mute_config: Option<MuteConfig>,
+    pub message_notifications: UserNotificationLevel,
+    pub flags: i32,
+    pub collapsed: bool,
+    pub channel_id: Snowflake,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+/// How long a guild or channel is muted for.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user-settings#mute-config-structure>
+pub struct MuteConfig {
+    /// When the mute ends, `None` if it does not
+    pub end_time: Option<DateTime<Utc>>,
+    /// The length of the mute the user selected, in seconds, or -1 if it does not end
+    pub selected_time_window: i32,
+}
+
+#[derive(
+    Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd,
+)]
+#[repr(u8)]
+/// Which messages the current user is notified of.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user-settings#message-notification-level>
+pub enum UserNotificationLevel {
+    AllMessages = 0,
+    OnlyMentions = 1,
+    NoMessages = 2,
+    /// Uses the level of the category or guild
+    #[default]
+    ParentDefault = 3,
+}
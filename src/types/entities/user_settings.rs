@@ -2,12 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::HashSet;
 use std::sync::{Arc, RwLock};
 
 use chrono::{serde::ts_milliseconds_option, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::gateway::Shared;
+use crate::types::Snowflake;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
@@ -148,12 +150,243 @@ impl Default for FriendSourceFlags {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// An entry of a user's guild list. Either a folder, or a single guild outside of any folder, in
+/// which case `id`, `name` and `color` are `None`.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user-settings#guild-folder-structure>
 pub struct GuildFolder {
-    pub color: u32,
+    pub color: Option<u32>,
     pub guild_ids: Vec<String>,
-    pub id: u16,
-    pub name: String,
+    pub id: Option<u64>,
+    pub name: Option<String>,
+}
+
+impl GuildFolder {
+    /// Returns true if this entry is an actual folder, and not a single guild outside of any
+    /// folder.
+    pub fn is_folder(&self) -> bool {
+        self.id.is_some()
+    }
+
+    /// Returns true if the guild is in this entry.
+    pub fn contains(&self, guild_id: Snowflake) -> bool {
+        let guild_id = guild_id.to_string();
+        self.guild_ids.contains(&guild_id)
+    }
+}
+
+/// A user's guild list, made up of folders and guilds outside of folders, in display order.
+///
+/// The list is kept consistent while it is modified: every guild appears exactly once, and empty
+/// folders are removed. Changes are sent to the server with
+/// [`ChorusUser::modify_guild_folders`](crate::instance::ChorusUser::modify_guild_folders).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GuildFolders {
+    entries: Vec<GuildFolder>,
+}
+
+impl GuildFolders {
+    pub fn new(entries: Vec<GuildFolder>) -> Self {
+        let mut folders = Self { entries };
+        folders.normalize();
+        folders
+    }
+
+    /// The entries of the guild list, in display order.
+    pub fn entries(&self) -> &[GuildFolder] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<GuildFolder> {
+        self.entries
+    }
+
+    /// The ids of all guilds in display order, as used for
+    /// [`UserSettings::guild_positions`].
+    pub fn guild_positions(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.guild_ids.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the folder with the given id.
+    pub fn folder(&self, folder_id: u64) -> Option<&GuildFolder> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == Some(folder_id))
+    }
+
+    /// Returns the entry containing the given guild.
+    pub fn folder_of(&self, guild_id: Snowflake) -> Option<&GuildFolder> {
+        self.entries.iter().find(|entry| entry.contains(guild_id))
+    }
+
+    /// Creates a new folder containing the given guilds, which are moved out of their current
+    /// entries. The folder takes the place of the first of the guilds, or is appended if none of
+    /// them is in the list yet.
+    ///
+    /// Returns the id of the new folder.
+    pub fn create_folder(
+        &mut self,
+        name: Option<String>,
+        color: Option<u32>,
+        guild_ids: &[Snowflake],
+    ) -> u64 {
+        let id = self
+            .entries
+            .iter()
+            .filter_map(|entry| entry.id)
+            .max()
+            .map_or(1, |max| max + 1);
+        let position = guild_ids
+            .first()
+            .and_then(|guild_id| self.position_of(*guild_id))
+            .unwrap_or(self.entries.len());
+        for guild_id in guild_ids {
+            self.take_guild(*guild_id);
+        }
+        self.entries.insert(
+            position.min(self.entries.len()),
+            GuildFolder {
+                color,
+                guild_ids: guild_ids.iter().map(Snowflake::to_string).collect(),
+                id: Some(id),
+                name,
+            },
+        );
+        self.normalize();
+        id
+    }
+
+    /// Moves a guild to `index` of the folder with the id `folder_id`, or, if `folder_id` is
+    /// `None`, out of any folder to `index` of the guild list itself.
+    ///
+    /// Returns false if there is no folder with the given id.
+    pub fn move_guild(
+        &mut self,
+        guild_id: Snowflake,
+        folder_id: Option<u64>,
+        index: usize,
+    ) -> bool {
+        if folder_id.map_or(false, |folder_id| self.folder(folder_id).is_none()) {
+            return false;
+        }
+        self.take_guild(guild_id);
+        match folder_id {
+            Some(folder_id) => {
+                let folder = self
+                    .entries
+                    .iter_mut()
+                    .find(|entry| entry.id == Some(folder_id))
+                    .unwrap();
+                let index = index.min(folder.guild_ids.len());
+                folder.guild_ids.insert(index, guild_id.to_string());
+            }
+            None => {
+                let index = index.min(self.entries.len());
+                self.entries.insert(
+                    index,
+                    GuildFolder {
+                        guild_ids: vec![guild_id.to_string()],
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+        self.normalize();
+        true
+    }
+
+    /// Removes a guild from the list, for example after leaving it. Returns false if the guild was
+    /// not in the list.
+    pub fn remove_guild(&mut self, guild_id: Snowflake) -> bool {
+        let removed = self.take_guild(guild_id);
+        self.normalize();
+        removed
+    }
+
+    /// Renames a folder. Returns false if there is no folder with the given id.
+    pub fn rename_folder(&mut self, folder_id: u64, name: Option<String>) -> bool {
+        match self.folder_mut(folder_id) {
+            Some(folder) => {
+                folder.name = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the color of a folder. Returns false if there is no folder with the given id.
+    pub fn set_folder_color(&mut self, folder_id: u64, color: Option<u32>) -> bool {
+        match self.folder_mut(folder_id) {
+            Some(folder) => {
+                folder.color = color;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Deletes a folder. Its guilds stay in its place, outside of any folder. Returns false if
+    /// there is no folder with the given id.
+    pub fn delete_folder(&mut self, folder_id: u64) -> bool {
+        let Some(position) = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == Some(folder_id))
+        else {
+            return false;
+        };
+        let folder = self.entries.remove(position);
+        let guilds = folder.guild_ids.into_iter().map(|guild_id| GuildFolder {
+            guild_ids: vec![guild_id],
+            ..Default::default()
+        });
+        self.entries.splice(position..position, guilds);
+        true
+    }
+
+    fn folder_mut(&mut self, folder_id: u64) -> Option<&mut GuildFolder> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.id == Some(folder_id))
+    }
+
+    fn position_of(&self, guild_id: Snowflake) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.contains(guild_id))
+    }
+
+    /// Removes a guild from all entries, leaving empty entries in place.
+    fn take_guild(&mut self, guild_id: Snowflake) -> bool {
+        let guild_id = guild_id.to_string();
+        let mut removed = false;
+        for entry in self.entries.iter_mut() {
+            let length = entry.guild_ids.len();
+            entry.guild_ids.retain(|id| *id != guild_id);
+            removed |= entry.guild_ids.len() != length;
+        }
+        removed
+    }
+
+    /// Removes duplicate guilds, keeping their first occurrence, and empty entries.
+    fn normalize(&mut self) {
+        let mut seen = HashSet::new();
+        for entry in self.entries.iter_mut() {
+            entry.guild_ids.retain(|id| seen.insert(id.clone()));
+        }
+        self.entries.retain(|entry| !entry.guild_ids.is_empty());
+    }
+}
+
+impl From<Vec<GuildFolder>> for GuildFolders {
+    fn from(entries: Vec<GuildFolder>) -> Self {
+        Self::new(entries)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -5,6 +5,8 @@
 use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::gateway::Shared;
 #[cfg(feature = "client")]
@@ -20,7 +22,7 @@ use crate::types::Composite;
 use crate::gateway::GatewayHandle;
 
 use crate::types::{
-    entities::{Guild, User},
+    entities::{Entitlement, Guild, User},
     utils::Snowflake,
 };
 
@@ -33,11 +35,12 @@ pub struct Webhook {
     #[serde(rename = "type")]
     pub webhook_type: i32,
     pub name: String,
-    pub avatar: String,
-    pub token: String,
-    pub guild_id: Snowflake,
+    pub avatar: Option<String>,
+    /// Only returned to users who can manage the webhook, and when getting it with its token
+    pub token: Option<String>,
+    pub guild_id: Option<Snowflake>,
     pub channel_id: Snowflake,
-    pub application_id: Snowflake,
+    pub application_id: Option<Snowflake>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[cfg_attr(feature = "sqlx", sqlx(skip))]
     pub user: Option<Shared<User>>,
@@ -47,3 +50,148 @@ pub struct Webhook {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 }
+
+/// The body of a request sent to an application's event webhooks url.
+///
+/// Endpoints receiving these requests should respond with `204 No Content`, including to
+/// [`ApplicationWebhookType::Ping`] requests, which are sent to verify the url.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/events/webhook-events#webhook-event-payloads>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationWebhook {
+    /// Version scheme for the webhook event, currently always 1
+    pub version: u8,
+    pub application_id: Snowflake,
+    #[serde(rename = "type")]
+    pub webhook_type: ApplicationWebhookType,
+    /// The event which occurred. Only present for [`ApplicationWebhookType::Event`].
+    pub event: Option<ApplicationWebhookEvent>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+/// # Reference
+/// See <https://discord.com/developers/docs/events/webhook-events#webhook-types>
+pub enum ApplicationWebhookType {
+    /// A ping to test the webhook url
+    #[default]
+    Ping = 0,
+    /// An event, see [`ApplicationWebhook::event`]
+    Event = 1,
+}
+
+/// An event received through an application's event webhooks url.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/events/webhook-events#event-body-object>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    try_from = "RawApplicationWebhookEvent",
+    into = "RawApplicationWebhookEvent"
+)]
+pub struct ApplicationWebhookEvent {
+    /// When the event occurred, as an ISO8601 timestamp. Note that it may not include a timezone,
+    /// in which case it is in UTC.
+    pub timestamp: String,
+    pub data: ApplicationWebhookEventData,
+}
+
+/// The type and data of an [`ApplicationWebhookEvent`].
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/events/webhook-events#event-types>
+#[derive(Debug, Clone)]
+pub enum ApplicationWebhookEventData {
+    /// Sent when the application was authorized by a user, to a guild or to their account
+    ApplicationAuthorized(Box<ApplicationAuthorized>),
+    /// Sent when an entitlement was created, for example when a user purchased a SKU
+    EntitlementCreate(Box<Entitlement>),
+    /// An event type which is not known to chorus, along with its raw data
+    Unknown { event_type: String, data: Value },
+}
+
+impl ApplicationWebhookEventData {
+    /// The type of the event, for example `APPLICATION_AUTHORIZED`.
+    pub fn event_type(&self) -> &str {
+        match self {
+            ApplicationWebhookEventData::ApplicationAuthorized(_) => "APPLICATION_AUTHORIZED",
+            ApplicationWebhookEventData::EntitlementCreate(_) => "ENTITLEMENT_CREATE",
+            ApplicationWebhookEventData::Unknown { event_type, .. } => event_type,
+        }
+    }
+}
+
+/// The event body as it is sent over the wire, before its data has been parsed according to its
+/// type.
+#[derive(Serialize, Deserialize)]
+struct RawApplicationWebhookEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    timestamp: String,
+    #[serde(default)]
+    data: Value,
+}
+
+impl TryFrom<RawApplicationWebhookEvent> for ApplicationWebhookEvent {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawApplicationWebhookEvent) -> Result<Self, Self::Error> {
+        let data = match raw.event_type.as_str() {
+            "APPLICATION_AUTHORIZED" => ApplicationWebhookEventData::ApplicationAuthorized(
+                serde_json::from_value(raw.data)?,
+            ),
+            "ENTITLEMENT_CREATE" => {
+                ApplicationWebhookEventData::EntitlementCreate(serde_json::from_value(raw.data)?)
+            }
+            _ => ApplicationWebhookEventData::Unknown {
+                event_type: raw.event_type,
+                data: raw.data,
+            },
+        };
+        Ok(ApplicationWebhookEvent {
+            timestamp: raw.timestamp,
+            data,
+        })
+    }
+}
+
+impl From<ApplicationWebhookEvent> for RawApplicationWebhookEvent {
+    fn from(event: ApplicationWebhookEvent) -> Self {
+        let event_type = event.data.event_type().to_string();
+        let data = match event.data {
+            ApplicationWebhookEventData::ApplicationAuthorized(data) => serde_json::to_value(data),
+            ApplicationWebhookEventData::EntitlementCreate(data) => serde_json::to_value(data),
+            ApplicationWebhookEventData::Unknown { data, .. } => Ok(data),
+        };
+        RawApplicationWebhookEvent {
+            event_type,
+            timestamp: event.timestamp,
+            data: data.unwrap_or_default(),
+        }
+    }
+}
+
+/// # Reference
+/// See <https://discord.com/developers/docs/events/webhook-events#application-authorized-application-authorized-structure>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationAuthorized {
+    /// Where the application was installed, if it was added to a guild or a user's account
+    pub integration_type: Option<ApplicationIntegrationType>,
+    /// The user who authorized the application
+    pub user: User,
+    /// The OAuth2 scopes the user authorized the application for
+    pub scopes: Vec<String>,
+    /// The guild the application was added to, if it was installed to one
+    pub guild: Option<Guild>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/application#application-object-application-integration-types>
+pub enum ApplicationIntegrationType {
+    #[default]
+    GuildInstall = 0,
+    UserInstall = 1,
+}
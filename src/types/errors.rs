@@ -29,16 +29,34 @@ pub enum GuildError {
     InvalidGuildFeature,
 }
 
+/// A form field, which has been filled in incorrectly.
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
 pub enum FieldFormatError {
-    #[error("Password must be between 1 and 72 characters.")]
-    PasswordError,
-    #[error("Username must be between 2 and 32 characters.")]
-    UsernameError,
+    #[error("Password {0}")]
+    Password(FieldFormatReason),
+    #[error("Username {0}")]
+    Username(FieldFormatReason),
     #[error("Consent must be 'true' to register.")]
-    ConsentError,
+    ConsentRequired,
     #[error("The provided email address is in an invalid format.")]
-    EmailError,
+    InvalidEmail,
+    #[error("The date of birth must be a date in the past, formatted as YYYY-MM-DD.")]
+    InvalidDateOfBirth,
+    #[error("A date of birth is required to register.")]
+    DateOfBirthRequired,
+    #[error("You must be at least {minimum_age} years old to register.")]
+    Underage { minimum_age: u8 },
+}
+
+/// Why the value of a form field is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+pub enum FieldFormatReason {
+    #[error("must be at least {min} characters long.")]
+    TooShort { min: usize },
+    #[error("must be at most {max} characters long.")]
+    TooLong { max: usize },
+    #[error("contains characters which are not allowed.")]
+    InvalidCharacters,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
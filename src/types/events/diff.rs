@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use super::WebSocketEvent;
+use crate::types::{Emoji, RoleObject, Snowflake, Sticker};
+
+/// What changed between two versions of a list of entities, which are matched by their ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeSet<T> {
+    /// Entities which are only in the new list
+    pub added: Vec<T>,
+    /// Entities which are only in the old list
+    pub removed: Vec<T>,
+    /// Entities which are in both lists but differ, as `(old, new)`
+    pub changed: Vec<(T, T)>,
+}
+
+impl<T> Default for ChangeSet<T> {
+    fn default() -> Self {
+        Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> ChangeSet<T> {
+    /// Compares two versions of a list, keeping the order of the new list for added and changed
+    /// entities and the order of the old list for removed ones.
+    pub fn between(old: &[T], new: &[T], id: impl Fn(&T) -> Snowflake) -> Self {
+        let old_by_id: HashMap<Snowflake, &T> =
+            old.iter().map(|entity| (id(entity), entity)).collect();
+        let new_ids: Vec<Snowflake> = new.iter().map(&id).collect();
+        let mut changes = Self::default();
+        for entity in new {
+            match old_by_id.get(&id(entity)) {
+                None => changes.added.push(entity.clone()),
+                Some(old) if *old != entity => {
+                    changes.changed.push(((*old).clone(), entity.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = old
+            .iter()
+            .filter(|entity| !new_ids.contains(&id(entity)))
+            .cloned()
+            .collect();
+        changes
+    }
+}
+
+impl<T> ChangeSet<T> {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// Not an actual gateway event; notified after a
+/// [`GuildEmojisUpdate`](super::GuildEmojisUpdate) with what changed compared to the cached
+/// emojis of the guild.
+///
+/// Only notified for [observed](crate::gateway::GatewayHandle::observe) guilds and guilds in
+/// the gateway's `Cache`, and only if something changed.
+pub struct GuildEmojisDiff {
+    pub guild_id: Snowflake,
+    pub emojis: ChangeSet<Emoji>,
+}
+
+impl WebSocketEvent for GuildEmojisDiff {}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// Not an actual gateway event; notified after a
+/// [`GuildStickersUpdate`](super::GuildStickersUpdate) with what changed compared to the cached
+/// stickers of the guild.
+///
+/// Only notified for [observed](crate::gateway::GatewayHandle::observe) guilds and guilds in
+/// the gateway's `Cache`, and only if something changed.
+pub struct GuildStickersDiff {
+    pub guild_id: Snowflake,
+    pub stickers: ChangeSet<Sticker>,
+}
+
+impl WebSocketEvent for GuildStickersDiff {}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// Not an actual gateway event; notified after a [`GuildRoleCreate`](super::GuildRoleCreate),
+/// [`GuildRoleUpdate`](super::GuildRoleUpdate) or [`GuildRoleDelete`](super::GuildRoleDelete)
+/// with what changed compared to the cached roles of the guild.
+///
+/// Only notified for [observed](crate::gateway::GatewayHandle::observe) guilds and guilds in
+/// the gateway's `Cache`, and only if something changed.
+pub struct GuildRolesDiff {
+    pub guild_id: Snowflake,
+    pub roles: ChangeSet<RoleObject>,
+}
+
+impl WebSocketEvent for GuildRolesDiff {}
@@ -0,0 +1,22 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use super::{ShardInfo, WebSocketEvent};
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Not an actual gateway event; notified for every event dispatched on a gateway connection,
+/// right before the event itself.
+///
+/// Tells apart the connections of a sharded bot, for example to attribute load to single shards.
+pub struct GatewayDispatch {
+    /// The shard of the connection, if it identified as one
+    pub shard: Option<ShardInfo>,
+    /// The name of the dispatched event, such as `MESSAGE_CREATE`
+    pub event_name: String,
+    pub sequence_number: Option<u64>,
+}
+
+impl WebSocketEvent for GatewayDispatch {}
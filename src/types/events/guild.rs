@@ -9,8 +9,8 @@ use serde::{Deserialize, Serialize};
 use crate::types::entities::{Guild, PublicUser, UnavailableGuild};
 use crate::types::events::WebSocketEvent;
 use crate::types::{
-    AuditLogEntry, Emoji, GuildMember, GuildScheduledEvent, IntoShared, JsonField, RoleObject,
-    Snowflake, SourceUrlField, Sticker,
+    AuditLogEntry, Emoji, GuildJoinRequest, GuildJoinRequestStatus, GuildMember, GuildMemberFlags,
+    GuildScheduledEvent, IntoShared, JsonField, RoleObject, Snowflake, SourceUrlField, Sticker,
 };
 
 use super::PresenceUpdate;
@@ -131,6 +131,9 @@ impl WebSocketEvent for GuildDelete {}
 /// See <https://discord.com/developers/docs/topics/gateway-events#guild-audit-log-entry-create>;
 /// Received to the client about an audit log entry being added;
 pub struct GuildAuditLogEntryCreate {
+    /// Not sent by every instance
+    #[serde(default)]
+    pub guild_id: Option<Snowflake>,
     #[serde(flatten)]
     pub entry: AuditLogEntry,
 }
@@ -200,6 +203,33 @@ pub struct GuildMemberUpdate {
     pub mute: Option<bool>,
     pub pending: Option<bool>,
     pub communication_disabled_until: Option<DateTime<Utc>>,
+    /// See [`GuildMemberUpdate::member_flags`]
+    pub flags: Option<u64>,
+}
+
+impl GuildMemberUpdate {
+    /// Returns the member's flags, ignoring unknown bits.
+    pub fn member_flags(&self) -> GuildMemberFlags {
+        GuildMemberFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+
+    /// Applies the update to the member it belongs to, keeping the fields it does not include.
+    pub fn apply(&self, member: &mut GuildMember) {
+        member.roles = self.roles.clone();
+        member.nick = self.nick.clone();
+        member.avatar = self.avatar.clone();
+        if let Some(joined_at) = self.joined_at {
+            member.joined_at = joined_at.to_rfc3339();
+        }
+        member.premium_since = self.premium_since.map(|since| since.to_rfc3339());
+        member.deaf = self.deaf.unwrap_or(member.deaf);
+        member.mute = self.mute.unwrap_or(member.mute);
+        member.pending = self.pending.or(member.pending);
+        member.communication_disabled_until = self
+            .communication_disabled_until
+            .map(|until| until.to_rfc3339());
+        member.flags = self.flags.or(member.flags);
+    }
 }
 
 impl WebSocketEvent for GuildMemberUpdate {}
@@ -212,7 +242,7 @@ pub struct GuildMembersChunk {
     pub chunk_index: u16,
     pub chunk_count: u16,
     pub not_found: Option<Vec<Snowflake>>,
-    pub presences: Option<PresenceUpdate>,
+    pub presences: Option<Vec<PresenceUpdate>>,
     pub nonce: Option<String>,
 }
 
@@ -333,3 +363,42 @@ pub struct GuildScheduledEventUserRemove {
 }
 
 impl WebSocketEvent for GuildScheduledEventUserRemove {}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+/// Sent when a user starts or submits a request to join a guild with member verification.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/topics/gateway-events#guild-join-request-create>
+pub struct GuildJoinRequestCreate {
+    pub status: GuildJoinRequestStatus,
+    pub request: GuildJoinRequest,
+    pub guild_id: Snowflake,
+}
+
+impl WebSocketEvent for GuildJoinRequestCreate {}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+/// Sent when a guild join request is submitted, approved or rejected.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/topics/gateway-events#guild-join-request-update>
+pub struct GuildJoinRequestUpdate {
+    pub status: GuildJoinRequestStatus,
+    pub request: GuildJoinRequest,
+    pub guild_id: Snowflake,
+}
+
+impl WebSocketEvent for GuildJoinRequestUpdate {}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+/// Sent when a guild join request is withdrawn or otherwise removed.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/topics/gateway-events#guild-join-request-delete>
+pub struct GuildJoinRequestDelete {
+    pub id: Snowflake,
+    pub user_id: Snowflake,
+    pub guild_id: Snowflake,
+}
+
+impl WebSocketEvent for GuildJoinRequestDelete {}
@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Snowflake;
+
+use super::WebSocketEvent;
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq)]
+/// Officially Undocumented
+///
+/// Sent to the server to subscribe to events of multiple guilds at once;
+/// Supersedes [LazyRequest](crate::types::LazyRequest) (opcode 14) on newer clients
+///
+/// See <https://docs.discord.sex/topics/gateway-events#update-guild-subscriptions>
+///
+/// {"op":37,"d":{"subscriptions":{"848582562217590824":{"typing":true,"activities":true,"threads":true}}}}
+pub struct GuildSubscriptionsBulk {
+    pub subscriptions: HashMap<Snowflake, GuildSubscription>,
+}
+
+impl WebSocketEvent for GuildSubscriptionsBulk {}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq)]
+/// The subscription state for one guild, as sent in a [GuildSubscriptionsBulk].
+///
+/// Fields that are `None` are left untouched by the server.
+pub struct GuildSubscription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typing: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activities: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_updates: Option<bool>,
+    /// Ids of members whose presence and member updates to receive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<Snowflake>>,
+    /// Member list ranges to subscribe to, per channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<HashMap<Snowflake, Vec<[u64; 2]>>>,
+    /// Ids of threads whose member lists to receive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_member_lists: Option<Vec<Snowflake>>,
+}
+
+impl GuildSubscription {
+    /// Merges a newer subscription into this one. Fields set on `other` overwrite the ones on
+    /// `self`, fields left as `None` keep their previous value.
+    pub fn merge(&mut self, other: GuildSubscription) {
+        macro_rules! merge_fields {
+            ($($field:ident),*) => {
+                $(
+                    if other.$field.is_some() {
+                        self.$field = other.$field;
+                    }
+                )*
+            };
+        }
+        merge_fields!(
+            typing,
+            activities,
+            threads,
+            member_updates,
+            members,
+            channels,
+            thread_member_lists
+        );
+    }
+}
@@ -2,7 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::events::{PresenceUpdate, WebSocketEvent};
+use crate::types::events::{UpdatePresence, WebSocketEvent};
+use crate::types::Snowflake;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -12,13 +13,19 @@ pub struct GatewayIdentifyPayload {
     pub properties: GatewayIdentifyConnectionProps,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compress: Option<bool>,
+    /// How many members a guild can have before only its online members are sent, between 50
+    /// and 250, 50 by default
     #[serde(skip_serializing_if = "Option::is_none")]
     pub large_threshold: Option<i16>,
-    //default: 50
+    /// Whether presence and typing events are sent, true by default
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub shard: Option<Vec<(i32, i32)>>,
+    pub guild_subscriptions: Option<bool>,
+    /// The shard this connection is, if the bot's guilds are split across several connections
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub presence: Option<PresenceUpdate>,
+    pub shard: Option<ShardInfo>,
+    /// The status to start the session with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence: Option<UpdatePresence>,
     // What is the difference between these two?
     // Intents is documented, capabilities is used in users
     // I wonder if these are interchangeable...
@@ -28,6 +35,48 @@ pub struct GatewayIdentifyPayload {
     pub capabilities: Option<i32>,
 }
 
+/// Identifies one of several gateway connections of a bot, each of which receives the events of a
+/// part of the bot's guilds. Sent as `[id, count]`.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway#sharding>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "(u64, u64)", into = "(u64, u64)")]
+pub struct ShardInfo {
+    /// The id of the shard, starting at 0
+    pub id: u64,
+    /// The total amount of shards
+    pub count: u64,
+}
+
+impl ShardInfo {
+    pub fn new(id: u64, count: u64) -> Self {
+        Self { id, count }
+    }
+
+    /// Returns the shard which receives the events of the given guild, if there are `count`
+    /// shards.
+    pub fn for_guild(guild_id: Snowflake, count: u64) -> Self {
+        let count = count.max(1);
+        Self {
+            id: (guild_id.0 >> 22) % count,
+            count,
+        }
+    }
+}
+
+impl From<(u64, u64)> for ShardInfo {
+    fn from((id, count): (u64, u64)) -> Self {
+        Self { id, count }
+    }
+}
+
+impl From<ShardInfo> for (u64, u64) {
+    fn from(shard: ShardInfo) -> Self {
+        (shard.id, shard.count)
+    }
+}
+
 impl Default for GatewayIdentifyPayload {
     fn default() -> Self {
         Self::common()
@@ -44,6 +93,7 @@ impl GatewayIdentifyPayload {
             properties: GatewayIdentifyConnectionProps::default(),
             compress: Some(false),
             large_threshold: None,
+            guild_subscriptions: None,
             shard: None,
             presence: None,
             intents: None,
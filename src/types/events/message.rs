@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    entities::{Emoji, GuildMember, Message, PublicUser},
+    entities::{Emoji, GuildMember, Message, PublicUser, ReactionType},
     Snowflake,
 };
 
@@ -42,6 +42,14 @@ pub struct MessageCreateUser {
     pub member: Option<GuildMember>,
 }
 
+impl MessageCreate {
+    /// Returns true if this dispatch is the echo of a message sent with the given nonce, for
+    /// example one returned by [`MessageSendSchema::ensure_nonce`](crate::types::MessageSendSchema::ensure_nonce).
+    pub fn matches_nonce(&self, nonce: &str) -> bool {
+        self.message.has_nonce(nonce)
+    }
+}
+
 impl WebSocketEvent for MessageCreate {}
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -55,6 +63,66 @@ pub struct MessageUpdate {
     pub mentions: Option<Vec<MessageCreateUser>>,
 }
 
+impl MessageUpdate {
+    /// Whether the update contains the whole message, rather than only the fields which changed,
+    /// such as the embeds resolved for its links.
+    pub fn is_full(&self) -> bool {
+        !self.message.timestamp.is_empty()
+    }
+
+    /// Applies the update to the message it belongs to, keeping the fields it does not include.
+    pub fn apply(&self, message: &mut Message) {
+        fn merge<T: Clone>(field: &mut Option<T>, update: &Option<T>) {
+            if update.is_some() {
+                field.clone_from(update);
+            }
+        }
+
+        let update = &self.message;
+        if self.is_full() {
+            message.timestamp.clone_from(&update.timestamp);
+            message.mention_everyone = update.mention_everyone;
+            message.pinned = update.pinned;
+            message.message_type = update.message_type;
+            #[cfg(feature = "sqlx")]
+            message.embeds.clone_from(&update.embeds);
+        }
+        merge(&mut message.author, &update.author);
+        merge(&mut message.content, &update.content);
+        merge(&mut message.edited_timestamp, &update.edited_timestamp);
+        merge(&mut message.tts, &update.tts);
+        merge(&mut message.mentions, &update.mentions);
+        merge(&mut message.mention_roles, &update.mention_roles);
+        merge(&mut message.mention_channels, &update.mention_channels);
+        merge(&mut message.attachments, &update.attachments);
+        #[cfg(not(feature = "sqlx"))]
+        merge(&mut message.embeds, &update.embeds);
+        merge(&mut message.reactions, &update.reactions);
+        merge(&mut message.nonce, &update.nonce);
+        merge(&mut message.webhook_id, &update.webhook_id);
+        merge(&mut message.activity, &update.activity);
+        merge(&mut message.application, &update.application);
+        merge(&mut message.application_id, &update.application_id);
+        merge(&mut message.message_reference, &update.message_reference);
+        merge(&mut message.flags, &update.flags);
+        merge(&mut message.referenced_message, &update.referenced_message);
+        merge(&mut message.interaction, &update.interaction);
+        merge(&mut message.thread, &update.thread);
+        merge(&mut message.components, &update.components);
+        merge(&mut message.sticker_items, &update.sticker_items);
+        merge(&mut message.stickers, &update.stickers);
+        merge(&mut message.position, &update.position);
+        merge(
+            &mut message.role_subscription_data,
+            &update.role_subscription_data,
+        );
+        merge(
+            &mut message.purchase_notification,
+            &update.purchase_notification,
+        );
+    }
+}
+
 impl WebSocketEvent for MessageUpdate {}
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -89,6 +157,13 @@ pub struct MessageReactionAdd {
     pub guild_id: Option<Snowflake>,
     pub member: Option<GuildMember>,
     pub emoji: Emoji,
+    /// Whether this is a burst reaction
+    #[serde(default)]
+    pub burst: bool,
+    #[serde(default)]
+    pub burst_colors: Vec<String>,
+    #[serde(rename = "type", default)]
+    pub reaction_type: ReactionType,
 }
 
 impl WebSocketEvent for MessageReactionAdd {}
@@ -102,6 +177,11 @@ pub struct MessageReactionRemove {
     pub message_id: Snowflake,
     pub guild_id: Option<Snowflake>,
     pub emoji: Emoji,
+    /// Whether this was a burst reaction
+    #[serde(default)]
+    pub burst: bool,
+    #[serde(rename = "type", default)]
+    pub reaction_type: ReactionType,
 }
 
 impl WebSocketEvent for MessageReactionRemove {}
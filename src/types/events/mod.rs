@@ -2,13 +2,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 
+use crate::errors::GatewayError;
+
 pub use application::*;
 pub use auto_moderation::*;
 pub use call::*;
 pub use channel::*;
+pub use diff::*;
+pub use dispatch::*;
 pub use guild::*;
+pub use guild_subscriptions::*;
 pub use heartbeat::*;
 pub use hello::*;
 pub use identify::*;
@@ -56,7 +63,10 @@ mod application;
 mod auto_moderation;
 mod call;
 mod channel;
+mod diff;
+mod dispatch;
 mod guild;
+mod guild_subscriptions;
 mod heartbeat;
 mod hello;
 mod identify;
@@ -112,7 +122,7 @@ pub struct GatewayReceivePayload<'a> {
 
     #[serde(borrow)]
     #[serde(rename = "d")]
-    pub event_data: Option<&'a serde_json::value::RawValue>,
+    pub event_data: Option<GatewayEventData<'a>>,
 
     #[serde(rename = "s")]
     pub sequence_number: Option<u64>,
@@ -123,6 +133,82 @@ pub struct GatewayReceivePayload<'a> {
 
 impl<'a> WebSocketEvent for GatewayReceivePayload<'a> {}
 
+/// The name deserializers of other encodings than JSON are asked for event data with, see
+/// [GatewayEventData].
+pub(crate) const RAW_EVENT_DATA: &str = "$chorus::private::RawEventData";
+
+/// The still undeserialized event data of a [GatewayReceivePayload], borrowed from the received
+/// message in the encoding it was received in.
+///
+/// The data is only deserialized once the type of the event is known, see
+/// [GatewayEventData::parse].
+#[derive(Debug, Clone, Copy)]
+pub enum GatewayEventData<'a> {
+    Json(&'a serde_json::value::RawValue),
+    /// A term in Erlang's External Term Format, without the version byte
+    #[cfg(feature = "etf")]
+    Etf(&'a [u8]),
+}
+
+impl<'a> GatewayEventData<'a> {
+    /// Deserializes the event data as `T`.
+    pub fn parse<T: Deserialize<'a>>(&self) -> Result<T, GatewayError> {
+        match self {
+            GatewayEventData::Json(json) => {
+                serde_json::from_str(json.get()).map_err(|e| GatewayError::InvalidPayload {
+                    error: e.to_string(),
+                })
+            }
+            #[cfg(feature = "etf")]
+            GatewayEventData::Etf(term) => crate::gateway::ErlpackCodec::decode_term(term),
+        }
+    }
+
+    /// Returns the event data as JSON, converting it if it was received in another encoding.
+    pub fn json(&self) -> Result<Cow<'a, str>, GatewayError> {
+        match self {
+            GatewayEventData::Json(json) => Ok(Cow::Borrowed(json.get())),
+            #[cfg(feature = "etf")]
+            GatewayEventData::Etf(_) => {
+                Ok(Cow::Owned(self.parse::<serde_json::Value>()?.to_string()))
+            }
+        }
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for GatewayEventData<'a> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EventDataVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for EventDataVisitor {
+            type Value = GatewayEventData<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("event data")
+            }
+
+            // serde_json does not know our name, and hands us its deserializer instead
+            fn visit_newtype_struct<D: serde::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                <&serde_json::value::RawValue>::deserialize(deserializer)
+                    .map(GatewayEventData::Json)
+            }
+
+            #[cfg(feature = "etf")]
+            fn visit_borrowed_bytes<E: serde::de::Error>(
+                self,
+                term: &'de [u8],
+            ) -> Result<Self::Value, E> {
+                Ok(GatewayEventData::Etf(term))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(RAW_EVENT_DATA, EventDataVisitor)
+    }
+}
+
 #[cfg(feature = "client")]
 /// An [`UpdateMessage<T>`] represents a received Gateway Message which contains updated
 /// information for an [`Updateable`] of Type T.
@@ -4,7 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::{Activity, WebSocketEvent};
+use crate::types::{Activity, UserStatus, WebSocketEvent};
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 /// Officially Undocumented
@@ -14,16 +14,41 @@ pub struct SessionsReplace {
     pub sessions: Vec<Session>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
 /// Session info for the current user
 pub struct Session {
+    /// The activities of the user on this session
     pub activities: Option<Vec<Activity>>,
     pub client_info: ClientInfo,
+    /// The id of the session, or `all` for the session which summarizes all others
     pub session_id: String,
     pub status: String,
+    /// Whether the user is active on this session, only sent for the summarizing session
+    #[serde(default)]
+    pub active: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+impl Session {
+    /// The id of the session which summarizes the status and activities of all sessions.
+    pub const SUMMARY_ID: &'static str = "all";
+
+    /// Returns true if this is the session which summarizes all others, not an actual one.
+    pub fn is_summary(&self) -> bool {
+        self.session_id == Self::SUMMARY_ID
+    }
+
+    /// Returns the status of the session, if it is a known one.
+    pub fn user_status(&self) -> Option<UserStatus> {
+        serde_json::from_value(serde_json::Value::String(self.status.clone())).ok()
+    }
+
+    /// Returns the kind of device the session is on.
+    pub fn platform(&self) -> SessionPlatform {
+        self.client_info.platform()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
 /// Another Client info object
 /// {"client":"web","os":"other","version":0}
 // Note: I don't think this one exists yet? Though I might've made a mistake and this might be a duplicate
@@ -33,4 +58,26 @@ pub struct ClientInfo {
     pub version: u8,
 }
 
+impl ClientInfo {
+    /// Returns the kind of device the client runs on.
+    pub fn platform(&self) -> SessionPlatform {
+        match self.client.as_deref() {
+            Some("desktop") => SessionPlatform::Desktop,
+            Some("web") => SessionPlatform::Web,
+            Some("mobile") => SessionPlatform::Mobile,
+            _ => SessionPlatform::Unknown,
+        }
+    }
+}
+
+/// The kind of device a [`Session`] is on, used to show on which devices a user is active.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionPlatform {
+    Desktop,
+    Web,
+    Mobile,
+    #[default]
+    Unknown,
+}
+
 impl WebSocketEvent for SessionsReplace {}
@@ -2,39 +2,111 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::types::{Snowflake, WebSocketEvent};
+use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::types::{Snowflake, WebSocketEvent};
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Sent when other users connect to the voice server, and once after connecting with the users
+/// which are already connected.
+///
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#clients-connect>
+///
+/// {"op":11,"d":{"user_ids":["1234567890"]}}
+pub struct VoiceClientsConnect {
+    pub user_ids: Vec<Snowflake>,
+}
+
+impl WebSocketEvent for VoiceClientsConnect {}
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Copy)]
 /// Sent when another user connects to the voice server.
 ///
-/// Contains the user id and "flags".
+/// Contains the user id and their voice flags.
 ///
-/// Not documented anywhere, if you know what this is, please reach out
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#client-flags>
 ///
 /// {"op":18,"d":{"user_id":"1234567890","flags":2}}
 pub struct VoiceClientConnectFlags {
     pub user_id: Snowflake,
-    // Likely some sort of bitflags
-    //
-    // Not always sent, sometimes null?
+    /// The user's voice flags, see [VoiceFlags]
+    ///
+    /// Not always sent, sometimes null?
     pub flags: Option<u8>,
 }
 
+impl VoiceClientConnectFlags {
+    /// Returns the user's voice flags, ignoring unknown bits.
+    pub fn voice_flags(&self) -> VoiceFlags {
+        VoiceFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+}
+
 impl WebSocketEvent for VoiceClientConnectFlags {}
 
+bitflags! {
+    /// Flags of a user connected to a voice server;
+    ///
+    /// See <https://discord-userdoccers.vercel.app/topics/voice-connections#voice-flags>
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+    pub struct VoiceFlags: u8 {
+        /// Whether the user has clips enabled
+        const CLIPS_ENABLED = 1 << 0;
+        /// Whether the user allows their voice to be recorded by others
+        const ALLOW_VOICE_RECORDING = 1 << 1;
+        /// Whether the user allows anyone in the channel to clip their stream
+        const ALLOW_ANY_VIEWER_CLIPS = 1 << 2;
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Copy)]
 /// Sent when another user connects to the voice server.
 ///
-/// Contains the user id and "platform".
+/// Contains the user id and the platform they connected from.
 ///
-/// Not documented anywhere, if you know what this is, please reach out
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#client-platform>
 ///
 /// {"op":20,"d":{"user_id":"1234567890","platform":0}}
 pub struct VoiceClientConnectPlatform {
     pub user_id: Snowflake,
-    // Likely an enum
+    /// The platform the user connected from, see [VoicePlatform]
     pub platform: u8,
 }
 
+impl VoiceClientConnectPlatform {
+    /// Returns the platform the user connected from, or `None` if it is not known to chorus.
+    pub fn voice_platform(&self) -> Option<VoicePlatform> {
+        VoicePlatform::try_from(self.platform).ok()
+    }
+}
+
 impl WebSocketEvent for VoiceClientConnectPlatform {}
+
+#[derive(Debug, Default, Serialize_repr, Deserialize_repr, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+/// The platforms users can connect to voice servers from;
+///
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#voice-platform>
+pub enum VoicePlatform {
+    #[default]
+    Desktop = 0,
+    Mobile = 1,
+    Xbox = 2,
+    Playstation = 3,
+}
+
+impl TryFrom<u8> for VoicePlatform {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Desktop),
+            1 => Ok(Self::Mobile),
+            2 => Ok(Self::Xbox),
+            3 => Ok(Self::Playstation),
+            other => Err(other),
+        }
+    }
+}
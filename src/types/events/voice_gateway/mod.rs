@@ -11,6 +11,7 @@ pub use client_disconnect::*;
 pub use hello::*;
 pub use identify::*;
 pub use media_sink_wants::*;
+pub use raw::*;
 pub use ready::*;
 pub use select_protocol::*;
 pub use session_description::*;
@@ -23,6 +24,7 @@ mod client_disconnect;
 mod hello;
 mod identify;
 mod media_sink_wants;
+mod raw;
 mod ready;
 mod select_protocol;
 mod session_description;
@@ -64,8 +66,8 @@ impl<'a> WebSocketEvent for VoiceGatewayReceivePayload<'a> {}
 /// The modes of encryption available in voice UDP connections;
 ///
 /// Not all encryption modes are implemented; it is generally recommended
-/// to use either [[VoiceEncryptionMode::Xsalsa20Poly1305]] or
-/// [[VoiceEncryptionMode::Xsalsa20Poly1305Suffix]]
+/// to use either [[VoiceEncryptionMode::Xsalsa20Poly1305]],
+/// [[VoiceEncryptionMode::Xsalsa20Poly1305Suffix]] or [[VoiceEncryptionMode::AeadAes256Gcm]]
 ///
 /// See <https://discord-userdoccers.vercel.app/topics/voice-connections#encryption-mode> and <https://discord.com/developers/docs/topics/voice-connections#establishing-a-voice-udp-connection-encryption-modes>
 #[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -88,7 +90,10 @@ pub enum VoiceEncryptionMode {
     // Officially Undocumented
     /// Not implemented yet, we have no idea what the rtpsize nonces are.
     Xsalsa20Poly1305LiteRtpsize,
-    /// Not implemented yet, we have no idea what the nonce is.
+    /// Use AES256-GCM encryption, using a 4 byte incremental value as a nonce and the rtp header
+    /// as associated data.
+    ///
+    /// Fully implemented
     AeadAes256Gcm,
     /// Not implemented yet, we have no idea what the rtpsize nonces are.
     AeadAes256GcmRtpsize,
@@ -151,6 +156,7 @@ pub const VOICE_HEARTBEAT_ACK: u8 = 6;
 pub const VOICE_RESUME: u8 = 7;
 pub const VOICE_HELLO: u8 = 8;
 pub const VOICE_RESUMED: u8 = 9;
+pub const VOICE_CLIENTS_CONNECT: u8 = 11;
 pub const VOICE_SSRC_DEFINITION: u8 = 12;
 pub const VOICE_CLIENT_DISCONNECT: u8 = 13;
 pub const VOICE_SESSION_UPDATE: u8 = 14;
@@ -168,3 +174,23 @@ pub const VOICE_BACKEND_VERSION: u8 = 16;
 // These two get simultaenously fired when a user joins, one has flags and one has a platform
 pub const VOICE_CLIENT_CONNECT_FLAGS: u8 = 18;
 pub const VOICE_CLIENT_CONNECT_PLATFORM: u8 = 20;
+
+// The DAVE (end-to-end encryption) opcodes, which are received as raw events for now
+//
+// See <https://daveprotocol.com/#voice-gateway-opcodes>
+pub const VOICE_DAVE_PREPARE_TRANSITION: u8 = 21;
+pub const VOICE_DAVE_EXECUTE_TRANSITION: u8 = 22;
+pub const VOICE_DAVE_TRANSITION_READY: u8 = 23;
+pub const VOICE_DAVE_PREPARE_EPOCH: u8 = 24;
+pub const VOICE_DAVE_MLS_EXTERNAL_SENDER: u8 = 25;
+pub const VOICE_DAVE_MLS_KEY_PACKAGE: u8 = 26;
+pub const VOICE_DAVE_MLS_PROPOSALS: u8 = 27;
+pub const VOICE_DAVE_MLS_COMMIT_WELCOME: u8 = 28;
+pub const VOICE_DAVE_MLS_ANNOUNCE_COMMIT_TRANSITION: u8 = 29;
+pub const VOICE_DAVE_MLS_WELCOME: u8 = 30;
+pub const VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME: u8 = 31;
+
+/// Returns whether the opcode belongs to the DAVE (end-to-end encryption) protocol.
+pub fn is_dave_opcode(op_code: u8) -> bool {
+    (VOICE_DAVE_PREPARE_TRANSITION..=VOICE_DAVE_MLS_INVALID_COMMIT_WELCOME).contains(&op_code)
+}
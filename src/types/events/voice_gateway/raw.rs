@@ -0,0 +1,21 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::types::WebSocketEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+/// Not an actual voice gateway event; a received payload which chorus does not parse (yet),
+/// such as the DAVE (end-to-end encryption) opcodes or opcodes it does not know about.
+///
+/// Allows handling these payloads without waiting for chorus to support them.
+pub struct VoiceRawEvent {
+    #[serde(rename = "op")]
+    pub op_code: u8,
+    #[serde(rename = "d")]
+    pub data: Value,
+}
+
+impl WebSocketEvent for VoiceRawEvent {}
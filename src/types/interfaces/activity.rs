@@ -3,59 +3,280 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use serde::{Deserialize, Serialize};
+use serde_aux::prelude::deserialize_option_number_from_string;
 
-use crate::types::{entities::Emoji, Snowflake};
+use crate::types::Snowflake;
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+/// What a user is doing, shown in their presence.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/presence#activity-object>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Activity {
-    name: String,
+    /// The name of the game or application, or "Custom Status" for
+    /// [custom statuses](ActivityType::Custom)
+    pub name: String,
     #[serde(rename = "type")]
-    activity_type: i32,
-    url: Option<String>,
-    created_at: i64,
-    timestamps: Option<ActivityTimestamps>,
-    application_id: Option<Snowflake>,
-    details: Option<String>,
-    state: Option<String>,
-    emoji: Option<Emoji>,
-    party: Option<ActivityParty>,
-    assets: Option<ActivityAssets>,
-    secrets: Option<ActivitySecrets>,
-    instance: Option<bool>,
-    flags: Option<i32>,
-    buttons: Option<Vec<ActivityButton>>,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
-struct ActivityTimestamps {
-    start: Option<i64>,
-    end: Option<i64>,
+    pub activity_type: ActivityType,
+    /// The stream url, only for [`Streaming`](ActivityType::Streaming) activities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// When the activity was added to the session, in unix milliseconds. Set by the gateway
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    /// The id of the session the activity belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// The platform the activity is happening on, such as `desktop`, `xbox` or `ps5`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supported_platforms: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<ActivityTimestamps>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<Snowflake>,
+    /// What the user is currently doing, such as the song title of Spotify activities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The state of the user's party, or the text of [custom statuses](ActivityType::Custom)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// Only for [custom statuses](ActivityType::Custom)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<ActivityEmoji>,
+    /// The id of the track of Spotify activities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub party: Option<ActivityParty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assets: Option<ActivityAssets>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<ActivitySecrets>,
+    /// Whether the activity is an instanced game session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u64>,
+    /// At most 2 buttons, see [`ActivityButton`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buttons: Option<Vec<ActivityButton>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ActivityMetadata>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivityParty {
-    id: Option<String>,
-    size: Option<Vec<(i32, i32)>>,
+impl Activity {
+    pub fn new(activity_type: ActivityType, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            activity_type,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a custom status with the given text.
+    pub fn custom_status(state: impl Into<String>) -> Self {
+        Self {
+            state: Some(state.into()),
+            ..Activity::new(ActivityType::Custom, "Custom Status")
+        }
+    }
+
+    /// Returns the urls of the [`buttons`](Activity::buttons), which are only sent to the
+    /// application of the activity.
+    pub fn button_urls(&self) -> Vec<&str> {
+        let mut urls: Vec<&str> = self
+            .buttons
+            .iter()
+            .flatten()
+            .filter_map(|button| match button {
+                ActivityButton::Link { url, .. } => Some(url.as_str()),
+                ActivityButton::Label(_) => None,
+            })
+            .collect();
+        if let Some(button_urls) = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.button_urls.as_ref())
+        {
+            urls.extend(button_urls.iter().map(String::as_str));
+        }
+        urls
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivityAssets {
-    large_image: Option<String>,
-    large_text: Option<String>,
-    small_image: Option<String>,
-    small_text: Option<String>,
+/// What kind of [`Activity`] a user is doing, which decides how it is displayed.
+///
+/// Serialized as its integer value. Types unknown to chorus are kept as
+/// [`ActivityType::Unknown`].
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/presence#activity-type>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd)]
+pub enum ActivityType {
+    /// Playing {name}
+    #[default]
+    Playing,
+    /// Streaming {details}
+    Streaming,
+    /// Listening to {name}
+    Listening,
+    /// Watching {name}
+    Watching,
+    /// {emoji} {state}
+    Custom,
+    /// Competing in {name}
+    Competing,
+    /// Hanging out in a voice channel, with the hang status as {state}
+    Hang,
+    /// An activity type chorus does not know yet, with its integer value
+    Unknown(u8),
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivitySecrets {
-    join: Option<String>,
-    spectate: Option<String>,
-    #[serde(rename = "match")]
-    match_string: Option<String>,
+impl From<u8> for ActivityType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ActivityType::Playing,
+            1 => ActivityType::Streaming,
+            2 => ActivityType::Listening,
+            3 => ActivityType::Watching,
+            4 => ActivityType::Custom,
+            5 => ActivityType::Competing,
+            6 => ActivityType::Hang,
+            value => ActivityType::Unknown(value),
+        }
+    }
+}
+
+impl From<ActivityType> for u8 {
+    fn from(activity_type: ActivityType) -> Self {
+        match activity_type {
+            ActivityType::Playing => 0,
+            ActivityType::Streaming => 1,
+            ActivityType::Listening => 2,
+            ActivityType::Watching => 3,
+            ActivityType::Custom => 4,
+            ActivityType::Competing => 5,
+            ActivityType::Hang => 6,
+            ActivityType::Unknown(value) => value,
+        }
+    }
+}
+
+impl Serialize for ActivityType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8((*self).into())
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivityType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(u8::deserialize(deserializer)?.into())
+    }
+}
+
+/// Unix timestamps in milliseconds, shown as elapsed or remaining time.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct ActivityTimestamps {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_number_from_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start: Option<i64>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_number_from_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end: Option<i64>,
 }
 
+/// The emoji of a custom status, which is either a unicode or a custom emoji.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ActivityEmoji {
+    /// The unicode emoji, or the name of the custom emoji
+    pub name: String,
+    /// Only for custom emojis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Snowflake>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animated: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ActivityParty {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The current and maximum size of the party
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<[u32; 2]>,
+}
+
+/// Images shown for the activity, as asset ids of the application, or prefixed urls such as
+/// `spotify:{id}` or `mp:external/{path}`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ActivityAssets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_image: Option<String>,
+    /// Shown when hovering over the large image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub small_image: Option<String>,
+    /// Shown when hovering over the small image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub small_text: Option<String>,
+}
+
+/// Secrets for joining and spectating the activity's game, only sent to its application.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ActivitySecrets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spectate: Option<String>,
+    #[serde(rename = "match", default, skip_serializing_if = "Option::is_none")]
+    pub match_string: Option<String>,
+}
+
+/// A button shown in the activity.
+///
+/// Buttons are set with their urls, but only their labels are sent to other users.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
-struct ActivityButton {
-    label: String,
-    url: String,
+#[serde(untagged)]
+pub enum ActivityButton {
+    /// A received button, see [`ActivityMetadata::button_urls`]
+    Label(String),
+    Link {
+        label: String,
+        url: String,
+    },
+}
+
+impl ActivityButton {
+    pub fn label(&self) -> &str {
+        match self {
+            ActivityButton::Label(label) => label,
+            ActivityButton::Link { label, .. } => label,
+        }
+    }
+}
+
+/// Additional data of an activity.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ActivityMetadata {
+    /// The urls of the [`buttons`](Activity::buttons), only sent to the activity's application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub button_urls: Option<Vec<String>>,
+    /// The Spotify ids of the artists of the track
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist_ids: Option<Vec<String>>,
+    /// The Spotify id of the album of the track
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_id: Option<String>,
+    /// The Spotify uri of the playlist or album being played
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_uri: Option<String>,
 }
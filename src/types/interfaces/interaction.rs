@@ -2,15 +2,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::types::entities::{AllowedMention, Embed};
-use crate::types::utils::Snowflake;
+use crate::types::entities::{AllowedMention, ApplicationCommandOptionChoice, Embed, MessageFlags};
+use crate::types::utils::{Locale, Snowflake};
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Interaction {
     pub id: Snowflake,
+    #[serde(default)]
+    pub application_id: Snowflake,
     pub r#type: InteractionType,
     pub data: Value,
     pub guild_id: Snowflake,
@@ -18,17 +23,57 @@ pub struct Interaction {
     pub member_id: Snowflake,
     pub token: String,
     pub version: i32,
+    /// The selected language of the invoking user
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// The preferred locale of the guild the interaction was sent from
+    #[serde(default)]
+    pub guild_locale: Option<Locale>,
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+impl Interaction {
+    /// Returns the submitted modal, if this is a [modal submit interaction](InteractionType::ModalSubmit).
+    pub fn modal_submit_data(&self) -> Option<ModalSubmitInteractionData> {
+        if self.r#type != InteractionType::ModalSubmit {
+            return None;
+        }
+        serde_json::from_value(self.data.clone()).ok()
+    }
+
+    /// Returns the translation of a response for the invoking user, see [`Locale::localize`].
+    ///
+    /// Uses the user's [locale](Interaction::locale), or the
+    /// [guild's locale](Interaction::guild_locale) if the user's locale is unknown.
+    pub fn localize<'a, S: AsRef<str>>(
+        &self,
+        translations: &'a HashMap<Locale, S>,
+        default: &'a str,
+    ) -> &'a str {
+        match self.locale.as_ref().or(self.guild_locale.as_ref()) {
+            Some(locale) => locale.localize(translations, default),
+            None => default,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum InteractionType {
     #[default]
     SelfCommand = 0,
     Ping = 1,
     ApplicationCommand = 2,
+    /// Sent when a user uses a component of a message, such as a button
+    MessageComponent = 3,
+    /// Sent while the user is typing an option with
+    /// [`autocomplete`](crate::types::ApplicationCommandOption::autocomplete) enabled
+    ApplicationCommandAutocomplete = 4,
+    /// Sent when a user submits a [modal](InteractionResponse::modal), see
+    /// [`ModalSubmitInteractionData`]
+    ModalSubmit = 5,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
 pub enum InteractionResponseType {
     SelfCommandResponse = 0,
     Pong = 1,
@@ -36,6 +81,159 @@ pub enum InteractionResponseType {
     ChannelMessage = 3,
     ChannelMessageWithSource = 4,
     AcknowledgeWithSource = 5,
+    /// Acknowledges a [component interaction](InteractionType::MessageComponent), editing the
+    /// message later
+    DeferredUpdateMessage = 6,
+    /// Edits the message of a [component interaction](InteractionType::MessageComponent)
+    UpdateMessage = 7,
+    /// Responds to an [autocomplete interaction](InteractionType::ApplicationCommandAutocomplete)
+    /// with choices
+    ApplicationCommandAutocompleteResult = 8,
+    /// Shows a [modal](InteractionModalCallbackData) to the user
+    Modal = 9,
+}
+
+/// The type of an interaction response, named like in Discord's documentation.
+pub type InteractionCallbackType = InteractionResponseType;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-response-object>
+pub struct InteractionResponse {
+    #[serde(rename = "type")]
+    pub response_type: InteractionResponseType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<InteractionCallbackData>,
+}
+
+impl InteractionResponse {
+    /// Responds with a message.
+    pub fn message(data: InteractionApplicationCommandCallbackData) -> Self {
+        Self {
+            response_type: InteractionResponseType::ChannelMessageWithSource,
+            data: Some(InteractionCallbackData::Message(data)),
+        }
+    }
+
+    /// Edits the message of a [component interaction](InteractionType::MessageComponent).
+    pub fn update_message(data: InteractionApplicationCommandCallbackData) -> Self {
+        Self {
+            response_type: InteractionResponseType::UpdateMessage,
+            data: Some(InteractionCallbackData::Message(data)),
+        }
+    }
+
+    /// Acknowledges a [component interaction](InteractionType::MessageComponent), without
+    /// showing that the application is "thinking". The message can be edited later with
+    /// [`Interaction::edit_original_response`](crate::types::Interaction::edit_original_response).
+    pub fn deferred_update() -> Self {
+        Self {
+            response_type: InteractionResponseType::DeferredUpdateMessage,
+            data: None,
+        }
+    }
+
+    /// Shows a modal to the user. Modals can not be shown in response to
+    /// [autocomplete](InteractionType::ApplicationCommandAutocomplete) or
+    /// [modal submit](InteractionType::ModalSubmit) interactions.
+    pub fn modal(data: InteractionModalCallbackData) -> Self {
+        Self {
+            response_type: InteractionResponseType::Modal,
+            data: Some(InteractionCallbackData::Modal(data)),
+        }
+    }
+
+    /// Acknowledges the interaction, showing that the application is "thinking". The response
+    /// is sent later by [editing the original response](crate::types::Interaction::edit_original_response),
+    /// and is only visible to the invoking user if `ephemeral` is set.
+    pub fn deferred(ephemeral: bool) -> Self {
+        Self {
+            response_type: InteractionResponseType::AcknowledgeWithSource,
+            data: ephemeral.then(|| {
+                InteractionCallbackData::Deferred(InteractionDeferredCallbackData {
+                    flags: Some(MessageFlags::EPHEMERAL.bits()),
+                })
+            }),
+        }
+    }
+
+    /// Returns the flags of the response's data, ignoring unknown bits.
+    pub fn message_flags(&self) -> MessageFlags {
+        let flags = match &self.data {
+            Some(InteractionCallbackData::Message(data)) => data.flags,
+            Some(InteractionCallbackData::Deferred(data)) => data.flags,
+            _ => None,
+        };
+        MessageFlags::from_bits_truncate(flags.unwrap_or_default())
+    }
+
+    /// Returns why the response's flags can not be sent, if they can not.
+    ///
+    /// Only [`MessageFlags::INTERACTION_RESPONSE`] flags are allowed, and only on responses which
+    /// create a message: [`ChannelMessageWithSource`](InteractionResponseType::ChannelMessageWithSource)
+    /// and deferred [`AcknowledgeWithSource`](InteractionResponseType::AcknowledgeWithSource)
+    /// responses.
+    pub fn flags_error(&self) -> Option<String> {
+        let flags = match &self.data {
+            Some(InteractionCallbackData::Message(data)) => data.flags,
+            Some(InteractionCallbackData::Deferred(data)) => data.flags,
+            _ => None,
+        }
+        .unwrap_or_default();
+        if flags == 0 {
+            return None;
+        }
+        if !matches!(
+            self.response_type,
+            InteractionResponseType::ChannelMessageWithSource
+                | InteractionResponseType::AcknowledgeWithSource
+        ) {
+            return Some(format!(
+                "Flags can not be set on {:?} responses",
+                self.response_type
+            ));
+        }
+        if flags & !MessageFlags::INTERACTION_RESPONSE.bits() != 0 {
+            return Some(format!(
+                "Only the flags {:?} can be set on interaction responses",
+                MessageFlags::INTERACTION_RESPONSE
+            ));
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum InteractionCallbackData {
+    Message(InteractionApplicationCommandCallbackData),
+    Autocomplete(InteractionAutocompleteCallbackData),
+    Modal(InteractionModalCallbackData),
+    Deferred(InteractionDeferredCallbackData),
+}
+
+/// The data of an interaction response, named like in Discord's documentation.
+pub type InteractionResponseData = InteractionCallbackData;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// The data of a deferred [`AcknowledgeWithSource`](InteractionResponseType::AcknowledgeWithSource)
+/// response, see [`InteractionResponse::deferred`].
+pub struct InteractionDeferredCallbackData {
+    /// Only [`EPHEMERAL`](MessageFlags::EPHEMERAL) has an effect, making the final response only
+    /// visible to the invoking user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// The choices suggested for the [focused](crate::types::ApplicationCommandInteractionDataOption::focused)
+/// option of an autocomplete interaction.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-response-object-autocomplete>
+pub struct InteractionAutocompleteCallbackData {
+    /// At most 25 choices
+    pub choices: Vec<ApplicationCommandOptionChoice>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -44,4 +242,138 @@ pub struct InteractionApplicationCommandCallbackData {
     pub content: String,
     pub embeds: Vec<Embed>,
     pub allowed_mentions: AllowedMention,
+    /// Only [`MessageFlags::INTERACTION_RESPONSE`] flags can be set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u64>,
+}
+
+impl InteractionApplicationCommandCallbackData {
+    /// Returns the message's flags, ignoring unknown bits.
+    pub fn message_flags(&self) -> MessageFlags {
+        MessageFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+
+    /// Makes the message only visible to the user who invoked the interaction.
+    pub fn ephemeral(mut self) -> Self {
+        self.flags = Some((self.message_flags() | MessageFlags::EPHEMERAL).bits());
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// A form shown to the user, see [`InteractionResponse::modal`]. Submitting it sends a
+/// [modal submit interaction](InteractionType::ModalSubmit).
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-response-object-modal>
+pub struct InteractionModalCallbackData {
+    /// Sent back in the [`ModalSubmitInteractionData`]
+    pub custom_id: String,
+    /// At most 45 characters
+    pub title: String,
+    /// Between 1 and 5 rows
+    pub components: Vec<ModalActionRow>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+/// The types of the components of a modal.
+pub enum ModalComponentType {
+    #[default]
+    ActionRow = 1,
+    TextInput = 4,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// A row of a modal, holding a single text input.
+pub struct ModalActionRow {
+    #[serde(rename = "type")]
+    pub component_type: ModalComponentType,
+    pub components: Vec<TextInput>,
+}
+
+impl ModalActionRow {
+    pub fn new(text_input: TextInput) -> Self {
+        Self {
+            component_type: ModalComponentType::ActionRow,
+            components: vec![text_input],
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// A text field of a modal.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/message-components#text-inputs>
+pub struct TextInput {
+    #[serde(rename = "type")]
+    pub component_type: ModalComponentType,
+    /// Identifies the input in the [`ModalSubmitInteractionData`]
+    pub custom_id: String,
+    /// Not included in submitted inputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<TextInputStyle>,
+    /// At most 45 characters, not included in submitted inputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+    /// The prefilled text, or the text the user submitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+impl TextInput {
+    pub fn new(
+        custom_id: impl Into<String>,
+        style: TextInputStyle,
+        label: impl Into<String>,
+    ) -> Self {
+        Self {
+            component_type: ModalComponentType::TextInput,
+            custom_id: custom_id.into(),
+            style: Some(style),
+            label: Some(label.into()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum TextInputStyle {
+    /// A single line
+    #[default]
+    Short = 1,
+    /// Multiple lines
+    Paragraph = 2,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// The [`data`](Interaction::data) of a [modal submit interaction](InteractionType::ModalSubmit).
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-object-modal-submit-data-structure>
+pub struct ModalSubmitInteractionData {
+    /// The [`custom_id`](InteractionModalCallbackData::custom_id) of the modal
+    pub custom_id: String,
+    pub components: Vec<ModalActionRow>,
+}
+
+impl ModalSubmitInteractionData {
+    /// Returns the text submitted in the input with the given custom id.
+    pub fn value(&self, custom_id: &str) -> Option<&str> {
+        self.components
+            .iter()
+            .flat_map(|row| &row.components)
+            .find(|input| input.custom_id == custom_id)
+            .and_then(|input| input.value.as_deref())
+    }
 }
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    ApplicationCommandOption, ApplicationCommandPermission, ApplicationCommandType, Locale,
+};
+
+/// Registers an application command, or replaces the command with the same name and type.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/application-commands#create-global-application-command>
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ApplicationCommandCreateSchema {
+    /// 1 - 32 characters
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_localizations: Option<HashMap<Locale, String>>,
+    /// 1 - 100 characters for [`ChatInput`](ApplicationCommandType::ChatInput) commands, and
+    /// empty for [context menu](ApplicationCommandType::is_context_menu) commands
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_localizations: Option<HashMap<Locale, String>>,
+    /// Only for [`ChatInput`](ApplicationCommandType::ChatInput) commands
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<ApplicationCommandOption>,
+    /// Defaults to [`ChatInput`](ApplicationCommandType::ChatInput)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<ApplicationCommandType>,
+    /// The permissions members need to use the command by default, as a bitwise value string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_member_permissions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<bool>,
+}
+
+/// Modifies an application command. Only the set fields are changed.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/application-commands#edit-global-application-command>
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ApplicationCommandModifySchema {
+    /// 1 - 32 characters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_localizations: Option<HashMap<Locale, String>>,
+    /// 1 - 100 characters, only for [`ChatInput`](ApplicationCommandType::ChatInput) commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_localizations: Option<HashMap<Locale, String>>,
+    /// Only for [`ChatInput`](ApplicationCommandType::ChatInput) commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<ApplicationCommandOption>>,
+    /// The permissions members need to use the command by default, as a bitwise value string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_member_permissions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<bool>,
+}
+
+/// Replaces the permissions of an application command in a guild.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/application-commands#edit-application-command-permissions>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ApplicationCommandPermissionsModifySchema {
+    /// At most 100 permissions
+    pub permissions: Vec<ApplicationCommandPermission>,
+}
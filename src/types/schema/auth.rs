@@ -2,8 +2,96 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use chrono::{Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::types::{FieldFormatError, FieldFormatReason};
+
+/// The minimum length of a username in characters, as enforced by Discord.
+pub const USERNAME_MIN_LENGTH: usize = 2;
+/// The maximum length of a username in characters, as enforced by Discord.
+pub const USERNAME_MAX_LENGTH: usize = 32;
+/// The minimum length of a password in characters, as enforced by Discord.
+pub const PASSWORD_MIN_LENGTH: usize = 1;
+/// The maximum length of a password in characters, as enforced by Discord.
+pub const PASSWORD_MAX_LENGTH: usize = 72;
+
+/// The format of dates of birth, such as `2000-01-31`.
+pub const DATE_OF_BIRTH_FORMAT: &str = "%Y-%m-%d";
+
+/// Checks the length of a value against the given bounds, counting characters, not bytes.
+fn check_length(value: &str, min: usize, max: usize) -> Result<(), FieldFormatReason> {
+    let length = value.chars().count();
+    if length < min {
+        return Err(FieldFormatReason::TooShort { min });
+    }
+    if length > max {
+        return Err(FieldFormatReason::TooLong { max });
+    }
+    Ok(())
+}
+
+/// Checks, if a username satisfies Discord's requirements.
+///
+/// Other servers may have different limits, so a server might still reject a username, which
+/// passes this check.
+pub fn validate_username(username: &str) -> Result<(), FieldFormatError> {
+    check_length(username.trim(), USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH)
+        .map_err(FieldFormatError::Username)?;
+    if username
+        .chars()
+        .any(|c| c.is_control() || matches!(c, '@' | '#' | ':'))
+        || username.contains("```")
+    {
+        return Err(FieldFormatError::Username(
+            FieldFormatReason::InvalidCharacters,
+        ));
+    }
+    Ok(())
+}
+
+/// Checks, if a password satisfies Discord's requirements.
+///
+/// Other servers may have different limits, so a server might still reject a password, which
+/// passes this check.
+pub fn validate_password(password: &str) -> Result<(), FieldFormatError> {
+    check_length(password, PASSWORD_MIN_LENGTH, PASSWORD_MAX_LENGTH)
+        .map_err(FieldFormatError::Password)
+}
+
+/// Checks, if an email address is roughly in a valid format.
+pub fn validate_email(email: &str) -> Result<(), FieldFormatError> {
+    match email.split_once('@') {
+        Some((local, domain))
+            if !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace) =>
+        {
+            Ok(())
+        }
+        _ => Err(FieldFormatError::InvalidEmail),
+    }
+}
+
+/// Parses a date of birth in the [`DATE_OF_BIRTH_FORMAT`], checking that it is not in the future.
+pub fn validate_date_of_birth(date_of_birth: &str) -> Result<NaiveDate, FieldFormatError> {
+    match NaiveDate::parse_from_str(date_of_birth, DATE_OF_BIRTH_FORMAT) {
+        Ok(date) if date <= Utc::now().date_naive() => Ok(date),
+        _ => Err(FieldFormatError::InvalidDateOfBirth),
+    }
+}
+
+/// Returns the age in full years on `date` of someone born on `date_of_birth`.
+pub fn age_on(date_of_birth: NaiveDate, date: NaiveDate) -> u32 {
+    let mut years = date.year() - date_of_birth.year();
+    if (date.month(), date.day()) < (date_of_birth.month(), date_of_birth.day()) {
+        years -= 1;
+    }
+    years.max(0) as u32
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct RegisterSchema {
@@ -12,11 +100,62 @@ pub struct RegisterSchema {
     pub consent: bool,
     pub email: Option<String>,
     pub fingerprint: Option<String>,
+    /// The code of an invite to join after registering. Required on invite-only instances.
     pub invite: Option<String>,
+    /// The date of birth in the [`DATE_OF_BIRTH_FORMAT`]. Required by instances with an age
+    /// gate; see [`RegisterSchema::set_date_of_birth`].
     pub date_of_birth: Option<String>,
     pub gift_code_sku_id: Option<String>,
     pub captcha_key: Option<String>,
     pub promotional_email_opt_in: Option<bool>,
+    /// A Spacebar registration token. Registration tokens are single-use and bypass the
+    /// instance's registration restrictions, such as disabled registration or required invites.
+    ///
+    /// Not part of the request body; Spacebar reads it from the `Referer` header.
+    #[serde(skip)]
+    pub registration_token: Option<String>,
+}
+
+impl RegisterSchema {
+    /// Checks the fields of this schema against Discord's requirements, returning the first
+    /// invalid field.
+    pub fn validate(&self) -> Result<(), FieldFormatError> {
+        validate_username(&self.username)?;
+        if let Some(password) = &self.password {
+            validate_password(password)?;
+        }
+        if let Some(email) = &self.email {
+            validate_email(email)?;
+        }
+        if let Some(date_of_birth) = &self.date_of_birth {
+            validate_date_of_birth(date_of_birth)?;
+        }
+        if !self.consent {
+            return Err(FieldFormatError::ConsentRequired);
+        }
+        Ok(())
+    }
+
+    /// Sets the date of birth, formatting it as the server expects.
+    pub fn set_date_of_birth(&mut self, date_of_birth: NaiveDate) {
+        self.date_of_birth = Some(date_of_birth.format(DATE_OF_BIRTH_FORMAT).to_string());
+    }
+
+    /// Checks that the date of birth is set and at least `minimum_age` years ago, as required by
+    /// instances with an age gate.
+    ///
+    /// The minimum age of an instance is part of its
+    /// [`RegistrationRequirements`](crate::types::RegistrationRequirements).
+    pub fn validate_age(&self, minimum_age: u8) -> Result<(), FieldFormatError> {
+        let Some(date_of_birth) = &self.date_of_birth else {
+            return Err(FieldFormatError::DateOfBirthRequired);
+        };
+        let date_of_birth = validate_date_of_birth(date_of_birth)?;
+        if age_on(date_of_birth, Utc::now().date_naive()) < minimum_age as u32 {
+            return Err(FieldFormatError::Underage { minimum_age });
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -34,6 +173,15 @@ pub struct LoginSchema {
     pub gift_code_sku_id: Option<String>,
 }
 
+impl LoginSchema {
+    /// Checks the password of this schema against Discord's requirements.
+    ///
+    /// The login is not checked, since it may be a username, email address or phone number.
+    pub fn validate(&self) -> Result<(), FieldFormatError> {
+        validate_password(&self.password)
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct TotpSchema {
@@ -42,3 +190,51 @@ pub struct TotpSchema {
     gift_code_sku_id: Option<String>,
     login_source: Option<String>,
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/authentication#verify-user-email>
+pub struct VerifyEmailSchema {
+    /// The verification token sent to the user's email address
+    pub token: String,
+    pub captcha_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Requests an SMS with a verification code to be sent to a phone number.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user#add-phone-number>
+pub struct PhoneCodeRequestSchema {
+    /// The phone number, in E.164 format
+    pub phone: String,
+    pub change_phone_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/authentication#verify-phone-number>
+pub struct VerifyPhoneSchema {
+    /// The phone number, in E.164 format
+    pub phone: String,
+    /// The code received via SMS
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// The result of verifying a phone number.
+pub struct VerifyPhoneResult {
+    /// A token proving the ownership of the phone number, used in [AddPhoneSchema]
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user#add-phone-number>
+pub struct AddPhoneSchema {
+    /// The token received from verifying the phone number
+    pub phone_token: String,
+    /// The current password of the user
+    pub password: String,
+    pub change_phone_reason: Option<String>,
+}
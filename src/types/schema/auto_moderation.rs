@@ -0,0 +1,88 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    AutoModerationAction, AutoModerationRuleEventType, AutoModerationRuleTriggerMetadata,
+    AutoModerationRuleTriggerMetadataForKeyword, AutoModerationRuleTriggerType, Snowflake,
+};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// The schema used to create an auto moderation rule.
+///
+/// A guild can have up to 6 keyword rules and one rule of each other trigger type.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/auto-moderation#create-auto-moderation-rule>
+pub struct AutoModerationRuleCreateSchema {
+    pub name: String,
+    pub event_type: AutoModerationRuleEventType,
+    pub trigger_type: AutoModerationRuleTriggerType,
+    /// Required for keyword, keyword preset, mention spam and member profile rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_metadata: Option<AutoModerationRuleTriggerMetadata>,
+    pub actions: Vec<AutoModerationAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// At most 20 roles, whose members the rule does not apply to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exempt_roles: Option<Vec<Snowflake>>,
+    /// At most 50 channels, in which the rule does not apply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exempt_channels: Option<Vec<Snowflake>>,
+}
+
+impl AutoModerationRuleCreateSchema {
+    /// Creates the schema of an enabled rule, which takes the given actions when a message
+    /// contains one of the keywords.
+    ///
+    /// Keywords may use `*` as a wildcard at their start or end.
+    pub fn keyword(
+        name: impl Into<String>,
+        keyword_filter: Vec<String>,
+        actions: Vec<AutoModerationAction>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            event_type: AutoModerationRuleEventType::MessageSend,
+            trigger_type: AutoModerationRuleTriggerType::Keyword,
+            trigger_metadata: Some(AutoModerationRuleTriggerMetadata::ForKeyword(
+                AutoModerationRuleTriggerMetadataForKeyword {
+                    keyword_filter,
+                    regex_patterns: Vec::new(),
+                    allow_list: Vec::new(),
+                },
+            )),
+            actions,
+            enabled: Some(true),
+            exempt_roles: None,
+            exempt_channels: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// The schema used to modify an auto moderation rule. Fields which are [`None`] are not changed.
+///
+/// The trigger type of a rule cannot be changed.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/resources/auto-moderation#modify-auto-moderation-rule>
+pub struct AutoModerationRuleModifySchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<AutoModerationRuleEventType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_metadata: Option<AutoModerationRuleTriggerMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actions: Option<Vec<AutoModerationAction>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exempt_roles: Option<Vec<Snowflake>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exempt_channels: Option<Vec<Snowflake>>,
+}
@@ -3,10 +3,13 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use bitflags::bitflags;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::types::ChannelType;
-use crate::types::{entities::PermissionOverwrite, Snowflake};
+use crate::types::{
+    entities::PermissionOverwrite, Channel, ChannelFlags, ChannelType, DefaultReaction,
+    ForumLayoutType, MessageSendSchema, RtcRegion, Snowflake, SortOrderType, Tag, ThreadMember,
+};
 
 #[derive(Debug, Deserialize, Serialize, Default, PartialEq, PartialOrd)]
 #[serde(rename_all = "snake_case")]
@@ -24,12 +27,17 @@ pub struct ChannelCreateSchema {
     pub parent_id: Option<Snowflake>,
     pub id: Option<Snowflake>,
     pub nsfw: Option<bool>,
-    pub rtc_region: Option<String>,
+    /// Only sent if set, as [`RtcRegion::Automatic`] is sent as `null`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtc_region: Option<RtcRegion>,
     pub default_auto_archive_duration: Option<i32>,
-    pub default_reaction_emoji: Option<String>,
-    pub flags: Option<i32>,
+    pub default_reaction_emoji: Option<DefaultReaction>,
+    pub flags: Option<ChannelFlags>,
     pub default_thread_rate_limit_per_user: Option<i32>,
     pub video_quality_mode: Option<i32>,
+    pub default_sort_order: Option<SortOrderType>,
+    pub default_forum_layout: Option<ForumLayoutType>,
+    pub available_tags: Option<Vec<Tag>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, PartialOrd)]
@@ -46,20 +54,68 @@ pub struct ChannelModifySchema {
     pub permission_overwrites: Option<Vec<PermissionOverwrite>>,
     pub parent_id: Option<Snowflake>,
     pub nsfw: Option<bool>,
-    pub rtc_region: Option<String>,
+    /// Only sent if set, as [`RtcRegion::Automatic`] is sent as `null`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtc_region: Option<RtcRegion>,
     pub default_auto_archive_duration: Option<i32>,
-    pub default_reaction_emoji: Option<String>,
-    pub flags: Option<i32>,
+    pub default_reaction_emoji: Option<DefaultReaction>,
+    pub flags: Option<ChannelFlags>,
     pub default_thread_rate_limit_per_user: Option<i32>,
     pub video_quality_mode: Option<i32>,
+    pub default_sort_order: Option<SortOrderType>,
+    pub default_forum_layout: Option<ForumLayoutType>,
+    pub available_tags: Option<Vec<Tag>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+/// Query parameters for fetching messages of a channel.
+///
+/// Without an anchor, the most recent messages of the channel are returned.
 pub struct GetChannelMessagesSchema {
     /// Between 1 and 100, defaults to 50.
     pub limit: Option<i32>,
     #[serde(flatten)]
-    pub anchor: ChannelMessagesAnchor,
+    pub anchor: Option<ChannelMessagesAnchor>,
+}
+
+/// Bounds of the message history exported by
+/// [`Channel::export_history`](crate::types::Channel::export_history).
+///
+/// Both bounds are exclusive. Leaving a bound as `None` exports from the start of the channel or
+/// up to its latest message respectively.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MessageHistoryRange {
+    pub after: Option<Snowflake>,
+    pub before: Option<Snowflake>,
+}
+
+impl MessageHistoryRange {
+    /// The entire message history of a channel.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Only export messages sent after the given message.
+    pub fn after(self, after: Snowflake) -> Self {
+        Self {
+            after: Some(after),
+            ..self
+        }
+    }
+
+    /// Only export messages sent before the given message.
+    pub fn before(self, before: Snowflake) -> Self {
+        Self {
+            before: Some(before),
+            ..self
+        }
+    }
+
+    /// Returns true if the given message id lies within this range.
+    pub fn contains(&self, id: Snowflake) -> bool {
+        self.after.map_or(true, |after| id > after)
+            && self.before.map_or(true, |before| id < before)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
@@ -71,24 +127,32 @@ pub enum ChannelMessagesAnchor {
 }
 
 impl GetChannelMessagesSchema {
+    /// Fetches the most recent messages, without an anchor.
+    pub fn latest() -> Self {
+        Self {
+            limit: None,
+            anchor: None,
+        }
+    }
+
     pub fn before(anchor: Snowflake) -> Self {
         Self {
             limit: None,
-            anchor: ChannelMessagesAnchor::Before(anchor),
+            anchor: Some(ChannelMessagesAnchor::Before(anchor)),
         }
     }
 
     pub fn around(anchor: Snowflake) -> Self {
         Self {
             limit: None,
-            anchor: ChannelMessagesAnchor::Around(anchor),
+            anchor: Some(ChannelMessagesAnchor::Around(anchor)),
         }
     }
 
     pub fn after(anchor: Snowflake) -> Self {
         Self {
             limit: None,
-            anchor: ChannelMessagesAnchor::After(anchor),
+            anchor: Some(ChannelMessagesAnchor::After(anchor)),
         }
     }
 
@@ -162,3 +226,125 @@ pub struct ModifyChannelPositionsSchema {
     pub lock_permissions: Option<bool>,
     pub parent_id: Option<Snowflake>,
 }
+
+/// Creates a thread in a forum or media channel, along with its first message.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#create-thread>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ForumThreadCreateSchema {
+    /// The title of the post, 1 - 100 characters
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_archive_duration: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_user: Option<i32>,
+    /// The ids of the parent channel's tags to apply, at most 5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_tags: Option<Vec<Snowflake>>,
+    pub message: MessageSendSchema,
+}
+
+/// Creates a thread from an existing message.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#create-thread-from-message>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct StartThreadFromMessageSchema {
+    /// 1 - 100 characters
+    pub name: String,
+    /// After how many minutes of inactivity the thread is archived: 60, 1440, 4320 or 10080
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_archive_duration: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_user: Option<i32>,
+}
+
+/// Creates a thread which does not start from an existing message.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#create-thread>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct StartThreadWithoutMessageSchema {
+    /// 1 - 100 characters
+    pub name: String,
+    /// After how many minutes of inactivity the thread is archived: 60, 1440, 4320 or 10080
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_archive_duration: Option<u32>,
+    /// [`ChannelType::GuildPrivateThread`] by default
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub thread_type: Option<ChannelType>,
+    /// Whether members without the
+    /// [`MANAGE_THREADS`](crate::types::PermissionFlags::MANAGE_THREADS) permission can invite
+    /// others to a private thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invitable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_user: Option<i32>,
+}
+
+/// Query parameters for listing the members of a thread.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#get-thread-members>
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GetThreadMembersSchema {
+    /// Whether to include the guild member of every thread member
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with_member: Option<bool>,
+    /// Only returns thread members with a user id after this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Snowflake>,
+    /// Between 1 and 100, defaults to 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+}
+
+/// Query parameters for listing the archived threads of a channel, newest first.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/channel#get-public-archived-threads>
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GetArchivedThreadsSchema {
+    /// Only returns threads archived before this time, see [`ArchivedThreads::next_page`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<DateTime<Utc>>,
+    /// Between 2 and 100, defaults to 50
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+}
+
+/// A page of archived threads.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ArchivedThreads {
+    pub threads: Vec<Channel>,
+    /// The thread members of the current user, for the threads they have joined
+    #[serde(default)]
+    pub members: Vec<ThreadMember>,
+    /// Whether there are more archived threads
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl ArchivedThreads {
+    /// Returns the query for the next page, which starts after the oldest thread of this page,
+    /// or `None` if this is the last page.
+    pub fn next_page(&self, limit: Option<u8>) -> Option<GetArchivedThreadsSchema> {
+        if !self.has_more {
+            return None;
+        }
+        let before = self
+            .threads
+            .iter()
+            .filter_map(|thread| {
+                let archived_at = &thread.thread_metadata.as_ref()?.archive_timestamp;
+                DateTime::parse_from_rfc3339(archived_at).ok()
+            })
+            .min()?
+            .with_timezone(&Utc);
+        Some(GetArchivedThreadsSchema {
+            before: Some(before),
+            limit,
+        })
+    }
+}
@@ -0,0 +1,34 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+/// How a bot should connect to the gateway.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway#get-gateway-bot>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct GatewayBotResponse {
+    pub url: String,
+    /// The recommended amount of shards to connect with
+    pub shards: u64,
+    pub session_start_limit: SessionStartLimit,
+}
+
+/// How many sessions a bot can still start.
+///
+/// # Reference
+/// See <https://docs.discord.sex/topics/gateway#session-start-limit-structure>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct SessionStartLimit {
+    /// How many sessions can be started per day
+    pub total: u32,
+    /// How many sessions can still be started today
+    pub remaining: u32,
+    /// In how many milliseconds the limit resets
+    pub reset_after: u64,
+    /// How many shards can identify at the same time, see
+    /// [`IdentifyQueue`](crate::gateway::IdentifyQueue)
+    pub max_concurrency: u64,
+}
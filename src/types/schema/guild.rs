@@ -9,8 +9,8 @@ use serde::{Deserialize, Serialize};
 use crate::types::entities::Channel;
 use crate::types::types::guild_configuration::GuildFeatures;
 use crate::types::{
-    Emoji, ExplicitContentFilterLevel, MessageNotificationLevel, Snowflake, Sticker,
-    SystemChannelFlags, VerificationLevel,
+    Emoji, ExplicitContentFilterLevel, GuildJoinRequestStatus, MessageNotificationLevel, Snowflake,
+    Sticker, SystemChannelFlags, ThemeColors, VerificationLevel,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -69,11 +69,36 @@ pub struct GuildModifySchema {
     pub premium_progress_bar_enabled: Option<bool>,
 }
 
+/// Represents the schema used to toggle the security actions of a guild, for example to lock it
+/// down during a raid.
+///
+/// Both actions are sent on every request: a `None` value re-enables invites or direct messages
+/// respectively. The timestamps may be at most 24 hours in the future.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#modify-guild-incident-actions>
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, Eq, PartialEq)]
+pub struct GuildIncidentActionsSchema {
+    /// When invites to the guild will be enabled again
+    pub invites_disabled_until: Option<DateTime<Utc>>,
+    /// When direct messages between guild members will be enabled again
+    pub dms_disabled_until: Option<DateTime<Utc>>,
+}
+
+/// Query parameters of [`ChorusUser::get_guilds`](crate::instance::ChorusUser::get_guilds).
 #[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct GetUserGuildSchema {
+    /// Only returns guilds with an id before this one
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub before: Option<Snowflake>,
+    /// Only returns guilds with an id after this one
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub after: Option<Snowflake>,
+    /// How many guilds to return, between 1 and 200
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u8>,
+    /// Whether to include the approximate member and presence counts of each guild
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub with_counts: Option<bool>,
 }
 
@@ -119,15 +144,44 @@ impl Default for GuildMemberSearchSchema {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct ModifyGuildMemberSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nick: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<Vec<Snowflake>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mute: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deaf: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub channel_id: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub communication_disabled_until: Option<DateTime<Utc>>,
-    pub flags: Option<GuildMemberFlags>,
+    /// Only [`GuildMemberFlags::SETTABLE`] flags can be set, see
+    /// [`ModifyGuildMemberSchema::bypass_verification`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u64>,
+}
+
+impl ModifyGuildMemberSchema {
+    /// Returns the member's flags, ignoring unknown bits.
+    pub fn member_flags(&self) -> GuildMemberFlags {
+        GuildMemberFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+
+    /// Sets whether the member can talk in the guild without meeting its verification
+    /// requirements.
+    ///
+    /// Requires the [`MANAGE_GUILD`](crate::types::PermissionFlags::MANAGE_GUILD),
+    /// [`MANAGE_ROLES`](crate::types::PermissionFlags::MANAGE_ROLES) or
+    /// [`MODERATE_MEMBERS`](crate::types::PermissionFlags::MODERATE_MEMBERS) permission.
+    pub fn bypass_verification(mut self, bypass: bool) -> Self {
+        let mut flags = self.member_flags();
+        flags.set(GuildMemberFlags::BYPASSES_VERIFICATION, bypass);
+        self.flags = Some(flags.bits());
+        self
+    }
 }
 
 bitflags! {
@@ -137,16 +191,28 @@ bitflags! {
     /// # Reference:
     /// See <https://discord-userdoccers.vercel.app/resources/guild#guild-member-flags>
     pub struct GuildMemberFlags: u64 {
+        /// The member has left and rejoined the guild
         const DID_REJOIN = 1 << 0;
+        /// The member has completed onboarding
         const COMPLETED_ONBOARDING = 1 << 1;
+        /// The member can talk in the guild without meeting its verification requirements
         const BYPASSES_VERIFICATION = 1 << 2;
+        /// The member has started onboarding
         const STARTED_ONBOARDING = 1 << 3;
-        const GUEST = 1 << 3;
+        /// The member is a guest and can only access the voice channel they were invited to
+        const GUEST = 1 << 4;
+        /// The member's name was blocked by automod
         const AUTOMOD_QUARANTINED_NAME = 1 << 7;
+        /// The member's bio was blocked by automod
         const AUTOMOD_QUARANTINED_BIO = 1 << 8;
     }
 }
 
+impl GuildMemberFlags {
+    /// The flags which can be set when modifying a guild member.
+    pub const SETTABLE: GuildMemberFlags = GuildMemberFlags::BYPASSES_VERIFICATION;
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct ModifyCurrentGuildMemberSchema {
     pub nick: Option<String>,
@@ -160,8 +226,12 @@ pub struct ModifyGuildMemberProfileSchema {
     pub pronouns: Option<String>,
     pub bio: Option<String>,
     pub banner: Option<String>,
-    pub accent_color: Option<String>,
-    pub theme_colors: Option<Vec<i32>>,
+    pub accent_color: Option<u32>,
+    /// Only sent if set, as `null` resets the guild profile's theme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme_colors: Option<ThemeColors>,
+    /// Only sent if set, as `null` resets the guild profile's effect.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub popout_animation_particle_type: Option<Snowflake>,
     pub emoji_id: Option<Snowflake>,
 }
@@ -173,3 +243,55 @@ pub struct GuildBansQuery {
     pub after: Option<Snowflake>,
     pub limit: Option<u16>,
 }
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Filters for listing a guild's audit log entries.
+///
+/// The limit argument is a number between 1 and 100.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/audit-log#get-guild-audit-log>
+pub struct GuildAuditLogsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+    /// Only returns entries of actions taken by this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Snowflake>,
+    /// Only returns entries of this [action type](crate::types::AuditLogEntry::action_type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_type: Option<u8>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Filters for listing a guild's join requests.
+///
+/// The limit argument is a number between 1 and 100.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#get-guild-join-requests>
+pub struct GuildJoinRequestsQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<GuildJoinRequestStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+/// Approves or rejects a guild join request.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/guild#action-guild-join-request>
+pub struct GuildJoinRequestActionSchema {
+    /// Either [`GuildJoinRequestStatus::Approved`] or [`GuildJoinRequestStatus::Rejected`].
+    pub action: GuildJoinRequestStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejection_reason: Option<String>,
+}
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AllowedMention, Component, Embed, MessageFlags};
+
+/// Edits the original response to an interaction, such as to send the final response to a
+/// [deferred](crate::types::InteractionResponse::deferred) interaction.
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/interactions/receiving-and-responding#edit-original-interaction-response>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct InteractionResponseModifySchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<Embed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_mentions: Option<AllowedMention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<Component>>,
+    /// Only [`SUPPRESS_EMBEDS`](MessageFlags::SUPPRESS_EMBEDS) can be set. Whether the response
+    /// is [`EPHEMERAL`](MessageFlags::EPHEMERAL) is decided when responding, and can not be
+    /// changed by editing it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u64>,
+}
+
+impl InteractionResponseModifySchema {
+    /// Returns why the flags can not be set on the response, if they can not.
+    pub fn flags_error(&self) -> Option<String> {
+        let flags = self.flags.unwrap_or_default();
+        if flags & MessageFlags::EPHEMERAL.bits() != 0 {
+            return Some(
+                "Responses can only be made ephemeral when responding, not when editing them"
+                    .to_string(),
+            );
+        }
+        if flags & !MessageFlags::SUPPRESS_EMBEDS.bits() != 0 {
+            return Some(format!(
+                "Only the flags {:?} can be set when editing a response",
+                MessageFlags::SUPPRESS_EMBEDS
+            ));
+        }
+        None
+    }
+}
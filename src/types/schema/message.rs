@@ -5,17 +5,26 @@
 use serde::{Deserialize, Serialize};
 
 use crate::types::entities::{
-    AllowedMention, Component, Embed, MessageReference, PartialDiscordFileAttachment,
+    AllowedMention, Component, Embed, MessageFlags, MessageReference, PartialDiscordFileAttachment,
 };
-use crate::types::{Attachment, Snowflake};
+use crate::types::{Attachment, MessageType, Snowflake};
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub struct MessageSendSchema {
     #[serde(rename = "type")]
-    pub message_type: Option<i32>,
+    pub message_type: Option<MessageType>,
     pub content: Option<String>,
+    /// Used to correlate the sent message with its `MESSAGE_CREATE` dispatch, see
+    /// [`MessageCreate::matches_nonce`](crate::types::MessageCreate::matches_nonce).
+    ///
+    /// [`Message::send`](crate::types::Message::send) generates one if it is not set.
     pub nonce: Option<String>,
+    /// If true, the server deduplicates messages with the same nonce which are sent within a few
+    /// minutes, returning the already created message instead of sending it again.
+    pub enforce_nonce: Option<bool>,
+    /// Whether the message is read out to users viewing the channel. Requires the
+    /// [`SEND_TTS_MESSAGES`](crate::types::PermissionFlags::SEND_TTS_MESSAGES) permission.
     pub tts: Option<bool>,
     pub embeds: Option<Vec<Embed>>,
     pub allowed_mentions: Option<AllowedMention>,
@@ -23,6 +32,177 @@ pub struct MessageSendSchema {
     pub components: Option<Vec<Component>>,
     pub sticker_ids: Option<Vec<String>>,
     pub attachments: Option<Vec<PartialDiscordFileAttachment>>,
+    /// Only [`MessageFlags::SENDABLE`] flags can be set.
+    pub flags: Option<u64>,
+}
+
+impl MessageSendSchema {
+    /// Returns the message's flags, ignoring unknown bits.
+    pub fn message_flags(&self) -> MessageFlags {
+        MessageFlags::from_bits_truncate(self.flags.unwrap_or_default())
+    }
+
+    /// Sends the message without triggering push and desktop notifications, like `@silent`
+    /// messages in official clients.
+    pub fn suppress_notifications(mut self) -> Self {
+        self.flags = Some((self.message_flags() | MessageFlags::SUPPRESS_NOTIFICATIONS).bits());
+        self
+    }
+
+    /// Returns why the message's flags can not be sent, if they can not.
+    ///
+    /// Only [`MessageFlags::SENDABLE`] flags are allowed.
+    pub fn flags_error(&self) -> Option<String> {
+        if self.flags.unwrap_or_default() & !MessageFlags::SENDABLE.bits() != 0 {
+            return Some(format!(
+                "Only the flags {:?} can be set when sending a message",
+                MessageFlags::SENDABLE
+            ));
+        }
+        None
+    }
+
+    /// Returns the first filename which an embed image or thumbnail refers to with an
+    /// `attachment://` url (see [`EmbedImage::attachment`](crate::types::EmbedImage::attachment)),
+    /// but which is not among the message's attachments.
+    pub fn unknown_attachment_reference(&self) -> Option<&str> {
+        unknown_attachment_reference(&self.embeds, &self.attachments)
+    }
+
+    /// Creates a message which only consists of stickers, the way official clients send
+    /// sticker-only messages on user accounts.
+    pub fn stickers(sticker_ids: Vec<Snowflake>) -> Self {
+        Self {
+            sticker_ids: Some(sticker_ids.iter().map(|id| id.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the nonce to a newly generated, snowflake-like value if none has been set yet.
+    /// Returns the message's nonce.
+    pub fn ensure_nonce(&mut self) -> &str {
+        self.nonce
+            .get_or_insert_with(|| Snowflake::generate().to_string())
+    }
+
+    /// Splits a message whose content is longer than `max_length` characters into multiple
+    /// messages, which can be sent one after another.
+    ///
+    /// The content is split at line breaks where possible, otherwise at whitespace. Code blocks
+    /// which span multiple parts are closed at the end of one part and reopened, with the same
+    /// language, at the start of the next one.
+    ///
+    /// The first part keeps the message's reply reference, while embeds, components, stickers and
+    /// attachments are sent with the last part. Every part has its own nonce.
+    pub fn split_content(&self, max_length: usize) -> Vec<MessageSendSchema> {
+        let content = match &self.content {
+            Some(content) if content.chars().count() > max_length => content,
+            _ => return vec![self.clone()],
+        };
+        let parts = split_content(content, max_length);
+        let last = parts.len() - 1;
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| {
+                let mut part = MessageSendSchema {
+                    message_type: self.message_type,
+                    content: Some(content),
+                    tts: self.tts,
+                    flags: self.flags,
+                    allowed_mentions: self.allowed_mentions.clone(),
+                    ..Default::default()
+                };
+                if index == 0 {
+                    part.message_reference = self.message_reference.clone();
+                }
+                if index == last {
+                    part.embeds = self.embeds.clone();
+                    part.components = self.components.clone();
+                    part.sticker_ids = self.sticker_ids.clone();
+                    part.attachments = self.attachments.clone();
+                }
+                part
+            })
+            .collect()
+    }
+}
+
+/// The characters which open and close a code block.
+const CODE_FENCE: &str = "```";
+
+/// Splits text into parts of at most `max_length` characters, see
+/// [`MessageSendSchema::split_content`].
+fn split_content(content: &str, max_length: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = content;
+    // The opening line of the code block the previous part ended in, like "```rust"
+    let mut open_fence: Option<String> = None;
+    loop {
+        let prefix = open_fence
+            .as_ref()
+            .map(|fence| format!("{}\n", fence))
+            .unwrap_or_default();
+        let prefix_length = prefix.chars().count();
+        if prefix_length + rest.chars().count() <= max_length {
+            parts.push(prefix + rest);
+            return parts;
+        }
+
+        let char_end = |count: usize| {
+            rest.char_indices()
+                .nth(count)
+                .map_or(rest.len(), |(index, _)| index)
+        };
+        // Leave room for closing a code block at the end of the part, if there might be one
+        let available = max_length.saturating_sub(prefix_length);
+        let fence_room = if open_fence.is_some() || rest[..char_end(available)].contains(CODE_FENCE)
+        {
+            CODE_FENCE.len() + 1
+        } else {
+            0
+        };
+        let budget = available.saturating_sub(fence_room).max(1);
+        let end = char_end(budget);
+        // A separator right after the budget still allows splitting there
+        let window = &rest[..char_end(budget + 1)];
+        let (chunk, remainder) = match window
+            .rfind('\n')
+            .or_else(|| window.rfind(char::is_whitespace))
+        {
+            Some(index) if index > 0 => {
+                let separator = window[index..].chars().next().map_or(1, char::len_utf8);
+                (&rest[..index], &rest[index + separator..])
+            }
+            _ => rest.split_at(end),
+        };
+        rest = remainder;
+
+        for line in chunk.lines() {
+            let line = line.trim_start();
+            if line.matches(CODE_FENCE).count() % 2 == 1 {
+                open_fence = match open_fence {
+                    Some(_) => None,
+                    None => Some(
+                        match line
+                            .rsplit(CODE_FENCE)
+                            .next()
+                            .and_then(|info| info.split_whitespace().next())
+                        {
+                            Some(language) => format!("{}{}", CODE_FENCE, language),
+                            None => CODE_FENCE.to_string(),
+                        },
+                    ),
+                };
+            }
+        }
+        let mut part = prefix + chunk;
+        if open_fence.is_some() {
+            part.push('\n');
+            part.push_str(CODE_FENCE);
+        }
+        parts.push(part);
+    }
 }
 
 #[derive(Debug)]
@@ -103,12 +283,26 @@ impl std::default::Default for MessageSearchQuery {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// Represents a greet message, which consists of up to 1 sticker.
+///
+/// # Reference:
+/// See <https://discord-userdoccers.vercel.app/resources/message#create-greet-message>
 pub struct CreateGreetMessage {
     pub sticker_ids: Vec<Snowflake>,
     pub allowed_mentions: Option<AllowedMention>,
     pub message_reference: Option<MessageReference>,
 }
 
+impl CreateGreetMessage {
+    pub fn new(sticker_ids: Vec<Snowflake>) -> Self {
+        Self {
+            sticker_ids,
+            allowed_mentions: None,
+            message_reference: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MessageAck {
     pub token: Option<String>,
@@ -128,3 +322,21 @@ pub struct MessageModifySchema {
     payload_json: Option<String>,
     attachments: Option<Vec<Attachment>>,
 }
+
+/// Returns the first filename which an embed refers to with an `attachment://` url, but which is
+/// not among the attachments.
+pub(crate) fn unknown_attachment_reference<'a>(
+    embeds: &'a Option<Vec<Embed>>,
+    attachments: &Option<Vec<PartialDiscordFileAttachment>>,
+) -> Option<&'a str> {
+    embeds
+        .iter()
+        .flatten()
+        .flat_map(|embed| embed.attachment_references())
+        .find(|filename| {
+            !attachments
+                .iter()
+                .flatten()
+                .any(|attachment| attachment.filename == *filename)
+        })
+}
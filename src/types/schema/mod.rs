@@ -3,19 +3,37 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 pub use apierror::*;
+pub use application_command::*;
 pub use auth::*;
+pub use auto_moderation::*;
 pub use channel::*;
+pub use gateway::*;
 pub use guild::*;
+pub use interaction::*;
 pub use message::*;
+pub use oauth2::*;
 pub use relationship::*;
+pub use report::*;
 pub use role::*;
+pub use stage_instance::*;
 pub use user::*;
+pub use voice_state::*;
+pub use webhook::*;
 
 mod apierror;
+mod application_command;
 mod auth;
+mod auto_moderation;
 mod channel;
+mod gateway;
 mod guild;
+mod interaction;
 mod message;
+mod oauth2;
 mod relationship;
+mod report;
 mod role;
+mod stage_instance;
 mod user;
+mod voice_state;
+mod webhook;
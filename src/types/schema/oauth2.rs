@@ -0,0 +1,36 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Snowflake;
+
+/// Authorizes an application for the scopes given in the query of the request, for example to add
+/// a bot to a guild.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/topics/oauth2#authorize>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct ApplicationAuthorizeSchema {
+    /// Whether the user authorizes the application, or denies the authorization
+    pub authorize: bool,
+    /// The guild to add the bot to, for the `bot` scope
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<Snowflake>,
+    /// The permissions to grant the bot in the guild; has to be a subset of the permissions
+    /// requested in the query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<String>,
+    /// The channel to create a webhook in, for the `webhook.incoming` scope
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_channel_id: Option<Snowflake>,
+}
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/topics/oauth2#authorize>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct ApplicationAuthorizeResponse {
+    /// Where the user is sent after the authorization, such as the application's redirect url
+    pub location: String,
+}
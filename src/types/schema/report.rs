@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ReportMenu, Snowflake};
+
+/// A schema used to submit a report, after walking through a [`ReportMenu`].
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/report#create-report>
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSchema {
+    pub version: String,
+    pub variant: String,
+    pub language: String,
+    /// The name of the menu
+    pub name: String,
+    /// The ids of the nodes visited, starting with the root node
+    pub breadcrumbs: Vec<u64>,
+    /// The values selected in the elements of the visited nodes, by element name
+    pub elements: HashMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_id: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Snowflake>,
+}
+
+impl ReportSchema {
+    /// Creates a report for the given menu, with the ids of the visited nodes. See
+    /// [`ReportMenu::path_to`].
+    pub fn new(menu: &ReportMenu, breadcrumbs: Vec<u64>) -> Self {
+        Self {
+            version: menu.version.clone(),
+            variant: menu.variant.clone(),
+            language: menu.language.clone(),
+            name: menu.name.clone(),
+            breadcrumbs,
+            ..Default::default()
+        }
+    }
+
+    /// Adds the values selected in an element.
+    pub fn element(mut self, name: &str, values: Vec<String>) -> Self {
+        self.elements.insert(name.to_string(), values);
+        self
+    }
+
+    /// Reports a message.
+    pub fn message(mut self, channel_id: Snowflake, message_id: Snowflake) -> Self {
+        self.channel_id = Some(channel_id);
+        self.message_id = Some(message_id);
+        self
+    }
+
+    /// Reports a user, optionally in the context of a guild.
+    pub fn user(mut self, user_id: Snowflake, guild_id: Option<Snowflake>) -> Self {
+        self.user_id = Some(user_id);
+        self.guild_id = guild_id;
+        self
+    }
+
+    /// Reports a guild.
+    pub fn guild(mut self, guild_id: Snowflake) -> Self {
+        self.guild_id = Some(guild_id);
+        self
+    }
+}
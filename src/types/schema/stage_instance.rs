@@ -0,0 +1,34 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Snowflake, StageInstancePrivacyLevel};
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/stage-instance#create-stage-instance>
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct StageInstanceCreateSchema {
+    pub channel_id: Snowflake,
+    /// 1 - 120 characters
+    pub topic: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy_level: Option<StageInstancePrivacyLevel>,
+    /// Whether to notify the guild's members that the stage started, requires the
+    /// [`MENTION_EVERYONE`](crate::types::PermissionFlags::MENTION_EVERYONE) permission
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_start_notification: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guild_scheduled_event_id: Option<Snowflake>,
+}
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/stage-instance#modify-stage-instance>
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct StageInstanceModifySchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy_level: Option<StageInstancePrivacyLevel>,
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::Snowflake;
+use crate::types::{MuteConfig, Snowflake, ThemeColors, UserNotificationLevel};
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -22,6 +22,34 @@ pub struct UserModifySchema {
     pub code: Option<String>,
     pub email: Option<String>,
     pub discriminator: Option<i16>,
+    /// Only sent if set, as `null` resets the profile theme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme_colors: Option<ThemeColors>,
+    /// Only sent if set, as `null` resets the profile effect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub popout_animation_particle_type: Option<Snowflake>,
+}
+
+/// A schema used to choose the guild whose tag the current user displays.
+///
+/// Setting `identity_guild_id` to `None` clears the user's primary guild.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user#modify-user-primary-guild>
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PrimaryGuildModifySchema {
+    pub identity_guild_id: Option<Snowflake>,
+    pub identity_enabled: Option<bool>,
+}
+
+/// Query parameters for searching all users of an instance, see
+/// [`ChorusUser::search_users`](crate::instance::ChorusUser::search_users).
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct UserSearchQuery {
+    /// Matched against usernames
+    pub query: String,
+    /// The maximum amount of users to return
+    pub limit: Option<u16>,
 }
 
 /// A schema used to create a private channel.
@@ -39,3 +67,51 @@ pub struct PrivateChannelCreateSchema {
     pub access_tokens: Option<Vec<String>>,
     pub nicks: Option<HashMap<Snowflake, String>>,
 }
+
+/// A schema used to modify the current user's notification settings of a guild. Only the set
+/// fields are changed.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/user-settings#modify-user-guild-settings>
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct UserGuildSettingsModifySchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub muted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_config: Option<MuteConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_everyone: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_roles: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_notifications: Option<UserNotificationLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mobile_push: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_muted_channels: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_scheduled_events: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_highlights: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<i32>,
+    /// The settings of channels to change, by channel id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_overrides: Option<HashMap<Snowflake, UserGuildSettingsChannelOverrideModifySchema>>,
+}
+
+/// The changes to the current user's settings of a channel, see
+/// [`UserGuildSettingsModifySchema::channel_overrides`].
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq, Eq)]
+pub struct UserGuildSettingsChannelOverrideModifySchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub muted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mute_config: Option<MuteConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_notifications: Option<UserNotificationLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapsed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<i32>,
+}
@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::Snowflake;
+
+/// Modifies the current user's voice state in a stage channel.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/voice#modify-current-user-voice-state>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct VoiceStateModifyCurrentUserSchema {
+    /// The stage channel the user is connected to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<Snowflake>,
+    /// Whether the user is in the audience, rather than a speaker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress: Option<bool>,
+    /// Requests to speak ("raises the hand") when set to a time, withdraws the request when set
+    /// to `Some(None)`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_with::rust::double_option"
+    )]
+    pub request_to_speak_timestamp: Option<Option<DateTime<Utc>>>,
+}
+
+/// Modifies another user's voice state in a stage channel.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/voice#modify-user-voice-state>
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct VoiceStateModifySchema {
+    /// The stage channel the user is connected to
+    pub channel_id: Snowflake,
+    /// Whether the user is in the audience, rather than a speaker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress: Option<bool>,
+}
@@ -0,0 +1,107 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::entities::{
+    AllowedMention, Component, Embed, MessageFlags, PartialDiscordFileAttachment,
+};
+use crate::types::Snowflake;
+
+use super::message::unknown_attachment_reference;
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/webhook#create-webhook>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct WebhookCreateSchema {
+    /// 1-80 characters, which can not contain `clyde` or `discord`
+    pub name: String,
+    /// The avatar as a data uri, such as `data:image/png;base64,...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+}
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/webhook#modify-webhook>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct WebhookModifySchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The avatar as a data uri, such as `data:image/png;base64,...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    /// The channel to move the webhook to. Can not be set when modifying a webhook with its
+    /// token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<Snowflake>,
+}
+
+/// A message sent through a webhook.
+///
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/webhook#execute-webhook>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct WebhookExecuteSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Overrides the name of the webhook for this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Overrides the avatar of the webhook for this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<bool>,
+    /// Up to 10 embeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<Embed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_mentions: Option<AllowedMention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<Component>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<PartialDiscordFileAttachment>>,
+    /// Only [`MessageFlags::SENDABLE`] flags can be set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<u64>,
+    /// Creates a thread with this name for the message, if the webhook is in a forum channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_name: Option<String>,
+    /// The tags of the thread created in a forum channel, see
+    /// [`WebhookExecuteSchema::thread_name`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_tags: Option<Vec<Snowflake>>,
+}
+
+impl WebhookExecuteSchema {
+    /// Returns the first filename which an embed image or thumbnail refers to with an
+    /// `attachment://` url, but which is not among the message's attachments.
+    pub fn unknown_attachment_reference(&self) -> Option<&str> {
+        unknown_attachment_reference(&self.embeds, &self.attachments)
+    }
+
+    /// Returns why the message's flags can not be sent, if they can not.
+    pub fn flags_error(&self) -> Option<String> {
+        if self.flags.unwrap_or_default() & !MessageFlags::SENDABLE.bits() != 0 {
+            return Some(format!(
+                "Only the flags {:?} can be set when executing a webhook",
+                MessageFlags::SENDABLE
+            ));
+        }
+        None
+    }
+}
+
+/// # Reference
+/// See <https://discord-userdoccers.vercel.app/resources/webhook#execute-webhook>
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookExecuteQuery {
+    /// Whether to wait for the message to be sent and return it, instead of returning right
+    /// away without it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait: Option<bool>,
+    /// Sends the message in a thread of the webhook's channel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<Snowflake>,
+}
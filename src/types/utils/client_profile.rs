@@ -0,0 +1,133 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{GatewayIdentifyConnectionProps, GatewayIdentifyPayload};
+
+/// The build number sent by the presets of [`ClientProfile`]
+const CLIENT_BUILD_NUMBER: u64 = 222963;
+/// The capabilities sent by the presets of [`ClientProfile`], see
+/// [`GatewayIdentifyPayload::default_w_client_capabilities`]
+const CLIENT_CAPABILITIES: i32 = 8189;
+/// The version of Electron the desktop presets of [`ClientProfile`] claim to run on
+const ELECTRON_VERSION: &str = "28.2.10";
+
+/// Describes the client a user account session presents itself as: the user agent of REST
+/// requests, along with the connection properties and capabilities sent when identifying.
+///
+/// Keeping these consistent with each other matters to instances which compare them, such as
+/// when detecting the client type of a session. Set it on an
+/// [`Instance`](crate::instance::Instance::client_profile) to use it for all of its requests and
+/// logins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientProfile {
+    /// Sent as the `User-Agent` header of REST requests, and as
+    /// [`browser_user_agent`](GatewayIdentifyConnectionProps::browser_user_agent) when identifying
+    pub user_agent: String,
+    pub properties: GatewayIdentifyConnectionProps,
+    pub capabilities: i32,
+}
+
+impl ClientProfile {
+    /// Creates a profile, setting the user agent of the properties to `user_agent`.
+    pub fn new(
+        user_agent: impl Into<String>,
+        mut properties: GatewayIdentifyConnectionProps,
+        capabilities: i32,
+    ) -> Self {
+        let user_agent = user_agent.into();
+        properties.browser_user_agent = user_agent.clone();
+        Self {
+            user_agent,
+            properties,
+            capabilities,
+        }
+    }
+
+    /// The stable desktop client on Windows.
+    pub fn stable_desktop() -> Self {
+        Self::desktop("stable", "1.0.9035")
+    }
+
+    /// The canary desktop client on Windows.
+    pub fn canary_desktop() -> Self {
+        Self::desktop("canary", "1.0.324")
+    }
+
+    /// The web client in Chrome on Windows.
+    pub fn web() -> Self {
+        Self::new(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+            GatewayIdentifyConnectionProps {
+                browser: String::from("Chrome"),
+                browser_version: String::from("120.0.0.0"),
+                os: String::from("Windows"),
+                os_version: Some(String::from("10")),
+                system_locale: String::from("en-US"),
+                release_channel: String::from("stable"),
+                client_build_number: CLIENT_BUILD_NUMBER,
+                ..GatewayIdentifyConnectionProps::common()
+            },
+            CLIENT_CAPABILITIES,
+        )
+    }
+
+    /// The mobile client on Android.
+    pub fn mobile() -> Self {
+        Self::new(
+            format!("Discord-Android/{};RNA", CLIENT_BUILD_NUMBER),
+            GatewayIdentifyConnectionProps {
+                browser: String::from("Discord Android"),
+                browser_version: String::new(),
+                os: String::from("Android"),
+                os_version: Some(String::from("34")),
+                device: Some(String::from("Pixel 7")),
+                system_locale: String::from("en-US"),
+                release_channel: String::from("googleRelease"),
+                client_build_number: CLIENT_BUILD_NUMBER,
+                ..GatewayIdentifyConnectionProps::common()
+            },
+            CLIENT_CAPABILITIES,
+        )
+    }
+
+    fn desktop(release_channel: &str, client_version: &str) -> Self {
+        Self::new(
+            format!(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) discord/{} Chrome/120.0.6099.291 Electron/{} Safari/537.36",
+                client_version, ELECTRON_VERSION
+            ),
+            GatewayIdentifyConnectionProps {
+                browser: String::from("Discord Client"),
+                browser_version: String::from(ELECTRON_VERSION),
+                os: String::from("Windows"),
+                os_version: Some(String::from("10.0.22631")),
+                system_locale: String::from("en-US"),
+                release_channel: release_channel.to_string(),
+                client_build_number: CLIENT_BUILD_NUMBER,
+                ..GatewayIdentifyConnectionProps::common()
+            },
+            CLIENT_CAPABILITIES,
+        )
+    }
+
+    /// Creates an identify payload with the properties and capabilities of this profile.
+    pub fn identify(&self, token: String) -> GatewayIdentifyPayload {
+        GatewayIdentifyPayload {
+            token,
+            properties: self.properties.clone(),
+            capabilities: Some(self.capabilities),
+            ..GatewayIdentifyPayload::common()
+        }
+    }
+
+    /// Returns the properties encoded the way clients send them in the `X-Super-Properties`
+    /// header of REST requests: as base64 encoded json.
+    pub fn super_properties(&self) -> String {
+        let json = serde_json::to_string(&self.properties).unwrap();
+        base64::engine::general_purpose::STANDARD.encode(json)
+    }
+}
@@ -0,0 +1,201 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A language the client can be displayed in, such as a user's or guild's preferred locale.
+///
+/// Serialized as its language code, such as `en-US`. Codes which are not known to chorus are kept
+/// as [`Locale::Other`].
+///
+/// # Reference
+/// See <https://discord.com/developers/docs/reference#locales>
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Locale {
+    Indonesian,
+    Danish,
+    German,
+    EnglishUk,
+    #[default]
+    EnglishUs,
+    Spanish,
+    SpanishLatam,
+    French,
+    Croatian,
+    Italian,
+    Lithuanian,
+    Hungarian,
+    Dutch,
+    Norwegian,
+    Polish,
+    PortugueseBrazilian,
+    Romanian,
+    Finnish,
+    Swedish,
+    Vietnamese,
+    Turkish,
+    Czech,
+    Greek,
+    Bulgarian,
+    Russian,
+    Ukrainian,
+    Hindi,
+    Thai,
+    ChineseChina,
+    Japanese,
+    ChineseTaiwan,
+    Korean,
+    /// A locale with the given language code, which is not known to chorus
+    Other(String),
+}
+
+impl Locale {
+    /// Returns the language code of the locale, such as `en-US`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Locale::Indonesian => "id",
+            Locale::Danish => "da",
+            Locale::German => "de",
+            Locale::EnglishUk => "en-GB",
+            Locale::EnglishUs => "en-US",
+            Locale::Spanish => "es-ES",
+            Locale::SpanishLatam => "es-419",
+            Locale::French => "fr",
+            Locale::Croatian => "hr",
+            Locale::Italian => "it",
+            Locale::Lithuanian => "lt",
+            Locale::Hungarian => "hu",
+            Locale::Dutch => "nl",
+            Locale::Norwegian => "no",
+            Locale::Polish => "pl",
+            Locale::PortugueseBrazilian => "pt-BR",
+            Locale::Romanian => "ro",
+            Locale::Finnish => "fi",
+            Locale::Swedish => "sv-SE",
+            Locale::Vietnamese => "vi",
+            Locale::Turkish => "tr",
+            Locale::Czech => "cs",
+            Locale::Greek => "el",
+            Locale::Bulgarian => "bg",
+            Locale::Russian => "ru",
+            Locale::Ukrainian => "uk",
+            Locale::Hindi => "hi",
+            Locale::Thai => "th",
+            Locale::ChineseChina => "zh-CN",
+            Locale::Japanese => "ja",
+            Locale::ChineseTaiwan => "zh-TW",
+            Locale::Korean => "ko",
+            Locale::Other(code) => code,
+        }
+    }
+
+    /// Returns the locale with the given language code. Unknown codes are kept as
+    /// [`Locale::Other`].
+    pub fn from_code(code: &str) -> Locale {
+        match code {
+            "id" => Locale::Indonesian,
+            "da" => Locale::Danish,
+            "de" => Locale::German,
+            "en-GB" => Locale::EnglishUk,
+            "en-US" => Locale::EnglishUs,
+            "es-ES" => Locale::Spanish,
+            "es-419" => Locale::SpanishLatam,
+            "fr" => Locale::French,
+            "hr" => Locale::Croatian,
+            "it" => Locale::Italian,
+            "lt" => Locale::Lithuanian,
+            "hu" => Locale::Hungarian,
+            "nl" => Locale::Dutch,
+            "no" => Locale::Norwegian,
+            "pl" => Locale::Polish,
+            "pt-BR" => Locale::PortugueseBrazilian,
+            "ro" => Locale::Romanian,
+            "fi" => Locale::Finnish,
+            "sv-SE" => Locale::Swedish,
+            "vi" => Locale::Vietnamese,
+            "tr" => Locale::Turkish,
+            "cs" => Locale::Czech,
+            "el" => Locale::Greek,
+            "bg" => Locale::Bulgarian,
+            "ru" => Locale::Russian,
+            "uk" => Locale::Ukrainian,
+            "hi" => Locale::Hindi,
+            "th" => Locale::Thai,
+            "zh-CN" => Locale::ChineseChina,
+            "ja" => Locale::Japanese,
+            "zh-TW" => Locale::ChineseTaiwan,
+            "ko" => Locale::Korean,
+            code => Locale::Other(code.to_string()),
+        }
+    }
+
+    /// Returns the language of the locale, such as `en` for `en-US`.
+    pub fn language(&self) -> &str {
+        let code = self.as_str();
+        code.split_once('-').map_or(code, |(language, _)| language)
+    }
+
+    /// Returns the translation for this locale out of the given translations.
+    ///
+    /// Falls back to a translation in the same language (`es-ES` for `es-419`), and then to
+    /// `default`.
+    pub fn localize<'a, S: AsRef<str>>(
+        &self,
+        translations: &'a HashMap<Locale, S>,
+        default: &'a str,
+    ) -> &'a str {
+        if let Some(translation) = translations.get(self) {
+            return translation.as_ref();
+        }
+        // Prefer the same fallback regardless of the map's iteration order
+        translations
+            .iter()
+            .filter(|(locale, _)| locale.language() == self.language())
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .map_or(default, |(_, translation)| translation.as_ref())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Locale::from_code(s))
+    }
+}
+
+impl From<&str> for Locale {
+    fn from(code: &str) -> Self {
+        Locale::from_code(code)
+    }
+}
+
+impl Serialize for Locale {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        Ok(Locale::from_code(&code))
+    }
+}
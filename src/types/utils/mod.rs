@@ -3,11 +3,17 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![allow(unused_imports)]
+pub use cdn::*;
+pub use client_profile::*;
+pub use locale::Locale;
 pub use regexes::*;
 pub use rights::Rights;
 pub use snowflake::Snowflake;
 
+mod cdn;
+mod client_profile;
 pub mod jwt;
+mod locale;
 mod regexes;
 mod rights;
 mod snowflake;
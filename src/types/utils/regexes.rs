@@ -13,4 +13,6 @@ lazy_static! {
     static ref ROLE_MENTION: Regex = Regex::new(r"<@&(\d+)>").unwrap();
     static ref EVERYONE_MENTION: Regex = Regex::new(r"@everyone").unwrap();
     static ref HERE_MENTION: Regex = Regex::new(r"@here").unwrap();
+    /// A custom emoji in a message's content, such as `<:name:id>` or `<a:name:id>`
+    pub(crate) static ref CUSTOM_EMOJI: Regex = Regex::new(r"<a?:\w+:(\d+)>").unwrap();
 }
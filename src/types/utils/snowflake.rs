@@ -85,7 +85,24 @@ impl<'de> serde::Deserialize<'de> for Snowflake {
             type Value = Snowflake;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("snowflake string")
+                formatter.write_str("snowflake string or integer")
+            }
+
+            // Snowflakes are integers in the External Term Format
+            fn visit_u64<E>(self, value: u64) -> Result<Snowflake, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Snowflake(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Snowflake, E>
+            where
+                E: serde::de::Error,
+            {
+                u64::try_from(value)
+                    .map(Snowflake)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(value), &self))
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Snowflake, E>
@@ -98,7 +115,9 @@ impl<'de> serde::Deserialize<'de> for Snowflake {
                 }
             }
         }
-        deserializer.deserialize_str(SnowflakeVisitor)
+        // Not deserialize_str: buffered content (#[serde(flatten)], untagged enums) would
+        // reject the integers the External Term Format uses
+        deserializer.deserialize_any(SnowflakeVisitor)
     }
 }
 
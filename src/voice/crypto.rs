@@ -4,7 +4,8 @@
 
 //! Defines cryptography functions used within the voice implementation.
 //!
-//! All functions in this module return a 24 byte long `Vec<u8>`.
+//! The `xsalsa20_poly1305` functions in this module return a 24 byte long `Vec<u8>`, the
+//! `aead_aes256_gcm` ones a 12 byte long one.
 
 /// Gets an `xsalsa20_poly1305` nonce from an rtppacket.
 ///
@@ -48,6 +49,25 @@ pub(crate) fn get_xsalsa20_poly1305_lite_nonce(packet: &[u8]) -> Vec<u8> {
     nonce
 }
 
+/// Gets an `aead_aes256_gcm` nonce from an rtppacket.
+///
+/// Like with `xsalsa20_poly1305_lite`, the nonce is an incremental 4 byte suffix, though padded
+/// to the 12 bytes AES-GCM uses. The rtp header is passed as associated data.
+///
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#encryption-mode>
+pub(crate) fn get_aead_aes256_gcm_nonce(packet: &[u8]) -> Vec<u8> {
+    let mut nonce = Vec::with_capacity(12);
+
+    nonce.append(&mut packet[(packet.len() - 4)..packet.len()].to_vec());
+
+    // The suffix is only 4 bytes, but the nonce has to be 12
+    while nonce.len() < 12 {
+        nonce.push(0);
+    }
+
+    nonce
+}
+
 #[test]
 // Asserts all functions that retrieve a nonce from packet bytes
 fn test_packet_nonce_derives() {
@@ -76,6 +96,9 @@ fn test_packet_nonce_derives() {
         72, 113, 33, 113, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
 
+    let nonce_4 = get_aead_aes256_gcm_nonce(&test_packet_bytes);
+    let nonce_4_expected = vec![72, 113, 33, 113, 0, 0, 0, 0, 0, 0, 0, 0];
+
     println!("nonce 1: {:?}", nonce_1);
     println!("nonce 2: {:?}", nonce_2);
     println!("nonce 3: {:?}", nonce_3);
@@ -83,8 +106,10 @@ fn test_packet_nonce_derives() {
     assert_eq!(nonce_1.len(), 24);
     assert_eq!(nonce_2.len(), 24);
     assert_eq!(nonce_3.len(), 24);
+    assert_eq!(nonce_4.len(), 12);
 
     assert_eq!(nonce_1, nonce_1_expected);
     assert_eq!(nonce_2, nonce_2_expected);
     assert_eq!(nonce_3, nonce_3_expected);
+    assert_eq!(nonce_4, nonce_4_expected);
 }
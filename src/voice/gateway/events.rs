@@ -8,7 +8,7 @@ use crate::{
     types::{
         SessionDescription, SessionUpdate, Speaking, SsrcDefinition, VoiceBackendVersion,
         VoiceClientConnectFlags, VoiceClientConnectPlatform, VoiceClientDisconnection,
-        VoiceMediaSinkWants, VoiceReady,
+        VoiceClientsConnect, VoiceMediaSinkWants, VoiceRawEvent, VoiceReady,
     },
 };
 
@@ -20,9 +20,14 @@ pub struct VoiceEvents {
     pub session_update: GatewayEvent<SessionUpdate>,
     pub speaking: GatewayEvent<Speaking>,
     pub ssrc_definition: GatewayEvent<SsrcDefinition>,
+    pub clients_connect: GatewayEvent<VoiceClientsConnect>,
     pub client_disconnect: GatewayEvent<VoiceClientDisconnection>,
     pub client_connect_flags: GatewayEvent<VoiceClientConnectFlags>,
     pub client_connect_platform: GatewayEvent<VoiceClientConnectPlatform>,
     pub media_sink_wants: GatewayEvent<VoiceMediaSinkWants>,
+    /// Payloads of the DAVE (end-to-end encryption) protocol, which chorus does not implement yet
+    pub dave: GatewayEvent<VoiceRawEvent>,
+    /// Payloads with opcodes chorus does not know about
+    pub unknown: GatewayEvent<VoiceRawEvent>,
     pub error: GatewayEvent<VoiceGatewayError>,
 }
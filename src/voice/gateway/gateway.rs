@@ -15,11 +15,12 @@ use crate::{
     errors::VoiceGatewayError,
     gateway::GatewayEvent,
     types::{
-        VoiceGatewayReceivePayload, VoiceHelloData, WebSocketEvent, VOICE_BACKEND_VERSION,
-        VOICE_CLIENT_CONNECT_FLAGS, VOICE_CLIENT_CONNECT_PLATFORM, VOICE_CLIENT_DISCONNECT,
-        VOICE_HEARTBEAT, VOICE_HEARTBEAT_ACK, VOICE_HELLO, VOICE_IDENTIFY, VOICE_MEDIA_SINK_WANTS,
-        VOICE_READY, VOICE_RESUME, VOICE_SELECT_PROTOCOL, VOICE_SESSION_DESCRIPTION,
-        VOICE_SESSION_UPDATE, VOICE_SPEAKING, VOICE_SSRC_DEFINITION,
+        is_dave_opcode, VoiceGatewayReceivePayload, VoiceHelloData, VoiceRawEvent, WebSocketEvent,
+        VOICE_BACKEND_VERSION, VOICE_CLIENTS_CONNECT, VOICE_CLIENT_CONNECT_FLAGS,
+        VOICE_CLIENT_CONNECT_PLATFORM, VOICE_CLIENT_DISCONNECT, VOICE_HEARTBEAT,
+        VOICE_HEARTBEAT_ACK, VOICE_HELLO, VOICE_IDENTIFY, VOICE_MEDIA_SINK_WANTS, VOICE_READY,
+        VOICE_RESUME, VOICE_SELECT_PROTOCOL, VOICE_SESSION_DESCRIPTION, VOICE_SESSION_UPDATE,
+        VOICE_SPEAKING, VOICE_SSRC_DEFINITION,
     },
     voice::gateway::{
         heartbeat::VoiceHeartbeatThreadCommunication, VoiceGatewayMessage, WebSocketBackend,
@@ -27,7 +28,8 @@ use crate::{
 };
 
 use super::{
-    events::VoiceEvents, heartbeat::VoiceHeartbeatHandler, Sink, Stream, VoiceGatewayHandle,
+    events::VoiceEvents, heartbeat::VoiceHeartbeatHandler, Sink, SsrcRegistry, Stream,
+    VoiceGatewayHandle,
 };
 
 #[derive(Debug)]
@@ -41,6 +43,10 @@ pub struct VoiceGateway {
 }
 
 impl VoiceGateway {
+    /// Connects to the voice gateway at the given url and spawns the tasks handling the connection.
+    ///
+    /// Dropping the returned future before it completes aborts the connection attempt, without
+    /// leaving any tasks behind.
     #[allow(clippy::new_ret_no_self)]
     pub async fn spawn(websocket_url: String) -> Result<VoiceGatewayHandle, VoiceGatewayError> {
         // Append the needed things to the websocket url
@@ -57,11 +63,26 @@ impl VoiceGateway {
 
         // Wait for the first hello and then spawn both tasks so we avoid nested tasks
         // This automatically spawns the heartbeat task, but from the main thread
+        let closed_error = || VoiceGatewayError::CannotConnect {
+            error: "The connection was closed before receiving hello".to_string(),
+        };
         #[cfg(not(target_arch = "wasm32"))]
-        let msg: VoiceGatewayMessage = websocket_receive.next().await.unwrap().unwrap().into();
+        let msg: VoiceGatewayMessage = websocket_receive
+            .next()
+            .await
+            .ok_or_else(closed_error)?
+            .map_err(|e| VoiceGatewayError::CannotConnect {
+                error: e.to_string(),
+            })?
+            .into();
         #[cfg(target_arch = "wasm32")]
-        let msg: VoiceGatewayMessage = websocket_receive.next().await.unwrap().into();
-        let gateway_payload: VoiceGatewayReceivePayload = serde_json::from_str(&msg.0).unwrap();
+        let msg: VoiceGatewayMessage = websocket_receive
+            .next()
+            .await
+            .ok_or_else(closed_error)?
+            .into();
+        let gateway_payload: VoiceGatewayReceivePayload =
+            serde_json::from_str(&msg.0).map_err(|_| VoiceGatewayError::FailedToDecodePayload)?;
 
         if gateway_payload.op_code != VOICE_HELLO {
             return Err(VoiceGatewayError::NonHelloOnInitiate {
@@ -76,7 +97,9 @@ impl VoiceGateway {
             serde_json::from_str(gateway_payload.data.get()).unwrap();
         let heartbeat_interval_seconds: f64 = gateway_hello.heartbeat_interval / 1000.0;
 
-        let voice_events = VoiceEvents::default();
+        let mut voice_events = VoiceEvents::default();
+        let ssrcs = SsrcRegistry::new();
+        ssrcs.subscribe(&mut voice_events);
         let shared_events = Arc::new(Mutex::new(voice_events));
 
         let mut gateway = VoiceGateway {
@@ -106,11 +129,23 @@ impl VoiceGateway {
         Ok(VoiceGatewayHandle {
             url: websocket_url.clone(),
             events: shared_events,
+            ssrcs,
             websocket_send: shared_websocket_send.clone(),
             kill_send: kill_send.clone(),
         })
     }
 
+    /// Like [`VoiceGateway::spawn`], but fails with [`VoiceGatewayError::ConnectionTimedOut`] if
+    /// the connection is not established within the given duration.
+    pub async fn spawn_with_timeout(
+        websocket_url: String,
+        timeout: Duration,
+    ) -> Result<VoiceGatewayHandle, VoiceGatewayError> {
+        crate::with_timeout(timeout, VoiceGateway::spawn(websocket_url))
+            .await
+            .unwrap_or(Err(VoiceGatewayError::ConnectionTimedOut))
+    }
+
     /// The main gateway listener task;
     pub async fn gateway_listen_task(&mut self) {
         loop {
@@ -254,6 +289,18 @@ impl VoiceGateway {
                     );
                 }
             }
+            VOICE_CLIENTS_CONNECT => {
+                trace!("VGW: Received Clients Connect");
+
+                let event = &mut self.events.lock().await.clients_connect;
+                let result = VoiceGateway::handle_event(gateway_payload.data.get(), event).await;
+                if result.is_err() {
+                    warn!(
+                        "Failed to parse VOICE_CLIENTS_CONNECT ({})",
+                        result.err().unwrap()
+                    );
+                }
+            }
             VOICE_CLIENT_DISCONNECT => {
                 trace!("VGW: Received Client Disconnect");
 
@@ -341,8 +388,23 @@ impl VoiceGateway {
                     gateway_payload.op_code
                 );
             }
-            _ => {
-                warn!("VGW: Received unrecognized voice gateway op code ({})! Please open an issue on the chorus github so we can implement it", gateway_payload.op_code);
+            op_code if is_dave_opcode(op_code) => {
+                trace!("VGW: Received DAVE opcode ({})", op_code);
+
+                let event = VoiceRawEvent {
+                    op_code,
+                    data: serde_json::from_str(gateway_payload.data.get()).unwrap_or_default(),
+                };
+                self.events.lock().await.dave.notify(event).await;
+            }
+            op_code => {
+                warn!("VGW: Received unrecognized voice gateway op code ({})! Please open an issue on the chorus github so we can implement it", op_code);
+
+                let event = VoiceRawEvent {
+                    op_code,
+                    data: serde_json::from_str(gateway_payload.data.get()).unwrap_or_default(),
+                };
+                self.events.lock().await.unknown.notify(event).await;
             }
         }
     }
@@ -17,7 +17,7 @@ use crate::types::{
     VOICE_SSRC_DEFINITION,
 };
 
-use super::{events::VoiceEvents, Sink, VoiceGatewayMessage};
+use super::{events::VoiceEvents, Sink, SsrcRegistry, VoiceGatewayMessage};
 
 /// Represents a handle to a Voice Gateway connection.
 /// Using this handle you can send Gateway Events directly.
@@ -25,6 +25,8 @@ use super::{events::VoiceEvents, Sink, VoiceGatewayMessage};
 pub struct VoiceGatewayHandle {
     pub url: String,
     pub events: Arc<Mutex<VoiceEvents>>,
+    /// The users sending the RTP streams received on this connection, by ssrc
+    pub ssrcs: SsrcRegistry,
     pub websocket_send: Arc<Mutex<Sink>>,
     /// Tells gateway tasks to close
     pub(super) kill_send: tokio::sync::broadcast::Sender<()>,
@@ -8,8 +8,10 @@ pub mod gateway;
 pub mod handle;
 pub mod heartbeat;
 pub mod message;
+pub mod ssrc;
 
 pub use backends::*;
 pub use gateway::*;
 pub use handle::*;
 pub use message::*;
+pub use ssrc::*;
@@ -0,0 +1,120 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+
+use crate::gateway::Observer;
+use crate::types::{Snowflake, Speaking, SsrcDefinition, VoiceClientDisconnection};
+
+use super::events::VoiceEvents;
+
+/// Keeps track of which user sends the RTP streams with which ssrc on a voice connection.
+///
+/// The registry is filled from [`Speaking`] and [`SsrcDefinition`] events, and the ssrcs of a
+/// user are forgotten once they disconnect ([`VoiceClientDisconnection`]). It is kept up to date
+/// by the [`VoiceGateway`](super::VoiceGateway) before any other observers are notified.
+///
+/// Clones share the same registry.
+#[derive(Debug, Clone, Default)]
+pub struct SsrcRegistry {
+    users: Arc<RwLock<HashMap<u32, Snowflake>>>,
+}
+
+impl SsrcRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of the user sending with the given ssrc, if known.
+    pub fn user_id(&self, ssrc: u32) -> Option<Snowflake> {
+        self.users.read().unwrap().get(&ssrc).copied()
+    }
+
+    /// Returns all known ssrcs of a user.
+    pub fn ssrcs(&self, user_id: Snowflake) -> Vec<u32> {
+        let mut ssrcs: Vec<u32> = self
+            .users
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, user)| **user == user_id)
+            .map(|(ssrc, _)| *ssrc)
+            .collect();
+        ssrcs.sort_unstable();
+        ssrcs
+    }
+
+    /// Returns a copy of the whole registry, mapping ssrcs to user ids.
+    pub fn entries(&self) -> HashMap<u32, Snowflake> {
+        self.users.read().unwrap().clone()
+    }
+
+    /// Assigns an ssrc to a user, replacing the user it was assigned to before.
+    pub fn insert(&self, ssrc: u32, user_id: Snowflake) {
+        self.users.write().unwrap().insert(ssrc, user_id);
+    }
+
+    /// Forgets all ssrcs of a user.
+    pub fn remove_user(&self, user_id: Snowflake) {
+        self.users
+            .write()
+            .unwrap()
+            .retain(|_, user| *user != user_id);
+    }
+
+    /// Forgets all ssrcs.
+    pub fn clear(&self) {
+        self.users.write().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.read().unwrap().is_empty()
+    }
+
+    /// Subscribes the registry to the events it is built from.
+    pub(super) fn subscribe(&self, events: &mut VoiceEvents) {
+        let observer = Arc::new(self.clone());
+        events.speaking.subscribe(observer.clone());
+        events.ssrc_definition.subscribe(observer.clone());
+        events.client_disconnect.subscribe(observer);
+    }
+}
+
+#[async_trait]
+impl Observer<Speaking> for SsrcRegistry {
+    async fn update(&self, data: &Speaking) {
+        if let Some(user_id) = data.user_id {
+            self.insert(data.ssrc, user_id);
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<SsrcDefinition> for SsrcRegistry {
+    async fn update(&self, data: &SsrcDefinition) {
+        let Some(user_id) = data.user_id else {
+            return;
+        };
+        // A ssrc of 0 means the stream is not defined
+        for ssrc in [data.audio_ssrc, data.video_ssrc, data.rtx_ssrc] {
+            if let Ok(ssrc @ 1..) = u32::try_from(ssrc) {
+                self.insert(ssrc, user_id);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Observer<VoiceClientDisconnection> for SsrcRegistry {
+    async fn update(&self, data: &VoiceClientDisconnection) {
+        self.remove_user(data.user_id);
+    }
+}
@@ -8,10 +8,16 @@ mod crypto;
 #[cfg(feature = "voice_gateway")]
 pub mod gateway;
 #[cfg(feature = "voice_udp")]
+pub mod receive;
+#[cfg(feature = "voice_udp")]
+pub mod recording;
+#[cfg(feature = "voice_udp")]
 pub mod udp;
 #[cfg(feature = "voice_udp")]
 pub mod voice_data;
 
 // Pub use this so users can interact with packet types if they want
+#[cfg(feature = "voice_opus")]
+pub use audiopus;
 #[cfg(feature = "voice_udp")]
 pub use discortp;
@@ -0,0 +1,230 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Receiving the audio of other users on a voice connection, as streams of Opus frames.
+//!
+//! A [`VoiceReceiver`] observes the decrypted RTP packets of a [`UdpHandle`] and hands their
+//! payloads to [`OpusStream`]s, one per user or one for everyone. An [`OpusStream`] can be
+//! turned into a [`PcmStream`] with [`AudioDecoder`]s, such as the libopus backed
+//! `OpusDecoder` of the `voice_opus` feature.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use discortp::rtp::Rtp;
+use futures_util::Stream;
+use log::*;
+use tokio::sync::mpsc;
+
+use crate::gateway::Observer;
+use crate::types::Snowflake;
+#[cfg(feature = "voice_gateway")]
+use crate::voice::gateway::SsrcRegistry;
+use crate::voice::recording::{strip_rtp_extension, AudioDecoder};
+use crate::voice::udp::UdpHandle;
+
+/// An Opus frame received from another user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpusFrame {
+    /// The ssrc of the RTP stream the frame was received on.
+    pub ssrc: u32,
+    /// The user sending the stream, if known.
+    pub user_id: Option<Snowflake>,
+    pub sequence: u16,
+    pub timestamp: u32,
+    /// The Opus packet, without the RTP header extension.
+    pub data: Vec<u8>,
+}
+
+/// Decoded audio received from another user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcmFrame {
+    /// The ssrc of the RTP stream the audio was received on.
+    pub ssrc: u32,
+    /// The user sending the stream, if known.
+    pub user_id: Option<Snowflake>,
+    pub timestamp: u32,
+    /// Interleaved stereo samples at [`SAMPLE_RATE`](crate::voice::recording::SAMPLE_RATE).
+    pub samples: Vec<i16>,
+}
+
+/// Hands the audio received on a voice connection to per-user streams.
+///
+/// Receiving is opt-in: nothing is buffered until the receiver is
+/// [subscribed](VoiceReceiver::subscribe) to a voice UDP connection and a stream has been
+/// requested. Frames are only buffered for streams which have not been dropped.
+///
+/// Clones share the same streams.
+#[derive(Debug, Clone, Default)]
+pub struct VoiceReceiver {
+    state: Arc<Mutex<ReceiverState>>,
+}
+
+#[derive(Debug, Default)]
+struct ReceiverState {
+    #[cfg(feature = "voice_gateway")]
+    ssrcs: Option<SsrcRegistry>,
+    /// The streams, with the user whose frames they receive, or [`None`] for all frames
+    senders: Vec<(Option<Snowflake>, mpsc::UnboundedSender<OpusFrame>)>,
+}
+
+impl VoiceReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up the users behind the received streams in the given registry, usually
+    /// [`VoiceGatewayHandle::ssrcs`](crate::voice::gateway::VoiceGatewayHandle::ssrcs).
+    ///
+    /// Without a registry, [`VoiceReceiver::user_stream`]s receive nothing.
+    #[cfg(feature = "voice_gateway")]
+    pub fn with_ssrc_registry(self, ssrcs: SsrcRegistry) -> Self {
+        self.state.lock().unwrap().ssrcs = Some(ssrcs);
+        self
+    }
+
+    /// Subscribes the receiver to the RTP packets of a voice UDP connection.
+    pub async fn subscribe(&self, handle: &UdpHandle) {
+        handle
+            .events
+            .lock()
+            .await
+            .rtp
+            .subscribe(Arc::new(self.clone()));
+    }
+
+    /// Returns a stream of the frames a user sends from now on.
+    pub fn user_stream(&self, user_id: Snowflake) -> OpusStream {
+        self.add_stream(Some(user_id))
+    }
+
+    /// Returns a stream of all frames received from now on.
+    pub fn stream(&self) -> OpusStream {
+        self.add_stream(None)
+    }
+
+    fn add_stream(&self, user_id: Option<Snowflake>) -> OpusStream {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.state.lock().unwrap().senders.push((user_id, sender));
+        OpusStream { receiver }
+    }
+
+    /// Passes a received RTP packet, whose payload has already been decrypted, to the streams.
+    pub fn receive(&self, rtp: &Rtp) {
+        let mut state = self.state.lock().unwrap();
+        #[cfg(feature = "voice_gateway")]
+        let user_id = state
+            .ssrcs
+            .as_ref()
+            .and_then(|ssrcs| ssrcs.user_id(rtp.ssrc));
+        #[cfg(not(feature = "voice_gateway"))]
+        let user_id = None;
+
+        let frame = OpusFrame {
+            ssrc: rtp.ssrc,
+            user_id,
+            sequence: rtp.sequence.into(),
+            timestamp: rtp.timestamp.into(),
+            data: strip_rtp_extension(&rtp.payload, rtp.extension != 0).to_vec(),
+        };
+        // Sending only fails once the stream has been dropped
+        state.senders.retain(|(user, sender)| {
+            if user.is_some() && *user != user_id {
+                return !sender.is_closed();
+            }
+            sender.send(frame.clone()).is_ok()
+        });
+    }
+}
+
+#[async_trait]
+impl Observer<Rtp> for VoiceReceiver {
+    async fn update(&self, data: &Rtp) {
+        self.receive(data);
+    }
+}
+
+/// A stream of received [`OpusFrame`]s, created by a [`VoiceReceiver`].
+#[derive(Debug)]
+pub struct OpusStream {
+    receiver: mpsc::UnboundedReceiver<OpusFrame>,
+}
+
+impl OpusStream {
+    /// Decodes the frames of this stream, creating a decoder with `new_decoder` for every ssrc.
+    ///
+    /// Opus decoders are stateful, so the frames of different speakers must not share one.
+    /// Frames which cannot be decoded are skipped.
+    pub fn decode(
+        self,
+        new_decoder: impl Fn() -> Box<dyn AudioDecoder> + Send + Sync + 'static,
+    ) -> PcmStream {
+        PcmStream {
+            frames: self,
+            new_decoder: Box::new(new_decoder),
+            decoders: HashMap::new(),
+        }
+    }
+}
+
+impl Stream for OpusStream {
+    type Item = OpusFrame;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// A stream of decoded [`PcmFrame`]s, created with [`OpusStream::decode`].
+pub struct PcmStream {
+    frames: OpusStream,
+    new_decoder: Box<dyn Fn() -> Box<dyn AudioDecoder> + Send + Sync>,
+    /// The decoder of each ssrc
+    decoders: HashMap<u32, Box<dyn AudioDecoder>>,
+}
+
+impl Stream for PcmStream {
+    type Item = PcmFrame;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let frame = match Pin::new(&mut self.frames).poll_next(cx) {
+                Poll::Ready(Some(frame)) => frame,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            let this = &mut *self;
+            let decoder = this
+                .decoders
+                .entry(frame.ssrc)
+                .or_insert_with(|| (this.new_decoder)());
+            match decoder.decode(&frame.data) {
+                Ok(samples) => {
+                    return Poll::Ready(Some(PcmFrame {
+                        ssrc: frame.ssrc,
+                        user_id: frame.user_id,
+                        timestamp: frame.timestamp,
+                        samples,
+                    }))
+                }
+                Err(e) => warn!(
+                    "Receive: Failed to decode packet of ssrc {}: {}",
+                    frame.ssrc, e
+                ),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for PcmStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PcmStream")
+            .field("frames", &self.frames)
+            .field("decoders", &self.decoders.len())
+            .finish()
+    }
+}
@@ -0,0 +1,853 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Utilities for recording the audio received on a voice connection.
+//!
+//! A [`VoiceRecorder`] observes the RTP packets of a [`UdpHandle`](super::udp::UdpHandle),
+//! decodes them with an [`AudioDecoder`] and passes the audio, placed on a common timeline, to a
+//! [`RecordingSink`]. Chorus comes with two sinks: [`PerUserWavSink`], which writes one WAV file
+//! per speaker, and [`MixdownSink`], which mixes all speakers into a single WAV file.
+//!
+//! An [`OggOpusRecorder`] instead writes the received Opus packets of every speaker into a
+//! separate Ogg Opus file as they are, without decoding them.
+//!
+//! With the `voice_opus` feature, chorus includes an Opus decoder backed by libopus,
+//! [`OpusDecoder`]. Other decoders can be plugged in by implementing [`AudioDecoder`].
+
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use discortp::rtp::Rtp;
+use log::*;
+
+use crate::gateway::Observer;
+use crate::types::Snowflake;
+#[cfg(feature = "voice_gateway")]
+use crate::voice::gateway::SsrcRegistry;
+
+/// The sample rate of voice audio, in Hz.
+pub const SAMPLE_RATE: u32 = 48_000;
+/// The amount of channels of voice audio; samples are interleaved.
+pub const CHANNELS: u16 = 2;
+
+/// Decodes the Opus packets of a single speaker.
+///
+/// A new decoder is created for every ssrc, since Opus decoders keep state between packets.
+pub trait AudioDecoder: Send {
+    /// Decodes an Opus packet into interleaved stereo samples at [`SAMPLE_RATE`].
+    fn decode(&mut self, packet: &[u8]) -> Result<Vec<i16>, Box<dyn Error + Send + Sync>>;
+}
+
+/// The largest amount of samples per channel in an Opus packet, 120ms at [`SAMPLE_RATE`].
+#[cfg(feature = "voice_opus")]
+const MAX_OPUS_PACKET_SAMPLES: usize = 5760;
+
+/// An [`AudioDecoder`] backed by libopus, through the `audiopus` crate.
+#[cfg(feature = "voice_opus")]
+#[derive(Debug)]
+pub struct OpusDecoder {
+    decoder: audiopus::coder::Decoder,
+}
+
+#[cfg(feature = "voice_opus")]
+impl OpusDecoder {
+    /// Creates a decoder for stereo audio at [`SAMPLE_RATE`].
+    pub fn new() -> Result<Self, audiopus::Error> {
+        let decoder = audiopus::coder::Decoder::new(
+            audiopus::SampleRate::Hz48000,
+            audiopus::Channels::Stereo,
+        )?;
+        Ok(Self { decoder })
+    }
+}
+
+#[cfg(feature = "voice_opus")]
+impl AudioDecoder for OpusDecoder {
+    fn decode(&mut self, packet: &[u8]) -> Result<Vec<i16>, Box<dyn Error + Send + Sync>> {
+        let mut samples = vec![0; MAX_OPUS_PACKET_SAMPLES * CHANNELS as usize];
+        let packet = audiopus::packet::Packet::try_from(packet)?;
+        let output = audiopus::MutSignals::try_from(&mut samples[..])?;
+        let decoded = self.decoder.decode(Some(packet), output, false)?;
+        samples.truncate(decoded * CHANNELS as usize);
+        Ok(samples)
+    }
+}
+
+/// Someone whose audio is being recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Speaker {
+    /// The ssrc of the RTP stream the audio is received on.
+    pub ssrc: u32,
+    /// The user sending the stream, if known.
+    pub user_id: Option<Snowflake>,
+}
+
+/// Receives the decoded audio of a [`VoiceRecorder`].
+pub trait RecordingSink: Send + fmt::Debug {
+    /// Receives decoded, interleaved stereo samples of a speaker.
+    ///
+    /// `position` is where the samples start on the recording's timeline, as the amount of
+    /// samples per channel since the recording started. Positions of one speaker usually
+    /// increase, but packets may arrive late; gaps between them are silence.
+    fn write(&mut self, speaker: Speaker, position: u64, samples: &[i16]) -> io::Result<()>;
+
+    /// Writes everything which is still buffered and finalizes the output.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Records the audio received on a voice connection into a [`RecordingSink`].
+///
+/// Subscribe it to the [`rtp`](super::udp::events::VoiceUDPEvents::rtp) event of a voice UDP
+/// connection, and call [`VoiceRecorder::finish`] once the recording should end.
+///
+/// Speakers are placed on the timeline by the time their first packet arrives, and their audio
+/// from then on by its RTP timestamps, so that pauses in speech are kept as silence.
+///
+/// Clones share the same recording.
+#[derive(Clone)]
+pub struct VoiceRecorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+struct RecorderState {
+    started_at: DateTime<Utc>,
+    new_decoder: Box<dyn Fn() -> Box<dyn AudioDecoder> + Send + Sync>,
+    tracks: HashMap<u32, Track>,
+    sink: Box<dyn RecordingSink>,
+    #[cfg(feature = "voice_gateway")]
+    ssrcs: Option<SsrcRegistry>,
+    finished: bool,
+}
+
+/// The state of a single RTP stream.
+struct Track {
+    decoder: Box<dyn AudioDecoder>,
+    /// The position of the stream's first packet on the timeline
+    start_position: u64,
+    /// The RTP timestamp of the stream's first packet
+    start_timestamp: u32,
+}
+
+impl VoiceRecorder {
+    /// Creates a recorder which writes into the given sink, creating a decoder with `new_decoder`
+    /// for every speaker. The recording's timeline starts now.
+    pub fn new(
+        sink: impl RecordingSink + 'static,
+        new_decoder: impl Fn() -> Box<dyn AudioDecoder> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RecorderState {
+                started_at: Utc::now(),
+                new_decoder: Box::new(new_decoder),
+                tracks: HashMap::new(),
+                sink: Box::new(sink),
+                #[cfg(feature = "voice_gateway")]
+                ssrcs: None,
+                finished: false,
+            })),
+        }
+    }
+
+    /// Looks up the users behind the recorded streams in the given registry, usually
+    /// [`VoiceGatewayHandle::ssrcs`](crate::voice::gateway::VoiceGatewayHandle::ssrcs).
+    #[cfg(feature = "voice_gateway")]
+    pub fn with_ssrc_registry(self, ssrcs: SsrcRegistry) -> Self {
+        self.state.lock().unwrap().ssrcs = Some(ssrcs);
+        self
+    }
+
+    /// Records an RTP packet, whose payload has already been decrypted.
+    pub fn record(&self, rtp: &Rtp) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.finished {
+            return Ok(());
+        }
+        let RecorderState {
+            started_at,
+            new_decoder,
+            tracks,
+            ..
+        } = &mut *state;
+
+        let timestamp = u32::from(rtp.timestamp);
+        let track = tracks.entry(rtp.ssrc).or_insert_with(|| {
+            let elapsed = (Utc::now() - *started_at).to_std().unwrap_or_default();
+            Track {
+                decoder: new_decoder(),
+                start_position: (elapsed.as_secs_f64() * SAMPLE_RATE as f64) as u64,
+                start_timestamp: timestamp,
+            }
+        });
+        // The wrapping difference treats packets from before the first one as far in the future;
+        // drop them instead
+        let offset = timestamp.wrapping_sub(track.start_timestamp);
+        if offset > u32::MAX / 2 {
+            return Ok(());
+        }
+        let position = track.start_position + offset as u64;
+
+        let samples = match track
+            .decoder
+            .decode(strip_rtp_extension(&rtp.payload, rtp.extension != 0))
+        {
+            Ok(samples) => samples,
+            Err(e) => {
+                warn!(
+                    "Recording: Failed to decode packet of ssrc {}: {}",
+                    rtp.ssrc, e
+                );
+                return Ok(());
+            }
+        };
+
+        let speaker = Speaker {
+            ssrc: rtp.ssrc,
+            #[cfg(feature = "voice_gateway")]
+            user_id: state
+                .ssrcs
+                .as_ref()
+                .and_then(|ssrcs| ssrcs.user_id(rtp.ssrc)),
+            #[cfg(not(feature = "voice_gateway"))]
+            user_id: None,
+        };
+        state.sink.write(speaker, position, &samples)
+    }
+
+    /// Ends the recording, finishing the sink. Packets received afterwards are ignored.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.finished {
+            return Ok(());
+        }
+        state.finished = true;
+        state.sink.finish()
+    }
+}
+
+impl fmt::Debug for VoiceRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("VoiceRecorder")
+            .field("started_at", &state.started_at)
+            .field("ssrcs", &state.tracks.keys().collect::<Vec<_>>())
+            .field("sink", &state.sink)
+            .field("finished", &state.finished)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Observer<Rtp> for VoiceRecorder {
+    async fn update(&self, data: &Rtp) {
+        if let Err(e) = self.record(data) {
+            warn!("Recording: Failed to write audio: {}", e);
+        }
+    }
+}
+
+/// Removes the RTP header extension, which Discord sends along with the encrypted payload, from
+/// a decrypted payload.
+pub(crate) fn strip_rtp_extension(payload: &[u8], extension: bool) -> &[u8] {
+    if !extension || payload.len() < 4 || payload[..2] != [0xBE, 0xDE] {
+        return payload;
+    }
+    let length = 4 + u16::from_be_bytes([payload[2], payload[3]]) as usize * 4;
+    payload.get(length..).unwrap_or_default()
+}
+
+/// Writes 16 bit PCM audio at [`SAMPLE_RATE`] with [`CHANNELS`] channels into a WAV file.
+///
+/// The sizes in the header are only correct once [`WavWriter::finish`] has been called.
+#[derive(Debug)]
+pub struct WavWriter<W: Write + Seek> {
+    writer: W,
+    /// The amount of bytes of audio written so far
+    data_length: u32,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// The length of the header, before the audio data
+    const HEADER_LENGTH: u32 = 44;
+
+    /// Writes the header of the file and returns a writer for its audio.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        let block_align = CHANNELS * 2;
+        let mut header = Vec::with_capacity(Self::HEADER_LENGTH as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(Self::HEADER_LENGTH - 8).to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        // PCM
+        header.extend_from_slice(&1u16.to_le_bytes());
+        header.extend_from_slice(&CHANNELS.to_le_bytes());
+        header.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        header.extend_from_slice(&(SAMPLE_RATE * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&16u16.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        writer.write_all(&header)?;
+        Ok(Self {
+            writer,
+            data_length: 0,
+        })
+    }
+
+    /// Appends interleaved samples.
+    pub fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        let bytes: Vec<u8> = samples
+            .iter()
+            .flat_map(|sample| sample.to_le_bytes())
+            .collect();
+        self.writer.write_all(&bytes)?;
+        self.data_length = self.data_length.saturating_add(bytes.len() as u32);
+        Ok(())
+    }
+
+    /// Appends the given amount of silent samples per channel.
+    pub fn write_silence(&mut self, samples_per_channel: u64) -> io::Result<()> {
+        const CHUNK: [i16; 4096] = [0; 4096];
+        let mut remaining = samples_per_channel * CHANNELS as u64;
+        while remaining > 0 {
+            let length = remaining.min(CHUNK.len() as u64) as usize;
+            self.write_samples(&CHUNK[..length])?;
+            remaining -= length as u64;
+        }
+        Ok(())
+    }
+
+    /// Fills in the sizes in the header and flushes the writer.
+    pub fn finish(&mut self) -> io::Result<()> {
+        let position = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer
+            .write_all(&(Self::HEADER_LENGTH - 8 + self.data_length).to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(40))?;
+        self.writer.write_all(&self.data_length.to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(position))?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// A [`RecordingSink`] which writes the audio of every speaker into a separate WAV file.
+///
+/// Every file starts at the beginning of the recording, so that the files can be played in sync.
+/// A speaker is identified by their ssrc; a user who reconnects gets a new file.
+pub struct PerUserWavSink<W: Write + Seek> {
+    create_writer: Box<dyn FnMut(Speaker) -> io::Result<W> + Send>,
+    files: HashMap<u32, (WavWriter<W>, u64)>,
+}
+
+impl<W: Write + Seek> PerUserWavSink<W> {
+    /// Creates a sink which opens the file of a speaker with `create_writer`, once they start
+    /// speaking.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use chorus::voice::recording::PerUserWavSink;
+    ///
+    /// let sink = PerUserWavSink::new(|speaker| {
+    ///     let name = match speaker.user_id {
+    ///         Some(user_id) => format!("{}.wav", user_id),
+    ///         None => format!("ssrc-{}.wav", speaker.ssrc),
+    ///     };
+    ///     std::fs::File::create(name)
+    /// });
+    /// ```
+    pub fn new(create_writer: impl FnMut(Speaker) -> io::Result<W> + Send + 'static) -> Self {
+        Self {
+            create_writer: Box::new(create_writer),
+            files: HashMap::new(),
+        }
+    }
+}
+
+impl<W: Write + Seek + Send> RecordingSink for PerUserWavSink<W> {
+    fn write(&mut self, speaker: Speaker, position: u64, samples: &[i16]) -> io::Result<()> {
+        let (file, written) = match self.files.entry(speaker.ssrc) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert((WavWriter::new((self.create_writer)(speaker)?)?, 0))
+            }
+        };
+        if position > *written {
+            file.write_silence(position - *written)?;
+            *written = position;
+        }
+        // Audio which overlaps what has already been written arrived too late
+        let skip = ((*written - position) * CHANNELS as u64) as usize;
+        let Some(samples) = samples.get(skip..) else {
+            return Ok(());
+        };
+        file.write_samples(samples)?;
+        *written += (samples.len() / CHANNELS as usize) as u64;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        for (file, _) in self.files.values_mut() {
+            file.finish()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> fmt::Debug for PerUserWavSink<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PerUserWavSink")
+            .field("ssrcs", &self.files.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A [`RecordingSink`] which mixes all speakers into a single WAV file.
+///
+/// Audio is kept in a buffer until the recording has moved on by
+/// [`latency`](MixdownSink::latency), so that packets of different speakers, which arrive
+/// in no particular order, can be mixed.
+pub struct MixdownSink<W: Write + Seek> {
+    writer: WavWriter<W>,
+    /// Interleaved samples which have not been written yet
+    buffer: VecDeque<i32>,
+    /// The position of the first sample in the buffer
+    buffer_position: u64,
+    /// How many samples per channel are buffered before being written
+    latency: u64,
+}
+
+impl<W: Write + Seek> MixdownSink<W> {
+    /// Creates a sink which writes the mixed audio into the given writer.
+    pub fn new(writer: W) -> io::Result<Self> {
+        Ok(Self {
+            writer: WavWriter::new(writer)?,
+            buffer: VecDeque::new(),
+            buffer_position: 0,
+            latency: SAMPLE_RATE as u64,
+        })
+    }
+
+    /// Sets how long audio is buffered before it is mixed and written. Defaults to one second.
+    pub fn latency(mut self, latency: std::time::Duration) -> Self {
+        self.latency = (latency.as_secs_f64() * SAMPLE_RATE as f64) as u64;
+        self
+    }
+
+    /// Writes the given amount of samples per channel from the front of the buffer, followed by
+    /// silence if the buffer is shorter than that.
+    fn flush(&mut self, samples_per_channel: u64) -> io::Result<()> {
+        let length = (samples_per_channel * CHANNELS as u64).min(self.buffer.len() as u64);
+        let mixed: Vec<i16> = self
+            .buffer
+            .drain(..length as usize)
+            .map(|sample| sample.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+            .collect();
+        self.writer.write_samples(&mixed)?;
+        self.buffer_position += length / CHANNELS as u64;
+
+        // Written in chunks of a second, so that long pauses don't have to be held in memory
+        let mut silence = samples_per_channel - length / CHANNELS as u64;
+        while silence > 0 {
+            let chunk = silence.min(SAMPLE_RATE as u64);
+            self.writer
+                .write_samples(&vec![0; (chunk * CHANNELS as u64) as usize])?;
+            self.buffer_position += chunk;
+            silence -= chunk;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying writer. Call [`RecordingSink::finish`] first.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+impl<W: Write + Seek> fmt::Debug for MixdownSink<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MixdownSink")
+            .field("buffer_position", &self.buffer_position)
+            .field("buffered", &self.buffer.len())
+            .field("latency", &self.latency)
+            .finish()
+    }
+}
+
+impl<W: Write + Seek + Send> RecordingSink for MixdownSink<W> {
+    fn write(&mut self, _speaker: Speaker, position: u64, samples: &[i16]) -> io::Result<()> {
+        // Audio which arrives after its part of the recording has been written is dropped
+        let skip = (self.buffer_position.saturating_sub(position) * CHANNELS as u64) as usize;
+        let Some(samples) = samples.get(skip..) else {
+            return Ok(());
+        };
+        // Audio far ahead of the buffer, such as after a long pause or a jump in the sender's
+        // timestamps, first moves the buffer up to it, instead of growing it to span the gap
+        let gap_end = position.saturating_sub(self.latency);
+        if gap_end > self.buffer_position {
+            self.flush(gap_end - self.buffer_position)?;
+        }
+        let start = ((position.max(self.buffer_position) - self.buffer_position) * CHANNELS as u64)
+            as usize;
+        if self.buffer.len() < start + samples.len() {
+            self.buffer.resize(start + samples.len(), 0);
+        }
+        for (index, sample) in samples.iter().enumerate() {
+            self.buffer[start + index] += *sample as i32;
+        }
+
+        let buffered = self.buffer.len() as u64 / CHANNELS as u64;
+        if buffered > self.latency {
+            self.flush(buffered - self.latency)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush(self.buffer.len() as u64 / CHANNELS as u64)?;
+        self.writer.finish()
+    }
+}
+
+/// An Opus packet of 20ms of silence, as sent by Discord clients when they stop speaking.
+const OPUS_SILENCE: [u8; 3] = [0xF8, 0xFF, 0xFE];
+
+/// The amount of samples per channel of [`OPUS_SILENCE`].
+const OPUS_SILENCE_SAMPLES: u64 = 960;
+
+/// Returns the amount of samples per channel at [`SAMPLE_RATE`] an Opus packet decodes to, from
+/// its table of contents byte and frame count, see RFC 6716, section 3.1.
+///
+/// Returns `None` if the packet is malformed.
+pub fn opus_packet_samples(packet: &[u8]) -> Option<u64> {
+    let toc = *packet.first()?;
+    let config = toc >> 3;
+    let frame_samples = match config {
+        // SILK: 10, 20, 40 or 60ms
+        0..=11 => [480, 960, 1920, 2880][config as usize % 4],
+        // Hybrid: 10 or 20ms
+        12..=15 => [480, 960][config as usize % 2],
+        // CELT: 2.5, 5, 10 or 20ms
+        _ => [120, 240, 480, 960][config as usize % 4],
+    };
+    let frames = match toc & 0b11 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => (*packet.get(1)? & 0b11_1111) as u64,
+    };
+    Some(frame_samples * frames)
+}
+
+/// Returns the checksum of an Ogg page, which is a CRC-32 with polynomial `0x04C11DB7`, without
+/// reflection or a final xor.
+fn ogg_crc(page: &[u8]) -> u32 {
+    page.iter().fold(0, |mut crc: u32, byte| {
+        crc ^= (*byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// Writes Opus packets of a single stereo stream into an Ogg Opus file (RFC 7845), without
+/// decoding them.
+///
+/// Every packet is written on its own page. The last page is only marked as the end of the
+/// stream once [`OggOpusWriter::finish`] has been called.
+#[derive(Debug)]
+pub struct OggOpusWriter<W: Write> {
+    writer: W,
+    serial: u32,
+    sequence: u32,
+    /// The amount of samples per channel written so far, including the pending packet
+    granule_position: u64,
+    /// The last packet, which is written once it is known whether it ends the stream
+    pending: Option<Vec<u8>>,
+}
+
+impl<W: Write> OggOpusWriter<W> {
+    /// Writes the identification and comment headers of the file and returns a writer for its
+    /// audio.
+    pub fn new(writer: W) -> io::Result<Self> {
+        let mut this = Self {
+            writer,
+            serial: rand::random(),
+            sequence: 0,
+            granule_position: 0,
+            pending: None,
+        };
+
+        let mut head = Vec::with_capacity(19);
+        head.extend_from_slice(b"OpusHead");
+        // Version
+        head.push(1);
+        head.push(CHANNELS as u8);
+        // Pre-skip; the packets are passed on as they were encoded
+        head.extend_from_slice(&0u16.to_le_bytes());
+        head.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        // Output gain
+        head.extend_from_slice(&0i16.to_le_bytes());
+        // Channel mapping family for mono or stereo
+        head.push(0);
+        this.write_page(&head, 0, 0x02)?;
+
+        let vendor = b"chorus";
+        let mut tags = Vec::with_capacity(20 + vendor.len());
+        tags.extend_from_slice(b"OpusTags");
+        tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        tags.extend_from_slice(vendor);
+        // No user comments
+        tags.extend_from_slice(&0u32.to_le_bytes());
+        this.write_page(&tags, 0, 0)?;
+        Ok(this)
+    }
+
+    /// Appends an Opus packet.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the packet is malformed or too large for a
+    /// single page.
+    pub fn write_packet(&mut self, packet: &[u8]) -> io::Result<()> {
+        let samples = opus_packet_samples(packet)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Malformed Opus packet"))?;
+        // A page has room for 255 lacing values of 255 bytes, the last of which ends the packet
+        if packet.len() >= 255 * 255 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Opus packet is too large for an Ogg page",
+            ));
+        }
+        if let Some(pending) = self.pending.take() {
+            self.write_page(&pending, self.granule_position, 0)?;
+        }
+        self.granule_position += samples;
+        self.pending = Some(packet.to_vec());
+        Ok(())
+    }
+
+    /// Appends the given amount of silent samples per channel, rounded down to whole 20ms
+    /// packets.
+    pub fn write_silence(&mut self, samples_per_channel: u64) -> io::Result<()> {
+        for _ in 0..samples_per_channel / OPUS_SILENCE_SAMPLES {
+            self.write_packet(&OPUS_SILENCE)?;
+        }
+        Ok(())
+    }
+
+    /// The amount of samples per channel written so far.
+    pub fn samples_written(&self) -> u64 {
+        self.granule_position
+    }
+
+    /// Writes the last page, marking the end of the stream, and flushes the writer.
+    ///
+    /// Nothing can be written afterwards.
+    pub fn finish(&mut self) -> io::Result<()> {
+        let pending = self.pending.take().unwrap_or_default();
+        self.write_page(&pending, self.granule_position, 0x04)?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes a page containing a single packet, which may be empty.
+    fn write_page(&mut self, packet: &[u8], granule_position: u64, flags: u8) -> io::Result<()> {
+        let mut lacing = vec![255; packet.len() / 255];
+        if !packet.is_empty() || flags & 0x04 == 0 {
+            lacing.push((packet.len() % 255) as u8);
+        }
+
+        let mut page = Vec::with_capacity(27 + lacing.len() + packet.len());
+        page.extend_from_slice(b"OggS");
+        // Version
+        page.push(0);
+        page.push(flags);
+        page.extend_from_slice(&granule_position.to_le_bytes());
+        page.extend_from_slice(&self.serial.to_le_bytes());
+        page.extend_from_slice(&self.sequence.to_le_bytes());
+        // The checksum is calculated with the field set to 0
+        page.extend_from_slice(&0u32.to_le_bytes());
+        page.push(lacing.len() as u8);
+        page.extend_from_slice(&lacing);
+        page.extend_from_slice(packet);
+        let crc = ogg_crc(&page);
+        page[22..26].copy_from_slice(&crc.to_le_bytes());
+
+        self.writer.write_all(&page)?;
+        self.sequence += 1;
+        Ok(())
+    }
+}
+
+/// Records the Opus packets received on a voice connection into a separate Ogg Opus file per
+/// speaker, without decoding them.
+///
+/// Subscribe it to the [`rtp`](super::udp::events::VoiceUDPEvents::rtp) event of a voice UDP
+/// connection, and call [`OggOpusRecorder::finish`] once the recording should end.
+///
+/// Like with [`PerUserWavSink`], every file starts at the beginning of the recording, and pauses
+/// in speech are filled with silence according to the RTP timestamps, so that the files can be
+/// played in sync. As silence is written in whole 20ms packets, speakers may drift apart by up
+/// to 20ms. Packets which arrive after later ones have been written are dropped.
+///
+/// Clones share the same recording.
+pub struct OggOpusRecorder<W: Write> {
+    state: Arc<Mutex<OggRecorderState<W>>>,
+}
+
+impl<W: Write> Clone for OggOpusRecorder<W> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+struct OggRecorderState<W: Write> {
+    started_at: DateTime<Utc>,
+    create_writer: Box<dyn FnMut(Speaker) -> io::Result<W> + Send>,
+    /// By ssrc, with the RTP timestamp of the stream's first packet
+    files: HashMap<u32, (OggOpusWriter<W>, u32)>,
+    #[cfg(feature = "voice_gateway")]
+    ssrcs: Option<SsrcRegistry>,
+    finished: bool,
+}
+
+impl<W: Write> OggOpusRecorder<W> {
+    /// Creates a recorder which opens the file of a speaker with `create_writer`, once they start
+    /// speaking. The recording's timeline starts now.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use chorus::voice::recording::OggOpusRecorder;
+    ///
+    /// let recorder = OggOpusRecorder::new(|speaker| {
+    ///     let name = match speaker.user_id {
+    ///         Some(user_id) => format!("{}.opus", user_id),
+    ///         None => format!("ssrc-{}.opus", speaker.ssrc),
+    ///     };
+    ///     std::fs::File::create(name)
+    /// });
+    /// ```
+    pub fn new(create_writer: impl FnMut(Speaker) -> io::Result<W> + Send + 'static) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(OggRecorderState {
+                started_at: Utc::now(),
+                create_writer: Box::new(create_writer),
+                files: HashMap::new(),
+                #[cfg(feature = "voice_gateway")]
+                ssrcs: None,
+                finished: false,
+            })),
+        }
+    }
+
+    /// Looks up the users behind the recorded streams in the given registry, usually
+    /// [`VoiceGatewayHandle::ssrcs`](crate::voice::gateway::VoiceGatewayHandle::ssrcs).
+    #[cfg(feature = "voice_gateway")]
+    pub fn with_ssrc_registry(self, ssrcs: SsrcRegistry) -> Self {
+        self.state.lock().unwrap().ssrcs = Some(ssrcs);
+        self
+    }
+
+    /// Records an RTP packet, whose payload has already been decrypted.
+    pub fn record(&self, rtp: &Rtp) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.finished {
+            return Ok(());
+        }
+        let speaker = Speaker {
+            ssrc: rtp.ssrc,
+            #[cfg(feature = "voice_gateway")]
+            user_id: state
+                .ssrcs
+                .as_ref()
+                .and_then(|ssrcs| ssrcs.user_id(rtp.ssrc)),
+            #[cfg(not(feature = "voice_gateway"))]
+            user_id: None,
+        };
+        let OggRecorderState {
+            started_at,
+            create_writer,
+            files,
+            ..
+        } = &mut *state;
+
+        let timestamp = u32::from(rtp.timestamp);
+        let (file, start_timestamp) = match files.entry(rtp.ssrc) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let mut file = OggOpusWriter::new(create_writer(speaker)?)?;
+                let elapsed = (Utc::now() - *started_at).to_std().unwrap_or_default();
+                file.write_silence((elapsed.as_secs_f64() * SAMPLE_RATE as f64) as u64)?;
+                // The file starts with the first packet, after the silence
+                let start = timestamp.wrapping_sub(file.samples_written() as u32);
+                entry.insert((file, start))
+            }
+        };
+        // The wrapping difference treats packets from before the first one as far in the future;
+        // drop them instead
+        let position = timestamp.wrapping_sub(*start_timestamp);
+        if position > u32::MAX / 2 || (position as u64) < file.samples_written() {
+            return Ok(());
+        }
+        file.write_silence(position as u64 - file.samples_written())?;
+        let packet = strip_rtp_extension(&rtp.payload, rtp.extension != 0);
+        if let Err(e) = file.write_packet(packet) {
+            warn!(
+                "Recording: Failed to write packet of ssrc {}: {}",
+                rtp.ssrc, e
+            );
+        }
+        Ok(())
+    }
+
+    /// Ends the recording, finishing every file. Packets received afterwards are ignored.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.finished {
+            return Ok(());
+        }
+        state.finished = true;
+        for (file, _) in state.files.values_mut() {
+            file.finish()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> fmt::Debug for OggOpusRecorder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("OggOpusRecorder")
+            .field("started_at", &state.started_at)
+            .field("ssrcs", &state.files.keys().collect::<Vec<_>>())
+            .field("finished", &state.finished)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<W: Write + Send + 'static> Observer<Rtp> for OggOpusRecorder<W> {
+    async fn update(&self, data: &Rtp) {
+        if let Err(e) = self.record(data) {
+            warn!("Recording: Failed to write audio: {}", e);
+        }
+    }
+}
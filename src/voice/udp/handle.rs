@@ -4,8 +4,11 @@
 
 use std::sync::Arc;
 
+use aes_gcm::Aes256Gcm;
 use crypto_secretbox::{
-    aead::Aead, cipher::generic_array::GenericArray, KeyInit, XSalsa20Poly1305,
+    aead::{Aead, Payload},
+    cipher::generic_array::GenericArray,
+    KeyInit, XSalsa20Poly1305,
 };
 use discortp::Packet;
 
@@ -160,6 +163,23 @@ impl UdpHandle {
                 }
                 bytes
             }
+            VoiceEncryptionMode::AeadAes256Gcm => {
+                // Same incremental value as lite, but AES-GCM nonces are 12 bytes
+                let mut data_lock = self.data.write().await;
+                let nonce = data_lock
+                    .last_udp_encryption_nonce
+                    .unwrap_or_default()
+                    .wrapping_add(1);
+
+                data_lock.last_udp_encryption_nonce = Some(nonce);
+                drop(data_lock);
+                let mut bytes = nonce.to_be_bytes().to_vec();
+
+                while bytes.len() < 12 {
+                    bytes.push(0);
+                }
+                bytes
+            }
             _ => {
                 error!(
                     "This voice encryption mode ({:?}) is not yet implemented.",
@@ -181,17 +201,16 @@ impl UdpHandle {
             let encryptor = XSalsa20Poly1305::new(key);
 
             encryption_result = encryptor.encrypt(nonce, payload);
-        }
-        // Note: currently unused because I have no idea what the AeadAes256Gcm nonce is
-        /*else if session_description.encryption_mode.is_aead_aes256_gcm() {
+        } else if session_description.encryption_mode.is_aead_aes256_gcm() {
             let nonce = GenericArray::from_slice(&nonce_bytes);
 
             let encryptor = Aes256Gcm::new(key);
 
-            encryption_result = encryptor.encrypt(nonce, payload);
+            // The rtp header is authenticated, though not encrypted
+            let aad = &packet.packet()[0..RTP_HEADER_SIZE as usize];
 
-        }*/
-        else {
+            encryption_result = encryptor.encrypt(nonce, Payload { msg: payload, aad });
+        } else {
             error!(
                 "This voice encryption mode ({:?}) is not yet implemented.",
                 session_description.encryption_mode
@@ -214,7 +233,10 @@ impl UdpHandle {
         // Append the nonce bytes, if needed
         // All other encryption modes have an explicit nonce, whereas Xsalsa20Poly1305
         // has the nonce as the rtp header.
-        if session_description.encryption_mode != VoiceEncryptionMode::Xsalsa20Poly1305 {
+        if session_description.encryption_mode == VoiceEncryptionMode::AeadAes256Gcm {
+            // Only the incremental 4 bytes are sent, not the padding
+            encrypted_payload.extend_from_slice(&nonce_bytes[0..4]);
+        } else if session_description.encryption_mode != VoiceEncryptionMode::Xsalsa20Poly1305 {
             encrypted_payload.append(&mut nonce_bytes);
         }
 
@@ -2,9 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use crypto_secretbox::aead::Aead;
+use aes_gcm::Aes256Gcm;
+use crypto_secretbox::aead::{Aead, Payload};
 use crypto_secretbox::cipher::generic_array::GenericArray;
 use crypto_secretbox::KeyInit;
 use crypto_secretbox::XSalsa20Poly1305;
@@ -24,6 +25,7 @@ use super::UdpSocket;
 use super::RTP_HEADER_SIZE;
 use crate::errors::VoiceUdpError;
 use crate::types::VoiceEncryptionMode;
+use crate::voice::crypto::get_aead_aes256_gcm_nonce;
 use crate::voice::crypto::get_xsalsa20_poly1305_lite_nonce;
 use crate::voice::crypto::get_xsalsa20_poly1305_nonce;
 use crate::voice::crypto::get_xsalsa20_poly1305_suffix_nonce;
@@ -45,6 +47,9 @@ impl UdpHandler {
     /// Spawns a new UDP handler and performs IP discovery.
     ///
     /// Mutates the given data_reference with the IP discovery data.
+    ///
+    /// Dropping the returned future before it completes aborts the IP discovery. See
+    /// [`UdpHandler::spawn_with_timeout`] to bound how long to wait for the discovery response.
     pub async fn spawn(
         data_reference: Arc<RwLock<VoiceData>>,
         url: SocketAddr,
@@ -139,6 +144,19 @@ impl UdpHandler {
         })
     }
 
+    /// Like [`UdpHandler::spawn`], but fails with [`VoiceUdpError::IpDiscoveryTimedOut`] if IP
+    /// discovery does not complete within the given duration.
+    pub async fn spawn_with_timeout(
+        data_reference: Arc<RwLock<VoiceData>>,
+        url: SocketAddr,
+        ssrc: u32,
+        timeout: Duration,
+    ) -> Result<UdpHandle, VoiceUdpError> {
+        crate::with_timeout(timeout, UdpHandler::spawn(data_reference, url, ssrc))
+            .await
+            .unwrap_or(Err(VoiceUdpError::IpDiscoveryTimedOut))
+    }
+
     /// The main listen task;
     ///
     /// Receives UDP messages and parses them.
@@ -304,6 +322,11 @@ impl UdpHandler {
                 ciphertext = ciphertext[0..ciphertext.len() - 4].to_vec();
                 get_xsalsa20_poly1305_lite_nonce(packet_bytes)
             }
+            VoiceEncryptionMode::AeadAes256Gcm => {
+                // Remove the suffix from the ciphertext
+                ciphertext = ciphertext[0..ciphertext.len() - 4].to_vec();
+                get_aead_aes256_gcm_nonce(packet_bytes)
+            }
             _ => {
                 error!(
                     "This voice encryption mode ({:?}) is not yet implemented.",
@@ -325,17 +348,22 @@ impl UdpHandler {
             let decryptor = XSalsa20Poly1305::new(key);
 
             decryption_result = decryptor.decrypt(nonce, ciphertext.as_ref());
-        }
-        // Note: currently unused because I have no idea what the AeadAes256Gcm nonce is
-        /*else if session_description.encryption_mode.is_aead_aes256_gcm() {
+        } else if session_description.encryption_mode.is_aead_aes256_gcm() {
             let nonce = GenericArray::from_slice(&nonce_bytes);
 
             let decryptor = Aes256Gcm::new(key);
 
-            decryption_result = decryptor.decrypt(nonce, ciphertext.as_ref());
+            // The rtp header is authenticated, though not encrypted
+            let aad = &packet_bytes[0..RTP_HEADER_SIZE as usize];
 
-        }*/
-        else {
+            decryption_result = decryptor.decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext.as_ref(),
+                    aad,
+                },
+            );
+        } else {
             error!(
                 "This voice encryption mode ({:?}) is not yet implemented.",
                 session_description.encryption_mode
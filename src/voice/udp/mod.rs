@@ -12,7 +12,9 @@ pub mod backends;
 pub mod events;
 pub mod handle;
 pub mod handler;
+pub mod sender;
 
 pub use backends::*;
 pub use handle::*;
 pub use handler::*;
+pub use sender::*;
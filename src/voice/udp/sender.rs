@@ -0,0 +1,123 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::time::Duration;
+
+use getrandom::getrandom;
+use tokio::time::Instant;
+
+use crate::errors::VoiceUdpError;
+
+use super::UdpHandle;
+
+/// The length of the Opus frames an [`AudioSender`] sends.
+pub const OPUS_FRAME_DURATION: Duration = Duration::from_millis(20);
+
+/// The amount of samples (per channel) in one 20ms frame at the 48 kHz RTP clock rate of voice
+/// connections; the RTP timestamp advances by this much with every frame.
+pub const OPUS_FRAME_SAMPLES: u32 = 960;
+
+/// An Opus frame which decodes to silence.
+///
+/// See <https://discord-userdoccers.vercel.app/topics/voice-connections#voice-data-interpolation>
+pub const OPUS_SILENCE_FRAME: [u8; 3] = [0xF8, 0xFF, 0xFE];
+
+/// How many silence frames to send after the last frame of audio, so that other clients do not
+/// interpolate the gap.
+const SILENCE_FRAMES: usize = 5;
+
+/// Sends a stream of 20ms Opus frames over a voice UDP connection.
+///
+/// Keeps the RTP timestamp advancing by [`OPUS_FRAME_SAMPLES`] for every frame, while the
+/// [`UdpHandle`] numbers the packets. By default frames are paced, so each call to
+/// [`AudioSender::send_frame`] waits until it is time for the next frame to be sent, which lets
+/// the sender be fed as fast as frames can be encoded.
+///
+/// Remember to set the speaking flag with the voice gateway before sending audio.
+#[derive(Debug, Clone)]
+pub struct AudioSender {
+    handle: UdpHandle,
+    timestamp: u32,
+    pacing: bool,
+    next_frame_at: Option<Instant>,
+}
+
+impl AudioSender {
+    /// Creates a new sender with a random initial timestamp, as recommended by RFC 3550.
+    pub fn new(handle: UdpHandle) -> Self {
+        let mut timestamp = [0; 4];
+        // A failed random generation only makes the stream start at 0, which is still valid
+        let _ = getrandom(&mut timestamp);
+        Self {
+            handle,
+            timestamp: u32::from_be_bytes(timestamp),
+            pacing: true,
+            next_frame_at: None,
+        }
+    }
+
+    /// Sets whether [`AudioSender::send_frame`] waits for the frame's turn before sending it.
+    ///
+    /// Defaults to `true`. Disable pacing if frames are already produced in real time.
+    pub fn pacing(mut self, pacing: bool) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// The RTP timestamp the next frame will be sent with.
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// Sends a 20ms Opus frame.
+    ///
+    /// # Errors
+    /// See [`UdpHandle::send_opus_data`].
+    pub async fn send_frame(&mut self, frame: Vec<u8>) -> Result<(), VoiceUdpError> {
+        if self.pacing {
+            self.wait_for_next_frame().await;
+        }
+
+        self.handle.send_opus_data(self.timestamp, frame).await?;
+        self.timestamp = self.timestamp.wrapping_add(OPUS_FRAME_SAMPLES);
+        Ok(())
+    }
+
+    /// Sends five frames of silence, which should be done whenever the audio stops.
+    ///
+    /// # Errors
+    /// See [`UdpHandle::send_opus_data`].
+    pub async fn send_silence(&mut self) -> Result<(), VoiceUdpError> {
+        for _ in 0..SILENCE_FRAMES {
+            self.send_frame(OPUS_SILENCE_FRAME.to_vec()).await?;
+        }
+        // After a pause, the next frame should be sent right away
+        self.next_frame_at = None;
+        Ok(())
+    }
+
+    /// Advances the timestamp by the given amount of frames without sending anything, so that
+    /// receivers know how long the stream was paused.
+    pub fn skip_frames(&mut self, frames: u32) {
+        self.timestamp = self
+            .timestamp
+            .wrapping_add(frames.wrapping_mul(OPUS_FRAME_SAMPLES));
+        self.next_frame_at = None;
+    }
+
+    /// Waits until the next frame is due.
+    ///
+    /// If we have fallen more than a frame behind, the schedule restarts now rather than sending
+    /// the missed frames in a burst.
+    async fn wait_for_next_frame(&mut self) {
+        let now = Instant::now();
+        let due = match self.next_frame_at {
+            Some(due) if due + OPUS_FRAME_DURATION >= now => due,
+            _ => now,
+        };
+
+        tokio::time::sleep_until(due).await;
+        self.next_frame_at = Some(due + OPUS_FRAME_DURATION);
+    }
+}
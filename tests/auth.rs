@@ -74,7 +74,7 @@ async fn test_wrong_login() {
 async fn test_login_with_token() {
     let mut bundle = common::setup().await;
 
-    let token = &bundle.user.token;
+    let token = &bundle.user.token();
     let other_user = bundle
         .instance
         .login_with_token(token.clone())
@@ -84,7 +84,7 @@ async fn test_login_with_token() {
         bundle.user.object.read().unwrap().id,
         other_user.object.read().unwrap().id
     );
-    assert_eq!(bundle.user.token, other_user.token);
+    assert_eq!(bundle.user.token(), other_user.token());
 
     common::teardown(bundle).await;
 }
@@ -101,3 +101,100 @@ async fn test_login_with_invalid_token() {
 
     common::teardown(bundle).await;
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_token_providers() {
+    use chorus::token::{StaticToken, TokenCallback, TokenProvider};
+
+    let static_token = StaticToken("token".to_string());
+    assert_eq!(static_token.refresh("token").await, None);
+    assert_eq!(
+        static_token.refresh("rotated").await,
+        Some("token".to_string())
+    );
+
+    let callback = TokenCallback::new(|rejected: String| async move {
+        (rejected == "old").then(|| "new".to_string())
+    });
+    assert_eq!(callback.refresh("old").await, Some("new".to_string()));
+    assert_eq!(callback.refresh("new").await, None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_clones_share_refreshed_token() {
+    use chorus::token::StaticToken;
+
+    let mut bundle = common::setup().await;
+    let token = bundle.user.token();
+    let mut clone = bundle.user.clone();
+    clone.set_token_provider(StaticToken("rotated".to_string()));
+    assert!(clone.refresh_token().await);
+    assert_eq!(bundle.user.token(), "rotated");
+
+    bundle.user.set_token(token);
+    assert_eq!(bundle.user.token(), clone.token());
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_memory_token_store() {
+    use chorus::token::{MemoryTokenStore, TokenStore};
+
+    let store = MemoryTokenStore::default();
+    assert_eq!(store.get("user").unwrap(), None);
+    store.set("user", "token").unwrap();
+    store.set("user", "rotated").unwrap();
+    assert_eq!(store.get("user").unwrap().as_deref(), Some("rotated"));
+    store.remove("user").unwrap();
+    assert_eq!(store.get("user").unwrap(), None);
+}
+
+#[cfg(all(feature = "token_store", not(target_arch = "wasm32")))]
+#[test]
+fn test_encrypted_token_file() {
+    use chorus::errors::TokenStoreError;
+    use chorus::token::{EncryptedTokenFile, TokenStore};
+
+    let path = std::env::temp_dir().join(format!("chorus-tokens-{}.json", std::process::id()));
+    let store = EncryptedTokenFile::new(&path, "passphrase").iterations(1000);
+    assert_eq!(store.get("user").unwrap(), None);
+    store.set("user", "secret token").unwrap();
+    store.set("other", "other token").unwrap();
+
+    // The token is not stored in plain text
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("secret token"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Permissions are restored on every write
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        store.set("other", "other token").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    // Nothing is left behind by the atomic write
+    let mut temporary_path = path.clone().into_os_string();
+    temporary_path.push(".tmp");
+    assert!(!std::path::Path::new(&temporary_path).exists());
+
+    // Tokens are read with the iterations they were stored with
+    let reopened = EncryptedTokenFile::new(&path, "passphrase");
+    assert_eq!(
+        reopened.get("user").unwrap().as_deref(),
+        Some("secret token")
+    );
+    let wrong = EncryptedTokenFile::new(&path, "wrong").iterations(1000);
+    assert_eq!(wrong.get("user"), Err(TokenStoreError::Decryption));
+
+    store.remove("user").unwrap();
+    assert_eq!(store.get("user").unwrap(), None);
+    assert_eq!(store.get("other").unwrap().as_deref(), Some("other token"));
+    std::fs::remove_file(&path).unwrap();
+}
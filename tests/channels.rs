@@ -3,9 +3,10 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use chorus::types::{
-    self, Channel, GetChannelMessagesSchema, MessageSendSchema, PermissionFlags,
-    PermissionOverwrite, PrivateChannelCreateSchema, RelationshipType, Snowflake,
+    self, Channel, GetChannelMessagesSchema, MessageHistoryRange, MessageSendSchema,
+    PermissionFlags, PermissionOverwrite, PrivateChannelCreateSchema, RelationshipType, Snowflake,
 };
+use futures_util::StreamExt;
 
 mod common;
 
@@ -28,6 +29,26 @@ async fn get_channel() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_channel_raw() {
+    let mut bundle = common::setup().await;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let response = bundle
+        .user
+        .request_raw(
+            http::Method::GET,
+            &format!("/channels/{}", channel_id),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(response.status.is_success());
+    assert_eq!(response.body["id"], channel_id.to_string());
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn delete_channel() {
@@ -62,6 +83,9 @@ async fn modify_channel() {
         flags: None,
         default_thread_rate_limit_per_user: None,
         video_quality_mode: None,
+        default_sort_order: None,
+        default_forum_layout: None,
+        available_tags: None,
     };
     let modified_channel = channel
         .modify(modify_data, None, &mut bundle.user)
@@ -129,6 +153,18 @@ async fn get_channel_messages() {
         10
     );
 
+    assert_eq!(
+        Channel::messages(
+            GetChannelMessagesSchema::latest().limit(5),
+            channel_id,
+            &mut bundle.user,
+        )
+        .await
+        .unwrap()
+        .len(),
+        5
+    );
+
     // around is currently bugged in spacebar: https://github.com/spacebarchat/server/issues/1072
     // assert_eq!(
     //     Channel::messages(
@@ -154,6 +190,49 @@ async fn get_channel_messages() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn export_channel_history() {
+    let mut bundle = common::setup().await;
+    let channel_id: Snowflake = bundle.channel.read().unwrap().id;
+    let mut sent = Vec::new();
+    for i in 0..5 {
+        let message = bundle
+            .user
+            .send_message(
+                MessageSendSchema {
+                    content: Some(format!("Message {i}")),
+                    ..Default::default()
+                },
+                channel_id,
+            )
+            .await
+            .unwrap();
+        sent.push(message.id);
+    }
+
+    let exported: Vec<Snowflake> =
+        Channel::export_history(&mut bundle.user, channel_id, MessageHistoryRange::all())
+            .map(|message| message.unwrap().id)
+            .collect()
+            .await;
+    assert_eq!(exported, sent);
+
+    let mut jsonl = Vec::new();
+    let count = Channel::export_history_jsonl(
+        &mut bundle.user,
+        channel_id,
+        MessageHistoryRange::all().after(sent[0]).before(sent[4]),
+        &mut jsonl,
+    )
+    .await
+    .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(String::from_utf8(jsonl).unwrap().lines().count(), 3);
+
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn create_dm() {
@@ -269,3 +348,120 @@ async fn remove_add_person_from_to_dm() {
         user_id
     );
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn forum_tag_validation() {
+    use chorus::api::channels::ForumChannel;
+    use chorus::types::{ChannelFlags, ChannelType, Tag};
+
+    let tag = |id: u64, name: &str| Tag {
+        id: Snowflake(id),
+        name: name.to_string(),
+        moderated: false,
+        emoji_id: None,
+        emoji_name: None,
+    };
+    let text_channel = Channel {
+        channel_type: ChannelType::GuildText,
+        ..Default::default()
+    };
+    assert!(ForumChannel::new(text_channel).is_none());
+
+    let forum = ForumChannel::new(Channel {
+        channel_type: ChannelType::GuildForum,
+        available_tags: Some(vec![tag(1, "Bug"), tag(2, "Question")]),
+        flags: Some(ChannelFlags::REQUIRE_TAG.bits() as i32),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(forum.requires_tag());
+    assert_eq!(forum.tag_by_name("question").unwrap().id, Snowflake(2));
+
+    assert!(forum.validate_tags(&[]).is_err());
+    assert!(forum.validate_tags(&[Snowflake(3)]).is_err());
+    let applied = forum.validate_tags(&[Snowflake(1)]).unwrap();
+    assert_eq!(applied[0].name, "Bug");
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn overwrite_template_remapping() {
+    use chorus::api::channels::{OverwriteTarget, OverwriteTemplate};
+    use chorus::types::{IntoShared, RoleObject};
+
+    let overwrite = |id: u64, overwrite_type: &str, allow: &str| PermissionOverwrite {
+        id: Snowflake(id),
+        overwrite_type: overwrite_type.to_string(),
+        allow: allow.to_string(),
+        deny: "0".to_string(),
+    };
+    let role = |id: u64, name: &str, position: u16| RoleObject {
+        id: Snowflake(id),
+        name: name.to_string(),
+        position,
+        ..Default::default()
+    };
+    let channel = Channel {
+        guild_id: Some(Snowflake(1)),
+        permission_overwrites: Some(vec![
+            overwrite(1, "0", "1024").into_shared(),
+            overwrite(10, "0", "2048").into_shared(),
+            overwrite(11, "0", "8").into_shared(),
+            overwrite(50, "1", "16").into_shared(),
+        ]),
+        ..Default::default()
+    };
+    let template = OverwriteTemplate::capture(
+        &channel,
+        &[
+            role(1, "@everyone", 0),
+            role(10, "Moderator", 2),
+            role(11, "Bot", 1),
+        ],
+    );
+    assert_eq!(template.overwrites[0].target, OverwriteTarget::Everyone);
+    assert_eq!(
+        template.overwrites[1].target,
+        OverwriteTarget::Role {
+            id: Snowflake(10),
+            name: Some("Moderator".to_string())
+        }
+    );
+
+    // In another guild, roles are matched by name, preferring the highest role
+    let resolved = template.resolve(
+        Snowflake(2),
+        &[
+            role(2, "@everyone", 0),
+            role(20, "Moderator", 1),
+            role(21, "Moderator", 3),
+        ],
+    );
+    assert_eq!(resolved.missing_roles, vec!["Bot".to_string()]);
+    let overwrites: Vec<(Snowflake, &str, &str)> = resolved
+        .overwrites
+        .iter()
+        .map(|o| (o.id, o.overwrite_type.as_str(), o.allow.as_str()))
+        .collect();
+    assert_eq!(
+        overwrites,
+        vec![
+            (Snowflake(2), "0", "1024"),
+            (Snowflake(21), "0", "2048"),
+            (Snowflake(50), "1", "16"),
+        ]
+    );
+
+    // In the same guild, roles keep their ids even if they were renamed
+    let resolved = template.resolve(
+        Snowflake(1),
+        &[
+            role(1, "@everyone", 0),
+            role(10, "Mod", 2),
+            role(11, "Bot", 1),
+        ],
+    );
+    assert!(resolved.missing_roles.is_empty());
+    assert_eq!(resolved.overwrites[1].id, Snowflake(10));
+}
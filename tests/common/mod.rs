@@ -7,7 +7,7 @@ use chorus::types::IntoShared;
 use chorus::{
     instance::{ChorusUser, Instance},
     types::{
-        Channel, ChannelCreateSchema, Guild, GuildCreateSchema, RegisterSchema,
+        Channel, ChannelCreateSchema, ChannelFlags, Guild, GuildCreateSchema, RegisterSchema,
         RoleCreateModifySchema, RoleObject,
     },
     UrlBundle,
@@ -49,6 +49,10 @@ impl TestBundle {
             gateway: Gateway::spawn(self.instance.urls.wss.clone())
                 .await
                 .unwrap(),
+            request_limiter: self.user.request_limiter.clone(),
+            token_provider: self.user.token_provider.clone(),
+            guild_members: self.user.guild_members.clone(),
+            check_permissions: self.user.check_permissions,
         }
     }
 }
@@ -88,9 +92,12 @@ pub(crate) async fn setup() -> TestBundle {
         rtc_region: None,
         default_auto_archive_duration: None,
         default_reaction_emoji: None,
-        flags: Some(0),
+        flags: Some(ChannelFlags::empty()),
         default_thread_rate_limit_per_user: Some(0),
         video_quality_mode: None,
+        default_sort_order: None,
+        default_forum_layout: None,
+        available_tags: None,
     };
     let mut user = instance.clone().register_account(reg).await.unwrap();
     let guild = Guild::create(&mut user, guild_create_schema).await.unwrap();
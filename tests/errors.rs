@@ -0,0 +1,103 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::error::Error;
+
+use chorus::errors::{ApiError, ChorusError, GatewayError, VerificationMethod};
+use chorus::types::Snowflake;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::*;
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn api_error_from_response_body() {
+    let error =
+        ApiError::from_response_body(403, r#"{"code": 50013, "message": "Missing Permissions"}"#);
+    assert_eq!(error.status, 403);
+    assert_eq!(error.code, Some(ApiError::MISSING_PERMISSIONS));
+    assert_eq!(error.message, "Missing Permissions");
+    assert_eq!(
+        error.to_string(),
+        "HTTP 403 (code 50013): Missing Permissions"
+    );
+
+    let error = ApiError::from_response_body(502, "Bad Gateway");
+    assert_eq!(error.code, None);
+    assert_eq!(error.message, "Bad Gateway");
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn chorus_error_classification() {
+    let forbidden = ChorusError::NoPermission {
+        route: "/api/guilds/1".to_string(),
+        source: ApiError::from_response_body(
+            403,
+            r#"{"code": 50013, "message": "Missing Permissions"}"#,
+        ),
+    };
+    assert!(forbidden.is_permission_error());
+    assert!(!forbidden.is_retryable());
+    assert_eq!(forbidden.route(), Some("/api/guilds/1"));
+    assert_eq!(
+        forbidden.source().unwrap().to_string(),
+        "HTTP 403 (code 50013): Missing Permissions"
+    );
+
+    let server_error = ChorusError::ReceivedErrorCode {
+        route: "/api/users/@me".to_string(),
+        source: ApiError::from_response_body(503, ""),
+    };
+    assert!(server_error.is_retryable());
+    assert!(!server_error.is_permission_error());
+    assert_eq!(server_error.api_error().unwrap().status, 503);
+
+    let rate_limited = ChorusError::RateLimited {
+        bucket: "Global".to_string(),
+    };
+    assert!(rate_limited.is_retryable());
+    assert!(rate_limited.api_error().is_none());
+    assert!(rate_limited.source().is_none());
+
+    // The message has been sent, so retrying would send it again
+    let publish_failed = ChorusError::PublishFailed {
+        message_id: Snowflake(1),
+        error: Box::new(rate_limited),
+    };
+    assert!(!publish_failed.is_retryable());
+    assert_eq!(
+        publish_failed.to_string(),
+        "The message 1 was sent, but publishing it failed: Ratelimited on Bucket Global"
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn verification_required_field_error() {
+    let error = ApiError::from_response_body(
+        400,
+        r#"{"code": 50035, "message": "Invalid Form Body", "errors": {"login": {"_errors": [{"code": "ACCOUNT_LOGIN_VERIFICATION_EMAIL", "message": "Email verification is required"}]}}}"#,
+    );
+    assert_eq!(error.message, "Invalid Form Body");
+    assert!(error.has_field_error("ACCOUNT_LOGIN_VERIFICATION_EMAIL"));
+    assert_eq!(
+        VerificationMethod::from_api_error(&error),
+        Some(VerificationMethod::Email)
+    );
+
+    let error = ApiError::from_response_body(400, r#"{"code": 50035, "message": "Invalid"}"#);
+    assert!(error.field_error_codes.is_empty());
+    assert_eq!(VerificationMethod::from_api_error(&error), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn gateway_error_close_code() {
+    assert_eq!(GatewayError::AuthenticationFailed.close_code(), Some(4004));
+    assert_eq!(GatewayError::DisallowedIntents.close_code(), Some(4014));
+    assert_eq!(GatewayError::ConnectionTimedOut.close_code(), None);
+}
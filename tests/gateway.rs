@@ -12,7 +12,7 @@ use chorus::errors::GatewayError;
 use chorus::gateway::*;
 use chorus::types::{
     self, Channel, ChannelCreateSchema, ChannelModifySchema, GatewayReady, IntoShared,
-    RoleCreateModifySchema, RoleObject,
+    RoleCreateModifySchema, RoleObject, Snowflake,
 };
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
@@ -34,6 +34,1633 @@ async fn test_gateway_establish() {
     common::teardown(bundle).await
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that connecting to a server which never completes the handshake times out
+async fn test_gateway_spawn_timeout() {
+    // The listener accepts the tcp connection, but never answers the websocket handshake
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let result = Gateway::spawn_with_timeout(url, Duration::from_millis(200)).await;
+    assert_eq!(result.err(), Some(GatewayError::ConnectionTimedOut));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that additional headers are sent along with the websocket handshake
+async fn test_gateway_handshake_headers() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buffer = [0; 4096];
+        let read = std::io::Read::read(&mut stream, &mut buffer).unwrap();
+        String::from_utf8_lossy(&buffer[..read]).to_lowercase()
+    });
+
+    let mut headers = http::HeaderMap::new();
+    headers.insert(
+        "cf-access-client-id",
+        http::HeaderValue::from_static("chorus"),
+    );
+    // The server closes the connection after reading the handshake, so this fails
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        Gateway::spawn_with_headers(url, &headers),
+    );
+    assert!(matches!(result.await, Ok(Err(_))));
+
+    let handshake = server.join().unwrap();
+    assert!(handshake.contains("cf-access-client-id: chorus"));
+}
+
+/// A [`WebSocketTransport`] which hands out in-memory connections, so the gateway can be tested
+/// without a server.
+#[derive(Debug)]
+struct MemoryTransport {
+    receive: std::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<GatewayMessage>>>,
+    sent: tokio::sync::mpsc::UnboundedSender<GatewayMessage>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl WebSocketTransport for MemoryTransport {
+    async fn connect(
+        &self,
+        _url: &str,
+        _headers: &http::HeaderMap,
+    ) -> Result<(Sink, Stream), GatewayError> {
+        let receive = self.receive.lock().unwrap().take().unwrap();
+        let sink = futures_util::sink::unfold(self.sent.clone(), |sent, message| async move {
+            sent.send(message).unwrap();
+            Ok::<_, GatewayError>(sent)
+        });
+        let stream = futures_util::stream::unfold(receive, |mut receive| async move {
+            let message = receive.recv().await?;
+            Some((Ok(message), receive))
+        });
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
+
+/// Like [`MemoryTransport`], but opens the next of the given connections every time it connects,
+/// recording the urls it connected to
+#[derive(Debug)]
+struct ReconnectingMemoryTransport {
+    connections: std::sync::Mutex<
+        std::collections::VecDeque<tokio::sync::mpsc::UnboundedReceiver<GatewayMessage>>,
+    >,
+    sent: tokio::sync::mpsc::UnboundedSender<GatewayMessage>,
+    urls: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl WebSocketTransport for ReconnectingMemoryTransport {
+    async fn connect(
+        &self,
+        url: &str,
+        _headers: &http::HeaderMap,
+    ) -> Result<(Sink, Stream), GatewayError> {
+        self.urls.lock().unwrap().push(url.to_string());
+        let receive = self
+            .connections
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| GatewayError::CannotConnect {
+                error: "No more connections".to_string(),
+            })?;
+        let sink = futures_util::sink::unfold(self.sent.clone(), |sent, message| async move {
+            sent.send(message).unwrap();
+            Ok::<_, GatewayError>(sent)
+        });
+        let stream = futures_util::stream::unfold(receive, |mut receive| async move {
+            let message = receive.recv().await?;
+            Some((Ok(message), receive))
+        });
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+}
+
+#[derive(Debug)]
+struct GatewayDispatchObserver {
+    channel: tokio::sync::mpsc::Sender<types::GatewayDispatch>,
+}
+
+#[async_trait]
+impl Observer<types::GatewayDispatch> for GatewayDispatchObserver {
+    async fn update(&self, data: &types::GatewayDispatch) {
+        self.channel.send(data.clone()).await.unwrap();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests running the gateway on a custom transport
+async fn test_gateway_custom_transport() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+
+    let gateway = Gateway::spawn_with_transport(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+    )
+    .await
+    .unwrap();
+
+    let (dispatch_send, mut dispatch_receive) = tokio::sync::mpsc::channel(1);
+    gateway
+        .events
+        .lock()
+        .await
+        .dispatch
+        .subscribe(Arc::new(GatewayDispatchObserver {
+            channel: dispatch_send,
+        }));
+
+    gateway
+        .send_identify(types::GatewayIdentifyPayload::common())
+        .await;
+    let identify = tokio::time::timeout(Duration::from_secs(5), server_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(identify.text().unwrap().contains(r#""op":2"#));
+
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"SESSIONS_REPLACE","s":1,"d":[]}"#.to_string(),
+        ))
+        .unwrap();
+    let dispatch = tokio::time::timeout(Duration::from_secs(5), dispatch_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(dispatch.event_name, "SESSIONS_REPLACE");
+    assert_eq!(dispatch.sequence_number, Some(1));
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests subscribing closures to events, and unsubscribing them again
+async fn test_gateway_subscribe_fn() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_transport(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        Arc::new(MemoryTransport {
+            receive: std::sync::Mutex::new(Some(receive)),
+            sent,
+        }),
+    )
+    .await
+    .unwrap();
+
+    let (first_send, mut first_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (second_send, mut second_receive) = tokio::sync::mpsc::unbounded_channel();
+    let first = gateway.events.lock().await.session.replace.subscribe_fn(
+        move |event: types::SessionsReplace| {
+            let first_send = first_send.clone();
+            async move {
+                first_send.send(event.sessions.len()).unwrap();
+            }
+        },
+    );
+    gateway
+        .events
+        .lock()
+        .await
+        .session
+        .replace
+        .subscribe_fn(move |_| {
+            let second_send = second_send.clone();
+            async move {
+                second_send.send(()).unwrap();
+            }
+        });
+
+    server_send.send(dispatch(1)).unwrap();
+    let sessions = tokio::time::timeout(Duration::from_secs(5), first_receive.recv())
+        .await
+        .unwrap();
+    assert_eq!(sessions, Some(0));
+    second_receive.recv().await.unwrap();
+
+    // Only the first closure is unsubscribed
+    gateway
+        .events
+        .lock()
+        .await
+        .session
+        .replace
+        .unsubscribe(first.as_ref());
+    server_send.send(dispatch(2)).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), second_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(first_receive.try_recv().is_err());
+    gateway.close().await;
+}
+
+/// Forwards the sequence numbers of dispatches as soon as they are handled, but only finishes
+/// handling them once released.
+#[derive(Debug)]
+struct BlockingDispatchObserver {
+    release: Arc<tokio::sync::Semaphore>,
+    channel: tokio::sync::mpsc::UnboundedSender<Option<u64>>,
+}
+
+#[async_trait]
+impl Observer<types::GatewayDispatch> for BlockingDispatchObserver {
+    async fn update(&self, data: &types::GatewayDispatch) {
+        self.channel.send(data.sequence_number).unwrap();
+        self.release.acquire().await.unwrap().forget();
+    }
+}
+
+fn dispatch(sequence_number: u64) -> GatewayMessage {
+    GatewayMessage::Text(format!(
+        r#"{{"op":0,"t":"SESSIONS_REPLACE","s":{},"d":[]}}"#,
+        sequence_number
+    ))
+}
+
+/// Spawns a gateway on a [`MemoryTransport`] with the given event buffer, and waits until it
+/// handles a first dispatch, which is blocked until released.
+async fn spawn_blocked_gateway(
+    event_buffer: EventBufferConfig,
+) -> (
+    GatewayHandle,
+    tokio::sync::mpsc::UnboundedSender<GatewayMessage>,
+    Arc<tokio::sync::Semaphore>,
+    tokio::sync::mpsc::UnboundedReceiver<Option<u64>>,
+) {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+        GatewayOptions {
+            event_buffer,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let release = Arc::new(tokio::sync::Semaphore::new(0));
+    let (dispatch_send, mut dispatch_receive) = tokio::sync::mpsc::unbounded_channel();
+    gateway
+        .events
+        .lock()
+        .await
+        .dispatch
+        .subscribe(Arc::new(BlockingDispatchObserver {
+            release: release.clone(),
+            channel: dispatch_send,
+        }));
+
+    server_send.send(dispatch(1)).unwrap();
+    let handled = tokio::time::timeout(Duration::from_secs(5), dispatch_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(handled, Some(1));
+    (gateway, server_send, release, dispatch_receive)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the oldest dispatches are dropped and counted if the event buffer is full
+async fn test_gateway_event_buffer_drop_oldest() {
+    let (gateway, server_send, release, mut dispatch_receive) =
+        spawn_blocked_gateway(EventBufferConfig {
+            capacity: 1,
+            overflow_policy: OverflowPolicy::DropOldest,
+        })
+        .await;
+
+    // The first dispatch is still being handled, so the second and third are dropped
+    for sequence_number in 2..=4 {
+        server_send.send(dispatch(sequence_number)).unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while gateway.dropped_events() < 2 {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(gateway.buffered_events(), 1);
+
+    release.add_permits(4);
+    let handled = tokio::time::timeout(Duration::from_secs(5), dispatch_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(handled, Some(4));
+    assert_eq!(gateway.dropped_events(), 2);
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the connection is closed if the event buffer overflows with the error policy
+async fn test_gateway_event_buffer_error() {
+    let (gateway, server_send, release, _dispatch_receive) =
+        spawn_blocked_gateway(EventBufferConfig {
+            capacity: 1,
+            overflow_policy: OverflowPolicy::Error,
+        })
+        .await;
+
+    server_send.send(dispatch(2)).unwrap();
+    server_send.send(dispatch(3)).unwrap();
+    release.add_permits(4);
+    let mut state = gateway.watch_state();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        state.wait_for(|state| state.is_closed()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(gateway.dropped_events(), 0);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that heartbeats are sent with a jittered first delay, in the interval from hello, and
+/// with the last sequence number
+async fn test_gateway_heartbeats() {
+    let interval = Duration::from_millis(200);
+    for _ in 0..100 {
+        assert!(heartbeat::first_heartbeat_delay(interval) < interval);
+    }
+
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":200}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_transport(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+    )
+    .await
+    .unwrap();
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"SESSIONS_REPLACE","s":5,"d":[]}"#.to_string(),
+        ))
+        .unwrap();
+
+    // The first heartbeat may be sent before the dispatch has been handled
+    let first = tokio::time::timeout(Duration::from_secs(5), server_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(first.text().unwrap().contains(r#""op":1"#));
+    server_send
+        .send(GatewayMessage::Text(r#"{"op":11}"#.to_string()))
+        .unwrap();
+
+    let second = tokio::time::timeout(Duration::from_secs(5), server_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let second: serde_json::Value = serde_json::from_str(second.text().unwrap()).unwrap();
+    assert_eq!(second, serde_json::json!({"op": 1, "d": 5}));
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the identify options of the gateway options are sent when identifying
+async fn test_gateway_identify_options() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+        GatewayOptions {
+            large_threshold: Some(250),
+            guild_subscriptions: Some(false),
+            presence: Some(types::UpdatePresence {
+                status: types::UserStatus::Dnd,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    // Options set by the payload itself take precedence
+    gateway
+        .send_identify(types::GatewayIdentifyPayload {
+            large_threshold: Some(100),
+            ..types::GatewayIdentifyPayload::common()
+        })
+        .await;
+    let identify = loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), server_receive.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        if message.op_code() == Some(2) {
+            break message;
+        }
+    };
+    let identify: serde_json::Value = serde_json::from_str(identify.text().unwrap()).unwrap();
+    assert_eq!(identify["d"]["large_threshold"], 100);
+    assert_eq!(identify["d"]["guild_subscriptions"], false);
+    assert_eq!(identify["d"]["presence"]["status"], "dnd");
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the sessions of the user are tracked from SESSIONS_REPLACE
+async fn test_gateway_sessions() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_transport(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+    )
+    .await
+    .unwrap();
+    assert!(gateway.sessions().is_empty());
+
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"SESSIONS_REPLACE","s":1,"d":[
+                {"activities":[],"client_info":{"client":"unknown","os":"unknown","version":0},"session_id":"all","status":"dnd","active":true},
+                {"activities":[],"client_info":{"client":"desktop","os":"linux","version":0},"session_id":"1","status":"dnd"},
+                {"activities":[],"client_info":{"client":"mobile","os":"android","version":0},"session_id":"2","status":"idle"}
+            ]}"#
+            .to_string(),
+        ))
+        .unwrap();
+    let sessions = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let sessions = gateway.sessions();
+            if !sessions.is_empty() {
+                return sessions;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(sessions.len(), 3);
+    assert!(sessions[0].is_summary());
+    assert_eq!(sessions[0].active, Some(true));
+    assert_eq!(sessions[1].platform(), types::SessionPlatform::Desktop);
+    assert_eq!(sessions[1].user_status(), Some(types::UserStatus::Dnd));
+    assert_eq!(sessions[2].platform(), types::SessionPlatform::Mobile);
+    gateway.close().await;
+}
+
+#[derive(Debug)]
+struct GuildListDiffObserver {
+    roles: tokio::sync::mpsc::UnboundedSender<types::GuildRolesDiff>,
+    emojis: tokio::sync::mpsc::UnboundedSender<types::GuildEmojisDiff>,
+    stickers: tokio::sync::mpsc::UnboundedSender<types::GuildStickersDiff>,
+}
+
+impl GuildListDiffObserver {
+    /// Subscribes a new observer to all guild list diffs, returning the receivers of the roles,
+    /// emojis and stickers diffs.
+    async fn subscribe(
+        gateway: &GatewayHandle,
+    ) -> (
+        tokio::sync::mpsc::UnboundedReceiver<types::GuildRolesDiff>,
+        tokio::sync::mpsc::UnboundedReceiver<types::GuildEmojisDiff>,
+        tokio::sync::mpsc::UnboundedReceiver<types::GuildStickersDiff>,
+    ) {
+        let (roles, roles_receive) = tokio::sync::mpsc::unbounded_channel();
+        let (emojis, emojis_receive) = tokio::sync::mpsc::unbounded_channel();
+        let (stickers, stickers_receive) = tokio::sync::mpsc::unbounded_channel();
+        let observer = Arc::new(GuildListDiffObserver {
+            roles,
+            emojis,
+            stickers,
+        });
+        let mut events = gateway.events.lock().await;
+        events.guild.roles_diff.subscribe(observer.clone());
+        events.guild.emojis_diff.subscribe(observer.clone());
+        events.guild.stickers_diff.subscribe(observer);
+        (roles_receive, emojis_receive, stickers_receive)
+    }
+}
+
+#[async_trait]
+impl Observer<types::GuildRolesDiff> for GuildListDiffObserver {
+    async fn update(&self, data: &types::GuildRolesDiff) {
+        self.roles.send(data.clone()).unwrap();
+    }
+}
+
+#[async_trait]
+impl Observer<types::GuildEmojisDiff> for GuildListDiffObserver {
+    async fn update(&self, data: &types::GuildEmojisDiff) {
+        self.emojis.send(data.clone()).unwrap();
+    }
+}
+
+#[async_trait]
+impl Observer<types::GuildStickersDiff> for GuildListDiffObserver {
+    async fn update(&self, data: &types::GuildStickersDiff) {
+        self.stickers.send(data.clone()).unwrap();
+    }
+}
+
+const STICKER_JSON: &str =
+    r#"{"id":"30","name":"sticker","tags":"wave","type":2,"format_type":1,"guild_id":"1"}"#;
+
+fn role(id: u64, name: &str) -> RoleObject {
+    RoleObject {
+        id: Snowflake(id),
+        name: name.to_string(),
+        permissions: "0".to_string(),
+        ..Default::default()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn test_gateway_guild_list_diffs() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_transport(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+    )
+    .await
+    .unwrap();
+
+    let guild = types::Guild {
+        id: Snowflake(1),
+        roles: Some(vec![
+            role(10, "a").into_shared(),
+            role(11, "b").into_shared(),
+        ]),
+        emojis: vec![types::Emoji {
+            id: Snowflake(20),
+            name: Some("old".to_string()),
+            ..Default::default()
+        }
+        .into_shared()],
+        ..Default::default()
+    };
+    let guild = guild.into_shared();
+    // Concurrent observers of the same guild share it
+    let (guild, concurrent) = tokio::join!(
+        gateway.observe(guild.clone()),
+        gateway.observe(guild.clone())
+    );
+    assert!(Arc::ptr_eq(&guild, &concurrent));
+
+    let (mut roles_receive, mut emojis_receive, mut stickers_receive) =
+        GuildListDiffObserver::subscribe(&gateway).await;
+
+    let role_json = |id: u64, name: &str| {
+        format!(
+            r#"{{"id":"{id}","name":"{name}","color":0,"hoist":false,"position":0,"permissions":"0","managed":false,"mentionable":false}}"#
+        )
+    };
+    for (event_name, data) in [
+        (
+            "GUILD_ROLE_UPDATE",
+            format!(r#"{{"guild_id":"1","role":{}}}"#, role_json(10, "c")),
+        ),
+        (
+            "GUILD_ROLE_CREATE",
+            format!(r#"{{"guild_id":"1","role":{}}}"#, role_json(12, "d")),
+        ),
+        (
+            "GUILD_ROLE_DELETE",
+            r#"{"guild_id":"1","role_id":"11"}"#.to_string(),
+        ),
+        (
+            "GUILD_EMOJIS_UPDATE",
+            r#"{"guild_id":"1","emojis":[{"id":"21","name":"new"}]}"#.to_string(),
+        ),
+        (
+            "GUILD_STICKERS_UPDATE",
+            format!(r#"{{"guild_id":"1","stickers":[{}]}}"#, STICKER_JSON),
+        ),
+    ] {
+        server_send
+            .send(GatewayMessage::Text(format!(
+                r#"{{"op":0,"t":"{event_name}","s":1,"d":{data}}}"#
+            )))
+            .unwrap();
+    }
+
+    let receive_roles = async {
+        let mut diffs = Vec::new();
+        for _ in 0..3 {
+            diffs.push(roles_receive.recv().await.unwrap());
+        }
+        diffs
+    };
+    let roles = tokio::time::timeout(Duration::from_secs(5), receive_roles)
+        .await
+        .unwrap();
+    assert_eq!(roles[0].roles.changed, vec![(role(10, "a"), role(10, "c"))]);
+    assert!(roles[0].roles.added.is_empty() && roles[0].roles.removed.is_empty());
+    assert_eq!(roles[1].roles.added, vec![role(12, "d")]);
+    assert_eq!(roles[2].roles.removed, vec![role(11, "b")]);
+
+    let emojis = tokio::time::timeout(Duration::from_secs(5), emojis_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(emojis.guild_id, Snowflake(1));
+    assert_eq!(emojis.emojis.added[0].id, Snowflake(21));
+    assert_eq!(emojis.emojis.removed[0].id, Snowflake(20));
+
+    let stickers = tokio::time::timeout(Duration::from_secs(5), stickers_receive.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stickers.stickers.added[0].id, Snowflake(30));
+    assert!(stickers.stickers.removed.is_empty());
+
+    let guild = guild.read().unwrap().clone();
+    let role_names: Vec<String> = guild
+        .roles
+        .iter()
+        .flatten()
+        .map(|role| role.read().unwrap().name.clone())
+        .collect();
+    assert_eq!(role_names, vec!["c", "d"]);
+    assert_eq!(guild.emojis.len(), 1);
+    assert_eq!(guild.emojis[0].read().unwrap().id, Snowflake(21));
+    assert_eq!(guild.stickers.unwrap()[0].id, Snowflake(30));
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+async fn test_gateway_message_cache() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+        GatewayOptions {
+            message_cache: Some(MessageCacheConfig {
+                capacity_per_channel: 2,
+                max_revisions: 1,
+            }),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let cache = gateway.message_cache().unwrap();
+
+    let message = |event_name: &str, id: u64, content: &str| {
+        GatewayMessage::Text(format!(
+            r#"{{"op":0,"t":"{event_name}","s":1,"d":{{"id":"{id}","channel_id":"1","content":"{content}","timestamp":"2024-01-01T00:00:00Z","tts":false,"mention_everyone":false,"pinned":false,"type":0}}}}"#
+        ))
+    };
+    for message in [
+        message("MESSAGE_CREATE", 10, "first"),
+        message("MESSAGE_CREATE", 11, "second"),
+        message("MESSAGE_CREATE", 12, "third"),
+        message("MESSAGE_UPDATE", 11, "edited"),
+        message("MESSAGE_UPDATE", 11, "edited again"),
+        // Neither a repeated update nor one with only some of the fields is an edit
+        message("MESSAGE_UPDATE", 12, "third"),
+        GatewayMessage::Text(
+            r#"{"op":0,"t":"MESSAGE_UPDATE","s":1,"d":{"id":"12","channel_id":"1","flags":4}}"#
+                .to_string(),
+        ),
+        GatewayMessage::Text(
+            r#"{"op":0,"t":"MESSAGE_DELETE","s":1,"d":{"id":"11","channel_id":"1"}}"#.to_string(),
+        ),
+    ] {
+        server_send.send(message).unwrap();
+    }
+    let deleted = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match cache.get(Snowflake(1), Snowflake(11)) {
+                Some(cached) if cached.deleted => return cached,
+                _ => sleep(Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(deleted.message.content.as_deref(), Some("edited again"));
+    assert_eq!(deleted.revisions.len(), 1);
+    assert_eq!(deleted.original().content.as_deref(), Some("edited"));
+    // The oldest message was evicted
+    let ids: Vec<Snowflake> = cache
+        .channel(Snowflake(1))
+        .iter()
+        .map(|cached| cached.message.id)
+        .collect();
+    assert_eq!(ids, vec![Snowflake(11), Snowflake(12)]);
+    let partially_updated = cache.get(Snowflake(1), Snowflake(12)).unwrap();
+    assert!(!partially_updated.is_edited());
+    assert_eq!(partially_updated.message.content.as_deref(), Some("third"));
+    assert_eq!(partially_updated.message.timestamp, "2024-01-01T00:00:00Z");
+    assert_eq!(partially_updated.message.flags, Some(4));
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that online members are kept, and that only the most recently used offline members are
+async fn test_gateway_member_cache() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+        GatewayOptions {
+            member_cache: Some(MemberCacheConfig {
+                max_offline_members_per_guild: 2,
+                keep_offline_presences: false,
+            }),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let cache = gateway.member_cache().unwrap();
+
+    let user = |id: u64| format!(r#"{{"id":"{id}","username":"user{id}","discriminator":"0"}}"#);
+    let member = |id: u64| {
+        format!(
+            r#"{{"user":{},"roles":[],"joined_at":"2024-01-01T00:00:00Z","deaf":false,"mute":false}}"#,
+            user(id)
+        )
+    };
+    let presence = |id: u64, status: &str| {
+        format!(
+            r#"{{"user":{},"guild_id":"1","status":"{status}","activities":[],"client_status":{{}}}}"#,
+            user(id)
+        )
+    };
+    let dispatch = |event_name: &str, data: String| {
+        GatewayMessage::Text(format!(r#"{{"op":0,"t":"{event_name}","s":1,"d":{data}}}"#))
+    };
+    for message in [
+        dispatch(
+            "GUILD_MEMBERS_CHUNK",
+            format!(
+                r#"{{"guild_id":"1","members":[{},{},{},{}],"chunk_index":0,"chunk_count":1,"presences":[{}]}}"#,
+                member(1),
+                member(2),
+                member(3),
+                member(4),
+                presence(1, "online")
+            ),
+        ),
+        dispatch(
+            "GUILD_MEMBER_UPDATE",
+            format!(
+                r#"{{"guild_id":"1","roles":[],"user":{},"nick":"renamed"}}"#,
+                user(3)
+            ),
+        ),
+        dispatch("PRESENCE_UPDATE", presence(1, "offline")),
+    ] {
+        server_send.send(message).unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while cache.len(Snowflake(1)) != 2 || cache.presence(Snowflake(1), Snowflake(1)).is_some() {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    // The least recently used offline members were evicted, the renamed one was used since
+    assert!(cache.get(Snowflake(1), Snowflake(2)).is_none());
+    assert!(cache.get(Snowflake(1), Snowflake(4)).is_none());
+    let renamed = cache.get(Snowflake(1), Snowflake(3)).unwrap();
+    assert_eq!(renamed.nick.as_deref(), Some("renamed"));
+    assert!(cache.get(Snowflake(1), Snowflake(1)).is_some());
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that created channels are known to the gateway before their create event arrives
+async fn test_created_channel_is_remembered() {
+    use std::io::{Read, Write};
+
+    const CHANNEL: &str = r#"{"id":"30","type":0,"name":"created","guild_id":"1"}"#;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buffer = [0; 4096];
+        let _ = stream.read(&mut buffer).unwrap();
+        let response = format!(
+            "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            CHANNEL.len(),
+            CHANNEL
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        Arc::new(MemoryTransport {
+            receive: std::sync::Mutex::new(Some(receive)),
+            sent,
+        }),
+        GatewayOptions {
+            #[cfg(feature = "cache")]
+            cache: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let instance = chorus::instance::Instance {
+        urls: chorus::UrlBundle::new(url.clone(), url.clone(), url.clone(), url),
+        ..Default::default()
+    };
+    let mut user = chorus::instance::ChorusUser::new(
+        instance.into_shared(),
+        String::new(),
+        None,
+        types::UserSettings::default().into_shared(),
+        types::User::default().into_shared(),
+        gateway.clone(),
+    );
+
+    let channel = Channel::create(
+        &mut user,
+        Snowflake(1),
+        None,
+        ChannelCreateSchema {
+            name: "created".to_string(),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    server.join().unwrap();
+    assert_eq!(channel.id, Snowflake(30));
+
+    let observed = gateway
+        .get_observed::<Channel>(Snowflake(30))
+        .await
+        .unwrap();
+    assert_eq!(observed.name.as_deref(), Some("created"));
+    #[cfg(feature = "cache")]
+    assert_eq!(
+        gateway.cache().unwrap().guild_channels(Snowflake(1))[0].id,
+        Snowflake(30)
+    );
+    gateway.close().await;
+}
+
+#[cfg(all(feature = "cache", not(target_arch = "wasm32")))]
+#[tokio::test]
+/// Tests that the cache follows guilds, channels, members and roles through gateway events
+async fn test_gateway_cache() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        Arc::new(MemoryTransport {
+            receive: std::sync::Mutex::new(Some(receive)),
+            sent,
+        }),
+        GatewayOptions {
+            cache: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let cache = gateway.cache().unwrap();
+    let (_roles_receive, mut emojis_receive, mut stickers_receive) =
+        GuildListDiffObserver::subscribe(&gateway).await;
+
+    let role = |id: u64, name: &str| {
+        format!(
+            r#"{{"id":"{id}","name":"{name}","color":0,"hoist":false,"position":0,"permissions":"0","managed":false,"mentionable":false}}"#
+        )
+    };
+    let guild = |id: u64| {
+        format!(
+            r#"{{"id":"{id}","name":"guild{id}","channels":[{{"id":"{id}0","type":0,"name":"general"}}],"roles":[{}]}}"#,
+            role(id * 100, "everyone")
+        )
+    };
+    let dispatch = |event_name: &str, data: String| {
+        GatewayMessage::Text(format!(r#"{{"op":0,"t":"{event_name}","s":1,"d":{data}}}"#))
+    };
+    for message in [
+        dispatch(
+            "READY",
+            format!(
+                r#"{{"v":9,"user":{{"id":"5","username":"me","discriminator":"0"}},"guilds":[{}],"session_id":"session"}}"#,
+                guild(1)
+            ),
+        ),
+        dispatch("GUILD_CREATE", guild(2)),
+        dispatch(
+            "CHANNEL_UPDATE",
+            r#"{"id":"10","type":0,"name":"renamed","guild_id":"1"}"#.to_string(),
+        ),
+        dispatch(
+            "GUILD_ROLE_CREATE",
+            format!(r#"{{"guild_id":"1","role":{}}}"#, role(101, "moderator")),
+        ),
+        dispatch(
+            "GUILD_MEMBER_ADD",
+            r#"{"guild_id":"1","user":{"id":"6","username":"user"},"roles":["101"],"joined_at":"2024-01-01T00:00:00Z","deaf":false,"mute":false}"#.to_string(),
+        ),
+        dispatch(
+            "GUILD_MEMBER_UPDATE",
+            r#"{"guild_id":"1","roles":[],"user":{"id":"6","username":"renamed"},"nick":"nick"}"#.to_string(),
+        ),
+        dispatch(
+            "GUILD_EMOJIS_UPDATE",
+            r#"{"guild_id":"1","emojis":[{"id":"7","name":"wave"}]}"#.to_string(),
+        ),
+        dispatch(
+            "GUILD_STICKERS_UPDATE",
+            format!(r#"{{"guild_id":"1","stickers":[{}]}}"#, STICKER_JSON),
+        ),
+        dispatch("GUILD_DELETE", r#"{"id":"2","unavailable":false}"#.to_string()),
+        dispatch("GUILD_ROLE_DELETE", r#"{"guild_id":"1","role_id":"100"}"#.to_string()),
+    ] {
+        server_send.send(message).unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(5), async {
+        // The role was cached since READY, and is deleted by the last event
+        while cache.member(Snowflake(1), Snowflake(6)).is_none()
+            || cache.role(Snowflake(100)).is_some()
+        {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(cache.current_user().unwrap().id, Snowflake(5));
+    assert_eq!(
+        cache.guild(Snowflake(1)).unwrap().name.as_deref(),
+        Some("guild1")
+    );
+    assert!(cache.guild(Snowflake(1)).unwrap().channels.is_none());
+    assert_eq!(
+        cache.channel(Snowflake(10)).unwrap().name.as_deref(),
+        Some("renamed")
+    );
+    assert_eq!(cache.guild_channels(Snowflake(1)).len(), 1);
+    assert_eq!(cache.guild_roles(Snowflake(1)).len(), 1);
+    assert_eq!(
+        cache.role(Snowflake(101)).unwrap().name,
+        "moderator".to_string()
+    );
+    let member = cache.member(Snowflake(1), Snowflake(6)).unwrap();
+    assert_eq!(member.nick.as_deref(), Some("nick"));
+    assert!(member.roles.is_empty());
+    assert_eq!(
+        cache.user(Snowflake(6)).unwrap().username.as_deref(),
+        Some("renamed")
+    );
+
+    // Deleted guilds are forgotten along with their channels and roles
+    assert!(cache.guild(Snowflake(2)).is_none());
+    assert!(cache.channel(Snowflake(20)).is_none());
+    assert!(cache.guild_roles(Snowflake(2)).is_empty());
+    assert_eq!(cache.guilds().len(), 1);
+
+    // Members are kept in the connection's member cache
+    assert!(Arc::ptr_eq(
+        &gateway.member_cache().unwrap(),
+        &cache.member_cache()
+    ));
+    let expressions = cache.expressions();
+    assert_eq!(expressions.guild_emojis(Snowflake(1)).len(), 1);
+    assert_eq!(
+        expressions.search_emojis("wave")[0].guild_id,
+        Some(Snowflake(1))
+    );
+    let full_guild = cache.full_guild(Snowflake(1)).unwrap();
+    assert_eq!(full_guild.channels.unwrap().len(), 1);
+    assert_eq!(full_guild.roles.unwrap().len(), 1);
+
+    // Guilds which are not observed are diffed with the cache
+    let emojis = emojis_receive.try_recv().unwrap();
+    assert_eq!(emojis.emojis.added[0].id, Snowflake(7));
+    let stickers = stickers_receive.try_recv().unwrap();
+    assert_eq!(stickers.stickers.added[0].id, Snowflake(30));
+
+    // Users read from the cache instead of making requests, which would fail without an instance
+    let mut user = chorus::instance::ChorusUser::new(
+        chorus::instance::Instance::default().into_shared(),
+        String::new(),
+        None,
+        types::UserSettings::default().into_shared(),
+        types::User {
+            id: Snowflake(6),
+            ..Default::default()
+        }
+        .into_shared(),
+        gateway.clone(),
+    );
+    let channel = chorus::api::LazyRef::<Channel>::new(Snowflake(10))
+        .resolve(&mut user)
+        .await
+        .unwrap();
+    assert_eq!(channel.name.as_deref(), Some("renamed"));
+    let me = chorus::api::LazyRef::<types::User>::new(Snowflake(5))
+        .resolve(&mut user)
+        .await
+        .unwrap();
+    assert_eq!(me.username, "me");
+    // The member has no roles left, and the @everyone role has been deleted
+    assert_eq!(
+        user.can(types::PermissionFlags::VIEW_CHANNEL, Snowflake(10))
+            .await,
+        Some(false)
+    );
+    assert_eq!(
+        user.can(types::PermissionFlags::VIEW_CHANNEL, Snowflake(99))
+            .await,
+        None
+    );
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that only the emojis and stickers of the identified user are counted
+async fn test_gateway_emoji_usage() {
+    let (server_send, receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, _server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(MemoryTransport {
+        receive: std::sync::Mutex::new(Some(receive)),
+        sent,
+    });
+    server_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+        GatewayOptions {
+            track_emoji_usage: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(gateway.emoji_usage(), Some(EmojiUsage::default()));
+
+    let message = |author_id: u64| {
+        GatewayMessage::Text(format!(
+            r#"{{"op":0,"t":"MESSAGE_CREATE","s":1,"d":{{"id":"{}","channel_id":"1","author":{{"id":"{author_id}","username":"user","discriminator":"0"}},"content":"<:a:20> <a:b:21> <:a:20>","sticker_items":[{{"id":"30","name":"sticker","format_type":1}}],"timestamp":"2024-01-01T00:00:00Z","tts":false,"mention_everyone":false,"pinned":false,"type":0}}}}"#,
+            Snowflake::generate().0
+        ))
+    };
+    let reaction = |user_id: u64| {
+        GatewayMessage::Text(format!(
+            r#"{{"op":0,"t":"MESSAGE_REACTION_ADD","s":1,"d":{{"user_id":"{user_id}","channel_id":"1","message_id":"10","emoji":{{"id":"21","name":"b"}}}}}}"#
+        ))
+    };
+    for message in [
+        GatewayMessage::Text(
+            r#"{"op":0,"t":"READY","s":1,"d":{"v":9,"user":{"id":"1","username":"user","discriminator":"0"},"guilds":[],"session_id":"1"}}"#
+                .to_string(),
+        ),
+        message(2),
+        reaction(2),
+        message(1),
+        reaction(1),
+        reaction(1),
+    ] {
+        server_send.send(message).unwrap();
+    }
+    let usage = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let usage = gateway.emoji_usage().unwrap();
+            if usage.emojis.get("21").map(|item| item.total_uses) == Some(3) {
+                return usage;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(usage.emojis.len(), 2);
+    assert_eq!(usage.emojis["20"].total_uses, 2);
+    assert_eq!(usage.stickers[&Snowflake(30)].total_uses, 1);
+    let frequently_used = usage.frequently_used(1);
+    assert_eq!(frequently_used.emojis, vec!["21".to_string()]);
+    assert_eq!(frequently_used.stickers, vec![Snowflake(30)]);
+
+    gateway.set_emoji_usage(EmojiUsage::default());
+    assert_eq!(gateway.emoji_usage(), Some(EmojiUsage::default()));
+    gateway.close().await;
+}
+
+/// Returns the next message the gateway sent with the given opcode.
+async fn next_sent(
+    server_receive: &mut tokio::sync::mpsc::UnboundedReceiver<GatewayMessage>,
+    op_code: u8,
+) -> serde_json::Value {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(10), server_receive.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        if message.op_code() == Some(op_code) {
+            return serde_json::from_str(message.text().unwrap()).unwrap();
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that a lost connection is reestablished and resumed, and that a new session is
+/// identified if the server does not resume it
+async fn test_gateway_reconnect() {
+    let (first_send, first_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (second_send, second_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(ReconnectingMemoryTransport {
+        connections: std::sync::Mutex::new([first_receive, second_receive].into()),
+        sent,
+        urls: std::sync::Mutex::new(Vec::new()),
+    });
+    let hello =
+        || GatewayMessage::Text(r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string());
+    first_send.send(hello()).unwrap();
+    second_send.send(hello()).unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway?v=9".to_string(),
+        &http::HeaderMap::new(),
+        transport.clone(),
+        GatewayOptions {
+            reconnect: Some(ReconnectConfig {
+                max_retries: 2,
+                backoff: Backoff::Constant(Duration::ZERO),
+            }),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    gateway
+        .send_identify(types::GatewayIdentifyPayload {
+            token: "token".to_string(),
+            ..types::GatewayIdentifyPayload::common()
+        })
+        .await;
+    next_sent(&mut server_receive, 2).await;
+    first_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"READY","s":1,"d":{"v":9,"user":{"id":"1","username":"user","discriminator":"0"},"guilds":[],"session_id":"session","resume_gateway_url":"memory://resume"}}"#
+                .to_string(),
+        ))
+        .unwrap();
+    first_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"SESSIONS_REPLACE","s":2,"d":[]}"#.to_string(),
+        ))
+        .unwrap();
+    assert!(gateway.wait_until_ready().await);
+    assert_eq!(gateway.session_id().as_deref(), Some("session"));
+
+    // The connection breaks
+    drop(first_send);
+    let resume = next_sent(&mut server_receive, 6).await;
+    assert_eq!(resume["d"]["token"], "token");
+    assert_eq!(resume["d"]["session_id"], "session");
+    assert_eq!(resume["d"]["seq"], "2");
+    assert_eq!(
+        *transport.urls.lock().unwrap(),
+        vec!["memory://gateway?v=9", "memory://resume?v=9"]
+    );
+    assert_eq!(gateway.state(), ConnectionState::Resuming);
+
+    // The session can not be resumed, so a new one is identified
+    second_send
+        .send(GatewayMessage::Text(r#"{"op":9,"d":false}"#.to_string()))
+        .unwrap();
+    let identify = next_sent(&mut server_receive, 2).await;
+    assert_eq!(identify["d"]["token"], "token");
+    assert_eq!(gateway.session_id(), None);
+    second_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"READY","s":1,"d":{"v":9,"user":{"id":"1","username":"user","discriminator":"0"},"guilds":[],"session_id":"new session"}}"#
+                .to_string(),
+        ))
+        .unwrap();
+    assert!(gateway.wait_until_ready().await);
+    assert_eq!(gateway.session_id().as_deref(), Some("new session"));
+
+    // There are no more connections to reconnect with
+    drop(second_send);
+    let mut state = gateway.watch_state();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        state.wait_for(|state| state.is_closed()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(transport.urls.lock().unwrap().len(), 4);
+}
+
+#[derive(Debug)]
+struct GatewayErrorObserver {
+    errors: tokio::sync::mpsc::UnboundedSender<GatewayError>,
+}
+
+#[async_trait]
+impl Observer<GatewayError> for GatewayErrorObserver {
+    async fn update(&self, data: &GatewayError) {
+        self.errors.send(data.clone()).unwrap();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that a connection whose heartbeats are not acknowledged is replaced and resumed
+async fn test_gateway_zombie_connection() {
+    let (first_send, first_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (second_send, second_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(ReconnectingMemoryTransport {
+        connections: std::sync::Mutex::new([first_receive, second_receive].into()),
+        sent,
+        urls: std::sync::Mutex::new(Vec::new()),
+    });
+    first_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":200}}"#.to_string(),
+        ))
+        .unwrap();
+    second_send
+        .send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    let gateway = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport.clone(),
+        GatewayOptions {
+            reconnect: Some(ReconnectConfig {
+                max_retries: 1,
+                backoff: Backoff::Constant(Duration::ZERO),
+            }),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let (errors_send, mut errors) = tokio::sync::mpsc::unbounded_channel();
+    gateway
+        .events
+        .lock()
+        .await
+        .error
+        .subscribe(Arc::new(GatewayErrorObserver {
+            errors: errors_send,
+        }));
+
+    gateway
+        .send_identify(types::GatewayIdentifyPayload {
+            token: "token".to_string(),
+            ..types::GatewayIdentifyPayload::common()
+        })
+        .await;
+    next_sent(&mut server_receive, 2).await;
+    first_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"READY","s":1,"d":{"v":9,"user":{"id":"1","username":"user","discriminator":"0"},"guilds":[],"session_id":"session"}}"#
+                .to_string(),
+        ))
+        .unwrap();
+    assert!(gateway.wait_until_ready().await);
+
+    // The heartbeat is never acknowledged, although the connection stays open
+    next_sent(&mut server_receive, 1).await;
+    let resume = next_sent(&mut server_receive, 6).await;
+    assert_eq!(resume["d"]["session_id"], "session");
+    assert_eq!(resume["d"]["seq"], "1");
+    assert_eq!(transport.urls.lock().unwrap().len(), 2);
+    assert_eq!(
+        tokio::time::timeout(Duration::from_secs(5), errors.recv())
+            .await
+            .unwrap()
+            .unwrap(),
+        GatewayError::HeartbeatAckMissed
+    );
+    gateway.close().await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests saving a session and resuming it on a new connection, and identifying again if it has
+/// expired
+async fn test_gateway_resume_saved_session() {
+    let (first_send, first_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (second_send, second_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let hello =
+        || GatewayMessage::Text(r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string());
+    first_send.send(hello()).unwrap();
+    second_send.send(hello()).unwrap();
+    let first = Gateway::spawn_with_transport(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        Arc::new(MemoryTransport {
+            receive: std::sync::Mutex::new(Some(first_receive)),
+            sent: sent.clone(),
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(first.session_state(), None);
+    first
+        .send_identify(types::GatewayIdentifyPayload {
+            token: "token".to_string(),
+            ..types::GatewayIdentifyPayload::common()
+        })
+        .await;
+    next_sent(&mut server_receive, 2).await;
+    first_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"READY","s":1,"d":{"v":9,"user":{"id":"1","username":"user","discriminator":"0"},"guilds":[],"session_id":"session","resume_gateway_url":"memory://resume"}}"#
+                .to_string(),
+        ))
+        .unwrap();
+    first_send.send(dispatch(2)).unwrap();
+    assert!(first.wait_until_ready().await);
+    let session = loop {
+        match first.session_state() {
+            Some(session) if session.sequence_number == 2 => break session,
+            _ => sleep(Duration::from_millis(10)).await,
+        }
+    };
+    assert_eq!(session.session_id, "session");
+    assert_eq!(
+        session.resume_gateway_url.as_deref(),
+        Some("memory://resume")
+    );
+    first.close().await;
+
+    // Saved and restored, like when the program is restarted
+    let session: GatewaySessionState =
+        serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+    let second = Gateway::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        Arc::new(MemoryTransport {
+            receive: std::sync::Mutex::new(Some(second_receive)),
+            sent,
+        }),
+        GatewayOptions {
+            reconnect: Some(ReconnectConfig::default()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    second
+        .resume_session(
+            types::GatewayIdentifyPayload {
+                token: "token".to_string(),
+                ..types::GatewayIdentifyPayload::common()
+            },
+            session.clone(),
+        )
+        .await;
+    let resume = next_sent(&mut server_receive, 6).await;
+    assert_eq!(resume["d"]["token"], "token");
+    assert_eq!(resume["d"]["session_id"], "session");
+    assert_eq!(resume["d"]["seq"], "2");
+    assert_eq!(second.session_state(), Some(session));
+
+    // The session has expired, so a new one is identified with the kept payload
+    second_send
+        .send(GatewayMessage::Text(r#"{"op":9,"d":false}"#.to_string()))
+        .unwrap();
+    let identify = next_sent(&mut server_receive, 2).await;
+    assert_eq!(identify["d"]["token"], "token");
+    second.close().await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_reconnect_backoff() {
+    let backoff = Backoff::Exponential {
+        initial: Duration::from_secs(1),
+        max: Duration::from_secs(5),
+    };
+    assert_eq!(backoff.delay(1), Duration::from_secs(1));
+    assert_eq!(backoff.delay(2), Duration::from_secs(2));
+    assert_eq!(backoff.delay(3), Duration::from_secs(4));
+    assert_eq!(backoff.delay(4), Duration::from_secs(5));
+    assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(5));
+    let backoff = Backoff::Constant(Duration::from_secs(3));
+    assert_eq!(backoff.delay(10), Duration::from_secs(3));
+
+    assert!(GatewayError::SessionTimedOut.is_reconnectable());
+    assert!(!GatewayError::SessionTimedOut.is_resumable());
+    assert!(!GatewayError::AuthenticationFailed.is_reconnectable());
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that every shard identifies as its own shard, and that their dispatches are aggregated
+async fn test_shard_manager() {
+    let (first_send, first_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (second_send, second_receive) = tokio::sync::mpsc::unbounded_channel();
+    let (sent, mut server_receive) = tokio::sync::mpsc::unbounded_channel();
+    let transport = Arc::new(ReconnectingMemoryTransport {
+        connections: std::sync::Mutex::new([first_receive, second_receive].into()),
+        sent,
+        urls: std::sync::Mutex::new(Vec::new()),
+    });
+    for send in [&first_send, &second_send] {
+        send.send(GatewayMessage::Text(
+            r#"{"op":10,"d":{"heartbeat_interval":45000}}"#.to_string(),
+        ))
+        .unwrap();
+    }
+    let shards = ShardManager::spawn_with_options(
+        "memory://gateway".to_string(),
+        &http::HeaderMap::new(),
+        transport,
+        GatewayOptions::default(),
+        2,
+    )
+    .await
+    .unwrap();
+    assert_eq!(shards.shard_count(), 2);
+    let mut dispatches = shards.dispatches();
+
+    shards
+        .identify(
+            types::GatewayIdentifyPayload::common(),
+            &IdentifyQueue::new(1).interval(Duration::ZERO),
+        )
+        .await;
+    let mut identified = vec![
+        next_sent(&mut server_receive, 2).await["d"]["shard"].clone(),
+        next_sent(&mut server_receive, 2).await["d"]["shard"].clone(),
+    ];
+    identified.sort_by_key(|shard| shard[0].as_u64());
+    assert_eq!(
+        identified,
+        vec![serde_json::json!([0, 2]), serde_json::json!([1, 2])]
+    );
+    assert_eq!(
+        shards.shard(1).unwrap().shard(),
+        Some(types::ShardInfo::new(1, 2))
+    );
+
+    second_send
+        .send(GatewayMessage::Text(
+            r#"{"op":0,"t":"SESSIONS_REPLACE","s":1,"d":[]}"#.to_string(),
+        ))
+        .unwrap();
+    let dispatch = tokio::time::timeout(Duration::from_secs(5), dispatches.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(dispatch.event_name, "SESSIONS_REPLACE");
+    assert_eq!(dispatch.shard, Some(types::ShardInfo::new(1, 2)));
+
+    // (1 << 22) is a guild id of shard 1
+    assert_eq!(
+        shards.shard_for_guild(Snowflake(1 << 22)).shard(),
+        Some(types::ShardInfo::new(1, 2))
+    );
+    shards.close().await;
+}
+
 #[derive(Debug)]
 struct GatewayReadyObserver {
     channel: tokio::sync::mpsc::Sender<()>,
@@ -69,7 +1696,7 @@ async fn test_gateway_authenticate() {
         .subscribe(observer);
 
     let mut identify = types::GatewayIdentifyPayload::common();
-    identify.token = bundle.user.token.clone();
+    identify.token = bundle.user.token();
 
     gateway.send_identify(identify).await;
 
@@ -86,6 +1713,34 @@ async fn test_gateway_authenticate() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests the connection state through identifying and closing
+async fn test_gateway_connection_state() {
+    let bundle = common::setup().await;
+
+    let gateway: GatewayHandle = Gateway::spawn(bundle.urls.wss.clone()).await.unwrap();
+    assert_eq!(gateway.state(), ConnectionState::Connecting);
+    let mut state = gateway.watch_state();
+
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.token = bundle.user.token();
+    gateway.send_identify(identify).await;
+    assert_eq!(*state.borrow_and_update(), ConnectionState::Identifying);
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for ready");
+        }
+        ready = gateway.wait_until_ready() => assert!(ready),
+    };
+
+    gateway.close().await;
+    assert_eq!(gateway.state(), ConnectionState::Closed { code: None });
+    assert!(!gateway.wait_until_ready().await);
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn test_self_updating_structs() {
@@ -211,88 +1866,657 @@ async fn test_recursive_self_updating_structs() {
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
 fn test_error() {
-    let error = GatewayMessage("4000".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4000".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::Unknown);
-    let error = GatewayMessage("4001".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4001".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::UnknownOpcode);
-    let error = GatewayMessage("4002".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4002".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::Decode);
-    let error = GatewayMessage("4003".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4003".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::NotAuthenticated);
-    let error = GatewayMessage("4004".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4004".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::AuthenticationFailed);
-    let error = GatewayMessage("4005".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4005".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::AlreadyAuthenticated);
-    let error = GatewayMessage("4007".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4007".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::InvalidSequenceNumber);
-    let error = GatewayMessage("4008".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4008".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::RateLimited);
-    let error = GatewayMessage("4009".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4009".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::SessionTimedOut);
-    let error = GatewayMessage("4010".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4010".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::InvalidShard);
-    let error = GatewayMessage("4011".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4011".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::ShardingRequired);
-    let error = GatewayMessage("4012".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4012".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::InvalidAPIVersion);
-    let error = GatewayMessage("4013".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4013".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::InvalidIntents);
-    let error = GatewayMessage("4014".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("4014".to_string()).error().unwrap();
     assert_eq!(error, GatewayError::DisallowedIntents);
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn test_message_from_frame() {
+    use tokio_tungstenite::tungstenite::protocol::frame::{coding::CloseCode, CloseFrame};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let text = r#"{"op":11}"#.to_string();
+    let pointer = text.as_ptr();
+    let message = GatewayMessage::from(Message::Text(text));
+    // The payload is taken over instead of copied
+    assert_eq!(message.text().unwrap().as_ptr(), pointer);
+    assert_eq!(message.payload().unwrap().op_code, 11);
+
+    let message = GatewayMessage::from(Message::Binary(br#"{"op":11}"#.to_vec()));
+    assert_eq!(message.op_code(), Some(11));
+
+    let message = GatewayMessage::from(Message::Close(Some(CloseFrame {
+        code: CloseCode::from(4004),
+        reason: "Authentication failed".into(),
+    })));
+    assert_eq!(message.error(), Some(GatewayError::AuthenticationFailed));
+
+    let message = GatewayMessage::from(Message::Ping(vec![1, 2, 3]));
+    assert!(message.is_empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_gateway_encoding() {
+    assert_eq!(
+        GatewayEncoding::from_url("wss://gateway.example/?v=9&encoding=json"),
+        GatewayEncoding::Json
+    );
+    assert_eq!(
+        GatewayEncoding::Json.apply_to_url("wss://gateway.example"),
+        "wss://gateway.example?encoding=json"
+    );
+    assert_eq!(
+        GatewayEncoding::Json.apply_to_url("wss://gateway.example/?encoding=etf&v=9"),
+        "wss://gateway.example/?v=9&encoding=json"
+    );
+}
+
+#[cfg(feature = "etf")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_erlpack_codec() {
+    assert_eq!(
+        GatewayEncoding::from_url("wss://gateway.example/?v=9&encoding=etf"),
+        GatewayEncoding::Etf
+    );
+
+    // {op: 10, d: {heartbeat_interval: 45000}, s: nil, t: nil}, with atom and binary keys, as
+    // sent by the gateway
+    let mut term = vec![131, 116, 0, 0, 0, 4];
+    term.extend([119, 2, b'o', b'p', 97, 10]);
+    term.extend([109, 0, 0, 0, 1, b'd', 116, 0, 0, 0, 1, 109, 0, 0, 0, 18]);
+    term.extend(b"heartbeat_interval");
+    term.extend([98, 0, 0, 175, 200]);
+    term.extend([119, 1, b's', 100, 0, 3, b'n', b'i', b'l']);
+    term.extend([119, 1, b't', 115, 3, b'n', b'i', b'l']);
+    let message = GatewayMessage::from_bytes(term);
+    assert!(matches!(message, GatewayMessage::Etf(_)));
+    assert_eq!(message.op_code(), Some(10));
+    let payload = message.payload().unwrap();
+    assert_eq!(payload.op_code, 10);
+    assert_eq!(payload.sequence_number, None);
+    assert_eq!(payload.event_name, None);
+    // The event data is deserialized straight from the term
+    let data = payload.event_data.unwrap();
+    let hello: chorus::types::HelloData = data.parse().unwrap();
+    assert_eq!(hello.heartbeat_interval, 45000);
+    assert_eq!(data.json().unwrap(), r#"{"heartbeat_interval":45000}"#);
+
+    let payload = serde_json::json!({
+        "op": 2,
+        "d": {
+            "token": "token",
+            "compress": false,
+            "large_threshold": 250,
+            "shard": [0, 1],
+            "activities": [],
+            "presence": null,
+            "since": 1_700_000_000_000u64,
+            "id": 1_150_000_000_000_000_000u64,
+            "offset": -1,
+            "ratio": 0.5,
+        }
+    });
+    let encoded = ErlpackCodec::encode(&payload.to_string()).unwrap();
+    assert_eq!(encoded[0], ETF_VERSION);
+    let decoded: serde_json::Value = ErlpackCodec::decode(&encoded).unwrap();
+    assert_eq!(decoded, payload);
+
+    assert!(ErlpackCodec::decode::<serde_json::Value>(&encoded[..encoded.len() - 1]).is_err());
+    assert!(ErlpackCodec::decode::<serde_json::Value>(&[131, 200]).is_err());
+    // Broken terms are an error, not an empty payload
+    assert!(matches!(
+        GatewayMessage::from_bytes(vec![131, 200]).payload(),
+        Err(GatewayError::Etf { .. })
+    ));
+
+    // Snowflakes are sent as integers, most of them too large for INTEGER_EXT
+    let dispatch = serde_json::json!({
+        "op": 0,
+        "t": "MESSAGE_CREATE",
+        "s": 3,
+        "d": {
+            "id": 1_150_000_000_000_000_001u64,
+            "channel_id": 1_150_000_000_000_000_002u64,
+            "guild_id": 1_150_000_000_000_000_003u64,
+            "author": {"id": 1_150_000_000_000_000_004u64, "username": "alice", "discriminator": "0"},
+            "member": {"roles": [1_150_000_000_000_000_005u64, 7], "joined_at": "2024-01-01T00:00:00+00:00", "deaf": false, "mute": false},
+            "content": "hello",
+            "timestamp": "2024-01-01T00:00:00+00:00",
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [1_150_000_000_000_000_005u64],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }
+    });
+    let message = GatewayMessage::from_bytes(ErlpackCodec::encode(&dispatch.to_string()).unwrap());
+    let payload = message.payload().unwrap();
+    assert_eq!(payload.event_name.as_deref(), Some("MESSAGE_CREATE"));
+    let create: types::MessageCreate = payload.event_data.unwrap().parse().unwrap();
+    assert_eq!(create.message.id, Snowflake(1_150_000_000_000_000_001));
+    assert_eq!(
+        create.message.channel_id,
+        Snowflake(1_150_000_000_000_000_002)
+    );
+    assert_eq!(create.guild_id, Some(Snowflake(1_150_000_000_000_000_003)));
+    assert_eq!(
+        create.message.author.unwrap().id,
+        Snowflake(1_150_000_000_000_000_004)
+    );
+    assert_eq!(
+        create.member.unwrap().roles,
+        vec![Snowflake(1_150_000_000_000_000_005), Snowflake(7)]
+    );
+
+    // Lists nested in each other, [[[...]]]
+    let nested = |depth: usize| {
+        let mut term = vec![131];
+        for _ in 0..depth {
+            term.extend([108, 0, 0, 0, 1]);
+        }
+        term.push(106);
+        term.extend(std::iter::repeat(106).take(depth));
+        term
+    };
+    assert!(ErlpackCodec::decode::<serde_json::Value>(&nested(MAX_DEPTH - 1)).is_ok());
+    assert!(ErlpackCodec::decode::<serde_json::Value>(&nested(100_000)).is_err());
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
 fn test_error_message() {
-    let error = GatewayMessage("Unknown Error".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("Unknown Error".to_string())
+        .error()
+        .unwrap();
     assert_eq!(error, GatewayError::Unknown);
-    let error = GatewayMessage("Unknown Opcode".to_string())
+    let error = GatewayMessage::Text("Unknown Opcode".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::UnknownOpcode);
-    let error = GatewayMessage("Decode Error".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("Decode Error".to_string())
+        .error()
+        .unwrap();
     assert_eq!(error, GatewayError::Decode);
-    let error = GatewayMessage("Not Authenticated".to_string())
+    let error = GatewayMessage::Text("Not Authenticated".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::NotAuthenticated);
-    let error = GatewayMessage("Authentication Failed".to_string())
+    let error = GatewayMessage::Text("Authentication Failed".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::AuthenticationFailed);
-    let error = GatewayMessage("Already Authenticated".to_string())
+    let error = GatewayMessage::Text("Already Authenticated".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::AlreadyAuthenticated);
-    let error = GatewayMessage("Invalid Seq".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("Invalid Seq".to_string())
+        .error()
+        .unwrap();
     assert_eq!(error, GatewayError::InvalidSequenceNumber);
-    let error = GatewayMessage("Rate Limited".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("Rate Limited".to_string())
+        .error()
+        .unwrap();
     assert_eq!(error, GatewayError::RateLimited);
-    let error = GatewayMessage("Session Timed Out".to_string())
+    let error = GatewayMessage::Text("Session Timed Out".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::SessionTimedOut);
-    let error = GatewayMessage("Invalid Shard".to_string()).error().unwrap();
+    let error = GatewayMessage::Text("Invalid Shard".to_string())
+        .error()
+        .unwrap();
     assert_eq!(error, GatewayError::InvalidShard);
-    let error = GatewayMessage("Sharding Required".to_string())
+    let error = GatewayMessage::Text("Sharding Required".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::ShardingRequired);
-    let error = GatewayMessage("Invalid API Version".to_string())
+    let error = GatewayMessage::Text("Invalid API Version".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::InvalidAPIVersion);
-    let error = GatewayMessage("Invalid Intent(s)".to_string())
+    let error = GatewayMessage::Text("Invalid Intent(s)".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::InvalidIntents);
-    let error = GatewayMessage("Disallowed Intent(s)".to_string())
+    let error = GatewayMessage::Text("Disallowed Intent(s)".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::DisallowedIntents);
     // Also test the dot thing
-    let error = GatewayMessage("Invalid Intent(s).".to_string())
+    let error = GatewayMessage::Text("Invalid Intent(s).".to_string())
         .error()
         .unwrap();
     assert_eq!(error, GatewayError::InvalidIntents);
 }
+
+#[derive(Debug)]
+struct MessageCreateObserver {
+    channel: tokio::sync::mpsc::Sender<types::Snowflake>,
+}
+
+#[async_trait]
+impl Observer<types::MessageCreate> for MessageCreateObserver {
+    async fn update(&self, data: &types::MessageCreate) {
+        self.channel.send(data.message.id).await.unwrap();
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_guild_events_subscription() {
+    let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+    let observer = MessageCreateObserver { channel: sender };
+    let mut events = events::Events::default();
+
+    events
+        .for_guild(types::Snowflake(1))
+        .subscribe::<types::MessageCreate>(Arc::new(MessageCreateObserver {
+            channel: observer.channel.clone(),
+        }));
+    assert!(events.message.create.is_observed());
+
+    // Only the view of the same guild can unsubscribe the observer
+    events
+        .for_guild(types::Snowflake(2))
+        .unsubscribe::<types::MessageCreate>(&observer);
+    assert!(events.message.create.is_observed());
+    events
+        .for_guild(types::Snowflake(1))
+        .unsubscribe::<types::MessageCreate>(&observer);
+    assert!(!events.message.create.is_observed());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_guild_events_filter() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let (guild_send, mut guild_receive) = tokio::sync::mpsc::channel(1);
+    let (other_send, mut other_receive) = tokio::sync::mpsc::channel(1);
+    {
+        let mut events = bundle.user.gateway.events.lock().await;
+        events
+            .for_guild(guild_id)
+            .subscribe(Arc::new(MessageCreateObserver {
+                channel: guild_send,
+            }) as Arc<dyn Observer<types::MessageCreate>>);
+        events
+            .for_guild(types::Snowflake(1))
+            .subscribe(Arc::new(MessageCreateObserver {
+                channel: other_send,
+            }) as Arc<dyn Observer<types::MessageCreate>>);
+    }
+
+    let message = types::Message::send(
+        &mut bundle.user,
+        channel_id,
+        types::MessageSendSchema {
+            content: Some("routed by guild".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    tokio::select! {
+        () = sleep(Duration::from_secs(20)) => {
+            panic!("Timed out waiting for event");
+        }
+        Some(id) = guild_receive.recv() => {
+            assert_eq!(id, message.id);
+        }
+    };
+    assert!(other_receive.try_recv().is_err());
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_subscription_manager() {
+    let guild_id = types::Snowflake(848582562217590824);
+    let mut manager = SubscriptionManager::new();
+    assert!(manager.is_empty());
+
+    let mut bulk = types::GuildSubscriptionsBulk::default();
+    bulk.subscriptions.insert(
+        guild_id,
+        types::GuildSubscription {
+            typing: Some(true),
+            threads: Some(true),
+            ..Default::default()
+        },
+    );
+    manager.subscribe(&bulk);
+
+    bulk.subscriptions.insert(
+        guild_id,
+        types::GuildSubscription {
+            threads: Some(false),
+            member_updates: Some(true),
+            ..Default::default()
+        },
+    );
+    manager.subscribe(&bulk);
+
+    let subscription = manager.get(guild_id).unwrap();
+    assert_eq!(subscription.typing, Some(true));
+    assert_eq!(subscription.threads, Some(false));
+    assert_eq!(subscription.member_updates, Some(true));
+    assert_eq!(subscription.activities, None);
+
+    let resent = serde_json::to_value(manager.to_bulk()).unwrap();
+    assert_eq!(
+        resent,
+        serde_json::json!({"subscriptions": {"848582562217590824": {"typing": true, "threads": false, "member_updates": true}}})
+    );
+
+    assert!(manager.unsubscribe(guild_id).is_some());
+    assert!(!manager.is_subscribed(guild_id));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_expression_index() {
+    let home = types::Snowflake(1);
+    let other = types::Snowflake(2);
+    let mut index = ExpressionIndex::new();
+    index.apply_emojis_update(
+        &serde_json::from_str(
+            r#"{"guild_id": "1", "emojis": [{"id": "10", "name": "party_parrot", "animated": true}, {"id": "11", "name": "parrot"}]}"#,
+        )
+        .unwrap(),
+    );
+    index.apply_emojis_update(
+        &serde_json::from_str(
+            r#"{"guild_id": "2", "emojis": [{"id": "20", "name": "Parrots"}, {"id": "21", "name": "cat", "available": false}]}"#,
+        )
+        .unwrap(),
+    );
+    index.apply_stickers_update(
+        &serde_json::from_str(
+            r#"{"guild_id": "2", "stickers": [{"id": "30", "name": "wave", "tags": "hello, hi", "type": 2, "format_type": 1}]}"#,
+        )
+        .unwrap(),
+    );
+
+    let found: Vec<u64> = index
+        .search_emojis(":PARROT:")
+        .iter()
+        .map(|emoji| emoji.id.0)
+        .collect();
+    assert_eq!(found, vec![11, 20, 10]);
+    assert_eq!(index.search_stickers("hi")[0].id, types::Snowflake(30));
+    assert_eq!(
+        index.emoji(types::Snowflake(20)).unwrap().guild_id,
+        Some(other)
+    );
+
+    let usable: Vec<u64> = index
+        .usable_emojis(Some(home), false)
+        .map(|emoji| emoji.id.0)
+        .collect();
+    assert_eq!(usable, vec![11]);
+    assert_eq!(index.usable_emojis(Some(home), true).count(), 3);
+    assert_eq!(index.usable_stickers(Some(home), false).count(), 0);
+    assert_eq!(index.usable_stickers(None, true).count(), 1);
+
+    index.remove_guild(other);
+    assert!(index.guild_emojis(other).is_empty());
+    assert_eq!(index.stickers().count(), 0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_shard_info() {
+    let mut identify = types::GatewayIdentifyPayload::common();
+    identify.shard = Some(types::ShardInfo::new(1, 4));
+    let value = serde_json::to_value(&identify).unwrap();
+    assert_eq!(value["shard"], serde_json::json!([1, 4]));
+
+    let shard = types::ShardInfo::for_guild(types::Snowflake(41771983423143937), 4);
+    assert_eq!(shard, types::ShardInfo::new(2, 4));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn test_identify_queue() {
+    let queue = IdentifyQueue::new(2).interval(Duration::from_millis(200));
+    assert_eq!(queue.bucket(0), queue.bucket(2));
+    assert_ne!(queue.bucket(0), queue.bucket(1));
+
+    let start = chrono::Utc::now();
+    queue.wait(0).await;
+    // Shard 1 is in a different bucket, and does not have to wait
+    queue.wait(1).await;
+    assert!(chrono::Utc::now() - start < chrono::Duration::milliseconds(200));
+    // Shard 2 has to wait for shard 0
+    queue.wait(2).await;
+    assert!(chrono::Utc::now() - start >= chrono::Duration::milliseconds(200));
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_client_profiles() {
+    for profile in [
+        types::ClientProfile::stable_desktop(),
+        types::ClientProfile::canary_desktop(),
+        types::ClientProfile::web(),
+        types::ClientProfile::mobile(),
+    ] {
+        let identify = profile.identify("token".to_string());
+        assert_eq!(identify.token, "token");
+        assert_eq!(identify.properties.browser_user_agent, profile.user_agent);
+        assert_eq!(identify.capabilities, Some(profile.capabilities));
+    }
+
+    let canary = types::ClientProfile::canary_desktop();
+    assert_eq!(canary.properties.release_channel, "canary");
+    assert!(canary.user_agent.contains("Electron"));
+
+    use base64::Engine;
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(canary.super_properties())
+        .unwrap();
+    let properties: serde_json::Value = serde_json::from_slice(&decoded).unwrap();
+    assert_eq!(properties["release_channel"], "canary");
+}
+
+/// Recorded payloads of dispatched events, used as the corpus of the dispatch tests
+const DISPATCH_CORPUS: &[(&str, &str)] = &[
+    (
+        "MESSAGE_CREATE",
+        r#"{"id": "1", "channel_id": "2", "guild_id": "3", "author": {"id": "4", "username": "user"}, "content": "hi", "timestamp": "2024-01-01T00:00:00.000000+00:00", "tts": false, "mention_everyone": false, "mentions": [], "attachments": [], "embeds": [], "pinned": false, "type": 0}"#,
+    ),
+    (
+        "MESSAGE_DELETE",
+        r#"{"id": "1", "channel_id": "2", "guild_id": "3"}"#,
+    ),
+    (
+        "TYPING_START",
+        r#"{"channel_id": "2", "guild_id": "3", "user_id": "4", "timestamp": 1700000000}"#,
+    ),
+    (
+        "GUILD_MEMBER_REMOVE",
+        r#"{"guild_id": "3", "user": {"id": "4", "username": "user"}}"#,
+    ),
+    (
+        "PRESENCE_UPDATE",
+        r#"{"user": {"id": "4"}, "guild_id": "3", "status": "idle", "activities": [{"name": "Game", "type": 0}], "client_status": {"desktop": "idle"}}"#,
+    ),
+    (
+        "SESSIONS_REPLACE",
+        r#"[{"activities": [], "client_info": {"client": "web", "os": "other", "version": 0}, "session_id": "ab5941b50d818b1f8d93b4b1b581b192", "status": "online"}]"#,
+    ),
+];
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_parse_dispatch() {
+    let names = dispatch_event_names();
+    for (event_name, json) in DISPATCH_CORPUS {
+        assert!(names.contains(event_name));
+        if let Err(error) = parse_dispatch(event_name, json) {
+            panic!("{error}");
+        }
+    }
+
+    let parsed = parse_dispatch("MESSAGE_CREATE", DISPATCH_CORPUS[0].1).unwrap();
+    assert_eq!(parsed.event_name(), "MESSAGE_CREATE");
+    let message = parsed.downcast_ref::<types::MessageCreate>().unwrap();
+    assert_eq!(message.message.content.as_deref(), Some("hi"));
+    assert!(parsed.downcast_ref::<types::MessageDelete>().is_none());
+
+    assert_eq!(
+        parse_dispatch("NOT_AN_EVENT", "{}").unwrap_err(),
+        chorus::errors::DispatchError::UnknownEvent {
+            event_name: "NOT_AN_EVENT".to_string()
+        }
+    );
+    assert!(matches!(
+        parse_dispatch("MESSAGE_DELETE", r#"{"id": 1}"#),
+        Err(chorus::errors::DispatchError::InvalidPayload { .. })
+    ));
+}
+
+/// Applies `mutate` to every object in a payload.
+fn mutate_objects(
+    value: &mut serde_json::Value,
+    mutate: &mut impl FnMut(&mut serde_json::Map<String, serde_json::Value>),
+) {
+    match value {
+        serde_json::Value::Object(object) => {
+            mutate(object);
+            for value in object.values_mut() {
+                mutate_objects(value, mutate);
+            }
+        }
+        serde_json::Value::Array(array) => {
+            for value in array {
+                mutate_objects(value, mutate);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn random_value(rng: &mut impl rand::Rng, depth: u8) -> serde_json::Value {
+    use serde_json::json;
+    match rng.gen_range(0..if depth == 0 { 6 } else { 8 }) {
+        0 => json!(null),
+        1 => json!(rng.gen::<bool>()),
+        2 => json!(rng.gen::<i64>()),
+        3 => json!(rng.gen::<f64>()),
+        4 => {
+            json!(["", "0", "18446744073709551615", "\u{0}", "🦀", "a\"b\\c"][rng.gen_range(0..6)])
+        }
+        5 => json!(u64::MAX.to_string()),
+        6 => (0..rng.gen_range(0..4))
+            .map(|_| random_value(rng, depth - 1))
+            .collect(),
+        _ => {
+            let mut object = serde_json::Map::new();
+            for key in [
+                "id",
+                "guild_id",
+                "channel_id",
+                "user",
+                "type",
+                "content",
+                "name",
+            ] {
+                if rng.gen() {
+                    object.insert(key.to_string(), random_value(rng, depth - 1));
+                }
+            }
+            serde_json::Value::Object(object)
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+/// Checks properties of parsing generated edge-case payloads
+fn test_parse_dispatch_properties() {
+    use rand::{Rng, SeedableRng};
+    // Seeded, so failures can be reproduced
+    let mut rng = rand::rngs::StdRng::seed_from_u64(1758);
+
+    for (event_name, json) in DISPATCH_CORPUS {
+        let payload: serde_json::Value = serde_json::from_str(json).unwrap();
+        for _ in 0..64 {
+            // Fields added in newer server versions are ignored
+            let mut extended = payload.clone();
+            mutate_objects(&mut extended, &mut |object| {
+                let key = format!("unknown_field_{}", rng.gen::<u16>());
+                object.insert(key, random_value(&mut rng, 2));
+            });
+            assert!(
+                parse_dispatch(event_name, &extended.to_string()).is_ok(),
+                "{event_name}: {extended}"
+            );
+
+            // Snowflakes may use the whole range of u64
+            let mut ids = payload.clone();
+            let id = ["0", "1", "18446744073709551615"][rng.gen_range(0..3)];
+            mutate_objects(&mut ids, &mut |object| {
+                for (key, value) in object.iter_mut() {
+                    if (key == "id" || key.ends_with("_id")) && value.is_string() {
+                        *value = id.into();
+                    }
+                }
+            });
+            assert!(
+                parse_dispatch(event_name, &ids.to_string()).is_ok(),
+                "{event_name}: {ids}"
+            );
+
+            // Truncated payloads are errors, not panics
+            let end = rng.gen_range(0..json.len());
+            if json.is_char_boundary(end) {
+                assert!(parse_dispatch(event_name, &json[..end]).is_err());
+            }
+        }
+    }
+
+    // Arbitrary payloads never panic, whether or not they parse
+    for event_name in dispatch_event_names() {
+        for _ in 0..16 {
+            let payload = random_value(&mut rng, 3);
+            let _ = parse_dispatch(event_name, &payload.to_string());
+        }
+    }
+}
@@ -2,8 +2,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::time::Duration;
+
+use chorus::api::BanEntry;
+use chorus::errors::ChorusError;
 use chorus::types::{
-    CreateChannelInviteSchema, Guild, GuildBanCreateSchema, GuildCreateSchema, GuildModifySchema,
+    ChannelCreateSchema, ChannelType, CreateChannelInviteSchema, Guild, GuildBanCreateSchema,
+    GuildCreateSchema, GuildModifySchema,
 };
 
 mod common;
@@ -44,6 +49,54 @@ async fn get_channels() {
     common::teardown(bundle).await;
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn create_channel_in_category() {
+    let mut bundle = common::setup().await;
+    let guild = bundle.guild.read().unwrap().clone();
+    let category = guild
+        .create_channel(
+            &mut bundle.user,
+            None,
+            ChannelCreateSchema {
+                name: "category".to_string(),
+                channel_type: Some(ChannelType::GuildCategory),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let channel = guild
+        .create_channel(
+            &mut bundle.user,
+            None,
+            ChannelCreateSchema {
+                name: "child".to_string(),
+                channel_type: Some(ChannelType::GuildText),
+                parent_id: Some(category.id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(channel.parent_id, Some(category.id));
+    // A text channel is not a valid parent
+    let result = guild
+        .create_channel(
+            &mut bundle.user,
+            None,
+            ChannelCreateSchema {
+                name: "grandchild".to_string(),
+                channel_type: Some(ChannelType::GuildText),
+                parent_id: Some(channel.id),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(ChorusError::InvalidArguments { .. })));
+    common::teardown(bundle).await;
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn guild_create_ban() {
@@ -82,6 +135,46 @@ async fn guild_create_ban() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn guild_import_export_bans() {
+    let mut bundle = common::setup().await;
+    let channel = bundle.channel.read().unwrap().clone();
+    let mut other_user = bundle.create_user("testuser1763").await;
+    let guild = bundle.guild.read().unwrap().clone();
+    let invite = bundle
+        .user
+        .create_channel_invite(CreateChannelInviteSchema::default(), channel.id)
+        .await
+        .unwrap();
+    other_user.accept_invite(&invite.code, None).await.unwrap();
+    let other_user_id = other_user.object.read().unwrap().id;
+
+    let bans = vec![BanEntry {
+        user_id: other_user_id,
+        reason: Some("Imported".to_string()),
+    }];
+    let mut progress = Vec::new();
+    let report = Guild::import_bans(
+        &mut bundle.user,
+        guild.id,
+        bans,
+        Duration::from_millis(10),
+        |update| progress.push(update.clone()),
+    )
+    .await;
+    assert!(report.is_complete());
+    assert_eq!(report.banned, vec![other_user_id]);
+    assert_eq!(progress.len(), 1);
+    assert!(progress[0].succeeded);
+
+    let exported = Guild::export_bans(&mut bundle.user, guild.id)
+        .await
+        .unwrap();
+    assert!(exported.iter().any(|ban| ban.user_id == other_user_id));
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn modify_guild() {
@@ -123,3 +216,26 @@ async fn guild_remove_member() {
     );
     common::teardown(bundle).await
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn clone_guild_structure() {
+    let mut bundle = common::setup().await;
+    let mut other_user = bundle.create_user("cloneuser").await;
+    let guild = bundle.guild.read().unwrap().clone();
+    let role_id = bundle.role.read().unwrap().id;
+    let channel_id = bundle.channel.read().unwrap().id;
+
+    let clone = guild
+        .clone_structure(&mut bundle.user, &mut other_user)
+        .await
+        .unwrap();
+    assert_eq!(clone.roles.get(&guild.id), Some(&clone.guild.id));
+    assert!(clone.roles.contains_key(&role_id));
+    assert!(clone.channels.contains_key(&channel_id));
+
+    Guild::delete(&mut other_user, clone.guild.id)
+        .await
+        .unwrap();
+    common::teardown(bundle).await;
+}
@@ -3,6 +3,9 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 mod common;
+
+use chorus::errors::ChorusError;
+use chorus::types::UserSearchQuery;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 #[cfg(target_arch = "wasm32")]
@@ -19,3 +22,161 @@ async fn generate_general_configuration_schema() {
         .unwrap();
     common::teardown(bundle).await;
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_instance_policies() {
+    let bundle = common::setup().await;
+    let policies = bundle.instance.policies().await.unwrap();
+    assert_eq!(
+        policies.tos_url,
+        bundle.instance.instance_info.tos_page.clone()
+    );
+    common::teardown(bundle).await;
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn search_users_requires_spacebar() {
+    let mut bundle = common::setup().await;
+    assert!(bundle.instance.is_spacebar());
+
+    let limits = bundle
+        .user
+        .belongs_to
+        .write()
+        .unwrap()
+        .limits_configuration
+        .take();
+    let result = bundle
+        .user
+        .search_users(UserSearchQuery {
+            query: "integrationtestuser".to_string(),
+            limit: Some(10),
+        })
+        .await;
+    assert!(matches!(
+        result,
+        Err(ChorusError::UnsupportedByInstance { .. })
+    ));
+    bundle.user.belongs_to.write().unwrap().limits_configuration = limits;
+    common::teardown(bundle).await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// Answers the given amount of http requests with `response`, returning the requests.
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_server(
+    response: String,
+    requests: usize,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        (0..requests)
+            .map(|_| {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0; 4096];
+                let read = stream.read(&mut buffer).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+                String::from_utf8_lossy(&buffer[..read]).to_lowercase()
+            })
+            .collect()
+    });
+    (url, server)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that the default headers are sent with the requests made while creating an instance
+async fn default_headers_while_creating() {
+    let mut headers = http::HeaderMap::new();
+    headers.insert(
+        "cf-access-client-id",
+        http::HeaderValue::from_static("chorus"),
+    );
+
+    // Looking up the urls tries three of them, which all fail
+    let (url, server) = spawn_server(NOT_FOUND.to_string(), 3);
+    let result = chorus::instance::Instance::new_with_headers(&url, headers.clone()).await;
+    assert!(matches!(result, Err(ChorusError::RequestFailed { .. })));
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("get /.well-known/spacebar "));
+
+    // The limits and the general configuration
+    let (url, server) = spawn_server(NOT_FOUND.to_string(), 2);
+    let urls = chorus::UrlBundle::new(url.clone(), url.clone(), url.clone(), url);
+    let instance = chorus::instance::Instance::from_url_bundle_with_headers(urls, headers)
+        .await
+        .unwrap();
+    assert!(instance.limits_configuration.is_none());
+    let requests = [requests, server.join().unwrap()].concat();
+    assert!(requests
+        .iter()
+        .all(|request| request.contains("cf-access-client-id: chorus")));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests reading the policies from the general configuration a Spacebar instance serves
+async fn policies_from_general_configuration() {
+    const BODY: &str = r#"{"instanceName":"Spacebar Instance","instanceDescription":"This is a Spacebar instance made in the pre-release days","frontPage":null,"tosPage":"https://example.com/tos","correspondenceEmail":null,"correspondenceUserID":null,"image":null,"instanceId":"1135263183358111744"}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        BODY.len(),
+        BODY
+    );
+    let (url, server) = spawn_server(response, 1);
+    let instance = chorus::instance::Instance {
+        urls: chorus::UrlBundle::new(url.clone(), url.clone(), url.clone(), url),
+        ..Default::default()
+    };
+
+    let policies = instance.policies().await.unwrap();
+    assert_eq!(policies.tos_url.as_deref(), Some("https://example.com/tos"));
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("get /policies/instance "));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::test]
+/// Tests that fetching the voice regions sends the instance's headers and reports rate limits
+async fn voice_regions_as_instance() {
+    const BODY: &str =
+        r#"[{"id":"us","name":"US","optimal":true,"deprecated":false,"custom":false}]"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        BODY.len(),
+        BODY
+    );
+    let (url, server) = spawn_server(response, 1);
+    let mut instance = chorus::instance::Instance {
+        urls: chorus::UrlBundle::new(url.clone(), url.clone(), url.clone(), url),
+        ..Default::default()
+    };
+    instance.set_default_header(
+        http::HeaderName::from_static("cf-access-client-id"),
+        http::HeaderValue::from_static("chorus"),
+    );
+
+    let regions = instance.get_voice_regions().await.unwrap();
+    assert_eq!(regions[0].id, "us");
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("get /voice/regions "));
+    assert!(requests[0].contains("cf-access-client-id: chorus"));
+
+    let response = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 2\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let (url, server) = spawn_server(response.to_string(), 1);
+    instance.urls = chorus::UrlBundle::new(url.clone(), url.clone(), url.clone(), url);
+    let mut events = instance.rate_limit_events();
+    let result = instance.get_voice_regions().await;
+    assert!(matches!(result, Err(ChorusError::RateLimited { .. })));
+    server.join().unwrap();
+    let event = events.try_recv().unwrap();
+    assert_eq!(event.route, "/voice/regions");
+    assert_eq!(event.wait, std::time::Duration::from_secs(2));
+}
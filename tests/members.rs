@@ -2,12 +2,20 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::time::Duration;
+
+use chorus::api::RoleBatch;
 use chorus::{errors::ChorusResult, types::GuildMember};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 #[cfg(target_arch = "wasm32")]
 wasm_bindgen_test_configure!(run_in_browser);
 
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
 mod common;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
@@ -32,3 +40,57 @@ async fn add_remove_role() -> ChorusResult<()> {
     common::teardown(bundle).await;
     Ok(())
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn set_roles_in_batch() -> ChorusResult<()> {
+    let mut bundle = common::setup().await;
+    let guild = bundle.guild.read().unwrap().id;
+    let role = bundle.role.read().unwrap().id;
+    let member_id = bundle.user.object.read().unwrap().id;
+
+    let member = GuildMember::set_roles(&mut bundle.user, guild, member_id, vec![role]).await?;
+    assert!(member.roles.contains(&role));
+
+    let mut progress = Vec::new();
+    let results = RoleBatch::new(guild)
+        .set_roles(member_id, Vec::new())
+        .add_role(member_id, role)
+        .apply(&mut bundle.user, |update| progress.push(update.completed))
+        .await;
+    assert_eq!(progress, vec![1, 2]);
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    let member = GuildMember::get(&mut bundle.user, guild, member_id).await?;
+    assert_eq!(member.roles, vec![role]);
+
+    common::teardown(bundle).await;
+    Ok(())
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+/// Tests that the user's own member, used for permission checks, follows member updates
+async fn own_member_follows_gateway() -> ChorusResult<()> {
+    let mut bundle = common::setup().await;
+    let guild = bundle.guild.read().unwrap().id;
+    let role = bundle.role.read().unwrap().id;
+    let member_id = bundle.user.object.read().unwrap().id;
+
+    GuildMember::add_role(&mut bundle.user, guild, member_id, role).await?;
+    let mut attempts = 0;
+    while !bundle
+        .user
+        .guild_members
+        .read()
+        .unwrap()
+        .get(&guild)
+        .map_or(false, |member| member.roles.contains(&role))
+    {
+        attempts += 1;
+        assert!(attempts < 500, "The member update was not applied");
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    common::teardown(bundle).await;
+    Ok(())
+}
@@ -4,8 +4,10 @@
 
 use std::fs::File;
 use std::io::{BufReader, Read};
+use std::time::Duration;
 
-use chorus::types::{self, Guild, Message, MessageSearchQuery};
+use chorus::errors::ChorusError;
+use chorus::types::{self, Channel, Guild, Message, MessageSearchQuery};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_test::*;
 #[cfg(target_arch = "wasm32")]
@@ -26,6 +28,47 @@ async fn send_message() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn send_message_with_confirmation() {
+    let mut bundle = common::setup().await;
+    let message = types::MessageSendSchema {
+        content: Some("A confirmed Message!".to_string()),
+        ..Default::default()
+    };
+    let channel_id = bundle.channel.read().unwrap().id;
+    let confirmed = Channel::send_with_confirmation(
+        &mut bundle.user,
+        channel_id,
+        message,
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap();
+    assert_eq!(confirmed.channel_id, channel_id);
+    assert_eq!(confirmed.content.as_deref(), Some("A confirmed Message!"));
+    assert!(confirmed.nonce.is_some());
+    common::teardown(bundle).await
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn get_message() {
+    let mut bundle = common::setup().await;
+    let message = types::MessageSendSchema {
+        content: Some("A Message!".to_string()),
+        ..Default::default()
+    };
+    let channel = bundle.channel.read().unwrap().clone();
+    let message = bundle.user.send_message(message, channel.id).await.unwrap();
+    let received = Message::get(&mut bundle.user, channel.id, message.id)
+        .await
+        .unwrap();
+    assert_eq!(received.id, message.id);
+    assert_eq!(received.content, message.content);
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn send_message_attachment() {
@@ -67,6 +110,56 @@ async fn send_message_attachment() {
     common::teardown(bundle).await
 }
 
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn send_message_attachment_boosted_guild() {
+    let mut bundle = common::setup().await;
+    let channel_id = bundle.channel.read().unwrap().id;
+    let guild = bundle.user.gateway.observe(bundle.guild.clone()).await;
+    bundle.user.gateway.observe(bundle.channel.clone()).await;
+    // Only the guild's premium tier decides the limit without instance limits
+    bundle.user.belongs_to.write().unwrap().limits_configuration = None;
+
+    let base_limit = 25 * 1024 * 1024;
+    assert_eq!(bundle.user.upload_limit(channel_id).await, Some(base_limit));
+
+    let attachment = types::PartialDiscordFileAttachment {
+        id: None,
+        filename: "large.bin".to_string(),
+        description: None,
+        content_type: None,
+        size: None,
+        url: None,
+        proxy_url: None,
+        width: None,
+        height: None,
+        ephemeral: None,
+        duration_secs: None,
+        waveform: None,
+        content: vec![0; base_limit as usize + 1],
+    };
+    let message = types::MessageSendSchema {
+        attachments: Some(vec![attachment]),
+        ..Default::default()
+    };
+    let result = bundle.user.send_message(message, channel_id).await;
+    assert_eq!(
+        result.err(),
+        Some(ChorusError::AttachmentTooLarge {
+            filename: "large.bin".to_string(),
+            size: base_limit + 1,
+            limit: base_limit,
+        })
+    );
+
+    guild.write().unwrap().premium_tier = Some(types::PremiumTier::Tier3);
+    assert_eq!(
+        bundle.user.upload_limit(channel_id).await,
+        Some(100 * 1024 * 1024)
+    );
+    common::teardown(bundle).await
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
 async fn search_messages() {
@@ -2,7 +2,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chorus::ratelimiter::ChorusRequest;
+use std::time::Duration;
+
+use chorus::api::routes::{self, Route};
+use chorus::ratelimiter::{
+    ChorusRequest, RateLimitEvent, RateLimitEventKind, RateLimitEvents, RequestLimiter,
+};
+use chorus::types::{LimitType, Snowflake};
 
 mod common;
 
@@ -28,3 +34,84 @@ async fn get_limit_config() {
     assert!(conf.user.max_guilds > 0);
     assert!(conf.user.max_friends > 0);
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn rate_limit_events() {
+    let events = RateLimitEvents::default();
+    // Emitting without any receivers must not fail
+    events.emit(RateLimitEvent {
+        kind: RateLimitEventKind::GlobalLimit,
+        route: "/api/users/@me".to_string(),
+        bucket: LimitType::Global,
+        wait: Duration::from_secs(1),
+    });
+    let mut receiver = events.clone().subscribe();
+    let event = RateLimitEvent {
+        kind: RateLimitEventKind::TooManyRequests,
+        route: "/api/channels/1/messages".to_string(),
+        bucket: LimitType::Channel(Snowflake(1)),
+        wait: Duration::from_secs(5),
+    };
+    events.emit(event.clone());
+    assert_eq!(receiver.try_recv().unwrap(), event);
+    assert!(receiver.try_recv().is_err());
+
+    let route = routes::channel_message(Snowflake(1), Snowflake(2));
+    assert!(!event.concerns(&route));
+    assert!(event.concerns(&routes::channel_messages(Snowflake(1))));
+    let global = RateLimitEvent {
+        route: "/api/channels/1/messages/2".to_string(),
+        bucket: LimitType::Global,
+        ..event
+    };
+    assert!(global.concerns(&route));
+    assert!(!global.concerns(&routes::channel_message(Snowflake(11), Snowflake(2))));
+
+    // Only the events concerning the route are taken into account
+    events.emit(global.clone());
+    events.emit(event);
+    assert_eq!(
+        RateLimitEvent::last_wait(&mut receiver, &route),
+        Some(Duration::from_secs(5))
+    );
+    assert_eq!(RateLimitEvent::last_wait(&mut receiver, &route), None);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn request_limiter() {
+    let limiter = RequestLimiter::new(2);
+    let first = limiter.clone().acquire().await;
+    let _second = limiter.clone().acquire().await;
+    assert_eq!(limiter.in_flight(), 2);
+    drop(first);
+    assert_eq!(limiter.in_flight(), 1);
+    assert_eq!(RequestLimiter::new(0).max_concurrent(), 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn route_major_parameters() {
+    let route = routes::channel_message(Snowflake(1), Snowflake(2));
+    assert_eq!(route.path(), "/channels/1/messages/2");
+    assert_eq!(route.limit_type(), LimitType::Channel(Snowflake(1)));
+    assert_eq!(
+        route.url("http://localhost:3001/api"),
+        "http://localhost:3001/api/channels/1/messages/2"
+    );
+    assert_eq!(
+        routes::guild_roles(Snowflake(3)).limit_type(),
+        LimitType::Guild(Snowflake(3))
+    );
+    assert_eq!(routes::auth_login().limit_type(), LimitType::AuthLogin);
+    assert_eq!(
+        routes::auth_register().limit_type(),
+        LimitType::AuthRegister
+    );
+    assert_eq!(routes::current_user().limit_type(), LimitType::Global);
+    assert_eq!(
+        Route::new("/webhooks/4/token?wait=true").limit_type(),
+        LimitType::Webhook(Snowflake(4))
+    );
+}
@@ -70,3 +70,24 @@ async fn get_and_delete_role() {
     );
     common::teardown(bundle).await
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn role_member_counts_and_ids() {
+    let mut bundle = common::setup().await;
+    let guild_id = bundle.guild.read().unwrap().id;
+    let role_id = bundle.role.read().unwrap().id;
+    let user_id = bundle.user.object.read().unwrap().id;
+    types::GuildMember::add_role(&mut bundle.user, guild_id, user_id, role_id)
+        .await
+        .unwrap();
+    let counts = RoleObject::get_member_counts(&mut bundle.user, guild_id)
+        .await
+        .unwrap();
+    assert_eq!(counts.get(&role_id), Some(&1));
+    let member_ids = RoleObject::get_member_ids(&mut bundle.user, guild_id, role_id)
+        .await
+        .unwrap();
+    assert_eq!(member_ids, vec![user_id]);
+    common::teardown(bundle).await
+}
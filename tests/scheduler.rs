@@ -0,0 +1,57 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use chorus::scheduler::{MemoryTaskStore, ScheduledAction};
+use chorus::types::MessageSendSchema;
+use chrono::{Duration, Utc};
+
+mod common;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::*;
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn scheduled_messages() {
+    let bundle = common::setup().await;
+    let channel_id = bundle.channel.read().unwrap().id;
+    let scheduler = bundle.user.scheduler(MemoryTaskStore::new());
+
+    let message = MessageSendSchema {
+        content: Some("A scheduled message".to_string()),
+        ..Default::default()
+    };
+    let due = scheduler
+        .schedule_message(Utc::now() - Duration::seconds(1), channel_id, message)
+        .await;
+    match &due.action {
+        ScheduledAction::SendMessage { message, .. } => {
+            assert!(message.nonce.is_some());
+            assert_eq!(message.enforce_nonce, Some(true));
+        }
+        other => panic!("Unexpected action: {:?}", other),
+    }
+    let later = scheduler
+        .schedule(
+            Utc::now() + Duration::hours(1),
+            ScheduledAction::Request {
+                method: "POST".to_string(),
+                path: format!("/channels/{}/typing", channel_id),
+                body: None,
+            },
+        )
+        .await;
+    assert_eq!(scheduler.tasks().await, vec![due.clone(), later.clone()]);
+
+    let finished = scheduler.run_due().await;
+    assert_eq!(finished.len(), 1);
+    assert_eq!(finished[0].0, due.id);
+    assert!(finished[0].1.is_ok());
+
+    scheduler.cancel(later.id).await;
+    assert!(scheduler.tasks().await.is_empty());
+    common::teardown(bundle).await;
+}
@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+
+use chorus::session::SessionManager;
+
+mod common;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::*;
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn session_manager() {
+    let mut bundle = common::setup().await;
+    let mut manager = SessionManager::new();
+    let second_user = bundle.create_user("sessionmanageruser").await;
+    let first_id = bundle.user.object.read().unwrap().id;
+    let second_id = second_user.object.read().unwrap().id;
+
+    assert!(manager
+        .add_user(bundle.clone_user_without_gateway().await)
+        .is_none());
+    assert!(manager.add_user(second_user).is_none());
+    assert_eq!(manager.len(), 2);
+    assert_eq!(manager.instances().count(), 1);
+
+    // Both users share the same instance
+    let first = manager
+        .get(&bundle.urls, first_id)
+        .unwrap()
+        .belongs_to
+        .clone();
+    let second = manager
+        .get(&bundle.urls, second_id)
+        .unwrap()
+        .belongs_to
+        .clone();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(manager.users_of(&bundle.urls).count(), 2);
+
+    let user = manager.get_mut(&bundle.urls, second_id).unwrap();
+    assert_eq!(user.get_user(None).await.unwrap().id, second_id);
+    user.clone().delete().await.unwrap();
+
+    manager.shutdown().await;
+    assert!(manager.is_empty());
+    assert!(manager.get(&bundle.urls, first_id).is_none());
+    common::teardown(bundle).await
+}
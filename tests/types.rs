@@ -898,14 +898,487 @@ mod config {
             assert!(fmt_domains.contains("Default API Version: 9"));
         }
     }
+
+    mod register_configuration {
+        use chorus::types::{RegisterConfiguration, RegistrationRequirements};
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn registration_requirements() {
+            let mut config = RegisterConfiguration::default();
+            config.email.required = true;
+            config.require_invite = true;
+            let requirements = RegistrationRequirements::from(&config);
+            assert!(requirements.registration_open);
+            assert!(requirements.email_required);
+            assert!(requirements.captcha_required);
+            assert!(requirements.invite_required);
+            assert!(requirements.date_of_birth_required);
+            assert_eq!(requirements.minimum_age, Some(13));
+
+            config.disabled = true;
+            config.date_of_birth.required = false;
+            let requirements = RegistrationRequirements::from(config);
+            assert!(!requirements.registration_open);
+            assert!(!requirements.date_of_birth_required);
+            assert_eq!(requirements.minimum_age, None);
+        }
+    }
+}
+
+mod schema {
+    use chorus::types::{
+        age_on, ApplicationAuthorizeSchema, ArchivedThreads, ChannelModifySchema, ChannelType,
+        Embed, EmbedImage, EmbedThumbnail, FieldFormatError, FieldFormatReason, LoginSchema,
+        MessageFlags, MessageReference, MessageSendSchema, ModifyGuildMemberProfileSchema,
+        PartialDiscordFileAttachment, PublicUser, RegisterSchema, RtcRegion, Snowflake,
+        StartThreadWithoutMessageSchema, ThemeColors, UserModifySchema, VoiceRegion,
+        VoiceStateModifyCurrentUserSchema, USERNAME_MAX_LENGTH,
+    };
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn thread_schemas() {
+        let schema = StartThreadWithoutMessageSchema {
+            name: "thread".to_string(),
+            thread_type: Some(ChannelType::GuildPublicThread),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&schema).unwrap(),
+            r#"{"name":"thread","type":11}"#
+        );
+
+        let thread = |id: u64, archived_at: &str| {
+            format!(
+                r#"{{"id":"{id}","type":11,"thread_metadata":{{"archived":true,"auto_archive_duration":60,"archive_timestamp":"{archived_at}","locked":false}}}}"#
+            )
+        };
+        let page: ArchivedThreads = serde_json::from_str(&format!(
+            r#"{{"threads":[{},{}],"members":[],"has_more":true}}"#,
+            thread(1, "2024-01-02T00:00:00+00:00"),
+            thread(2, "2024-01-01T00:00:00+00:00")
+        ))
+        .unwrap();
+        let next = page.next_page(Some(2)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&next).unwrap(),
+            r#"{"before":"2024-01-01T00:00:00Z","limit":2}"#
+        );
+        let last = ArchivedThreads {
+            has_more: false,
+            ..page
+        };
+        assert!(last.next_page(None).is_none());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn voice_state_request_to_speak() {
+        let unchanged = VoiceStateModifyCurrentUserSchema {
+            suppress: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&unchanged).unwrap(),
+            r#"{"suppress":true}"#
+        );
+
+        let lowered = VoiceStateModifyCurrentUserSchema {
+            channel_id: Some(Snowflake(1)),
+            request_to_speak_timestamp: Some(None),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&lowered).unwrap(),
+            r#"{"channel_id":"1","request_to_speak_timestamp":null}"#
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn message_send_flags() {
+        let message = MessageSendSchema {
+            content: Some("quiet please".to_string()),
+            ..Default::default()
+        }
+        .suppress_notifications();
+        assert_eq!(
+            message.flags,
+            Some(MessageFlags::SUPPRESS_NOTIFICATIONS.bits())
+        );
+        assert!(message.flags_error().is_none());
+
+        let tts = MessageSendSchema {
+            tts: Some(true),
+            ..message.clone()
+        };
+        assert!(tts.flags_error().is_none());
+
+        let crossposted = MessageSendSchema {
+            flags: Some(MessageFlags::CROSSPOSTED.bits()),
+            ..Default::default()
+        };
+        assert!(crossposted.flags_error().is_some());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn embed_attachment_references() {
+        let embed = Embed {
+            image: Some(EmbedImage::attachment("chart.png")),
+            thumbnail: Some(EmbedThumbnail::new("https://example.com/icon.png")),
+            ..Default::default()
+        };
+        assert_eq!(embed.attachment_references(), vec!["chart.png"]);
+        assert_eq!(
+            serde_json::to_value(&embed).unwrap()["image"]["url"],
+            "attachment://chart.png"
+        );
+
+        let mut message = MessageSendSchema {
+            embeds: Some(vec![embed]),
+            ..Default::default()
+        };
+        assert_eq!(message.unknown_attachment_reference(), Some("chart.png"));
+
+        message.attachments = Some(vec![PartialDiscordFileAttachment {
+            id: None,
+            filename: "chart.png".to_string(),
+            description: None,
+            content_type: None,
+            size: None,
+            url: None,
+            proxy_url: None,
+            height: None,
+            width: None,
+            ephemeral: None,
+            duration_secs: None,
+            waveform: None,
+            content: vec![0],
+        }]);
+        assert_eq!(message.unknown_attachment_reference(), None);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn split_message_content() {
+        let short = MessageSendSchema {
+            content: Some("short".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(short.split_content(20), vec![short.clone()]);
+
+        let message = MessageSendSchema {
+            content: Some("first line\nsecond line with words".to_string()),
+            message_reference: Some(MessageReference {
+                message_id: Snowflake(1),
+                channel_id: Snowflake(2),
+                guild_id: None,
+                fail_if_not_exists: None,
+            }),
+            sticker_ids: Some(vec!["1".to_string()]),
+            ..Default::default()
+        };
+        let parts = message.split_content(20);
+        let contents: Vec<_> = parts
+            .iter()
+            .map(|part| part.content.clone().unwrap())
+            .collect();
+        assert_eq!(contents, vec!["first line", "second line with", "words"]);
+        assert!(parts[0].message_reference.is_some());
+        assert!(parts[1].message_reference.is_none());
+        assert!(parts[0].sticker_ids.is_none());
+        assert!(parts[2].sticker_ids.is_some());
+
+        // Code blocks are closed and reopened across parts
+        let code = MessageSendSchema {
+            content: Some("```rust\nlet a = 1;\nlet b = 2;\n```".to_string()),
+            ..Default::default()
+        };
+        let contents: Vec<_> = code
+            .split_content(24)
+            .into_iter()
+            .map(|part| part.content.unwrap())
+            .collect();
+        assert_eq!(
+            contents,
+            vec!["```rust\nlet a = 1;\n```", "```rust\nlet b = 2;\n```"]
+        );
+        assert!(contents.iter().all(|part| part.chars().count() <= 24));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn channel_rtc_region() {
+        let region: VoiceRegion = serde_json::from_str(
+            r#"{"id": "rotterdam", "name": "Rotterdam", "optimal": true, "deprecated": false, "custom": false}"#,
+        )
+        .unwrap();
+        let mut schema = ChannelModifySchema::default();
+        assert!(serde_json::to_value(&schema)
+            .unwrap()
+            .get("rtc_region")
+            .is_none());
+
+        schema.rtc_region = Some(RtcRegion::from(&region));
+        assert_eq!(
+            serde_json::to_value(&schema).unwrap()["rtc_region"],
+            serde_json::json!("rotterdam")
+        );
+
+        schema.rtc_region = Some(RtcRegion::Automatic);
+        let value = serde_json::to_value(&schema).unwrap();
+        assert_eq!(value["rtc_region"], serde_json::Value::Null);
+        assert!(value.get("rtc_region").is_some());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn profile_theme_round_trip() {
+        let json = r#"{"id": "1", "accent_color": 16711680, "theme_colors": [1184274, 16777215]}"#;
+        let user: PublicUser = serde_json::from_str(json).unwrap();
+        assert_eq!(user.accent_color, Some(0xff0000));
+        assert_eq!(
+            user.theme_colors,
+            Some(ThemeColors::new(0x121212, 0xffffff))
+        );
+        let value = serde_json::to_value(user).unwrap();
+        assert_eq!(
+            value["theme_colors"],
+            serde_json::json!([1184274, 16777215])
+        );
+
+        // Unset theme fields must not be sent, as null resets them
+        let schema = ModifyGuildMemberProfileSchema {
+            pronouns: None,
+            bio: Some("Hi".to_string()),
+            banner: None,
+            accent_color: None,
+            theme_colors: None,
+            popout_animation_particle_type: None,
+            emoji_id: None,
+        };
+        let value = serde_json::to_value(&schema).unwrap();
+        assert!(value.get("theme_colors").is_none());
+        assert!(value.get("popout_animation_particle_type").is_none());
+
+        let schema = ModifyGuildMemberProfileSchema {
+            theme_colors: Some(ThemeColors::new(1, 2)),
+            popout_animation_particle_type: Some(Snowflake(3)),
+            ..schema
+        };
+        let value = serde_json::to_value(schema).unwrap();
+        assert_eq!(value["theme_colors"], serde_json::json!([1, 2]));
+        assert_eq!(value["popout_animation_particle_type"], "3");
+
+        let schema: UserModifySchema = serde_json::from_str(r#"{"theme_colors": [1, 2]}"#).unwrap();
+        assert_eq!(schema.theme_colors, Some(ThemeColors::new(1, 2)));
+        let value = serde_json::to_value(&schema).unwrap();
+        assert!(value.get("popout_animation_particle_type").is_none());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn register_schema_validation() {
+        let mut schema = RegisterSchema {
+            username: "Test".to_string(),
+            password: Some("correct horse battery staple".to_string()),
+            email: Some("test@example.com".to_string()),
+            consent: true,
+            ..Default::default()
+        };
+        assert_eq!(schema.validate(), Ok(()));
+
+        schema.username = "a".to_string();
+        assert_eq!(
+            schema.validate(),
+            Err(FieldFormatError::Username(FieldFormatReason::TooShort {
+                min: 2
+            }))
+        );
+        schema.username = "a".repeat(USERNAME_MAX_LENGTH + 1);
+        assert_eq!(
+            schema.validate(),
+            Err(FieldFormatError::Username(FieldFormatReason::TooLong {
+                max: USERNAME_MAX_LENGTH
+            }))
+        );
+        schema.username = "test#1234".to_string();
+        assert_eq!(
+            schema.validate(),
+            Err(FieldFormatError::Username(
+                FieldFormatReason::InvalidCharacters
+            ))
+        );
+        schema.username = "Test".to_string();
+
+        schema.password = Some("a".repeat(73));
+        assert_eq!(
+            schema.validate(),
+            Err(FieldFormatError::Password(FieldFormatReason::TooLong {
+                max: 72
+            }))
+        );
+        schema.password = None;
+
+        schema.email = Some("not an email".to_string());
+        assert_eq!(schema.validate(), Err(FieldFormatError::InvalidEmail));
+        schema.email = None;
+
+        schema.consent = false;
+        assert_eq!(schema.validate(), Err(FieldFormatError::ConsentRequired));
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn register_schema_date_of_birth() {
+        let mut schema = RegisterSchema {
+            username: "Test".to_string(),
+            consent: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            schema.validate_age(13),
+            Err(FieldFormatError::DateOfBirthRequired)
+        );
+
+        schema.set_date_of_birth(chrono::NaiveDate::from_ymd_opt(2000, 1, 31).unwrap());
+        assert_eq!(schema.date_of_birth.as_deref(), Some("2000-01-31"));
+        assert_eq!(schema.validate(), Ok(()));
+        assert_eq!(schema.validate_age(13), Ok(()));
+        assert_eq!(
+            schema.validate_age(200),
+            Err(FieldFormatError::Underage { minimum_age: 200 })
+        );
+
+        schema.date_of_birth = Some("31.01.2000".to_string());
+        assert_eq!(schema.validate(), Err(FieldFormatError::InvalidDateOfBirth));
+        schema.date_of_birth = Some("3000-01-01".to_string());
+        assert_eq!(schema.validate(), Err(FieldFormatError::InvalidDateOfBirth));
+
+        let born = chrono::NaiveDate::from_ymd_opt(2008, 6, 15).unwrap();
+        let day_before_birthday = chrono::NaiveDate::from_ymd_opt(2021, 6, 14).unwrap();
+        assert_eq!(age_on(born, day_before_birthday), 12);
+        assert_eq!(age_on(born, day_before_birthday.succ_opt().unwrap()), 13);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn login_schema_validation() {
+        let schema = LoginSchema {
+            login: "test@example.com".to_string(),
+            password: String::new(),
+            ..Default::default()
+        };
+        assert_eq!(
+            schema.validate(),
+            Err(FieldFormatError::Password(FieldFormatReason::TooShort {
+                min: 1
+            }))
+        );
+        assert_eq!(
+            schema.validate().unwrap_err().to_string(),
+            "Password must be at least 1 characters long."
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn register_schema_registration_token_is_not_serialized() {
+        let schema = RegisterSchema {
+            username: "Test".to_string(),
+            consent: true,
+            invite: Some("abcdef".to_string()),
+            registration_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(schema).unwrap();
+        assert_eq!(json["invite"], "abcdef");
+        assert!(json.get("registration_token").is_none());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn bot_authorization_url() {
+        let authorization = chorus::api::BotAuthorization::new(Snowflake(1234))
+            .scopes(vec!["bot".to_string()])
+            .permissions(chorus::types::PermissionFlags::ADMINISTRATOR)
+            .guild(Snowflake(5678), true);
+        assert_eq!(
+            authorization.url("https://example.com/"),
+            "https://example.com/oauth2/authorize?client_id=1234&scope=bot&permissions=8&guild_id=5678&disable_guild_select=true"
+        );
+        assert_eq!(
+            authorization.route().path(),
+            "/oauth2/authorize?client_id=1234&scope=bot&permissions=8&guild_id=5678&disable_guild_select=true"
+        );
+
+        let schema = ApplicationAuthorizeSchema {
+            authorize: true,
+            guild_id: Some(Snowflake(5678)),
+            permissions: Some("8".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&schema).unwrap(),
+            r#"{"authorize":true,"guild_id":"5678","permissions":"8"}"#
+        );
+    }
 }
 
 mod entities {
     use std::sync::{Arc, RwLock};
 
-    use chorus::types::{ApplicationFlags, ConfigEntity, Emoji, User};
+    use chorus::api::LazyRef;
+    use chorus::types::{
+        ApplicationFlags, ConfigEntity, Emoji, Guild, GuildMember, ImageFormat, IntoShared,
+        PublicUser, Snowflake, User,
+    };
     use serde_json::json;
 
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn lazy_references() {
+        let channel = chorus::types::Channel {
+            id: Snowflake(2),
+            parent_id: Some(Snowflake(1)),
+            ..Default::default()
+        };
+        assert!(channel.owner().is_none());
+        let parent = channel.parent().unwrap();
+        assert_eq!(parent.id(), Snowflake(1));
+        assert!(!parent.is_resolved());
+        assert_eq!(parent, LazyRef::new(Snowflake(1)));
+
+        let guild = Guild {
+            id: Snowflake(3),
+            name: Some("guild".to_string()),
+            ..Default::default()
+        };
+        let resolved = LazyRef::with_value(Snowflake(3), guild);
+        // Clones share the cached entity
+        let clone = resolved.clone();
+        assert_eq!(clone.get().unwrap().name.as_deref(), Some("guild"));
+        resolved.invalidate();
+        assert!(!clone.is_resolved());
+
+        let mut message = chorus::types::Message {
+            channel_id: Snowflake(2),
+            ..Default::default()
+        };
+        assert!(message.author().is_none());
+        message.author = Some(PublicUser {
+            id: Snowflake(4),
+            username: Some("author".to_string()),
+            ..Default::default()
+        });
+        let author = message.author().unwrap();
+        assert_eq!(author.id(), Snowflake(4));
+        assert_eq!(author.get().unwrap().username, "author");
+        assert_eq!(message.channel().id(), Snowflake(2));
+    }
+
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
     #[cfg_attr(not(target_arch = "wasm32"), test)]
     fn application() {
@@ -952,10 +1425,97 @@ mod entities {
         assert_ne!(emoji, another_emoji);
     }
 
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn emoji_url() {
+        let emoji = Emoji {
+            id: Snowflake(1234),
+            ..Default::default()
+        };
+        assert_eq!(
+            emoji.url("https://cdn.example.com", ImageFormat::Webp, Some(64)),
+            "https://cdn.example.com/emojis/1234.webp?size=64"
+        );
+        assert_eq!(
+            emoji.url("https://cdn.example.com", ImageFormat::Png, Some(50)),
+            "https://cdn.example.com/emojis/1234.png"
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn emoji_is_usable_by() {
+        let guild = Guild::default();
+        let role_id = Snowflake::generate();
+        let mut member = GuildMember {
+            user: Some(PublicUser::default().into_shared()),
+            ..Default::default()
+        };
+        let mut emoji = Emoji {
+            guild_id: Some(guild.id),
+            ..Default::default()
+        };
+        assert!(emoji.is_usable_by(&member, &guild));
+        emoji.available = Some(false);
+        assert!(!emoji.is_usable_by(&member, &guild));
+        emoji.available = Some(true);
+        emoji.roles = Some(vec![role_id]);
+        assert!(!emoji.is_usable_by(&member, &guild));
+        member.roles.push(role_id);
+        assert!(emoji.is_usable_by(&member, &guild));
+        emoji.animated = Some(true);
+        assert!(!emoji.is_usable_by(&member, &guild));
+        member.user.as_ref().unwrap().write().unwrap().premium_type = Some(2);
+        assert!(emoji.is_usable_by(&member, &guild));
+        // Role restricted emojis can't be used in other guilds, even with Nitro
+        assert!(!emoji.is_usable_by(&member, &Guild::default()));
+    }
+
     mod guild {
         use std::hash::{Hash, Hasher};
 
-        use chorus::types::{Guild, GuildInvite};
+        use chorus::api::{GuildConsistencyReport, GuildInconsistency};
+        use chorus::types::types::subconfigs::limits::message::MessageLimits;
+        use chorus::types::{
+            AuditLog, AuditLogChange, AuditLogEntry, AuditLogRole, AuditLogValueChange, Channel,
+            GetUserGuildSchema, Guild, GuildAuditLogEntryCreate, GuildAuditLogsQuery,
+            GuildIncidentActionsSchema, GuildInvite, GuildJoinRequestActionSchema,
+            GuildJoinRequestStatus, GuildJoinRequestUpdate, GuildMemberFlags, GuildMemberUpdate,
+            GuildScheduledEvent, GuildScheduledEventUserAdd, GuildScheduledEventUserRemove,
+            IntoShared, Message, ModifyGuildMemberSchema, PermissionFlags, PermissionOverwrite,
+            PremiumTier, RoleObject, Snowflake, UserGuild, VerificationLevel,
+        };
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_incidents() {
+            let guild: Guild = serde_json::from_str(
+                r#"{"id": "1", "safety_alerts_channel_id": "2", "incidents_data": {"invites_disabled_until": "2999-01-01T00:00:00+00:00", "dms_disabled_until": null, "dm_spam_detected_at": null, "raid_detected_at": "2024-01-01T00:00:00+00:00"}}"#,
+            )
+            .unwrap();
+            assert_eq!(guild.safety_alerts_channel_id, Some(Snowflake(2)));
+            let incidents = guild.incidents_data.unwrap();
+            assert!(incidents.invites_disabled());
+            assert!(!incidents.dms_disabled());
+            assert!(incidents.is_alert_mode());
+            assert!(incidents.raid_detected());
+            assert!(!incidents.dm_spam_detected());
+
+            // Lifting a lockdown requires explicitly sending null for both actions
+            assert_eq!(
+                serde_json::to_string(&GuildIncidentActionsSchema::default()).unwrap(),
+                r#"{"invites_disabled_until":null,"dms_disabled_until":null}"#
+            );
+
+            let message: Message = serde_json::from_str(
+                r#"{"id": "1", "channel_id": "2", "content": "2024-01-02T00:00:00+00:00", "timestamp": "2024-01-01T00:00:00+00:00", "mention_everyone": false, "pinned": false, "type": 36}"#,
+            )
+            .unwrap();
+            assert_eq!(
+                message.incident_alert_mode_until().unwrap().to_rfc3339(),
+                "2024-01-02T00:00:00+00:00"
+            );
+        }
 
         #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
         #[cfg_attr(not(target_arch = "wasm32"), test)]
@@ -1004,6 +1564,509 @@ mod entities {
 
             assert_eq!(guild1, guild2);
         }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_join_requests() {
+            let event: GuildJoinRequestUpdate = serde_json::from_str(
+                r#"{"status": "SUBMITTED", "guild_id": "1", "request": {"id": "3", "join_request_id": "3", "created_at": "2024-01-01T00:00:00+00:00", "application_status": "SUBMITTED", "guild_id": "1", "user_id": "2", "form_responses": [{"field_type": "TERMS", "label": "Read the rules", "response": true}], "rejection_reason": null, "actioned_at": null, "last_seen": null}}"#,
+            )
+            .unwrap();
+            assert_eq!(event.status, GuildJoinRequestStatus::Submitted);
+            assert!(event.request.is_pending());
+            assert_eq!(event.request.user_id, Snowflake(2));
+            assert_eq!(event.request.form_responses.len(), 1);
+
+            let schema = GuildJoinRequestActionSchema {
+                action: GuildJoinRequestStatus::Rejected,
+                rejection_reason: Some("spam".to_string()),
+            };
+            assert_eq!(
+                serde_json::to_value(schema).unwrap(),
+                serde_json::json!({"action": "REJECTED", "rejection_reason": "spam"})
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn member_permissions() {
+            let guild: Guild = serde_json::from_str(
+                r#"{"id": "1", "owner_id": "9", "roles": [
+                    {"id": "1", "name": "@everyone", "color": 0, "hoist": false, "position": 0, "permissions": "3072", "managed": false, "mentionable": false},
+                    {"id": "2", "name": "Moderator", "color": 0, "hoist": false, "position": 1, "permissions": "8192", "managed": false, "mentionable": false},
+                    {"id": "3", "name": "Admin", "color": 0, "hoist": false, "position": 2, "permissions": "8", "managed": false, "mentionable": false}
+                ]}"#,
+            )
+            .unwrap();
+            let user = Snowflake(5);
+            let send = PermissionFlags::VIEW_CHANNEL | PermissionFlags::SEND_MESSAGES;
+
+            assert_eq!(guild.member_permissions(user, &[], &[]), send);
+            assert!(guild
+                .member_permissions(user, &[Snowflake(2)], &[])
+                .contains(PermissionFlags::MANAGE_MESSAGES));
+            assert_eq!(
+                guild.member_permissions(user, &[Snowflake(3)], &[]),
+                PermissionFlags::all()
+            );
+            assert_eq!(
+                guild.member_permissions(Snowflake(9), &[], &[]),
+                PermissionFlags::all()
+            );
+
+            let overwrite =
+                |id: u64, overwrite_type: &str, allow: &str, deny: &str| PermissionOverwrite {
+                    id: Snowflake(id),
+                    overwrite_type: overwrite_type.to_string(),
+                    allow: allow.to_string(),
+                    deny: deny.to_string(),
+                };
+            // Read-only for @everyone, writable for moderators, hidden from the user
+            let read_only = [
+                overwrite(1, "0", "0", "2048"),
+                overwrite(2, "0", "2048", "0"),
+            ];
+            assert_eq!(
+                guild.member_permissions(user, &[], &read_only),
+                PermissionFlags::VIEW_CHANNEL
+            );
+            assert!(guild
+                .member_permissions(user, &[Snowflake(2)], &read_only)
+                .contains(send));
+            let hidden = [overwrite(5, "1", "0", "1024")];
+            assert!(guild.member_permissions(user, &[], &hidden).is_empty());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_upload_limit() {
+            let mut guild = Guild::default();
+            assert_eq!(guild.upload_limit(None), 25 * 1024 * 1024);
+            guild.premium_tier = Some(PremiumTier::Tier3);
+            assert_eq!(guild.upload_limit(None), 100 * 1024 * 1024);
+            let limits = MessageLimits {
+                max_attachment_size: 1024,
+                ..Default::default()
+            };
+            assert_eq!(guild.upload_limit(Some(&limits)), 1024);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn scheduled_event_user_count() {
+            let mut event = GuildScheduledEvent {
+                id: Snowflake(1),
+                ..Default::default()
+            };
+            let add = GuildScheduledEventUserAdd {
+                guild_scheduled_event_id: Snowflake(1),
+                ..Default::default()
+            };
+            assert!(event.apply_user_add(&add));
+            assert!(event.apply_user_add(&add));
+            assert_eq!(event.user_count, Some(2));
+
+            let remove = GuildScheduledEventUserRemove {
+                guild_scheduled_event_id: Snowflake(1),
+                ..Default::default()
+            };
+            assert!(event.apply_user_remove(&remove));
+            assert_eq!(event.user_count, Some(1));
+
+            let other_event = GuildScheduledEventUserRemove {
+                guild_scheduled_event_id: Snowflake(2),
+                ..Default::default()
+            };
+            assert!(!event.apply_user_remove(&other_event));
+            assert_eq!(event.user_count, Some(1));
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn audit_log() {
+            let event: GuildAuditLogEntryCreate = serde_json::from_str(
+                r#"{"guild_id": "1", "id": "2", "action_type": 22, "user_id": "3", "target_id": "4"}"#,
+            )
+            .unwrap();
+            assert_eq!(event.guild_id, Some(Snowflake(1)));
+            assert_eq!(event.entry.id, Snowflake(2));
+
+            let audit_log: AuditLog =
+                serde_json::from_str(r#"{"audit_log_entries": [{"id": "2", "action_type": 22}]}"#)
+                    .unwrap();
+            assert_eq!(audit_log.audit_log_entries.len(), 1);
+            assert!(audit_log.users.is_empty());
+
+            let query = GuildAuditLogsQuery {
+                after: Some(Snowflake(2)),
+                limit: Some(100),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_string(&query).unwrap(),
+                r#"{"after":"2","limit":100}"#
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn audit_log_changes() {
+            let entry: AuditLogEntry = serde_json::from_str(
+                r#"{"id": "2", "action_type": 25, "changes": [
+                    {"key": "$add", "new_value": [{"id": "5", "name": "Moderator"}]},
+                    {"key": "name", "old_value": "old", "new_value": "new"},
+                    {"key": "verification_level", "old_value": 0, "new_value": 2},
+                    {"key": "position", "old_value": "not a number"},
+                    {"key": "unknown_key", "new_value": {"a": 1}}
+                ]}"#,
+            )
+            .unwrap();
+            let changes: Vec<AuditLogChange> = entry
+                .changes
+                .unwrap()
+                .iter()
+                .map(|change| change.read().unwrap().clone())
+                .collect();
+
+            assert_eq!(
+                changes[0],
+                AuditLogChange::AddRoles(AuditLogValueChange {
+                    old_value: None,
+                    new_value: Some(vec![AuditLogRole {
+                        id: Snowflake(5),
+                        name: Some("Moderator".to_string()),
+                    }]),
+                })
+            );
+            assert_eq!(
+                changes[1],
+                AuditLogChange::Name(AuditLogValueChange {
+                    old_value: Some("old".to_string()),
+                    new_value: Some("new".to_string()),
+                })
+            );
+            assert_eq!(
+                changes[2],
+                AuditLogChange::VerificationLevel(AuditLogValueChange {
+                    old_value: Some(VerificationLevel::None),
+                    new_value: Some(VerificationLevel::Medium),
+                })
+            );
+            // Values of an unexpected type are kept as they are
+            assert_eq!(changes[3].key(), "position");
+            assert!(matches!(changes[3], AuditLogChange::Other { .. }));
+            assert_eq!(changes[4].key(), "unknown_key");
+
+            for change in changes {
+                let json = serde_json::to_string(&change).unwrap();
+                assert_eq!(
+                    serde_json::from_str::<AuditLogChange>(&json).unwrap(),
+                    change
+                );
+            }
+
+            let created = AuditLogChange::Name(AuditLogValueChange {
+                old_value: None,
+                new_value: Some("new".to_string()),
+            });
+            assert_eq!(
+                serde_json::to_string(&created).unwrap(),
+                r#"{"key":"name","new_value":"new"}"#
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn consistency_report() {
+            let role = |id: u64, name: &str| RoleObject {
+                id: Snowflake(id),
+                name: name.to_string(),
+                ..Default::default()
+            };
+            let channel = |id: u64, position: i32| Channel {
+                id: Snowflake(id),
+                position: Some(position),
+                ..Default::default()
+            };
+            let cached = Guild {
+                id: Snowflake(1),
+                roles: Some(vec![
+                    role(1, "everyone").into_shared(),
+                    role(2, "old").into_shared(),
+                ]),
+                channels: Some(vec![
+                    channel(3, 0).into_shared(),
+                    channel(4, 1).into_shared(),
+                ]),
+                approximate_member_count: Some(10),
+                ..Default::default()
+            };
+
+            let report = GuildConsistencyReport::new(
+                &cached,
+                &[role(1, "everyone"), role(2, "old")],
+                &[channel(3, 0), channel(4, 1)],
+                Some(10),
+            );
+            assert!(report.is_consistent());
+
+            let report = GuildConsistencyReport::new(
+                &cached,
+                &[role(1, "everyone"), role(2, "new"), role(5, "added")],
+                &[channel(3, 2)],
+                Some(11),
+            );
+            assert_eq!(report.guild_id, Snowflake(1));
+            assert_eq!(
+                report.inconsistencies,
+                vec![
+                    GuildInconsistency::RoleMismatch {
+                        role_id: Snowflake(2),
+                        fields: vec!["name"]
+                    },
+                    GuildInconsistency::MissingRole {
+                        role_id: Snowflake(5)
+                    },
+                    GuildInconsistency::ChannelMismatch {
+                        channel_id: Snowflake(3),
+                        fields: vec!["position"]
+                    },
+                    GuildInconsistency::StaleChannel {
+                        channel_id: Snowflake(4)
+                    },
+                    GuildInconsistency::MemberCountMismatch {
+                        cached: 10,
+                        actual: 11
+                    },
+                ]
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn user_guild() {
+            let guild: UserGuild = serde_json::from_str(
+                r#"{"id": "1", "name": "test", "icon": null, "banner": null, "owner": false, "permissions": "2056", "features": ["COMMUNITY", "SOME_NEW_FEATURE"], "approximate_member_count": 3, "approximate_presence_count": 2}"#,
+            )
+            .unwrap();
+            assert_eq!(guild.id, Snowflake(1));
+            assert_eq!(guild.features.len(), 2);
+            assert_eq!(guild.approximate_member_count, Some(3));
+            assert_eq!(
+                guild.permission_flags(),
+                PermissionFlags::ADMINISTRATOR | PermissionFlags::SEND_MESSAGES
+            );
+
+            // Unset query parameters are left out
+            assert_eq!(
+                serde_json::to_string(&GetUserGuildSchema {
+                    before: None,
+                    after: Some(Snowflake(1)),
+                    limit: None,
+                    with_counts: Some(true),
+                })
+                .unwrap(),
+                r#"{"after":"1","with_counts":true}"#
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_member_flags() {
+            let update: GuildMemberUpdate = serde_json::from_str(
+                r#"{"guild_id": "1", "roles": [], "user": {"id": "2", "username": "test", "discriminator": "0"}, "flags": 22}"#,
+            )
+            .unwrap();
+            assert_eq!(
+                update.member_flags(),
+                GuildMemberFlags::COMPLETED_ONBOARDING
+                    | GuildMemberFlags::BYPASSES_VERIFICATION
+                    | GuildMemberFlags::GUEST
+            );
+
+            let schema = ModifyGuildMemberSchema::default().bypass_verification(true);
+            assert_eq!(schema.member_flags(), GuildMemberFlags::SETTABLE);
+            let json = serde_json::to_value(&schema).unwrap();
+            assert_eq!(json["flags"], 4);
+            // Unset fields are left as they are, not cleared with null
+            assert_eq!(
+                serde_json::to_string(&ModifyGuildMemberSchema::default()).unwrap(),
+                "{}"
+            );
+            let schema = ModifyGuildMemberSchema {
+                nick: Some("alice".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_string(&schema).unwrap(),
+                r#"{"nick":"alice"}"#
+            );
+        }
+    }
+
+    mod user_settings {
+        use chorus::types::{
+            GuildFolder, GuildFolders, Snowflake, UserGuildSettingsModifySchema,
+            UserGuildSettingsUpdate, UserNotificationLevel,
+        };
+
+        fn single(guild_id: &str) -> GuildFolder {
+            GuildFolder {
+                guild_ids: vec![guild_id.to_string()],
+                ..Default::default()
+            }
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn guild_folders() {
+            let mut folders = GuildFolders::new(vec![single("1"), single("2"), single("3")]);
+
+            let folder_id = folders.create_folder(
+                Some("Folder".to_string()),
+                Some(0xff0000),
+                &[Snowflake(2), Snowflake(3)],
+            );
+            assert_eq!(folders.entries().len(), 2);
+            assert_eq!(folders.guild_positions(), vec!["1", "2", "3"]);
+            assert_eq!(folders.folder_of(Snowflake(3)).unwrap().id, Some(folder_id));
+
+            assert!(folders.move_guild(Snowflake(1), Some(folder_id), 1));
+            assert_eq!(folders.entries().len(), 1);
+            assert_eq!(folders.guild_positions(), vec!["2", "1", "3"]);
+
+            assert!(folders.move_guild(Snowflake(3), None, 0));
+            assert_eq!(folders.guild_positions(), vec!["3", "2", "1"]);
+            assert!(!folders.folder_of(Snowflake(3)).unwrap().is_folder());
+            assert!(!folders.move_guild(Snowflake(3), Some(folder_id + 1), 0));
+
+            assert!(folders.rename_folder(folder_id, Some("Renamed".to_string())));
+            assert_eq!(
+                folders.folder(folder_id).unwrap().name.as_deref(),
+                Some("Renamed")
+            );
+
+            assert!(folders.delete_folder(folder_id));
+            assert_eq!(folders.entries().len(), 3);
+            assert_eq!(folders.guild_positions(), vec!["3", "2", "1"]);
+
+            assert!(folders.remove_guild(Snowflake(2)));
+            assert!(!folders.remove_guild(Snowflake(2)));
+            assert_eq!(folders.guild_positions(), vec!["3", "1"]);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn user_guild_settings() {
+            let event: UserGuildSettingsUpdate = serde_json::from_str(
+                r#"{"version":2,"suppress_roles":false,"suppress_everyone":false,"notify_highlights":0,"muted":true,"mute_scheduled_events":false,"mute_config":{"end_time":"2024-01-01T12:00:00+00:00","selected_time_window":3600},"mobile_push":true,"message_notifications":1,"hide_muted_channels":false,"guild_id":"1","flags":0,"channel_overrides":[{"muted":true,"mute_config":null,"message_notifications":3,"flags":4096,"collapsed":false,"channel_id":"2"},{"muted":false,"mute_config":null,"message_notifications":0,"flags":0,"collapsed":false,"channel_id":"3"}]}"#,
+            )
+            .unwrap();
+            let settings = event.settings;
+            assert_eq!(settings.guild_id, Some(Snowflake(1)));
+
+            let before = "2024-01-01T11:00:00Z".parse().unwrap();
+            let after = "2024-01-01T13:00:00Z".parse().unwrap();
+            assert!(settings.is_muted_at(before));
+            assert!(!settings.is_muted_at(after));
+
+            // Channels in a muted category are muted, indefinitely
+            assert!(settings.is_channel_muted_at(Snowflake(3), Some(Snowflake(2)), after));
+            assert!(!settings.is_channel_muted_at(Snowflake(3), None, after));
+
+            assert_eq!(
+                settings.channel_notifications(Snowflake(2), None),
+                UserNotificationLevel::OnlyMentions
+            );
+            assert_eq!(
+                settings.channel_notifications(Snowflake(3), Some(Snowflake(2))),
+                UserNotificationLevel::AllMessages
+            );
+
+            let schema = UserGuildSettingsModifySchema {
+                muted: Some(false),
+                message_notifications: Some(UserNotificationLevel::NoMessages),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_string(&schema).unwrap(),
+                r#"{"muted":false,"message_notifications":2}"#
+            );
+        }
+    }
+
+    mod presence {
+        use chorus::types::{
+            Activity, ActivityButton, ActivityType, PresenceUpdate, Snowflake, UserStatus,
+        };
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn activities() {
+            let presence: PresenceUpdate = serde_json::from_str(
+                r#"{
+                    "user": {"id": "1"},
+                    "status": "online",
+                    "client_status": {"desktop": "online"},
+                    "activities": [
+                        {"name": "Custom Status", "type": 4, "state": "busy", "emoji": {"name": "🔥"}, "created_at": 1700000000000},
+                        {
+                            "name": "Spotify", "type": 2, "details": "Song", "state": "Artist",
+                            "sync_id": "track", "session_id": "session", "party": {"id": "spotify:1"},
+                            "assets": {"large_image": "spotify:image", "large_text": "Album"},
+                            "timestamps": {"start": "1700000000000", "end": 1700000180000},
+                            "metadata": {"album_id": "album", "artist_ids": ["artist"], "context_uri": "spotify:album:album"}
+                        },
+                        {
+                            "name": "Game", "type": 0, "application_id": "2",
+                            "party": {"id": "party", "size": [1, 4]},
+                            "secrets": {"join": "secret"},
+                            "buttons": ["Join"], "metadata": {"button_urls": ["https://example.com"]}
+                        }
+                    ]
+                }"#,
+            )
+            .unwrap();
+            assert_eq!(presence.status, UserStatus::Online);
+            let [custom, spotify, game] = presence.activities.as_slice() else {
+                panic!("Expected 3 activities");
+            };
+            assert_eq!(custom.activity_type, ActivityType::Custom);
+            assert_eq!(custom.emoji.as_ref().unwrap().id, None);
+            assert_eq!(spotify.activity_type, ActivityType::Listening);
+            assert_eq!(spotify.sync_id.as_deref(), Some("track"));
+            assert_eq!(spotify.timestamps.unwrap().start, Some(1_700_000_000_000));
+            assert_eq!(
+                spotify.metadata.as_ref().unwrap().album_id.as_deref(),
+                Some("album")
+            );
+            assert_eq!(game.application_id, Some(Snowflake(2)));
+            assert_eq!(game.party.as_ref().unwrap().size, Some([1, 4]));
+            assert_eq!(game.buttons.as_ref().unwrap()[0].label(), "Join");
+            assert_eq!(game.button_urls(), vec!["https://example.com"]);
+
+            // Buttons are sent with their urls, and unset fields are left out
+            let activity = Activity {
+                buttons: Some(vec![ActivityButton::Link {
+                    label: "Join".to_string(),
+                    url: "https://example.com".to_string(),
+                }]),
+                ..Activity::new(ActivityType::Playing, "Game")
+            };
+            assert_eq!(
+                serde_json::to_value(activity).unwrap(),
+                serde_json::json!({"name": "Game", "type": 0, "buttons": [{"label": "Join", "url": "https://example.com"}]})
+            );
+            assert_eq!(
+                serde_json::to_value(Activity::custom_status("busy")).unwrap(),
+                serde_json::json!({"name": "Custom Status", "type": 4, "state": "busy"})
+            );
+
+            // Activity types added after this version of chorus are kept
+            let activity: Activity = serde_json::from_str(r#"{"name": "New", "type": 9}"#).unwrap();
+            assert_eq!(activity.activity_type, ActivityType::Unknown(9));
+            assert_eq!(serde_json::to_value(activity).unwrap()["type"], 9);
+        }
     }
 
     mod relationship {
@@ -1039,7 +2102,118 @@ mod entities {
     }
 
     mod message {
-        use chorus::types::{Message, Snowflake};
+        use chorus::types::{
+            Message, MessageCreate, MessageSendSchema, MessageType, Reaction,
+            RoleSubscriptionListing, Snowflake,
+        };
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn message_nonces() {
+            let mut schema = MessageSendSchema::default();
+            let nonce = schema.ensure_nonce().to_string();
+            assert!(nonce.parse::<u64>().is_ok());
+            assert_eq!(schema.ensure_nonce(), nonce);
+
+            let mut schema = MessageSendSchema {
+                nonce: Some("custom".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(schema.ensure_nonce(), "custom");
+
+            let echo: MessageCreate = serde_json::from_str(
+                r#"{"id": "1", "channel_id": "2", "timestamp": "2024-01-01T00:00:00+00:00", "mention_everyone": false, "pinned": false, "type": 0, "nonce": 1234}"#,
+            )
+            .unwrap();
+            assert!(echo.matches_nonce("1234"));
+            assert!(!echo.matches_nonce("123"));
+
+            let echo: MessageCreate = serde_json::from_str(
+                r#"{"id": "1", "channel_id": "2", "timestamp": "2024-01-01T00:00:00+00:00", "mention_everyone": false, "pinned": false, "type": 0, "nonce": "custom"}"#,
+            )
+            .unwrap();
+            assert!(echo.matches_nonce("custom"));
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn message_types() {
+            assert_eq!(MessageType::from(31), MessageType::StageTopic);
+            assert_eq!(MessageType::from(44), MessageType::PurchaseNotification);
+            assert_eq!(MessageType::from(43), MessageType::Unknown(43));
+            assert_eq!(i32::from(MessageType::Unknown(1000)), 1000);
+            assert_eq!(
+                serde_json::to_string(&MessageType::ThreadCreated).unwrap(),
+                "18"
+            );
+            assert!(!MessageType::Reply.is_system());
+            assert!(MessageType::AutoModerationAction.is_system());
+
+            let message: Message = serde_json::from_str(
+                r#"{"id": "1", "channel_id": "2", "author": {"id": "3", "username": "alice"}, "content": "Weekly Q&A", "timestamp": "2024-01-01T00:00:00+00:00", "mention_everyone": false, "pinned": false, "type": 31}"#,
+            )
+            .unwrap();
+            assert_eq!(message.message_type, MessageType::StageTopic);
+            assert_eq!(
+                message.system_content().unwrap(),
+                "alice changed the Stage topic: **Weekly Q&A**"
+            );
+
+            let message: Message = serde_json::from_str(
+                r#"{"id": "1", "channel_id": "2", "author": {"id": "3", "username": "alice"}, "content": "hi", "timestamp": "2024-01-01T00:00:00+00:00", "mention_everyone": false, "pinned": false, "type": 1000}"#,
+            )
+            .unwrap();
+            assert_eq!(message.message_type, MessageType::Unknown(1000));
+            assert!(message.system_content().is_none());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn role_subscription_messages() {
+            let message: Message = serde_json::from_str(
+                r#"{"id": "1", "channel_id": "2", "timestamp": "2024-01-01T00:00:00+00:00", "mention_everyone": false, "pinned": false, "type": 25, "role_subscription_data": {"role_subscription_listing_id": "3", "tier_name": "Gold", "total_months_subscribed": 2, "is_renewal": true}, "purchase_notification": {"type": 0, "guild_product_purchase": {"listing_id": "4", "product_name": "Wallpaper"}}}"#,
+            )
+            .unwrap();
+            let data = message.role_subscription_data.unwrap();
+            assert_eq!(data.tier_name, "Gold");
+            assert!(data.is_renewal);
+            let purchase = message
+                .purchase_notification
+                .unwrap()
+                .guild_product_purchase
+                .unwrap();
+            assert_eq!(purchase.listing_id, Snowflake(4));
+
+            let listing: RoleSubscriptionListing = serde_json::from_str(
+                r#"{"id": "5", "application_id": "6", "name": "Gold", "role_id": "7", "role_benefits": {"benefits": [{"ref_id": "8", "ref_type": 1, "emoji_id": null, "emoji_name": "🥇", "name": null, "description": "Gold chat"}]}, "subscription_plans": [{"id": "9", "name": "Gold", "sku_id": "10", "interval": 1, "interval_count": 1, "price": 499, "currency": "usd"}], "published": true}"#,
+            )
+            .unwrap();
+            assert_eq!(listing.role_id, Some(Snowflake(7)));
+            assert_eq!(listing.role_benefits.unwrap().benefits.len(), 1);
+            assert_eq!(listing.subscription_plans[0].price, Some(499));
+            assert!(!listing.archived);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn reaction_counts() {
+            // Instances without burst reactions only send the total count
+            let reaction: Reaction = serde_json::from_str(
+                r#"{"count": 3, "me": true, "emoji": {"id": "1", "name": "a"}}"#,
+            )
+            .unwrap();
+            assert_eq!(reaction.burst_count, 0);
+            assert!(reaction.burst_colors.is_empty());
+            assert_eq!(reaction.normal_count(), 3);
+
+            let reaction: Reaction = serde_json::from_str(
+                r##"{"count": 5, "count_details": {"normal": 3, "burst": 2}, "burst_count": 2, "me": false, "burst_me": true, "burst_colors": ["#ff0000"], "emoji": {"id": "1", "name": "a"}}"##,
+            )
+            .unwrap();
+            assert_eq!(reaction.normal_count(), 3);
+            assert_eq!(reaction.count_details.unwrap().burst, 2);
+            assert!(reaction.burst_me);
+        }
 
         #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
         #[cfg_attr(not(target_arch = "wasm32"), test)]
@@ -1055,4 +2229,718 @@ mod entities {
             assert_eq!(message1, message2);
         }
     }
+
+    mod report {
+        use chorus::types::{ReportMenu, ReportSchema, ReportType, Snowflake};
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn report_menu_path() {
+            let menu: ReportMenu = serde_json::from_str(
+                r#"{"name": "message", "variant": "4", "version": "1.0", "language": "en", "root_node_id": 1, "success_node_id": 4, "fail_node_id": 5, "nodes": {
+                    "1": {"id": 1, "key": "start", "header": "What's wrong?", "children": [["Spam", 2], ["Harassment", 3]]},
+                    "2": {"id": 2, "key": "spam", "header": "Spam", "report_type": "spam", "button": {"type": "submit", "target": 4}, "children": []},
+                    "3": {"id": 3, "key": "harassment", "header": "Harassment", "elements": [{"name": "reasons", "type": "checkbox", "data": [["insults", "Insults"]]}], "children": [["Submit", 4]]},
+                    "4": {"id": 4, "key": "success", "header": "Thanks!", "children": []},
+                    "5": {"id": 5, "key": "fail", "header": "Something went wrong", "children": []}
+                }}"#,
+            )
+            .unwrap();
+            assert_eq!(menu.root().unwrap().children.len(), 2);
+            assert_eq!(menu.path_to(3), Some(vec![1, 3]));
+            assert_eq!(menu.path_to(4), Some(vec![1, 3, 4]));
+            assert_eq!(menu.path_to(5), None);
+            assert_eq!(
+                menu.node(2).unwrap().button.as_ref().unwrap().target,
+                Some(4)
+            );
+
+            let schema = ReportSchema::new(&menu, menu.path_to(3).unwrap())
+                .element("reasons", vec!["insults".to_string()])
+                .message(Snowflake(10), Snowflake(11));
+            let value = serde_json::to_value(schema).unwrap();
+            assert_eq!(value["breadcrumbs"], serde_json::json!([1, 3]));
+            assert_eq!(value["message_id"], "11");
+            assert_eq!(value["variant"], "4");
+            assert!(value.get("user_id").is_none());
+            assert_eq!(
+                ReportType::GuildDirectoryEntry.to_string(),
+                "guild_directory_entry"
+            );
+        }
+    }
+
+    mod webhook {
+        use chorus::api::WebhookClient;
+        use chorus::types::{
+            ApplicationWebhook, ApplicationWebhookEventData, ApplicationWebhookType,
+            EntitlementType, Snowflake, Webhook, WebhookExecuteQuery, WebhookExecuteSchema,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn application_webhook_events() {
+            let ping: ApplicationWebhook =
+                serde_json::from_str(r#"{"version": 1, "application_id": "1", "type": 0}"#)
+                    .unwrap();
+            assert_eq!(ping.webhook_type, ApplicationWebhookType::Ping);
+            assert!(ping.event.is_none());
+
+            let authorized: ApplicationWebhook = serde_json::from_str(
+                r#"{"version": 1, "application_id": "1", "type": 1, "event": {"type": "APPLICATION_AUTHORIZED", "timestamp": "2024-10-18T14:42:53.064834", "data": {"integration_type": 1, "user": {"id": "2", "username": "alice", "discriminator": "0"}, "scopes": ["applications.commands"]}}}"#,
+            )
+            .unwrap();
+            let event = authorized.event.unwrap();
+            match event.data {
+                ApplicationWebhookEventData::ApplicationAuthorized(data) => {
+                    assert_eq!(data.user.id, Snowflake(2));
+                    assert_eq!(data.scopes, vec!["applications.commands"]);
+                    assert!(data.guild.is_none());
+                }
+                other => panic!("Unexpected event data: {:?}", other),
+            }
+
+            let entitlement: ApplicationWebhook = serde_json::from_str(
+                r#"{"version": 1, "application_id": "1", "type": 1, "event": {"type": "ENTITLEMENT_CREATE", "timestamp": "2024-10-18T14:42:53.064834Z", "data": {"id": "3", "sku_id": "4", "application_id": "1", "user_id": "2", "type": 8, "deleted": false, "starts_at": null, "ends_at": null}}}"#,
+            )
+            .unwrap();
+            match entitlement.event.unwrap().data {
+                ApplicationWebhookEventData::EntitlementCreate(data) => {
+                    assert_eq!(
+                        data.entitlement_type,
+                        EntitlementType::ApplicationSubscription
+                    );
+                    assert_eq!(data.user_id, Some(Snowflake(2)));
+                }
+                other => panic!("Unexpected event data: {:?}", other),
+            }
+
+            let unknown: ApplicationWebhook = serde_json::from_str(
+                r#"{"version": 1, "application_id": "1", "type": 1, "event": {"type": "QUEST_USER_ENROLLMENT", "timestamp": "2024-10-18T14:42:53Z", "data": {}}}"#,
+            )
+            .unwrap();
+            let data = unknown.event.unwrap().data;
+            assert_eq!(data.event_type(), "QUEST_USER_ENROLLMENT");
+            assert!(matches!(data, ApplicationWebhookEventData::Unknown { .. }));
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn webhook_with_null_fields() {
+            let webhook: Webhook = serde_json::from_str(
+                r#"{"id": "1", "type": 1, "name": "hook", "avatar": null, "channel_id": "2", "guild_id": "3", "application_id": null}"#,
+            )
+            .unwrap();
+            assert_eq!(webhook.channel_id, Snowflake(2));
+            assert!(webhook.token.is_none());
+            assert!(webhook.application_id.is_none());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn webhook_client_from_url() {
+            let client =
+                WebhookClient::from_url("https://example.com/api/webhooks/1234/abc-def?wait=true")
+                    .unwrap();
+            assert_eq!(client.api_url, "https://example.com/api");
+            assert_eq!(client.id, Snowflake(1234));
+            assert_eq!(client.token, "abc-def");
+
+            assert!(WebhookClient::from_url("https://example.com/api/webhooks/1234").is_err());
+            assert!(WebhookClient::from_url("https://example.com/api/webhooks/abc/def").is_err());
+            assert!(WebhookClient::from_url("https://example.com/api/channels/1/2").is_err());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn webhook_client_for_instance() {
+            let mut instance = chorus::instance::Instance::default();
+            instance.urls.api = "https://example.com/api".to_string();
+            instance.set_default_header(
+                http::HeaderName::from_static("cf-access-client-id"),
+                http::HeaderValue::from_static("chorus"),
+            );
+            let client = WebhookClient::for_instance(&instance, Snowflake(1234), "abc-def");
+            assert_eq!(client.api_url, "https://example.com/api");
+            assert_eq!(client.headers["cf-access-client-id"], "chorus");
+            assert_ne!(
+                client,
+                WebhookClient::new(&instance.urls.api, Snowflake(1234), "abc-def")
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn webhook_execute_schema() {
+            let schema = WebhookExecuteSchema {
+                content: Some("hi".to_string()),
+                username: Some("bot".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_value(&schema).unwrap(),
+                json!({"content": "hi", "username": "bot"})
+            );
+            assert!(schema.flags_error().is_none());
+
+            let query = WebhookExecuteQuery {
+                wait: Some(true),
+                thread_id: Some(Snowflake(5)),
+            };
+            assert_eq!(
+                serde_json::to_value(query).unwrap(),
+                json!({"wait": true, "thread_id": "5"})
+            );
+        }
+    }
+
+    mod auto_moderation {
+        use chorus::types::{
+            AutoModerationAction, AutoModerationActionExecution, AutoModerationActionMetadata,
+            AutoModerationActionType, AutoModerationRule, AutoModerationRuleCreateSchema,
+            AutoModerationRuleTriggerType, Snowflake,
+        };
+        use serde_json::json;
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn rule_create_schema() {
+            let schema = AutoModerationRuleCreateSchema::keyword(
+                "No spoilers",
+                vec!["spoiler*".to_string()],
+                vec![
+                    AutoModerationAction::block_message(Some("No spoilers!".to_string())),
+                    AutoModerationAction::send_alert_message(Snowflake(5)),
+                    AutoModerationAction::timeout(60),
+                ],
+            );
+            assert_eq!(
+                serde_json::to_value(schema).unwrap(),
+                json!({
+                    "name": "No spoilers",
+                    "event_type": 1,
+                    "trigger_type": 1,
+                    "trigger_metadata": {
+                        "keyword_filter": ["spoiler*"],
+                        "regex_patterns": [],
+                        "allow_list": []
+                    },
+                    "actions": [
+                        {"type": 1, "metadata": {"custom_message": "No spoilers!"}},
+                        {"type": 2, "metadata": {"channel_id": "5"}},
+                        {"type": 3, "metadata": {"duration_seconds": 60}}
+                    ],
+                    "enabled": true
+                })
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn rule_and_action_execution() {
+            let rule: AutoModerationRule = serde_json::from_value(json!({
+                "id": "1",
+                "guild_id": "2",
+                "name": "Profiles",
+                "creator_id": "3",
+                "event_type": 2,
+                "trigger_type": 6,
+                "trigger_metadata": {
+                    "keyword_filter": ["bad"],
+                    "regex_patterns": [],
+                    "allow_list": []
+                },
+                "actions": [{"type": 4}],
+                "enabled": true,
+                "exempt_roles": [],
+                "exempt_channels": []
+            }))
+            .unwrap();
+            assert_eq!(
+                rule.trigger_type,
+                AutoModerationRuleTriggerType::MemberProfile
+            );
+            assert_eq!(
+                rule.actions[0].read().unwrap().action_type,
+                AutoModerationActionType::BlockMemberInteraction
+            );
+
+            let execution: AutoModerationActionExecution = serde_json::from_value(json!({
+                "guild_id": "2",
+                "action": {"type": 2, "metadata": {"channel_id": "5"}},
+                "rule_id": "1",
+                "rule_trigger_type": 1,
+                "user_id": "4",
+                "channel_id": "6",
+                "message_id": "7",
+                "content": "a spoiler",
+                "matched_keyword": "spoiler*",
+                "matched_content": "spoiler"
+            }))
+            .unwrap();
+            assert_eq!(execution.rule_id, Snowflake(1));
+            assert_eq!(execution.matched_keyword.as_deref(), Some("spoiler*"));
+            let metadata = execution.action.metadata.unwrap();
+            assert!(matches!(
+                &*metadata.read().unwrap(),
+                AutoModerationActionMetadata::ForSendAlertMessage(alert)
+                    if alert.channel_id == Snowflake(5)
+            ));
+        }
+    }
+
+    mod interaction {
+        use chorus::api::{MAX_AUTOCOMPLETE_CHOICES, MAX_CHOICE_LENGTH, MAX_MODAL_ROWS};
+        use chorus::errors::ChorusError;
+        use chorus::types::{
+            ApplicationCommand, ApplicationCommandCreateSchema, ApplicationCommandInteractionData,
+            ApplicationCommandModifySchema, ApplicationCommandOptionChoice,
+            ApplicationCommandPermission, ApplicationCommandPermissionType,
+            ApplicationCommandPermissionsModifySchema, ApplicationCommandType,
+            GuildApplicationCommandPermissions, Interaction,
+            InteractionApplicationCommandCallbackData, InteractionAutocompleteCallbackData,
+            InteractionCallbackData, InteractionModalCallbackData, InteractionResponse,
+            InteractionResponseModifySchema, InteractionResponseType, InteractionType, Locale,
+            MessageFlags, ModalActionRow, Snowflake, TextInput, TextInputStyle,
+        };
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn application_command_options() {
+            let data: ApplicationCommandInteractionData = serde_json::from_str(
+                r#"{
+                    "id": "1",
+                    "name": "settings",
+                    "options": [{
+                        "name": "notifications",
+                        "type": 2,
+                        "options": [{
+                            "name": "mute",
+                            "type": 1,
+                            "options": [
+                                {"name": "reason", "type": 3, "value": "spam"},
+                                {"name": "hours", "type": 4, "value": 12},
+                                {"name": "silent", "type": 5, "value": true},
+                                {"name": "target", "type": 6, "value": "42"},
+                                {"name": "role", "type": 8, "value": "7"},
+                                {"name": "log", "type": 7, "value": "9"}
+                            ]
+                        }]
+                    }],
+                    "resolved": {
+                        "users": {"42": {"id": "42", "username": "target", "discriminator": "0"}},
+                        "members": {"42": {"nick": "t", "roles": ["7"], "joined_at": "2024-01-01T00:00:00Z"}},
+                        "roles": {"7": {
+                            "id": "7", "name": "muted", "color": 0, "hoist": false,
+                            "position": 1, "permissions": "0", "managed": false, "mentionable": false
+                        }},
+                        "channels": {"9": {"id": "9", "name": "log", "type": 0, "permissions": "0"}}
+                    }
+                }"#,
+            )
+            .unwrap();
+            assert_eq!(data.subcommand_path(), vec!["notifications", "mute"]);
+            assert_eq!(data.subcommand_options().len(), 6);
+            assert_eq!(data.get_string("reason"), Some("spam".to_string()));
+            assert_eq!(data.get_integer("hours"), Some(12));
+            assert_eq!(data.get_boolean("silent"), Some(true));
+            assert_eq!(data.get_snowflake("target"), Some(Snowflake(42)));
+            assert_eq!(data.get_user("target").unwrap().username, "target");
+            let member = data.get_member("target").unwrap();
+            assert_eq!(member.nick.as_deref(), Some("t"));
+            assert_eq!(member.user.unwrap().read().unwrap().id, Snowflake(42));
+            assert_eq!(data.get_role("role").unwrap().name, "muted");
+            assert_eq!(
+                data.get_channel("log").unwrap().name.as_deref(),
+                Some("log")
+            );
+            assert!(data.get_attachment("log").is_none());
+            // Wrong types and missing options are not returned
+            assert_eq!(data.get_string("hours"), None);
+            assert_eq!(data.get_string("missing"), None);
+
+            let plain: ApplicationCommandInteractionData =
+                serde_json::from_str(r#"{"id": "1", "name": "ping"}"#).unwrap();
+            assert!(plain.subcommand_path().is_empty());
+            assert_eq!(plain.get_user("target"), None);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn localization() {
+            let command: ApplicationCommand = serde_json::from_str(
+                r#"{
+                    "id": "1",
+                    "application_id": "2",
+                    "name": "ping",
+                    "name_localizations": {"de": "pingen", "es-ES": "sonar", "xx-YY": "?"},
+                    "description": "Pings the bot",
+                    "options": []
+                }"#,
+            )
+            .unwrap();
+            let localizations = command.name_localizations.as_ref().unwrap();
+            assert!(localizations.contains_key(&Locale::Other("xx-YY".to_string())));
+            assert_eq!(command.localized_name(&Locale::German), "pingen");
+            // Falls back to the same language, and then to the default
+            assert_eq!(command.localized_name(&Locale::SpanishLatam), "sonar");
+            assert_eq!(command.localized_name(&Locale::French), "ping");
+            assert_eq!(
+                command.localized_description(&Locale::German),
+                "Pings the bot"
+            );
+
+            let interaction: Interaction = serde_json::from_value(serde_json::json!({
+                "id": "1",
+                "type": "ApplicationCommand",
+                "data": {},
+                "guild_id": "2",
+                "channel_id": "3",
+                "member_id": "4",
+                "token": "token",
+                "version": 1,
+                "guild_locale": "de"
+            }))
+            .unwrap();
+            assert_eq!(interaction.locale, None);
+            assert_eq!(interaction.localize(localizations, "ping"), "pingen");
+            assert_eq!(
+                serde_json::to_string(&Locale::EnglishUs).unwrap(),
+                r#""en-US""#
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn autocomplete() {
+            let data: ApplicationCommandInteractionData = serde_json::from_str(
+                r#"{
+                    "id": "1",
+                    "name": "play",
+                    "options": [
+                        {"name": "volume", "type": 4, "value": 10},
+                        {"name": "song", "type": 3, "value": "never gon", "focused": true}
+                    ]
+                }"#,
+            )
+            .unwrap();
+            let focused = data.focused_option().unwrap();
+            assert_eq!(focused.name, "song");
+            assert_eq!(focused.value, "never gon");
+
+            let choice = |name: &str| ApplicationCommandOptionChoice {
+                name: name.to_string(),
+                name_localizations: None,
+                value: serde_json::json!(name),
+            };
+            let response = InteractionResponse {
+                response_type: InteractionResponseType::ApplicationCommandAutocompleteResult,
+                data: Some(InteractionCallbackData::Autocomplete(
+                    InteractionAutocompleteCallbackData::new(vec![choice(
+                        "Never Gonna Give You Up",
+                    )])
+                    .unwrap(),
+                )),
+            };
+            assert_eq!(
+                serde_json::to_value(response).unwrap(),
+                serde_json::json!({
+                    "type": 8,
+                    "data": {"choices": [{
+                        "name": "Never Gonna Give You Up",
+                        "name_localizations": null,
+                        "value": "Never Gonna Give You Up"
+                    }]}
+                })
+            );
+
+            let too_many = (0..=MAX_AUTOCOMPLETE_CHOICES)
+                .map(|i| choice(&i.to_string()))
+                .collect();
+            assert!(matches!(
+                InteractionAutocompleteCallbackData::new(too_many),
+                Err(ChorusError::InvalidArguments { .. })
+            ));
+            let too_long = choice(&"a".repeat(MAX_CHOICE_LENGTH + 1));
+            assert!(InteractionAutocompleteCallbackData::new(vec![too_long]).is_err());
+            assert!(InteractionAutocompleteCallbackData::new(vec![choice("")]).is_err());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn context_menu_commands() {
+            let data: ApplicationCommandInteractionData = serde_json::from_str(
+                r#"{
+                    "id": "1",
+                    "name": "Report user",
+                    "type": 2,
+                    "target_id": "42",
+                    "resolved": {"users": {"42": {"id": "42", "username": "target", "discriminator": "0"}}}
+                }"#,
+            )
+            .unwrap();
+            assert!(data.r#type.is_context_menu());
+            assert_eq!(data.target_user().unwrap().username, "target");
+            assert!(data.target_member().is_none());
+            assert!(data.target_message().is_none());
+
+            let message = ApplicationCommandCreateSchema {
+                name: "Bookmark".to_string(),
+                r#type: Some(ApplicationCommandType::Message),
+                ..Default::default()
+            };
+            assert!(message.validate().is_ok());
+            assert_eq!(
+                serde_json::to_string(&message).unwrap(),
+                r#"{"name":"Bookmark","type":3}"#
+            );
+            let described = ApplicationCommandCreateSchema {
+                description: "Bookmarks a message".to_string(),
+                ..message
+            };
+            assert!(described.validate().is_err());
+            let slash = ApplicationCommandCreateSchema {
+                name: "ping".to_string(),
+                ..Default::default()
+            };
+            assert!(slash.validate().is_err());
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn application_command_management() {
+            let rename = ApplicationCommandModifySchema {
+                name: Some("pong".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(
+                serde_json::to_string(&rename).unwrap(),
+                r#"{"name":"pong"}"#
+            );
+
+            let schema = ApplicationCommandPermissionsModifySchema {
+                permissions: vec![ApplicationCommandPermission {
+                    id: Snowflake(2),
+                    permission_type: ApplicationCommandPermissionType::Role,
+                    permission: false,
+                }],
+            };
+            assert_eq!(
+                serde_json::to_string(&schema).unwrap(),
+                r#"{"permissions":[{"id":"2","type":1,"permission":false}]}"#
+            );
+            let permissions: GuildApplicationCommandPermissions = serde_json::from_str(
+                r#"{"id":"3","application_id":"4","guild_id":"1","permissions":[{"id":"2","type":1,"permission":false}]}"#,
+            )
+            .unwrap();
+            assert_eq!(
+                *permissions.permissions[0].read().unwrap(),
+                schema.permissions[0]
+            );
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn ephemeral_responses() {
+            let deferred = InteractionResponse::deferred(true);
+            assert_eq!(deferred.flags_error(), None);
+            assert_eq!(
+                serde_json::to_value(&deferred).unwrap(),
+                serde_json::json!({"type": 5, "data": {"flags": 64}})
+            );
+            assert_eq!(
+                serde_json::to_value(InteractionResponse::deferred(false)).unwrap(),
+                serde_json::json!({"type": 5})
+            );
+
+            let message = InteractionApplicationCommandCallbackData {
+                content: "Only you can see this".to_string(),
+                ..Default::default()
+            }
+            .ephemeral();
+            assert!(message.message_flags().contains(MessageFlags::EPHEMERAL));
+            let response = InteractionResponse {
+                response_type: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(InteractionCallbackData::Message(message.clone())),
+            };
+            assert_eq!(response.flags_error(), None);
+            // Flags are only allowed on responses which create a message
+            let pong = InteractionResponse {
+                response_type: InteractionResponseType::Pong,
+                data: Some(InteractionCallbackData::Message(message)),
+            };
+            assert!(pong.flags_error().is_some());
+            let crossposted = InteractionResponse {
+                response_type: InteractionResponseType::ChannelMessageWithSource,
+                data: Some(InteractionCallbackData::Message(
+                    InteractionApplicationCommandCallbackData {
+                        flags: Some(MessageFlags::CROSSPOSTED.bits()),
+                        ..Default::default()
+                    },
+                )),
+            };
+            assert!(crossposted.flags_error().is_some());
+
+            // Responses can not be made ephemeral after the fact
+            let edit = InteractionResponseModifySchema {
+                content: Some("Done".to_string()),
+                flags: Some(MessageFlags::EPHEMERAL.bits()),
+                ..Default::default()
+            };
+            assert!(edit.flags_error().is_some());
+            let edit = InteractionResponseModifySchema {
+                flags: Some(MessageFlags::SUPPRESS_EMBEDS.bits()),
+                ..edit
+            };
+            assert_eq!(edit.flags_error(), None);
+        }
+
+        #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+        #[cfg_attr(not(target_arch = "wasm32"), test)]
+        fn modal_responses() {
+            let input = TextInput {
+                placeholder: Some("Tell us more".to_string()),
+                ..TextInput::new("feedback", TextInputStyle::Paragraph, "Feedback")
+            };
+            let modal = InteractionModalCallbackData::new(
+                "feedback_form",
+                "Send feedback",
+                vec![ModalActionRow::new(input.clone())],
+            )
+            .unwrap();
+            assert_eq!(
+                serde_json::to_value(InteractionResponse::modal(modal.clone())).unwrap(),
+                serde_json::json!({
+                    "type": 9,
+                    "data": {
+                        "custom_id": "feedback_form",
+                        "title": "Send feedback",
+                        "components": [{
+                            "type": 1,
+                            "components": [{
+                                "type": 4,
+                                "custom_id": "feedback",
+                                "style": 2,
+                                "label": "Feedback",
+                                "placeholder": "Tell us more"
+                            }]
+                        }]
+                    }
+                })
+            );
+            let response: InteractionResponse = serde_json::from_value(
+                serde_json::to_value(InteractionResponse::modal(modal)).unwrap(),
+            )
+            .unwrap();
+            assert!(matches!(
+                response.data,
+                Some(InteractionCallbackData::Modal(_))
+            ));
+
+            let rows = vec![ModalActionRow::new(input.clone()); MAX_MODAL_ROWS + 1];
+            assert!(InteractionModalCallbackData::new("form", "Title", rows).is_err());
+            assert!(InteractionModalCallbackData::new(
+                "form",
+                "",
+                vec![ModalActionRow::new(input)]
+            )
+            .is_err());
+            assert!(InteractionModalCallbackData::new("form", "Title", Vec::new()).is_err());
+
+            assert_eq!(
+                serde_json::to_value(InteractionResponse::deferred_update()).unwrap(),
+                serde_json::json!({"type": 6})
+            );
+
+            let interaction: Interaction = serde_json::from_value(serde_json::json!({
+                "id": "1",
+                "application_id": "2",
+                "type": "ModalSubmit",
+                "data": {
+                    "custom_id": "feedback_form",
+                    "components": [{"type": 1, "components": [{"type": 4, "custom_id": "feedback", "value": "Great!"}]}]
+                },
+                "guild_id": "3",
+                "channel_id": "4",
+                "member_id": "5",
+                "token": "token",
+                "version": 1
+            }))
+            .unwrap();
+            assert_eq!(interaction.r#type, InteractionType::ModalSubmit);
+            let submitted = interaction.modal_submit_data().unwrap();
+            assert_eq!(submitted.custom_id, "feedback_form");
+            assert_eq!(submitted.value("feedback"), Some("Great!"));
+            assert_eq!(submitted.value("missing"), None);
+        }
+    }
+}
+
+mod voice_gateway {
+    use chorus::types::{
+        is_dave_opcode, Snowflake, VoiceClientConnectFlags, VoiceClientConnectPlatform,
+        VoiceClientsConnect, VoiceFlags, VoicePlatform, VOICE_CLIENT_CONNECT_PLATFORM,
+    };
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn client_connect_payloads() {
+        let connect: VoiceClientsConnect =
+            serde_json::from_str(r#"{"user_ids":["1","2"]}"#).unwrap();
+        assert_eq!(connect.user_ids, vec![Snowflake(1), Snowflake(2)]);
+
+        let flags: VoiceClientConnectFlags =
+            serde_json::from_str(r#"{"user_id":"1","flags":10}"#).unwrap();
+        // Unknown bits are ignored
+        assert_eq!(flags.voice_flags(), VoiceFlags::ALLOW_VOICE_RECORDING);
+
+        let platform: VoiceClientConnectPlatform =
+            serde_json::from_str(r#"{"user_id":"1","platform":1}"#).unwrap();
+        assert_eq!(platform.voice_platform(), Some(VoicePlatform::Mobile));
+        let platform: VoiceClientConnectPlatform =
+            serde_json::from_str(r#"{"user_id":"1","platform":42}"#).unwrap();
+        assert_eq!(platform.voice_platform(), None);
+
+        assert!(is_dave_opcode(25));
+        assert!(!is_dave_opcode(VOICE_CLIENT_CONNECT_PLATFORM));
+    }
+
+    #[cfg(feature = "voice_gateway")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+    async fn ssrc_registry() {
+        use chorus::gateway::Observer;
+        use chorus::types::{Speaking, SsrcDefinition, VoiceClientDisconnection};
+        use chorus::voice::gateway::SsrcRegistry;
+
+        let registry = SsrcRegistry::new();
+        registry
+            .update(&Speaking {
+                ssrc: 10,
+                user_id: Some(Snowflake(1)),
+                ..Default::default()
+            })
+            .await;
+        registry
+            .update(&SsrcDefinition {
+                audio_ssrc: 20,
+                video_ssrc: 21,
+                user_id: Some(Snowflake(2)),
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(registry.user_id(10), Some(Snowflake(1)));
+        assert_eq!(registry.ssrcs(Snowflake(2)), vec![20, 21]);
+        // Undefined (0) ssrcs are not registered
+        assert_eq!(registry.len(), 3);
+
+        registry
+            .update(&VoiceClientDisconnection {
+                user_id: Snowflake(2),
+            })
+            .await;
+        assert_eq!(registry.user_id(20), None);
+        assert_eq!(registry.len(), 1);
+    }
 }
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use chorus::types::{PublicUser, Snowflake, User};
+use chorus::types::{PrimaryGuildModifySchema, PublicUser, Snowflake, User, UserUpdate};
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
 #[cfg_attr(not(target_arch = "wasm32"), test)]
@@ -20,3 +20,35 @@ fn to_public_user() {
     let from_user = user.into_public_user();
     assert_eq!(public_user, from_user);
 }
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn primary_guild() {
+    let update: UserUpdate = serde_json::from_str(
+        r#"{"id": "1", "username": "alice", "primary_guild": {"identity_guild_id": "2", "identity_enabled": true, "tag": "CHRS", "badge": "abc"}}"#,
+    )
+    .unwrap();
+    let primary_guild = update.user.primary_guild.unwrap();
+    assert!(primary_guild.is_displayed());
+    assert_eq!(
+        primary_guild.badge_url("https://cdn.example.com").unwrap(),
+        "https://cdn.example.com/clan-badges/2/abc.png"
+    );
+
+    // Older instances send the same structure as "clan", others leave it out entirely
+    let user: User = serde_json::from_str(
+        r#"{"id": "1", "username": "alice", "discriminator": "0", "clan": {"identity_guild_id": "2", "identity_enabled": null}}"#,
+    )
+    .unwrap();
+    let clan = user.primary_guild.unwrap();
+    assert_eq!(clan.identity_guild_id, Some(Snowflake(2)));
+    assert!(!clan.is_displayed());
+    let user: User =
+        serde_json::from_str(r#"{"id": "1", "username": "alice", "discriminator": "0"}"#).unwrap();
+    assert!(user.primary_guild.is_none());
+
+    assert_eq!(
+        serde_json::to_string(&PrimaryGuildModifySchema::default()).unwrap(),
+        r#"{"identity_guild_id":null,"identity_enabled":null}"#
+    );
+}
@@ -0,0 +1,546 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+#![cfg(feature = "voice_udp")]
+
+use std::io::{self, Cursor};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use aes_gcm::Aes256Gcm;
+use chorus::types::{SessionDescription, VoiceEncryptionMode, VoiceReady};
+use chorus::voice::discortp::rtp::{MutableRtpPacket, Rtp, RtpPacket, RtpType};
+use chorus::voice::discortp::Packet;
+use chorus::voice::receive::*;
+use chorus::voice::recording::*;
+use chorus::voice::udp::{AudioSender, UdpHandle, UdpHandler, OPUS_FRAME_SAMPLES};
+use chorus::voice::voice_data::VoiceData;
+use crypto_secretbox::aead::{Aead, Payload};
+use crypto_secretbox::{KeyInit, XSalsa20Poly1305};
+use futures_util::StreamExt;
+use tokio::net::UdpSocket;
+use tokio::sync::RwLock;
+
+/// A decoder which turns every packet into a 20ms frame, whose samples are the packet's first byte
+#[derive(Debug)]
+struct ConstantDecoder;
+
+impl AudioDecoder for ConstantDecoder {
+    fn decode(
+        &mut self,
+        packet: &[u8],
+    ) -> Result<Vec<i16>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(vec![packet[0] as i16; 960 * CHANNELS as usize])
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct CollectingSink {
+    written: Arc<Mutex<Vec<(Speaker, u64, usize)>>>,
+}
+
+impl RecordingSink for CollectingSink {
+    fn write(&mut self, speaker: Speaker, position: u64, samples: &[i16]) -> io::Result<()> {
+        self.written
+            .lock()
+            .unwrap()
+            .push((speaker, position, samples.len()));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn rtp(ssrc: u32, timestamp: u32, payload: Vec<u8>) -> Rtp {
+    Rtp {
+        version: 2,
+        padding: 0,
+        extension: 0,
+        csrc_count: 0,
+        marker: 0,
+        payload_type: RtpType::Dynamic(120),
+        sequence: 0.into(),
+        timestamp: timestamp.into(),
+        ssrc,
+        csrc_list: Vec::new(),
+        payload,
+    }
+}
+
+#[test]
+fn test_recorder_timeline() {
+    let sink = CollectingSink::default();
+    let recorder = VoiceRecorder::new(sink.clone(), || Box::new(ConstantDecoder));
+
+    recorder.record(&rtp(1, 1000, vec![1])).unwrap();
+    // A pause in speech
+    recorder.record(&rtp(1, 1000 + 48_000, vec![1])).unwrap();
+    // A packet from before the first one
+    recorder.record(&rtp(1, 500, vec![1])).unwrap();
+    recorder.finish().unwrap();
+    // Ignored after finishing
+    recorder.record(&rtp(1, 1960, vec![1])).unwrap();
+
+    let written = sink.written.lock().unwrap();
+    assert_eq!(written.len(), 2);
+    let start = written[0].1;
+    assert_eq!(written[0].0.ssrc, 1);
+    assert_eq!(written[1].1, start + 48_000);
+    assert_eq!(written[1].2, 960 * CHANNELS as usize);
+}
+
+#[test]
+fn test_mixdown_sink() {
+    let mut sink = MixdownSink::new(Cursor::new(Vec::new())).unwrap();
+    let first = Speaker {
+        ssrc: 1,
+        user_id: None,
+    };
+    let second = Speaker {
+        ssrc: 2,
+        user_id: None,
+    };
+    sink.write(first, 0, &[100; 4]).unwrap();
+    sink.write(second, 1, &[i16::MAX; 4]).unwrap();
+    sink.finish().unwrap();
+
+    let wav = sink.into_inner().into_inner();
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(&wav[8..12], b"WAVE");
+    // 3 stereo samples of audio
+    assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 12);
+    let samples: Vec<i16> = wav[44..]
+        .chunks(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+    // Overlapping audio is mixed, without overflowing
+    assert_eq!(
+        samples,
+        vec![100, 100, i16::MAX, i16::MAX, i16::MAX, i16::MAX]
+    );
+}
+
+#[test]
+fn test_mixdown_sink_gap() {
+    let mut sink = MixdownSink::new(Cursor::new(Vec::new())).unwrap();
+    let speaker = Speaker {
+        ssrc: 1,
+        user_id: None,
+    };
+    sink.write(speaker, 0, &[1; 4]).unwrap();
+    // Ten seconds later, the gap is written as silence and only a second stays buffered
+    sink.write(speaker, 10 * SAMPLE_RATE as u64, &[2; 4])
+        .unwrap();
+    assert!(format!("{sink:?}").contains("buffered: 96000"));
+    sink.finish().unwrap();
+
+    let wav = sink.into_inner().into_inner();
+    let samples: Vec<i16> = wav[44..]
+        .chunks(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+    assert_eq!(
+        samples.len(),
+        (10 * SAMPLE_RATE as usize + 2) * CHANNELS as usize
+    );
+    assert_eq!(samples[..4], [1; 4]);
+    assert!(samples[4..samples.len() - 4]
+        .iter()
+        .all(|sample| *sample == 0));
+    assert_eq!(samples[samples.len() - 4..], [2; 4]);
+}
+
+/// Splits an Ogg file into its pages, as (header type, granule position, packet), checking their
+/// sequence numbers and checksums
+fn ogg_pages(mut bytes: &[u8]) -> Vec<(u8, u64, Vec<u8>)> {
+    let mut pages = Vec::new();
+    while !bytes.is_empty() {
+        assert_eq!(&bytes[..4], b"OggS");
+        let sequence = u32::from_le_bytes(bytes[18..22].try_into().unwrap());
+        assert_eq!(sequence as usize, pages.len());
+        let segments = bytes[26] as usize;
+        let length: usize = bytes[27..27 + segments].iter().map(|l| *l as usize).sum();
+        let page_length = 27 + segments + length;
+
+        let mut page = bytes[..page_length].to_vec();
+        let crc = u32::from_le_bytes(page[22..26].try_into().unwrap());
+        page[22..26].copy_from_slice(&[0; 4]);
+        let mut expected: u32 = 0;
+        for byte in &page {
+            expected ^= (*byte as u32) << 24;
+            for _ in 0..8 {
+                expected = if expected & 0x8000_0000 != 0 {
+                    (expected << 1) ^ 0x04C1_1DB7
+                } else {
+                    expected << 1
+                };
+            }
+        }
+        assert_eq!(crc, expected);
+
+        pages.push((
+            bytes[5],
+            u64::from_le_bytes(bytes[6..14].try_into().unwrap()),
+            bytes[27 + segments..page_length].to_vec(),
+        ));
+        bytes = &bytes[page_length..];
+    }
+    pages
+}
+
+#[test]
+fn test_opus_packet_samples() {
+    // CELT, 20ms
+    assert_eq!(opus_packet_samples(&[0xF8, 0xFF, 0xFE]), Some(960));
+    // SILK, 60ms
+    assert_eq!(opus_packet_samples(&[0x18]), Some(2880));
+    // Hybrid, 10ms, two frames
+    assert_eq!(opus_packet_samples(&[0x61]), Some(960));
+    // CELT, 2.5ms, an arbitrary amount of frames
+    assert_eq!(opus_packet_samples(&[0x83, 4]), Some(480));
+    assert_eq!(opus_packet_samples(&[0x83]), None);
+    assert_eq!(opus_packet_samples(&[]), None);
+}
+
+#[test]
+fn test_ogg_opus_writer() {
+    let mut writer = OggOpusWriter::new(Vec::new()).unwrap();
+    writer.write_packet(&[0xF8, 1, 2, 3]).unwrap();
+    writer.write_silence(960 * 2 + 100).unwrap();
+    assert_eq!(writer.samples_written(), 960 * 3);
+    assert!(writer.write_packet(&[]).is_err());
+    writer.finish().unwrap();
+
+    let pages = ogg_pages(&writer.into_inner());
+    assert_eq!(pages.len(), 5);
+    // Beginning of the stream
+    assert_eq!(pages[0].0, 0x02);
+    assert_eq!(&pages[0].2[..8], b"OpusHead");
+    assert_eq!(pages[0].2[9], 2);
+    assert_eq!(&pages[1].2[..8], b"OpusTags");
+    assert_eq!(pages[2], (0, 960, vec![0xF8, 1, 2, 3]));
+    assert_eq!(pages[3], (0, 1920, vec![0xF8, 0xFF, 0xFE]));
+    // End of the stream
+    assert_eq!(pages[4], (0x04, 2880, vec![0xF8, 0xFF, 0xFE]));
+}
+
+#[derive(Debug, Default, Clone)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_ogg_opus_recorder() {
+    let buffer = SharedBuffer::default();
+    let file = buffer.clone();
+    let recorder = OggOpusRecorder::new(move |_| Ok(file.clone()));
+
+    recorder.record(&rtp(1, 1000, vec![0xF8, 1])).unwrap();
+    // A pause in speech of two packets
+    recorder
+        .record(&rtp(1, 1000 + 960 * 3, vec![0xF8, 2]))
+        .unwrap();
+    // A packet which arrived too late
+    recorder.record(&rtp(1, 1000 + 960, vec![0xF8, 3])).unwrap();
+    recorder.finish().unwrap();
+    // Ignored after finishing
+    recorder
+        .record(&rtp(1, 1000 + 960 * 4, vec![0xF8, 4]))
+        .unwrap();
+
+    let pages = ogg_pages(&buffer.0.lock().unwrap());
+    let packets: Vec<Vec<u8>> = pages[2..].iter().map(|page| page.2.clone()).collect();
+    // The file may start with silence, up to when the first packet arrived
+    let start = packets.len() - 4;
+    assert!(packets[..start]
+        .iter()
+        .all(|packet| *packet == [0xF8, 0xFF, 0xFE]));
+    assert_eq!(
+        packets[start..],
+        [
+            vec![0xF8, 1],
+            vec![0xF8, 0xFF, 0xFE],
+            vec![0xF8, 0xFF, 0xFE],
+            vec![0xF8, 2]
+        ]
+    );
+    assert_eq!(pages.last().unwrap().0, 0x04);
+}
+
+#[cfg(feature = "voice_opus")]
+#[test]
+fn test_opus_decoder() {
+    use chorus::voice::audiopus::coder::Encoder;
+    use chorus::voice::audiopus::{Application, Channels, SampleRate};
+
+    let encoder = Encoder::new(SampleRate::Hz48000, Channels::Stereo, Application::Audio).unwrap();
+    // A 20ms frame of a 440Hz tone on both channels
+    let input: Vec<i16> = (0..960)
+        .flat_map(|i| {
+            let sample =
+                (f64::sin(i as f64 * 440.0 * std::f64::consts::TAU / 48_000.0) * 8000.0) as i16;
+            [sample, sample]
+        })
+        .collect();
+    let mut packet = vec![0; 4000];
+    let length = encoder.encode(&input, &mut packet).unwrap();
+    packet.truncate(length);
+
+    let mut decoder = OpusDecoder::new().unwrap();
+    let samples = decoder.decode(&packet).unwrap();
+    assert_eq!(samples.len(), 960 * CHANNELS as usize);
+    assert!(samples.iter().any(|sample| *sample != 0));
+
+    assert!(decoder.decode(&[]).is_err());
+}
+
+/// Connects a [UdpHandle] to a local socket, which answers ip discovery and is then connected
+/// to the handle.
+async fn connect_udp(
+    encryption_mode: VoiceEncryptionMode,
+    key: [u8; 32],
+) -> (UdpHandle, UdpSocket) {
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = server.local_addr().unwrap();
+
+    let discovery = tokio::spawn(async move {
+        let mut buf = [0; 128];
+        let (size, client) = server.recv_from(&mut buf).await.unwrap();
+        // Answer with the request turned into a response
+        buf[1] = 2;
+        server.send_to(&buf[..size], client).await.unwrap();
+        server.connect(client).await.unwrap();
+        server
+    });
+
+    let data = Arc::new(RwLock::new(VoiceData::default()));
+    let handle = UdpHandler::spawn(data.clone(), address, 7).await.unwrap();
+    let server = discovery.await.unwrap();
+    assert_eq!(data.read().await.ip_discovery.as_ref().unwrap().ssrc, 7);
+
+    {
+        let mut data = data.write().await;
+        data.ready_data = Some(VoiceReady {
+            ssrc: 7,
+            ..Default::default()
+        });
+        data.session_description = Some(SessionDescription {
+            encryption_mode,
+            secret_key: key,
+            ..Default::default()
+        });
+    }
+
+    (handle, server)
+}
+
+#[tokio::test]
+async fn test_audio_sender() {
+    let key = [3; 32];
+    let (handle, server) = connect_udp(VoiceEncryptionMode::Xsalsa20Poly1305, key).await;
+
+    let mut sender = AudioSender::new(handle);
+    let first_timestamp = sender.timestamp();
+    let start = Instant::now();
+    for frame in 0..3u8 {
+        sender.send_frame(vec![frame; 10]).await.unwrap();
+    }
+    // Frames are sent 20ms apart
+    assert!(start.elapsed() >= Duration::from_millis(40));
+
+    let cipher = XSalsa20Poly1305::new(&key.into());
+    let mut last_sequence = None;
+    for frame in 0..3u8 {
+        let mut buf = [0; 128];
+        let size = server.recv(&mut buf).await.unwrap();
+        let packet = RtpPacket::new(&buf[..size]).unwrap();
+        assert_eq!(packet.get_ssrc(), 7);
+        assert_eq!(
+            u32::from(packet.get_timestamp()),
+            first_timestamp.wrapping_add(frame as u32 * OPUS_FRAME_SAMPLES)
+        );
+        let sequence = u16::from(packet.get_sequence());
+        if let Some(last) = last_sequence {
+            assert_eq!(sequence, u16::wrapping_add(last, 1));
+        }
+        last_sequence = Some(sequence);
+
+        let mut nonce = [0; 24];
+        nonce[..12].copy_from_slice(&buf[..12]);
+        let payload = cipher
+            .decrypt(&nonce.into(), packet.payload())
+            .expect("Payload should be encrypted with the session key");
+        assert_eq!(payload, vec![frame; 10]);
+    }
+}
+
+#[tokio::test]
+async fn test_audio_sender_aead_aes256_gcm() {
+    let key = [5; 32];
+    let (handle, server) = connect_udp(VoiceEncryptionMode::AeadAes256Gcm, key).await;
+
+    let mut sender = AudioSender::new(handle).pacing(false);
+    for frame in 0..3u8 {
+        sender.send_frame(vec![frame; 10]).await.unwrap();
+    }
+
+    let cipher = Aes256Gcm::new(&key.into());
+    for frame in 0..3u8 {
+        let mut buf = [0; 128];
+        let size = server.recv(&mut buf).await.unwrap();
+        let packet = RtpPacket::new(&buf[..size]).unwrap();
+
+        // The payload ends with the 4 byte incremental nonce, padded to 12 bytes
+        let (ciphertext, suffix) = packet.payload().split_at(packet.payload().len() - 4);
+        assert_eq!(suffix, (frame as u32 + 1).to_be_bytes());
+        let mut nonce = [0; 12];
+        nonce[..4].copy_from_slice(suffix);
+        let payload = cipher
+            .decrypt(
+                &nonce.into(),
+                Payload {
+                    msg: ciphertext,
+                    aad: &buf[..12],
+                },
+            )
+            .expect("Payload should be encrypted with the session key");
+        assert_eq!(payload, vec![frame; 10]);
+    }
+}
+
+/// An aead_aes256_gcm rtp packet with the key `0..32` and the payload `[0xF8, 0xFF, 0xFE]`,
+/// encrypted with python's `cryptography` package: the rtp header, the ciphertext with its 16
+/// byte tag and the 4 byte nonce suffix. The rtp header is the associated data.
+const AEAD_AES256_GCM_PACKET: [u8; 35] = [
+    128, 120, 0, 1, 0, 0, 3, 192, 0, 0, 0, 7, 188, 201, 51, 78, 2, 65, 141, 249, 180, 50, 39, 160,
+    77, 60, 231, 250, 116, 14, 254, 0, 0, 0, 1,
+];
+
+fn aead_aes256_gcm_key() -> [u8; 32] {
+    core::array::from_fn(|i| i as u8)
+}
+
+#[tokio::test]
+async fn test_aead_aes256_gcm_encrypt_vector() {
+    let (handle, _server) =
+        connect_udp(VoiceEncryptionMode::AeadAes256Gcm, aead_aes256_gcm_key()).await;
+
+    let mut packet = AEAD_AES256_GCM_PACKET[..12].to_vec();
+    packet.extend_from_slice(&[0xF8, 0xFF, 0xFE]);
+    let packet = MutableRtpPacket::new(&mut packet).unwrap();
+    let encrypted = handle.encrypt_rtp_packet_payload(&packet).await.unwrap();
+    assert_eq!(encrypted, AEAD_AES256_GCM_PACKET);
+}
+
+#[tokio::test]
+async fn test_aead_aes256_gcm_decrypt_vector() {
+    let (handle, server) =
+        connect_udp(VoiceEncryptionMode::AeadAes256Gcm, aead_aes256_gcm_key()).await;
+    let receiver = VoiceReceiver::new();
+    receiver.subscribe(&handle).await;
+    let mut stream = receiver.stream();
+
+    // A packet whose authenticated header was tampered with is dropped
+    let mut tampered = AEAD_AES256_GCM_PACKET;
+    tampered[7] = 193;
+    server.send(&tampered).await.unwrap();
+    server.send(&AEAD_AES256_GCM_PACKET).await.unwrap();
+
+    let frame = stream.next().await.unwrap();
+    assert_eq!((frame.ssrc, frame.timestamp), (7, 960));
+    assert_eq!(frame.data, vec![0xF8, 0xFF, 0xFE]);
+}
+
+/// A decoder whose samples are the number of packets it has decoded
+#[derive(Debug, Default)]
+struct CountingDecoder(i16);
+
+impl AudioDecoder for CountingDecoder {
+    fn decode(
+        &mut self,
+        _packet: &[u8],
+    ) -> Result<Vec<i16>, Box<dyn std::error::Error + Send + Sync>> {
+        self.0 += 1;
+        Ok(vec![self.0; 960 * CHANNELS as usize])
+    }
+}
+
+#[tokio::test]
+async fn test_pcm_stream_decoder_per_ssrc() {
+    let receiver = VoiceReceiver::new();
+    let decoded = receiver
+        .stream()
+        .decode(|| Box::<CountingDecoder>::default());
+
+    receiver.receive(&rtp(1, 100, vec![1]));
+    receiver.receive(&rtp(2, 200, vec![2]));
+    receiver.receive(&rtp(1, 1060, vec![1]));
+    receiver.receive(&rtp(3, 300, vec![3]));
+
+    let decoded: Vec<(u32, i16)> = decoded
+        .take(4)
+        .map(|frame| (frame.ssrc, frame.samples[0]))
+        .collect()
+        .await;
+    // Every ssrc has its own decoder state
+    assert_eq!(decoded, vec![(1, 1), (2, 1), (1, 2), (3, 1)]);
+}
+
+#[cfg(feature = "voice_gateway")]
+#[tokio::test]
+async fn test_voice_receiver() {
+    use chorus::types::Snowflake;
+    use chorus::voice::gateway::SsrcRegistry;
+
+    let ssrcs = SsrcRegistry::new();
+    ssrcs.insert(1, Snowflake(10));
+    ssrcs.insert(2, Snowflake(20));
+    let receiver = VoiceReceiver::new().with_ssrc_registry(ssrcs);
+
+    let mut first = receiver.user_stream(Snowflake(10));
+    let second = receiver.user_stream(Snowflake(20));
+    let mut everyone = receiver.stream();
+
+    receiver.receive(&rtp(1, 100, vec![1]));
+    receiver.receive(&rtp(2, 200, vec![2]));
+    // An unknown ssrc only reaches the stream of everyone
+    receiver.receive(&rtp(3, 300, vec![3]));
+    let mut with_extension = rtp(1, 1060, vec![0xBE, 0xDE, 0, 1, 9, 9, 9, 9, 4]);
+    with_extension.extension = 1;
+    receiver.receive(&with_extension);
+
+    let frame = first.next().await.unwrap();
+    assert_eq!(frame.user_id, Some(Snowflake(10)));
+    assert_eq!((frame.ssrc, frame.timestamp, frame.data), (1, 100, vec![1]));
+    assert_eq!(first.next().await.unwrap().data, vec![4]);
+
+    let ssrcs: Vec<u32> = everyone
+        .by_ref()
+        .take(4)
+        .map(|frame| frame.ssrc)
+        .collect()
+        .await;
+    assert_eq!(ssrcs, vec![1, 2, 3, 1]);
+
+    let mut decoded = second.decode(|| Box::new(ConstantDecoder));
+    let frame = decoded.next().await.unwrap();
+    assert_eq!(frame.user_id, Some(Snowflake(20)));
+    assert_eq!(frame.samples, vec![2; 960 * CHANNELS as usize]);
+
+    // Dropped streams no longer receive frames
+    drop(first);
+    drop(decoded);
+    receiver.receive(&rtp(1, 2020, vec![5]));
+    assert_eq!(everyone.next().await.unwrap().data, vec![5]);
+}